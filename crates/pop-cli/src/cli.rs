@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use crate::common::output;
 use std::{fmt::Display, io::Result};
 #[cfg(test)]
 pub(crate) use tests::MockCli;
@@ -13,6 +14,8 @@ pub(crate) mod traits {
 		fn confirm(&mut self, prompt: impl Display) -> impl Confirm;
 		/// Prints an info message.
 		fn info(&mut self, text: impl Display) -> Result<()>;
+		/// Constructs a new [`Input`] prompt.
+		fn input(&mut self, prompt: impl Display) -> impl Input;
 		/// Prints a header of the prompt sequence.
 		fn intro(&mut self, title: impl Display) -> Result<()>;
 		/// Constructs a new [`MultiSelect`] prompt.
@@ -21,6 +24,8 @@ pub(crate) mod traits {
 		fn outro(&mut self, message: impl Display) -> Result<()>;
 		/// Prints a footer of the prompt sequence with a failure style.
 		fn outro_cancel(&mut self, message: impl Display) -> Result<()>;
+		/// Constructs a new [`Select`] prompt.
+		fn select<T: Clone + Eq>(&mut self, prompt: impl Display) -> impl Select<T>;
 		/// Prints a success message.
 		fn success(&mut self, message: impl Display) -> Result<()>;
 		/// Prints a warning message.
@@ -33,6 +38,14 @@ pub(crate) mod traits {
 		fn interact(&mut self) -> Result<bool>;
 	}
 
+	/// A text input prompt.
+	pub trait Input {
+		/// Sets the default value of the input.
+		fn default_input(self, value: &str) -> Self;
+		/// Starts the prompt interaction.
+		fn interact(&mut self) -> Result<String>;
+	}
+
 	/// A multi-select prompt.
 	pub trait MultiSelect<T> {
 		/// Starts the prompt interaction.
@@ -42,9 +55,20 @@ pub(crate) mod traits {
 		/// Sets whether the input is required.
 		fn required(self, required: bool) -> Self;
 	}
+
+	/// A select prompt.
+	pub trait Select<T> {
+		/// Sets the value selected initially.
+		fn initial_value(self, value: T) -> Self;
+		/// Starts the prompt interaction.
+		fn interact(&mut self) -> Result<T>;
+		/// Adds an item to the list of options.
+		fn item(self, value: T, label: impl Display, hint: impl Display) -> Self;
+	}
 }
 
-/// A command line interface using cliclack.
+/// A command line interface using cliclack, whose output is suppressed whilst machine-readable
+/// progress events are emitted.
 pub(crate) struct Cli;
 impl traits::Cli for Cli {
 	/// Constructs a new [`Confirm`] prompt.
@@ -54,14 +78,19 @@ impl traits::Cli for Cli {
 
 	/// Prints an info message.
 	fn info(&mut self, text: impl Display) -> Result<()> {
-		cliclack::log::info(text)
+		output::log::info(text)
+	}
+
+	/// Constructs a new [`Input`] prompt.
+	fn input(&mut self, prompt: impl Display) -> impl traits::Input {
+		Input(cliclack::input(prompt))
 	}
 
 	/// Prints a header of the prompt sequence.
 	fn intro(&mut self, title: impl Display) -> Result<()> {
-		cliclack::clear_screen()?;
+		output::clear_screen()?;
 		cliclack::set_theme(crate::style::Theme);
-		cliclack::intro(format!("{}: {title}", console::style(" Pop CLI ").black().on_magenta()))
+		output::intro(format!("{}: {title}", console::style(" Pop CLI ").black().on_magenta()))
 	}
 
 	/// Constructs a new [`MultiSelect`] prompt.
@@ -71,22 +100,27 @@ impl traits::Cli for Cli {
 
 	/// Prints a footer of the prompt sequence.
 	fn outro(&mut self, message: impl Display) -> Result<()> {
-		cliclack::outro(message)
+		output::outro(message)
 	}
 
 	/// Prints a footer of the prompt sequence with a failure style.
 	fn outro_cancel(&mut self, message: impl Display) -> Result<()> {
-		cliclack::outro_cancel(message)
+		output::outro_cancel(message)
+	}
+
+	/// Constructs a new [`Select`] prompt.
+	fn select<T: Clone + Eq>(&mut self, prompt: impl Display) -> impl traits::Select<T> {
+		Select::<T>(cliclack::select(prompt))
 	}
 
 	/// Prints a success message.
 	fn success(&mut self, message: impl Display) -> Result<()> {
-		cliclack::log::success(message)
+		output::log::success(message)
 	}
 
 	/// Prints a warning message.
 	fn warning(&mut self, message: impl Display) -> Result<()> {
-		cliclack::log::warning(message)
+		output::log::warning(message)
 	}
 }
 
@@ -99,6 +133,21 @@ impl traits::Confirm for Confirm {
 	}
 }
 
+/// A text input prompt using cliclack.
+struct Input(cliclack::Input);
+impl traits::Input for Input {
+	/// Sets the default value of the input.
+	fn default_input(mut self, value: &str) -> Self {
+		self.0 = self.0.default_input(value);
+		self
+	}
+
+	/// Starts the prompt interaction.
+	fn interact(&mut self) -> Result<String> {
+		self.0.interact()
+	}
+}
+
 /// A multi-select prompt using cliclack.
 struct MultiSelect<T: Clone + Eq>(cliclack::MultiSelect<T>);
 
@@ -121,21 +170,48 @@ impl<T: Clone + Eq> traits::MultiSelect<T> for MultiSelect<T> {
 	}
 }
 
+/// A select prompt using cliclack.
+struct Select<T: Clone + Eq>(cliclack::Select<T>);
+
+impl<T: Clone + Eq> traits::Select<T> for Select<T> {
+	/// Sets the value selected initially.
+	fn initial_value(mut self, value: T) -> Self {
+		self.0 = self.0.initial_value(value);
+		self
+	}
+
+	/// Starts the prompt interaction.
+	fn interact(&mut self) -> Result<T> {
+		self.0.interact()
+	}
+
+	/// Adds an item to the list of options.
+	fn item(mut self, value: T, label: impl Display, hint: impl Display) -> Self {
+		self.0 = self.0.item(value, label, hint);
+		self
+	}
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
 	use super::traits::*;
 	use std::{fmt::Display, io::Result};
 
+	/// The prompt, items and index of the item selected expected of a select prompt.
+	type SelectExpectation = (String, Option<Vec<(String, String)>>, usize);
+
 	/// Mock Cli with optional expectations
 	#[derive(Default)]
 	pub(crate) struct MockCli {
 		confirm_expectation: Option<(String, bool)>,
 		info_expectations: Vec<String>,
+		input_expectation: Option<(String, String)>,
 		intro_expectation: Option<String>,
 		outro_expectation: Option<String>,
 		multiselect_expectation:
 			Option<(String, Option<bool>, bool, Option<Vec<(String, String)>>)>,
 		outro_cancel_expectation: Option<String>,
+		select_expectation: Option<SelectExpectation>,
 		success_expectations: Vec<String>,
 		warning_expectations: Vec<String>,
 	}
@@ -155,6 +231,11 @@ pub(crate) mod tests {
 			self
 		}
 
+		pub(crate) fn expect_input(mut self, prompt: impl Display, input: String) -> Self {
+			self.input_expectation = Some((prompt.to_string(), input));
+			self
+		}
+
 		pub(crate) fn expect_intro(mut self, title: impl Display) -> Self {
 			self.intro_expectation = Some(title.to_string());
 			self
@@ -181,6 +262,16 @@ pub(crate) mod tests {
 			self
 		}
 
+		pub(crate) fn expect_select(
+			mut self,
+			prompt: impl Display,
+			items: Option<Vec<(String, String)>>,
+			item: usize,
+		) -> Self {
+			self.select_expectation = Some((prompt.to_string(), items, item));
+			self
+		}
+
 		pub(crate) fn expect_success(mut self, message: impl Display) -> Self {
 			self.success_expectations.push(message.to_string());
 			self
@@ -198,6 +289,9 @@ pub(crate) mod tests {
 			if !self.info_expectations.is_empty() {
 				panic!("`{}` info log expectations not satisfied", self.info_expectations.join(","))
 			}
+			if let Some((expectation, _)) = self.input_expectation {
+				panic!("`{expectation}` input expectation not satisfied")
+			}
 			if let Some(expectation) = self.intro_expectation {
 				panic!("`{expectation}` intro expectation not satisfied")
 			}
@@ -210,6 +304,9 @@ pub(crate) mod tests {
 			if let Some(expectation) = self.outro_cancel_expectation {
 				panic!("`{expectation}` outro cancel expectation not satisfied")
 			}
+			if let Some((prompt, _, _)) = self.select_expectation {
+				panic!("`{prompt}` select prompt expectation not satisfied")
+			}
 			if !self.success_expectations.is_empty() {
 				panic!(
 					"`{}` success log expectations not satisfied",
@@ -242,6 +339,15 @@ pub(crate) mod tests {
 			Ok(())
 		}
 
+		fn input(&mut self, prompt: impl Display) -> impl Input {
+			let prompt = prompt.to_string();
+			if let Some((expectation, input)) = self.input_expectation.take() {
+				assert_eq!(expectation, prompt, "prompt does not satisfy expectation");
+				return MockInput { default_input: None, input: Some(input) };
+			}
+			MockInput::default()
+		}
+
 		fn intro(&mut self, title: impl Display) -> Result<()> {
 			if let Some(expectation) = self.intro_expectation.take() {
 				assert_eq!(expectation, title.to_string(), "intro does not satisfy expectation");
@@ -288,6 +394,20 @@ pub(crate) mod tests {
 			Ok(())
 		}
 
+		fn select<T: Clone + Eq>(&mut self, prompt: impl Display) -> impl Select<T> {
+			let prompt = prompt.to_string();
+			if let Some((expectation, items_expectation, item)) = self.select_expectation.take() {
+				assert_eq!(expectation, prompt, "prompt does not satisfy expectation");
+				return MockSelect {
+					items_expectation,
+					item: Some(item),
+					items: vec![],
+					initial_value: None,
+				};
+			}
+			MockSelect::default()
+		}
+
 		fn success(&mut self, message: impl Display) -> Result<()> {
 			let message = message.to_string();
 			self.success_expectations.retain(|x| *x != message);
@@ -313,6 +433,25 @@ pub(crate) mod tests {
 		}
 	}
 
+	/// Mock input prompt
+	#[derive(Default)]
+	struct MockInput {
+		default_input: Option<String>,
+		input: Option<String>,
+	}
+
+	impl Input for MockInput {
+		fn default_input(mut self, value: &str) -> Self {
+			self.default_input = Some(value.to_string());
+			self
+		}
+
+		fn interact(&mut self) -> Result<String> {
+			// Pass the expected input, otherwise the default.
+			Ok(self.input.clone().or_else(|| self.default_input.clone()).unwrap_or_default())
+		}
+	}
+
 	/// Mock multi-select prompt
 	pub(crate) struct MockMultiSelect<T> {
 		required_expectation: Option<bool>,
@@ -360,4 +499,43 @@ pub(crate) mod tests {
 			self
 		}
 	}
+
+	/// Mock select prompt
+	pub(crate) struct MockSelect<T> {
+		items_expectation: Option<Vec<(String, String)>>,
+		/// The index of the item selected, otherwise the initial value.
+		item: Option<usize>,
+		items: Vec<T>,
+		initial_value: Option<T>,
+	}
+
+	impl<T> MockSelect<T> {
+		pub(crate) fn default() -> Self {
+			Self { items_expectation: None, item: None, items: vec![], initial_value: None }
+		}
+	}
+
+	impl<T: Clone + Eq> Select<T> for MockSelect<T> {
+		fn initial_value(mut self, value: T) -> Self {
+			self.initial_value = Some(value);
+			self
+		}
+
+		fn interact(&mut self) -> Result<T> {
+			// Pass the expected item, otherwise the initial value.
+			let item = self.item.and_then(|i| self.items.get(i)).or(self.initial_value.as_ref());
+			Ok(item.cloned().expect("an item to be selected"))
+		}
+
+		fn item(mut self, value: T, label: impl Display, hint: impl Display) -> Self {
+			// Check expectations
+			if let Some(items) = self.items_expectation.as_mut() {
+				let item = (label.to_string(), hint.to_string());
+				assert!(items.contains(&item), "`{item:?}` item does not satisfy any expectations");
+				items.retain(|x| *x != item);
+			}
+			self.items.push(value);
+			self
+		}
+	}
 }
@@ -2,8 +2,9 @@
 
 use crate::cli;
 use clap::Args;
+use pop_common::Resolution;
 use pop_contracts::{build_smart_contract, Verbosity};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(not(test))]
 use std::{thread::sleep, time::Duration};
 
@@ -16,6 +17,12 @@ pub struct BuildContractCommand {
 	/// usage. For production, always build in release mode to exclude debug features.
 	#[clap(short, long)]
 	pub(crate) release: bool,
+	/// Require the lockfile to be up to date, failing rather than updating it.
+	#[arg(long)]
+	pub(crate) locked: bool,
+	/// Build without accessing the network, using only the dependencies cached locally.
+	#[arg(long)]
+	pub(crate) offline: bool,
 	// Deprecation flag, used to specify whether the deprecation warning is shown.
 	#[clap(skip)]
 	pub(crate) valid: bool,
@@ -42,8 +49,14 @@ impl BuildContractCommand {
 		}
 
 		// Build contract.
-		let build_result =
-			build_smart_contract(self.path.as_deref(), self.release, Verbosity::Default)?;
+		super::ensure_toolchain(self.path.as_deref().unwrap_or(Path::new("./")), cli)?;
+		let resolution = Resolution { locked: self.locked, offline: self.offline };
+		let build_result = build_smart_contract(
+			self.path.as_deref(),
+			self.release,
+			Verbosity::Default,
+			&resolution,
+		)?;
 		cli.success(build_result.display())?;
 		cli.outro("Build completed successfully!")?;
 		Ok("contract")
@@ -76,8 +89,14 @@ mod tests {
 				}
 
 				assert_eq!(
-					BuildContractCommand { path: Some(path.join(name)), release, valid }
-						.build(&mut cli)?,
+					BuildContractCommand {
+						path: Some(path.join(name)),
+						release,
+						locked: false,
+						offline: false,
+						valid,
+					}
+					.build(&mut cli)?,
 					"contract"
 				);
 
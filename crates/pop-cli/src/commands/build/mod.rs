@@ -5,15 +5,27 @@ use clap::{Args, Subcommand};
 #[cfg(feature = "contract")]
 use contract::BuildContractCommand;
 use duct::cmd;
-use std::path::PathBuf;
+use pop_common::{
+	toolchain::{Toolchain, TOOLCHAIN_FILE},
+	Features, Profile, Resolution,
+};
+use std::path::{Path, PathBuf};
 #[cfg(feature = "parachain")]
-use {parachain::BuildParachainCommand, spec::BuildSpecCommand};
+use {
+	parachain::BuildParachainCommand,
+	pop_common::ArtifactSigner,
+	pop_parachains::{runtime_size_report, RuntimeArtifacts},
+	runtime::BuildRuntimeCommand,
+	spec::BuildSpecCommand,
+};
 
 #[cfg(feature = "contract")]
 pub(crate) mod contract;
 #[cfg(feature = "parachain")]
 pub(crate) mod parachain;
 #[cfg(feature = "parachain")]
+pub(crate) mod runtime;
+#[cfg(feature = "parachain")]
 pub(crate) mod spec;
 
 /// Arguments for building a project.
@@ -31,10 +43,54 @@ pub(crate) struct BuildArgs {
 	/// For production, always build in release mode to exclude debug features.
 	#[clap(short, long)]
 	pub(crate) release: bool,
+	/// Build with a custom profile defined within the manifest (e.g. "production"), rather than
+	/// the debug or release profile.
+	#[arg(long, conflicts_with = "release")]
+	pub(crate) profile: Option<Profile>,
+	/// Comma-separated list of features to be activated (e.g. "runtime-benchmarks,try-runtime").
+	#[arg(short = 'F', long, value_delimiter = ',')]
+	pub(crate) features: Vec<String>,
+	/// Do not activate the `default` features.
+	#[arg(long)]
+	pub(crate) no_default_features: bool,
+	/// Require the lockfile to be up to date, failing rather than updating it.
+	#[arg(long)]
+	pub(crate) locked: bool,
+	/// Build without accessing the network, using only the dependencies cached locally.
+	#[arg(long)]
+	pub(crate) offline: bool,
+	/// Cross-compile for the specified target triple (e.g. "aarch64-unknown-linux-gnu").
+	#[arg(long, value_name = "TRIPLE")]
+	pub(crate) target: Option<String>,
 	/// Parachain ID to be used when generating the chain spec files.
 	#[arg(short = 'i', long = "id")]
 	#[cfg(feature = "parachain")]
 	pub(crate) id: Option<u32>,
+	/// Build on a remote host over SSH (e.g. "user@build-server:2222"), syncing the artifacts
+	/// back. Defaults to the `[remote]` host configured in `pop.toml`, if any.
+	#[arg(long, value_name = "HOST")]
+	#[cfg(feature = "parachain")]
+	pub(crate) remote: Option<String>,
+	/// Launch the node in development mode on the remote host once built, forwarding its RPC port
+	/// [default: 9944] so that it can be reached locally, until terminated.
+	#[arg(long, value_name = "RPC_PORT", num_args = 0..=1, default_missing_value = "9944")]
+	#[cfg(feature = "parachain")]
+	pub(crate) launch: Option<u16>,
+	/// Build within a container, rather than with the local toolchain. Offered whenever the
+	/// toolchain required to build locally is missing.
+	#[arg(long, conflicts_with = "remote")]
+	#[cfg(feature = "parachain")]
+	pub(crate) container: bool,
+	/// Build through a compilation cache to speed up rebuilds, using `sccache` if installed.
+	/// Always enabled when a `[cache]` is configured in `pop.toml`.
+	#[arg(long)]
+	#[cfg(feature = "parachain")]
+	pub(crate) cache: bool,
+	/// Sign the binary and runtime once built, so that their publication can be authenticated:
+	/// either `minisign:<secret-key>` or `sigstore` (keyless, via `cosign`).
+	#[arg(long, value_name = "SIGNER")]
+	#[cfg(feature = "parachain")]
+	pub(crate) sign: Option<ArtifactSigner>,
 }
 
 /// Build a parachain, smart contract or Rust package.
@@ -49,10 +105,14 @@ pub(crate) enum Command {
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
 	Contract(BuildContractCommand),
+	/// Build only the runtime of a parachain, producing its WASM blobs.
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "r")]
+	Runtime(BuildRuntimeCommand),
 	/// Build a chain specification and its genesis artifacts.
 	#[cfg(feature = "parachain")]
 	#[clap(alias = "s")]
-	Spec(BuildSpecCommand),
+	Spec(Box<BuildSpecCommand>),
 }
 
 impl Command {
@@ -62,8 +122,14 @@ impl Command {
 		#[cfg(feature = "contract")]
 		if pop_contracts::is_supported(args.path.as_deref())? {
 			// All commands originating from root command are valid
-			BuildContractCommand { path: args.path, release: args.release, valid: true }
-				.execute()?;
+			BuildContractCommand {
+				path: args.path,
+				release: args.release,
+				locked: args.locked,
+				offline: args.offline,
+				valid: true,
+			}
+			.execute()?;
 			return Ok("contract");
 		}
 
@@ -75,7 +141,18 @@ impl Command {
 				path: args.path,
 				package: args.package,
 				release: args.release,
+				profile: args.profile,
+				features: args.features,
+				no_default_features: args.no_default_features,
+				locked: args.locked,
+				offline: args.offline,
+				target: args.target,
 				id: args.id,
+				remote: args.remote,
+				launch: args.launch,
+				container: args.container,
+				cache: args.cache,
+				sign: args.sign,
 				valid: true,
 			}
 			.execute()?;
@@ -96,23 +173,84 @@ impl Command {
 		let project = if args.package.is_some() { "package" } else { "project" };
 		cli.intro(format!("Building your {project}"))?;
 
+		let path = args.path.unwrap_or_else(|| "./".into());
+		ensure_toolchain(&path, cli)?;
 		let mut _args = vec!["build"];
 		if let Some(package) = args.package.as_deref() {
 			_args.push("--package");
 			_args.push(package)
 		}
-		if args.release {
-			_args.push("--release");
+		let mode = args.profile.unwrap_or_else(|| args.release.into());
+		let features =
+			Features { features: args.features, no_default_features: args.no_default_features };
+		let resolution = Resolution { locked: args.locked, offline: args.offline };
+		let (profile_args, features, resolution) =
+			(mode.args(), features.args(), resolution.args());
+		if let Some(target) = args.target.as_deref() {
+			_args.extend(["--target", target]);
 		}
-		cmd("cargo", _args).dir(args.path.unwrap_or_else(|| "./".into())).run()?;
+		_args.extend(profile_args.iter().chain(&features).chain(&resolution).map(String::as_str));
+		cmd("cargo", _args).dir(path).run()?;
 
-		let mode = if args.release { "RELEASE" } else { "DEBUG" };
 		cli.info(format!("The {project} was built in {mode} mode."))?;
 		cli.outro("Build completed successfully!")?;
 		Ok(project)
 	}
 }
 
+/// Installs whatever is missing of the toolchain pinned within the `rust-toolchain.toml` file of a
+/// project, if any, before it is built. Warns when the pinned channel moves over time.
+///
+/// # Arguments
+/// * `project` - The project directory.
+/// * `cli` - The CLI implementation to be used.
+pub(crate) fn ensure_toolchain(
+	project: &Path,
+	cli: &mut impl cli::traits::Cli,
+) -> anyhow::Result<()> {
+	let Some(toolchain) = Toolchain::load(project)? else {
+		return Ok(());
+	};
+	if toolchain.is_moving() {
+		cli.warning(format!(
+			"NOTE: the `{}` channel pinned within {TOOLCHAIN_FILE} moves over time, so builds may not be reproducible. Consider pinning a release (e.g. `1.81.0`)...",
+			toolchain.channel
+		))?;
+	}
+	let missing = toolchain.missing();
+	if !missing.is_empty() {
+		cli.info(format!("Installing {missing}, as pinned within {TOOLCHAIN_FILE}..."))?;
+		toolchain.ensure()?;
+	}
+	Ok(())
+}
+
+/// Reports the size of a built runtime, warning when it grew significantly since the previous
+/// build.
+///
+/// # Arguments
+/// * `artifacts` - The artifacts of the runtime.
+/// * `cli` - The CLI implementation to be used.
+#[cfg(feature = "parachain")]
+pub(crate) fn report_runtime_size(
+	artifacts: &RuntimeArtifacts,
+	cli: &mut impl cli::traits::Cli,
+) -> anyhow::Result<()> {
+	match runtime_size_report(artifacts) {
+		Ok(report) => {
+			cli.info(format!("Runtime size report:\n{report}"))?;
+			if report.regressed() {
+				cli.warning(format!(
+					"NOTE: the compressed runtime grew by {:.1}% since the previous build",
+					report.growth().unwrap_or_default()
+				))?;
+			}
+		},
+		Err(e) => cli.warning(format!("NOTE: unable to report the size of the runtime: {e}"))?,
+	}
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -141,7 +279,18 @@ mod tests {
 							path: Some(path.join(name)),
 							package: package.clone(),
 							release,
+							profile: None,
+							features: vec![],
+							no_default_features: false,
+							locked: false,
+							offline: false,
+							target: None,
 							id: None,
+							remote: None,
+							launch: None,
+							container: false,
+							cache: false,
+							sign: None,
 						},
 						&mut cli,
 					)?,
@@ -154,4 +303,20 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	#[cfg(feature = "parachain")]
+	fn report_runtime_size_warns_without_runtime() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let artifacts = RuntimeArtifacts {
+			package: "runtime".into(),
+			compact: temp_dir.path().join("runtime.compact.wasm"),
+			compressed: temp_dir.path().join("runtime.compact.compressed.wasm"),
+		};
+		let mut cli = MockCli::new().expect_warning(
+			"NOTE: unable to report the size of the runtime: IO error: No such file or directory (os error 2)",
+		);
+		report_runtime_size(&artifacts, &mut cli)?;
+		cli.verify()
+	}
 }
@@ -1,10 +1,30 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::{cli, style::style};
+use crate::{
+	cli::{self, traits::Confirm as _},
+	commands::stats,
+	common::output::{log, Spinner},
+	style::style,
+};
 use clap::Args;
-use pop_common::Profile;
-use pop_parachains::build_parachain;
-use std::path::PathBuf;
+use pop_common::{
+	cache::CompilationCache,
+	hooks::{HookContext, Hooks, Stage},
+	missing_toolchain,
+	preflight::{self, Requirements, Shortfall, SKIP_VAR},
+	progress::{locked_packages, ndjson_requested, NdjsonReporter},
+	remote::Remote,
+	stats::{Stats, Timer},
+	ArtifactSigner, BuildMessage, BuildReporter, Features, Profile, Resolution,
+};
+use pop_parachains::{
+	build_parachain, build_parachain_in_container, build_parachain_remotely, launch_remotely,
+	BuildOptions,
+};
+use std::{
+	cell::{Cell, RefCell},
+	path::PathBuf,
+};
 #[cfg(not(test))]
 use std::{thread::sleep, time::Duration};
 
@@ -19,9 +39,48 @@ pub struct BuildParachainCommand {
 	/// For production, always build in release mode to exclude debug features.
 	#[clap(short, long, default_value = "true")]
 	pub(crate) release: bool,
+	/// Build with a custom profile defined within the manifest (e.g. "production"), rather than
+	/// the debug or release profile.
+	#[arg(long, conflicts_with = "release")]
+	pub(crate) profile: Option<Profile>,
+	/// Comma-separated list of features to be activated (e.g. "runtime-benchmarks,try-runtime").
+	#[arg(short = 'F', long, value_delimiter = ',')]
+	pub(crate) features: Vec<String>,
+	/// Do not activate the `default` features.
+	#[arg(long)]
+	pub(crate) no_default_features: bool,
+	/// Require the lockfile to be up to date, failing rather than updating it.
+	#[arg(long)]
+	pub(crate) locked: bool,
+	/// Build without accessing the network, using only the dependencies cached locally.
+	#[arg(long)]
+	pub(crate) offline: bool,
+	/// Cross-compile for the specified target triple (e.g. "aarch64-unknown-linux-gnu").
+	#[arg(long, value_name = "TRIPLE")]
+	pub(crate) target: Option<String>,
 	/// Parachain ID to be used when generating the chain spec files.
 	#[arg(short = 'i', long = "id")]
 	pub(crate) id: Option<u32>,
+	/// Build on a remote host over SSH (e.g. "user@build-server:2222"), syncing the artifacts
+	/// back. Defaults to the `[remote]` host configured in `pop.toml`, if any.
+	#[arg(long, value_name = "HOST")]
+	pub(crate) remote: Option<String>,
+	/// Launch the node in development mode on the remote host once built, forwarding its RPC port
+	/// [default: 9944] so that it can be reached locally, until terminated.
+	#[arg(long, value_name = "RPC_PORT", num_args = 0..=1, default_missing_value = "9944")]
+	pub(crate) launch: Option<u16>,
+	/// Build within a container, rather than with the local toolchain. Offered whenever the
+	/// toolchain required to build locally is missing.
+	#[arg(long, conflicts_with = "remote")]
+	pub(crate) container: bool,
+	/// Build through a compilation cache to speed up rebuilds, using `sccache` if installed.
+	/// Always enabled when a `[cache]` is configured in `pop.toml`.
+	#[arg(long)]
+	pub(crate) cache: bool,
+	/// Sign the binary and runtime once built, so that their publication can be authenticated:
+	/// either `minisign:<secret-key>` or `sigstore` (keyless, via `cosign`).
+	#[arg(long, value_name = "SIGNER")]
+	pub(crate) sign: Option<ArtifactSigner>,
 	// Deprecation flag, used to specify whether the deprecation warning is shown.
 	#[clap(skip)]
 	pub(crate) valid: bool,
@@ -30,14 +89,20 @@ pub struct BuildParachainCommand {
 impl BuildParachainCommand {
 	/// Executes the command.
 	pub(crate) fn execute(self) -> anyhow::Result<&'static str> {
-		self.build(&mut cli::Cli)
+		let stats = crate::cache().ok().map(|cache| Stats::new(&cache));
+		self.build(&mut cli::Cli, stats.as_ref())
 	}
 
 	/// Builds a parachain.
 	///
 	/// # Arguments
 	/// * `cli` - The CLI implementation to be used.
-	fn build(self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<&'static str> {
+	/// * `stats` - The records to which the duration of the build is appended, if any.
+	fn build(
+		self,
+		cli: &mut impl cli::traits::Cli,
+		stats: Option<&Stats>,
+	) -> anyhow::Result<&'static str> {
 		let project = if self.package.is_some() { "package" } else { "parachain" };
 		cli.intro(format!("Building your {project}"))?;
 
@@ -55,16 +120,152 @@ impl BuildParachainCommand {
 		// Build parachain.
 		cli.warning("NOTE: this may take some time...")?;
 		let project_path = self.path.unwrap_or_else(|| PathBuf::from("./"));
-		let mode: Profile = self.release.into();
-		let binary = build_parachain(&project_path, self.package, &mode, None)?;
+		let mode = self.profile.unwrap_or_else(|| self.release.into());
+		let features =
+			Features { features: self.features, no_default_features: self.no_default_features };
+		let resolution = Resolution { locked: self.locked, offline: self.offline };
+		let remote = match self.remote.as_deref() {
+			Some(host) => Some(Remote::parse(host)?),
+			None => Remote::load(&project_path)?,
+		};
+		if self.launch.is_some() && remote.is_none() {
+			cli.outro_cancel("🚫 The node can only be launched once built on a remote host: specify `--remote` or configure a `[remote]` in pop.toml.")?;
+			return Ok(project);
+		}
+		// Install the toolchain pinned by the project, or otherwise offer to build within a
+		// container if the local toolchain is missing.
+		let mut container = self.container;
+		if remote.is_none() && !container {
+			super::ensure_toolchain(&project_path, cli)?;
+			if let Some(missing) = missing_toolchain() {
+				container = cli
+					.confirm(format!(
+						"{missing} is not installed. Would you like to build within a container instead?"
+					))
+					.interact()?;
+			}
+		}
+		// Warn if the resources estimated to be required to build locally are unavailable, as the
+		// build may then fail, allowing it to be cancelled. Resources are not detected by tests,
+		// which would otherwise depend on those of the host.
+		if remote.is_none() {
+			let shortfalls = match cfg!(test) {
+				true => vec![],
+				false => preflight::shortfalls(&project_path, &Requirements::build(&project_path)),
+			};
+			if !confirm_resources(&shortfalls, cli)? {
+				cli.outro_cancel("🚫 The build was cancelled.")?;
+				return Ok(project);
+			}
+		}
+		// Build through a compilation cache when requested or configured, but only locally.
+		let cache = match remote.is_none() && !container {
+			true if self.cache => CompilationCache::resolve(&project_path)?,
+			true => CompilationCache::load(&project_path)?,
+			false => None,
+		};
+		if let Some(cache) = &cache {
+			cache.enable()?;
+			cli.info(format!("Using the `{}` compilation cache...", cache.wrapper))?;
+		} else if self.cache && remote.is_none() && !container {
+			cli.warning("NOTE: no compilation cache was found. Install `sccache` or configure a `[cache]` in pop.toml...")?;
+		}
+		let hooks = Hooks::load(&project_path)?;
+		hooks.run(Stage::PreBuild, &HookContext::default())?;
+		let reporter = ndjson_requested()
+			.then(|| NdjsonReporter::stderr("build").with_total(locked_packages(&project_path)));
+		if let Some(reporter) = &reporter {
+			reporter.started();
+		}
+		let timer = Timer::start("build")
+			.project(&project_path)
+			.cached(mode.target_directory_for(&project_path, self.target.as_deref()).exists());
+		let options = BuildOptions {
+			package: self.package,
+			features,
+			resolution,
+			target: self.target,
+			container,
+			..BuildOptions::new(mode.clone())
+		};
+		let result = match &remote {
+			Some(remote) => {
+				cli.info(format!("Building on {}...", remote.host))?;
+				build_parachain_remotely(remote, &project_path, &options)
+			},
+			None if container => {
+				cli.info("Building within a container...")?;
+				build_parachain_in_container(&project_path, &options)
+			},
+			None => {
+				let progress = BuildProgress::new(reporter.as_ref());
+				let result = build_parachain(&project_path, &options, &progress);
+				progress.finish(result.is_ok());
+				result
+			},
+		};
+		if let Some(stats) = stats {
+			stats::record(stats, timer.finish(result.is_ok()));
+		}
+		if let Some(stats) = cache.as_ref().and_then(CompilationCache::stats) {
+			cli.info(format!("Compilation cache: {stats}"))?;
+		}
+		let artifacts = result.inspect_err(|e| {
+			if let Some(reporter) = &reporter {
+				reporter.failed(&e.to_string());
+			}
+		})?;
+		if let Some(reporter) = reporter {
+			reporter.artifact(&artifacts.binary);
+			if let Some(runtime) = &artifacts.runtime {
+				reporter.artifact(&runtime.compressed);
+			}
+			reporter.completed();
+		}
+		let mut context = HookContext::default().artifact("binary", &artifacts.binary);
+		if let Some(runtime) = &artifacts.runtime {
+			context = context.artifact("runtime", &runtime.compressed);
+		}
+		// Sign the artifacts before the post-build hooks, so that hooks can publish signatures.
+		let mut signatures = vec![];
+		if let Some(signer) = &self.sign {
+			cli.info(format!("Signing the artifacts with {signer}..."))?;
+			let runtime = artifacts.runtime.as_ref().map(|runtime| &runtime.compressed);
+			for (name, artifact) in [("binary", Some(&artifacts.binary)), ("runtime", runtime)] {
+				let Some(artifact) = artifact else { continue };
+				let signature = signer.sign(artifact)?;
+				context = context.artifact(&format!("{name}-signature"), &signature);
+				signatures.push(signature);
+			}
+		}
+		hooks.run(Stage::PostBuild, &context)?;
+		if let Some(runtime) = &artifacts.runtime {
+			super::report_runtime_size(runtime, cli)?;
+		}
 		cli.info(format!("The {project} was built in {mode} mode."))?;
 		cli.outro("Build completed successfully!")?;
-		let generated_files = [format!("Binary generated at: {}", binary.display())];
+		let mut generated_files =
+			vec![format!("Binary generated at: {}", artifacts.binary.display())];
+		if let Some(runtime) = &artifacts.runtime {
+			generated_files.push(format!("Runtime generated at: {}", runtime.compact.display()));
+			generated_files
+				.push(format!("Compressed runtime generated at: {}", runtime.compressed.display()));
+		}
+		generated_files
+			.extend(signatures.iter().map(|s| format!("Signature generated at: {}", s.display())));
 		let generated_files: Vec<_> = generated_files
 			.iter()
 			.map(|s| style(format!("{} {s}", console::Emoji("●", ">"))).dim().to_string())
 			.collect();
 		cli.success(format!("Generated files:\n{}", generated_files.join("\n")))?;
+		if let (Some(remote), Some(port)) = (&remote, self.launch) {
+			let node = launch_remotely(remote, &project_path, &options, &["--dev"], port)?;
+			cli.info(format!(
+				"🚀 The node was launched on {}, reachable at ws://127.0.0.1:{port} - ctrl-c to terminate",
+				remote.host
+			))?;
+			node.wait()?;
+		}
 		cli.outro(format!(
 			"Need help? Learn more at {}\n",
 			style("https://learn.onpop.io").magenta().underlined()
@@ -74,6 +275,102 @@ impl BuildParachainCommand {
 	}
 }
 
+/// Warns of any shortfalls in the resources estimated to be required by a build, confirming
+/// whether to build regardless.
+///
+/// # Arguments
+/// * `shortfalls` - The shortfalls in the resources available.
+/// * `cli` - The CLI implementation to be used.
+fn confirm_resources(
+	shortfalls: &[Shortfall],
+	cli: &mut impl cli::traits::Cli,
+) -> anyhow::Result<bool> {
+	if shortfalls.is_empty() {
+		return Ok(true);
+	}
+	for shortfall in shortfalls {
+		cli.warning(format!("⚠️ {shortfall}"))?;
+	}
+	Ok(cli
+		.confirm(format!(
+			"The build may fail due to insufficient resources (set `{SKIP_VAR}=1` to skip this check). Would you like to build regardless?"
+		))
+		.interact()?)
+}
+
+/// Renders the progress of a build as a spinner, or emits progress events when requested.
+pub(super) struct BuildProgress<'a> {
+	/// The spinner showing the crate being compiled, which is not rendered whilst progress events
+	/// are emitted.
+	spinner: Spinner,
+	/// The reporter of progress events, if requested.
+	events: Option<&'a NdjsonReporter>,
+	/// The number of crates compiled.
+	compiled: Cell<usize>,
+	/// The number of warnings emitted.
+	warnings: Cell<usize>,
+	/// The errors emitted, rendered once the spinner has stopped.
+	errors: RefCell<Vec<String>>,
+}
+
+impl<'a> BuildProgress<'a> {
+	/// Starts rendering the progress of a build.
+	///
+	/// # Arguments
+	/// * `events` - The reporter of progress events, if requested.
+	pub(super) fn new(events: Option<&'a NdjsonReporter>) -> Self {
+		let spinner = Spinner::start("Compiling...");
+		Self {
+			spinner,
+			events,
+			compiled: Cell::new(0),
+			warnings: Cell::new(0),
+			errors: RefCell::new(vec![]),
+		}
+	}
+
+	/// Stops rendering the progress of the build.
+	///
+	/// # Arguments
+	/// * `success` - Whether the build succeeded.
+	pub(super) fn finish(&self, success: bool) {
+		let summary = format!(
+			"{} crates compiled with {} warning(s)",
+			self.compiled.get(),
+			self.warnings.get()
+		);
+		match success {
+			true => self.spinner.stop(summary),
+			false => self.spinner.error(summary),
+		}
+		// Errors are rendered in full, as the build output is otherwise not shown. Progress events
+		// include them already.
+		for error in self.errors.take() {
+			let _ = log::error(error);
+		}
+	}
+}
+
+impl BuildReporter for BuildProgress<'_> {
+	fn report(&self, message: &BuildMessage) {
+		if let Some(events) = self.events {
+			events.report(message);
+		}
+		match message {
+			BuildMessage::Compiled { name, .. } => {
+				self.compiled.set(self.compiled.get() + 1);
+				self.spinner.set_message(format!(
+					"Compiling {name} ({} crates compiled)",
+					self.compiled.get()
+				));
+			},
+			BuildMessage::Warning(_) => self.warnings.set(self.warnings.get() + 1),
+			BuildMessage::Error(error) => self.errors.borrow_mut().push(error.clone()),
+			BuildMessage::Finished { .. } => {},
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -133,10 +430,21 @@ mod tests {
 							path: Some(path.join(name)),
 							package: package.clone(),
 							release,
+							profile: None,
+							features: vec![],
+							no_default_features: false,
+							locked: false,
+							offline: false,
+							target: None,
 							id: None,
+							remote: None,
+							launch: None,
+							container: false,
+							cache: false,
+							sign: None,
 							valid,
 						}
-						.build(&mut cli)?,
+						.build(&mut cli, None)?,
 						project
 					);
 
@@ -147,4 +455,57 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn launch_requires_remote() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut cli = MockCli::new()
+			.expect_intro("Building your parachain")
+			.expect_warning("NOTE: this may take some time...")
+			.expect_outro_cancel("🚫 The node can only be launched once built on a remote host: specify `--remote` or configure a `[remote]` in pop.toml.");
+		assert_eq!(
+			BuildParachainCommand {
+				path: Some(temp_dir.path().to_path_buf()),
+				package: None,
+				release: true,
+				profile: None,
+				features: vec![],
+				no_default_features: false,
+				locked: false,
+				offline: false,
+				target: None,
+				id: None,
+				remote: None,
+				launch: Some(9944),
+				container: false,
+				cache: false,
+				sign: None,
+				valid: true,
+			}
+			.build(&mut cli, None)?,
+			"parachain"
+		);
+		cli.verify()
+	}
+
+	#[test]
+	fn confirm_resources_works() -> anyhow::Result<()> {
+		let mut cli = MockCli::new();
+		assert!(confirm_resources(&[], &mut cli)?);
+		cli.verify()?;
+
+		const GIB: u64 = 1024 * 1024 * 1024;
+		let shortfall = Shortfall::Disk { available: 2 * GIB, required: 20 * GIB };
+		for confirm in [false, true] {
+			let mut cli = MockCli::new()
+				.expect_warning(format!("⚠️ {shortfall}"))
+				.expect_confirm(
+					"The build may fail due to insufficient resources (set `POP_SKIP_PREFLIGHT=1` to skip this check). Would you like to build regardless?",
+					confirm,
+				);
+			assert_eq!(confirm_resources(&[shortfall], &mut cli)?, confirm);
+			cli.verify()?;
+		}
+		Ok(())
+	}
 }
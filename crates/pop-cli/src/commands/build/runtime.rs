@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::parachain::BuildProgress;
+use crate::{cli, style::style};
+use clap::Args;
+use pop_common::{Features, Profile, Resolution};
+use pop_parachains::{
+	build_deterministic_runtime, build_runtime, BuildOptions, RuntimeDigest, Srtool, SRTOOL_TAG,
+};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct BuildRuntimeCommand {
+	/// Directory path for your project [default: current directory].
+	#[arg(long)]
+	pub(crate) path: Option<PathBuf>,
+	/// The runtime package to be built [default: the runtime located within the project].
+	#[arg(short = 'p', long)]
+	pub(crate) package: Option<String>,
+	/// For production, always build in release mode to exclude debug features.
+	#[clap(short, long)]
+	pub(crate) release: bool,
+	/// Build with a custom profile defined within the manifest (e.g. "production"), rather than
+	/// the debug or release profile.
+	#[arg(long, conflicts_with = "release")]
+	pub(crate) profile: Option<Profile>,
+	/// Comma-separated list of features to be activated (e.g. "runtime-benchmarks,try-runtime").
+	#[arg(short = 'F', long, value_delimiter = ',')]
+	pub(crate) features: Vec<String>,
+	/// Do not activate the `default` features.
+	#[arg(long)]
+	pub(crate) no_default_features: bool,
+	/// Require the lockfile to be up to date, failing rather than updating it.
+	#[arg(long)]
+	pub(crate) locked: bool,
+	/// Build without accessing the network, using only the dependencies cached locally.
+	#[arg(long)]
+	pub(crate) offline: bool,
+	/// Build deterministically within the srtool container, so that the runtime can be
+	/// reproduced and its digest verified (requires Docker or Podman).
+	#[arg(long)]
+	pub(crate) deterministic: bool,
+	/// The tag of the srtool image used for deterministic builds.
+	#[arg(long, default_value = SRTOOL_TAG, requires = "deterministic")]
+	pub(crate) srtool_tag: String,
+}
+
+impl BuildRuntimeCommand {
+	/// Executes the command.
+	pub(crate) fn execute(self) -> anyhow::Result<&'static str> {
+		self.build(&mut cli::Cli)
+	}
+
+	/// Builds a runtime.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	fn build(self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<&'static str> {
+		cli.intro("Building your runtime")?;
+		cli.warning("NOTE: this may take some time...")?;
+		let project_path = self.path.unwrap_or_else(|| PathBuf::from("./"));
+		let mode = self.profile.unwrap_or_else(|| self.release.into());
+		let features =
+			Features { features: self.features, no_default_features: self.no_default_features };
+		let resolution = Resolution { locked: self.locked, offline: self.offline };
+		if self.deterministic {
+			let srtool = Srtool {
+				tag: self.srtool_tag,
+				package: self.package,
+				profile: match &mode {
+					Profile::Debug => "dev".to_string(),
+					Profile::Release => "release".to_string(),
+					Profile::Custom(name) => name.clone(),
+				},
+				features,
+			};
+			let build = match build_deterministic_runtime(&project_path, &srtool) {
+				Ok(build) => build,
+				Err(e) => {
+					cli.outro_cancel(format!("🚫 {e}"))?;
+					return Ok("runtime");
+				},
+			};
+			cli.info(format!(
+				"The {} runtime was built deterministically with {}.",
+				build.package, build.rustc
+			))?;
+			let digest = |name: &str, digest: &RuntimeDigest| {
+				style(format!(
+					"{} {name} runtime generated at: {}\n  size: {} bytes\n  blake2-256: {}\n  proposal hash: {}\n  ipfs: {}",
+					console::Emoji("●", ">"),
+					digest.wasm.display(),
+					digest.size,
+					digest.blake2_256,
+					digest.proposal_hash,
+					digest.ipfs_hash
+				))
+				.dim()
+				.to_string()
+			};
+			cli.success(format!(
+				"Generated files:\n{}\n{}",
+				digest("Compact", &build.compact),
+				digest("Compressed", &build.compressed)
+			))?;
+			cli.outro("Build completed successfully!")?;
+			return Ok("runtime");
+		}
+		let options = BuildOptions {
+			package: self.package,
+			features,
+			resolution,
+			..BuildOptions::new(mode.clone())
+		};
+		let progress = BuildProgress::new(None);
+		let result = build_runtime(&project_path, &options, &progress);
+		progress.finish(result.is_ok());
+		let artifacts = match result {
+			Ok(artifacts) => artifacts,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok("runtime");
+			},
+		};
+		super::report_runtime_size(&artifacts, cli)?;
+		cli.info(format!("The {} runtime was built in {mode} mode.", artifacts.package))?;
+		let generated_files = [
+			format!("Compact runtime generated at: {}", artifacts.compact.display()),
+			format!("Compressed runtime generated at: {}", artifacts.compressed.display()),
+		];
+		let generated_files: Vec<_> = generated_files
+			.iter()
+			.map(|s| style(format!("{} {s}", console::Emoji("●", ">"))).dim().to_string())
+			.collect();
+		cli.success(format!("Generated files:\n{}", generated_files.join("\n")))?;
+		cli.outro("Build completed successfully!")?;
+		Ok("runtime")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cli::MockCli;
+
+	#[test]
+	fn build_fails_without_runtime() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut cli = MockCli::new()
+			.expect_intro("Building your runtime")
+			.expect_warning("NOTE: this may take some time...")
+			.expect_outro_cancel("🚫 Configuration error: no runtime package found; specify the runtime package to be built");
+		assert_eq!(
+			BuildRuntimeCommand {
+				path: Some(temp_dir.path().to_path_buf()),
+				package: None,
+				release: false,
+				profile: None,
+				features: vec![],
+				no_default_features: false,
+				locked: false,
+				offline: false,
+				deterministic: false,
+				srtool_tag: SRTOOL_TAG.into(),
+			}
+			.build(&mut cli)?,
+			"runtime"
+		);
+		cli.verify()
+	}
+
+	#[test]
+	fn deterministic_build_fails_without_runtime() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut cli = MockCli::new()
+			.expect_intro("Building your runtime")
+			.expect_warning("NOTE: this may take some time...")
+			.expect_outro_cancel("🚫 Configuration error: no runtime package found; specify the runtime package to be built");
+		assert_eq!(
+			BuildRuntimeCommand {
+				path: Some(temp_dir.path().to_path_buf()),
+				package: None,
+				release: true,
+				profile: None,
+				features: vec![],
+				no_default_features: false,
+				locked: false,
+				offline: false,
+				deterministic: true,
+				srtool_tag: SRTOOL_TAG.into(),
+			}
+			.build(&mut cli)?,
+			"runtime"
+		);
+		cli.verify()
+	}
+}
@@ -7,11 +7,20 @@ use crate::{
 };
 use clap::{Args, ValueEnum};
 use cliclack::{confirm, input};
-use pop_common::Profile;
+use pop_common::{
+	calls::parse_override,
+	hooks::{HookContext, Hooks, Stage},
+	parse_sink, Profile,
+};
 use pop_parachains::{
-	binary_path, build_parachain, export_wasm_file, generate_genesis_state_file,
-	generate_plain_chain_spec, generate_raw_chain_spec, is_supported, ChainSpec,
+	binary_path, build_parachain, build_runtime, chain_spec_builder, export_head_data,
+	export_wasm_file, extract_genesis_code, generate_genesis_state_file, generate_plain_chain_spec,
+	generate_plain_chain_spec_with_runtime, generate_raw_chain_spec,
+	generate_raw_chain_spec_with_runtime, is_supported, list_presets, load_collator_keys,
+	load_endowments, runtime_artifacts, validate_chain_spec, BuildOptions, ChainSpec,
+	ChainSpecTemplate, TemplateVariables,
 };
+use serde_json::Value;
 use std::{
 	env::current_dir,
 	fs::create_dir_all,
@@ -25,6 +34,8 @@ use strum_macros::{AsRefStr, Display, EnumString};
 const DEFAULT_PARA_ID: u32 = 2000;
 const DEFAULT_PROTOCOL_ID: &str = "my-protocol";
 const DEFAULT_SPEC_NAME: &str = "chain-spec.json";
+/// The balance of each account endowed within a chain spec template, unless specified.
+const DEFAULT_ENDOWMENT: u128 = 1_000_000_000_000_000;
 
 #[derive(
 	AsRefStr,
@@ -101,6 +112,12 @@ pub(crate) enum RelayChain {
 		detailed_message = "Local configuration for Westend network."
 	)]
 	WestendLocal,
+	#[strum(
+		serialize = "rococo-local",
+		message = "Rococo Local",
+		detailed_message = "Local configuration for Rococo network."
+	)]
+	RococoLocal,
 	#[strum(
 		serialize = "kusama",
 		message = "Kusama",
@@ -143,35 +160,140 @@ pub struct BuildSpecCommand {
 	/// Whether to keep localhost as a bootnode.
 	#[clap(long, default_value = "true")]
 	pub(crate) default_bootnode: bool,
+	/// The multiaddress of a bootnode to be added to the spec, including its peer ID (e.g.
+	/// `/dns/pop.r0gue.io/tcp/30333/p2p/12D3KooW...`).
+	#[arg(long = "bootnode")]
+	pub(crate) bootnodes: Vec<String>,
+	/// A telemetry endpoint to be added to the spec, either a multiaddress or a websocket URL
+	/// (e.g. `wss://telemetry.polkadot.io/submit/`), with a verbosity of 0.
+	#[arg(long = "telemetry-endpoint")]
+	pub(crate) telemetry_endpoints: Vec<String>,
 	/// Type of the chain [default: development].
 	#[arg(short = 't', long = "type", value_enum)]
 	pub(crate) chain_type: Option<ChainType>,
 	/// Relay chain this parachain will connect to [default: paseo-local].
 	#[arg(long, value_enum)]
 	pub(crate) relay: Option<RelayChain>,
+	/// The genesis preset from which the spec is generated (e.g. "development" or
+	/// "local_testnet") [default: the default chain of the node].
+	#[arg(long, conflicts_with = "template")]
+	pub(crate) preset: Option<String>,
+	/// List the genesis presets exposed by the runtime of the project, using
+	/// `chain-spec-builder`.
+	#[arg(long)]
+	pub(crate) list_presets: bool,
+	/// Generate the specs from the runtime using `chain-spec-builder`, rather than from the node.
+	/// Used automatically for projects without a node.
+	#[arg(long)]
+	pub(crate) from_runtime: bool,
+	/// A JSON patch deep-merged into the plain chain spec before the raw chain spec is generated,
+	/// e.g. `{"genesis": {"runtimeGenesis": {"patch": {"balances": ...}}}}`. A `null` removes
+	/// the key.
+	#[arg(long)]
+	pub(crate) genesis_patch: Option<PathBuf>,
+	/// A JSON file specifying the initial collator set, which replaces the invulnerables and
+	/// session keys within the genesis, e.g. `[{"account": "5Grw...", "keys": {"aura":
+	/// "5Grw..."}}]`.
+	#[arg(long)]
+	pub(crate) collator_keys: Option<PathBuf>,
+	/// A CSV (`account,balance` lines) or JSON file of the accounts endowed at genesis, which
+	/// replace the balances within the genesis. Addresses are validated against the `ss58Format`
+	/// of the chain spec, and accounts endowed more than once are rejected.
+	#[arg(long)]
+	pub(crate) endowments: Option<PathBuf>,
+	/// The EVM chain identifier of an EVM-compatible (Frontier) chain [default: the parachain ID,
+	/// where the spec mirrors it].
+	#[arg(long)]
+	pub(crate) evm_chain_id: Option<u64>,
+	/// An EVM account endowed at genesis, specified as `<address>=<balance>` (e.g.
+	/// `--evm-balance 0xf24F...=1000000000000000000`), which replace the EVM accounts within the
+	/// genesis. Accounts with code, such as precompiles, are retained.
+	#[arg(long = "evm-balance", value_parser = parse_evm_balance)]
+	pub(crate) evm_balances: Vec<(String, u128)>,
+	/// Comma-separated list of the addresses of the precompiles of an EVM-compatible chain, at
+	/// which code is placed within the genesis so that they are treated as contracts.
+	#[arg(long = "precompile", value_delimiter = ',')]
+	pub(crate) precompiles: Vec<String>,
 	/// Protocol-id to use in the specification.
 	#[arg(long = "protocol-id")]
 	pub(crate) protocol_id: Option<String>,
 	/// Whether the genesis state file should be generated [default: true].
 	#[clap(long = "genesis-state", default_value = "true")]
 	pub(crate) genesis_state: bool,
+	/// Export the genesis state from the head of a live parachain rather than from its genesis,
+	/// to re-register or recover the parachain: the websocket endpoint of a node of the
+	/// parachain.
+	#[arg(long)]
+	pub(crate) head_from: Option<url::Url>,
+	/// The number of the block whose head is exported via `--head-from` [default: the finalized
+	/// head].
+	#[arg(long, requires = "head_from")]
+	pub(crate) head_at: Option<u32>,
 	/// Whether the genesis code file should be generated [default: true].
 	#[clap(long = "genesis-code", default_value = "true")]
 	pub(crate) genesis_code: bool,
+	/// Publish the generated files to a destination: a directory, a tarball (`*.tar.gz`) or
+	/// S3-compatible storage (`s3://bucket/prefix`, using the `aws` CLI).
+	#[arg(long)]
+	pub(crate) publish: Option<String>,
+	/// Generate the plain chain spec from a template, rather than from the node. Placeholders
+	/// such as `{{sudo}}`, `{{collators}}` and `{{endowments}}` are substituted.
+	#[arg(long)]
+	pub(crate) template: Option<PathBuf>,
+	/// The environment whose overlay (`<template>.<env>.json`) is merged into the template.
+	#[arg(long, requires = "template")]
+	pub(crate) env: Option<String>,
+	/// The sudo account, substituted for `{{sudo}}` within the template.
+	#[arg(long, requires = "template")]
+	pub(crate) sudo: Option<String>,
+	/// A collator account, substituted for `{{collators}}` within the template.
+	#[arg(long = "collator", requires = "template")]
+	pub(crate) collators: Vec<String>,
+	/// An endowed account, substituted for `{{endowments}}` within the template.
+	#[arg(long = "endow", requires = "template")]
+	pub(crate) endowed: Vec<String>,
+	/// The balance of each endowed account.
+	#[arg(long, default_value_t = DEFAULT_ENDOWMENT)]
+	pub(crate) endowment: u128,
+	/// A further variable substituted within the template, specified as `<name>=<value>`, where
+	/// the value is parsed as JSON where possible (e.g. `--var token_decimals=12`).
+	#[arg(long = "var", value_parser = parse_variable, requires = "template")]
+	pub(crate) variables: Vec<(String, Value)>,
+}
+
+/// Parses a variable substituted within a chain spec template.
+fn parse_variable(arg: &str) -> Result<(String, Value), String> {
+	let (name, value) = parse_override(arg).map_err(|e| e.to_string())?;
+	let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+	Ok((name, value))
+}
+
+/// Parses an EVM account endowed at genesis.
+fn parse_evm_balance(arg: &str) -> Result<(String, u128), String> {
+	let (address, balance) = parse_override(arg).map_err(|e| e.to_string())?;
+	let balance = balance
+		.replace('_', "")
+		.parse()
+		.map_err(|_| format!("invalid balance `{balance}`"))?;
+	Ok((address, balance))
 }
 
 impl BuildSpecCommand {
 	/// Executes the command.
 	pub(crate) async fn execute(self) -> anyhow::Result<&'static str> {
 		// Checks for appchain project in `./`.
-		if is_supported(None)? {
+		if is_supported(None)? && self.list_presets {
+			self.list_presets(&mut Cli).await?;
+			Ok("spec")
+		} else if is_supported(None)? {
 			// If para id has been provided we can build the spec
 			// otherwise, we need to guide the user.
 			let _ = match self.id {
-				Some(_) => self.build(&mut Cli),
+				Some(_) => self.build(&mut Cli).await,
+				None if self.template.is_some() => self.build(&mut Cli).await,
 				None => {
 					let config = guide_user_to_generate_spec(self).await?;
-					config.build(&mut Cli)
+					config.build(&mut Cli).await
 				},
 			};
 			Ok("spec")
@@ -184,15 +306,84 @@ impl BuildSpecCommand {
 		}
 	}
 
+	/// Lists the genesis presets exposed by the runtime of the project.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn list_presets(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Listing genesis presets")?;
+		let cwd = current_dir().unwrap_or(PathBuf::from("./"));
+		let mode: Profile = self.release.into();
+		let Ok(runtime) =
+			runtime_artifacts(&mode.target_directory(&cwd), None).map(|runtime| runtime.compressed)
+		else {
+			cli.outro_cancel(
+				"🚫 The runtime was not found. Build the project first with `pop build`.",
+			)?;
+			return Ok(());
+		};
+		let presets = match source_chain_spec_builder(cli).await {
+			Ok(builder) => list_presets(&builder, &runtime).map_err(anyhow::Error::from),
+			Err(e) => Err(e),
+		};
+		match presets {
+			Ok(presets) if presets.is_empty() =>
+				cli.outro_cancel("🚫 The runtime does not expose any genesis presets.")?,
+			Ok(presets) => {
+				for preset in &presets {
+					cli.info(preset)?;
+				}
+				cli.outro("Generate a spec from a preset with `pop build spec --preset <PRESET>`")?;
+			},
+			Err(e) => cli.outro_cancel(format!(
+				"🚫 {e}. Install it with `cargo install staging-chain-spec-builder`."
+			))?,
+		}
+		Ok(())
+	}
+
+	/// The variables substituted within the template.
+	fn template_variables(&self) -> TemplateVariables {
+		TemplateVariables {
+			sudo: self.sudo.clone(),
+			collators: self.collators.clone(),
+			endowments: self
+				.endowed
+				.iter()
+				.map(|account| (account.clone(), self.endowment))
+				.collect(),
+			custom: self.variables.iter().cloned().collect(),
+		}
+	}
+
 	/// Builds a parachain spec.
 	///
 	/// # Arguments
 	/// * `cli` - The CLI implementation to be used.
-	fn build(self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<&'static str> {
+	async fn build(self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<&'static str> {
 		cli.intro("Building your chain spec")?;
 
-		// Either a para id was already provided or user has been guided to provide one.
-		let para_id = self.id.unwrap_or(DEFAULT_PARA_ID);
+		// Render the template for the environment, if specified.
+		let template = match self.template.as_deref() {
+			Some(path) => Some(
+				ChainSpecTemplate::load(path)?
+					.render(self.env.as_deref(), &self.template_variables())?,
+			),
+			None => None,
+		};
+		let templated = template.is_some();
+
+		// Either a para id was already provided, is specified by the template or user has been
+		// guided to provide one.
+		let para_id = self
+			.id
+			.or_else(|| {
+				template
+					.as_ref()
+					.and_then(|spec| spec.get_parachain_id())
+					.and_then(|id| u32::try_from(id).ok())
+			})
+			.unwrap_or(DEFAULT_PARA_ID);
 		// Notify user in case we need to build the parachain project.
 		if !self.release {
 			cli.warning("NOTE: this command defaults to DEBUG builds for development chain types. Please use `--release` (or simply `-r` for a release build...)")?;
@@ -200,6 +391,10 @@ impl BuildSpecCommand {
 			sleep(Duration::from_secs(3))
 		}
 
+		let cwd = current_dir().unwrap_or(PathBuf::from("./"));
+		let hooks = Hooks::load(&cwd)?;
+		hooks.run(Stage::PreSpec, &HookContext::default().value("para_id", para_id.to_string()))?;
+
 		let spinner = cliclack::spinner();
 		spinner.start("Generating chain specification...");
 
@@ -222,38 +417,104 @@ impl BuildSpecCommand {
 		}
 		plain_chain_spec.set_extension("json");
 
-		// Locate binary, if it doesn't exist trigger build.
+		// Locate binary, if it doesn't exist trigger build. Projects without a node generate
+		// their specs from the runtime instead.
 		let mode: Profile = self.release.into();
-		let cwd = current_dir().unwrap_or(PathBuf::from("./"));
-		let binary_path = match binary_path(&mode.target_directory(&cwd), &cwd.join("node")) {
-			Ok(binary_path) => binary_path,
-			_ => {
-				cli.info("Node was not found. The project will be built locally.".to_string())?;
-				cli.warning("NOTE: this may take some time...")?;
-				build_parachain(&cwd, None, &mode, None)?
-			},
+		let generator = if self.from_runtime || !cwd.join("node").exists() {
+			let runtime = match runtime_artifacts(&mode.target_directory(&cwd), None)
+				.map(|runtime| runtime.compressed)
+			{
+				Ok(runtime) => runtime,
+				_ => {
+					cli.info("Runtime was not found. The runtime will be built locally.")?;
+					cli.warning("NOTE: this may take some time...")?;
+					build_runtime(&cwd, &BuildOptions::new(mode.clone()), &())?.compressed
+				},
+			};
+			SpecGenerator::Runtime { builder: source_chain_spec_builder(cli).await?, runtime }
+		} else {
+			match binary_path(&mode.target_directory(&cwd), &cwd.join("node")) {
+				Ok(binary_path) => SpecGenerator::Node(binary_path),
+				_ => {
+					cli.info("Node was not found. The project will be built locally.".to_string())?;
+					cli.warning("NOTE: this may take some time...")?;
+					SpecGenerator::Node(
+						build_parachain(&cwd, &BuildOptions::new(mode.clone()), &())?.binary,
+					)
+				},
+			}
 		};
 
 		// Generate plain spec.
 		spinner.set_message("Generating plain chain specification...");
 		let mut generated_files = vec![];
-		generate_plain_chain_spec(&binary_path, &plain_chain_spec, self.default_bootnode)?;
+		let mut artifacts = vec![("plain_chain_spec", plain_chain_spec.clone())];
+		match (template, &generator) {
+			(Some(spec), _) => spec.to_file(&plain_chain_spec)?,
+			(None, SpecGenerator::Node(binary_path)) => generate_plain_chain_spec(
+				binary_path,
+				&plain_chain_spec,
+				self.default_bootnode,
+				self.preset.as_deref(),
+				self.relay.as_ref().map(|relay| relay.as_ref()),
+			)?,
+			(None, SpecGenerator::Runtime { builder, runtime }) =>
+				generate_plain_chain_spec_with_runtime(
+					builder,
+					runtime,
+					&plain_chain_spec,
+					self.preset.as_deref(),
+					para_id,
+					self.relay.clone().unwrap_or_default().as_ref(),
+				)?,
+		}
 		generated_files.push(format!(
 			"Plain text chain specification file generated at: {}",
 			plain_chain_spec.display()
 		));
 
-		// Customize spec based on input.
+		// Customize spec based on input. Values specified by a template are only replaced when
+		// explicitly provided.
 		let mut chain_spec = ChainSpec::from(&plain_chain_spec)?;
-		chain_spec.replace_para_id(para_id)?;
-		let relay = self.relay.unwrap_or(RelayChain::PaseoLocal).to_string();
-		chain_spec.replace_relay_chain(&relay)?;
-		let chain_type = self.chain_type.unwrap_or(ChainType::Development).to_string();
-		chain_spec.replace_chain_type(&chain_type)?;
+		if !templated || self.id.is_some() {
+			chain_spec.replace_para_id(para_id)?;
+		}
+		if let Some(relay) = self.relay.clone().or((!templated).then_some(RelayChain::PaseoLocal)) {
+			chain_spec.replace_relay_chain(relay.as_ref())?;
+		}
+		if let Some(chain_type) = self.chain_type.or((!templated).then_some(ChainType::Development))
+		{
+			chain_spec.replace_chain_type(chain_type.as_ref())?;
+		}
 		if self.protocol_id.is_some() {
 			let protocol_id = self.protocol_id.unwrap_or(DEFAULT_PROTOCOL_ID.to_string());
 			chain_spec.replace_protocol_id(&protocol_id)?;
 		}
+		for bootnode in &self.bootnodes {
+			chain_spec.add_bootnode(bootnode)?;
+		}
+		for endpoint in &self.telemetry_endpoints {
+			chain_spec.add_telemetry_endpoint(endpoint, 0)?;
+		}
+		if let Some(keys) = self.collator_keys.as_deref() {
+			chain_spec.replace_collators(&load_collator_keys(keys)?)?;
+		}
+		if let Some(endowments) = self.endowments.as_deref() {
+			let endowments = load_endowments(endowments, chain_spec.get_ss58_format())?;
+			chain_spec.replace_endowments(&endowments)?;
+		}
+		if let Some(chain_id) = self.evm_chain_id {
+			chain_spec.replace_evm_chain_id(chain_id)?;
+		}
+		if !self.evm_balances.is_empty() {
+			chain_spec.replace_evm_balances(&self.evm_balances)?;
+		}
+		if !self.precompiles.is_empty() {
+			chain_spec.replace_evm_precompiles(&self.precompiles)?;
+		}
+		if let Some(patch) = self.genesis_patch.as_deref() {
+			chain_spec.apply_patch_file(patch)?;
+		}
 		chain_spec.to_file(&plain_chain_spec)?;
 
 		// Generate raw spec.
@@ -264,32 +525,101 @@ impl BuildSpecCommand {
 			.unwrap_or(DEFAULT_SPEC_NAME)
 			.trim_end_matches(".json");
 		let raw_spec_name = format!("{spec_name}-raw.json");
-		let raw_chain_spec =
-			generate_raw_chain_spec(&binary_path, &plain_chain_spec, &raw_spec_name)?;
+		let raw_chain_spec = match &generator {
+			SpecGenerator::Node(binary_path) =>
+				generate_raw_chain_spec(binary_path, &plain_chain_spec, &raw_spec_name)?,
+			SpecGenerator::Runtime { builder, .. } =>
+				generate_raw_chain_spec_with_runtime(builder, &plain_chain_spec, &raw_spec_name)?,
+		};
 		generated_files.push(format!(
 			"Raw chain specification file generated at: {}",
 			raw_chain_spec.display()
 		));
+		artifacts.push(("raw_chain_spec", raw_chain_spec.clone()));
+
+		// Check the generated specs for structural problems.
+		let relay = self.relay.as_ref().map(|relay| relay.as_ref());
+		let live = chain_spec.get_chain_type() == Some(ChainType::Live.as_ref());
+		let mut diagnostics = validate_chain_spec(&chain_spec, relay, live);
+		for diagnostic in validate_chain_spec(&ChainSpec::from(&raw_chain_spec)?, relay, live) {
+			if !diagnostics.contains(&diagnostic) {
+				diagnostics.push(diagnostic);
+			}
+		}
 
 		// Generate genesis artifacts.
 		if self.genesis_code {
 			spinner.set_message("Generating genesis code...");
 			let wasm_file_name = format!("para-{}.wasm", para_id);
-			let wasm_file = export_wasm_file(&binary_path, &raw_chain_spec, &wasm_file_name)?;
+			let wasm_file = match &generator {
+				SpecGenerator::Node(binary_path) =>
+					export_wasm_file(binary_path, &raw_chain_spec, &wasm_file_name)?,
+				SpecGenerator::Runtime { .. } =>
+					extract_genesis_code(&raw_chain_spec, &wasm_file_name)?,
+			};
 			generated_files
 				.push(format!("WebAssembly runtime file exported at: {}", wasm_file.display()));
+			artifacts.push(("genesis_code", wasm_file));
 		}
 
 		if self.genesis_state {
-			spinner.set_message("Generating genesis state...");
-			let genesis_file_name = format!("para-{}-genesis-state", para_id);
-			let genesis_state_file =
-				generate_genesis_state_file(&binary_path, &raw_chain_spec, &genesis_file_name)?;
-			generated_files
-				.push(format!("Genesis State file exported at: {}", genesis_state_file.display()));
+			match (&generator, self.head_from.as_ref()) {
+				(_, Some(url)) => {
+					spinner.set_message(format!("Exporting the head of {url}..."));
+					let genesis_file = raw_chain_spec
+						.parent()
+						.unwrap_or(Path::new("./"))
+						.join(format!("para-{}-genesis-state", para_id));
+					let genesis_state_file =
+						export_head_data(url.as_str(), self.head_at, &genesis_file).await?;
+					generated_files.push(format!(
+						"Genesis State file exported from the head of {url} at: {}",
+						genesis_state_file.display()
+					));
+					artifacts.push(("genesis_state", genesis_state_file));
+				},
+				(SpecGenerator::Node(binary_path), None) => {
+					spinner.set_message("Generating genesis state...");
+					let genesis_file_name = format!("para-{}-genesis-state", para_id);
+					let genesis_state_file = generate_genesis_state_file(
+						binary_path,
+						&raw_chain_spec,
+						&genesis_file_name,
+					)?;
+					generated_files.push(format!(
+						"Genesis State file exported at: {}",
+						genesis_state_file.display()
+					));
+					artifacts.push(("genesis_state", genesis_state_file));
+				},
+				(SpecGenerator::Runtime { .. }, None) => cli.warning(
+					"NOTE: the genesis state can only be generated with a node, so was skipped.",
+				)?,
+			}
+		}
+
+		let context = artifacts.iter().fold(
+			HookContext::default().value("para_id", para_id.to_string()),
+			|context, (name, path)| context.artifact(name, path),
+		);
+		hooks.run(Stage::PostSpec, &context)?;
+
+		// Publish generated files.
+		if let Some(destination) = self.publish.as_deref() {
+			spinner.set_message("Publishing generated files...");
+			let mut sink = parse_sink(destination)?;
+			for (_, artifact) in &artifacts {
+				let name = artifact.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+				let location = sink.write_file(name, artifact)?;
+				generated_files.push(format!("Published to: {location}"));
+			}
+			sink.finish()?;
 		}
 
 		cli.intro("Building your chain spec".to_string())?;
+		for diagnostic in diagnostics {
+			cli.warning(format!("NOTE: the chain spec may be invalid: {diagnostic}"))?;
+		}
 		let generated_files: Vec<_> = generated_files
 			.iter()
 			.map(|s| style(format!("{} {s}", console::Emoji("●", ">"))).dim().to_string())
@@ -304,6 +634,32 @@ impl BuildSpecCommand {
 	}
 }
 
+/// How the specs of a project are generated.
+enum SpecGenerator {
+	/// With the node binary.
+	Node(PathBuf),
+	/// From the runtime, using `chain-spec-builder`.
+	Runtime {
+		/// The path to the `chain-spec-builder` binary.
+		builder: PathBuf,
+		/// The path to the runtime.
+		runtime: PathBuf,
+	},
+}
+
+/// Sources `chain-spec-builder`, unless already installed or cached, returning its path.
+///
+/// # Arguments
+/// * `cli` - The CLI implementation to be used.
+async fn source_chain_spec_builder(cli: &mut impl cli::traits::Cli) -> anyhow::Result<PathBuf> {
+	let binary = chain_spec_builder(&crate::cache()?, None)?;
+	if !binary.exists() {
+		cli.info(format!("📦 Sourcing {}...", binary.name()))?;
+		binary.source(false, &(), true).await?;
+	}
+	Ok(binary.path())
+}
+
 /// Guide the user to generate their chain specification.
 async fn guide_user_to_generate_spec(args: BuildSpecCommand) -> anyhow::Result<BuildSpecCommand> {
 	Cli.intro("Generate your chain spec")?;
@@ -445,10 +801,31 @@ async fn guide_user_to_generate_spec(args: BuildSpecCommand) -> anyhow::Result<B
 		release: profile,
 		id: Some(para_id),
 		default_bootnode,
+		bootnodes: args.bootnodes,
+		telemetry_endpoints: args.telemetry_endpoints,
 		chain_type: Some(chain_type),
 		relay: Some(relay_chain),
 		protocol_id: Some(protocol_id),
 		genesis_state,
+		head_from: args.head_from,
+		head_at: args.head_at,
 		genesis_code,
+		publish: args.publish,
+		preset: args.preset,
+		list_presets: args.list_presets,
+		from_runtime: args.from_runtime,
+		genesis_patch: args.genesis_patch,
+		collator_keys: args.collator_keys,
+		endowments: args.endowments,
+		evm_chain_id: args.evm_chain_id,
+		evm_balances: args.evm_balances,
+		precompiles: args.precompiles,
+		template: args.template,
+		env: args.env,
+		sudo: args.sudo,
+		collators: args.collators,
+		endowed: args.endowed,
+		endowment: args.endowment,
+		variables: args.variables,
 	})
 }
@@ -6,7 +6,8 @@ use clap::Args;
 use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
 use console::style;
 use pop_contracts::{
-	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, set_up_call, CallOpts,
+	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, export_repro, set_up_call,
+	CallOpts, Repro, ReproCall,
 };
 use sp_weights::Weight;
 use std::path::PathBuf;
@@ -15,44 +16,50 @@ use std::path::PathBuf;
 pub struct CallContractCommand {
 	/// Path to the contract build directory.
 	#[arg(short = 'p', long)]
-	path: Option<PathBuf>,
+	pub(crate) path: Option<PathBuf>,
 	/// The address of the contract to call.
 	#[clap(name = "contract", long, env = "CONTRACT")]
-	contract: String,
+	pub(crate) contract: String,
 	/// The name of the contract message to call.
 	#[clap(long, short)]
-	message: String,
-	/// The constructor arguments, encoded as strings.
+	pub(crate) message: String,
+	/// The message arguments, in a JSON5 or Rust struct-literal style (e.g. `true`,
+	/// `{ to: "5GrwvaEF...", amount: 10 }` or `Some([1, 2])`).
 	#[clap(long, num_args = 0..)]
-	args: Vec<String>,
+	pub(crate) args: Vec<String>,
 	/// Transfers an initial balance to the instantiated contract.
 	#[clap(name = "value", long, default_value = "0")]
-	value: String,
+	pub(crate) value: String,
 	/// Maximum amount of gas to be used for this command.
 	/// If not specified it will perform a dry-run to estimate the gas consumed for the
 	/// instantiation.
 	#[clap(name = "gas", long)]
-	gas_limit: Option<u64>,
+	pub(crate) gas_limit: Option<u64>,
 	/// Maximum proof size for this command.
 	/// If not specified it will perform a dry-run to estimate the proof size required.
 	#[clap(long)]
-	proof_size: Option<u64>,
+	pub(crate) proof_size: Option<u64>,
 	/// Websocket endpoint of a node.
 	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
-	url: url::Url,
+	pub(crate) url: url::Url,
 	/// Secret key URI for the account deploying the contract.
 	///
 	/// e.g.
 	/// - for a dev account "//Alice"
 	/// - with a password "//Alice///SECRET_PASSWORD"
 	#[clap(name = "suri", long, short, default_value = "//Alice")]
-	suri: String,
+	pub(crate) suri: String,
 	/// Submit an extrinsic for on-chain execution.
 	#[clap(short('x'), long)]
-	execute: bool,
+	pub(crate) execute: bool,
 	/// Perform a dry-run via RPC to estimate the gas usage. This does not submit a transaction.
 	#[clap(long, conflicts_with = "execute")]
-	dry_run: bool,
+	pub(crate) dry_run: bool,
+	/// Export a reproduction bundle to the specified path should the dry-run fail, containing
+	/// the source of the contract, its toolchain and the failing call, which can be replayed
+	/// with `pop call repro`.
+	#[clap(long)]
+	pub(crate) repro: Option<PathBuf>,
 }
 
 impl CallContractCommand {
@@ -95,7 +102,11 @@ impl CallContractCommand {
 		if !self.execute {
 			let spinner = cliclack::spinner();
 			spinner.start("Calling the contract...");
-			let call_dry_run_result = dry_run_call(&call_exec).await?;
+			let result = dry_run_call(&call_exec).await;
+			if let Err(e) = &result {
+				self.save_repro(&e.to_string())?;
+			}
+			let call_dry_run_result = result?;
 			log::info(format!("Result: {}", call_dry_run_result))?;
 			log::warning("Your call has not been executed.")?;
 			log::warning(format!(
@@ -115,6 +126,7 @@ impl CallContractCommand {
 					},
 					Err(e) => {
 						spinner.error(format!("{e}"));
+						self.save_repro(&e.to_string())?;
 						outro_cancel("Call failed.")?;
 						return Ok(());
 					},
@@ -133,4 +145,26 @@ impl CallContractCommand {
 		outro("Call completed successfully!")?;
 		Ok(())
 	}
+
+	/// Exports a reproduction bundle of the failing call, if requested.
+	///
+	/// # Arguments
+	/// * `trace` - The output of the failing dry-run.
+	fn save_repro(&self, trace: &str) -> anyhow::Result<()> {
+		let Some(archive) = &self.repro else { return Ok(()) };
+		let project = self.path.clone().unwrap_or_else(|| PathBuf::from("./"));
+		let call = ReproCall {
+			contract: self.contract.clone(),
+			message: self.message.clone(),
+			args: self.args.clone(),
+			value: self.value.clone(),
+			gas_limit: self.gas_limit,
+			proof_size: self.proof_size,
+			url: self.url.to_string(),
+		};
+		let repro = Repro::new(&project, env!("CARGO_PKG_VERSION"), call, trace)?;
+		export_repro(archive, &project, &repro)?;
+		log::info(format!("Reproduction bundle exported to {}", archive.display()))?;
+		Ok(())
+	}
 }
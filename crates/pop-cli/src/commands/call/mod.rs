@@ -4,8 +4,15 @@ use clap::{Args, Subcommand};
 
 #[cfg(feature = "contract")]
 pub(crate) mod contract;
+#[cfg(feature = "parachain")]
+pub(crate) mod referendum;
+#[cfg(feature = "contract")]
+pub(crate) mod repro;
+#[cfg(feature = "parachain")]
+pub(crate) mod runtime_api;
+pub(crate) mod saved;
 
-/// Arguments for calling a smart contract.
+/// Arguments for calling a smart contract, or a call saved within the project.
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
 pub(crate) struct CallArgs {
@@ -13,11 +20,30 @@ pub(crate) struct CallArgs {
 	pub command: Command,
 }
 
-/// Call a smart contract.
+/// Call a smart contract, or a call saved within the project.
 #[derive(Subcommand)]
 pub(crate) enum Command {
 	/// Call a contract
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
 	Contract(contract::CallContractCommand),
+	/// Rehearse a governance proposal on a fork, enacting it as a referendum on the specified
+	/// track and reporting the resulting events and state changes.
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "g")]
+	Referendum(referendum::ReferendumCommand),
+	/// Replay the failing call of a reproduction bundle, exported with `pop call contract
+	/// --repro`.
+	#[cfg(feature = "contract")]
+	#[clap(alias = "r")]
+	Repro(repro::ReproCommand),
+	/// Call a method of a runtime API of a chain, encoding its arguments and decoding its result
+	/// using the metadata of the chain, or list its runtime APIs.
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "a")]
+	RuntimeApi(runtime_api::RuntimeApiCommand),
+	/// Invoke a call saved within the `[calls]` table of `pop.toml`, overriding any of its
+	/// arguments, or list the saved calls.
+	#[clap(alias = "s")]
+	Saved(saved::SavedCallCommand),
 }
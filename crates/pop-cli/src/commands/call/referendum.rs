@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{set_up_client, simulate_referendum, Impersonator};
+
+#[derive(Args)]
+pub(crate) struct ReferendumCommand {
+	/// The SCALE encoded call proposed, as a hex string.
+	pub(crate) call: String,
+	/// The track on which the referendum is submitted (e.g. `root` or `whitelisted_caller`).
+	#[arg(short, long, default_value = "root")]
+	pub(crate) track: String,
+	/// The account submitting the referendum, which must be able to pay its deposits: either an
+	/// address, `sudo` or `treasury`.
+	#[arg(long, default_value = "treasury")]
+	pub(crate) proposer: String,
+	/// A storage key, as a hex string, whose change is reported (e.g. as computed by `pop
+	/// inspect storage-key`).
+	#[arg(long = "watch")]
+	pub(crate) watch: Vec<String>,
+	/// Websocket endpoint of a node of the fork (e.g. launched with chopsticks, with
+	/// `mock-signature-host: true`).
+	#[arg(long, default_value = "ws://localhost:8000")]
+	pub(crate) url: url::Url,
+}
+
+impl ReferendumCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Enacts the proposal on the fork, reporting the outcome.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro(format!("Simulating a referendum on the `{}` track", self.track))?;
+		let url = self.url.as_str();
+		let proposer = match self.proposer.as_str() {
+			"sudo" => match set_up_client(url).await {
+				Ok(client) => Impersonator::sudo(&client).await,
+				Err(e) => Err(e),
+			},
+			"treasury" => Ok(Impersonator::treasury()),
+			account => Impersonator::new(account),
+		};
+		let simulation = match proposer {
+			Ok(proposer) =>
+				simulate_referendum(url, &self.call, &self.track, &proposer, &self.watch).await,
+			Err(e) => Err(e),
+		};
+		let simulation = match simulation {
+			Ok(simulation) => simulation,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		if let Some(referendum) = simulation.referendum {
+			cli.info(format!("Referendum #{referendum} submitted by {}", self.proposer))?;
+		}
+		for event in &simulation.events {
+			cli.info(format!("{}::{} {}", event.pallet, event.variant, event.fields))?;
+		}
+		let none = "None".to_string();
+		for change in &simulation.changes {
+			match change.changed() {
+				true => cli.info(format!(
+					"{}: {} -> {}",
+					change.key,
+					change.before.as_ref().unwrap_or(&none),
+					change.after.as_ref().unwrap_or(&none)
+				))?,
+				false => cli.info(format!("{}: unchanged", change.key))?,
+			}
+		}
+		match simulation.dispatched {
+			Ok(()) => cli.outro(format!(
+				"✅ The proposal was enacted within block #{} ({:?})",
+				simulation.block, simulation.hash
+			))?,
+			Err(e) => cli.outro_cancel(format!("🚫 The proposal failed to dispatch: {e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn run_fails_with_invalid_call() -> anyhow::Result<()> {
+		let command = ReferendumCommand {
+			call: "0x123".into(),
+			track: "root".into(),
+			proposer: "treasury".into(),
+			watch: vec![],
+			url: "ws://localhost:8000".parse()?,
+		};
+		let mut cli = MockCli::new()
+			.expect_intro("Simulating a referendum on the `root` track")
+			.expect_outro_cancel("🚫 Configuration error: `0x123` is not a valid hex string");
+		command.run(&mut cli).await?;
+		cli.verify()
+	}
+}
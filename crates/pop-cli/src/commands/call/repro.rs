@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_contracts::{dry_run_call, import_repro, set_up_call, CallOpts};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct ReproCommand {
+	/// The path of the reproduction bundle.
+	pub(crate) bundle: PathBuf,
+	/// The directory in which the contract project is unpacked [default: `./repro`].
+	#[arg(short = 'p', long)]
+	pub(crate) path: Option<PathBuf>,
+	/// Websocket endpoint of a node, overriding that of the bundle.
+	#[arg(long)]
+	pub(crate) url: Option<url::Url>,
+	/// Secret key URI of the account making the call.
+	#[arg(short, long, default_value = "//Alice")]
+	pub(crate) suri: String,
+}
+
+impl ReproCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Imports a reproduction bundle and replays the dry-run of its failing call.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro(format!("Replaying {}", self.bundle.display()))?;
+		let path = self.path.clone().unwrap_or_else(|| PathBuf::from("./repro"));
+		let repro = match import_repro(&self.bundle, &path) {
+			Ok(repro) => repro,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		let toolchain = &repro.toolchain;
+		cli.info(format!(
+			"Contract unpacked to {}, built with {} and {} (pop {})",
+			path.display(),
+			toolchain.language,
+			toolchain.compiler,
+			toolchain.pop
+		))?;
+		let call = repro.call;
+		let url = match &self.url {
+			Some(url) => url.clone(),
+			None => call.url.parse()?,
+		};
+		cli.info(format!("Calling `{}` of {} on {url}", call.message, call.contract))?;
+		let call_exec = set_up_call(CallOpts {
+			path: Some(path),
+			contract: call.contract,
+			message: call.message,
+			args: call.args,
+			value: call.value,
+			gas_limit: call.gas_limit,
+			proof_size: call.proof_size,
+			url,
+			suri: self.suri.clone(),
+			execute: false,
+		})
+		.await?;
+		match dry_run_call(&call_exec).await {
+			Ok(result) => cli.outro(format!(
+				"✅ The issue did not reproduce: the call returned {result}, rather than: {}",
+				repro.trace
+			))?,
+			Err(e) => cli.outro_cancel(format!("🚫 Reproduced: {e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn replay_fails_without_bundle() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let bundle = temp_dir.path().join("repro.tar.gz");
+		let command = ReproCommand {
+			bundle: bundle.clone(),
+			path: Some(temp_dir.path().join("repro")),
+			url: None,
+			suri: "//Alice".into(),
+		};
+		let mut cli = MockCli::new()
+			.expect_intro(format!("Replaying {}", bundle.display()))
+			.expect_outro_cancel(format!(
+				"🚫 IO error: {}",
+				std::fs::File::open(&bundle).unwrap_err()
+			));
+		command.run(&mut cli).await?;
+		cli.verify()
+	}
+}
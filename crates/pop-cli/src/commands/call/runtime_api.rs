@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{call_runtime_api, runtime_api_methods, set_up_client};
+
+/// The endpoint of the chain called, unless specified.
+const DEFAULT_URL: &str = "ws://localhost:9944";
+
+#[derive(Args)]
+pub(crate) struct RuntimeApiCommand {
+	/// The method of the runtime API, specified as `<api>::<method>` (e.g.
+	/// `AccountNonceApi::account_nonce`).
+	#[arg(value_parser = parse_method, required_unless_present = "list")]
+	pub(crate) method: Option<(String, String)>,
+	/// The arguments of the method, in a JSON5 or Rust struct-literal style (e.g. `Some(1)` or
+	/// `{ id: 1 }`).
+	pub(crate) args: Vec<String>,
+	/// List the methods of the runtime APIs of the chain, rather than calling one.
+	#[arg(long, conflicts_with = "method")]
+	pub(crate) list: bool,
+	/// Websocket endpoint of a node.
+	#[arg(long, default_value = DEFAULT_URL)]
+	pub(crate) url: url::Url,
+}
+
+fn parse_method(method: &str) -> Result<(String, String), String> {
+	match method.split_once("::") {
+		Some((api, method)) if !api.is_empty() && !method.is_empty() =>
+			Ok((api.to_string(), method.to_string())),
+		_ => Err(format!("expected `<api>::<method>`, found `{method}`")),
+	}
+}
+
+impl RuntimeApiCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Calls a method of a runtime API of the chain, or lists the methods of its runtime APIs.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		let Some((api, method)) = &self.method else {
+			cli.intro("Listing the runtime APIs of the chain")?;
+			let metadata = match set_up_client(self.url.as_str()).await {
+				Ok(client) => client.metadata(),
+				Err(e) => {
+					cli.outro_cancel(format!("🚫 {e}"))?;
+					return Ok(());
+				},
+			};
+			let methods: Vec<_> =
+				runtime_api_methods(&metadata).iter().map(|m| m.to_string()).collect();
+			cli.info(methods.join("\n"))?;
+			cli.outro(format!("✅ {} methods found", methods.len()))?;
+			return Ok(());
+		};
+		cli.intro(format!("Calling {api}::{method}"))?;
+		match call_runtime_api(self.url.as_str(), api, method, &self.args).await {
+			Ok(result) => {
+				cli.info(format!("Encoded: {}", result.encoded))?;
+				cli.outro(format!("✅ {}", result.value))?;
+			},
+			Err(e) => cli.outro_cancel(format!("🚫 {e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_method_works() {
+		assert_eq!(
+			parse_method("AccountNonceApi::account_nonce"),
+			Ok(("AccountNonceApi".into(), "account_nonce".into()))
+		);
+		for method in ["AccountNonceApi", "AccountNonceApi::", "::account_nonce"] {
+			assert!(parse_method(method).is_err());
+		}
+	}
+}
@@ -0,0 +1,288 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli;
+use clap::Args;
+use pop_common::{
+	calls::{parse_override, CallTarget, SavedCall},
+	hooks::CONFIG_FILE,
+};
+use std::path::{Path, PathBuf};
+
+/// The endpoint of the chain called, unless specified.
+const DEFAULT_URL: &str = "ws://localhost:9944";
+/// The secret key URI of the signer, unless specified.
+const DEFAULT_SURI: &str = "//Alice";
+
+#[derive(Args)]
+pub(crate) struct SavedCallCommand {
+	/// The name of the call, as saved within the `[calls]` table of `pop.toml` [default: lists
+	/// the saved calls].
+	pub(crate) name: Option<String>,
+	/// Overrides an argument of the call, specified as `<name>=<value>` (e.g. `--arg
+	/// value=1000`).
+	#[arg(long = "arg", value_parser = parse_arg_override)]
+	pub(crate) args: Vec<(String, String)>,
+	/// Websocket endpoint of a node, overriding that of the saved call.
+	#[arg(long)]
+	pub(crate) url: Option<url::Url>,
+	/// Secret key URI of the signer, overriding that of the saved call.
+	#[arg(short, long)]
+	pub(crate) suri: Option<String>,
+	/// Submit a call to a pallet on behalf of an account of a fork (e.g. launched with
+	/// chopsticks, with `mock-signature-host: true`), without its key: either an address, `sudo`
+	/// or `treasury`.
+	#[arg(long, conflicts_with = "suri")]
+	pub(crate) impersonate: Option<String>,
+	/// How the fees of a call to a pallet are paid, to test sponsored transactions: `native`,
+	/// `asset:<id>` (paid in an asset of the `Assets` pallet, swapped via a conversion pool on
+	/// asset hubs), `location:<location>` (paid in the asset at an XCM location, e.g.
+	/// `1/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)`) or `proxy:<account>` (the signer
+	/// pays as a proxy of the account) [default: native].
+	#[arg(long, conflicts_with = "impersonate")]
+	pub(crate) fee_payment: Option<String>,
+	/// Directory path of the project [default: current directory].
+	#[arg(short = 'p', long)]
+	pub(crate) path: Option<PathBuf>,
+	/// Submit a call to a contract for on-chain execution, rather than performing a dry run.
+	/// Calls to pallets are always submitted.
+	#[arg(short('x'), long)]
+	pub(crate) execute: bool,
+}
+
+fn parse_arg_override(arg: &str) -> Result<(String, String), String> {
+	parse_override(arg).map_err(|e| e.to_string())
+}
+
+impl SavedCallCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut cli::Cli).await
+	}
+
+	/// Invokes a saved call, or lists the saved calls if no call is specified.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		let project = self.path.clone().unwrap_or_else(|| PathBuf::from("./"));
+		let Some(name) = self.name.clone() else {
+			return list(&project, cli);
+		};
+		let call = match SavedCall::load(&project, &name) {
+			Ok(call) => call,
+			Err(e) => {
+				cli.intro(format!("Calling `{name}`"))?;
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		match call.target.clone() {
+			CallTarget::Contract { contract, message } => {
+				#[cfg(feature = "contract")]
+				return self.call_contract(&project, &call, contract, message, cli).await;
+				#[cfg(not(feature = "contract"))]
+				{
+					let _ = (contract, message);
+					cli.intro(format!("Calling `{name}`"))?;
+					cli.outro_cancel("🚫 calling contracts requires the `contract` feature")?;
+					Ok(())
+				}
+			},
+			CallTarget::Extrinsic { pallet, function } => {
+				cli.intro(format!("Calling `{name}`: {}", call.target))?;
+				#[cfg(feature = "parachain")]
+				match self.submit(&call, &pallet, &function).await {
+					Ok(hash) => {
+						cli.success(format!("Extrinsic {hash} finalized"))?;
+						cli.outro("Call completed successfully!")?;
+					},
+					Err(e) => cli.outro_cancel(format!("🚫 {e}"))?,
+				}
+				#[cfg(not(feature = "parachain"))]
+				{
+					let _ = (pallet, function);
+					cli.outro_cancel("🚫 calling pallets requires the `parachain` feature")?;
+				}
+				Ok(())
+			},
+		}
+	}
+
+	/// Submits a saved call to a pallet, returning the hash of the extrinsic.
+	#[cfg(feature = "parachain")]
+	async fn submit(
+		&self,
+		call: &SavedCall,
+		pallet: &str,
+		function: &str,
+	) -> anyhow::Result<String> {
+		use pop_parachains::{
+			call_arg_names, construct_extrinsic, set_up_client, submit_extrinsic,
+			submit_impersonated, FeePayment, Impersonator,
+		};
+
+		let fee_payment: FeePayment = self.fee_payment.as_deref().unwrap_or("native").parse()?;
+		let url = self
+			.url
+			.as_ref()
+			.map(|url| url.to_string())
+			.or_else(|| call.url.clone())
+			.unwrap_or_else(|| DEFAULT_URL.to_string());
+		let suri = self.suri.as_deref().or(call.suri.as_deref()).unwrap_or(DEFAULT_SURI);
+		let client = set_up_client(&url).await?;
+		let metadata = client.metadata();
+		let names = call_arg_names(&metadata, pallet, function)?;
+		let names: Vec<_> = names.iter().map(String::as_str).collect();
+		let args = call.resolve_args(&names, &self.args)?;
+		let extrinsic = construct_extrinsic(&metadata, pallet, function, &args)?;
+		let hash = match self.impersonate.as_deref() {
+			Some("sudo") =>
+				submit_impersonated(&url, &extrinsic, &Impersonator::sudo(&client).await?).await?,
+			Some("treasury") =>
+				submit_impersonated(&url, &extrinsic, &Impersonator::treasury()).await?,
+			Some(account) =>
+				submit_impersonated(&url, &extrinsic, &Impersonator::new(account)?).await?,
+			None => submit_extrinsic(&url, &extrinsic, suri, None, &fee_payment).await?,
+		};
+		Ok(format!("{hash:?}"))
+	}
+
+	/// Calls a saved call to a contract, built within the project.
+	#[cfg(feature = "contract")]
+	async fn call_contract(
+		self,
+		project: &Path,
+		call: &SavedCall,
+		contract: String,
+		message: String,
+		cli: &mut impl cli::traits::Cli,
+	) -> anyhow::Result<()> {
+		use pop_contracts::MetadataRegistry;
+
+		let metadata = MetadataRegistry::global().load(Some(project))?;
+		let args = match metadata.arg_names(&message) {
+			Some(names) => call.resolve_args(&names, &self.args),
+			None => Err(pop_common::Error::Config(format!(
+				"the `{message}` message was not found in the metadata of {}",
+				metadata.name()
+			))),
+		};
+		let args = match args {
+			Ok(args) => args,
+			Err(e) => {
+				cli.intro(format!("Calling `{}`: {}", self.name.unwrap_or_default(), call.target))?;
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		let url = match self.url {
+			Some(url) => url,
+			None => call.url.as_deref().unwrap_or(DEFAULT_URL).parse()?,
+		};
+		super::contract::CallContractCommand {
+			path: Some(project.to_path_buf()),
+			contract,
+			message,
+			args,
+			value: "0".to_string(),
+			gas_limit: None,
+			proof_size: None,
+			url,
+			suri: self.suri.or(call.suri.clone()).unwrap_or_else(|| DEFAULT_SURI.to_string()),
+			execute: self.execute,
+			dry_run: false,
+			repro: None,
+		}
+		.execute()
+		.await
+	}
+}
+
+/// Lists the calls saved within a project.
+///
+/// # Arguments
+/// * `project` - The project directory.
+/// * `cli` - The CLI implementation to be used.
+fn list(project: &Path, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+	cli.intro("Saved calls")?;
+	let calls = match SavedCall::load_all(project) {
+		Ok(calls) => calls,
+		Err(e) => {
+			cli.outro_cancel(format!("🚫 {e}"))?;
+			return Ok(());
+		},
+	};
+	if calls.is_empty() {
+		cli.outro_cancel(format!(
+			"🚫 No calls are saved; save calls within the `[calls]` table of {CONFIG_FILE}"
+		))?;
+		return Ok(());
+	}
+	for (name, call) in &calls {
+		let description =
+			call.description.as_deref().map(|d| format!(" - {d}")).unwrap_or_default();
+		cli.info(format!("{name}: {}{description}", call.target))?;
+	}
+	cli.outro("Invoke a saved call with `pop call saved <NAME> [--arg <NAME>=<VALUE>]`")?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cli::MockCli;
+	use std::fs::write;
+
+	fn command(project: &Path, name: Option<&str>) -> SavedCallCommand {
+		SavedCallCommand {
+			name: name.map(String::from),
+			args: vec![],
+			url: None,
+			suri: None,
+			impersonate: None,
+			fee_payment: None,
+			path: Some(project.to_path_buf()),
+			execute: false,
+		}
+	}
+
+	#[tokio::test]
+	async fn list_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut cli = MockCli::new().expect_intro("Saved calls").expect_outro_cancel(
+			"🚫 No calls are saved; save calls within the `[calls]` table of pop.toml",
+		);
+		command(temp_dir.path(), None).run(&mut cli).await?;
+		cli.verify()?;
+
+		write(
+			temp_dir.path().join(CONFIG_FILE),
+			"[calls.fund-qa]\ndescription = \"Fund the QA account\"\npallet = \"Balances\"\nfunction = \"transfer_keep_alive\"\n\n[calls.flip]\ncontract = \"5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A\"\nmessage = \"flip\"\n",
+		)?;
+		let mut cli = MockCli::new()
+			.expect_intro("Saved calls")
+			.expect_info("flip: flip of 5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A")
+			.expect_info("fund-qa: Balances::transfer_keep_alive - Fund the QA account")
+			.expect_outro(
+				"Invoke a saved call with `pop call saved <NAME> [--arg <NAME>=<VALUE>]`",
+			);
+		command(temp_dir.path(), None).run(&mut cli).await?;
+		cli.verify()
+	}
+
+	#[tokio::test]
+	async fn run_fails_without_saved_call() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut cli = MockCli::new().expect_intro("Calling `fund-qa`").expect_outro_cancel(
+			"🚫 Configuration error: no `fund-qa` call is saved, as no calls are saved in pop.toml",
+		);
+		command(temp_dir.path(), Some("fund-qa")).run(&mut cli).await?;
+		cli.verify()
+	}
+
+	#[test]
+	fn parse_arg_override_works() {
+		assert_eq!(parse_arg_override("value=1"), Ok(("value".to_string(), "1".to_string())));
+		assert!(parse_arg_override("value").is_err());
+	}
+}
@@ -3,10 +3,14 @@
 use crate::{cli::traits::*, style::style};
 use anyhow::Result;
 use clap::{Args, Subcommand};
+use cliclack::log;
+use pop_common::gc::{Collector, Policy};
 use std::{
 	fs::{read_dir, remove_file},
-	path::PathBuf,
+	path::{Path, PathBuf},
 };
+#[cfg(feature = "parachain")]
+use {pop_parachains::Snapshots, std::time::Duration};
 
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
@@ -21,6 +25,10 @@ pub(crate) enum Command {
 	/// Remove cached artifacts.
 	#[clap(alias = "c")]
 	Cache(CleanCommandArgs),
+	/// Remove cached snapshots of live chain state.
+	#[clap(alias = "s")]
+	#[cfg(feature = "parachain")]
+	Snapshots(CleanSnapshotsArgs),
 }
 
 #[derive(Args)]
@@ -28,6 +36,18 @@ pub struct CleanCommandArgs {
 	/// Pass flag to remove all artifacts
 	#[arg(short = 'a', long)]
 	pub(crate) all: bool,
+	/// Remove the versioned binaries not retained by the retention policy configured within the
+	/// `.gc.toml` file of the cache, which is otherwise applied after binaries are sourced.
+	#[arg(long, conflicts_with = "all")]
+	pub(crate) gc: bool,
+}
+
+#[derive(Args)]
+#[cfg(feature = "parachain")]
+pub struct CleanSnapshotsArgs {
+	/// Only remove the snapshots older than the specified number of days.
+	#[arg(long)]
+	pub(crate) older_than: Option<u64>,
 }
 
 /// Removes cached artifacts.
@@ -132,6 +152,109 @@ impl<'a, CLI: Cli> CleanCacheCommand<'a, CLI> {
 	}
 }
 
+/// Removes the cached artifacts not retained by the retention policy of the cache.
+pub(crate) struct ReclaimCacheCommand<'a, CLI: Cli> {
+	/// The cli to be used.
+	pub(crate) cli: &'a mut CLI,
+	/// The cache to be used.
+	pub(crate) cache: PathBuf,
+}
+
+impl<'a, CLI: Cli> ReclaimCacheCommand<'a, CLI> {
+	/// Executes the command.
+	pub(crate) fn execute(self) -> Result<()> {
+		self.cli.intro("Reclaim cached artifacts")?;
+		let report = Collector::new(&self.cache, Policy::load(&self.cache)?).collect()?;
+		for reclaimed in &report.reclaimed {
+			let name = reclaimed.path.file_name().unwrap_or_default().to_string_lossy();
+			self.cli.info(format!(
+				"🗑️ {name} : {}MiB ({})",
+				reclaimed.size / 1_048_576,
+				reclaimed.reason
+			))?;
+		}
+		self.cli.outro(format!(
+			"ℹ️ {} artifacts removed, {}MiB reclaimed, {}MiB remaining",
+			report.reclaimed.len(),
+			report.reclaimed_size() / 1_048_576,
+			report.remaining / 1_048_576
+		))?;
+		Ok(())
+	}
+}
+
+/// Removes cached snapshots of live chain state.
+#[cfg(feature = "parachain")]
+pub(crate) struct CleanSnapshotsCommand<'a, CLI: Cli> {
+	/// The cli to be used.
+	pub(crate) cli: &'a mut CLI,
+	/// The cache to be used.
+	pub(crate) cache: PathBuf,
+	/// Only remove the snapshots older than the specified number of days.
+	pub(crate) older_than: Option<u64>,
+}
+
+#[cfg(feature = "parachain")]
+impl<'a, CLI: Cli> CleanSnapshotsCommand<'a, CLI> {
+	/// Executes the command.
+	pub(crate) fn execute(self) -> Result<()> {
+		self.cli.intro("Remove cached snapshots")?;
+		let snapshots = Snapshots::new(&self.cache, Duration::ZERO);
+		let older_than = self.older_than.map(|days| Duration::from_secs(days * 24 * 60 * 60));
+		let selected: Vec<_> = snapshots
+			.list()?
+			.into_iter()
+			.filter(|s| older_than.is_none_or(|age| s.age() >= age))
+			.collect();
+		if selected.is_empty() {
+			self.cli.outro("ℹ️ No snapshots to remove")?;
+			return Ok(());
+		}
+		for snapshot in &selected {
+			let name = snapshot.path.file_name().unwrap_or_default().to_string_lossy();
+			self.cli.info(format!(
+				"📸 {name} : {}MiB, taken {} day(s) ago",
+				snapshot.size / 1_048_576,
+				snapshot.age().as_secs() / (24 * 60 * 60)
+			))?;
+		}
+		let prompt = format!("Are you sure you want to remove {} snapshot(s)?", selected.len());
+		if !self.cli.confirm(prompt).interact()? {
+			self.cli.outro("ℹ️ No snapshots removed")?;
+			return Ok(());
+		}
+		let removed = snapshots.prune(older_than)?;
+		self.cli.outro(format!("ℹ️ {} snapshots removed", removed.len()))?;
+		Ok(())
+	}
+}
+
+/// Applies the retention policy of the cache after an operation has added to it, never failing
+/// the operation itself.
+///
+/// # Arguments
+/// * `cache` - The cache.
+/// * `in_use` - The binaries in use by the operation, which are retained.
+pub(crate) fn reclaim(cache: &Path, in_use: impl IntoIterator<Item = PathBuf>) -> Result<()> {
+	let report = match Policy::load(cache)
+		.and_then(|policy| Collector::new(cache, policy).protect(in_use).collect())
+	{
+		Ok(report) => report,
+		Err(e) => {
+			log::warning(format!("⚠️ Unable to apply the retention policy of the cache: {e}"))?;
+			return Ok(());
+		},
+	};
+	if !report.reclaimed.is_empty() {
+		log::info(format!(
+			"♻️ Reclaimed {}MiB from the cache by removing {} artifacts no longer retained",
+			report.reclaimed_size() / 1_048_576,
+			report.reclaimed.len()
+		))?;
+	}
+	Ok(())
+}
+
 /// Returns the contents of the specified path.
 fn contents(path: &PathBuf) -> Result<Vec<(String, PathBuf, u64)>> {
 	let mut contents: Vec<_> = read_dir(path)?
@@ -144,7 +267,7 @@ fn contents(path: &PathBuf) -> Result<Vec<(String, PathBuf, u64)>> {
 					.map(|f| (f.0 .0, f.0 .1, f.1.len()))
 			})
 		})
-		.filter(|(name, _, _)| !name.starts_with('.'))
+		.filter(|(name, path, _)| !name.starts_with('.') && path.is_file())
 		.collect();
 	contents.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
 	Ok(contents)
@@ -154,7 +277,10 @@ fn contents(path: &PathBuf) -> Result<Vec<(String, PathBuf, u64)>> {
 mod tests {
 	use super::*;
 	use crate::cli::MockCli;
-	use std::fs::File;
+	use std::{
+		fs::{set_permissions, File, Permissions},
+		os::unix::fs::PermissionsExt,
+	};
 
 	#[test]
 	fn clean_cache_has_intro() -> Result<()> {
@@ -384,4 +510,51 @@ mod tests {
 		);
 		Ok(())
 	}
+
+	#[test]
+	fn reclaim_cache_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path().to_path_buf();
+		for version in ["v1.11.0", "v1.12.0"] {
+			let path = cache.join(format!("polkadot-{version}"));
+			File::create(&path)?;
+			set_permissions(path, Permissions::from_mode(0o755))?;
+		}
+		std::fs::write(cache.join(".gc.toml"), "keep-last = 1")?;
+		let mut cli = MockCli::new()
+			.expect_intro("Reclaim cached artifacts")
+			.expect_outro("ℹ️ 1 artifacts removed, 0MiB reclaimed, 0MiB remaining");
+
+		ReclaimCacheCommand { cli: &mut cli, cache: cache.clone() }.execute()?;
+
+		assert_eq!(contents(&cache)?.len(), 1);
+		cli.verify()
+	}
+
+	#[test]
+	#[cfg(feature = "parachain")]
+	fn clean_snapshots_works() -> Result<()> {
+		use pop_parachains::{LiveState, SnapshotKind};
+		let temp = tempfile::tempdir()?;
+		let cache = temp.path().to_path_buf();
+		let snapshots = Snapshots::new(&cache, Duration::ZERO);
+		let state = LiveState { uri: "ws://localhost:9944".into(), at: None, pallets: vec![] };
+		std::fs::write(snapshots.register(SnapshotKind::RemoteExternalities, &state)?, "state")?;
+
+		let mut cli = MockCli::new()
+			.expect_intro("Remove cached snapshots")
+			.expect_outro("ℹ️ No snapshots to remove");
+		CleanSnapshotsCommand { cli: &mut cli, cache: cache.clone(), older_than: Some(1) }
+			.execute()?;
+		cli.verify()?;
+
+		let mut cli = MockCli::new()
+			.expect_intro("Remove cached snapshots")
+			.expect_info("📸 localhost_9944-latest.snap : 0MiB, taken 0 day(s) ago")
+			.expect_confirm("Are you sure you want to remove 1 snapshot(s)?", true)
+			.expect_outro("ℹ️ 1 snapshots removed");
+		CleanSnapshotsCommand { cli: &mut cli, cache, older_than: None }.execute()?;
+		assert!(snapshots.list()?.is_empty());
+		cli.verify()
+	}
 }
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::traits::*;
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use pop_common::environment::{
+	export_environment, import_environment, Environment, ExportOptions, Summary,
+};
+use std::{env, path::PathBuf};
+
+/// The environment variable from which the keystore passphrase is read, if set.
+const PASSPHRASE_VAR: &str = "POP_ENV_PASSPHRASE";
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct EnvArgs {
+	#[command(subcommand)]
+	pub(crate) command: Command,
+}
+
+/// Export or import a pop environment.
+#[derive(Subcommand)]
+pub(crate) enum Command {
+	/// Export the environment to an archive, so that an identical setup can be imported elsewhere.
+	#[clap(alias = "e")]
+	Export(EnvCommandArgs),
+	/// Import an environment from an archive previously exported.
+	#[clap(alias = "i")]
+	Import(EnvCommandArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct EnvCommandArgs {
+	/// The path of the archive.
+	pub(crate) archive: PathBuf,
+	/// The path of the project.
+	#[arg(short, long, default_value = "./")]
+	pub(crate) path: PathBuf,
+	/// Include the binaries within the cache when exporting.
+	#[arg(short, long)]
+	pub(crate) binaries: bool,
+	/// The keystore directory, which is encrypted with a passphrase when exported. The passphrase
+	/// is read from `POP_ENV_PASSPHRASE` if set, otherwise prompted for.
+	#[arg(short, long)]
+	pub(crate) keystore: Option<PathBuf>,
+}
+
+/// Exports or imports an environment.
+pub(crate) struct EnvCommand<'a, CLI: Cli> {
+	/// The cli to be used.
+	pub(crate) cli: &'a mut CLI,
+	/// The cache to be used.
+	pub(crate) cache: PathBuf,
+	/// The arguments of the command.
+	pub(crate) args: EnvCommandArgs,
+	/// The passphrase of the keystore, if any.
+	pub(crate) passphrase: Option<String>,
+}
+
+impl<'a, CLI: Cli> EnvCommand<'a, CLI> {
+	/// Exports the environment.
+	pub(crate) fn export(mut self) -> Result<()> {
+		self.cli.intro("Export environment")?;
+		let options =
+			ExportOptions { binaries: self.args.binaries, passphrase: self.passphrase.clone() };
+		match export_environment(&self.args.archive, &self.environment(), &options) {
+			Ok(summary) => {
+				self.describe(&summary)?;
+				self.cli
+					.outro(format!("✅ Environment exported to {}", self.args.archive.display()))?;
+			},
+			Err(e) => self.cli.outro_cancel(format!("🚫 Unable to export the environment: {e}"))?,
+		}
+		Ok(())
+	}
+
+	/// Imports the environment.
+	pub(crate) fn import(mut self) -> Result<()> {
+		self.cli.intro("Import environment")?;
+		match import_environment(
+			&self.args.archive,
+			&self.environment(),
+			self.passphrase.as_deref(),
+		) {
+			Ok(summary) => {
+				self.describe(&summary)?;
+				self.cli.outro(format!(
+					"✅ Environment imported from {}",
+					self.args.archive.display()
+				))?;
+			},
+			Err(e) => self.cli.outro_cancel(format!("🚫 Unable to import the environment: {e}"))?,
+		}
+		Ok(())
+	}
+
+	fn environment(&self) -> Environment {
+		Environment {
+			project: self.args.path.clone(),
+			cache: self.cache.clone(),
+			keystore: self.args.keystore.clone(),
+		}
+	}
+
+	fn describe(&mut self, summary: &Summary) -> Result<()> {
+		for file in &summary.project {
+			self.cli.info(format!("📄 {}", file.display()))?;
+		}
+		if !summary.binaries.is_empty() {
+			self.cli.info(format!("📦 {} cached binaries", summary.binaries.len()))?;
+		}
+		if summary.keystore {
+			self.cli.info("🔐 keystore (encrypted)")?;
+		}
+		Ok(())
+	}
+}
+
+/// The passphrase of the keystore, read from `POP_ENV_PASSPHRASE` or otherwise prompted for. No
+/// passphrase is required when no keystore is specified.
+///
+/// # Arguments
+/// * `args` - The arguments of the command.
+pub(crate) fn passphrase(args: &EnvCommandArgs) -> Result<Option<String>> {
+	if args.keystore.is_none() {
+		return Ok(None);
+	}
+	if let Ok(passphrase) = env::var(PASSPHRASE_VAR) {
+		return Ok(Some(passphrase));
+	}
+	Ok(Some(
+		cliclack::password("Enter the passphrase of the keystore")
+			.mask('▪')
+			.interact()?,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use std::fs::{create_dir, write};
+
+	#[test]
+	fn export_and_import_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("project");
+		create_dir(&project)?;
+		write(project.join("pop.toml"), "[hooks]")?;
+		let archive = temp_dir.path().join("environment.tar.gz");
+		let args = || EnvCommandArgs {
+			archive: archive.clone(),
+			path: project.clone(),
+			binaries: false,
+			keystore: None,
+		};
+
+		let mut cli = MockCli::new()
+			.expect_intro("Export environment")
+			.expect_info("📄 pop.toml")
+			.expect_outro(format!("✅ Environment exported to {}", archive.display()));
+		let cache = temp_dir.path().join("cache");
+		EnvCommand { cli: &mut cli, cache: cache.clone(), args: args(), passphrase: None }
+			.export()?;
+		cli.verify()?;
+
+		std::fs::remove_file(project.join("pop.toml"))?;
+		let mut cli = MockCli::new()
+			.expect_intro("Import environment")
+			.expect_info("📄 pop.toml")
+			.expect_outro(format!("✅ Environment imported from {}", archive.display()));
+		EnvCommand { cli: &mut cli, cache, args: args(), passphrase: None }.import()?;
+		assert!(project.join("pop.toml").exists());
+		cli.verify()
+	}
+}
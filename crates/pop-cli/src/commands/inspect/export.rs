@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::{self, Cli},
+	style::style,
+};
+use clap::Args;
+use pop_parachains::{export_chain_data, ExportFormat};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct ExportCommand {
+	/// Websocket endpoint of a node of the chain, retaining the state of the blocks exported
+	/// (e.g. an archive node).
+	#[arg(long)]
+	pub(crate) url: url::Url,
+	/// The number of the first block exported.
+	#[arg(long)]
+	pub(crate) from: u32,
+	/// The number of the last block exported [default: the first block].
+	#[arg(long)]
+	pub(crate) to: Option<u32>,
+	/// The format of the export.
+	#[arg(long, value_enum, default_value_t)]
+	pub(crate) format: ExportFormat,
+	/// The path of the database or SQL script, or the directory of Parquet files [default:
+	/// `chain-data.db`, `chain-data.sql` or `chain-data`, according to the format].
+	#[arg(short = 'o', long)]
+	pub(crate) output: Option<PathBuf>,
+}
+
+impl ExportCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Exports the decoded extrinsics and events of a range of blocks, so that they can be
+	/// analysed without an indexer.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Export chain data")?;
+		let to = self.to.unwrap_or(self.from);
+		let output = self.output.clone().unwrap_or_else(|| match self.format {
+			ExportFormat::Sqlite => "chain-data.db".into(),
+			ExportFormat::Sql => "chain-data.sql".into(),
+			ExportFormat::Parquet => "chain-data".into(),
+		});
+		let spinner = cliclack::spinner();
+		spinner.start(format!("Exporting blocks #{} to #{to}...", self.from));
+		let result =
+			export_chain_data(self.url.as_str(), self.from..=to, self.format, &output, |n| {
+				spinner.set_message(format!("Decoded block #{n} of #{to}..."))
+			})
+			.await;
+		spinner.clear();
+		match result {
+			Ok(files) => {
+				let files: Vec<_> = files
+					.iter()
+					.map(|f| {
+						style(format!("{} {}", console::Emoji("●", ">"), f.display()))
+							.dim()
+							.to_string()
+					})
+					.collect();
+				cli.success(format!("Generated files:\n{}", files.join("\n")))?;
+				cli.outro(format!("✅ Exported blocks #{} to #{to}", self.from))?;
+			},
+			Err(e) => cli.outro_cancel(format!("🚫 Unable to export the chain data: {e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn run_fails_with_invalid_range() -> anyhow::Result<()> {
+		let mut cli = MockCli::new().expect_intro("Export chain data").expect_outro_cancel(
+			"🚫 Unable to export the chain data: Export error: invalid block range: 10 to 1",
+		);
+		ExportCommand {
+			url: "ws://127.0.0.1:9944".parse()?,
+			from: 10,
+			to: Some(1),
+			format: ExportFormat::Sql,
+			output: None,
+		}
+		.run(&mut cli)
+		.await?;
+		cli.verify()
+	}
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod export;
+pub(crate) mod release_notes;
+pub(crate) mod runtime;
+pub(crate) mod spec;
+pub(crate) mod storage_key;
+pub(crate) mod test_vectors;
+
+/// Arguments for inspecting the state of a chain.
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct InspectArgs {
+	#[command(subcommand)]
+	pub command: Command,
+}
+
+/// Inspect the state of a chain, its chain specification or its runtime.
+#[derive(Subcommand)]
+pub(crate) enum Command {
+	/// Compute the storage key of a storage entry, or decode a storage key into the entry and
+	/// keys to which it refers.
+	#[clap(alias = "k")]
+	StorageKey(storage_key::StorageKeyCommand),
+	/// Report the properties of a chain specification, such as its para id, bootnodes and code
+	/// hash, decoding its raw genesis storage if a node is specified.
+	#[clap(alias = "s")]
+	Spec(spec::InspectSpecCommand),
+	/// Report the properties of a runtime, such as its version, hashes and the metadata versions
+	/// it provides, read from its wasm without a running node, optionally verifying that it is
+	/// the runtime of a live chain.
+	#[clap(alias = "r")]
+	Runtime(runtime::InspectRuntimeCommand),
+	/// Generate the release notes of a runtime upgrade, such as the pallets, calls and storage
+	/// entries added, changed or removed, from the metadata of the old and new runtimes.
+	#[clap(alias = "n")]
+	ReleaseNotes(release_notes::ReleaseNotesCommand),
+	/// Export the decoded extrinsics and events of a range of blocks to SQLite or Parquet, so
+	/// that they can be analysed without an indexer.
+	#[clap(alias = "e")]
+	Export(export::ExportCommand),
+	/// Generate deterministic encode/decode test vectors of a type within the metadata of a
+	/// chain, as JSON and hex, for testing clients in other languages against the chain.
+	#[clap(alias = "v")]
+	TestVectors(test_vectors::TestVectorsCommand),
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{load_metadata, ReleaseNotes};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct ReleaseNotesCommand {
+	/// The metadata of the old runtime: either a websocket endpoint of a node running it, or the
+	/// path of a file containing its SCALE-encoded metadata (e.g. as produced by `subxt
+	/// metadata`).
+	pub(crate) old: String,
+	/// The metadata of the new runtime: either a websocket endpoint of a node running it, or the
+	/// path of a file containing its SCALE-encoded metadata.
+	pub(crate) new: String,
+	/// Path of a file to which the release notes are written, as Markdown.
+	#[arg(short = 'o', long)]
+	pub(crate) output: Option<PathBuf>,
+}
+
+impl ReleaseNotesCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Generates the release notes of a runtime upgrade from the differences between the
+	/// metadata of the old and new runtimes.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Generate release notes")?;
+		let mut metadata = Vec::with_capacity(2);
+		for source in [&self.old, &self.new] {
+			match load_metadata(source).await {
+				Ok(loaded) => metadata.push(loaded),
+				Err(e) => {
+					cli.outro_cancel(format!("🚫 Unable to load the metadata from {source}: {e}"))?;
+					return Ok(());
+				},
+			}
+		}
+		let notes = ReleaseNotes::new(&metadata[0], &metadata[1]);
+		for warning in notes.warnings() {
+			cli.warning(format!("NOTE: {warning}"))?;
+		}
+		match &self.output {
+			Some(output) => {
+				std::fs::write(output, format!("{notes}\n"))?;
+				cli.outro(format!("✅ Release notes written to {}", output.display()))?;
+			},
+			None => {
+				cli.info(notes)?;
+				cli.outro("✅ Release notes generated")?;
+			},
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn run_fails_without_metadata() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let old = temp_dir.path().join("old.scale").display().to_string();
+		let mut cli =
+			MockCli::new()
+				.expect_intro("Generate release notes")
+				.expect_outro_cancel(format!(
+				"🚫 Unable to load the metadata from {old}: IO error: No such file or directory (os error 2)"
+			));
+		ReleaseNotesCommand { old: old.clone(), new: old, output: None }
+			.run(&mut cli)
+			.await?;
+		cli.verify()
+	}
+}
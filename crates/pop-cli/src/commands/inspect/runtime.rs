@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{inspect_runtime, runtime_artifacts, verify_runtime};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct InspectRuntimeCommand {
+	/// Path of the runtime [default: the most recently built runtime of the project].
+	pub(crate) wasm: Option<PathBuf>,
+	/// Directory path of the project, within which the runtime was built [default: current
+	/// directory].
+	#[arg(short = 'p', long, conflicts_with = "wasm")]
+	pub(crate) path: Option<PathBuf>,
+	/// Websocket endpoint of a node of a live chain, whose runtime code is verified to be the
+	/// runtime (e.g. when reviewing an upgrade proposal).
+	#[arg(long)]
+	pub(crate) url: Option<url::Url>,
+}
+
+impl InspectRuntimeCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Reports the properties of a runtime, read from its wasm, verifying that it is the runtime
+	/// of a live chain if a node is specified.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Inspect a runtime")?;
+		let wasm = match &self.wasm {
+			Some(wasm) => wasm.clone(),
+			None => {
+				let project = self.path.clone().unwrap_or_else(|| PathBuf::from("./"));
+				match runtime_artifacts(&project.join("target/release"), None)
+					.map(|runtime| runtime.compressed)
+				{
+					Ok(wasm) => wasm,
+					Err(_) => {
+						cli.outro_cancel(
+							"🚫 No runtime found: build it with `pop build --release`, or specify its path",
+						)?;
+						return Ok(());
+					},
+				}
+			},
+		};
+		match inspect_runtime(&wasm) {
+			Ok(info) => cli.info(format!("{}\n{info}", wasm.display()))?,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 Unable to inspect {}: {e}", wasm.display()))?;
+				return Ok(());
+			},
+		}
+		let Some(url) = &self.url else {
+			cli.outro("✅ Runtime inspected")?;
+			return Ok(());
+		};
+		let spinner = cliclack::spinner();
+		spinner.start(format!("Fetching the runtime code of {url}..."));
+		let verification = verify_runtime(&wasm, url.as_str()).await;
+		spinner.clear();
+		match verification {
+			Ok(verification) if verification.matches() =>
+				cli.outro(format!("✅ The {verification}"))?,
+			Ok(verification) => cli.outro_cancel(format!("🚫 The {verification}"))?,
+			Err(e) => cli.outro_cancel(format!("🚫 Unable to verify the runtime: {e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn run_fails_with_invalid_runtime() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let wasm = temp_dir.path().join("runtime.wasm");
+		std::fs::write(&wasm, "not wasm")?;
+		let mut cli = MockCli::new().expect_intro("Inspect a runtime").expect_outro_cancel(
+			format!("🚫 Unable to inspect {}: Invalid runtime: not a wasm module", wasm.display()),
+		);
+		InspectRuntimeCommand { wasm: Some(wasm), path: None, url: None }
+			.run(&mut cli)
+			.await?;
+		cli.verify()
+	}
+}
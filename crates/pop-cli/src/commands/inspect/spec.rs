@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{
+	decode_raw_storage, set_up_client, validate_chain_spec, ChainSpec, ChainSpecSummary,
+};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct InspectSpecCommand {
+	/// The path of the chain specification, either plain or raw.
+	pub(crate) path: PathBuf,
+	/// The relay chain to which the parachain is expected to connect (e.g. `paseo`).
+	#[arg(long = "relay")]
+	pub(crate) relay_chain: Option<String>,
+	/// Websocket endpoint of a node running the runtime of the chain, whose metadata is used to
+	/// decode the keys of the raw genesis storage.
+	#[arg(long)]
+	pub(crate) url: Option<url::Url>,
+}
+
+impl InspectSpecCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Reports the human-readable properties of a chain specification and any structural
+	/// problems, along with its decoded genesis storage if a node is specified.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro(format!("Inspecting {}", self.path.display()))?;
+		let spec = match ChainSpec::from(&self.path) {
+			Ok(spec) => spec,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		cli.info(ChainSpecSummary::from(&spec))?;
+		let live = spec.get_chain_type() == Some("Live");
+		for diagnostic in validate_chain_spec(&spec, self.relay_chain.as_deref(), live) {
+			cli.warning(format!("NOTE: {diagnostic}"))?;
+		}
+		let Some(url) = &self.url else {
+			cli.outro("✅ Inspection complete.")?;
+			return Ok(());
+		};
+		let storage = match set_up_client(url.as_str()).await {
+			Ok(client) => decode_raw_storage(&spec, &client.metadata()),
+			Err(e) => Err(e),
+		};
+		match storage {
+			Ok(storage) => {
+				let decoded = storage.iter().filter(|entry| entry.decoded.is_some()).count();
+				for entry in storage {
+					let key = entry.decoded.unwrap_or(entry.key);
+					cli.info(format!("{key}: {}", entry.value))?;
+				}
+				cli.outro(format!("✅ Inspection complete: decoded {decoded} storage key(s)."))?;
+			},
+			Err(e) => cli.outro_cancel(format!("🚫 {e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use serde_json::json;
+	use std::fs::write;
+
+	#[tokio::test]
+	async fn inspect_spec_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("chain-spec.json");
+		let spec = json!({"name": "Pop Network", "id": "pop", "genesis": {"raw": {"top": {}}}});
+		write(&path, spec.to_string())?;
+		let command =
+			InspectSpecCommand { path: path.clone(), relay_chain: Some("paseo".into()), url: None };
+		let mut cli = MockCli::new()
+			.expect_intro(format!("Inspecting {}", path.display()))
+			.expect_info(ChainSpecSummary::from(&ChainSpec::from(&path)?))
+			.expect_warning("NOTE: missing `para_id`")
+			.expect_warning("NOTE: missing `relay_chain`, expected `paseo`")
+			.expect_warning(
+				"NOTE: the raw genesis storage does not contain the runtime code (`:code`)",
+			)
+			.expect_outro("✅ Inspection complete.");
+		command.run(&mut cli).await?;
+		cli.verify()
+	}
+}
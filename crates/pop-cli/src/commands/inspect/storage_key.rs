@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{decode_storage_key, set_up_client, storage_key};
+
+/// The endpoint of the chain inspected, unless specified.
+const DEFAULT_URL: &str = "ws://localhost:9944";
+
+#[derive(Args)]
+pub(crate) struct StorageKeyCommand {
+	/// The storage entry, specified as `<pallet>::<entry>` (e.g. `System::Account`).
+	#[arg(value_parser = parse_entry, required_unless_present = "decode")]
+	pub(crate) entry: Option<(String, String)>,
+	/// The keys of the storage entry. Trailing keys may be omitted to compute the prefix of a
+	/// map.
+	pub(crate) keys: Vec<String>,
+	/// Decode the specified storage key, rather than computing one.
+	#[arg(long, conflicts_with = "entry")]
+	pub(crate) decode: Option<String>,
+	/// Websocket endpoint of a node, from which the metadata of the chain is fetched.
+	#[arg(long, default_value = DEFAULT_URL)]
+	pub(crate) url: url::Url,
+}
+
+fn parse_entry(entry: &str) -> Result<(String, String), String> {
+	match entry.split_once("::") {
+		Some((pallet, entry)) if !pallet.is_empty() && !entry.is_empty() =>
+			Ok((pallet.to_string(), entry.to_string())),
+		_ => Err(format!("expected `<pallet>::<entry>`, found `{entry}`")),
+	}
+}
+
+impl StorageKeyCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Computes or decodes a storage key, using the metadata of the chain.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		match (&self.decode, &self.entry) {
+			(Some(key), _) => cli.intro(format!("Decoding the storage key {key}"))?,
+			(None, Some((pallet, entry))) =>
+				cli.intro(format!("Computing the storage key of {pallet}::{entry}"))?,
+			(None, None) =>
+				return Err(anyhow::anyhow!("expected a storage entry, or a storage key to decode")),
+		}
+		let metadata = match set_up_client(self.url.as_str()).await {
+			Ok(client) => client.metadata(),
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		let result = match (&self.decode, &self.entry) {
+			(Some(key), _) => decode_storage_key(&metadata, key).map(|decoded| decoded.to_string()),
+			(None, Some((pallet, entry))) => storage_key(&metadata, pallet, entry, &self.keys),
+			(None, None) => unreachable!("validated above"),
+		};
+		match result {
+			Ok(output) => cli.outro(format!("✅ {output}"))?,
+			Err(e) => cli.outro_cancel(format!("🚫 {e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_entry_works() {
+		assert_eq!(parse_entry("System::Account"), Ok(("System".into(), "Account".into())));
+		for entry in ["System", "System::", "::Account"] {
+			assert!(parse_entry(entry).is_err());
+		}
+	}
+}
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{generate_test_vectors, load_metadata};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct TestVectorsCommand {
+	/// The type: its path (e.g. `sp_runtime::multiaddress::MultiAddress`), the last segment of its
+	/// path if unique (e.g. `AccountInfo`), a primitive (e.g. `u128`) or its identifier within the
+	/// type registry (e.g. `#10`).
+	#[arg(name = "type")]
+	pub(crate) ty: String,
+	/// The metadata of the chain: either a websocket endpoint of a node, or the path of a file
+	/// containing its SCALE-encoded metadata (e.g. as produced by `subxt metadata`).
+	#[arg(long, default_value = "ws://localhost:9944")]
+	pub(crate) metadata: String,
+	/// The maximum number of test vectors generated.
+	#[arg(short = 'n', long, default_value_t = 16)]
+	pub(crate) count: usize,
+	/// Path of a file to which the test vectors are written, as JSON.
+	#[arg(short = 'o', long)]
+	pub(crate) output: Option<PathBuf>,
+}
+
+impl TestVectorsCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Generates encode/decode test vectors of a type within the metadata of a chain, for
+	/// testing clients in other languages.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro(format!("Generate test vectors of {}", self.ty))?;
+		let metadata = match load_metadata(&self.metadata).await {
+			Ok(metadata) => metadata,
+			Err(e) => {
+				cli.outro_cancel(format!(
+					"🚫 Unable to load the metadata from {}: {e}",
+					self.metadata
+				))?;
+				return Ok(());
+			},
+		};
+		let vectors = match generate_test_vectors(&metadata, &self.ty, self.count) {
+			Ok(vectors) => vectors,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		let summary =
+			format!("✅ {} test vectors of {} generated", vectors.vectors.len(), vectors.type_path);
+		match &self.output {
+			Some(output) => {
+				std::fs::write(output, format!("{}\n", vectors.to_json()?))?;
+				cli.outro(format!("{summary}, written to {}", output.display()))?;
+			},
+			None => {
+				cli.info(vectors.to_json()?)?;
+				cli.outro(summary)?;
+			},
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn run_fails_without_metadata() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let metadata = temp_dir.path().join("metadata.scale").display().to_string();
+		let mut cli = MockCli::new()
+			.expect_intro("Generate test vectors of AccountInfo")
+			.expect_outro_cancel(format!(
+				"🚫 Unable to load the metadata from {metadata}: IO error: No such file or directory (os error 2)"
+			));
+		TestVectorsCommand { ty: "AccountInfo".into(), metadata, count: 16, output: None }
+			.run(&mut cli)
+			.await?;
+		cli.verify()
+	}
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use console::{style, Emoji, Term};
+use pop_parachains::{find_networks, log_path, network_nodes, LogOptions, LogStream};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct LogsCommand {
+	/// The name of the node whose log is streamed. The nodes of the network are listed if not
+	/// specified.
+	pub(crate) node: Option<String>,
+	/// The directory within which the network was launched [default: the most recently launched
+	/// network].
+	#[arg(long, value_name = "DIR")]
+	pub(crate) network: Option<PathBuf>,
+	/// Keep streaming lines as they are appended to the log.
+	#[arg(short, long)]
+	pub(crate) follow: bool,
+	/// Only stream the last lines of the existing log.
+	#[arg(short = 'n', long, value_name = "LINES")]
+	pub(crate) tail: Option<usize>,
+	/// Only stream lines matching the regular expression (e.g. "ERROR|WARN").
+	#[arg(long)]
+	pub(crate) filter: Option<String>,
+}
+
+impl LogsCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Streams the log of a node of a local network to stdout, or lists the nodes of the network.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Show the logs of a local network")?;
+		let network = match &self.network {
+			Some(network) => Some(network.clone()),
+			None => find_networks(&std::env::temp_dir())?.into_iter().next(),
+		};
+		let Some(network) = network else {
+			cli.outro_cancel("🚫 No local network found. Launch one with `pop up parachain`.")?;
+			return Ok(());
+		};
+		let nodes = network_nodes(&network)?;
+		let Some(node) = &self.node else {
+			let nodes: Vec<_> = nodes
+				.iter()
+				.map(|node| {
+					style(format!(
+						"{} {node}: {}",
+						Emoji("●", ">"),
+						log_path(&network, node).display()
+					))
+					.dim()
+					.to_string()
+				})
+				.collect();
+			cli.info(format!(
+				"Nodes of the network at {}:\n{}",
+				network.display(),
+				nodes.join("\n")
+			))?;
+			cli.outro("Stream the log of a node with `pop logs <NODE>`")?;
+			return Ok(());
+		};
+		if !nodes.contains(node) {
+			cli.outro_cancel(format!(
+				"🚫 The network at {} has no node named `{node}`. Available nodes: {}",
+				network.display(),
+				nodes.join(", ")
+			))?;
+			return Ok(());
+		}
+		let mut options = LogOptions { follow: self.follow, tail: self.tail, filter: None };
+		if let Some(filter) = &self.filter {
+			options = options.filter(filter)?;
+		}
+		let stream = LogStream::open(&log_path(&network, node), options)?;
+		if self.follow {
+			cli.info(format!("Following the log of {node}. Press Ctrl+C to stop."))?;
+			tokio::select! {
+				result = print_logs(stream) => result?,
+				result = tokio::signal::ctrl_c() => result?,
+			}
+		} else {
+			print_logs(stream).await?;
+		}
+		cli.outro("Done")?;
+		Ok(())
+	}
+}
+
+/// Prints the lines of a log to stdout, until the end of the stream.
+///
+/// # Arguments
+/// * `stream` - The stream of log lines.
+pub(crate) async fn print_logs(mut stream: LogStream) -> anyhow::Result<()> {
+	let term = Term::stdout();
+	while let Some(line) = stream.next().await {
+		term.write_line(&line?)?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use std::fs::{create_dir_all, write};
+
+	fn command(network: PathBuf, node: Option<&str>) -> LogsCommand {
+		LogsCommand {
+			node: node.map(String::from),
+			network: Some(network),
+			follow: false,
+			tail: None,
+			filter: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn run_lists_nodes() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let network = temp_dir.path().to_path_buf();
+		create_dir_all(network.join("alice"))?;
+		write(log_path(&network, "alice"), "imported #1\n")?;
+		let mut cli = MockCli::new()
+			.expect_intro("Show the logs of a local network")
+			.expect_info(format!(
+				"Nodes of the network at {}:\n{}",
+				network.display(),
+				style(format!(
+					"{} alice: {}",
+					Emoji("●", ">"),
+					log_path(&network, "alice").display()
+				))
+				.dim()
+			))
+			.expect_outro("Stream the log of a node with `pop logs <NODE>`");
+		command(network, None).run(&mut cli).await?;
+		cli.verify()
+	}
+
+	#[tokio::test]
+	async fn run_fails_with_unknown_node() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let network = temp_dir.path().to_path_buf();
+		create_dir_all(network.join("alice"))?;
+		write(log_path(&network, "alice"), "")?;
+		let mut cli = MockCli::new()
+			.expect_intro("Show the logs of a local network")
+			.expect_outro_cancel(format!(
+				"🚫 The network at {} has no node named `bob`. Available nodes: alice",
+				network.display()
+			));
+		command(network, Some("bob")).run(&mut cli).await?;
+		cli.verify()
+	}
+}
@@ -8,8 +8,16 @@ use serde_json::{json, Value};
 pub(crate) mod build;
 pub(crate) mod call;
 pub(crate) mod clean;
+pub(crate) mod env;
+#[cfg(feature = "parachain")]
+pub(crate) mod inspect;
 pub(crate) mod install;
+#[cfg(feature = "parachain")]
+pub(crate) mod logs;
 pub(crate) mod new;
+pub(crate) mod pipeline;
+pub(crate) mod setup;
+pub(crate) mod stats;
 pub(crate) mod test;
 pub(crate) mod up;
 
@@ -26,21 +34,41 @@ pub(crate) enum Command {
 	#[clap(alias = "b", about = about_build())]
 	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Build(build::BuildArgs),
-	/// Call a smart contract.
+	/// Call a smart contract, or a call saved within the project.
 	#[clap(alias = "c")]
-	#[cfg(feature = "contract")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Call(call::CallArgs),
-	/// Launch a local network or deploy a smart contract.
+	/// Launch a local network, deploy a smart contract or onboard onto a relay chain.
 	#[clap(alias = "u")]
 	#[cfg(any(feature = "parachain", feature = "contract"))]
-	Up(up::UpArgs),
-	/// Test a smart contract.
+	Up(Box<up::UpArgs>),
+	/// Test a smart contract, or check a runtime against live state.
 	#[clap(alias = "t")]
-	#[cfg(feature = "contract")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Test(test::TestArgs),
+	/// Inspect the state of a chain, its chain specification or its runtime.
+	#[clap(alias = "I")]
+	#[cfg(feature = "parachain")]
+	Inspect(inspect::InspectArgs),
+	/// Show the logs of the nodes of a local network, optionally following them.
+	#[clap(alias = "l")]
+	#[cfg(feature = "parachain")]
+	Logs(logs::LogsCommand),
 	/// Remove generated/cached artifacts.
 	#[clap(alias = "C")]
 	Clean(clean::CleanArgs),
+	/// Run a declarative pipeline of pop operations.
+	#[clap(alias = "P")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Pipeline(pipeline::PipelineCommand),
+	/// Choose the settings used by pop, such as consent to usage metrics and the cache location.
+	/// Offered on the first interactive run.
+	Setup(setup::SetupCommand),
+	/// Show how long builds, binary sourcing and launches take, as recorded locally.
+	Stats(stats::StatsArgs),
+	/// Export or import the environment, including its configuration, cached binaries and
+	/// keystore.
+	Env(env::EnvArgs),
 }
 
 /// Help message for the build command.
@@ -83,6 +111,10 @@ impl Command {
 					// deeper in the stack
 					cmd.execute().await.map(|_| json!("default"))
 				},
+				#[cfg(feature = "parachain")]
+				new::Command::Indexer(cmd) => cmd.execute().await.map(|_| Value::Null),
+				#[cfg(feature = "parachain")]
+				new::Command::Network(cmd) => cmd.execute().await.map(|_| Value::Null),
 			},
 			#[cfg(any(feature = "parachain", feature = "contract"))]
 			Self::Build(args) => match args.command {
@@ -93,35 +125,99 @@ impl Command {
 					#[cfg(feature = "contract")]
 					build::Command::Contract(cmd) => cmd.execute().map(|_| Value::Null),
 					#[cfg(feature = "parachain")]
+					build::Command::Runtime(cmd) => cmd.execute().map(|_| Value::Null),
+					#[cfg(feature = "parachain")]
 					build::Command::Spec(cmd) => cmd.execute().await.map(|_| Value::Null),
 				},
 			},
-			#[cfg(feature = "contract")]
+			#[cfg(any(feature = "parachain", feature = "contract"))]
 			Self::Call(args) => match args.command {
+				#[cfg(feature = "contract")]
 				call::Command::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+				#[cfg(feature = "contract")]
+				call::Command::Repro(cmd) => cmd.execute().await.map(|_| Value::Null),
+				#[cfg(feature = "parachain")]
+				call::Command::Referendum(cmd) => cmd.execute().await.map(|_| Value::Null),
+				#[cfg(feature = "parachain")]
+				call::Command::RuntimeApi(cmd) => cmd.execute().await.map(|_| Value::Null),
+				call::Command::Saved(cmd) => cmd.execute().await.map(|_| Value::Null),
 			},
 			#[cfg(any(feature = "parachain", feature = "contract"))]
 			Self::Up(args) => match args.command {
-				#[cfg(feature = "parachain")]
-				up::Command::Parachain(cmd) => cmd.execute().await.map(|_| Value::Null),
-				#[cfg(feature = "contract")]
-				up::Command::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+				None => up::Command::execute(*args).await.map(|_| Value::Null),
+				Some(cmd) => match cmd {
+					#[cfg(feature = "parachain")]
+					up::Command::Parachain(cmd) => cmd.execute().await.map(|_| Value::Null),
+					#[cfg(feature = "parachain")]
+					up::Command::Faucet(cmd) => cmd.execute().await.map(|_| Value::Null),
+					#[cfg(feature = "parachain")]
+					up::Command::Monitor(cmd) => cmd.execute().await.map(|_| Value::Null),
+					#[cfg(feature = "parachain")]
+					up::Command::RuntimeUpgrade(cmd) => cmd.execute().await.map(|_| Value::Null),
+					#[cfg(feature = "contract")]
+					up::Command::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+				},
 			},
-			#[cfg(feature = "contract")]
+			#[cfg(any(feature = "parachain", feature = "contract"))]
 			Self::Test(args) => match args.command {
+				#[cfg(feature = "contract")]
 				test::Command::Contract(cmd) => match cmd.execute().await {
 					Ok(feature) => Ok(json!(feature)),
 					Err(e) => Err(e),
 				},
+				#[cfg(feature = "parachain")]
+				test::Command::Runtime(cmd) => cmd.execute().map(|_| Value::Null),
+				#[cfg(feature = "parachain")]
+				test::Command::Features(cmd) => cmd.execute().map(|_| Value::Null),
+			},
+			#[cfg(feature = "parachain")]
+			Self::Inspect(args) => match args.command {
+				inspect::Command::StorageKey(cmd) => cmd.execute().await.map(|_| Value::Null),
+				inspect::Command::Spec(cmd) => cmd.execute().await.map(|_| Value::Null),
+				inspect::Command::Runtime(cmd) => cmd.execute().await.map(|_| Value::Null),
+				inspect::Command::ReleaseNotes(cmd) => cmd.execute().await.map(|_| Value::Null),
+				inspect::Command::Export(cmd) => cmd.execute().await.map(|_| Value::Null),
+				inspect::Command::TestVectors(cmd) => cmd.execute().await.map(|_| Value::Null),
 			},
+			#[cfg(feature = "parachain")]
+			Self::Logs(cmd) => cmd.execute().await.map(|_| Value::Null),
 			Self::Clean(args) => match args.command {
+				clean::Command::Cache(cmd_args) if cmd_args.gc =>
+					clean::ReclaimCacheCommand { cli: &mut Cli, cache: cache()? }
+						.execute()
+						.map(|_| Value::Null),
 				clean::Command::Cache(cmd_args) => {
 					// Initialize command and execute
 					clean::CleanCacheCommand { cli: &mut Cli, cache: cache()?, all: cmd_args.all }
 						.execute()
 						.map(|_| Value::Null)
 				},
+				#[cfg(feature = "parachain")]
+				clean::Command::Snapshots(cmd_args) => clean::CleanSnapshotsCommand {
+					cli: &mut Cli,
+					cache: cache()?,
+					older_than: cmd_args.older_than,
+				}
+				.execute()
+				.map(|_| Value::Null),
 			},
+			#[cfg(any(feature = "parachain", feature = "contract"))]
+			Self::Pipeline(cmd) => cmd.execute().await.map(|_| Value::Null),
+			Self::Setup(cmd) => cmd.execute().map(|_| Value::Null),
+			Self::Stats(args) => stats::StatsCommand { cli: &mut Cli, cache: cache()?, args }
+				.execute()
+				.map(|_| Value::Null),
+			Self::Env(args) => match args.command {
+				env::Command::Export(args) => {
+					let passphrase = env::passphrase(&args)?;
+					env::EnvCommand { cli: &mut Cli, cache: cache()?, args, passphrase }.export()
+				},
+				env::Command::Import(args) => {
+					let passphrase = env::passphrase(&args)?;
+					env::EnvCommand { cli: &mut Cli, cache: cache()?, args, passphrase }.import()
+				},
+			}
+			.map(|_| Value::Null),
 		}
 	}
 }
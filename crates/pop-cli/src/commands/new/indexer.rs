@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::{self, Cli},
+	style::style,
+};
+use clap::Args;
+use pop_parachains::{generate_indexer, IndexedChain, IndexerFramework};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct NewIndexerCommand {
+	/// Directory of the project, whose name is used as the name of the project [default:
+	/// `indexer`].
+	pub(crate) path: Option<PathBuf>,
+	/// Websocket endpoint of a node of the chain to be indexed.
+	#[arg(long)]
+	pub(crate) url: url::Url,
+	/// The framework of the project.
+	#[arg(short, long, value_enum, default_value_t)]
+	pub(crate) framework: IndexerFramework,
+	/// Comma-separated list of the pallets whose events are indexed [default: all pallets].
+	#[arg(short, long = "pallet", value_delimiter = ',')]
+	pub(crate) pallets: Vec<String>,
+}
+
+impl NewIndexerCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Generates an indexer project pre-configured for a chain, with a starter schema derived
+	/// from the events of its runtime.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Generate an indexer")?;
+		let path = self.path.clone().unwrap_or_else(|| "indexer".into());
+		if path.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+			cli.outro_cancel(format!("🚫 The directory {} already exists.", path.display()))?;
+			return Ok(());
+		}
+		let name = path
+			.file_name()
+			.map(|name| name.to_string_lossy().to_string())
+			.unwrap_or_else(|| "indexer".into());
+		let spinner = cliclack::spinner();
+		spinner.start(format!("Fetching the metadata of the chain at {}...", self.url));
+		let result = match IndexedChain::fetch(self.url.as_str()).await {
+			Ok(chain) => generate_indexer(self.framework, &name, &chain, &self.pallets, &path),
+			Err(e) => Err(e),
+		};
+		spinner.clear();
+		let files = match result {
+			Ok(files) => files,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 Unable to generate the indexer: {e}"))?;
+				return Ok(());
+			},
+		};
+		let files: Vec<_> = files
+			.iter()
+			.map(|f| {
+				style(format!("{} {}", console::Emoji("●", ">"), f.display())).dim().to_string()
+			})
+			.collect();
+		cli.success(format!("Generated files:\n{}", files.join("\n")))?;
+		cli.info(match self.framework {
+			IndexerFramework::SubQuery => format!(
+				"Next, within {}: run `npm install` and `npm run build`, then start the indexer with `npm start` (requires PostgreSQL).",
+				path.display()
+			),
+			IndexerFramework::Squid => format!(
+				"Next, within {}: run `npm install`, `npm run codegen`, `npm run build` and `npm run migration`, then start the indexer with `npm run process` (requires PostgreSQL).",
+				path.display()
+			),
+		})?;
+		cli.outro(format!("✅ Generated the indexer {name}"))?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn run_fails_with_existing_directory() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		std::fs::write(temp_dir.path().join("package.json"), "{}")?;
+		let mut cli = MockCli::new().expect_intro("Generate an indexer").expect_outro_cancel(
+			format!("🚫 The directory {} already exists.", temp_dir.path().display()),
+		);
+		NewIndexerCommand {
+			path: Some(temp_dir.path().to_path_buf()),
+			url: "ws://127.0.0.1:9944".parse()?,
+			framework: IndexerFramework::Squid,
+			pallets: vec![],
+		}
+		.run(&mut cli)
+		.await?;
+		cli.verify()
+	}
+}
@@ -5,6 +5,10 @@ use clap::{Args, Subcommand};
 #[cfg(feature = "contract")]
 pub mod contract;
 #[cfg(feature = "parachain")]
+pub mod indexer;
+#[cfg(feature = "parachain")]
+pub mod network;
+#[cfg(feature = "parachain")]
 pub mod pallet;
 #[cfg(feature = "parachain")]
 pub mod parachain;
@@ -30,7 +34,7 @@ pub struct NewArgs {
 	pub command: Command,
 }
 
-/// Generate a new parachain, pallet or smart contract.
+/// Generate a new parachain, pallet, smart contract, indexer or network configuration.
 #[derive(Subcommand)]
 pub enum Command {
 	/// Generate a new parachain
@@ -45,4 +49,12 @@ pub enum Command {
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
 	Contract(contract::NewContractCommand),
+	/// Generate a new indexer of a chain (SubQuery or Squid)
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "i")]
+	Indexer(indexer::NewIndexerCommand),
+	/// Generate a network configuration launching the collator nodes of the workspace
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "n")]
+	Network(network::NewNetworkCommand),
 }
@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::{self, Cli},
+	style::style,
+};
+use clap::Args;
+use pop_common::Profile;
+use pop_parachains::{workspace_nodes, WorkspaceNetwork};
+use std::{fs::write, path::PathBuf};
+
+#[derive(Args)]
+pub struct NewNetworkCommand {
+	/// Directory path of the workspace [default: current directory].
+	#[arg(long)]
+	pub(crate) path: Option<PathBuf>,
+	/// The file to which the network configuration is written, relative to the workspace.
+	#[arg(short, long, default_value = "network.toml")]
+	pub(crate) output: PathBuf,
+	/// The relay chain launched alongside the parachains [default: the relay chain of the chain
+	/// specification of a node, otherwise that chosen by `pop setup`, otherwise `rococo-local`].
+	#[arg(short, long)]
+	pub(crate) relay_chain: Option<String>,
+	/// Launch the binaries built with the specified profile (e.g. "debug" or "production").
+	#[arg(long, default_value = "release")]
+	pub(crate) profile: Profile,
+	/// Overwrite any existing network configuration file.
+	#[arg(long)]
+	pub(crate) force: bool,
+}
+
+impl NewNetworkCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(mut self) -> anyhow::Result<()> {
+		if self.relay_chain.is_none() {
+			let path = self.path.clone().unwrap_or_else(|| "./".into());
+			let declared = workspace_nodes(&path)
+				.map(|nodes| nodes.iter().any(|node| node.relay_chain.is_some()))
+				.unwrap_or_default();
+			if !declared {
+				self.relay_chain = crate::settings().map(|settings| settings.relay_chain);
+			}
+		}
+		self.run(&mut Cli)
+	}
+
+	/// Generates a network configuration launching the collator nodes of the workspace, each as
+	/// a parachain, alongside a local relay chain.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Generate a network configuration")?;
+		let path = self.path.clone().unwrap_or_else(|| "./".into());
+		let output = path.join(&self.output);
+		if output.exists() && !self.force {
+			cli.outro_cancel(format!(
+				"🚫 The file {} already exists. Use `--force` to overwrite it.",
+				output.display()
+			))?;
+			return Ok(());
+		}
+		let network = match WorkspaceNetwork::inspect(&path, self.relay_chain.as_deref()) {
+			Ok(network) => network,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 Unable to inspect the workspace: {e}"))?;
+				return Ok(());
+			},
+		};
+		let file = output.file_name().map(|f| f.to_string_lossy()).unwrap_or_default();
+		write(&output, network.render(&format!("./{file}"), &self.profile))?;
+		let parachains: Vec<_> = network
+			.parachains
+			.iter()
+			.map(|(id, node)| {
+				style(format!("{} {}: {}", console::Emoji("●", ">"), id, node.package))
+					.dim()
+					.to_string()
+			})
+			.collect();
+		cli.success(format!(
+			"Parachains launched alongside {}:\n{}",
+			network.relay_chain,
+			parachains.join("\n")
+		))?;
+		cli.info(format!(
+			"Next, build the nodes with the {} profile, then launch the network with `pop up parachain -f {}`.",
+			self.profile.to_string().to_lowercase(),
+			self.output.display()
+		))?;
+		cli.outro(format!("✅ Generated {}", output.display()))?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use std::fs::{create_dir_all, read_to_string};
+
+	fn command(path: PathBuf) -> NewNetworkCommand {
+		NewNetworkCommand {
+			path: Some(path),
+			output: "network.toml".into(),
+			relay_chain: None,
+			profile: Profile::Release,
+			force: false,
+		}
+	}
+
+	#[test]
+	fn run_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		write(path.join("Cargo.toml"), "[workspace]\nmembers = [\"node\"]\nresolver = \"2\"\n")?;
+		create_dir_all(path.join("node/src"))?;
+		write(
+			path.join("node/Cargo.toml"),
+			"[package]\nname = \"my-node\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\ncumulus-client-cli = \"0.1\"\n",
+		)?;
+		write(path.join("node/src/main.rs"), "fn main() {}")?;
+		let output = path.join("network.toml");
+		let mut cli = MockCli::new()
+			.expect_intro("Generate a network configuration")
+			.expect_success(format!(
+				"Parachains launched alongside rococo-local:\n{}",
+				style(format!("{} 2000: my-node", console::Emoji("●", ">"))).dim()
+			))
+			.expect_info("Next, build the nodes with the release profile, then launch the network with `pop up parachain -f network.toml`.")
+			.expect_outro(format!("✅ Generated {}", output.display()));
+		command(path.to_path_buf()).run(&mut cli)?;
+		cli.verify()?;
+		assert!(read_to_string(&output)?.contains("default_command = \"./target/release/my-node\""));
+		Ok(())
+	}
+
+	#[test]
+	fn run_fails_with_existing_file() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let output = temp_dir.path().join("network.toml");
+		write(&output, "")?;
+		let mut cli = MockCli::new()
+			.expect_intro("Generate a network configuration")
+			.expect_outro_cancel(format!(
+				"🚫 The file {} already exists. Use `--force` to overwrite it.",
+				output.display()
+			));
+		command(temp_dir.path().to_path_buf()).run(&mut cli)?;
+		cli.verify()
+	}
+}
@@ -9,8 +9,8 @@ use clap::{Args, Subcommand};
 use cliclack::{confirm, input, multiselect, outro, outro_cancel};
 use pop_common::{add_crate_to_workspace, find_workspace_toml, prefix_with_current_dir_if_needed};
 use pop_parachains::{
-	create_pallet_template, TemplatePalletConfig, TemplatePalletConfigCommonTypes,
-	TemplatePalletOptions, TemplatePalletStorageTypes,
+	add_pallet_to_runtime, create_pallet_template, TemplatePalletConfig,
+	TemplatePalletConfigCommonTypes, TemplatePalletOptions, TemplatePalletStorageTypes,
 };
 use std::{fs, path::PathBuf, process::Command};
 use strum::{EnumMessage, IntoEnumIterator};
@@ -25,6 +25,8 @@ fn after_help_simple() -> &'static str {
             -> Will automatically create a pallet called my pallet in the directory ./pallets
         pop new pallet advanced 
             -> Will unlock the advanced mode. pop new pallet advanced --help for further info.
+        pop new pallet pallets/my-pallet --runtime runtime
+            -> Will also add the pallet to the runtime in ./runtime, at the next free pallet index.
     "#
 }
 
@@ -47,6 +49,13 @@ pub struct NewPalletCommand {
 	pub(crate) authors: Option<String>,
 	#[arg(short, long, help = "Pallet description", default_value = "Frame Pallet")]
 	pub(crate) description: Option<String>,
+	/// Add the pallet to the runtime at the specified path, declaring it at the lowest free index
+	/// from `--pallet-index`.
+	#[arg(long, value_name = "PATH")]
+	pub(crate) runtime: Option<PathBuf>,
+	/// The index from which a free index is sought for the pallet within the runtime.
+	#[arg(long, value_name = "INDEX", default_value_t = 50, requires = "runtime")]
+	pub(crate) pallet_index: u8,
 	#[command(subcommand)]
 	pub(crate) mode: Option<Mode>,
 }
@@ -196,6 +205,18 @@ impl NewPalletCommand {
 			.output()?;
 
 		spinner.stop("Generation complete");
+
+		if let Some(runtime) = &self.runtime {
+			match add_pallet_to_runtime(&pallet_path, runtime, self.pallet_index) {
+				Ok((name, index)) => Cli.info(format!(
+					"`{name}` was declared within the runtime at index {index}: implement its `Config` for the runtime to build it."
+				))?,
+				Err(e) => Cli.warning(format!(
+					"The pallet could not be added to the runtime at {}: {e}",
+					runtime.display()
+				))?,
+			}
+		}
 		outro(format!(
 			"cd into \"{}\" and enjoy hacking! 🚀",
 			pallet_path
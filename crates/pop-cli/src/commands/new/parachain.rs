@@ -17,10 +17,13 @@ use cliclack::{
 use pop_common::{
 	enum_variants,
 	templates::{Template, Type},
-	Git, GitHub, Release,
+	toolchain::DEFAULT_CHANNEL,
+	vendor::{CrateSource, DEFAULT_VENDOR_DIR},
+	Git, GitHub, Release, Toolchain,
 };
 use pop_parachains::{
-	instantiate_template_dir, is_initial_endowment_valid, Config, Parachain, Provider,
+	apply_runtime_preset, instantiate_template_dir, is_initial_endowment_valid, next_steps,
+	post_install_checks, Config, Parachain, Provider, RuntimePreset,
 };
 use std::{fs, path::Path, str::FromStr, thread::sleep, time::Duration};
 use strum::VariantArray;
@@ -67,6 +70,33 @@ pub struct NewParachainCommand {
 		help = "Verifies the commit SHA when fetching the latest license and release from GitHub."
 	)]
 	pub(crate) verify: bool,
+	#[arg(
+		long = "preset",
+		help = "Vetted runtime configuration to apply to the generated runtime (e.g. XCM, block time or proxy settings).",
+		value_parser = enum_variants!(RuntimePreset)
+	)]
+	pub(crate) presets: Vec<RuntimePreset>,
+	#[arg(
+		long,
+		help = "Checks that the generated parachain compiles, in addition to the toolchains it requires."
+	)]
+	pub(crate) check: bool,
+	#[arg(
+		long,
+		help = "Prints the post-install checks and next steps as JSON, for use by other tools (e.g. IDEs)."
+	)]
+	pub(crate) json: bool,
+	#[arg(
+		long,
+		help = "Vendors the crates of the generated parachain via `cargo vendor`, configuring cargo to build without access to crates.io."
+	)]
+	pub(crate) vendor: bool,
+	#[arg(
+		long,
+		conflicts_with = "vendor",
+		help = "Configures cargo to source crates from a mirror of crates.io, specified as the URL of its index (e.g. sparse+https://crates.example.com/index/)."
+	)]
+	pub(crate) registry_mirror: Option<String>,
 }
 
 impl NewParachainCommand {
@@ -106,10 +136,23 @@ impl NewParachainCommand {
 			tag_version,
 			config,
 			self.verify,
+			&parachain_config.presets,
+			parachain_config.check,
+			parachain_config.json,
+			self.crate_source(),
 		)
 		.await?;
 		Ok(template)
 	}
+
+	/// Where the crates of the generated parachain are to be sourced from, if not crates.io.
+	fn crate_source(&self) -> Option<CrateSource> {
+		match (&self.registry_mirror, self.vendor) {
+			(Some(registry), _) => Some(CrateSource::Mirror(registry.clone())),
+			(None, true) => Some(CrateSource::Vendored(DEFAULT_VENDOR_DIR.into())),
+			(None, false) => None,
+		}
+	}
 }
 
 /// Guide the user to generate a parachain from available templates.
@@ -162,9 +205,15 @@ async fn guide_user_to_generate_parachain(verify: bool) -> Result<NewParachainCo
 		decimals: Some(customizable_options.decimals),
 		initial_endowment: Some(customizable_options.initial_endowment),
 		verify,
+		presets: vec![],
+		check: false,
+		json: false,
+		vendor: false,
+		registry_mirror: None,
 	})
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn generate_parachain_from_template(
 	name_template: &String,
 	provider: &Provider,
@@ -172,6 +221,10 @@ async fn generate_parachain_from_template(
 	tag_version: Option<String>,
 	config: Config,
 	verify: bool,
+	presets: &[RuntimePreset],
+	check: bool,
+	json: bool,
+	crate_source: Option<CrateSource>,
 ) -> Result<()> {
 	Cli.intro(format!(
 		"Generating \"{name_template}\" using {} from {}!",
@@ -184,6 +237,10 @@ async fn generate_parachain_from_template(
 	let spinner = cliclack::spinner();
 	spinner.start("Generating parachain...");
 	let tag = instantiate_template_dir(template, destination_path, tag_version, config)?;
+	// Pin the toolchain, unless already pinned by the template.
+	if Toolchain::load(destination_path)?.is_none() {
+		Toolchain::parachain(DEFAULT_CHANNEL).save(destination_path)?;
+	}
 	if let Err(err) = Git::git_init(destination_path, "initialized parachain") {
 		if err.class() == git2::ErrorClass::Config && err.code() == git2::ErrorCode::NotFound {
 			outro_cancel("git signature could not be found. Please configure your git config with your name and email")?;
@@ -206,6 +263,30 @@ async fn generate_parachain_from_template(
 			.unwrap_or_default()
 	))?;
 
+	for preset in presets {
+		match apply_runtime_preset(preset, &destination_path.join("runtime")) {
+			Ok(module) => log::info(format!(
+				"Applied the `{preset}` preset to {}: see the module for any remaining integration steps.",
+				module.display()
+			))?,
+			Err(e) => warning(format!("NOTE: the `{preset}` preset could not be applied: {e}"))?,
+		}
+	}
+
+	if let Some(source) = crate_source {
+		let spinner = cliclack::spinner();
+		spinner.start("Configuring the sources of crates...");
+		let result = source.configure(destination_path);
+		spinner.clear();
+		match result {
+			Ok(config) => log::info(format!(
+				"Configured cargo to build without access to crates.io within {}.",
+				config.display()
+			))?,
+			Err(e) => warning(format!("NOTE: the sources of crates could not be configured: {e}"))?,
+		}
+	}
+
 	if !template.is_audited() {
 		// warn about audit status and licensing
 		warning(format!("NOTE: the resulting parachain is not guaranteed to be audited or reviewed for security vulnerabilities.\n{}",
@@ -213,21 +294,37 @@ async fn generate_parachain_from_template(
 							.dim()))?;
 	}
 
-	// add next steps
-	let mut next_steps = vec![
-		format!("cd into \"{name_template}\" and enjoy hacking! 🚀"),
-		"Use `pop build` to build your parachain.".into(),
-	];
-	if let Some(network_config) = template.network_config() {
-		next_steps.push(format!(
-			"Use `pop up parachain -f {network_config}` to launch your parachain on a local network."
-		))
+	// Check the toolchains required by the template, reporting any remaining setup.
+	let spinner = cliclack::spinner();
+	spinner.start("Running post-install checks...");
+	let checks = post_install_checks(template, destination_path, check);
+	spinner.clear();
+	for check in checks.iter().filter(|c| !c.passed) {
+		let remedy = check.remedy.as_deref().unwrap_or_default();
+		warning(format!("NOTE: expected {}.\n{}", check.name, style(remedy).dim()))?;
 	}
+
+	// add next steps
+	let steps = next_steps(template, destination_path);
+	let mut next_steps: Vec<_> = steps
+		.commands
+		.iter()
+		.map(|step| format!("{}: `{}`", step.description, step.command))
+		.collect();
+	next_steps.extend(
+		steps
+			.ports
+			.iter()
+			.map(|port| format!("{} exposes RPC on port {}", port.node, port.rpc_port)),
+	);
 	let next_steps: Vec<_> = next_steps
 		.iter()
 		.map(|s| style(format!("{} {s}", console::Emoji("●", ">"))).dim().to_string())
 		.collect();
 	success(format!("Next Steps:\n{}", next_steps.join("\n")))?;
+	if json {
+		println!("{}", serde_json::json!({ "checks": checks, "next_steps": steps }));
+	}
 
 	outro(format!(
 		"Need help? Learn more at {}\n",
@@ -439,6 +536,35 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn crate_source_works() {
+		let parse = |args: &[&str]| {
+			let cli =
+				Cli::parse_from(["pop", "new", "parachain", "my-parachain"].iter().chain(args));
+			let New(NewArgs { command: ParachainCommand(command) }) = cli.command else {
+				panic!("unable to parse command")
+			};
+			command.crate_source()
+		};
+		assert_eq!(parse(&[]), None);
+		assert_eq!(parse(&["--vendor"]), Some(CrateSource::Vendored("vendor".into())));
+		let registry = "sparse+https://crates.example.com/index/";
+		assert_eq!(
+			parse(&["--registry-mirror", registry]),
+			Some(CrateSource::Mirror(registry.into()))
+		);
+		assert!(Cli::try_parse_from([
+			"pop",
+			"new",
+			"parachain",
+			"my-parachain",
+			"--vendor",
+			"--registry-mirror",
+			registry
+		])
+		.is_err());
+	}
+
 	#[tokio::test]
 	async fn test_new_parachain_command_execute() -> Result<()> {
 		let dir = tempdir()?;
@@ -452,6 +578,11 @@ mod tests {
 			decimals: Some(12),
 			initial_endowment: Some("1u64 << 60".to_string()),
 			verify: false,
+			presets: vec![],
+			check: false,
+			json: false,
+			vendor: false,
+			registry_mirror: None,
 		};
 		command.execute().await?;
 
@@ -0,0 +1,243 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::style;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use cliclack::{intro, log, outro, outro_cancel};
+use duct::cmd;
+use pop_common::pipeline::{Pipeline, Step, StepKind};
+use std::{env::current_exe, path::PathBuf};
+
+/// The environment variable exposing the endpoint of a launched network's relay chain to
+/// subsequent steps.
+const RELAY_CHAIN_URL_VAR: &str = "POP_RELAY_CHAIN_URL";
+
+#[derive(Args)]
+pub(crate) struct PipelineCommand {
+	/// The pipeline file to be run, in TOML or YAML format.
+	#[arg(short, long, default_value = "pipeline.toml")]
+	pub(crate) file: PathBuf,
+	/// Show the planned steps without running them.
+	#[arg(long)]
+	pub(crate) dry_run: bool,
+	/// Whether the output should be verbose.
+	#[arg(short, long, action)]
+	pub(crate) verbose: bool,
+}
+
+impl PipelineCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> Result<()> {
+		intro(format!("{}: Run a pipeline", style(" Pop CLI ").black().on_magenta()))?;
+		let pipeline = Pipeline::load(&self.file)?;
+		let plan = pipeline.plan()?;
+		if self.dry_run {
+			for (index, step) in plan.iter().enumerate() {
+				log::info(format!("{}. {}: {}", index + 1, step.id, describe(step)))?;
+			}
+			outro(format!("{} steps planned", plan.len()))?;
+			return Ok(());
+		}
+
+		// Any networks launched are kept running until the pipeline completes.
+		#[cfg(feature = "parachain")]
+		let mut networks = Vec::new();
+		let mut relay_chain_url: Option<String> = None;
+		for (index, step) in plan.iter().enumerate() {
+			log::step(format!("[{}/{}] {}: {}", index + 1, plan.len(), step.id, describe(step)))?;
+			let result = match step.kind {
+				#[cfg(feature = "parachain")]
+				StepKind::Launch => launch(step, self.verbose).await.map(|(network, url)| {
+					relay_chain_url = url;
+					networks.push(network);
+				}),
+				#[cfg(feature = "parachain")]
+				StepKind::Fund => fund(step, relay_chain_url.as_deref()).await,
+				#[cfg(not(feature = "parachain"))]
+				StepKind::Launch | StepKind::Fund =>
+					Err(anyhow!("`{}` steps require the `parachain` feature", step.id)),
+				_ => run(step, relay_chain_url.as_deref()),
+			};
+			if let Err(e) = result {
+				outro_cancel(format!("🚫 Step `{}` failed: {e}", step.id))?;
+				return Err(anyhow!("the pipeline failed at step `{}`", step.id));
+			}
+		}
+		#[cfg(feature = "parachain")]
+		drop(networks);
+		outro(format!("✅ Pipeline completed: {} steps run", plan.len()))?;
+		Ok(())
+	}
+}
+
+/// A description of the operation performed by a step.
+///
+/// # Arguments
+/// * `step` - The step.
+fn describe(step: &Step) -> String {
+	match step.kind {
+		StepKind::Run => step.command().unwrap_or_default().to_string(),
+		StepKind::Fund => format!(
+			"fund {} with {}",
+			step.option("to").unwrap_or_default(),
+			step.options
+				.get("amount")
+				.map(|a| a.to_string())
+				.unwrap_or_default()
+				.trim_matches('"')
+		),
+		kind => ["pop"]
+			.into_iter()
+			.chain(kind.subcommand().iter().copied())
+			.map(String::from)
+			.chain(step.args())
+			.collect::<Vec<_>>()
+			.join(" "),
+	}
+}
+
+/// Runs a step as a separate process, via its `pop` subcommand or the specified command.
+///
+/// # Arguments
+/// * `step` - The step.
+/// * `relay_chain_url` - The endpoint of a launched relay chain, if any.
+fn run(step: &Step, relay_chain_url: Option<&str>) -> Result<()> {
+	let mut expression = match step.kind {
+		StepKind::Run => cmd("sh", ["-c", step.command().unwrap_or_default()]),
+		kind =>
+			cmd(current_exe()?, kind.subcommand().iter().map(|s| s.to_string()).chain(step.args())),
+	};
+	if let Some(url) = relay_chain_url {
+		expression = expression.env(RELAY_CHAIN_URL_VAR, url);
+	}
+	expression.run()?;
+	Ok(())
+}
+
+/// Launches a local network, sourcing any missing binaries without prompting.
+///
+/// # Arguments
+/// * `step` - The step, whose options specify the network configuration.
+/// * `verbose` - Whether the output should be verbose.
+#[cfg(feature = "parachain")]
+async fn launch(step: &Step, verbose: bool) -> Result<(impl Sized, Option<String>)> {
+	use crate::commands::up::parachain::ZombienetCommand;
+	use pop_parachains::Zombienet;
+
+	let cache = crate::cache()?;
+	let parachains: Option<Vec<String>> = step.options.get("parachain").map(|value| match value {
+		serde_json::Value::Array(values) =>
+			values.iter().filter_map(|v| v.as_str()).map(String::from).collect(),
+		value => value.as_str().map(String::from).into_iter().collect(),
+	});
+	let mut zombienet = Zombienet::new(
+		&cache,
+		step.option("file").unwrap_or_default(),
+		step.option("relay-chain"),
+		step.option("relay-chain-runtime"),
+		step.option("system-parachain"),
+		step.option("system-parachain-runtime"),
+		parachains.as_ref(),
+	)
+	.await?;
+	if ZombienetCommand::source_binaries(&mut zombienet, &cache, verbose, true).await? {
+		return Err(anyhow!("the required binaries could not be sourced"));
+	}
+	let network = zombienet.spawn().await?;
+	let url = network.relaychain().nodes().first().map(|node| node.ws_uri().to_string());
+	if let Some(url) = &url {
+		log::info(format!("Network launched, relay chain available at {url}"))?;
+	}
+	Ok((network, url))
+}
+
+/// Funds an account via a balance transfer, signed by `//Alice` unless a `suri` option is
+/// specified. The signer is configured for the accounts of the chain, unless a `scheme` option
+/// (e.g. `ecdsa`) specifies the signature scheme of the key. A `fee-payment` option (e.g.
+/// `asset:1984`) specifies how the fees of the transfer are paid.
+///
+/// # Arguments
+/// * `step` - The step, whose options specify the transfer.
+/// * `relay_chain_url` - The endpoint of a launched relay chain, used if no `url` option is
+///   specified.
+#[cfg(feature = "parachain")]
+async fn fund(step: &Step, relay_chain_url: Option<&str>) -> Result<()> {
+	use pop_parachains::{
+		construct_transfer_extrinsic, submit_extrinsic, FeePayment, SignatureScheme,
+	};
+
+	let url = step
+		.option("url")
+		.or(relay_chain_url)
+		.ok_or_else(|| anyhow!("no `url` option specified and no network launched"))?;
+	// Amounts may exceed the range of TOML integers, so are also accepted as strings.
+	let amount: u128 = match step.options.get("amount") {
+		Some(serde_json::Value::String(amount)) => amount.parse().ok(),
+		Some(amount) => amount.as_u64().map(u128::from),
+		None => None,
+	}
+	.ok_or_else(|| anyhow!("invalid `amount` option"))?;
+	let call = construct_transfer_extrinsic(step.option("to").unwrap_or_default(), amount)?;
+	let scheme = step
+		.option("scheme")
+		.map(|s| s.parse::<SignatureScheme>())
+		.transpose()
+		.map_err(|_| anyhow!("invalid `scheme` option"))?;
+	let fee_payment: FeePayment = step.option("fee-payment").unwrap_or("native").parse()?;
+	submit_extrinsic(url, &call, step.option("suri").unwrap_or("//Alice"), scheme, &fee_payment)
+		.await?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::{read_to_string, write};
+
+	#[test]
+	fn describe_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("pipeline.yaml");
+		write(
+			&path,
+			r#"
+steps:
+  - id: spec
+    kind: spec
+    options: { id: 2000, release: true }
+  - id: fund
+    kind: fund
+    options: { to: 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY, amount: 1000 }
+  - id: test
+    kind: run
+    options: { command: cargo test }
+"#,
+		)?;
+		let pipeline = Pipeline::load(&path)?;
+		assert_eq!(describe(&pipeline.steps[0]), "pop build spec --id 2000 --release");
+		assert_eq!(
+			describe(&pipeline.steps[1]),
+			"fund 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY with 1000"
+		);
+		assert_eq!(describe(&pipeline.steps[2]), "cargo test");
+		Ok(())
+	}
+
+	#[test]
+	fn run_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("pipeline.toml");
+		let output = temp_dir.path().join("output");
+		write(
+			&path,
+			format!(
+				"[[steps]]\nid = \"run\"\nkind = \"run\"\noptions = {{ command = 'echo $POP_RELAY_CHAIN_URL > {}' }}",
+				output.display()
+			),
+		)?;
+		let pipeline = Pipeline::load(&path)?;
+		run(&pipeline.steps[0], Some("ws://127.0.0.1:9944"))?;
+		assert_eq!(read_to_string(output)?, "ws://127.0.0.1:9944\n");
+		Ok(())
+	}
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{
+	self,
+	traits::{Confirm, Input as _, Select},
+};
+use clap::Args;
+use pop_common::bootstrap::{Answer, Bootstrap, Input};
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub(crate) struct SetupCommand {
+	/// The location of the cache [default: the cache directory of the system].
+	#[arg(long, value_name = "DIR")]
+	pub(crate) cache: Option<PathBuf>,
+	/// The relay chain used by default for local networks (e.g. "paseo-local").
+	#[arg(long)]
+	pub(crate) relay_chain: Option<String>,
+}
+
+impl SetupCommand {
+	/// Executes the command.
+	pub(crate) fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut cli::Cli, &crate::config_dir()?, crate::default_cache()?)
+	}
+
+	/// Walks through the setup steps, persisting the resulting settings.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	/// * `dir` - The configuration directory within which the settings are persisted.
+	/// * `default_cache` - The default location of the cache.
+	fn run(
+		&self,
+		cli: &mut impl cli::traits::Cli,
+		dir: &Path,
+		default_cache: PathBuf,
+	) -> anyhow::Result<()> {
+		cli.intro("Set up pop")?;
+		let mut bootstrap = Bootstrap::new(dir, default_cache)?;
+		while let Some(step) = bootstrap.step() {
+			let answer = match &step.input {
+				Input::Confirm { .. } => Answer::Confirm(cli.confirm(&step.prompt).interact()?),
+				// Answers specified via arguments are not prompted for.
+				Input::Text { default } => Answer::Text(match &self.cache {
+					Some(cache) => cache.display().to_string(),
+					None => cli.input(&step.prompt).default_input(default).interact()?,
+				}),
+				Input::Select { options, default } => Answer::Text(match &self.relay_chain {
+					Some(relay_chain) => relay_chain.clone(),
+					None => {
+						let mut prompt = cli.select(&step.prompt).initial_value(default.clone());
+						for (value, description) in options {
+							prompt = prompt.item(value.clone(), value, description);
+						}
+						prompt.interact()?
+					},
+				}),
+				Input::Report(checks) => {
+					for check in checks {
+						match &check.version {
+							Some(version) => cli.success(format!("{}: {version}", check.name))?,
+							None => cli.warning(format!(
+								"{} is not installed: {}",
+								check.name, check.hint
+							))?,
+						}
+					}
+					Answer::Acknowledge
+				},
+			};
+			if let Err(e) = bootstrap.answer(answer) {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			}
+		}
+		let settings = bootstrap.finish()?;
+		cli.info(format!(
+			"Usage metrics: {}\nCache: {}\nDefault relay chain: {}",
+			if settings.telemetry { "shared" } else { "not shared" },
+			settings.cache.display(),
+			settings.relay_chain
+		))?;
+		cli.outro(format!("✅ Settings saved within {}", dir.display()))?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use pop_common::bootstrap::Settings;
+
+	#[test]
+	fn run_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let config = temp_dir.path().join("config");
+		let cache = temp_dir.path().join("cache");
+		let mut cli = MockCli::new()
+			.expect_intro("Set up pop")
+			.expect_confirm(
+				"Would you like to share anonymous usage metrics to help improve pop?",
+				false,
+			)
+			.expect_info(format!(
+				"Usage metrics: not shared\nCache: {}\nDefault relay chain: westend-local",
+				cache.display()
+			))
+			.expect_outro(format!("✅ Settings saved within {}", config.display()));
+		SetupCommand { cache: None, relay_chain: Some("westend-local".into()) }.run(
+			&mut cli,
+			&config,
+			cache.clone(),
+		)?;
+		cli.verify()?;
+		assert_eq!(
+			Settings::load(&config)?,
+			Some(Settings { telemetry: false, cache, relay_chain: "westend-local".into() })
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn run_prompts_for_unspecified_answers() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let config = temp_dir.path().join("config");
+		let cache = temp_dir.path().join("cache");
+		let mut cli = MockCli::new()
+			.expect_intro("Set up pop")
+			.expect_input(
+				"Where should downloaded binaries and artifacts be cached?",
+				cache.display().to_string(),
+			)
+			.expect_select(
+				"Which relay chain should be used by default for local networks?",
+				Some(vec![
+					("paseo-local".into(), "A local Paseo relay chain".into()),
+					("rococo-local".into(), "A local Rococo relay chain".into()),
+					("westend-local".into(), "A local Westend relay chain".into()),
+					("polkadot-local".into(), "A local Polkadot relay chain".into()),
+				]),
+				3,
+			)
+			.expect_outro(format!("✅ Settings saved within {}", config.display()));
+		SetupCommand { cache: None, relay_chain: None }.run(
+			&mut cli,
+			&config,
+			temp_dir.path().join("default"),
+		)?;
+		cli.verify()?;
+		assert_eq!(
+			Settings::load(&config)?,
+			Some(Settings { telemetry: false, cache, relay_chain: "polkadot-local".into() })
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn run_fails_with_unknown_relay_chain() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let config = temp_dir.path().join("config");
+		let mut cli = MockCli::new()
+			.expect_intro("Set up pop")
+			.expect_confirm(
+				"Would you like to share anonymous usage metrics to help improve pop?",
+				true,
+			)
+			.expect_outro_cancel(
+				"🚫 Configuration error: `kusama-local` is not one of: paseo-local, rococo-local, westend-local, polkadot-local",
+			);
+		SetupCommand { cache: None, relay_chain: Some("kusama-local".into()) }.run(
+			&mut cli,
+			&config,
+			temp_dir.path().join("cache"),
+		)?;
+		cli.verify()?;
+		assert!(Settings::load(&config)?.is_none());
+		Ok(())
+	}
+}
@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::traits::*;
+use anyhow::Result;
+use clap::Args;
+use pop_common::stats::{sparkline, Filter, Record, Stats, Summary};
+use std::{
+	path::PathBuf,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The number of most recent runs shown within a trendline.
+const TREND_LENGTH: usize = 20;
+
+#[derive(Args)]
+pub(crate) struct StatsArgs {
+	/// Only show statistics for the specified operation (e.g. "build", "source" or "launch").
+	#[arg(short, long)]
+	pub(crate) operation: Option<String>,
+	/// Only show statistics for the project at the specified path.
+	#[arg(long)]
+	pub(crate) path: Option<PathBuf>,
+	/// Only show statistics for operations performed within the specified number of days.
+	#[arg(short, long)]
+	pub(crate) days: Option<u64>,
+}
+
+/// Shows statistics about the durations of operations, as recorded locally.
+pub(crate) struct StatsCommand<'a, CLI: Cli> {
+	/// The cli to be used.
+	pub(crate) cli: &'a mut CLI,
+	/// The cache within which statistics are recorded.
+	pub(crate) cache: PathBuf,
+	/// The arguments of the command.
+	pub(crate) args: StatsArgs,
+}
+
+impl<'a, CLI: Cli> StatsCommand<'a, CLI> {
+	/// Executes the command.
+	pub(crate) fn execute(self) -> Result<()> {
+		self.cli.intro("Operation statistics")?;
+		let stats = Stats::new(&self.cache);
+		let since = self.args.days.map(|days| {
+			let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+			now.saturating_sub(days * 24 * 60 * 60)
+		});
+		let filter = Filter {
+			operation: self.args.operation.clone(),
+			project: self.args.path.clone(),
+			since,
+		};
+		let summaries = stats.query(&filter)?;
+		if summaries.is_empty() {
+			self.cli.outro("ℹ️ No operations have been recorded yet.")?;
+			return Ok(());
+		}
+		for summary in &summaries {
+			self.cli.info(describe(summary))?;
+		}
+
+		// Show how the duration of each operation is trending for the project.
+		let project = self.args.path.clone().unwrap_or_else(|| PathBuf::from("./"));
+		for summary in &summaries {
+			let trend = stats.trend(&project, &summary.operation, TREND_LENGTH)?;
+			if trend.len() > 1 {
+				self.cli.info(format!(
+					"📈 {} trend for this project (last {} runs): {} {}",
+					summary.operation,
+					trend.len(),
+					sparkline(&trend),
+					duration(*trend.last().expect("trend is not empty")),
+				))?;
+			}
+		}
+		self.cli.outro("Statistics are recorded locally and never reported.")?;
+		Ok(())
+	}
+}
+
+/// Records an operation, ignoring any failure so that recording never affects the operation
+/// itself.
+///
+/// # Arguments
+/// * `stats` - The records to which the operation is appended.
+/// * `record` - The record of the operation.
+pub(crate) fn record(stats: &Stats, record: Record) {
+	let _ = stats.record(&record);
+}
+
+/// A description of the durations of an operation.
+///
+/// # Arguments
+/// * `summary` - The summary of the operation.
+fn describe(summary: &Summary) -> String {
+	let mut description = format!(
+		"⏱️ {}: {} run(s), {} failed - mean {}, median {}, fastest {}, slowest {}",
+		summary.operation,
+		summary.count,
+		summary.failures,
+		duration(summary.mean_ms),
+		duration(summary.median_ms),
+		duration(summary.min_ms),
+		duration(summary.max_ms),
+	);
+	if let (Some(cached), Some(uncached)) = (summary.cached_mean_ms, summary.uncached_mean_ms) {
+		description.push_str(&format!(
+			"\n   with cache {}, without cache {}",
+			duration(cached),
+			duration(uncached)
+		));
+	}
+	description
+}
+
+/// A human-readable duration.
+///
+/// # Arguments
+/// * `ms` - The duration, in milliseconds.
+fn duration(ms: u64) -> String {
+	match ms {
+		ms if ms < 1_000 => format!("{ms}ms"),
+		ms if ms < 60_000 => format!("{:.1}s", ms as f64 / 1_000.0),
+		ms => format!("{}m {}s", ms / 60_000, (ms % 60_000) / 1_000),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	fn args() -> StatsArgs {
+		StatsArgs { operation: None, path: None, days: None }
+	}
+
+	#[test]
+	fn stats_without_records_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut cli = MockCli::new()
+			.expect_intro("Operation statistics")
+			.expect_outro("ℹ️ No operations have been recorded yet.");
+		StatsCommand { cli: &mut cli, cache: temp_dir.path().to_path_buf(), args: args() }
+			.execute()?;
+		cli.verify()
+	}
+
+	#[test]
+	fn stats_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("project");
+		std::fs::create_dir(&project)?;
+		let stats = Stats::new(temp_dir.path());
+		for (duration_ms, cached) in [(125_000, false), (8_000, true), (12_000, true)] {
+			stats.record(&Record {
+				operation: "build".to_string(),
+				subject: None,
+				project: Some(project.canonicalize()?),
+				timestamp: 1,
+				duration_ms,
+				success: true,
+				cached,
+			})?;
+		}
+		let mut cli = MockCli::new()
+			.expect_intro("Operation statistics")
+			.expect_info("⏱️ build: 3 run(s), 0 failed - mean 48.3s, median 12.0s, fastest 8.0s, slowest 2m 5s\n   with cache 10.0s, without cache 2m 5s")
+			.expect_info("📈 build trend for this project (last 3 runs): █▁▁ 12.0s")
+			.expect_outro("Statistics are recorded locally and never reported.");
+		let args = StatsArgs { path: Some(project), ..args() };
+		StatsCommand { cli: &mut cli, cache: temp_dir.path().to_path_buf(), args }.execute()?;
+		cli.verify()
+	}
+
+	#[test]
+	fn duration_works() {
+		assert_eq!(duration(850), "850ms");
+		assert_eq!(duration(12_340), "12.3s");
+		assert_eq!(duration(185_000), "3m 5s");
+	}
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{audit_features, AUDITED_FEATURES};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct TestFeaturesCommand {
+	/// Path to the workspace.
+	#[arg(short, long)]
+	path: Option<PathBuf>,
+	/// Fix any misconfigured features within the manifests.
+	#[arg(long)]
+	fix: bool,
+}
+
+impl TestFeaturesCommand {
+	/// Executes the command.
+	pub(crate) fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli)
+	}
+
+	/// Audits the propagation of features across the workspace, fixing any issues if requested.
+	fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro(format!("Auditing the {} features", AUDITED_FEATURES.join(", ")))?;
+		let path = self.path.clone().unwrap_or_else(|| PathBuf::from("./"));
+		let issues = match audit_features(&path) {
+			Ok(issues) => issues,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		if issues.is_empty() {
+			cli.outro("✅ All features are propagated.")?;
+			return Ok(());
+		}
+		for issue in &issues {
+			cli.warning(issue)?;
+		}
+		if !self.fix {
+			cli.outro_cancel(format!(
+				"🚫 Found {} misconfigured feature(s). Run again with `--fix` to fix them.",
+				issues.len()
+			))?;
+			return Ok(());
+		}
+		for issue in &issues {
+			issue.fix()?;
+		}
+		cli.outro(format!("✅ Fixed {} misconfigured feature(s).", issues.len()))?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use std::fs::{create_dir_all, read_to_string, write};
+
+	#[test]
+	fn fix_features_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().to_path_buf();
+		for (name, manifest) in [
+			("pallet", "[package]\nname = \"pallet\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[features]\ndefault = [\"std\"]\nstd = []\n"),
+			("runtime", "[package]\nname = \"runtime\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\npallet = { path = \"../pallet\", default-features = false }\n\n[features]\ndefault = [\"std\"]\nstd = []\n"),
+		] {
+			create_dir_all(path.join(name).join("src"))?;
+			write(path.join(name).join("Cargo.toml"), manifest)?;
+			write(path.join(name).join("src/lib.rs"), "")?;
+		}
+		write(path.join("Cargo.toml"), "[workspace]\nmembers = [\"pallet\", \"runtime\"]\n")?;
+		let manifest = path.join("runtime/Cargo.toml").canonicalize()?;
+		let intro = "Auditing the std, runtime-benchmarks, try-runtime features";
+
+		let command = TestFeaturesCommand { path: Some(path.clone()), fix: false };
+		let mut cli = MockCli::new()
+			.expect_intro(intro)
+			.expect_warning(format!(
+				"{}:11: the `std` feature of `runtime` does not enable \"pallet/std\"",
+				manifest.display()
+			))
+			.expect_outro_cancel(
+				"🚫 Found 1 misconfigured feature(s). Run again with `--fix` to fix them.",
+			);
+		command.run(&mut cli)?;
+		cli.verify()?;
+
+		let command = TestFeaturesCommand { path: Some(path.clone()), fix: true };
+		let mut cli = MockCli::new()
+			.expect_intro(intro)
+			.expect_outro("✅ Fixed 1 misconfigured feature(s).");
+		command.run(&mut cli)?;
+		cli.verify()?;
+		assert!(read_to_string(&manifest)?.contains("std = [\"pallet/std\"]"));
+
+		let mut cli = MockCli::new()
+			.expect_intro(intro)
+			.expect_outro("✅ All features are propagated.");
+		command.run(&mut cli)?;
+		cli.verify()
+	}
+}
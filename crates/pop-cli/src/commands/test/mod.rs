@@ -4,6 +4,10 @@ use clap::{Args, Subcommand};
 
 #[cfg(feature = "contract")]
 pub mod contract;
+#[cfg(feature = "parachain")]
+pub mod features;
+#[cfg(feature = "parachain")]
+pub mod runtime;
 
 /// Arguments for testing.
 #[derive(Args)]
@@ -13,11 +17,22 @@ pub(crate) struct TestArgs {
 	pub command: Command,
 }
 
-/// Test a smart contract.
+/// Test a smart contract, check a runtime against live state, or audit the propagation of
+/// features.
 #[derive(Subcommand)]
 pub(crate) enum Command {
 	/// Test a smart contract
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
 	Contract(contract::TestContractCommand),
+	/// Check a runtime's migrations and `try_state` hooks against state scraped from a live
+	/// endpoint.
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "r")]
+	Runtime(runtime::TestRuntimeCommand),
+	/// Audit the propagation of the `std`, `runtime-benchmarks` and `try-runtime` features across
+	/// a workspace.
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "f")]
+	Features(features::TestFeaturesCommand),
 }
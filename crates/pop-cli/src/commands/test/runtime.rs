@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::{traits::Cli as _, Cli},
+	style::style,
+};
+use clap::Args;
+use pop_parachains::{
+	try_runtime_binary, try_runtime_live, LiveState, SnapshotKind, Snapshots, TryRuntimeCheck,
+};
+use std::{path::PathBuf, time::Duration};
+
+#[derive(Args)]
+pub(crate) struct TestRuntimeCommand {
+	/// Path to the runtime, built with the `try-runtime` feature enabled.
+	#[arg(short, long)]
+	runtime: PathBuf,
+	/// The endpoint of a node from which live state is scraped.
+	#[arg(short, long)]
+	uri: String,
+	/// The hash of the block at which state is scraped [default: latest finalized block].
+	#[arg(long)]
+	at: Option<String>,
+	/// Only scrape the state of the specified pallets.
+	#[arg(long = "pallet")]
+	pallets: Vec<String>,
+	/// Produce the specified number of blocks on top of the state, checking the `try_state` hooks,
+	/// rather than executing the runtime upgrade.
+	#[arg(long)]
+	fast_forward: Option<u32>,
+	/// The age, in minutes, after which a cached snapshot of the latest state is scraped again.
+	#[arg(long, default_value_t = 60)]
+	max_age: u64,
+	/// Path to the try-runtime binary [default: `try-runtime` on the `PATH`].
+	#[arg(long)]
+	binary: Option<PathBuf>,
+}
+
+impl TestRuntimeCommand {
+	/// Executes the command.
+	pub(crate) fn execute(self) -> anyhow::Result<()> {
+		Cli.intro("Checking runtime against live state")?;
+		let binary = match try_runtime_binary(self.binary.as_deref()) {
+			Ok(binary) => binary,
+			Err(e) => {
+				Cli.outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			},
+		};
+		let check = match self.fast_forward {
+			Some(blocks) => TryRuntimeCheck::FastForward(blocks),
+			None => TryRuntimeCheck::OnRuntimeUpgrade,
+		};
+		let state = LiveState { uri: self.uri, at: self.at, pallets: self.pallets };
+		let snapshots = Snapshots::new(&crate::cache()?, Duration::from_secs(self.max_age * 60));
+		if let Some(snapshot) = snapshots.get(SnapshotKind::RemoteExternalities, &state) {
+			Cli.info(format!("Reusing cached snapshot {}", style(snapshot.path.display()).dim()))?;
+		}
+		match try_runtime_live(&binary, &self.runtime, &check, &state, &snapshots) {
+			Ok(_) => Cli.outro("✅ The runtime passed all checks against live state.")?,
+			Err(e) => Cli.outro_cancel(format!("🚫 The runtime failed its checks: {e}"))?,
+		}
+		Ok(())
+	}
+}
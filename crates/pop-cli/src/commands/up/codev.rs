@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::contract::has_contract_been_built;
+use pop_common::{manifest::from_path, Resolution};
+use pop_contracts::{
+	build_smart_contract, dry_run_gas_estimate_instantiate, ensure_deployable, find_contracts,
+	instantiate_smart_contract, runtime_contracts_pallet, set_up_deployment, AddressBook,
+	ContractsPallet, UpOpts, Verbosity, ADDRESS_BOOK,
+};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// The account deploying the contracts of the workspace.
+const DEPLOYER: &str = "//Alice";
+
+/// The outcome of deploying the contracts of a workspace to its parachain.
+pub(crate) struct Deployment {
+	/// The addresses of the contracts deployed.
+	pub(crate) address_book: AddressBook,
+	/// The path of the address book.
+	pub(crate) path: PathBuf,
+	/// The contracts which could not be deployed, alongside the error.
+	pub(crate) failed: Vec<(String, String)>,
+}
+
+/// Checks that the runtime of the parachain project in the workspace can host the contracts of
+/// the workspace, before the network is launched. Runtimes only including `pallet-revive` require
+/// the contracts to have been compiled for PolkaVM.
+///
+/// # Arguments
+/// * `workspace` - The root of the workspace.
+pub(crate) fn check_runtime(workspace: &Path) -> anyhow::Result<ContractsPallet> {
+	let runtime = workspace.join("runtime");
+	let pallet = runtime_contracts_pallet(&runtime)?.ok_or_else(|| {
+		anyhow::anyhow!(
+			"the runtime at {} does not include `pallet-contracts` or `pallet-revive`, so cannot host contracts",
+			runtime.display()
+		)
+	})?;
+	for contract in find_contracts(workspace) {
+		ensure_deployable(pallet, &contract)?;
+	}
+	Ok(pallet)
+}
+
+/// Builds the contracts of a workspace, where not yet built, and deploys each to the parachain
+/// by calling its `new` constructor, recording their addresses within the address book of the
+/// workspace (see [`ADDRESS_BOOK`]).
+///
+/// # Arguments
+/// * `workspace` - The root of the workspace.
+/// * `url` - The endpoint of a collator of the parachain.
+pub(crate) async fn deploy_contracts(workspace: &Path, url: &Url) -> anyhow::Result<Deployment> {
+	let mut address_book = AddressBook::new(url.as_str());
+	let mut failed = vec![];
+	for contract in find_contracts(workspace) {
+		let name = from_path(Some(&contract))?.package().name().to_string();
+		match deploy(&contract, url).await {
+			Ok(address) => address_book.insert(&name, &address),
+			Err(e) => failed.push((name, e.to_string())),
+		}
+	}
+	let path = workspace.join(ADDRESS_BOOK);
+	address_book.save(&path)?;
+	Ok(Deployment { address_book, path, failed })
+}
+
+/// Builds a contract, where not yet built, and instantiates it, returning its address.
+async fn deploy(contract: &Path, url: &Url) -> anyhow::Result<String> {
+	if !has_contract_been_built(Some(contract)) {
+		build_smart_contract(Some(contract), true, Verbosity::Quiet, &Resolution::default())?;
+	}
+	let instantiate_exec = set_up_deployment(UpOpts {
+		path: Some(contract.to_path_buf()),
+		constructor: "new".into(),
+		args: vec![],
+		value: "0".into(),
+		gas_limit: None,
+		proof_size: None,
+		salt: None,
+		url: url.clone(),
+		suri: DEPLOYER.into(),
+	})
+	.await?;
+	let weight_limit = dry_run_gas_estimate_instantiate(&instantiate_exec).await?;
+	Ok(instantiate_smart_contract(instantiate_exec, weight_limit).await?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::{create_dir_all, write};
+
+	#[test]
+	fn check_runtime_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let workspace = temp_dir.path();
+		let runtime = workspace.join("runtime");
+		create_dir_all(runtime.join("src"))?;
+		write(runtime.join("src/lib.rs"), "")?;
+		let manifest = |dependencies: &str| {
+			write(
+				runtime.join("Cargo.toml"),
+				format!("[package]\nname = \"runtime\"\nversion = \"0.1.0\"\n\n[dependencies]\n{dependencies}"),
+			)
+		};
+		manifest("")?;
+		assert!(check_runtime(workspace)
+			.unwrap_err()
+			.to_string()
+			.contains("cannot host contracts"));
+		manifest("pallet-revive = \"0.1.0\"\n")?;
+		assert_eq!(check_runtime(workspace)?, ContractsPallet::Revive);
+		manifest("pallet-contracts = \"38.0.0\"\n")?;
+		assert_eq!(check_runtime(workspace)?, ContractsPallet::Contracts);
+		// Contracts compiled to Wasm cannot be deployed via `pallet-revive`.
+		let contract = workspace.join("flipper");
+		create_dir_all(contract.join("src"))?;
+		write(contract.join("src/lib.rs"), "")?;
+		write(
+			contract.join("Cargo.toml"),
+			"[package]\nname = \"flipper\"\nversion = \"0.1.0\"\n\n[dependencies]\nink = \"5.0.0\"\n",
+		)?;
+		assert_eq!(check_runtime(workspace)?, ContractsPallet::Contracts);
+		manifest("pallet-revive = \"0.1.0\"\n")?;
+		assert!(check_runtime(workspace)
+			.unwrap_err()
+			.to_string()
+			.contains("has not been built for PolkaVM"));
+		Ok(())
+	}
+}
@@ -2,13 +2,18 @@
 
 use crate::{
 	cli::{traits::Cli as _, Cli},
-	common::contracts::check_contracts_node_and_prompt,
+	common::{contracts::check_contracts_node_and_prompt, notify::notify},
 	style::style,
 };
 use clap::Args;
 use cliclack::{confirm, log, log::error, spinner};
 use console::{Emoji, Style};
-use pop_common::manifest::from_path;
+use pop_common::{
+	hooks::{HookContext, Hooks, Stage},
+	manifest::from_path,
+	notify::Event,
+	Resolution,
+};
 use pop_contracts::{
 	build_smart_contract, dry_run_gas_estimate_instantiate, dry_run_upload,
 	instantiate_smart_contract, is_chain_alive, parse_hex_bytes, run_contracts_node,
@@ -35,7 +40,8 @@ pub struct UpContractCommand {
 	/// The name of the contract constructor to call.
 	#[clap(name = "constructor", long, default_value = "new")]
 	constructor: String,
-	/// The constructor arguments, encoded as strings.
+	/// The constructor arguments, in a JSON5 or Rust struct-literal style (e.g. `true`,
+	/// `{ to: "5GrwvaEF...", amount: 10 }` or `Some([1, 2])`).
 	#[clap(long, num_args = 0..)]
 	args: Vec<String>,
 	/// Transfers an initial balance to the instantiated contract.
@@ -87,7 +93,12 @@ impl UpContractCommand {
 			Cli.warning("NOTE: contract has not yet been built.")?;
 			let spinner = spinner();
 			spinner.start("Building contract in RELEASE mode...");
-			let result = match build_smart_contract(self.path.as_deref(), true, Verbosity::Quiet) {
+			let result = match build_smart_contract(
+				self.path.as_deref(),
+				true,
+				Verbosity::Quiet,
+				&Resolution::default(),
+			) {
 				Ok(result) => result,
 				Err(e) => {
 					Cli.outro_cancel(format!("🚫 An error occurred building your contract: {e}\nUse `pop build` to retry with build output."))?;
@@ -222,14 +233,47 @@ impl UpContractCommand {
 
 		// Finally upload and instantiate.
 		if !self.dry_run {
+			let context = HookContext::default().value("url", self.url.as_str());
+			let hooks = match self.hooks().and_then(|hooks| {
+				hooks.run(Stage::PreDeploy, &context)?;
+				Ok(hooks)
+			}) {
+				Ok(hooks) => hooks,
+				Err(e) => {
+					error(format!("{e}"))?;
+					Self::terminate_node(process)?;
+					Cli.outro_cancel(FAILED)?;
+					return Ok(());
+				},
+			};
 			let spinner = spinner();
 			spinner.start("Uploading and instantiating the contract...");
 			let contract_address =
-				instantiate_smart_contract(instantiate_exec, weight_limit).await?;
+				match instantiate_smart_contract(instantiate_exec, weight_limit).await {
+					Ok(address) => address,
+					Err(e) => {
+						spinner.error(format!("An error occurred instantiating the contract: {e}"));
+						Self::terminate_node(process)?;
+						Cli.outro_cancel(FAILED)?;
+						return Ok(());
+					},
+				};
 			spinner.stop(format!(
 				"Contract deployed and instantiated: The Contract Address is {:?}",
 				contract_address
 			));
+			let event = Event::ContractDeployed {
+				address: contract_address.clone(),
+				url: self.url.to_string(),
+			};
+			notify(self.path.as_deref().unwrap_or_else(|| Path::new("./")), &event).await?;
+			let context = context.value("contract_address", &contract_address);
+			if let Err(e) = hooks.run(Stage::PostDeploy, &context) {
+				error(format!("{e}"))?;
+				Self::terminate_node(process)?;
+				Cli.outro_cancel(FAILED)?;
+				return Ok(());
+			}
 			Self::terminate_node(process)?;
 			Cli.outro(COMPLETE)?;
 		}
@@ -257,6 +301,18 @@ impl UpContractCommand {
 				},
 			};
 		} else {
+			// Hook failures are reported here, leaving the caller to terminate any local node.
+			let context = HookContext::default().value("url", self.url.as_str());
+			let hooks = match self.hooks().and_then(|hooks| {
+				hooks.run(Stage::PreDeploy, &context)?;
+				Ok(hooks)
+			}) {
+				Ok(hooks) => hooks,
+				Err(e) => {
+					error(format!("{e}"))?;
+					return Err(e);
+				},
+			};
 			let spinner = spinner();
 			spinner.start("Uploading your contract...");
 			let code_hash = match upload_smart_contract(&upload_exec).await {
@@ -268,10 +324,20 @@ impl UpContractCommand {
 			};
 			spinner.stop(format!("Contract uploaded: The code hash is {:?}", code_hash));
 			log::warning("NOTE: The contract has not been instantiated.")?;
+			let context = context.value("code_hash", format!("{code_hash:?}"));
+			if let Err(e) = hooks.run(Stage::PostDeploy, &context) {
+				error(format!("{e}"))?;
+				return Err(e.into());
+			}
 		}
 		Ok(())
 	}
 
+	/// Loads the lifecycle hooks configured for the contract project.
+	fn hooks(&self) -> anyhow::Result<Hooks> {
+		Ok(Hooks::load(self.path.as_deref().unwrap_or_else(|| Path::new("./")))?)
+	}
+
 	/// Handles the optional termination of a local running node.
 	fn terminate_node(process: Option<(Child, NamedTempFile)>) -> anyhow::Result<()> {
 		// Prompt to close any launched node
@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use clap::Args;
+use pop_parachains::{Captcha, Faucet, FaucetConfig};
+use std::{net::SocketAddr, time::Duration};
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct FaucetCommand {
+	/// Websocket endpoint of a node of the chain.
+	#[arg(long, default_value = "ws://localhost:9944")]
+	pub(crate) url: Url,
+	/// Secret key URI of the account from which funds are dripped.
+	#[arg(long, default_value = "//Alice")]
+	pub(crate) suri: String,
+	/// The amount dripped per request, in the smallest unit of the native currency.
+	#[arg(long)]
+	pub(crate) amount: u128,
+	/// The port on which the faucet listens.
+	#[arg(long, default_value_t = 8088)]
+	pub(crate) port: u16,
+	/// Listen on all interfaces, rather than only locally.
+	#[arg(long)]
+	pub(crate) public: bool,
+	/// The number of seconds for which both an account and a client must wait between drips.
+	#[arg(long, default_value_t = 3600)]
+	pub(crate) cooldown: u64,
+	/// The secret key of a captcha site, requiring clients to submit a captcha response with
+	/// each request.
+	#[arg(long)]
+	pub(crate) captcha_secret: Option<String>,
+	/// The `siteverify` endpoint verifying captcha responses (e.g. of reCAPTCHA or Cloudflare
+	/// Turnstile) [default: hCaptcha].
+	#[arg(long, requires = "captcha_secret")]
+	pub(crate) captcha_url: Option<Url>,
+	/// The number of trusted reverse proxies the faucet is served behind, from whose
+	/// `X-Forwarded-For` header the address of clients is taken.
+	#[arg(long, default_value = "0")]
+	pub(crate) trusted_proxies: usize,
+}
+
+impl FaucetCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Serves a faucet dripping funds on the chain, until interrupted.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Serve a faucet")?;
+		if self.amount == 0 {
+			cli.outro_cancel("🚫 The amount dripped must be greater than zero.")?;
+			return Ok(());
+		}
+		let faucet = match Faucet::start(self.config()).await {
+			Ok(faucet) => faucet,
+			Err(e) => {
+				cli.outro_cancel(format!("🚫 Unable to start the faucet: {e}"))?;
+				return Ok(());
+			},
+		};
+		cli.info(format!(
+			"Dripping {} per request from {} on {}, at most once every {}s per account and client.",
+			self.amount, self.suri, self.url, self.cooldown
+		))?;
+		cli.success(format!(
+			"Faucet served at {} (request funds with `POST /drip` and a JSON body of `{{\"address\": ...}}`). Press Ctrl+C to stop.",
+			faucet.url()
+		))?;
+		tokio::signal::ctrl_c().await?;
+		faucet.stop();
+		cli.outro("Done")?;
+		Ok(())
+	}
+
+	/// The configuration of the faucet.
+	fn config(&self) -> FaucetConfig {
+		let mut config = FaucetConfig::new(self.url.clone(), self.suri.clone(), self.amount);
+		let ip = match self.public {
+			true => [0, 0, 0, 0],
+			false => [127, 0, 0, 1],
+		};
+		config.listen = SocketAddr::from((ip, self.port));
+		config.cooldown = Duration::from_secs(self.cooldown);
+		config.captcha = self.captcha_secret.clone().map(|secret| match &self.captcha_url {
+			Some(verify_url) => Captcha { verify_url: verify_url.clone(), secret },
+			None => Captcha::hcaptcha(secret),
+		});
+		config.trusted_proxies = self.trusted_proxies;
+		config
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	fn command() -> anyhow::Result<FaucetCommand> {
+		Ok(FaucetCommand {
+			url: "ws://127.0.0.1:1".parse()?,
+			suri: "//Alice".into(),
+			amount: 10,
+			port: 0,
+			public: false,
+			cooldown: 60,
+			captcha_secret: Some("secret".into()),
+			captcha_url: None,
+			trusted_proxies: 0,
+		})
+	}
+
+	#[test]
+	fn config_works() -> anyhow::Result<()> {
+		let config = command()?.config();
+		assert_eq!(config.listen, SocketAddr::from(([127, 0, 0, 1], 0)));
+		assert_eq!(config.cooldown, Duration::from_secs(60));
+		assert_eq!(config.captcha, Some(Captcha::hcaptcha("secret".into())));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn run_fails_without_amount() -> anyhow::Result<()> {
+		let mut cli = MockCli::new()
+			.expect_intro("Serve a faucet")
+			.expect_outro_cancel("🚫 The amount dripped must be greater than zero.");
+		FaucetCommand { amount: 0, ..command()? }.run(&mut cli).await?;
+		cli.verify()
+	}
+}
@@ -2,17 +2,46 @@
 
 use clap::{Args, Subcommand};
 
+#[cfg(all(feature = "parachain", feature = "contract"))]
+mod codev;
 #[cfg(feature = "contract")]
 mod contract;
 #[cfg(feature = "parachain")]
-mod parachain;
+mod faucet;
+#[cfg(feature = "parachain")]
+mod monitor;
+#[cfg(feature = "parachain")]
+pub(crate) mod parachain;
+#[cfg(feature = "parachain")]
+mod reserve;
+#[cfg(feature = "parachain")]
+mod upgrade;
+#[cfg(feature = "parachain")]
+mod watch;
 
-/// Arguments for launching or deploying.
+/// Arguments for launching or deploying. A subcommand is required unless reserving a parachain
+/// identifier, with help displayed when neither is specified.
 #[derive(Args)]
-#[command(args_conflicts_with_subcommands = true)]
+#[command(
+	args_conflicts_with_subcommands = true,
+	arg_required_else_help = true,
+	subcommand_negates_reqs = true
+)]
 pub(crate) struct UpArgs {
 	#[command(subcommand)]
-	pub(crate) command: Command,
+	pub(crate) command: Option<Command>,
+	/// Reserve the next free parachain identifier on the relay chain, paying its deposit.
+	#[cfg(feature = "parachain")]
+	#[arg(long, required = true)]
+	pub(crate) reserve_id: bool,
+	/// Websocket endpoint of a node of the relay chain.
+	#[cfg(feature = "parachain")]
+	#[arg(long, default_value = "ws://localhost:9944", requires = "reserve_id")]
+	pub(crate) relay_url: url::Url,
+	/// Secret key URI of the account signing the transactions submitted to the relay chain.
+	#[cfg(feature = "parachain")]
+	#[arg(long, default_value = "//Alice", requires = "reserve_id")]
+	pub(crate) suri: String,
 }
 
 /// Launch a local network or deploy a smart contract.
@@ -21,9 +50,65 @@ pub(crate) enum Command {
 	#[cfg(feature = "parachain")]
 	/// Launch a local network.
 	#[clap(alias = "p")]
-	Parachain(parachain::ZombienetCommand),
+	Parachain(Box<parachain::ZombienetCommand>),
+	#[cfg(feature = "parachain")]
+	/// Serve a faucet dripping funds on a chain (e.g. a team testnet), rate limited per account
+	/// and client.
+	#[clap(alias = "f")]
+	Faucet(faucet::FaucetCommand),
+	#[cfg(feature = "parachain")]
+	/// Monitor chains for reorgs deeper than a threshold and finality stalls, posting alerts to
+	/// the webhooks configured for the project.
+	#[clap(alias = "m")]
+	Monitor(monitor::MonitorCommand),
+	#[cfg(feature = "parachain")]
+	/// Upgrade the runtime of a live parachain, authorizing and then enacting the upgrade.
+	RuntimeUpgrade(upgrade::RuntimeUpgradeCommand),
 	#[cfg(feature = "contract")]
 	/// Deploy a smart contract.
 	#[clap(alias = "c")]
 	Contract(contract::UpContractCommand),
 }
+
+impl Command {
+	/// Executes the flow requested by the arguments, in the absence of a subcommand: reserving a
+	/// parachain identifier, as clap requires either `--reserve-id` or a subcommand.
+	pub(crate) async fn execute(args: UpArgs) -> anyhow::Result<()> {
+		#[cfg(feature = "parachain")]
+		reserve::ReserveIdCommand { relay_url: args.relay_url, suri: args.suri }
+			.execute()
+			.await?;
+		#[cfg(not(feature = "parachain"))]
+		let _ = args;
+		Ok(())
+	}
+}
+
+#[cfg(all(test, feature = "parachain"))]
+mod tests {
+	use super::*;
+	use crate::{Cli, Command::Up};
+	use clap::{error::ErrorKind, Parser};
+
+	#[test]
+	fn up_args_require_subcommand_or_reserve_id() {
+		let error = Cli::try_parse_from(["pop", "up"]).err().expect("help is displayed");
+		assert_eq!(error.kind(), ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand);
+		assert!(Cli::try_parse_from(["pop", "up", "--suri", "//Bob"]).is_err());
+
+		let Ok(Cli { command: Up(args), .. }) =
+			Cli::try_parse_from(["pop", "up", "--reserve-id", "--suri", "//Bob"])
+		else {
+			panic!("unable to parse command")
+		};
+		assert!(args.command.is_none() && args.reserve_id);
+		assert_eq!(args.suri, "//Bob");
+
+		let Ok(Cli { command: Up(args), .. }) =
+			Cli::try_parse_from(["pop", "up", "parachain", "-f", "network.toml"])
+		else {
+			panic!("unable to parse command")
+		};
+		assert!(matches!(args.command, Some(Command::Parachain(_))));
+	}
+}
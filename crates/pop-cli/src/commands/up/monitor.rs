@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::{self, Cli},
+	common::notify::notify,
+};
+use clap::Args;
+use pop_common::notify::Event;
+use pop_parachains::{ChainEvent, ChainMonitor, MonitorRules};
+use std::{
+	path::{Path, PathBuf},
+	time::Duration,
+};
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct MonitorCommand {
+	/// Websocket endpoints of the nodes to be monitored.
+	#[arg(long = "url", default_value = "ws://localhost:9944")]
+	pub(crate) urls: Vec<Url>,
+	/// Alert on reorgs retracting more than this number of best blocks.
+	#[arg(long, default_value_t = 1)]
+	pub(crate) reorg_depth: u32,
+	/// Alert once no block has been finalized for this number of seconds.
+	#[arg(long, default_value_t = 60)]
+	pub(crate) finality_stall: u64,
+	/// Directory path of the project whose configured webhooks are notified of alerts.
+	#[arg(short, long)]
+	pub(crate) path: Option<PathBuf>,
+}
+
+impl MonitorCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Monitors the endpoints for deep reorgs and finality stalls, until interrupted.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Monitor chains")?;
+		if self.finality_stall == 0 {
+			cli.outro_cancel("🚫 The finality stall threshold must be greater than zero.")?;
+			return Ok(());
+		}
+		let rules = MonitorRules {
+			reorg_depth: self.reorg_depth,
+			finality_stall: Duration::from_secs(self.finality_stall),
+		};
+		let urls: Vec<_> = self.urls.iter().map(|url| url.to_string()).collect();
+		cli.info(format!(
+			"Alerting on reorgs deeper than {} blocks and finality stalls beyond {}s.",
+			self.reorg_depth, self.finality_stall
+		))?;
+		cli.success(format!("Monitoring {}. Press Ctrl+C to stop.", urls.join(", ")))?;
+		let project = self.path.as_deref().unwrap_or_else(|| Path::new("./"));
+		let mut monitor = ChainMonitor::start(urls, rules);
+		loop {
+			let event = tokio::select! {
+				event = monitor.next() => event,
+				_ = tokio::signal::ctrl_c() => break,
+			};
+			let Some(event) = event else {
+				cli.warning("⚠️ No endpoints remain monitored.")?;
+				break;
+			};
+			match event.is_alert() {
+				true => cli.warning(format!("🚨 {event}"))?,
+				false => cli.info(event.to_string())?,
+			}
+			if let Some(event) = notification(&event) {
+				notify(project, &event).await?;
+			}
+		}
+		monitor.stop();
+		cli.outro("Done")?;
+		Ok(())
+	}
+}
+
+/// The notification posted to webhooks for an event, if it is an alert.
+///
+/// # Arguments
+/// * `event` - The event observed.
+fn notification(event: &ChainEvent) -> Option<Event> {
+	match event {
+		ChainEvent::Reorg { url, depth, block, .. } =>
+			Some(Event::ReorgDetected { url: url.clone(), depth: *depth, block: *block }),
+		ChainEvent::FinalityStalled { url, last_finalized, elapsed } =>
+			Some(Event::FinalityStalled {
+				url: url.clone(),
+				last_finalized: *last_finalized,
+				seconds: elapsed.as_secs(),
+			}),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[test]
+	fn notification_works() {
+		let url = "ws://localhost:9944".to_string();
+		let event = ChainEvent::Reorg {
+			url: url.clone(),
+			depth: 3,
+			common_ancestor: 3,
+			block: 5,
+			hash: Default::default(),
+		};
+		assert_eq!(
+			notification(&event),
+			Some(Event::ReorgDetected { url: url.clone(), depth: 3, block: 5 })
+		);
+		let event = ChainEvent::FinalityStalled {
+			url: url.clone(),
+			last_finalized: None,
+			elapsed: Duration::from_millis(90_500),
+		};
+		assert_eq!(
+			notification(&event),
+			Some(Event::FinalityStalled { url: url.clone(), last_finalized: None, seconds: 90 })
+		);
+		assert_eq!(notification(&ChainEvent::Reconnected { url }), None);
+	}
+
+	#[tokio::test]
+	async fn run_fails_without_finality_stall() -> anyhow::Result<()> {
+		let mut cli = MockCli::new()
+			.expect_intro("Monitor chains")
+			.expect_outro_cancel("🚫 The finality stall threshold must be greater than zero.");
+		MonitorCommand {
+			urls: vec!["ws://127.0.0.1:1".parse()?],
+			reorg_depth: 1,
+			finality_stall: 0,
+			path: None,
+		}
+		.run(&mut cli)
+		.await?;
+		cli.verify()
+	}
+}
@@ -1,21 +1,49 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::style::{style, Theme};
-use clap::Args;
-use cliclack::{
-	clear_screen, confirm, intro, log, multi_progress, outro, outro_cancel, set_theme, ProgressBar,
-	Theme as _, ThemeState,
+use super::watch::{watch, WatchMode};
+use crate::{
+	commands::{clean::reclaim, logs::print_logs, stats},
+	common::{
+		notify::notify,
+		output::{clear_screen, intro, log, outro, outro_cancel, Spinner},
+	},
+	style::{style, Theme},
 };
+use clap::Args;
+use cliclack::{confirm, multi_progress, set_theme, ProgressBar, Theme as _, ThemeState};
 use console::{Emoji, Style, Term};
 use duct::cmd;
-use pop_common::Status;
-use pop_parachains::{Error, IndexSet, NetworkNode, Zombienet};
-use std::{path::Path, time::Duration};
-use tokio::time::sleep;
+use pop_common::{
+	hooks::{HookContext, Hooks, Stage},
+	keyring::{derive_accounts, export_keyring, DEV_PHRASE},
+	notify::{Event, EventKind, Notifier, Webhook},
+	preflight::{self, Requirements},
+	progress::{ndjson_requested, NdjsonReporter},
+	proxy::{ProxyConfig, RpcProxy},
+	stats::{Stats, Timer},
+	Status,
+};
+use pop_parachains::{
+	open_hrmp_channels, set_up_client, EndpointReport, Error, IndexSet, LogOptions, NetworkLogs,
+	NetworkNode, NodeReadiness, ReadinessProbe, RpcSecurity, Topology, Watchdog, WatchdogPolicy,
+	Zombienet,
+};
+use std::{
+	env::temp_dir,
+	fs::write,
+	path::{Path, PathBuf},
+	time::Duration,
+};
+use url::Url;
+
+/// The interval between probes of the readiness of a network.
+const READINESS_INTERVAL: Duration = Duration::from_secs(2);
 
 #[derive(Args)]
 pub(crate) struct ZombienetCommand {
-	/// The Zombienet network configuration file to be used.
+	/// The Zombienet network configuration file to be used. Parachain commands and chain
+	/// specifications may reference published artifacts by URL (e.g. the assets of a release),
+	/// launching the network without a local project.
 	#[arg(short, long)]
 	file: String,
 	/// The version of the binary to be used for the relay chain, as per the release tag (e.g.
@@ -39,18 +67,146 @@ pub(crate) struct ZombienetCommand {
 	/// A specific binary name can also be optionally specified via query string parameter (e.g. 'https://github.com/org/repository?binaryname#ref'), defaulting to the name of the repository when not specified.
 	#[arg(short, long)]
 	parachain: Option<Vec<String>>,
-	/// The command to run after the network has been launched.
+	/// The command to run after the network has been launched and is ready.
 	#[clap(name = "cmd", short = 'c', long)]
 	command: Option<String>,
+	/// Stream the log of the specified node once the network has been launched, until
+	/// terminated.
+	#[arg(long, value_name = "NODE")]
+	follow: Option<String>,
+	/// Wait until the network is usable before reporting it as launched: every node must have a
+	/// reachable RPC server, peers (where other nodes of its chain exist) and an advancing
+	/// finalized height.
+	#[arg(long)]
+	wait_ready: bool,
+	/// The number of seconds to wait for the network to be ready before terminating it.
+	#[arg(long, value_name = "SECS", default_value_t = 300)]
+	ready_timeout: u64,
 	/// Whether the output should be verbose.
 	#[arg(short, long, action)]
 	verbose: bool,
 	/// Automatically source all needed binaries required without prompting for confirmation.
 	#[clap(short('y'), long)]
 	skip_confirm: bool,
+	/// The name of a node to be fronted by a local JSON-RPC proxy, which logs all RPC traffic
+	/// relayed to the node.
+	#[arg(long = "rpc-proxy", value_name = "NODE")]
+	rpc_proxy: Option<String>,
+	/// Only log RPC messages whose method matches the regular expression (e.g. "^chain_").
+	#[arg(long = "rpc-filter", requires = "rpc_proxy")]
+	rpc_filter: Option<String>,
+	/// Watch the parachain project in the current directory and the network configuration file,
+	/// rebuilding the project on changes and restarting only the affected nodes.
+	#[arg(long)]
+	watch: bool,
+	/// How changes are applied to the running network when watching.
+	#[arg(long, value_enum, default_value_t, requires = "watch")]
+	watch_mode: WatchMode,
+	/// Secret key URI of the sudo key of the parachains, used to upgrade their runtimes when
+	/// watching.
+	#[arg(long, default_value = "//Alice", requires = "watch")]
+	suri: String,
+	/// Export the topology of the launched network to the specified file, as a Graphviz DOT graph
+	/// if its extension is `.dot` or `.gv`, otherwise as JSON.
+	#[arg(long, value_name = "PATH")]
+	topology: Option<PathBuf>,
+	/// Write a machine-readable report of the endpoints of each node (RPC, Prometheus, p2p, base
+	/// path) to the specified file once launched, as JSON, or to stdout if `-`.
+	#[arg(long, value_name = "PATH")]
+	report: Option<PathBuf>,
+	/// Derive the specified number of accounts from a base mnemonic, endowing them within the
+	/// genesis of the relay chain and each parachain (e.g. for load tests).
+	#[arg(long, value_name = "COUNT")]
+	accounts: Option<usize>,
+	/// The base mnemonic from which accounts are derived [default: the development mnemonic].
+	#[arg(long, requires = "accounts")]
+	mnemonic: Option<String>,
+	/// The balance endowed to each derived account.
+	#[arg(long, default_value_t = 1_000_000_000_000_000, requires = "accounts")]
+	endowment: u64,
+	/// The file to which the derived accounts are exported, as a JSON keyring.
+	#[arg(long, value_name = "PATH", default_value = "accounts.json", requires = "accounts")]
+	keyring: PathBuf,
+	/// Secure the RPC endpoints of the collators for public exposure, generating the
+	/// configuration of an nginx gateway, including a self-signed certificate for local HTTPS
+	/// testing, within the specified directory.
+	#[arg(long, value_name = "DIR")]
+	secure_rpc: Option<PathBuf>,
+	/// The domain at which the gateway is served.
+	#[arg(long, default_value = "localhost", requires = "secure_rpc")]
+	rpc_domain: String,
+	/// The port on which the gateway listens.
+	#[arg(long, default_value_t = 8443, requires = "secure_rpc")]
+	rpc_gateway_port: u16,
+	/// Comma-separated list of origins permitted to access the RPC endpoints (e.g.
+	/// "https://polkadot.js.org").
+	#[arg(long, value_delimiter = ',', requires = "secure_rpc")]
+	rpc_cors: Vec<String>,
+	/// The maximum number of RPC calls per minute, per connection.
+	#[arg(long, value_name = "CALLS", requires = "secure_rpc")]
+	rpc_rate_limit: Option<u32>,
+	/// The maximum number of concurrent RPC connections to each collator.
+	#[arg(long, requires = "secure_rpc")]
+	rpc_max_connections: Option<u32>,
+	/// Rotate the bearer token and certificate of a gateway previously generated.
+	#[arg(long, requires = "secure_rpc")]
+	rotate_credentials: bool,
+	/// Restart any collators which fail consecutive health checks, with an exponential backoff,
+	/// so that long-running networks recover from crashes.
+	#[arg(long)]
+	auto_restart: bool,
+	/// The number of seconds between health checks of each collator.
+	#[arg(long, value_name = "SECS", default_value_t = 10)]
+	health_check_interval: u64,
+	/// The maximum number of consecutive restarts of a collator before it is no longer restarted
+	/// [default: unlimited].
+	#[arg(long, value_name = "COUNT", requires = "auto_restart")]
+	max_restarts: Option<u32>,
+	/// A webhook to which alerts are posted when collators crash or recover (e.g. a Slack incoming
+	/// webhook URL), alongside any configured within `pop.toml`.
+	#[arg(long, value_name = "URL")]
+	alert_webhook: Option<Url>,
+	/// Deploy the contracts of the workspace to the parachain of the project once launched, whose
+	/// runtime must include `pallet-contracts` or `pallet-revive`, recording their addresses
+	/// within the address book of the workspace (`.pop/address-book.json`).
+	#[cfg(feature = "contract")]
+	#[arg(long)]
+	deploy_contracts: bool,
 }
 
 impl ZombienetCommand {
+	/// The policy by which collators are supervised.
+	fn watchdog_policy(&self) -> WatchdogPolicy {
+		WatchdogPolicy {
+			interval: Duration::from_secs(self.health_check_interval.max(1)),
+			restart: self.auto_restart,
+			max_restarts: self.max_restarts,
+			..Default::default()
+		}
+	}
+
+	/// The webhooks notified of crashed or recovered collators: those configured within the
+	/// `pop.toml` file of the project, alongside any alert webhook specified.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	fn notifier(&self, project: &Path) -> anyhow::Result<Notifier> {
+		let notifier = match Notifier::load(project) {
+			Ok(notifier) => notifier,
+			Err(e) => {
+				log::warning(format!("⚠️ Unable to notify webhooks: {e}"))?;
+				Notifier::default()
+			},
+		};
+		Ok(match &self.alert_webhook {
+			Some(url) => notifier.with_webhook(Webhook {
+				url: url.to_string(),
+				events: vec![EventKind::CollatorCrashed, EventKind::CollatorRecovered],
+			}),
+			None => notifier,
+		})
+	}
+
 	/// Executes the command.
 	pub(crate) async fn execute(self) -> anyhow::Result<()> {
 		clear_screen()?;
@@ -89,15 +245,179 @@ impl ZombienetCommand {
 		if Self::source_binaries(&mut zombienet, &cache, self.verbose, self.skip_confirm).await? {
 			return Ok(());
 		}
+		// Keep the cache from growing unbounded, retaining the binaries required by the network.
+		reclaim(&cache, zombienet.binaries().map(|b| b.path()))?;
+
+		// Determine the HRMP channels to be opened once the network is launched.
+		let channels = match zombienet.hrmp_channels() {
+			Ok(channels) => channels,
+			Err(Error::Config(message)) => {
+				outro_cancel(format!("🚫 A configuration error occurred: `{message}`"))?;
+				return Ok(());
+			},
+			Err(e) => return Err(e.into()),
+		};
+
+		// Fail early if the resources required to launch the network are unavailable.
+		let requirements = Requirements::launch(zombienet.node_count());
+		if let Err(e) = preflight::check("launch the network", &temp_dir(), &requirements) {
+			outro_cancel(format!("🚫 {e}"))?;
+			return Ok(());
+		}
+
+		// Endow any accounts requested within the genesis of the network.
+		if let Some(count) = self.accounts {
+			let accounts = derive_accounts(self.mnemonic.as_deref().unwrap_or(DEV_PHRASE), count)?;
+			let addresses: Vec<_> = accounts.iter().map(|a| a.address.clone()).collect();
+			if let Err(e) = zombienet.endow(&addresses, self.endowment) {
+				outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			}
+			export_keyring(&accounts, &self.keyring)?;
+			log::info(format!(
+				"ℹ️ {count} accounts will be endowed, exported to {}",
+				self.keyring.display()
+			))?;
+		}
+
+		// Secure the RPC endpoints of the collators, if requested.
+		if let Some(dir) = self.secure_rpc.as_deref() {
+			let security = RpcSecurity {
+				domain: self.rpc_domain.clone(),
+				port: self.rpc_gateway_port,
+				cors: self.rpc_cors.clone(),
+				rate_limit: self.rpc_rate_limit,
+				max_connections: self.rpc_max_connections,
+			};
+			let gateway = zombienet.secure_rpc(&security, dir).and_then(|ports| {
+				let gateway = security.generate(dir, &ports)?;
+				match self.rotate_credentials {
+					true => security.rotate(dir),
+					false => Ok(gateway),
+				}
+			});
+			match gateway {
+				Ok(gateway) => log::info(format!(
+					"🔐 RPC endpoints will be served at https://{}:{} once {} is included within nginx, authenticating with the bearer token saved in {}",
+					security.domain,
+					security.port,
+					gateway.config().display(),
+					dir.display()
+				))?,
+				Err(e) => {
+					outro_cancel(format!("🚫 {e}"))?;
+					return Ok(());
+				},
+			}
+		}
+
+		// Check that the runtime of the project can host the contracts of the workspace.
+		#[cfg(feature = "contract")]
+		if self.deploy_contracts {
+			if let Err(e) = super::codev::check_runtime(Path::new("./")) {
+				outro_cancel(format!("🚫 {e}"))?;
+				return Ok(());
+			}
+		}
+
+		// Run any hooks configured for the project before launching.
+		let hooks = Hooks::load(Path::new("./"))?;
+		let context = HookContext::default().artifact("network_config", &self.file);
+		if let Err(e) = hooks.run(Stage::PreLaunch, &context) {
+			outro_cancel(format!("🚫 {e}"))?;
+			return Ok(());
+		}
 
 		// Finally spawn network and wait for signal to terminate
-		let spinner = cliclack::spinner();
-		spinner.start("🚀 Launching local network...");
-		match zombienet.spawn().await {
+		let spinner = Spinner::reporting("🚀 Launching local network...", "launch");
+		let timer = Timer::start("launch").subject(&self.file).project(Path::new("./"));
+		let result = zombienet.spawn().await;
+		stats::record(&Stats::new(&cache), timer.finish(result.is_ok()));
+		match result {
 			Ok(network) => {
 				let mut result =
 					"🚀 Network launched successfully - ctrl-c to terminate".to_string();
 				let base_dir = network.base_dir().expect("base_dir expected to exist");
+				let node = network.relaychain().nodes().into_iter().next();
+				let mut context = context.value("base_dir", base_dir);
+				if let Some(node) = node {
+					context = context.value("relay_chain_url", node.ws_uri());
+				}
+				// Wait until the network is usable, rather than assuming so after a fixed delay.
+				let mut ready = None;
+				if self.wait_ready || self.command.is_some() {
+					let mut probe = ReadinessProbe::from_network(&network);
+					let limit = Duration::from_secs(self.ready_timeout);
+					let progress = |readiness: &[NodeReadiness]| {
+						let count = readiness.iter().filter(|r| r.is_ready()).count();
+						let percent = (count * 100 / readiness.len().max(1)) as u8;
+						let message = format!(
+							"Waiting for the network to be ready: {count} of {} nodes ready...",
+							readiness.len()
+						);
+						spinner.set_progress(Some(percent), message);
+					};
+					match probe.wait(limit, READINESS_INTERVAL, progress).await {
+						Ok(readiness) => ready = Some(readiness.len()),
+						Err(e) => {
+							spinner.error(format!("🚫 {e}"));
+							outro_cancel("🚫 The network has been terminated.")?;
+							return Ok(());
+						},
+					}
+				}
+				// Open the HRMP channels declared within the network configuration via the sudo key
+				// of the relay chain, skipping any already opened within its genesis.
+				let mut opened = Vec::new();
+				if let Some(node) = node.filter(|_| !channels.is_empty()) {
+					spinner.set_message("Opening HRMP channels...");
+					let result = match set_up_client(node.ws_uri()).await {
+						Ok(client) => open_hrmp_channels(&client, &channels, "//Alice").await,
+						Err(e) => Err(e),
+					};
+					match result {
+						Ok(channels) => opened = channels,
+						Err(e) => {
+							spinner.error(format!("🚫 Unable to open the HRMP channels: {e}"));
+							outro_cancel("🚫 The network has been terminated.")?;
+							return Ok(());
+						},
+					}
+				}
+				// Deploy the contracts of the workspace to the parachain of the project: the first
+				// which is not a system parachain.
+				#[cfg(feature = "contract")]
+				let mut deployment = None;
+				#[cfg(feature = "contract")]
+				if self.deploy_contracts {
+					let mut parachains = network.parachains();
+					parachains.sort_by_key(|p| (p.para_id() < 2000, p.para_id()));
+					let Some(collator) =
+						parachains.first().and_then(|p| p.collators().into_iter().next())
+					else {
+						spinner.error("🚫 The network does not include a parachain.");
+						outro_cancel("🚫 The network has been terminated.")?;
+						return Ok(());
+					};
+					spinner.set_message("Deploying the contracts of the workspace...");
+					let url = Url::parse(collator.ws_uri())?;
+					match super::codev::deploy_contracts(Path::new("./"), &url).await {
+						Ok(deployed) => {
+							context = context.artifact("address_book", &deployed.path);
+							deployment = Some(deployed);
+						},
+						Err(e) => {
+							spinner.error(format!("🚫 {e}"));
+							outro_cancel("🚫 The network has been terminated.")?;
+							return Ok(());
+						},
+					}
+				}
+				if let Err(e) = hooks.run(Stage::PostLaunch, &context) {
+					spinner.error(format!("🚫 {e}"));
+					outro_cancel("🚫 The network has been terminated.")?;
+					return Ok(());
+				}
 				let bar = Style::new().magenta().dim().apply_to(Emoji("│", "|"));
 
 				let output = |node: &NetworkNode| -> String {
@@ -142,15 +462,207 @@ impl ZombienetCommand {
 					}
 				}
 
+				if let Some(count) = ready {
+					result.push_str(&format!(
+						"\n{bar}  ✅ All {count} nodes are ready: reachable, peered and finalizing blocks"
+					));
+				}
+
+				if !channels.is_empty() {
+					let channels: Vec<_> = channels
+						.iter()
+						.map(|c| format!("{} → {}", c.sender, c.recipient))
+						.collect();
+					result.push_str(&format!(
+						"\n{bar}  🔗 HRMP channels: {} ({} opened via sudo, usable from the next session)",
+						channels.join(", "),
+						opened.len()
+					));
+				}
+
 				if let Some(command) = &self.command {
 					run_custom_command(&spinner, command).await?;
 				}
 
+				// Front the specified node with a logging proxy.
+				let mut proxy = None;
+				if let Some(name) = self.rpc_proxy.as_deref() {
+					let node = network.get_node(name)?;
+					let mut config = ProxyConfig::new(Url::parse(node.ws_uri())?);
+					if let Some(filter) = self.rpc_filter.as_deref() {
+						config = config.filter(filter)?;
+					}
+					let rpc_proxy = RpcProxy::start(config, |message| {
+						let _ = Term::stderr().write_line(&message.format(true));
+					})
+					.await?;
+					result.push_str(&format!(
+						"\n{bar}  🔍 RPC traffic to {name} is logged via {}",
+						rpc_proxy.url()
+					));
+					proxy = Some(rpc_proxy);
+				}
+
+				#[cfg(feature = "contract")]
+				if let Some(deployment) = &deployment {
+					result.push_str(&format!(
+						"\n{bar}  📜 {} contracts deployed to {}, recorded in {}",
+						deployment.address_book.contracts.len(),
+						deployment.address_book.url,
+						deployment.path.display()
+					));
+					for (name, address) in &deployment.address_book.contracts {
+						result.push_str(&format!("\n{bar}       {name}: {address}"));
+					}
+					for (name, error) in &deployment.failed {
+						result
+							.push_str(&format!("\n{bar}       {name}: failed to deploy ({error})"));
+					}
+				}
+
+				if let Some(path) = &self.topology {
+					let topology = Topology::from_network(&network);
+					let contents = match path.extension().and_then(|e| e.to_str()) {
+						Some("dot" | "gv") => topology.to_dot(),
+						_ => topology.to_json()?,
+					};
+					write(path, contents)?;
+					result.push_str(&format!(
+						"\n{bar}  🗺️ Network topology exported to {}",
+						path.display()
+					));
+				}
+
+				// The report is printed to stdout once the spinner has stopped.
+				let mut report = None;
+				if let Some(path) = &self.report {
+					let json = EndpointReport::from_network(&network)?.to_json()?;
+					if path.as_os_str() == "-" {
+						report = Some(json);
+					} else {
+						write(path, format!("{json}\n"))?;
+						result.push_str(&format!(
+							"\n{bar}  📡 Endpoint report written to {}",
+							path.display()
+						));
+					}
+				}
+
+				let policy = self.watchdog_policy();
+				let restarts = match self.auto_restart {
+					true => format!(
+						", restarted if they fail {} consecutive checks",
+						policy.failure_threshold
+					),
+					false => String::new(),
+				};
+				result.push_str(&format!(
+					"\n{bar}  🩺 Collators are health-checked every {}s{restarts}{}",
+					policy.interval.as_secs(),
+					self.alert_webhook.as_ref().map_or(String::new(), |url| format!(
+						", posting alerts to {}",
+						url.host_str().unwrap_or_default()
+					))
+				));
+
+				if self.watch {
+					result.push_str(&format!(
+						"\n{bar}  👀 Watching for changes to the project and network configuration"
+					));
+				}
+
+				let mut logs = None;
+				if let Some(node) = &self.follow {
+					let options = LogOptions { follow: true, ..Default::default() };
+					match network.logs(node, options) {
+						Ok(stream) => logs = Some(stream),
+						Err(e) => {
+							spinner.error(format!("🚫 Unable to follow the log of {node}: {e}"));
+							outro_cancel("🚫 The network has been terminated.")?;
+							return Ok(());
+						},
+					}
+					result.push_str(&format!("\n{bar}  📜 Following the log of {node}"));
+				}
 				spinner.stop(result);
-				tokio::signal::ctrl_c().await?;
+				if let Some(report) = report {
+					Term::stdout().write_line(&report)?;
+				}
+				let project = Path::new("./");
+				let nodes = network
+					.relaychain()
+					.nodes()
+					.into_iter()
+					.chain(network.parachains().into_iter().flat_map(|p| p.collators()))
+					.map(|node| (node.name().to_string(), node.ws_uri().to_string()))
+					.collect();
+				let relay_chain = network.relaychain().chain().to_string();
+				notify(project, &Event::NetworkUp { relay_chain, nodes }).await?;
+				for parachain in &parachains {
+					if let Some(collator) = parachain.collators().into_iter().next() {
+						let event = Event::ParaOnboarded {
+							para_id: parachain.para_id(),
+							url: collator.ws_uri().to_string(),
+						};
+						notify(project, &event).await?;
+					}
+				}
+				#[cfg(feature = "contract")]
+				if let Some(deployment) = &deployment {
+					for address in deployment.address_book.contracts.values() {
+						let event = Event::ContractDeployed {
+							address: address.to_string(),
+							url: deployment.address_book.url.to_string(),
+						};
+						notify(project, &event).await?;
+					}
+				}
+				let collators = network
+					.parachains()
+					.into_iter()
+					.flat_map(|p| p.collators().into_iter().cloned().collect::<Vec<_>>());
+				let mut watchdog = Watchdog::start(collators, self.watchdog_policy());
+				let notifier = self.notifier(project)?;
+				let terminated = async {
+					let follow = async {
+						match logs {
+							Some(stream) => print_logs(stream).await?,
+							None => std::future::pending().await,
+						}
+						Ok::<_, anyhow::Error>(())
+					};
+					if self.watch {
+						let relay_nodes =
+							network.relaychain().nodes().into_iter().map(|n| (None, n.clone()));
+						let collators = network.parachains().into_iter().flat_map(|p| {
+							p.collators().into_iter().map(move |n| (Some(p.para_id()), n.clone()))
+						});
+						let nodes = relay_nodes.chain(collators).collect();
+						tokio::select! {
+							result = watch(self.file.clone().into(), nodes, self.watch_mode, &self.suri) => result?,
+							result = tokio::signal::ctrl_c() => result?,
+							result = follow => result?,
+						}
+					} else {
+						tokio::select! {
+							result = tokio::signal::ctrl_c() => result?,
+							result = follow => result?,
+						}
+					}
+					Ok::<_, anyhow::Error>(())
+				};
+				tokio::select! {
+					result = terminated => result?,
+					result = supervise(&mut watchdog, &notifier) => result?,
+				}
+				watchdog.stop();
+				if let Some(proxy) = proxy {
+					proxy.stop();
+				}
 				outro("Done")?;
 			},
 			Err(e) => {
+				spinner.error(format!("🚫 {e}"));
 				outro_cancel(format!("🚫 Could not launch local network: {e}"))?;
 			},
 		}
@@ -158,7 +670,7 @@ impl ZombienetCommand {
 		Ok(())
 	}
 
-	async fn source_binaries(
+	pub(crate) async fn source_binaries(
 		zombienet: &mut Zombienet,
 		cache: &Path,
 		verbose: bool,
@@ -169,6 +681,7 @@ impl ZombienetCommand {
 		if binaries.is_empty() {
 			return Ok(false);
 		}
+		let stats = Stats::new(cache);
 
 		// Check if any missing binaries
 		let missing: IndexSet<_> = binaries
@@ -276,13 +789,50 @@ impl ZombienetCommand {
 
 		// Source binaries
 		let release = true;
+		if ndjson_requested() {
+			// Report progress as machine-readable events on stderr, one step per binary, as well as
+			// the overall progress of sourcing the binaries.
+			let sourcing = NdjsonReporter::stderr("source");
+			sourcing.started();
+			let total = binaries.len();
+			let mut error = false;
+			for (sourced, binary) in binaries.into_iter().enumerate() {
+				let reporter = NdjsonReporter::stderr(&format!("source.{}", binary.name()));
+				reporter.started();
+				let timer = Timer::start("source").subject(binary.name());
+				let result = binary.source(release, &reporter, verbose).await;
+				stats::record(&stats, timer.finish(result.is_ok()));
+				match result {
+					Ok(()) => {
+						reporter.artifact(&binary.path());
+						reporter.completed();
+					},
+					Err(e) => {
+						reporter.failed(&e.to_string());
+						error = true;
+					},
+				}
+				let percent = ((sourced + 1) * 100 / total) as u8;
+				sourcing.progress(Some(percent), binary.name());
+			}
+			if error {
+				sourcing
+					.failed("Cannot launch the network until all required binaries are available.");
+				return Ok(true);
+			}
+			sourcing.completed();
+			return Ok(false);
+		}
 		match verbose {
 			true => {
 				let reporter = VerboseReporter;
 				for binary in binaries {
 					log::info(format!("📦 Sourcing {}...", binary.name()))?;
 					Term::stderr().clear_last_lines(1)?;
-					if let Err(e) = binary.source(release, &reporter, verbose).await {
+					let timer = Timer::start("source").subject(binary.name());
+					let result = binary.source(release, &reporter, verbose).await;
+					stats::record(&stats, timer.finish(result.is_ok()));
+					if let Err(e) = result {
 						reporter.update(&format!("Sourcing failed: {e}"));
 						outro_cancel(
 							"🚫 Cannot launch the network until all required binaries are available.",
@@ -306,7 +856,10 @@ impl ZombienetCommand {
 				for (binary, progress) in queue {
 					let prefix = format!("{}: ", binary.name());
 					let progress_reporter = ProgressReporter(prefix, progress);
-					if let Err(e) = binary.source(release, &progress_reporter, verbose).await {
+					let timer = Timer::start("source").subject(binary.name());
+					let result = binary.source(release, &progress_reporter, verbose).await;
+					stats::record(&stats, timer.finish(result.is_ok()));
+					if let Err(e) = result {
 						progress_reporter.1.error(format!("🚫 {}: {e}", binary.name()));
 						error = true;
 					}
@@ -326,9 +879,30 @@ impl ZombienetCommand {
 	}
 }
 
-async fn run_custom_command(spinner: &ProgressBar, command: &str) -> Result<(), anyhow::Error> {
-	spinner.set_message(format!("Spinning up network & running command: {}", command));
-	sleep(Duration::from_secs(15)).await;
+/// Reports the events of a watchdog supervising the collators of a network, notifying webhooks
+/// of any crashed or recovered collators. Only returns on failure.
+///
+/// # Arguments
+/// * `watchdog` - The watchdog supervising the collators.
+/// * `notifier` - The webhooks to be notified.
+async fn supervise(watchdog: &mut Watchdog, notifier: &Notifier) -> anyhow::Result<()> {
+	while let Some(event) = watchdog.next().await {
+		match event.is_alert() {
+			true => log::warning(format!("🩺 {event}"))?,
+			false => log::info(format!("🩺 {event}"))?,
+		}
+		if let Some(event) = event.notification() {
+			if let Err(e) = notifier.notify(&event).await {
+				log::warning(format!("⚠️ Unable to notify webhooks: {e}"))?;
+			}
+		}
+	}
+	// No collators remain supervised.
+	std::future::pending().await
+}
+
+async fn run_custom_command(spinner: &Spinner, command: &str) -> Result<(), anyhow::Error> {
+	spinner.set_message(format!("Running command: {}", command));
 
 	// Split the command into the base command and arguments
 	let mut parts = command.split_whitespace();
@@ -343,7 +917,7 @@ async fn run_custom_command(spinner: &ProgressBar, command: &str) -> Result<(),
 }
 
 /// Reports any observed status updates to a progress bar.
-struct ProgressReporter(String, ProgressBar);
+pub(crate) struct ProgressReporter(pub(crate) String, pub(crate) ProgressBar);
 
 impl Status for ProgressReporter {
 	fn update(&self, status: &str) {
@@ -377,7 +951,7 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_run_custom_command() -> Result<(), anyhow::Error> {
-		let spinner = ProgressBar::new(1);
+		let spinner = Spinner::start("Running command...");
 
 		// Define the command to be executed
 		let command = "echo 2 + 2";
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::{self, Cli};
+use pop_parachains::reserve_para_id;
+use url::Url;
+
+/// Reserves a parachain identifier on a relay chain.
+pub(crate) struct ReserveIdCommand {
+	/// Websocket endpoint of a node of the relay chain.
+	pub(crate) relay_url: Url,
+	/// Secret key URI of the account reserving the identifier.
+	pub(crate) suri: String,
+}
+
+impl ReserveIdCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<Option<u32>> {
+		self.run(&mut Cli).await
+	}
+
+	/// Reserves the next free parachain identifier, returning the identifier reserved.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<Option<u32>> {
+		cli.intro("Reserve a parachain identifier")?;
+		let spinner = cliclack::spinner();
+		spinner.start(format!("Reserving a parachain identifier on {}...", self.relay_url));
+		match reserve_para_id(self.relay_url.as_str(), &self.suri).await {
+			Ok(para_id) => {
+				spinner.stop(format!("Parachain identifier {para_id} reserved"));
+				cli.outro(format!(
+					"✅ Reserved para id {para_id}: use it when building the chain spec with `pop build spec --id {para_id}`"
+				))?;
+				Ok(Some(para_id))
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				cli.outro_cancel(format!("🚫 Unable to reserve a parachain identifier: {e}"))?;
+				Ok(None)
+			},
+		}
+	}
+}
@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::parachain::ProgressReporter;
+use crate::{
+	cli::{self, Cli},
+	common::notify::notify,
+};
+use clap::Args;
+use pop_common::{notify::Event, Profile};
+use pop_parachains::{runtime_artifacts, upgrade_parachain, Enactment};
+use std::{
+	path::{Path, PathBuf},
+	time::{Duration, UNIX_EPOCH},
+};
+
+#[derive(Args)]
+pub(crate) struct RuntimeUpgradeCommand {
+	/// Path of the new runtime [default: the most recently built runtime of the project].
+	#[arg(long)]
+	pub(crate) wasm: Option<PathBuf>,
+	/// Directory path of the project, within which the runtime was built [default: current
+	/// directory].
+	#[arg(short = 'p', long)]
+	pub(crate) path: Option<PathBuf>,
+	/// The profile with which the runtime of the project was built (e.g. "production")
+	/// [default: release].
+	#[arg(long, conflicts_with = "wasm")]
+	pub(crate) profile: Option<Profile>,
+	/// Websocket endpoint of a node of the parachain.
+	#[arg(long, default_value = "ws://localhost:9944")]
+	pub(crate) url: url::Url,
+	/// Secret key URI of the sudo key, authorizing and enacting the upgrade.
+	#[arg(short, long, default_value = "//Alice")]
+	pub(crate) suri: String,
+	/// Enact the upgrade at the specified block, authorizing it immediately.
+	#[arg(long, value_name = "BLOCK", conflicts_with_all = ["at_session", "at_time"])]
+	pub(crate) at_block: Option<u32>,
+	/// Enact the upgrade once the specified session has started, authorizing it immediately.
+	#[arg(long, value_name = "SESSION", conflicts_with = "at_time")]
+	pub(crate) at_session: Option<u32>,
+	/// Enact the upgrade at the first block produced after the specified time, in seconds since
+	/// the Unix epoch, authorizing it immediately.
+	#[arg(long, value_name = "TIMESTAMP")]
+	pub(crate) at_time: Option<u64>,
+}
+
+impl RuntimeUpgradeCommand {
+	/// When the upgrade is to be enacted, if delayed.
+	fn enactment(&self) -> Option<Enactment> {
+		match (self.at_block, self.at_session, self.at_time) {
+			(Some(block), ..) => Some(Enactment::Block(block)),
+			(_, Some(session), _) => Some(Enactment::Session(session)),
+			(.., Some(time)) => Some(Enactment::Time(UNIX_EPOCH + Duration::from_secs(time))),
+			_ => None,
+		}
+	}
+
+	/// Executes the command.
+	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+		self.run(&mut Cli).await
+	}
+
+	/// Upgrades the runtime of a live parachain, authorizing the upgrade and then enacting it.
+	///
+	/// # Arguments
+	/// * `cli` - The CLI implementation to be used.
+	async fn run(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		cli.intro("Upgrade the runtime of a parachain")?;
+		let wasm = match &self.wasm {
+			Some(wasm) => wasm.clone(),
+			None => {
+				let project = self.path.clone().unwrap_or_else(|| PathBuf::from("./"));
+				let profile = self.profile.clone().unwrap_or(Profile::Release);
+				match runtime_artifacts(&profile.resolve_target_directory(&project, None), None)
+					.map(|runtime| runtime.compressed)
+				{
+					Ok(wasm) => wasm,
+					Err(_) => {
+						cli.outro_cancel(
+							"🚫 No runtime found: build it with `pop build --release`, or specify one with `--wasm`",
+						)?;
+						return Ok(());
+					},
+				}
+			},
+		};
+		cli.info(format!("Upgrading to {}", wasm.display()))?;
+
+		let spinner = cliclack::spinner();
+		spinner.start(format!("Connecting to {}...", self.url));
+		let status = ProgressReporter(String::new(), spinner);
+		let enactment = self.enactment();
+		match upgrade_parachain(self.url.as_str(), &wasm, &self.suri, enactment.as_ref(), &status)
+			.await
+		{
+			Ok(upgrade) => {
+				status.1.stop(format!(
+					"Parachain {} upgraded from spec version {} to {}",
+					upgrade.para_id, upgrade.previous_spec_version, upgrade.spec_version
+				));
+				let event = Event::UpgradeEnacted {
+					para_id: upgrade.para_id,
+					previous_spec_version: upgrade.previous_spec_version,
+					spec_version: upgrade.spec_version,
+				};
+				notify(self.path.as_deref().unwrap_or_else(|| Path::new("./")), &event).await?;
+				cli.outro("✅ The new runtime is live")?;
+			},
+			Err(e) => {
+				status.1.error(format!("{e}"));
+				cli.outro_cancel(format!("🚫 Unable to upgrade the runtime: {e}"))?;
+			},
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[tokio::test]
+	async fn run_fails_without_runtime() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let command = RuntimeUpgradeCommand {
+			wasm: None,
+			path: Some(temp_dir.path().to_path_buf()),
+			profile: None,
+			url: "ws://localhost:9944".parse()?,
+			suri: "//Alice".into(),
+			at_block: None,
+			at_session: None,
+			at_time: None,
+		};
+		let mut cli = MockCli::new()
+			.expect_intro("Upgrade the runtime of a parachain")
+			.expect_outro_cancel(
+				"🚫 No runtime found: build it with `pop build --release`, or specify one with `--wasm`",
+			);
+		command.run(&mut cli).await?;
+		cli.verify()
+	}
+
+	#[test]
+	fn enactment_works() -> anyhow::Result<()> {
+		use clap::Parser;
+
+		#[derive(Parser)]
+		struct Command {
+			#[command(flatten)]
+			upgrade: RuntimeUpgradeCommand,
+		}
+
+		let enactment = |args: &[&str]| -> anyhow::Result<Option<Enactment>> {
+			let args = ["pop"].iter().chain(args);
+			Ok(Command::try_parse_from(args)?.upgrade.enactment())
+		};
+		assert_eq!(enactment(&[])?, None);
+		assert_eq!(enactment(&["--at-block", "1000"])?, Some(Enactment::Block(1_000)));
+		assert_eq!(enactment(&["--at-session", "10"])?, Some(Enactment::Session(10)));
+		assert_eq!(
+			enactment(&["--at-time", "1700000000"])?,
+			Some(Enactment::Time(UNIX_EPOCH + Duration::from_secs(1_700_000_000)))
+		);
+		assert!(enactment(&["--at-block", "1000", "--at-session", "10"]).is_err());
+		Ok(())
+	}
+}
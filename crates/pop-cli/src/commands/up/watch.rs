@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::common::notify::notify;
+use clap::ValueEnum;
+use cliclack::log;
+use pop_common::{notify::Event, watch::Watcher, Profile};
+use pop_parachains::{
+	build_parachain, runtime_artifacts, set_up_client, sudo_upgrade, BuildOptions, NetworkNode,
+	NetworkState, RelaunchPlan,
+};
+use std::{
+	fs::{canonicalize, read},
+	path::{Path, PathBuf},
+	time::Duration,
+};
+
+/// The interval at which the project is polled for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// The period without further changes before the project is rebuilt.
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// How changes to a project are applied to a running network.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum WatchMode {
+	/// Restart the collators running the rebuilt node.
+	#[default]
+	Restart,
+	/// Upgrade the runtime of the parachains whose collators run the rebuilt node, via sudo.
+	Upgrade,
+}
+
+/// Watches the project in the current directory and the network configuration file, rebuilding
+/// the project on changes and applying the result to the running network until cancelled. Only
+/// the nodes affected by a change are restarted, leaving all other nodes running.
+///
+/// # Arguments
+/// * `config` - The network configuration file used to launch the network.
+/// * `nodes` - The nodes of the running network, with the identifier of the parachain of each
+///   collator.
+/// * `mode` - How changes are applied to the running network.
+/// * `suri` - The secret URI of the sudo key of the parachains, used to upgrade their runtimes.
+pub(crate) async fn watch(
+	config: PathBuf,
+	nodes: Vec<(Option<u32>, NetworkNode)>,
+	mode: WatchMode,
+	suri: &str,
+) -> anyhow::Result<()> {
+	let project = PathBuf::from("./");
+	let config = canonicalize(&config)?;
+	let mut paths = vec![project.clone()];
+	if !config.starts_with(canonicalize(&project)?) {
+		paths.push(config.clone());
+	}
+	let mut watcher = Watcher::new(paths);
+	let mut state = NetworkState::capture(&config, nodes.iter().map(|(_, n)| n))?;
+	// The runtime produced by the most recent build, if any.
+	let mut runtime = None;
+	loop {
+		let changes = watcher.wait(POLL_INTERVAL, DEBOUNCE).await;
+		// Rebuild the project, unless only the network configuration changed.
+		if changes.iter().any(|c| canonicalize(c).map_or(true, |c| c != config)) {
+			log::info(format!("🔄 {} file(s) changed, rebuilding...", changes.len()))?;
+			let path = project.clone();
+			let result = tokio::task::spawn_blocking(move || {
+				build_parachain(&path, &BuildOptions::new(Profile::Release), &())
+			})
+			.await?;
+			match result {
+				Ok(artifacts) => runtime = artifacts.runtime.map(|r| r.compressed),
+				Err(e) => {
+					log::error(format!("🚫 Build failed, waiting for further changes: {e}"))?;
+					continue;
+				},
+			}
+			// Ignore any changes made by the build itself.
+			watcher.changes();
+		}
+
+		let current = match NetworkState::capture(&config, nodes.iter().map(|(_, n)| n)) {
+			Ok(current) => current,
+			Err(e) => {
+				log::error(format!("🚫 {e}"))?;
+				continue;
+			},
+		};
+		// The previous state is retained until changes are applied, so that any changes which
+		// could not be applied continue to be reported.
+		let plan = state.diff(&current);
+		if plan.requires_relaunch() {
+			log::warning(format!(
+				"⚠️ The network must be relaunched to apply all changes, which remain pending: {}",
+				plan.reasons.join(", ")
+			))?;
+		}
+		if plan.restart.is_empty() {
+			if !plan.requires_relaunch() {
+				log::info("ℹ️ No running nodes are affected by the changes")?;
+			}
+			continue;
+		}
+		let result = match mode {
+			WatchMode::Restart => restart(&plan, &nodes).await,
+			WatchMode::Upgrade => upgrade(&plan, &nodes, &project, runtime.as_deref(), suri).await,
+		};
+		match result {
+			Ok(()) => state.apply(&current, &plan.restart),
+			Err(e) => log::error(format!("🚫 Unable to apply changes: {e}"))?,
+		}
+	}
+}
+
+/// Restarts the affected nodes, so that they run the rebuilt binaries.
+///
+/// # Arguments
+/// * `plan` - The changes to be applied.
+/// * `nodes` - The nodes of the running network.
+async fn restart(plan: &RelaunchPlan, nodes: &[(Option<u32>, NetworkNode)]) -> anyhow::Result<()> {
+	for name in plan.restart(nodes.iter().map(|(_, n)| n)).await? {
+		log::success(format!("✅ Restarted {name}"))?;
+	}
+	Ok(())
+}
+
+/// Upgrades the runtime of the parachains whose collators are affected to the rebuilt runtime
+/// via sudo, waiting for each upgrade to be enacted. Any other affected nodes are restarted.
+///
+/// # Arguments
+/// * `plan` - The changes to be applied.
+/// * `nodes` - The nodes of the running network.
+/// * `project` - The path of the project.
+/// * `runtime` - The runtime produced by the most recent build, otherwise located within the
+///   project.
+/// * `suri` - The secret URI of the sudo key of the parachains.
+async fn upgrade(
+	plan: &RelaunchPlan,
+	nodes: &[(Option<u32>, NetworkNode)],
+	project: &Path,
+	runtime: Option<&Path>,
+	suri: &str,
+) -> anyhow::Result<()> {
+	let affected = |node: &NetworkNode| plan.restart.iter().any(|name| name == node.name());
+	// Restart any affected relay chain nodes.
+	let relay_nodes = RelaunchPlan {
+		restart: nodes
+			.iter()
+			.filter(|(para_id, node)| para_id.is_none() && affected(node))
+			.map(|(_, node)| node.name().to_string())
+			.collect(),
+		reasons: vec![],
+	};
+	restart(&relay_nodes, nodes).await?;
+
+	let mut parachains: Vec<(u32, &NetworkNode)> = vec![];
+	for (para_id, node) in nodes {
+		if let Some(para_id) =
+			para_id.filter(|id| affected(node) && parachains.iter().all(|(p, _)| p != id))
+		{
+			parachains.push((para_id, node));
+		}
+	}
+	if parachains.is_empty() {
+		return Ok(());
+	}
+	let wasm = match runtime {
+		Some(runtime) => runtime.to_path_buf(),
+		None => runtime_artifacts(&Profile::Release.target_directory(project), None)
+			.map(|runtime| runtime.compressed)?,
+	};
+	let code = read(&wasm)?;
+	for (para_id, collator) in parachains {
+		log::info(format!("⏳ Upgrading the runtime of parachain {para_id}..."))?;
+		let client = set_up_client(collator.ws_uri()).await?;
+		let (previous, current) = sudo_upgrade(&client, code.clone(), suri, None).await?;
+		let event = Event::UpgradeEnacted {
+			para_id,
+			previous_spec_version: previous,
+			spec_version: current,
+		};
+		notify(project, &event).await?;
+		log::success(format!(
+			"✅ Upgraded the runtime of parachain {para_id} from spec version {previous} to {current}"
+		))?;
+	}
+	Ok(())
+}
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use crate::commands::clean::reclaim;
 use cliclack::{confirm, log::warning, spinner};
 use pop_contracts::contracts_node_generator;
 use std::path::PathBuf;
@@ -11,7 +12,7 @@ use std::path::PathBuf;
 /// * `skip_confirm`: A boolean indicating whether to skip confirmation prompts.
 pub async fn check_contracts_node_and_prompt(skip_confirm: bool) -> anyhow::Result<PathBuf> {
 	let cache_path: PathBuf = crate::cache()?;
-	let mut binary = contracts_node_generator(cache_path, None).await?;
+	let mut binary = contracts_node_generator(cache_path.clone(), None).await?;
 	let mut node_path = binary.path();
 	if !binary.exists() {
 		warning("⚠️ The substrate-contracts-node binary is not found.")?;
@@ -62,6 +63,8 @@ pub async fn check_contracts_node_and_prompt(skip_confirm: bool) -> anyhow::Resu
 			node_path = binary.path();
 		}
 	}
+	// Keep the cache from growing unbounded, retaining the node in use.
+	reclaim(&cache_path, [node_path.clone()])?;
 
 	Ok(node_path)
 }
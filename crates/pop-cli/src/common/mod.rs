@@ -3,3 +3,5 @@
 #[cfg(feature = "contract")]
 pub mod contracts;
 pub mod helpers;
+pub mod notify;
+pub mod output;
@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use cliclack::log;
+use pop_common::notify::{Event, Notifier};
+use std::path::Path;
+
+/// Posts an event to the webhooks configured within the `pop.toml` file of a project. Failures
+/// are reported as warnings, so that notifications never interrupt the command.
+///
+/// # Arguments
+/// * `project` - The project directory.
+/// * `event` - The event.
+pub(crate) async fn notify(project: &Path, event: &Event) -> anyhow::Result<()> {
+	let result = match Notifier::load(project) {
+		Ok(notifier) => notifier.notify(event).await,
+		Err(e) => Err(e),
+	};
+	if let Err(e) = result {
+		log::warning(format!("⚠️ Unable to notify webhooks: {e}"))?;
+	}
+	Ok(())
+}
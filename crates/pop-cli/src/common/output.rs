@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Output rendered via cliclack, which is suppressed whenever machine-readable progress events
+//! have been requested, as both are written to stderr.
+
+use cliclack::ProgressBar;
+use pop_common::progress::{ndjson_requested, NdjsonReporter};
+use std::{fmt::Display, io::Result};
+
+/// Clears the screen.
+pub(crate) fn clear_screen() -> Result<()> {
+	match ndjson_requested() {
+		true => Ok(()),
+		false => cliclack::clear_screen(),
+	}
+}
+
+/// Prints a header of the prompt sequence.
+///
+/// # Arguments
+/// * `title` - The title of the header.
+pub(crate) fn intro(title: impl Display) -> Result<()> {
+	match ndjson_requested() {
+		true => Ok(()),
+		false => cliclack::intro(title),
+	}
+}
+
+/// Prints a footer of the prompt sequence.
+///
+/// # Arguments
+/// * `message` - The message of the footer.
+pub(crate) fn outro(message: impl Display) -> Result<()> {
+	match ndjson_requested() {
+		true => Ok(()),
+		false => cliclack::outro(message),
+	}
+}
+
+/// Prints a footer of the prompt sequence with a failure style.
+///
+/// # Arguments
+/// * `message` - The message of the footer.
+pub(crate) fn outro_cancel(message: impl Display) -> Result<()> {
+	match ndjson_requested() {
+		true => Ok(()),
+		false => cliclack::outro_cancel(message),
+	}
+}
+
+/// Log messages, mirroring those of cliclack.
+pub(crate) mod log {
+	use super::*;
+
+	/// Prints an info message.
+	pub(crate) fn info(text: impl Display) -> Result<()> {
+		match ndjson_requested() {
+			true => Ok(()),
+			false => cliclack::log::info(text),
+		}
+	}
+
+	/// Prints a success message.
+	pub(crate) fn success(text: impl Display) -> Result<()> {
+		match ndjson_requested() {
+			true => Ok(()),
+			false => cliclack::log::success(text),
+		}
+	}
+
+	/// Prints a warning message.
+	pub(crate) fn warning(text: impl Display) -> Result<()> {
+		match ndjson_requested() {
+			true => Ok(()),
+			false => cliclack::log::warning(text),
+		}
+	}
+
+	/// Prints an error message.
+	pub(crate) fn error(text: impl Display) -> Result<()> {
+		match ndjson_requested() {
+			true => Ok(()),
+			false => cliclack::log::error(text),
+		}
+	}
+}
+
+/// A spinner, which is not rendered when progress events have been requested, optionally
+/// reporting its progress as the events of a step instead.
+pub(crate) struct Spinner {
+	/// The spinner, unless progress events have been requested.
+	bar: Option<ProgressBar>,
+	/// The reporter of progress events, if requested.
+	events: Option<NdjsonReporter>,
+}
+
+impl Spinner {
+	/// Starts a spinner.
+	///
+	/// # Arguments
+	/// * `message` - The message of the spinner.
+	pub(crate) fn start(message: impl Display) -> Self {
+		Self::new(message, None, ndjson_requested())
+	}
+
+	/// Starts a spinner, reporting its progress as the events of a step when requested.
+	///
+	/// # Arguments
+	/// * `message` - The message of the spinner.
+	/// * `step` - The identifier of the step.
+	pub(crate) fn reporting(message: impl Display, step: &str) -> Self {
+		Self::new(message, Some(step), ndjson_requested())
+	}
+
+	fn new(message: impl Display, step: Option<&str>, requested: bool) -> Self {
+		match requested {
+			true => {
+				let events = step.map(NdjsonReporter::stderr);
+				if let Some(events) = &events {
+					events.started();
+				}
+				Self { bar: None, events }
+			},
+			false => {
+				let bar = cliclack::spinner();
+				bar.start(message);
+				Self { bar: Some(bar), events: None }
+			},
+		}
+	}
+
+	/// Sets the message of the spinner.
+	///
+	/// # Arguments
+	/// * `message` - The message.
+	pub(crate) fn set_message(&self, message: impl Display) {
+		self.set_progress(None, message)
+	}
+
+	/// Sets the message of the spinner, reporting the percentage of the step completed.
+	///
+	/// # Arguments
+	/// * `percent` - The percentage of the step completed, if known.
+	/// * `message` - The message.
+	pub(crate) fn set_progress(&self, percent: Option<u8>, message: impl Display) {
+		if let Some(events) = &self.events {
+			events.progress(percent, &message.to_string());
+		}
+		if let Some(bar) = &self.bar {
+			bar.set_message(message);
+		}
+	}
+
+	/// Stops the spinner, reporting the step as completed.
+	///
+	/// # Arguments
+	/// * `message` - The final message.
+	pub(crate) fn stop(&self, message: impl Display) {
+		if let Some(events) = &self.events {
+			events.completed();
+		}
+		if let Some(bar) = &self.bar {
+			bar.stop(message);
+		}
+	}
+
+	/// Stops the spinner with an error, reporting the step as failed.
+	///
+	/// # Arguments
+	/// * `message` - The error message.
+	pub(crate) fn error(&self, message: impl Display) {
+		if let Some(events) = &self.events {
+			events.failed(&message.to_string());
+		}
+		if let Some(bar) = &self.bar {
+			bar.error(message);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn spinner_is_not_rendered_when_progress_events_requested() {
+		let spinner = Spinner::new("Launching...", Some("launch"), true);
+		assert!(spinner.bar.is_none() && spinner.events.is_some());
+		spinner.set_progress(Some(50), "1 of 2 nodes ready");
+		spinner.stop("Launched");
+
+		let spinner = Spinner::new("Launching...", None, true);
+		assert!(spinner.bar.is_none() && spinner.events.is_none());
+
+		let spinner = Spinner::new("Launching...", Some("launch"), false);
+		assert!(spinner.bar.is_some() && spinner.events.is_none());
+		spinner.stop("Launched");
+	}
+}
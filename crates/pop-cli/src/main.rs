@@ -6,8 +6,16 @@ compile_error!("feature \"contract\" or feature \"parachain\" must be enabled");
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use commands::*;
+use pop_common::{
+	bootstrap::{is_first_run, Settings},
+	migration::migrate_cache,
+};
 use serde_json::json;
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+	fs::create_dir_all,
+	io::{stdin, IsTerminal},
+	path::PathBuf,
+};
 #[cfg(feature = "telemetry")]
 use {
 	pop_telemetry::{config_file_path, record_cli_command, record_cli_used, Telemetry},
@@ -22,10 +30,20 @@ mod style;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+	// Localize the messages produced by the library crates, using any translations installed
+	// within the configuration directory.
+	if let Ok(dir) = config_dir() {
+		pop_common::i18n::set_catalog(pop_common::i18n::catalog_from_env(&dir.join("locales")));
+	}
+
+	let cli = Cli::parse();
+	// Walk through the setup on the first run, before telemetry is initialized so that the
+	// consent given is respected.
+	first_run(&cli.command)?;
+
 	#[cfg(feature = "telemetry")]
 	let maybe_tel = init().unwrap_or(None);
 
-	let cli = Cli::parse();
 	let res = cli.command.execute().await;
 
 	#[cfg(feature = "telemetry")]
@@ -58,20 +76,62 @@ pub struct Cli {
 	command: Command,
 }
 
-/// Determines the cache to be used.
+/// Determines the cache to be used, as chosen by `pop setup` or otherwise the default.
 fn cache() -> Result<PathBuf> {
-	let cache_path = dirs::cache_dir()
-		.ok_or(anyhow!("the cache directory could not be determined"))?
-		.join("pop");
+	let cache_path = match settings() {
+		Some(settings) => settings.cache,
+		None => default_cache()?,
+	};
 	// Creates pop dir if needed
 	create_dir_all(cache_path.as_path())?;
+	// Upgrades the layout of a cache created by a previous release, if needed.
+	migrate_cache(&cache_path)?;
 	Ok(cache_path)
 }
 
+/// The default location of the cache.
+fn default_cache() -> Result<PathBuf> {
+	Ok(dirs::cache_dir()
+		.ok_or(anyhow!("the cache directory could not be determined"))?
+		.join("pop"))
+}
+
+/// The configuration directory, within which the settings chosen by `pop setup` are persisted.
+fn config_dir() -> Result<PathBuf> {
+	Ok(dirs::config_dir()
+		.ok_or(anyhow!("the config directory could not be determined"))?
+		.join("pop"))
+}
+
+/// Walks through the setup on the first run, unless the setup is the command being run or the
+/// terminal is not interactive, in which case the defaults are used until `pop setup` is run.
+///
+/// # Arguments
+/// * `command` - The command being run.
+fn first_run(command: &Command) -> Result<()> {
+	if matches!(command, Command::Setup(_)) || !stdin().is_terminal() {
+		return Ok(());
+	}
+	let dir = config_dir()?;
+	if !is_first_run(&dir) {
+		return Ok(());
+	}
+	setup::SetupCommand { cache: None, relay_chain: None }.execute()
+}
+
+/// The settings chosen by `pop setup`, if completed.
+fn settings() -> Option<Settings> {
+	Settings::load(&config_dir().ok()?).ok().flatten()
+}
+
 /// Initializes telemetry.
 #[cfg(feature = "telemetry")]
 fn init() -> Result<Option<Telemetry>> {
 	env_logger::init();
+	// Respect the consent given during `pop setup`.
+	if settings().is_some_and(|settings| !settings.telemetry) {
+		return Ok(None);
+	}
 	let maybe_config_path = config_file_path();
 
 	let maybe_tel = maybe_config_path.ok().map(|path| Telemetry::new(&path));
@@ -109,7 +169,7 @@ mod tests {
 
 	#[test]
 	fn test_cache() -> Result<(), Box<dyn std::error::Error>> {
-		let path = cache()?;
+		let path = default_cache()?;
 		assert_eq!(path.file_name().unwrap().to_str().unwrap().to_string(), "pop");
 		Ok(())
 	}
@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	helpers::{decode_hex, encode_hex},
+	Error,
+};
+use sp_core::{
+	crypto::{AccountId32, Ss58AddressFormat, Ss58Codec},
+	keccak_256,
+};
+use std::fmt::{Display, Formatter};
+
+/// The prefix of the generic Substrate address format.
+pub const SUBSTRATE_PREFIX: u16 = 42;
+/// The suffix of a 32-byte account mapped from a 20-byte account, as used by `pallet-revive`.
+const MAPPED_SUFFIX: [u8; 12] = [0xEE; 12];
+
+/// An account, in either of the formats used by chains.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Account {
+	/// A 32-byte account (`AccountId32`), as used by most Substrate chains.
+	Id32([u8; 32]),
+	/// A 20-byte account (`AccountId20`), as used by Ethereum-compatible chains.
+	Id20([u8; 20]),
+}
+
+impl Account {
+	/// Parses an account from either an SS58 address of any network, or a hex-encoded public
+	/// key or account (32 or 20 bytes).
+	///
+	/// # Arguments
+	/// * `account` - The account to be parsed.
+	pub fn parse(account: &str) -> Result<Self, Error> {
+		let account = account.trim();
+		if let Some(hex) = account.strip_prefix("0x") {
+			let bytes = decode_hex(hex)
+				.ok_or_else(|| Error::Account(format!("`{account}` is not valid hex")))?;
+			return match bytes.len() {
+				32 => Ok(Account::Id32(bytes.try_into().expect("length checked"))),
+				20 => Ok(Account::Id20(bytes.try_into().expect("length checked"))),
+				len => Err(Error::Account(format!(
+					"`{account}` is {len} bytes, whereas accounts are either 32 or 20 bytes"
+				))),
+			};
+		}
+		let (account, _) = parse_ss58(account)?;
+		Ok(account)
+	}
+
+	/// The 32-byte account. A 20-byte account is rejected with a suggestion of its mapped
+	/// 32-byte account, as chains using 32-byte accounts cannot use it directly.
+	pub fn id32(&self) -> Result<[u8; 32], Error> {
+		match self {
+			Account::Id32(account) => Ok(*account),
+			Account::Id20(_) => Err(Error::Account(format!(
+				"{self} is a 20-byte (Ethereum-style) account; did you mean its mapped account {}?",
+				encode_hex(&self.to_id32())
+			))),
+		}
+	}
+
+	/// The 20-byte account. A 32-byte account is rejected, unless it was mapped from a 20-byte
+	/// account.
+	pub fn id20(&self) -> Result<[u8; 20], Error> {
+		match self {
+			Account::Id20(account) => Ok(*account),
+			Account::Id32(account) if account.ends_with(&MAPPED_SUFFIX) =>
+				Ok(account[..20].try_into().expect("length checked")),
+			Account::Id32(_) => Err(Error::Account(format!(
+				"{} is a 32-byte account; did you mean its derived 20-byte account {}?",
+				self.to_ss58(SUBSTRATE_PREFIX),
+				encode_hex(&self.to_id20())
+			))),
+		}
+	}
+
+	/// Converts the account into a 32-byte account, where a 20-byte account is mapped by padding
+	/// it with `0xEE` bytes.
+	pub fn to_id32(&self) -> [u8; 32] {
+		match self {
+			Account::Id32(account) => *account,
+			Account::Id20(account) => {
+				let mut mapped = [0u8; 32];
+				mapped[..20].copy_from_slice(account);
+				mapped[20..].copy_from_slice(&MAPPED_SUFFIX);
+				mapped
+			},
+		}
+	}
+
+	/// Converts the account into a 20-byte account, where a 32-byte account is either unmapped
+	/// or derived from the last 20 bytes of its keccak-256 hash.
+	pub fn to_id20(&self) -> [u8; 20] {
+		match self {
+			Account::Id20(account) => *account,
+			Account::Id32(account) if account.ends_with(&MAPPED_SUFFIX) =>
+				account[..20].try_into().expect("length checked"),
+			Account::Id32(account) => keccak_256(account)[12..].try_into().expect("length checked"),
+		}
+	}
+
+	/// The SS58 address of the account, as a 32-byte account.
+	///
+	/// # Arguments
+	/// * `prefix` - The address format of the network.
+	pub fn to_ss58(&self, prefix: u16) -> String {
+		AccountId32::new(self.to_id32())
+			.to_ss58check_with_version(Ss58AddressFormat::custom(prefix))
+	}
+
+	/// The account, hex encoded.
+	pub fn to_hex(&self) -> String {
+		match self {
+			Account::Id32(account) => encode_hex(account),
+			Account::Id20(account) => encode_hex(account),
+		}
+	}
+}
+
+impl Display for Account {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Account::Id32(_) => write!(f, "{}", self.to_ss58(SUBSTRATE_PREFIX)),
+			Account::Id20(_) => write!(f, "{}", self.to_hex()),
+		}
+	}
+}
+
+/// Parses an account, as accepted by any API accepting accounts: an SS58 address of any
+/// network, or a hex-encoded 32-byte public key or account.
+///
+/// # Arguments
+/// * `account` - The account to be parsed.
+pub fn parse_account(account: &str) -> Result<[u8; 32], Error> {
+	Account::parse(account)?.id32()
+}
+
+/// Validates that an address is of the address format of a network, suggesting the address in
+/// the expected format otherwise. Hex-encoded accounts are accepted as is.
+///
+/// # Arguments
+/// * `address` - The address to be validated.
+/// * `prefix` - The address format of the network.
+pub fn validate_address(address: &str, prefix: u16) -> Result<Account, Error> {
+	let address = address.trim();
+	if address.starts_with("0x") {
+		return Account::parse(address);
+	}
+	let (account, format) = parse_ss58(address)?;
+	if format != prefix {
+		return Err(Error::Account(format!(
+			"this is {} address (prefix {format}); did you mean {}?",
+			network(format),
+			account.to_ss58(prefix)
+		)));
+	}
+	Ok(account)
+}
+
+/// A description of the network using an address format (e.g. "a Kusama-format").
+///
+/// # Arguments
+/// * `prefix` - The address format.
+pub fn network(prefix: u16) -> String {
+	let name = Ss58AddressFormat::custom(prefix).to_string();
+	if name.parse::<u16>().is_ok() {
+		return format!("a prefix-{prefix}");
+	}
+	let mut chars = name.chars();
+	let name: String = chars
+		.next()
+		.map(|c| c.to_uppercase().chain(chars).collect())
+		.unwrap_or_default();
+	let article = if name.starts_with(['A', 'E', 'I', 'O', 'U']) { "an" } else { "a" };
+	format!("{article} {name}-format")
+}
+
+/// Parses an SS58 address, returning the account and its address format.
+fn parse_ss58(address: &str) -> Result<(Account, u16), Error> {
+	let (account, format) = AccountId32::from_ss58check_with_version(address)
+		.map_err(|e| Error::Account(format!("`{address}` is not a valid address: {e}")))?;
+	let account: &[u8; 32] = account.as_ref();
+	Ok((Account::Id32(*account), format.prefix()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const ALICE_POLKADOT: &str = "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5";
+	const ALICE_KUSAMA: &str = "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F";
+	const ALICE_HEX: &str = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+	#[test]
+	fn parse_works() -> Result<(), Error> {
+		let alice = Account::parse(ALICE)?;
+		for address in [ALICE_POLKADOT, ALICE_KUSAMA, ALICE_HEX] {
+			assert_eq!(Account::parse(address)?, alice);
+		}
+		assert_eq!(alice.to_hex(), ALICE_HEX);
+		assert_eq!(alice.to_ss58(0), ALICE_POLKADOT);
+		assert_eq!(alice.to_ss58(2), ALICE_KUSAMA);
+		assert_eq!(alice.to_string(), ALICE);
+		assert_eq!(
+			Account::parse("0x52908400098527886E0F7030069857D2E4169EE7")?,
+			Account::Id20([
+				0x52, 0x90, 0x84, 0x00, 0x09, 0x85, 0x27, 0x88, 0x6e, 0x0f, 0x70, 0x30, 0x06, 0x98,
+				0x57, 0xd2, 0xe4, 0x16, 0x9e, 0xe7
+			])
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_fails() {
+		assert!(
+			matches!(Account::parse("alice"), Err(Error::Account(e)) if e.starts_with("`alice` is not a valid address"))
+		);
+		assert!(
+			matches!(Account::parse("0x1234"), Err(Error::Account(e)) if e.contains("2 bytes"))
+		);
+		assert!(
+			matches!(Account::parse("0xzz"), Err(Error::Account(e)) if e.contains("not valid hex"))
+		);
+	}
+
+	#[test]
+	fn conversions_work() -> Result<(), Error> {
+		let account = Account::parse("0x52908400098527886e0f7030069857d2e4169ee7")?;
+		let mapped = account.to_id32();
+		assert!(mapped.ends_with(&MAPPED_SUFFIX));
+		assert_eq!(Account::Id32(mapped).id20()?, account.to_id20());
+		assert!(
+			matches!(account.id32(), Err(Error::Account(e)) if e.contains("did you mean its mapped account 0x52908400098527886e0f7030069857d2e4169ee7eeeeeeeeeeeeeeeeeeeeeeee"))
+		);
+
+		let alice = Account::parse(ALICE)?;
+		assert_eq!(alice.to_id20(), keccak_256(&alice.to_id32())[12..]);
+		assert!(matches!(alice.id20(), Err(Error::Account(e)) if e.contains("32-byte account")));
+		assert_eq!(parse_account(ALICE_KUSAMA)?, alice.to_id32());
+		Ok(())
+	}
+
+	#[test]
+	fn validate_address_works() -> Result<(), Error> {
+		assert_eq!(validate_address(ALICE_POLKADOT, 0)?, Account::parse(ALICE)?);
+		assert_eq!(validate_address(ALICE_HEX, 0)?, Account::parse(ALICE)?);
+		assert!(matches!(
+			validate_address(ALICE_KUSAMA, 0),
+			Err(Error::Account(e)) if e == format!("this is a Kusama-format address (prefix 2); did you mean {ALICE_POLKADOT}?")
+		));
+		assert!(matches!(
+			validate_address(ALICE, 0),
+			Err(Error::Account(e)) if e.starts_with("this is a Substrate-format address (prefix 42)")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn network_works() {
+		assert_eq!(network(0), "a Polkadot-format");
+		assert_eq!(network(2), "a Kusama-format");
+		assert_eq!(network(16_000), "a prefix-16000");
+	}
+}
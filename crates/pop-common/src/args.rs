@@ -0,0 +1,776 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{helpers::decode_hex, Account, Error};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use subxt::ext::scale_value::{BitSequence, Composite, Primitive, Value, ValueDef, Variant};
+
+/// Parses an argument of a call (e.g. an extrinsic or contract message), validating it against
+/// its type within the metadata.
+///
+/// Arguments are specified in a JSON5 or Rust struct-literal style, so that complex types can be
+/// composed without encoding them manually:
+/// * structs - `{ x: 1, y: 2 }` or `Point { x: 1, y: 2 }`;
+/// * enums - `Id("5GrwvaEF...")`, `Transfer { amount: 10 }`, `"Unit"` or `{ "Id": "0x.." }`;
+/// * sequences, arrays and tuples - `[1, 2, 3]` or `(1, true)`;
+/// * options - `null`/`None`, `Some(1)` or simply `1`;
+/// * bytes - `"0x0102"`, text or, for 32 and 20-byte arrays, an account address.
+///
+/// # Arguments
+/// * `name` - The name of the argument, used to locate any error.
+/// * `input` - The argument, as specified.
+/// * `ty` - The type of the argument within the registry.
+/// * `types` - The registry of types of the metadata.
+pub fn parse_arg(
+	name: &str,
+	input: &str,
+	ty: u32,
+	types: &PortableRegistry,
+) -> Result<Value, Error> {
+	let value = Parser::new(input).parse().map_err(|e| invalid(name, e))?;
+	normalize(value, ty, types, name)
+}
+
+/// A parser of arguments specified in a JSON5 or Rust struct-literal style.
+struct Parser<'a> {
+	/// The input being parsed.
+	input: &'a str,
+	/// The position within the input.
+	position: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn new(input: &'a str) -> Self {
+		Self { input, position: 0 }
+	}
+
+	/// Parses the input as a single value.
+	fn parse(mut self) -> Result<Value, String> {
+		let value = self.value()?;
+		self.skip();
+		match self.peek() {
+			Some(_) => Err(self.unexpected("the end of the input")),
+			None => Ok(value),
+		}
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.input[self.position..].chars().next()
+	}
+
+	fn bump(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.position += c.len_utf8();
+		Some(c)
+	}
+
+	/// Skips any whitespace and comments.
+	fn skip(&mut self) {
+		loop {
+			let rest = &self.input[self.position..];
+			let trimmed = rest.trim_start();
+			self.position += rest.len() - trimmed.len();
+			if trimmed.starts_with("//") {
+				self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+			} else if let Some(comment) = trimmed.strip_prefix("/*") {
+				self.position += comment.find("*/").map_or(trimmed.len(), |end| end + 4);
+			} else {
+				return;
+			}
+		}
+	}
+
+	fn unexpected(&self, expected: &str) -> String {
+		match self.peek() {
+			Some(c) => format!("expected {expected}, found `{c}` at position {}", self.position),
+			None => format!("expected {expected}, found the end of the input"),
+		}
+	}
+
+	fn expect(&mut self, expected: char) -> Result<(), String> {
+		self.skip();
+		if self.peek() != Some(expected) {
+			return Err(self.unexpected(&format!("`{expected}`")));
+		}
+		self.bump();
+		Ok(())
+	}
+
+	fn value(&mut self) -> Result<Value, String> {
+		self.skip();
+		match self.peek() {
+			Some('[') => self.items('[', ']').map(Value::unnamed_composite),
+			Some('(') => self.items('(', ')').map(Value::unnamed_composite),
+			Some('{') => self.fields().map(Value::named_composite),
+			Some(quote @ ('"' | '\'')) => self.string(quote).map(Value::string),
+			Some(c) if c.is_ascii_digit() || c == '-' || c == '+' => self.number(),
+			Some(c) if c.is_alphabetic() || c == '_' => self.ident_value(),
+			_ => Err(self.unexpected("a value")),
+		}
+	}
+
+	/// Parses a comma-separated list of values, allowing a trailing comma.
+	fn items(&mut self, open: char, close: char) -> Result<Vec<Value>, String> {
+		self.expect(open)?;
+		let mut items = vec![];
+		loop {
+			self.skip();
+			if self.peek() == Some(close) {
+				self.bump();
+				return Ok(items);
+			}
+			items.push(self.value()?);
+			self.skip();
+			match self.peek() {
+				Some(',') => {
+					self.bump();
+				},
+				Some(c) if c == close => {},
+				_ => return Err(self.unexpected(&format!("`,` or `{close}`"))),
+			}
+		}
+	}
+
+	/// Parses a comma-separated list of fields, whose names may be quoted.
+	fn fields(&mut self) -> Result<Vec<(String, Value)>, String> {
+		self.expect('{')?;
+		let mut fields: Vec<(String, Value)> = vec![];
+		loop {
+			self.skip();
+			let name = match self.peek() {
+				Some('}') => {
+					self.bump();
+					return Ok(fields);
+				},
+				Some(quote @ ('"' | '\'')) => self.string(quote)?,
+				Some(c) if c.is_alphanumeric() || c == '_' => self.ident(),
+				_ => return Err(self.unexpected("a field name")),
+			};
+			if fields.iter().any(|(field, _)| *field == name) {
+				return Err(format!("the `{name}` field is specified more than once"));
+			}
+			self.expect(':')?;
+			fields.push((name, self.value()?));
+			self.skip();
+			match self.peek() {
+				Some(',') => {
+					self.bump();
+				},
+				Some('}') => {},
+				_ => return Err(self.unexpected("`,` or `}`")),
+			}
+		}
+	}
+
+	fn ident(&mut self) -> String {
+		let start = self.position;
+		while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+			self.bump();
+		}
+		self.input[start..self.position].to_string()
+	}
+
+	/// Parses a keyword, or a variant or struct literal (e.g. `Some(1)` or `Point { x: 1 }`).
+	fn ident_value(&mut self) -> Result<Value, String> {
+		let mut name = self.ident();
+		// Paths (e.g. `MultiAddress::Id`) are resolved by their last segment.
+		while self.input[self.position..].starts_with("::") {
+			self.position += 2;
+			name = self.ident();
+			if name.is_empty() {
+				return Err(self.unexpected("an identifier"));
+			}
+		}
+		match name.as_str() {
+			"true" => return Ok(Value::bool(true)),
+			"false" => return Ok(Value::bool(false)),
+			"null" => return Ok(Value::unnamed_variant("None", [])),
+			_ => {},
+		}
+		self.skip();
+		match self.peek() {
+			Some('(') => Ok(Value::unnamed_variant(name, self.items('(', ')')?)),
+			Some('{') => Ok(Value::named_variant(name, self.fields()?)),
+			_ => Ok(Value::unnamed_variant(name, [])),
+		}
+	}
+
+	/// Parses an integer, allowing Rust-style underscore separators. Unquoted hex (e.g. `0x01`)
+	/// and addresses are parsed as strings.
+	fn number(&mut self) -> Result<Value, String> {
+		let start = self.position;
+		let sign = self.peek().filter(|c| matches!(c, '-' | '+'));
+		if sign.is_some() {
+			self.bump();
+		}
+		let literal = self.ident();
+		if self.peek() == Some('.') {
+			return Err(format!("decimal numbers are not supported, found at position {start}"));
+		}
+		let digits = literal.replace('_', "");
+		if digits.is_empty() {
+			return Err(self.unexpected("a number"));
+		}
+		if !digits.chars().all(|c| c.is_ascii_digit()) {
+			return match sign {
+				None => Ok(Value::string(literal)),
+				Some(_) => Err(format!("`{}` is not a number", &self.input[start..self.position])),
+			};
+		}
+		let out_of_range = |_| format!("`{}` is out of range", &self.input[start..self.position]);
+		match sign {
+			Some('-') => format!("-{digits}").parse().map(Value::i128).map_err(out_of_range),
+			_ => digits.parse().map(Value::u128).map_err(out_of_range),
+		}
+	}
+
+	/// Parses a string delimited by the specified quote, unescaping any escaped characters.
+	fn string(&mut self, quote: char) -> Result<String, String> {
+		let start = self.position;
+		self.bump();
+		let mut string = String::new();
+		while let Some(c) = self.bump() {
+			match c {
+				c if c == quote => return Ok(string),
+				'\\' => string.push(match self.bump() {
+					Some('n') => '\n',
+					Some('r') => '\r',
+					Some('t') => '\t',
+					Some('0') => '\0',
+					Some('u') => {
+						let code = self.input.get(self.position..self.position + 4);
+						let c = code
+							.and_then(|code| u32::from_str_radix(code, 16).ok())
+							.and_then(char::from_u32)
+							.ok_or_else(|| {
+								format!("invalid unicode escape at position {}", self.position)
+							})?;
+						self.position += 4;
+						c
+					},
+					Some(c) => c,
+					None => break,
+				}),
+				c => string.push(c),
+			}
+		}
+		Err(format!("unterminated string starting at position {start}"))
+	}
+}
+
+fn invalid(path: &str, message: impl Into<String>) -> Error {
+	Error::Argument { path: path.to_string(), message: message.into() }
+}
+
+/// Describes a value, for use within errors.
+fn describe(value: &Value) -> String {
+	match &value.value {
+		ValueDef::Composite(Composite::Named(_)) => "a struct".into(),
+		ValueDef::Composite(Composite::Unnamed(_)) => "a list".into(),
+		ValueDef::Variant(variant) => format!("the `{}` variant", variant.name),
+		ValueDef::BitSequence(_) => "a bit sequence".into(),
+		ValueDef::Primitive(Primitive::Bool(_)) => "a bool".into(),
+		ValueDef::Primitive(Primitive::Char(_) | Primitive::String(_)) => "a string".into(),
+		ValueDef::Primitive(_) => "a number".into(),
+	}
+}
+
+fn composite(composite: Composite<()>) -> Value {
+	Value { value: ValueDef::Composite(composite), context: () }
+}
+
+/// Validates a value against its type, normalising it into the shape expected by the type so that
+/// it can be encoded.
+///
+/// # Arguments
+/// * `value` - The value.
+/// * `ty` - The type of the value within the registry.
+/// * `types` - The registry of types of the metadata.
+/// * `path` - The location of the value within the argument.
+fn normalize(value: Value, ty: u32, types: &PortableRegistry, path: &str) -> Result<Value, Error> {
+	let Some(resolved) = types.resolve(ty) else {
+		return Err(invalid(path, format!("type {ty} was not found within the metadata")));
+	};
+	match &resolved.type_def {
+		TypeDef::Composite(def) => {
+			// Struct literals may specify the name of the type (e.g. `Point { x: 1 }`).
+			let value = match value.value {
+				ValueDef::Variant(Variant { name, values })
+					if resolved.path.ident().is_some_and(|ident| ident == name) =>
+					composite(values),
+				value => Value { value, context: () },
+			};
+			normalize_fields(&def.fields, value, types, path).map(composite)
+		},
+		TypeDef::Variant(def) => {
+			let variant = |name: &str| def.variants.iter().find(|v| v.name == name);
+			// Options accept their value directly, so `1` is equivalent to `Some(1)`.
+			if resolved.path.ident().is_some_and(|ident| ident == "Option") &&
+				!matches!(&value.value, ValueDef::Variant(v) if variant(&v.name).is_some())
+			{
+				if let Some(some) = variant("Some").and_then(|v| v.fields.first()) {
+					let path = format!("{path}.Some");
+					return Ok(Value::unnamed_variant(
+						"Some",
+						[normalize(value, some.ty.id, types, &path)?],
+					));
+				}
+			}
+			let description = describe(&value);
+			let (name, fields) = match value.value {
+				ValueDef::Variant(Variant { name, values }) => (name, composite(values)),
+				// Unit variants may be specified by name, as within JSON.
+				ValueDef::Primitive(Primitive::String(name)) =>
+					(name, Value::unnamed_composite([])),
+				// Variants may also be specified as a single field, named by the variant.
+				ValueDef::Composite(Composite::Named(mut fields)) if fields.len() == 1 =>
+					fields.remove(0),
+				_ => {
+					let names: Vec<_> =
+						def.variants.iter().map(|v| format!("`{}`", v.name)).collect();
+					return Err(invalid(
+						path,
+						format!(
+							"expected one of the variants {}, found {description}",
+							names.join(", ")
+						),
+					));
+				},
+			};
+			let Some(variant) = variant(&name) else {
+				let names: Vec<_> = def.variants.iter().map(|v| format!("`{}`", v.name)).collect();
+				return Err(invalid(
+					path,
+					format!("unknown variant `{name}`, expected one of {}", names.join(", ")),
+				));
+			};
+			let path = format!("{path}.{name}");
+			Ok(Value::variant(name, normalize_fields(&variant.fields, fields, types, &path)?))
+		},
+		TypeDef::Sequence(def) => normalize_items(value, def.type_param.id, None, types, path)
+			.map(Value::unnamed_composite),
+		TypeDef::Array(def) =>
+			normalize_items(value, def.type_param.id, Some(def.len as usize), types, path)
+				.map(Value::unnamed_composite),
+		TypeDef::Tuple(def) => {
+			let types_ids: Vec<_> = def.fields.iter().map(|f| f.id).collect();
+			normalize_unnamed(&types_ids, value, types, path).map(Value::unnamed_composite)
+		},
+		TypeDef::Primitive(primitive) => normalize_primitive(value, primitive, path),
+		TypeDef::Compact(def) => normalize(value, def.type_param.id, types, path),
+		TypeDef::BitSequence(_) => {
+			let description = describe(&value);
+			let bits = match value.value {
+				ValueDef::Composite(Composite::Unnamed(values)) => values
+					.iter()
+					.enumerate()
+					.map(|(i, value)| match &value.value {
+						ValueDef::Primitive(Primitive::Bool(bit)) => Ok(*bit),
+						ValueDef::Primitive(Primitive::U128(bit @ (0 | 1))) => Ok(*bit == 1),
+						_ => Err(invalid(
+							&format!("{path}[{i}]"),
+							format!("expected a bit, found {}", describe(value)),
+						)),
+					})
+					.collect::<Result<BitSequence, _>>()?,
+				_ =>
+					return Err(invalid(
+						path,
+						format!("expected a list of bits, found {description}"),
+					)),
+			};
+			Ok(Value::bit_sequence(bits))
+		},
+	}
+}
+
+/// Validates the fields of a struct or variant.
+fn normalize_fields(
+	fields: &[Field<PortableForm>],
+	value: Value,
+	types: &PortableRegistry,
+	path: &str,
+) -> Result<Composite<()>, Error> {
+	if fields.is_empty() || fields.iter().any(|f| f.name.is_none()) {
+		let type_ids: Vec<_> = fields.iter().map(|f| f.ty.id).collect();
+		return normalize_unnamed(&type_ids, value, types, path).map(Composite::Unnamed);
+	}
+	let names: Vec<_> = fields.iter().filter_map(|f| f.name.as_ref()).collect();
+	let description = describe(&value);
+	let ValueDef::Composite(Composite::Named(mut values)) = value.value else {
+		let names: Vec<_> = names.iter().map(|name| format!("`{name}`")).collect();
+		return Err(invalid(
+			path,
+			format!("expected a struct with the fields {}, found {description}", names.join(", ")),
+		));
+	};
+	let mut normalized = Vec::with_capacity(fields.len());
+	for (field, name) in fields.iter().zip(&names) {
+		let path = format!("{path}.{name}");
+		let Some(index) = values.iter().position(|(n, _)| n == *name) else {
+			return Err(invalid(&path, "the field is missing"));
+		};
+		let (_, value) = values.remove(index);
+		normalized.push((name.to_string(), normalize(value, field.ty.id, types, &path)?));
+	}
+	if let Some((name, _)) = values.first() {
+		let names: Vec<_> = names.iter().map(|name| format!("`{name}`")).collect();
+		return Err(invalid(
+			&format!("{path}.{name}"),
+			format!("unknown field, expected one of {}", names.join(", ")),
+		));
+	}
+	Ok(Composite::Named(normalized))
+}
+
+/// Validates the unnamed fields of a tuple, struct or variant.
+fn normalize_unnamed(
+	type_ids: &[u32],
+	value: Value,
+	types: &PortableRegistry,
+	path: &str,
+) -> Result<Vec<Value>, Error> {
+	// A single field is transparent, so that a wrapper (e.g. `AccountId([u8; 32])`) accepts the
+	// value it wraps as well as the value wrapped explicitly (e.g. `Id(..)`).
+	if let [ty] = type_ids {
+		let wrapped = match &value.value {
+			ValueDef::Composite(Composite::Unnamed(values)) if values.len() == 1 =>
+				Some(values[0].clone()),
+			_ => None,
+		};
+		return match wrapped {
+			Some(wrapped) => normalize(wrapped, *ty, types, path)
+				.or_else(|e| normalize(value, *ty, types, path).map_err(|_| e)),
+			None => normalize(value, *ty, types, path),
+		}
+		.map(|value| vec![value]);
+	}
+	let description = describe(&value);
+	let values = match value.value {
+		ValueDef::Composite(Composite::Unnamed(values)) => values,
+		ValueDef::Composite(Composite::Named(values)) if values.is_empty() => vec![],
+		_ => {
+			let expected = match type_ids.len() {
+				0 => "no values".to_string(),
+				len => format!("a list of {len} values"),
+			};
+			return Err(invalid(path, format!("expected {expected}, found {description}")));
+		},
+	};
+	if values.len() != type_ids.len() {
+		return Err(invalid(
+			path,
+			format!("expected {} values, found {}", type_ids.len(), values.len()),
+		));
+	}
+	values
+		.into_iter()
+		.zip(type_ids)
+		.enumerate()
+		.map(|(i, (value, ty))| normalize(value, *ty, types, &format!("{path}[{i}]")))
+		.collect()
+}
+
+/// Validates the items of a sequence or array, whose bytes may also be specified as a string.
+fn normalize_items(
+	value: Value,
+	ty: u32,
+	len: Option<usize>,
+	types: &PortableRegistry,
+	path: &str,
+) -> Result<Vec<Value>, Error> {
+	let is_bytes = types
+		.resolve(ty)
+		.is_some_and(|t| matches!(t.type_def, TypeDef::Primitive(TypeDefPrimitive::U8)));
+	let description = describe(&value);
+	let values = match value.value {
+		ValueDef::Primitive(Primitive::String(string)) if is_bytes =>
+			bytes(&string, len, path)?.into_iter().map(|b| Value::u128(b as u128)).collect(),
+		ValueDef::Composite(Composite::Unnamed(values)) => values
+			.into_iter()
+			.enumerate()
+			.map(|(i, value)| normalize(value, ty, types, &format!("{path}[{i}]")))
+			.collect::<Result<_, _>>()?,
+		ValueDef::Composite(Composite::Named(values)) if values.is_empty() => vec![],
+		_ => return Err(invalid(path, format!("expected a list, found {description}"))),
+	};
+	match len {
+		Some(len) if values.len() != len =>
+			Err(invalid(path, format!("expected {len} items, found {}", values.len()))),
+		_ => Ok(values),
+	}
+}
+
+/// The bytes specified by a string, being either hex, an account address when an account is
+/// expected, or otherwise text.
+fn bytes(string: &str, len: Option<usize>, path: &str) -> Result<Vec<u8>, Error> {
+	if let Some(hex) = string.strip_prefix("0x") {
+		return decode_hex(hex).ok_or_else(|| invalid(path, format!("`{string}` is not valid hex")));
+	}
+	match len {
+		Some(32) => Account::parse(string)
+			.and_then(|account| account.id32())
+			.map(|account| account.to_vec())
+			.map_err(|e| invalid(path, e.to_string())),
+		Some(20) => Account::parse(string)
+			.and_then(|account| account.id20())
+			.map(|account| account.to_vec())
+			.map_err(|e| invalid(path, e.to_string())),
+		_ => Ok(string.as_bytes().to_vec()),
+	}
+}
+
+/// Validates a primitive value, including the range of integers, which may also be specified as
+/// strings to avoid the loss of precision of large numbers within JSON.
+fn normalize_primitive(
+	value: Value,
+	primitive: &TypeDefPrimitive,
+	path: &str,
+) -> Result<Value, Error> {
+	use TypeDefPrimitive::*;
+	let expected = match primitive {
+		Str => "`string`".to_string(),
+		primitive => format!("`{}`", format!("{primitive:?}").to_lowercase()),
+	};
+	let description = describe(&value);
+	let (min, max): (i128, u128) = match primitive {
+		U8 => (0, u8::MAX.into()),
+		U16 => (0, u16::MAX.into()),
+		U32 => (0, u32::MAX.into()),
+		U64 => (0, u64::MAX.into()),
+		U128 | U256 => (0, u128::MAX),
+		I8 => (i8::MIN.into(), i8::MAX as u128),
+		I16 => (i16::MIN.into(), i16::MAX as u128),
+		I32 => (i32::MIN.into(), i32::MAX as u128),
+		I64 => (i64::MIN.into(), i64::MAX as u128),
+		I128 | I256 => (i128::MIN, i128::MAX as u128),
+		Bool | Char | Str => {
+			return match (primitive, value.value) {
+				(Bool, ValueDef::Primitive(Primitive::Bool(b))) => Ok(Value::bool(b)),
+				(Char, ValueDef::Primitive(Primitive::Char(c))) => Ok(Value::char(c)),
+				(Char, ValueDef::Primitive(Primitive::String(s))) if s.chars().count() == 1 =>
+					Ok(Value::char(s.chars().next().expect("length checked"))),
+				(Str, ValueDef::Primitive(Primitive::String(s))) => Ok(Value::string(s)),
+				_ => Err(invalid(path, format!("expected {expected}, found {description}"))),
+			};
+		},
+	};
+	let number = match value.value {
+		ValueDef::Primitive(Primitive::U128(n)) => Ok(n.to_string()),
+		ValueDef::Primitive(Primitive::I128(n)) => Ok(n.to_string()),
+		ValueDef::Primitive(Primitive::String(s)) => Ok(s.replace('_', "")),
+		_ => Err(invalid(path, format!("expected {expected}, found {description}"))),
+	}?;
+	let out_of_range = || invalid(path, format!("`{number}` is out of range for {expected}"));
+	match number.strip_prefix('-') {
+		Some(_) => {
+			let n: i128 = number.parse().map_err(|_| out_of_range())?;
+			if n < min {
+				return Err(out_of_range());
+			}
+			Ok(Value::i128(n))
+		},
+		None => {
+			let n: u128 = number
+				.parse()
+				.map_err(|_| invalid(path, format!("`{number}` is not {expected}")))?;
+			if n > max {
+				return Err(out_of_range());
+			}
+			Ok(if min < 0 { Value::i128(n as i128) } else { Value::u128(n) })
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{
+		build::{Fields, Variants},
+		meta_type, Path, Registry, Type, TypeInfo,
+	};
+
+	struct Point;
+
+	impl TypeInfo for Point {
+		type Identity = Self;
+
+		fn type_info() -> Type {
+			Type::builder().path(Path::new("Point", "tests")).composite(
+				Fields::named()
+					.field(|f| f.ty::<u32>().name("x"))
+					.field(|f| f.ty::<Option<String>>().name("label")),
+			)
+		}
+	}
+
+	struct Address;
+
+	impl TypeInfo for Address {
+		type Identity = Self;
+
+		fn type_info() -> Type {
+			Type::builder().path(Path::new("Address", "tests")).variant(
+				Variants::new()
+					.variant("Id", |v| {
+						v.index(0).fields(Fields::unnamed().field(|f| f.ty::<[u8; 32]>()))
+					})
+					.variant("Index", |v| {
+						v.index(1).fields(Fields::unnamed().field(|f| f.compact::<u32>()))
+					})
+					.variant("At", |v| {
+						v.index(2).fields(Fields::named().field(|f| f.ty::<Point>().name("point")))
+					})
+					.variant_unit("Any", 3),
+			)
+		}
+	}
+
+	fn registry<T: TypeInfo + 'static>() -> (u32, PortableRegistry) {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<T>());
+		(ty.id, registry.into())
+	}
+
+	fn parse<T: TypeInfo + 'static>(input: &str) -> Result<Value, Error> {
+		let (ty, types) = registry::<T>();
+		parse_arg("arg", input, ty, &types)
+	}
+
+	fn error<T: TypeInfo + 'static>(input: &str) -> (String, String) {
+		match parse::<T>(input) {
+			Err(Error::Argument { path, message }) => (path, message),
+			result => panic!("expected an argument error, found {result:?}"),
+		}
+	}
+
+	#[test]
+	fn parser_works() -> Result<(), String> {
+		let parse = |input| Parser::new(input).parse();
+		assert_eq!(
+			parse("{ x: 1, 'label': \"a\\n\\u0062\", /* comment */ }")?,
+			Value::named_composite([("x", Value::u128(1)), ("label", Value::string("a\nb"))])
+		);
+		assert_eq!(
+			parse("[-1, 1_000, true, null] // comment")?,
+			Value::unnamed_composite([
+				Value::i128(-1),
+				Value::u128(1000),
+				Value::bool(true),
+				Value::unnamed_variant("None", []),
+			])
+		);
+		assert_eq!(
+			parse("Address::Id(0x01)")?,
+			Value::unnamed_variant("Id", [Value::string("0x01")])
+		);
+		assert_eq!(
+			parse("Point { x: 1 }")?,
+			Value::named_variant("Point", [("x", Value::u128(1))])
+		);
+		assert_eq!(parse("None")?, Value::unnamed_variant("None", []));
+		assert!(parse("[1, 2").unwrap_err().contains("found the end of the input"));
+		assert!(parse("{ x 1 }").unwrap_err().contains("expected `:`, found `1` at position 4"));
+		assert!(parse("{ x: 1, x: 2 }").unwrap_err().contains("more than once"));
+		assert!(parse("1.5").unwrap_err().contains("decimal"));
+		assert!(parse("\"a").unwrap_err().contains("unterminated"));
+		assert!(parse("1 2").unwrap_err().contains("expected the end of the input"));
+		Ok(())
+	}
+
+	#[test]
+	fn parse_arg_works() -> Result<(), Error> {
+		let point = |label: Value| {
+			composite(Composite::Named(vec![("x".into(), Value::u128(1)), ("label".into(), label)]))
+		};
+		let some = |s: &str| Value::unnamed_variant("Some", [Value::string(s)]);
+		assert_eq!(
+			parse::<Point>("{ x: 1, label: null }")?,
+			point(Value::unnamed_variant("None", []))
+		);
+		// Struct literals, quoted numbers and implicit options.
+		assert_eq!(parse::<Point>("Point { label: 'a', x: '1' }")?, point(some("a")));
+		assert_eq!(parse::<Point>(r#"{ "x": 1, "label": Some("a") }"#)?, point(some("a")));
+
+		let account = [1u8; 32];
+		let id = Value::unnamed_variant(
+			"Id",
+			[Value::unnamed_composite(account.map(|b| Value::u128(b.into())))],
+		);
+		assert_eq!(parse::<Address>(&format!("Id(\"0x{}\")", "01".repeat(32)))?, id);
+		assert_eq!(parse::<Address>(&format!("{{ Id: \"0x{}\" }}", "01".repeat(32)))?, id);
+		assert_eq!(
+			parse::<Address>("Index(1)")?,
+			Value::unnamed_variant("Index", [Value::u128(1)])
+		);
+		assert_eq!(parse::<Address>("\"Any\"")?, Value::unnamed_variant("Any", []));
+		assert_eq!(
+			parse::<Address>("At { point: { x: 1, label: 'a' } }")?,
+			Value::named_variant("At", [("point", point(some("a")))])
+		);
+		// Accounts may be specified by their address.
+		let alice = parse::<[u8; 32]>("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")?;
+		assert_eq!(
+			alice,
+			Value::unnamed_composite(
+				Account::parse("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")?
+					.id32()?
+					.map(|b| Value::u128(b.into()))
+			)
+		);
+		assert_eq!(
+			parse::<Vec<u8>>("'hi'")?,
+			Value::unnamed_composite([Value::u128(104), Value::u128(105)])
+		);
+		assert_eq!(
+			parse::<(i8, bool)>("[-1, true]")?,
+			Value::unnamed_composite([Value::i128(-1), Value::bool(true)])
+		);
+		assert_eq!(parse::<Option<u32>>("5")?, Value::unnamed_variant("Some", [Value::u128(5)]));
+		assert_eq!(parse::<Option<u32>>("None")?, Value::unnamed_variant("None", []));
+		Ok(())
+	}
+
+	#[test]
+	fn parse_arg_locates_errors() {
+		assert_eq!(
+			error::<Point>("{ label: null }"),
+			("arg.x".into(), "the field is missing".into())
+		);
+		assert_eq!(
+			error::<Point>("{ x: 1, label: null, y: 2 }"),
+			("arg.y".into(), "unknown field, expected one of `x`, `label`".into())
+		);
+		assert_eq!(
+			error::<Point>("{ x: 1, label: 2 }"),
+			("arg.label.Some".into(), "expected `string`, found a number".into())
+		);
+		assert_eq!(
+			error::<Point>("[1, 2]"),
+			("arg".into(), "expected a struct with the fields `x`, `label`, found a list".into())
+		);
+		assert_eq!(
+			error::<Address>("At { point: { x: -1, label: null } }"),
+			("arg.At.point.x".into(), "`-1` is out of range for `u32`".into())
+		);
+		assert_eq!(
+			error::<Address>("Other"),
+			(
+				"arg".into(),
+				"unknown variant `Other`, expected one of `Id`, `Index`, `At`, `Any`".into()
+			)
+		);
+		assert_eq!(
+			error::<Address>("Id(\"0x01\")"),
+			("arg.Id".into(), "expected 32 items, found 1".into())
+		);
+		assert_eq!(
+			error::<Vec<u16>>("[1, 70000]"),
+			("arg[1]".into(), "`70000` is out of range for `u16`".into())
+		);
+		assert_eq!(error::<(u8, u8)>("(1)"), ("arg".into(), "expected 2 values, found 1".into()));
+		assert_eq!(
+			error::<u8>("[1"),
+			("arg".into(), "expected `,` or `]`, found the end of the input".into())
+		);
+	}
+}
@@ -0,0 +1,362 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{i18n::message, Error};
+use duct::cmd;
+use serde::{Deserialize, Serialize};
+use std::{
+	fs::{create_dir_all, read_to_string, write},
+	path::{Path, PathBuf},
+};
+
+/// The name of the file within which the results of the setup are persisted.
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// The relay chains which can be selected as the default.
+const RELAY_CHAINS: [(&str, &str); 4] = [
+	("paseo-local", "A local Paseo relay chain"),
+	("rococo-local", "A local Rococo relay chain"),
+	("westend-local", "A local Westend relay chain"),
+	("polkadot-local", "A local Polkadot relay chain"),
+];
+
+/// The settings resulting from the first-run setup.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Settings {
+	/// Whether anonymous usage metrics may be collected.
+	pub telemetry: bool,
+	/// The location of the cache.
+	pub cache: PathBuf,
+	/// The default relay chain used when launching local networks.
+	pub relay_chain: String,
+}
+
+impl Settings {
+	/// Loads the settings persisted within a directory, if the setup has been completed.
+	///
+	/// # Arguments
+	/// * `dir` - The configuration directory.
+	pub fn load(dir: &Path) -> Result<Option<Self>, Error> {
+		let path = dir.join(SETTINGS_FILE);
+		if !path.exists() {
+			return Ok(None);
+		}
+		let settings = serde_json::from_str(&read_to_string(&path)?)
+			.map_err(|e| Error::Config(format!("invalid settings in {}: {e}", path.display())))?;
+		Ok(Some(settings))
+	}
+
+	/// Persists the settings within a directory.
+	///
+	/// # Arguments
+	/// * `dir` - The configuration directory.
+	pub fn save(&self, dir: &Path) -> Result<PathBuf, Error> {
+		create_dir_all(dir)?;
+		let path = dir.join(SETTINGS_FILE);
+		let contents =
+			serde_json::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+		write(&path, contents)?;
+		Ok(path)
+	}
+}
+
+/// Whether this is the first run, with the setup yet to be completed.
+///
+/// # Arguments
+/// * `dir` - The configuration directory.
+pub fn is_first_run(dir: &Path) -> bool {
+	!dir.join(SETTINGS_FILE).exists()
+}
+
+/// The identifier of a setup step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepId {
+	/// Consent to the collection of anonymous usage metrics.
+	Telemetry,
+	/// The location of the cache.
+	Cache,
+	/// The default relay chain.
+	RelayChain,
+	/// Checks of the required toolchain.
+	Toolchain,
+}
+
+/// The input expected by a setup step.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+	/// A yes/no confirmation.
+	Confirm {
+		/// The default answer.
+		default: bool,
+	},
+	/// Free text.
+	Text {
+		/// The default answer.
+		default: String,
+	},
+	/// A selection from a list of options, as (value, description) pairs.
+	Select {
+		/// The options.
+		options: Vec<(String, String)>,
+		/// The value selected by default.
+		default: String,
+	},
+	/// A report of toolchain checks, requiring acknowledgement only.
+	Report(Vec<ToolCheck>),
+}
+
+/// A step of the first-run setup, to be presented by a frontend.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+	/// The identifier of the step.
+	pub id: StepId,
+	/// The prompt to be displayed.
+	pub prompt: String,
+	/// The input expected.
+	pub input: Input,
+}
+
+/// An answer to a setup step.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+	/// An answer to a confirmation.
+	Confirm(bool),
+	/// Free text or a selected value.
+	Text(String),
+	/// Acknowledgement of a report.
+	Acknowledge,
+}
+
+/// The result of checking for a required tool.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolCheck {
+	/// The name of the tool.
+	pub name: String,
+	/// The version found, if installed.
+	pub version: Option<String>,
+	/// How the tool can be installed.
+	pub hint: String,
+}
+
+impl ToolCheck {
+	/// Checks whether a tool is installed by running it with the specified arguments.
+	///
+	/// # Arguments
+	/// * `name` - The name of the tool.
+	/// * `args` - The arguments used to query its version.
+	/// * `hint` - How the tool can be installed.
+	fn run(name: &str, args: &[&str], hint: &str) -> Self {
+		let version = cmd(name, args)
+			.stderr_null()
+			.read()
+			.ok()
+			.and_then(|output| output.lines().next().map(|line| line.trim().to_string()));
+		Self { name: name.to_string(), version, hint: hint.to_string() }
+	}
+
+	/// Whether the tool is installed.
+	pub fn installed(&self) -> bool {
+		self.version.is_some()
+	}
+}
+
+/// Checks for the tools required by pop.
+pub fn check_toolchain() -> Vec<ToolCheck> {
+	let mut checks = vec![
+		ToolCheck::run("rustc", &["--version"], "install Rust via https://rustup.rs"),
+		ToolCheck::run("cargo", &["--version"], "install Rust via https://rustup.rs"),
+		ToolCheck::run(
+			"protoc",
+			&["--version"],
+			"install the protobuf compiler, e.g. `pop install`",
+		),
+	];
+	let wasm = cmd("rustup", ["target", "list", "--installed"])
+		.stderr_null()
+		.read()
+		.ok()
+		.filter(|targets| targets.lines().any(|t| t.trim() == "wasm32-unknown-unknown"))
+		.map(|_| "installed".to_string());
+	checks.push(ToolCheck {
+		name: "wasm32-unknown-unknown".into(),
+		version: wasm,
+		hint: "run `rustup target add wasm32-unknown-unknown`".into(),
+	});
+	checks
+}
+
+/// Drives the first-run setup, presenting a sequence of steps and collecting the answers.
+pub struct Bootstrap {
+	/// The configuration directory within which the settings are persisted.
+	dir: PathBuf,
+	/// The settings, updated as steps are answered.
+	settings: Settings,
+	/// The steps, in order.
+	steps: Vec<Step>,
+	/// The index of the current step.
+	current: usize,
+}
+
+impl Bootstrap {
+	/// Starts the setup, using any previously persisted settings as defaults.
+	///
+	/// # Arguments
+	/// * `dir` - The configuration directory within which the settings are persisted.
+	/// * `default_cache` - The default location of the cache.
+	pub fn new(dir: &Path, default_cache: PathBuf) -> Result<Self, Error> {
+		let settings = Settings::load(dir)?.unwrap_or(Settings {
+			// Usage metrics are only shared once consent has been given.
+			telemetry: false,
+			cache: default_cache,
+			relay_chain: RELAY_CHAINS[0].0.to_string(),
+		});
+		let steps = vec![
+			Step {
+				id: StepId::Telemetry,
+				prompt: message("setup.telemetry", &[]),
+				input: Input::Confirm { default: settings.telemetry },
+			},
+			Step {
+				id: StepId::Cache,
+				prompt: message("setup.cache", &[]),
+				input: Input::Text { default: settings.cache.display().to_string() },
+			},
+			Step {
+				id: StepId::RelayChain,
+				prompt: message("setup.relay_chain", &[]),
+				input: Input::Select {
+					options: RELAY_CHAINS
+						.iter()
+						.map(|(value, description)| (value.to_string(), description.to_string()))
+						.collect(),
+					default: settings.relay_chain.clone(),
+				},
+			},
+			Step {
+				id: StepId::Toolchain,
+				prompt: message("setup.toolchain", &[]),
+				input: Input::Report(check_toolchain()),
+			},
+		];
+		Ok(Self { dir: dir.to_path_buf(), settings, steps, current: 0 })
+	}
+
+	/// The current step, or `None` once all steps have been answered.
+	pub fn step(&self) -> Option<&Step> {
+		self.steps.get(self.current)
+	}
+
+	/// Answers the current step, advancing to the next.
+	///
+	/// # Arguments
+	/// * `answer` - The answer to the current step.
+	pub fn answer(&mut self, answer: Answer) -> Result<(), Error> {
+		let step = self.step().ok_or_else(|| Error::Config("the setup is complete".into()))?;
+		match (&step.input, answer) {
+			(Input::Confirm { .. }, Answer::Confirm(telemetry)) =>
+				self.settings.telemetry = telemetry,
+			(Input::Text { .. }, Answer::Text(cache)) if !cache.trim().is_empty() =>
+				self.settings.cache = PathBuf::from(cache.trim()),
+			(Input::Select { options, .. }, Answer::Text(relay_chain))
+				if options.iter().any(|(value, _)| *value == relay_chain) =>
+				self.settings.relay_chain = relay_chain,
+			(Input::Report(_), Answer::Acknowledge) => {},
+			(Input::Select { options, .. }, Answer::Text(answer)) => {
+				let options: Vec<_> = options.iter().map(|(value, _)| value.as_str()).collect();
+				return Err(Error::Config(message(
+					"setup.invalid_option",
+					&[("answer", &answer), ("options", &options.join(", "))],
+				)));
+			},
+			_ =>
+				return Err(Error::Config(message(
+					"setup.invalid_answer",
+					&[("prompt", &step.prompt)],
+				))),
+		}
+		self.current += 1;
+		Ok(())
+	}
+
+	/// Completes the setup, persisting the settings.
+	pub fn finish(self) -> Result<Settings, Error> {
+		if let Some(step) = self.step() {
+			return Err(Error::Config(format!("{:?} has not been answered", step.id)));
+		}
+		create_dir_all(&self.settings.cache)?;
+		self.settings.save(&self.dir)?;
+		Ok(self.settings)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn bootstrap_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let config = temp_dir.path().join("config");
+		let cache = temp_dir.path().join("cache");
+		assert!(is_first_run(&config));
+
+		let mut bootstrap = Bootstrap::new(&config, cache.clone())?;
+		assert_eq!(bootstrap.step().unwrap().id, StepId::Telemetry);
+		assert_eq!(bootstrap.step().unwrap().input, Input::Confirm { default: false });
+		bootstrap.answer(Answer::Confirm(false))?;
+		assert_eq!(
+			bootstrap.step().unwrap().input,
+			Input::Text { default: cache.display().to_string() }
+		);
+		assert!(matches!(
+			bootstrap.answer(Answer::Text(" ".into())),
+			Err(Error::Config(e)) if e.starts_with("invalid answer to: Where should")
+		));
+		bootstrap.answer(Answer::Text(cache.display().to_string()))?;
+		assert!(matches!(
+			bootstrap.answer(Answer::Text("kusama-local".into())),
+			Err(Error::Config(e))
+				if e == "`kusama-local` is not one of: paseo-local, rococo-local, westend-local, polkadot-local"
+		));
+		bootstrap.answer(Answer::Text("westend-local".into()))?;
+		assert!(matches!(bootstrap.step().unwrap().input, Input::Report(_)));
+		bootstrap.answer(Answer::Acknowledge)?;
+		assert!(bootstrap.step().is_none());
+
+		let settings = bootstrap.finish()?;
+		assert_eq!(
+			settings,
+			Settings {
+				telemetry: false,
+				cache: cache.clone(),
+				relay_chain: "westend-local".into()
+			}
+		);
+		assert!(cache.is_dir());
+		assert!(!is_first_run(&config));
+		assert_eq!(Settings::load(&config)?, Some(settings));
+		Ok(())
+	}
+
+	#[test]
+	fn finish_fails_when_incomplete() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let bootstrap = Bootstrap::new(temp_dir.path(), temp_dir.path().join("cache"))?;
+		assert!(matches!(
+			bootstrap.finish(),
+			Err(Error::Config(e)) if e == "Telemetry has not been answered"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn check_toolchain_works() {
+		let checks = check_toolchain();
+		assert_eq!(
+			checks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+			vec!["rustc", "cargo", "protoc", "wasm32-unknown-unknown"]
+		);
+		// The tests themselves are run via cargo.
+		assert!(checks[1].installed());
+	}
+}
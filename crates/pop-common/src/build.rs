@@ -1,23 +1,95 @@
+use crate::Error;
+use duct::cmd;
+use serde_json::Value;
 use std::{
 	fmt,
+	io::{BufRead, BufReader},
 	path::{Path, PathBuf},
+	str::FromStr,
 };
 
+/// The target to which runtimes are compiled.
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
 /// Enum representing a build profile.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Profile {
 	/// Debug profile, optimized for debugging.
 	Debug,
 	/// Release profile, optimized without any debugging functionality.
 	Release,
+	/// A custom profile defined within the manifest (e.g. `production`).
+	Custom(String),
 }
 
 impl Profile {
 	/// Returns the corresponding path to the target directory.
 	pub fn target_directory(&self, path: &Path) -> PathBuf {
+		self.target_directory_for(path, None)
+	}
+
+	/// Returns the corresponding path to the target directory, when cross-compiling for the
+	/// specified target triple.
+	///
+	/// # Arguments
+	/// * `path` - The path to the project.
+	/// * `target` - The target triple, if cross-compiling.
+	pub fn target_directory_for(&self, path: &Path, target: Option<&str>) -> PathBuf {
+		self.within(&path.join("target"), target)
+	}
+
+	/// Returns the corresponding path to the target directory as resolved by cargo, honouring
+	/// custom target directories (e.g. `CARGO_TARGET_DIR`), when cross-compiling for the specified
+	/// target triple.
+	///
+	/// # Arguments
+	/// * `path` - The path to the project.
+	/// * `target` - The target triple, if cross-compiling.
+	pub fn resolve_target_directory(&self, path: &Path, target: Option<&str>) -> PathBuf {
+		self.within(&target_dir(path), target)
+	}
+
+	/// Returns the corresponding path within a target directory.
+	///
+	/// # Arguments
+	/// * `target_directory` - The target directory.
+	/// * `target` - The target triple, if cross-compiling.
+	fn within(&self, target_directory: &Path, target: Option<&str>) -> PathBuf {
+		let target_directory = match target {
+			Some(target) => target_directory.join(target),
+			None => target_directory.to_path_buf(),
+		};
 		match self {
-			Profile::Release => path.join("target/release"),
-			Profile::Debug => path.join("target/debug"),
+			Profile::Release => target_directory.join("release"),
+			Profile::Debug => target_directory.join("debug"),
+			// Cargo's built-in `test` and `bench` profiles output to those of `dev` and `release`.
+			Profile::Custom(name) if name == "test" => target_directory.join("debug"),
+			Profile::Custom(name) if name == "bench" => target_directory.join("release"),
+			Profile::Custom(name) => target_directory.join(name),
+		}
+	}
+
+	/// The arguments passed to cargo to select the profile.
+	pub fn args(&self) -> Vec<String> {
+		match self {
+			Profile::Debug => vec![],
+			Profile::Release => vec!["--release".to_string()],
+			Profile::Custom(name) => vec!["--profile".to_string(), name.clone()],
+		}
+	}
+}
+
+impl FromStr for Profile {
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"dev" | "debug" => Ok(Profile::Debug),
+			"release" => Ok(Profile::Release),
+			name if !name.is_empty() &&
+				name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+				Ok(Profile::Custom(name.to_string())),
+			name => Err(Error::Config(format!("invalid profile name: `{name}`"))),
 		}
 	}
 }
@@ -37,6 +109,375 @@ impl fmt::Display for Profile {
 		match self {
 			Self::Debug => write!(f, "DEBUG"),
 			Self::Release => write!(f, "RELEASE"),
+			Self::Custom(name) => write!(f, "{}", name.to_uppercase()),
+		}
+	}
+}
+
+/// Resolves the target directory of a project as cargo does, honouring custom target directories
+/// (e.g. `CARGO_TARGET_DIR` or `build.target-dir`). Defaults to the `target` directory of the
+/// project when cargo is unable to resolve it.
+///
+/// # Arguments
+/// * `path` - The path to the project.
+pub fn target_dir(path: &Path) -> PathBuf {
+	cmd("cargo", ["metadata", "--format-version", "1", "--no-deps", "--offline"])
+		.dir(path)
+		.stderr_null()
+		.read()
+		.ok()
+		.and_then(|metadata| {
+			let metadata: Value = serde_json::from_str(&metadata).ok()?;
+			metadata.get("target_directory")?.as_str().map(PathBuf::from)
+		})
+		.unwrap_or_else(|| match std::env::var_os("CARGO_TARGET_DIR") {
+			Some(target_dir) => path.join(target_dir),
+			None => path.join("target"),
+		})
+}
+
+/// Validates that a target triple is installed for the toolchain, as required to cross-compile
+/// for it. Toolchains not managed by `rustup` are assumed to support the target.
+///
+/// # Arguments
+/// * `target` - The target triple (e.g. "aarch64-unknown-linux-gnu").
+pub fn validate_target(target: &str) -> Result<(), Error> {
+	let Ok(installed) = cmd("rustup", ["target", "list", "--installed"]).stderr_null().read()
+	else {
+		return Ok(());
+	};
+	if installed.lines().any(|t| t.trim() == target) {
+		return Ok(());
+	}
+	Err(Error::Config(format!(
+		"the `{target}` target is not installed; install it with `rustup target add {target}`"
+	)))
+}
+
+/// Determines whether the toolchain required to build a parachain locally is missing, returning
+/// a description of what is missing. Toolchains not managed by `rustup` are assumed to include
+/// the wasm target.
+pub fn missing_toolchain() -> Option<String> {
+	if cmd("cargo", ["--version"]).stdout_null().stderr_null().run().is_err() {
+		return Some("cargo".into());
+	}
+	match validate_target(WASM_TARGET) {
+		Ok(()) => None,
+		Err(_) => Some(format!("the `{WASM_TARGET}` target")),
+	}
+}
+
+/// A message emitted by cargo while building, as parsed from its JSON output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildMessage {
+	/// A crate has been compiled, or was already up to date (`fresh`).
+	Compiled {
+		/// The name of the crate.
+		name: String,
+		/// Whether the crate was already up to date.
+		fresh: bool,
+	},
+	/// The compiler emitted a warning.
+	Warning(String),
+	/// The compiler emitted an error.
+	Error(String),
+	/// The build has finished.
+	Finished {
+		/// Whether the build succeeded.
+		success: bool,
+	},
+}
+
+impl BuildMessage {
+	/// Parses a line of the JSON output of cargo, ignoring messages which are not reported.
+	///
+	/// # Arguments
+	/// * `line` - A line of the output of cargo, built with `--message-format json`.
+	pub fn parse(line: &str) -> Option<Self> {
+		let message: Value = serde_json::from_str(line).ok()?;
+		match message.get("reason")?.as_str()? {
+			"compiler-artifact" => Some(BuildMessage::Compiled {
+				name: message.pointer("/target/name")?.as_str()?.to_string(),
+				fresh: message.get("fresh").and_then(Value::as_bool).unwrap_or_default(),
+			}),
+			"compiler-message" => {
+				let diagnostic = message.get("message")?;
+				let rendered = diagnostic
+					.get("rendered")
+					.and_then(Value::as_str)
+					.or_else(|| diagnostic.get("message").and_then(Value::as_str))?
+					.trim_end()
+					.to_string();
+				match diagnostic.get("level")?.as_str()? {
+					"warning" => Some(BuildMessage::Warning(rendered)),
+					"error" => Some(BuildMessage::Error(rendered)),
+					_ => None,
+				}
+			},
+			"build-finished" => Some(BuildMessage::Finished {
+				success: message.get("success").and_then(Value::as_bool).unwrap_or_default(),
+			}),
+			_ => None,
 		}
 	}
 }
+
+/// Receives the progress of a build, so that consumers can render it (e.g. as a progress bar)
+/// rather than passing the output of cargo through.
+pub trait BuildReporter {
+	/// Reports a message emitted by cargo.
+	fn report(&self, message: &BuildMessage);
+}
+
+impl BuildReporter for () {
+	// no-op: build messages are ignored
+	fn report(&self, _message: &BuildMessage) {}
+}
+
+/// Runs cargo, reporting the messages it emits.
+///
+/// # Arguments
+/// * `args` - The arguments passed to cargo (e.g. `build --release`).
+/// * `dir` - The directory within which cargo is run.
+/// * `reporter` - Receives the messages emitted by cargo.
+pub fn run_cargo(args: &[String], dir: &Path, reporter: &impl BuildReporter) -> Result<(), Error> {
+	let args = args
+		.iter()
+		.cloned()
+		.chain(["--message-format=json-diagnostic-rendered-ansi".into()]);
+	let handle = cmd("cargo", args.collect::<Vec<_>>())
+		.dir(dir)
+		.stderr_capture()
+		.unchecked()
+		.reader()?;
+	let mut errors = 0;
+	for line in BufReader::new(&handle).lines() {
+		if let Some(message) = BuildMessage::parse(&line?) {
+			errors += usize::from(matches!(message, BuildMessage::Error(_)));
+			reporter.report(&message);
+		}
+	}
+	let output = handle.try_wait()?.expect("output is complete once fully read");
+	if !output.status.success() {
+		// Errors not reported as messages (e.g. an invalid manifest) are only written to stderr.
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		let reason = match errors {
+			0 => stderr
+				.lines()
+				.find(|l| l.starts_with("error"))
+				.unwrap_or("cargo failed")
+				.trim()
+				.to_string(),
+			errors => format!("compilation failed with {errors} error(s)"),
+		};
+		return Err(Error::Build(reason));
+	}
+	Ok(())
+}
+
+/// The cargo features with which a package is built.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Features {
+	/// The features to be activated.
+	pub features: Vec<String>,
+	/// Whether the default features are deactivated.
+	pub no_default_features: bool,
+}
+
+impl Features {
+	/// The arguments passed to cargo to select the features.
+	pub fn args(&self) -> Vec<String> {
+		let mut args = vec![];
+		if !self.features.is_empty() {
+			args.push("--features".to_string());
+			args.push(self.features.join(","));
+		}
+		if self.no_default_features {
+			args.push("--no-default-features".to_string());
+		}
+		args
+	}
+}
+
+/// How cargo resolves the dependencies of a package, so that builds can be guaranteed to respect
+/// the lockfile or to not access the network (e.g. within CI or air-gapped environments).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Resolution {
+	/// Require the lockfile to be up to date, failing rather than updating it.
+	pub locked: bool,
+	/// Resolve dependencies without accessing the network, using only those cached locally.
+	pub offline: bool,
+}
+
+impl Resolution {
+	/// The arguments passed to cargo to select how dependencies are resolved.
+	pub fn args(&self) -> Vec<String> {
+		let mut args = vec![];
+		if self.locked {
+			args.push("--locked".to_string());
+		}
+		if self.offline {
+			args.push("--offline".to_string());
+		}
+		args
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn profile_works() -> Result<(), Error> {
+		let path = Path::new("project");
+		for (name, profile, target, args) in [
+			("dev", Profile::Debug, "project/target/debug", vec![]),
+			("release", Profile::Release, "project/target/release", vec!["--release"]),
+			(
+				"production",
+				Profile::Custom("production".into()),
+				"project/target/production",
+				vec!["--profile", "production"],
+			),
+			(
+				"test",
+				Profile::Custom("test".into()),
+				"project/target/debug",
+				vec!["--profile", "test"],
+			),
+			(
+				"bench",
+				Profile::Custom("bench".into()),
+				"project/target/release",
+				vec!["--profile", "bench"],
+			),
+		] {
+			assert_eq!(name.parse::<Profile>()?, profile);
+			assert_eq!(profile.target_directory(path), PathBuf::from(target));
+			assert_eq!(profile.args(), args);
+		}
+		assert_eq!(Profile::Custom("production".into()).to_string(), "PRODUCTION");
+		assert_eq!(
+			Profile::Release.target_directory_for(path, Some("aarch64-unknown-linux-gnu")),
+			PathBuf::from("project/target/aarch64-unknown-linux-gnu/release")
+		);
+		assert!(matches!("".parse::<Profile>(), Err(Error::Config(..))));
+		assert!(matches!("../escape".parse::<Profile>(), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn target_dir_honours_custom_target_directories() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().canonicalize()?;
+		std::fs::write(
+			project.join("Cargo.toml"),
+			"[package]\nname = \"project\"\nversion = \"0.1.0\"\n\n[lib]\npath = \"lib.rs\"\n",
+		)?;
+		std::fs::write(project.join("lib.rs"), "")?;
+		assert_eq!(target_dir(&project), project.join("target"));
+		std::fs::create_dir(project.join(".cargo"))?;
+		std::fs::write(project.join(".cargo/config.toml"), "[build]\ntarget-dir = \"custom\"\n")?;
+		assert_eq!(target_dir(&project), project.join("custom"));
+		assert_eq!(
+			Profile::Release.resolve_target_directory(&project, None),
+			project.join("custom/release")
+		);
+		assert_eq!(Profile::Release.target_directory(&project), project.join("target/release"));
+		Ok(())
+	}
+
+	#[test]
+	fn features_args_works() {
+		assert!(Features::default().args().is_empty());
+		let features = Features {
+			features: vec!["runtime-benchmarks".into(), "try-runtime".into()],
+			no_default_features: true,
+		};
+		assert_eq!(
+			features.args(),
+			["--features", "runtime-benchmarks,try-runtime", "--no-default-features"]
+		);
+	}
+
+	#[test]
+	fn resolution_args_works() {
+		assert!(Resolution::default().args().is_empty());
+		assert_eq!(Resolution { locked: true, offline: false }.args(), ["--locked"]);
+		assert_eq!(Resolution { locked: true, offline: true }.args(), ["--locked", "--offline"]);
+	}
+
+	#[test]
+	fn validate_target_fails_when_missing() {
+		if cmd("rustup", ["--version"]).stdout_null().stderr_null().run().is_err() {
+			return;
+		}
+		assert!(matches!(
+			validate_target("missing-unknown-target"),
+			Err(Error::Config(e)) if e.contains("rustup target add missing-unknown-target")
+		));
+	}
+
+	#[test]
+	fn build_message_parse_works() {
+		assert_eq!(
+			BuildMessage::parse(
+				r#"{"reason":"compiler-artifact","target":{"name":"serde"},"fresh":true}"#
+			),
+			Some(BuildMessage::Compiled { name: "serde".into(), fresh: true })
+		);
+		assert_eq!(
+			BuildMessage::parse(
+				r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused","rendered":"warning: unused\n"}}"#
+			),
+			Some(BuildMessage::Warning("warning: unused".into()))
+		);
+		assert_eq!(
+			BuildMessage::parse(
+				r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types"}}"#
+			),
+			Some(BuildMessage::Error("mismatched types".into()))
+		);
+		assert_eq!(
+			BuildMessage::parse(r#"{"reason":"build-finished","success":false}"#),
+			Some(BuildMessage::Finished { success: false })
+		);
+		assert_eq!(BuildMessage::parse(r#"{"reason":"build-script-executed"}"#), None);
+		assert_eq!(BuildMessage::parse("Compiling serde"), None);
+	}
+
+	#[test]
+	fn run_cargo_reports_messages() -> anyhow::Result<()> {
+		struct Messages(std::sync::Mutex<Vec<BuildMessage>>);
+		impl BuildReporter for Messages {
+			fn report(&self, message: &BuildMessage) {
+				self.0.lock().unwrap().push(message.clone());
+			}
+		}
+
+		let temp_dir = tempfile::tempdir()?;
+		cmd("cargo", ["new", "hello_world", "--bin"]).dir(temp_dir.path()).run()?;
+		let project = temp_dir.path().join("hello_world");
+		let messages = Messages(Default::default());
+		run_cargo(&["build".into()], &project, &messages)?;
+		let messages = messages.0.into_inner().unwrap();
+		assert!(
+			messages.contains(&BuildMessage::Compiled { name: "hello_world".into(), fresh: false })
+		);
+		assert_eq!(messages.last(), Some(&BuildMessage::Finished { success: true }));
+
+		std::fs::write(project.join("src/main.rs"), "fn main() { let x: u8 = \"\"; }")?;
+		let messages = Messages(Default::default());
+		assert!(matches!(
+			run_cargo(&["build".into()], &project, &messages),
+			Err(Error::Build(e)) if e == "compilation failed with 1 error(s)"
+		));
+		assert!(messages
+			.0
+			.into_inner()
+			.unwrap()
+			.iter()
+			.any(|m| matches!(m, BuildMessage::Error(e) if e.contains("mismatched types"))));
+		Ok(())
+	}
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{hooks::CONFIG_FILE, migration::read_config, Error};
+use duct::cmd;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+	env,
+	fmt::{Display, Formatter},
+	path::Path,
+};
+
+/// The compilation cache detected, when not configured.
+const SCCACHE: &str = "sccache";
+/// The environment variable with which cargo wraps invocations of rustc.
+const RUSTC_WRAPPER: &str = "RUSTC_WRAPPER";
+
+/// A compilation cache wrapping invocations of rustc (e.g. `sccache`), so that rebuilds reuse
+/// previously compiled crates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompilationCache {
+	/// The wrapper of rustc (e.g. `sccache` or a path to it).
+	pub wrapper: String,
+}
+
+/// The contents of the project configuration file relevant to the compilation cache.
+#[derive(Default, Deserialize)]
+struct Config {
+	cache: Option<CacheConfig>,
+}
+
+/// The `[cache]` table of the project configuration file.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CacheConfig {
+	rustc_wrapper: String,
+}
+
+impl CompilationCache {
+	/// Loads the compilation cache configured within the `[cache]` table of the `pop.toml` file of
+	/// a project (e.g. `rustc-wrapper = "sccache"`), if any.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn load(project: &Path) -> Result<Option<Self>, Error> {
+		let path = project.join(CONFIG_FILE);
+		let Some(config) = read_config(project)? else {
+			return Ok(None);
+		};
+		let config: Config = toml_edit::de::from_str(&config).map_err(|e| {
+			Error::Config(format!("invalid compilation cache in {}: {e}", path.display()))
+		})?;
+		Ok(config.cache.map(|cache| Self { wrapper: cache.rustc_wrapper }))
+	}
+
+	/// Detects the compilation cache to be used: a wrapper already specified with `RUSTC_WRAPPER`,
+	/// otherwise `sccache` if installed.
+	pub fn detect() -> Option<Self> {
+		if let Some(wrapper) = env::var(RUSTC_WRAPPER).ok().filter(|w| !w.trim().is_empty()) {
+			return Some(Self { wrapper });
+		}
+		cmd(SCCACHE, ["--version"])
+			.stdout_null()
+			.stderr_null()
+			.run()
+			.is_ok()
+			.then(|| Self { wrapper: SCCACHE.to_string() })
+	}
+
+	/// Resolves the compilation cache of a project, preferring that configured within its
+	/// `pop.toml` file over that detected.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn resolve(project: &Path) -> Result<Option<Self>, Error> {
+		Ok(Self::load(project)?.or_else(Self::detect))
+	}
+
+	/// Configures the environment so that subsequent builds are run through the cache, resetting
+	/// its statistics where supported so that they reflect only those builds.
+	pub fn enable(&self) -> Result<(), Error> {
+		let wrapper = self.wrapper.trim();
+		if cmd(wrapper, ["--version"]).stdout_null().stderr_null().run().is_err() {
+			return Err(Error::Config(format!(
+				"the `{wrapper}` compilation cache could not be run; check that it is installed"
+			)));
+		}
+		if self.is_sccache() {
+			// Best effort: statistics are merely informative.
+			let _ = cmd(wrapper, ["--zero-stats"]).stdout_null().stderr_null().run();
+		}
+		env::set_var(RUSTC_WRAPPER, wrapper);
+		Ok(())
+	}
+
+	/// The statistics of the cache, where supported by the wrapper.
+	pub fn stats(&self) -> Option<CacheStats> {
+		if !self.is_sccache() {
+			return None;
+		}
+		let output = cmd(self.wrapper.trim(), ["--show-stats", "--stats-format", "json"])
+			.stderr_null()
+			.read()
+			.ok()?;
+		CacheStats::parse(&output)
+	}
+
+	fn is_sccache(&self) -> bool {
+		Path::new(self.wrapper.trim())
+			.file_stem()
+			.is_some_and(|name| name.to_string_lossy() == SCCACHE)
+	}
+}
+
+/// The statistics of a compilation cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CacheStats {
+	/// The number of compilations served from the cache.
+	pub hits: u64,
+	/// The number of compilations not found within the cache.
+	pub misses: u64,
+}
+
+impl CacheStats {
+	/// Parses the statistics reported by `sccache --show-stats --stats-format json`.
+	///
+	/// # Arguments
+	/// * `output` - The output of sccache.
+	pub fn parse(output: &str) -> Option<Self> {
+		let stats = serde_json::from_str::<Value>(output).ok()?.get("stats")?.clone();
+		let count = |name: &str| -> Option<u64> {
+			let counts = stats.get(name)?.get("counts")?.as_object()?;
+			Some(counts.values().filter_map(Value::as_u64).sum())
+		};
+		Some(Self { hits: count("cache_hits")?, misses: count("cache_misses")? })
+	}
+
+	/// The proportion of compilations served from the cache, as a percentage.
+	pub fn hit_rate(&self) -> f64 {
+		match self.hits + self.misses {
+			0 => 0.0,
+			total => self.hits as f64 * 100.0 / total as f64,
+		}
+	}
+}
+
+impl Display for CacheStats {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{} hit(s), {} miss(es) ({:.1}% hit rate)",
+			self.hits,
+			self.misses,
+			self.hit_rate()
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert_eq!(CompilationCache::load(temp_dir.path())?, None);
+		write(temp_dir.path().join(CONFIG_FILE), "[hooks]\npre-build = \"echo\"")?;
+		assert_eq!(CompilationCache::load(temp_dir.path())?, None);
+		write(temp_dir.path().join(CONFIG_FILE), "[cache]\nrustc-wrapper = \"/usr/bin/sccache\"")?;
+		let cache = CompilationCache::load(temp_dir.path())?.expect("cache is configured");
+		assert_eq!(cache.wrapper, "/usr/bin/sccache");
+		assert!(cache.is_sccache());
+		assert!(!CompilationCache { wrapper: "cachepot".into() }.is_sccache());
+		write(temp_dir.path().join(CONFIG_FILE), "[cache]\nwrapper = \"sccache\"")?;
+		assert!(matches!(CompilationCache::load(temp_dir.path()), Err(Error::Config(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn parse_stats_works() {
+		let output = r#"{"stats":{"compile_requests":12,"cache_hits":{"counts":{"Rust":9,"C/C++":1},"adv_counts":{}},"cache_misses":{"counts":{"Rust":2},"adv_counts":{}}}}"#;
+		let stats = CacheStats::parse(output).expect("valid statistics");
+		assert_eq!(stats, CacheStats { hits: 10, misses: 2 });
+		assert_eq!(stats.to_string(), "10 hit(s), 2 miss(es) (83.3% hit rate)");
+		assert_eq!(CacheStats::default().hit_rate(), 0.0);
+		assert_eq!(CacheStats::parse("not json"), None);
+	}
+}
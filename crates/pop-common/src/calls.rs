@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{hooks::CONFIG_FILE, migration::read_config, Error};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+	path::Path,
+};
+
+/// The target of a saved call.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum CallTarget {
+	/// A dispatchable function of a pallet (e.g. `Balances::transfer_keep_alive`).
+	Extrinsic {
+		/// The name of the pallet.
+		pallet: String,
+		/// The name of the function.
+		function: String,
+	},
+	/// A message of a deployed contract.
+	Contract {
+		/// The address of the contract.
+		contract: String,
+		/// The name of the message.
+		message: String,
+	},
+}
+
+impl Display for CallTarget {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CallTarget::Extrinsic { pallet, function } => write!(f, "{pallet}::{function}"),
+			CallTarget::Contract { contract, message } => write!(f, "{message} of {contract}"),
+		}
+	}
+}
+
+/// A named, parameterized call saved within the `[calls]` table of the `pop.toml` file of a
+/// project, so that frequently repeated operations can be invoked by name, overriding any of its
+/// arguments.
+///
+/// Arguments are specified by name, either as TOML values or as strings in the notation accepted
+/// by [`crate::parse_arg`] (e.g. `"Id('5GrwvaEF...')"`).
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SavedCall {
+	/// A description of the call.
+	#[serde(default)]
+	pub description: Option<String>,
+	/// The target of the call.
+	#[serde(flatten)]
+	pub target: CallTarget,
+	/// The default arguments of the call, by name.
+	#[serde(default)]
+	pub args: BTreeMap<String, Value>,
+	/// The websocket endpoint of the chain, if not the default.
+	#[serde(default)]
+	pub url: Option<String>,
+	/// The secret key URI of the signer, if not the default.
+	#[serde(default)]
+	pub suri: Option<String>,
+}
+
+/// The contents of the project configuration file relevant to saved calls.
+#[derive(Default, Deserialize)]
+struct Config {
+	#[serde(default)]
+	calls: BTreeMap<String, SavedCall>,
+}
+
+impl SavedCall {
+	/// Loads the calls saved within the `[calls]` table of the `pop.toml` file of a project, by
+	/// name.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn load_all(project: &Path) -> Result<BTreeMap<String, Self>, Error> {
+		let path = project.join(CONFIG_FILE);
+		let Some(config) = read_config(project)? else {
+			return Ok(BTreeMap::new());
+		};
+		let config: Config = toml_edit::de::from_str(&config).map_err(|e| {
+			Error::Config(format!("invalid saved calls in {}: {e}", path.display()))
+		})?;
+		Ok(config.calls)
+	}
+
+	/// Loads a call saved within the `pop.toml` file of a project.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	/// * `name` - The name of the call.
+	pub fn load(project: &Path, name: &str) -> Result<Self, Error> {
+		let mut calls = Self::load_all(project)?;
+		calls.remove(name).ok_or_else(|| {
+			let names: Vec<_> = calls.keys().map(|name| format!("`{name}`")).collect();
+			Error::Config(match names.is_empty() {
+				true =>
+					format!("no `{name}` call is saved, as no calls are saved in {CONFIG_FILE}"),
+				false => format!("no `{name}` call is saved, expected one of {}", names.join(", ")),
+			})
+		})
+	}
+
+	/// Resolves the arguments of the call in the order expected by its target, applying any
+	/// overrides to its default arguments.
+	///
+	/// # Arguments
+	/// * `names` - The names of the arguments expected by the target, in order.
+	/// * `overrides` - The arguments overridden, by name.
+	pub fn resolve_args(
+		&self,
+		names: &[&str],
+		overrides: &[(String, String)],
+	) -> Result<Vec<String>, Error> {
+		let mut args: BTreeMap<&str, String> = self
+			.args
+			.iter()
+			.map(|(name, value)| {
+				let arg = match value {
+					Value::String(value) => value.clone(),
+					value => value.to_string(),
+				};
+				(name.as_str(), arg)
+			})
+			.collect();
+		args.extend(overrides.iter().map(|(name, value)| (name.as_str(), value.clone())));
+		if let Some(unknown) = args.keys().find(|name| !names.contains(name)) {
+			let names: Vec<_> = names.iter().map(|name| format!("`{name}`")).collect();
+			return Err(Error::Config(format!(
+				"unknown argument `{unknown}`, expected one of {}",
+				names.join(", ")
+			)));
+		}
+		names
+			.iter()
+			.map(|name| {
+				args.remove(name).ok_or_else(|| {
+					Error::Config(format!(
+						"the `{name}` argument is not specified; specify it with `--arg {name}=<value>`"
+					))
+				})
+			})
+			.collect()
+	}
+}
+
+/// Parses an argument overridden when invoking a saved call, specified as `<name>=<value>`.
+///
+/// # Arguments
+/// * `arg` - The argument overridden.
+pub fn parse_override(arg: &str) -> Result<(String, String), Error> {
+	match arg.split_once('=') {
+		Some((name, value)) if !name.trim().is_empty() =>
+			Ok((name.trim().to_string(), value.to_string())),
+		_ => Err(Error::Config(format!("`{arg}` is not specified as `<name>=<value>`"))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	const CONFIG: &str = r#"
+[calls.fund-qa]
+description = "Fund the QA account"
+pallet = "Balances"
+function = "transfer_keep_alive"
+args = { dest = "Id('5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY')", value = 1000 }
+url = "ws://127.0.0.1:9944"
+
+[calls.flip]
+contract = "5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A"
+message = "flip"
+"#;
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(SavedCall::load_all(temp_dir.path())?.is_empty());
+		write(temp_dir.path().join(CONFIG_FILE), CONFIG)?;
+		let calls = SavedCall::load_all(temp_dir.path())?;
+		assert_eq!(calls.keys().collect::<Vec<_>>(), ["flip", "fund-qa"]);
+		let call = SavedCall::load(temp_dir.path(), "fund-qa")?;
+		assert_eq!(call.description.as_deref(), Some("Fund the QA account"));
+		assert_eq!(
+			call.target,
+			CallTarget::Extrinsic {
+				pallet: "Balances".into(),
+				function: "transfer_keep_alive".into()
+			}
+		);
+		assert_eq!(call.target.to_string(), "Balances::transfer_keep_alive");
+		assert_eq!(call.url.as_deref(), Some("ws://127.0.0.1:9944"));
+		assert_eq!(
+			SavedCall::load(temp_dir.path(), "flip")?.target,
+			CallTarget::Contract {
+				contract: "5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A".into(),
+				message: "flip".into()
+			}
+		);
+		assert!(matches!(
+			SavedCall::load(temp_dir.path(), "missing"),
+			Err(Error::Config(e)) if e.ends_with("expected one of `flip`, `fund-qa`")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn resolve_args_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		write(temp_dir.path().join(CONFIG_FILE), CONFIG)?;
+		let call = SavedCall::load(temp_dir.path(), "fund-qa")?;
+		let names = ["dest", "value"];
+		assert_eq!(
+			call.resolve_args(&names, &[])?,
+			["Id('5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY')", "1000"]
+		);
+		assert_eq!(call.resolve_args(&names, &[("value".into(), "5".into())])?[1], "5");
+		assert!(matches!(
+			call.resolve_args(&names, &[("amount".into(), "5".into())]),
+			Err(Error::Config(e)) if e == "unknown argument `amount`, expected one of `dest`, `value`"
+		));
+		assert!(matches!(
+			call.resolve_args(&["dest", "value", "keep_alive"], &[]),
+			Err(Error::Config(e)) if e.starts_with("the `keep_alive` argument is not specified")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn parse_override_works() -> Result<()> {
+		assert_eq!(parse_override("value=1=2")?, ("value".to_string(), "1=2".to_string()));
+		assert!(parse_override("value").is_err());
+		assert!(parse_override("=1").is_err());
+		Ok(())
+	}
+}
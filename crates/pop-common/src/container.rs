@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use duct::cmd;
+use std::path::{Path, PathBuf};
+
+/// A container engine, used to run commands within reproducible environments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Engine {
+	/// Docker.
+	Docker,
+	/// Podman, which accepts the same arguments as Docker.
+	Podman,
+}
+
+impl Engine {
+	/// Detects the container engine available, preferring Docker.
+	pub fn detect() -> Result<Self, Error> {
+		[Engine::Docker, Engine::Podman]
+			.into_iter()
+			.find(|engine| {
+				cmd(engine.binary(), ["--version"]).stdout_null().stderr_null().run().is_ok()
+			})
+			.ok_or_else(|| {
+				Error::Container("no container engine found; install Docker or Podman".into())
+			})
+	}
+
+	/// The binary of the engine.
+	pub fn binary(&self) -> &'static str {
+		match self {
+			Engine::Docker => "docker",
+			Engine::Podman => "podman",
+		}
+	}
+}
+
+/// A container run from an image, with host directories mounted within it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Container {
+	/// The image from which the container is run.
+	image: String,
+	/// The host directories mounted within the container, with their container paths.
+	mounts: Vec<(PathBuf, String)>,
+	/// The environment variables of the container.
+	env: Vec<(String, String)>,
+	/// The working directory within the container.
+	workdir: Option<String>,
+	/// The user, as `uid:gid`, as which the command is run within the container.
+	user: Option<String>,
+}
+
+impl Container {
+	/// Creates a new container.
+	///
+	/// # Arguments
+	/// * `image` - The image from which the container is run, including its tag.
+	pub fn new(image: impl Into<String>) -> Self {
+		Self { image: image.into(), mounts: vec![], env: vec![], workdir: None, user: None }
+	}
+
+	/// Mounts a host directory within the container.
+	///
+	/// # Arguments
+	/// * `host` - The host directory.
+	/// * `container` - The path of the directory within the container.
+	pub fn mount(mut self, host: &Path, container: impl Into<String>) -> Self {
+		self.mounts.push((host.to_path_buf(), container.into()));
+		self
+	}
+
+	/// Sets an environment variable of the container.
+	///
+	/// # Arguments
+	/// * `key` - The name of the variable.
+	/// * `value` - The value of the variable.
+	pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.env.push((key.into(), value.into()));
+		self
+	}
+
+	/// Sets the working directory within the container.
+	///
+	/// # Arguments
+	/// * `workdir` - The working directory.
+	pub fn workdir(mut self, workdir: impl Into<String>) -> Self {
+		self.workdir = Some(workdir.into());
+		self
+	}
+
+	/// Sets the user as which the command is run within the container.
+	///
+	/// # Arguments
+	/// * `user` - The user, as `uid:gid`.
+	pub fn user(mut self, user: impl Into<String>) -> Self {
+		self.user = Some(user.into());
+		self
+	}
+
+	/// The arguments passed to the engine to run a command within the container.
+	///
+	/// # Arguments
+	/// * `command` - The command run within the container, otherwise that of the image.
+	pub fn args(&self, command: &[&str]) -> Vec<String> {
+		let mut args = vec!["run".to_string(), "--rm".to_string()];
+		if let Some(user) = &self.user {
+			args.extend(["--user".to_string(), user.clone()]);
+		}
+		for (host, container) in &self.mounts {
+			args.extend(["-v".to_string(), format!("{}:{container}", host.display())]);
+		}
+		for (key, value) in &self.env {
+			args.extend(["-e".to_string(), format!("{key}={value}")]);
+		}
+		if let Some(workdir) = &self.workdir {
+			args.extend(["-w".to_string(), workdir.clone()]);
+		}
+		args.push(self.image.clone());
+		args.extend(command.iter().map(|a| a.to_string()));
+		args
+	}
+
+	/// Runs a command within the container, returning its output.
+	///
+	/// # Arguments
+	/// * `engine` - The container engine.
+	/// * `command` - The command run within the container, otherwise that of the image.
+	pub fn run(&self, engine: Engine, command: &[&str]) -> Result<String, Error> {
+		let output = cmd(engine.binary(), self.args(command)).stdout_capture().unchecked().run()?;
+		if !output.status.success() {
+			return Err(Error::Container(format!("`{}` failed with {}", self.image, output.status)));
+		}
+		Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+	}
+
+	/// Runs a command within the container, streaming its output to that of the current process.
+	///
+	/// # Arguments
+	/// * `engine` - The container engine.
+	/// * `command` - The command run within the container, otherwise that of the image.
+	pub fn stream(&self, engine: Engine, command: &[&str]) -> Result<(), Error> {
+		let output = cmd(engine.binary(), self.args(command)).unchecked().run()?;
+		if !output.status.success() {
+			return Err(Error::Container(format!("`{}` failed with {}", self.image, output.status)));
+		}
+		Ok(())
+	}
+}
+
+/// The user of the current process, as `uid:gid`, so that files written within mounted
+/// directories by a container remain owned by the host user.
+pub fn host_user() -> Result<String, Error> {
+	let uid = cmd("id", ["-u"]).read()?;
+	let gid = cmd("id", ["-g"]).read()?;
+	Ok(format!("{}:{}", uid.trim(), gid.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn args_works() {
+		let container = Container::new("docker.io/paritytech/srtool:1.81.0")
+			.mount(Path::new("/project"), "/build")
+			.env("PACKAGE", "runtime")
+			.workdir("/build")
+			.user("1000:1000");
+		assert_eq!(
+			container.args(&["build", "--json"]),
+			[
+				"run",
+				"--rm",
+				"--user",
+				"1000:1000",
+				"-v",
+				"/project:/build",
+				"-e",
+				"PACKAGE=runtime",
+				"-w",
+				"/build",
+				"docker.io/paritytech/srtool:1.81.0",
+				"build",
+				"--json"
+			]
+		);
+	}
+
+	#[test]
+	fn host_user_works() -> Result<(), Error> {
+		let user = host_user()?;
+		let (uid, gid) = user.split_once(':').expect("uid and gid are separated by a colon");
+		assert!(uid.parse::<u32>().is_ok() && gid.parse::<u32>().is_ok());
+		Ok(())
+	}
+
+	#[test]
+	fn engine_binary_works() {
+		assert_eq!(Engine::Docker.binary(), "docker");
+		assert_eq!(Engine::Podman.binary(), "podman");
+	}
+}
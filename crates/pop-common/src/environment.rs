@@ -0,0 +1,476 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{hooks::CONFIG_FILE, Error};
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, KeyInit};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+	fs::{create_dir_all, read_dir, read_to_string, File},
+	io::Read,
+	path::{Component, Path, PathBuf},
+};
+use tar::{Archive, Builder, EntryType, Header};
+use toml_edit::DocumentMut;
+use walkdir::WalkDir;
+
+/// The name of the manifest describing the contents of an exported environment.
+const MANIFEST: &str = "manifest.json";
+/// The name of the encrypted keystore within an exported environment.
+const KEYSTORE: &str = "keystore.enc";
+/// The version of the format of exported environments.
+const VERSION: u32 = 2;
+/// The number of PBKDF2 iterations used to derive keys from a passphrase.
+#[cfg(not(test))]
+const ITERATIONS: u32 = 600_000;
+#[cfg(test)]
+const ITERATIONS: u32 = 1_000;
+/// The maximum number of PBKDF2 iterations accepted when importing, bounding the cost of
+/// deriving the key of an imported keystore.
+const MAX_ITERATIONS: u32 = 10_000_000;
+/// The lengths of the salt, nonce and authentication tag of the encrypted keystore.
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+/// The directories never searched for network manifests.
+const IGNORED: [&str; 3] = [".git", "node_modules", "target"];
+
+/// The locations making up a pop environment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Environment {
+	/// The project directory, containing `pop.toml` and any network manifests.
+	pub project: PathBuf,
+	/// The cache directory, containing sourced binaries.
+	pub cache: PathBuf,
+	/// The keystore directory, if any.
+	pub keystore: Option<PathBuf>,
+}
+
+/// Options for exporting an environment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExportOptions {
+	/// Whether the binaries within the cache are included.
+	pub binaries: bool,
+	/// The passphrase used to encrypt the keystore, which is only included if specified.
+	pub passphrase: Option<String>,
+}
+
+/// The manifest describing the contents of an exported environment.
+#[derive(Debug, Deserialize, Serialize)]
+struct Manifest {
+	/// The version of the format.
+	version: u32,
+	/// The number of PBKDF2 iterations used to encrypt the keystore, if included.
+	keystore_iterations: Option<u32>,
+}
+
+/// The contents of an exported or imported environment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+	/// The project files, relative to the project directory.
+	pub project: Vec<PathBuf>,
+	/// The binaries, relative to the cache directory.
+	pub binaries: Vec<PathBuf>,
+	/// Whether the keystore was included.
+	pub keystore: bool,
+}
+
+/// Exports an environment to a compressed tar archive, so that an identical setup can be
+/// imported on another machine. The keystore is encrypted with the specified passphrase.
+///
+/// # Arguments
+/// * `archive` - The path of the archive to be written.
+/// * `environment` - The environment to be exported.
+/// * `options` - What is included within the archive.
+pub fn export_environment(
+	archive: &Path,
+	environment: &Environment,
+	options: &ExportOptions,
+) -> Result<Summary, Error> {
+	let mut builder = Builder::new(GzEncoder::new(File::create(archive)?, Compression::default()));
+	let mut summary = Summary::default();
+
+	for file in project_files(&environment.project)? {
+		builder.append_path_with_name(
+			environment.project.join(&file),
+			Path::new("project").join(&file),
+		)?;
+		summary.project.push(file);
+	}
+
+	if options.binaries && environment.cache.exists() {
+		let mut binaries: Vec<_> = read_dir(&environment.cache)?
+			.flatten()
+			.filter(|e| e.path().is_file())
+			.map(|e| PathBuf::from(e.file_name()))
+			// Hidden files contain local state, with the exception of the retention policy.
+			.filter(|name| {
+				!name.to_string_lossy().starts_with('.') ||
+					name == Path::new(crate::gc::POLICY_FILE)
+			})
+			.collect();
+		binaries.sort();
+		for binary in binaries {
+			builder.append_path_with_name(
+				environment.cache.join(&binary),
+				Path::new("cache").join(&binary),
+			)?;
+			summary.binaries.push(binary);
+		}
+	}
+
+	let mut keystore_iterations = None;
+	if let (Some(keystore), Some(passphrase)) = (&environment.keystore, &options.passphrase) {
+		if keystore.exists() {
+			let mut contents = Builder::new(Vec::new());
+			contents.append_dir_all(".", keystore)?;
+			let encrypted = encrypt(&contents.into_inner()?, passphrase, ITERATIONS);
+			append(&mut builder, KEYSTORE, &encrypted)?;
+			keystore_iterations = Some(ITERATIONS);
+			summary.keystore = true;
+		}
+	}
+
+	let manifest = Manifest { version: VERSION, keystore_iterations };
+	let manifest =
+		serde_json::to_vec_pretty(&manifest).map_err(|e| Error::Config(e.to_string()))?;
+	append(&mut builder, MANIFEST, &manifest)?;
+	builder.into_inner()?.finish()?;
+	Ok(summary)
+}
+
+/// Imports an environment from an archive created by [`export_environment`], overwriting any
+/// existing files. Only regular files and directories are unpacked, with archives containing
+/// links rejected.
+///
+/// # Arguments
+/// * `archive` - The path of the archive.
+/// * `environment` - The locations to which the environment is imported.
+/// * `passphrase` - The passphrase used to decrypt the keystore, required if it is included.
+pub fn import_environment(
+	archive: &Path,
+	environment: &Environment,
+	passphrase: Option<&str>,
+) -> Result<Summary, Error> {
+	// The manifest is read first, so that incompatible archives are rejected before importing.
+	let manifest: Manifest = {
+		let mut archive = Archive::new(GzDecoder::new(File::open(archive)?));
+		let mut manifest = None;
+		for entry in archive.entries()? {
+			let mut entry = entry?;
+			if entry.path()?.as_ref() == Path::new(MANIFEST) {
+				let mut contents = String::new();
+				entry.read_to_string(&mut contents)?;
+				manifest = Some(serde_json::from_str(&contents).map_err(|e| {
+					Error::Environment(format!("invalid environment manifest: {e}"))
+				})?);
+			}
+		}
+		manifest.ok_or_else(|| Error::Environment("the environment manifest is missing".into()))?
+	};
+	if manifest.version != VERSION {
+		return Err(Error::Environment(format!(
+			"unsupported environment version: {}",
+			manifest.version
+		)));
+	}
+	if let Some(iterations) =
+		manifest.keystore_iterations.filter(|i| !(1..=MAX_ITERATIONS).contains(i))
+	{
+		return Err(Error::Environment(format!(
+			"unsupported number of keystore iterations: {iterations}"
+		)));
+	}
+	if manifest.keystore_iterations.is_some() && passphrase.is_none() {
+		return Err(Error::Environment("a passphrase is required to import the keystore".into()));
+	}
+
+	let mut summary = Summary::default();
+	let mut archive = Archive::new(GzDecoder::new(File::open(archive)?));
+	for entry in archive.entries()? {
+		let mut entry = entry?;
+		let path = entry.path()?.into_owned();
+		if !matches!(entry.header().entry_type(), EntryType::Regular | EntryType::Directory) {
+			return Err(Error::Environment(format!(
+				"unsupported entry within archive: {}",
+				path.display()
+			)));
+		}
+		if let Ok(file) = path.strip_prefix("project") {
+			unpack(&mut entry, &environment.project, file)?;
+			summary.project.push(file.to_path_buf());
+		} else if let Ok(binary) = path.strip_prefix("cache") {
+			unpack(&mut entry, &environment.cache, binary)?;
+			summary.binaries.push(binary.to_path_buf());
+		} else if path == Path::new(KEYSTORE) {
+			let (Some(keystore), Some(passphrase), Some(iterations)) =
+				(&environment.keystore, passphrase, manifest.keystore_iterations)
+			else {
+				continue;
+			};
+			let mut encrypted = vec![];
+			entry.read_to_end(&mut encrypted)?;
+			let contents = decrypt(&encrypted, passphrase, iterations)?;
+			create_dir_all(keystore)?;
+			Archive::new(contents.as_slice()).unpack(keystore)?;
+			summary.keystore = true;
+		}
+	}
+	Ok(summary)
+}
+
+/// The files within a project making up its environment: its `pop.toml` and any network
+/// manifests, relative to the project directory.
+///
+/// # Arguments
+/// * `project` - The project directory.
+fn project_files(project: &Path) -> Result<Vec<PathBuf>, Error> {
+	let mut files = vec![];
+	if project.join(CONFIG_FILE).exists() {
+		files.push(PathBuf::from(CONFIG_FILE));
+	}
+	let entries = WalkDir::new(project).max_depth(3).sort_by_file_name().into_iter();
+	for entry in entries.filter_entry(|e| !IGNORED.iter().any(|i| e.file_name() == *i)).flatten() {
+		let path = entry.path();
+		if path.extension().is_some_and(|e| e == "toml") && is_network_manifest(path) {
+			files.push(path.strip_prefix(project).unwrap_or(path).to_path_buf());
+		}
+	}
+	Ok(files)
+}
+
+/// Whether a file is a network configuration file, as used to launch a local network.
+fn is_network_manifest(path: &Path) -> bool {
+	read_to_string(path)
+		.ok()
+		.and_then(|contents| contents.parse::<DocumentMut>().ok())
+		.is_some_and(|config| config.contains_key("relaychain"))
+}
+
+/// Appends a file with the specified contents to an archive.
+fn append<W: std::io::Write>(
+	builder: &mut Builder<W>,
+	path: &str,
+	contents: &[u8],
+) -> Result<(), Error> {
+	let mut header = Header::new_gnu();
+	header.set_size(contents.len() as u64);
+	header.set_mode(0o600);
+	header.set_cksum();
+	builder.append_data(&mut header, path, contents)?;
+	Ok(())
+}
+
+/// Unpacks an archive entry within a directory, rejecting any path outside of it.
+fn unpack<R: Read>(entry: &mut tar::Entry<R>, dir: &Path, path: &Path) -> Result<(), Error> {
+	if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_)))
+	{
+		return Err(Error::Environment(format!("invalid path within archive: {}", path.display())));
+	}
+	let destination = dir.join(path);
+	if let Some(parent) = destination.parent() {
+		create_dir_all(parent)?;
+	}
+	entry.unpack(destination)?;
+	Ok(())
+}
+
+/// Derives the encryption key from a passphrase.
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+	let mut key = [0u8; 32];
+	pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+	key
+}
+
+/// Encrypts data with a passphrase via ChaCha20-Poly1305, using a random salt and nonce. The
+/// result contains the salt, nonce and ciphertext, followed by the authentication tag.
+fn encrypt(data: &[u8], passphrase: &str, iterations: u32) -> Vec<u8> {
+	let mut salt = [0u8; SALT_LEN];
+	let mut nonce = [0u8; NONCE_LEN];
+	rand::thread_rng().fill_bytes(&mut salt);
+	rand::thread_rng().fill_bytes(&mut nonce);
+	let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt, iterations).into());
+	let ciphertext = cipher
+		.encrypt(&nonce.into(), data)
+		.expect("encryption only fails for inputs exceeding 256GiB");
+	[salt.as_slice(), nonce.as_slice(), &ciphertext].concat()
+}
+
+/// Decrypts data encrypted via [`encrypt`], failing if the passphrase is incorrect or the data
+/// has been tampered with.
+fn decrypt(encrypted: &[u8], passphrase: &str, iterations: u32) -> Result<Vec<u8>, Error> {
+	if encrypted.len() < SALT_LEN + NONCE_LEN + TAG_LEN {
+		return Err(Error::Environment("the encrypted keystore is truncated".into()));
+	}
+	let (salt, rest) = encrypted.split_at(SALT_LEN);
+	let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+	let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt, iterations).into());
+	let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("nonce is 12 bytes");
+	cipher.decrypt(&nonce.into(), ciphertext).map_err(|_| {
+		Error::Environment("unable to decrypt the keystore: incorrect passphrase".into())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::{create_dir_all, write};
+
+	const NETWORK: &str = "[relaychain]\nchain = \"paseo-local\"\n";
+
+	fn environment(root: &Path) -> Environment {
+		Environment {
+			project: root.join("project"),
+			cache: root.join("cache"),
+			keystore: Some(root.join("keystore")),
+		}
+	}
+
+	fn setup(root: &Path) -> Result<Environment> {
+		let environment = environment(root);
+		let project = &environment.project;
+		create_dir_all(project.join("networks"))?;
+		create_dir_all(project.join("target/release"))?;
+		write(project.join(CONFIG_FILE), "[hooks]\npre-build = \"echo\"")?;
+		write(project.join("networks/local.toml"), NETWORK)?;
+		write(project.join("target/release/network.toml"), NETWORK)?;
+		write(project.join("Cargo.toml"), "[package]\nname = \"parachain\"")?;
+		create_dir_all(&environment.cache)?;
+		write(environment.cache.join("polkadot-v1.13.0"), "polkadot")?;
+		write(environment.cache.join(".stats.jsonl"), "{}")?;
+		let keystore = environment.keystore.as_ref().unwrap();
+		create_dir_all(keystore.join("keys"))?;
+		write(keystore.join("keys/alice"), "//Alice")?;
+		Ok(environment)
+	}
+
+	#[test]
+	fn export_and_import_works() -> Result<()> {
+		let source = tempfile::tempdir()?;
+		let environment = setup(source.path())?;
+		let archive = source.path().join("environment.tar.gz");
+		let options =
+			ExportOptions { binaries: true, passphrase: Some("correct horse".to_string()) };
+		let exported = export_environment(&archive, &environment, &options)?;
+		assert_eq!(
+			exported,
+			Summary {
+				project: vec![PathBuf::from(CONFIG_FILE), PathBuf::from("networks/local.toml")],
+				binaries: vec![PathBuf::from("polkadot-v1.13.0")],
+				keystore: true,
+			}
+		);
+
+		let destination = tempfile::tempdir()?;
+		let imported = self::environment(destination.path());
+		assert!(matches!(
+			import_environment(&archive, &imported, None),
+			Err(Error::Environment(e)) if e.contains("passphrase is required")
+		));
+		assert!(matches!(
+			import_environment(&archive, &imported, Some("wrong")),
+			Err(Error::Environment(e)) if e.contains("incorrect passphrase")
+		));
+		assert_eq!(import_environment(&archive, &imported, Some("correct horse"))?, exported);
+		assert_eq!(read_to_string(imported.project.join("networks/local.toml"))?, NETWORK);
+		assert_eq!(read_to_string(imported.cache.join("polkadot-v1.13.0"))?, "polkadot");
+		assert!(!imported.cache.join(".stats.jsonl").exists());
+		assert_eq!(
+			read_to_string(imported.keystore.as_ref().unwrap().join("keys/alice"))?,
+			"//Alice"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn export_excludes_binaries_and_keystore_by_default() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let environment = setup(temp_dir.path())?;
+		let archive = temp_dir.path().join("environment.tar.gz");
+		let summary = export_environment(&archive, &environment, &ExportOptions::default())?;
+		assert!(summary.binaries.is_empty() && !summary.keystore);
+		assert_eq!(summary.project.len(), 2);
+		// No passphrase is required when the keystore is excluded.
+		let destination = tempfile::tempdir()?;
+		import_environment(&archive, &self::environment(destination.path()), None)?;
+		Ok(())
+	}
+
+	#[test]
+	fn encrypt_works() -> Result<()> {
+		let encrypted = encrypt(b"secret", "passphrase", 10);
+		assert_eq!(encrypted.len(), SALT_LEN + NONCE_LEN + 6 + TAG_LEN);
+		assert!(!encrypted.windows(6).any(|w| w == b"secret"));
+		assert_eq!(decrypt(&encrypted, "passphrase", 10)?, b"secret");
+		// A random nonce is used for each encryption.
+		assert_ne!(
+			encrypt(b"secret", "passphrase", 10)[SALT_LEN..SALT_LEN + NONCE_LEN],
+			encrypted[SALT_LEN..SALT_LEN + NONCE_LEN]
+		);
+		assert!(matches!(decrypt(&encrypted, "incorrect", 10), Err(Error::Environment(..))));
+		let mut tampered = encrypted.clone();
+		tampered[SALT_LEN + NONCE_LEN] ^= 1;
+		assert!(matches!(decrypt(&tampered, "passphrase", 10), Err(Error::Environment(..))));
+		assert!(matches!(decrypt(&encrypted[..10], "passphrase", 10), Err(Error::Environment(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn import_fails_without_manifest() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let archive = temp_dir.path().join("environment.tar.gz");
+		let mut builder =
+			Builder::new(GzEncoder::new(File::create(&archive)?, Compression::default()));
+		append(&mut builder, "project/pop.toml", b"")?;
+		builder.into_inner()?.finish()?;
+		assert!(matches!(
+			import_environment(&archive, &environment(temp_dir.path()), None),
+			Err(Error::Environment(e)) if e.contains("missing")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn import_rejects_links() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let archive = temp_dir.path().join("environment.tar.gz");
+		let environment = environment(temp_dir.path());
+		for entry_type in [EntryType::Symlink, EntryType::Link] {
+			let mut builder =
+				Builder::new(GzEncoder::new(File::create(&archive)?, Compression::default()));
+			append(&mut builder, MANIFEST, br#"{"version":2,"keystore_iterations":null}"#)?;
+			let mut header = Header::new_gnu();
+			header.set_entry_type(entry_type);
+			header.set_size(0);
+			builder.append_link(&mut header, "project/networks", "/etc")?;
+			append(&mut builder, "project/networks/passwd", b"")?;
+			builder.into_inner()?.finish()?;
+			assert!(matches!(
+				import_environment(&archive, &environment, None),
+				Err(Error::Environment(e)) if e.ends_with("archive: project/networks")
+			));
+		}
+		assert!(!environment.project.join("networks").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn import_rejects_excessive_iterations() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let archive = temp_dir.path().join("environment.tar.gz");
+		for iterations in [0, MAX_ITERATIONS + 1, u32::MAX] {
+			let mut builder =
+				Builder::new(GzEncoder::new(File::create(&archive)?, Compression::default()));
+			let manifest = format!(r#"{{"version":2,"keystore_iterations":{iterations}}}"#);
+			append(&mut builder, MANIFEST, manifest.as_bytes())?;
+			builder.into_inner()?.finish()?;
+			assert!(matches!(
+				import_environment(&archive, &environment(temp_dir.path()), Some("passphrase")),
+				Err(Error::Environment(e)) if e.contains("keystore iterations")
+			));
+		}
+		Ok(())
+	}
+}
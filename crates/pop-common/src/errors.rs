@@ -1,30 +1,67 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::{sourcing, templates};
+use crate::{i18n, sourcing, templates};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
-	#[error("Anyhow error: {0}")]
+	#[error("{}", localized("account", &[("0", .0.as_str())]))]
+	Account(String),
+	#[error("{}", localized("anyhow_error", &[("0", &.0.to_string())]))]
 	AnyhowError(#[from] anyhow::Error),
-	#[error("Configuration error: {0}")]
+	#[error("{}", localized("argument", &[("path", .path.as_str()), ("message", .message.as_str())]))]
+	Argument { path: String, message: String },
+	#[error("{}", localized("build", &[("0", .0.as_str())]))]
+	Build(String),
+	#[error("{}", localized("config", &[("0", .0.as_str())]))]
 	Config(String),
-	#[error("a git error occurred: {0}")]
+	#[error("{}", localized("container", &[("0", .0.as_str())]))]
+	Container(String),
+	#[error("{}", localized("environment", &[("0", .0.as_str())]))]
+	Environment(String),
+	#[error("{}", localized("git", &[("0", .0.as_str())]))]
 	Git(String),
-	#[error("IO error: {0}")]
+	#[error("{}", localized("hook", &[("0", .0.as_str())]))]
+	Hook(String),
+	#[error("{}", localized("io", &[("0", &.0.to_string())]))]
 	IO(#[from] std::io::Error),
-	#[error("Failed to get manifest path: {0}")]
+	#[error("{}", localized("key_pair_creation", &[("0", .0.as_str())]))]
+	KeyPairCreation(String),
+	#[error("{}", localized("manifest_path", &[("0", .0.as_str())]))]
 	ManifestPath(String),
-	#[error("Manifest error: {0}")]
+	#[error("{}", localized("manifest_error", &[("0", &.0.to_string())]))]
 	ManifestError(#[from] cargo_toml::Error),
-	#[error("ParseError error: {0}")]
+	#[error("{}", localized("notification", &[("0", .0.as_str())]))]
+	Notification(String),
+	#[error("{}", localized("parse_secret_uri", &[("0", .0.as_str())]))]
+	ParseSecretURI(String),
+	#[error("{}", localized("parse_error", &[("0", &.0.to_string())]))]
 	ParseError(#[from] url::ParseError),
-	#[error("SourceError error: {0}")]
+	#[error("{}", localized("preflight", &[("0", .0.as_str())]))]
+	Preflight(String),
+	#[error("{}", localized("remote", &[("0", .0.as_str())]))]
+	Remote(String),
+	#[error("{}", localized("signing", &[("0", .0.as_str())]))]
+	Signing(String),
+	#[error("{}", localized("source_error", &[("0", &.0.to_string())]))]
 	SourceError(#[from] sourcing::Error),
-	#[error("TemplateError error: {0}")]
+	#[error("{}", localized("template_error", &[("0", &.0.to_string())]))]
 	TemplateError(#[from] templates::Error),
-	#[error("Unsupported command: {0}")]
+	#[error("{}", localized("toolchain", &[("0", .0.as_str())]))]
+	Toolchain(String),
+	#[error("{}", localized("unsupported_command", &[("0", .0.as_str())]))]
 	UnsupportedCommand(String),
-	#[error("Unsupported platform: {arch} {os}")]
+	#[error("{}", localized("unsupported_platform", &[("arch", .arch), ("os", .os)]))]
 	UnsupportedPlatform { arch: &'static str, os: &'static str },
+	#[error("{}", localized("vendor", &[("0", .0.as_str())]))]
+	Vendor(String),
+}
+
+/// Formats the message of an error via the catalog of user-facing messages.
+///
+/// # Arguments
+/// * `variant` - The identifier of the error variant.
+/// * `args` - The arguments of the message, as (name, value) pairs.
+fn localized(variant: &str, args: &[(&str, &str)]) -> String {
+	i18n::message(&format!("error.common.{variant}"), args)
 }
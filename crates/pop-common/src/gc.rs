@@ -0,0 +1,497 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use serde::Deserialize;
+use std::{
+	fmt::{Display, Formatter},
+	fs::{metadata, read_dir, read_to_string, remove_file, Metadata},
+	os::unix::fs::PermissionsExt,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+
+/// The name of the file within the cache configuring its retention policy, hidden so that it is
+/// not listed as a cached artifact.
+pub const POLICY_FILE: &str = ".gc.toml";
+
+/// The directory within the cache in which snapshots of live chain state are stored.
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+/// The directory within the cache in which generated chain specifications are stored.
+pub const CHAIN_SPECS_DIR: &str = "chain-specs";
+
+/// The retention policy of the cache, evaluated after operations which source binaries into it or
+/// on request via `pop clean cache --gc`. Versioned binaries (e.g. `polkadot-v1.13.0`), snapshots
+/// and generated chain specifications are subject to it; other artifacts are never reclaimed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Policy {
+	/// The maximum total size of the cache, in MiB. The least recently modified artifacts are
+	/// removed until the cache is within the limit.
+	#[serde(default)]
+	pub max_size_mib: Option<u64>,
+	/// The number of most recent versions kept of each binary, or all if not specified. Only
+	/// applies to versioned binaries.
+	#[serde(default)]
+	pub keep_last: Option<usize>,
+	/// The number of days after which unmodified artifacts are removed.
+	#[serde(default)]
+	pub ttl_days: Option<u64>,
+}
+
+impl Policy {
+	/// Loads the retention policy configured within a cache, defaulting to retaining everything.
+	///
+	/// # Arguments
+	/// * `cache` - The cache directory.
+	pub fn load(cache: &Path) -> Result<Self, Error> {
+		let path = cache.join(POLICY_FILE);
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		toml_edit::de::from_str(&read_to_string(&path)?)
+			.map_err(|e| Error::Config(format!("invalid cache policy in {}: {e}", path.display())))
+	}
+}
+
+/// Why an artifact was reclaimed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reason {
+	/// Newer versions of the binary are cached.
+	Superseded,
+	/// The artifact has not been modified within the configured number of days.
+	Expired,
+	/// The cache exceeded its maximum size.
+	SizeLimit,
+}
+
+impl Display for Reason {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let reason = match self {
+			Reason::Superseded => "superseded by newer versions",
+			Reason::Expired => "expired",
+			Reason::SizeLimit => "cache size limit exceeded",
+		};
+		write!(f, "{reason}")
+	}
+}
+
+/// An artifact reclaimed from the cache.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reclaimed {
+	/// The path of the artifact.
+	pub path: PathBuf,
+	/// The size of the artifact, in bytes.
+	pub size: u64,
+	/// Why the artifact was reclaimed.
+	pub reason: Reason,
+	/// The metadata stored alongside the artifact, removed with it, if any.
+	pub metadata: Option<PathBuf>,
+}
+
+/// A report of the artifacts reclaimed from the cache.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+	/// The artifacts reclaimed.
+	pub reclaimed: Vec<Reclaimed>,
+	/// The total size of the artifacts subject to the policy remaining, excluding any protected,
+	/// in bytes.
+	pub remaining: u64,
+}
+
+impl Report {
+	/// The total size of the artifacts reclaimed, in bytes.
+	pub fn reclaimed_size(&self) -> u64 {
+		self.reclaimed.iter().map(|r| r.size).sum()
+	}
+}
+
+/// An artifact within the cache which may be reclaimed.
+#[derive(Clone, Debug)]
+struct Artifact {
+	path: PathBuf,
+	/// The size of the artifact, including that of its metadata.
+	size: u64,
+	modified: SystemTime,
+	/// The name of the binary, excluding its version, if the artifact is a versioned binary.
+	binary: Option<String>,
+	/// The metadata stored alongside the artifact, if any.
+	metadata: Option<PathBuf>,
+}
+
+/// Applies a retention policy to the cache.
+pub struct Collector {
+	/// The cache directory.
+	cache: PathBuf,
+	/// The retention policy.
+	policy: Policy,
+	/// Artifacts which are never reclaimed, such as those in use.
+	protected: Vec<PathBuf>,
+}
+
+impl Collector {
+	/// Creates a new collector for a cache.
+	///
+	/// # Arguments
+	/// * `cache` - The cache directory.
+	/// * `policy` - The retention policy.
+	pub fn new(cache: &Path, policy: Policy) -> Self {
+		Self { cache: cache.to_path_buf(), policy, protected: vec![] }
+	}
+
+	/// Protects artifacts from being reclaimed, such as those in use by the current operation.
+	///
+	/// # Arguments
+	/// * `paths` - The paths of the artifacts.
+	pub fn protect(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+		self.protected.extend(paths);
+		self
+	}
+
+	/// Determines the artifacts which would be reclaimed, without removing them.
+	pub fn plan(&self) -> Result<Report, Error> {
+		self.evaluate(SystemTime::now())
+	}
+
+	/// Reclaims the artifacts not retained by the policy.
+	pub fn collect(&self) -> Result<Report, Error> {
+		let report = self.plan()?;
+		for reclaimed in &report.reclaimed {
+			remove_file(&reclaimed.path)?;
+			if let Some(metadata) = &reclaimed.metadata {
+				remove_file(metadata)?;
+			}
+		}
+		Ok(report)
+	}
+
+	/// Evaluates the policy against the contents of the cache at the specified time.
+	fn evaluate(&self, now: SystemTime) -> Result<Report, Error> {
+		let mut artifacts = self.artifacts()?;
+		// Most recently modified first.
+		artifacts.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)));
+		let mut reclaimed: Vec<Reclaimed> = vec![];
+		let reclaim = |reclaimed: &mut Vec<Reclaimed>, artifact: &Artifact, reason| {
+			if !reclaimed.iter().any(|r| r.path == artifact.path) {
+				reclaimed.push(Reclaimed {
+					path: artifact.path.clone(),
+					size: artifact.size,
+					reason,
+					metadata: artifact.metadata.clone(),
+				});
+			}
+		};
+
+		if let Some(keep_last) = self.policy.keep_last {
+			let mut binaries: Vec<(&str, usize)> = vec![];
+			for artifact in &artifacts {
+				let Some(binary) = artifact.binary.as_deref() else { continue };
+				let kept = match binaries.iter_mut().find(|(b, _)| *b == binary) {
+					Some((_, kept)) => kept,
+					None => {
+						binaries.push((binary, 0));
+						&mut binaries.last_mut().expect("binary was just added").1
+					},
+				};
+				match *kept < keep_last {
+					true => *kept += 1,
+					false => reclaim(&mut reclaimed, artifact, Reason::Superseded),
+				}
+			}
+		}
+
+		if let Some(ttl) = self.policy.ttl_days.map(|days| Duration::from_secs(days * 24 * 60 * 60))
+		{
+			for artifact in &artifacts {
+				if now.duration_since(artifact.modified).is_ok_and(|age| age > ttl) {
+					reclaim(&mut reclaimed, artifact, Reason::Expired);
+				}
+			}
+		}
+
+		if let Some(max_size) = self.policy.max_size_mib.map(|mib| mib * 1024 * 1024) {
+			let mut size: u64 = artifacts
+				.iter()
+				.filter(|a| !reclaimed.iter().any(|r| r.path == a.path))
+				.map(|a| a.size)
+				.sum();
+			// Least recently modified first.
+			for artifact in artifacts.iter().rev() {
+				if size <= max_size {
+					break;
+				}
+				if !reclaimed.iter().any(|r| r.path == artifact.path) {
+					size -= artifact.size;
+					reclaim(&mut reclaimed, artifact, Reason::SizeLimit);
+				}
+			}
+		}
+
+		let total: u64 = artifacts.iter().map(|a| a.size).sum();
+		let remaining = total - reclaimed.iter().map(|r| r.size).sum::<u64>();
+		Ok(Report { reclaimed, remaining })
+	}
+
+	/// The artifacts within the cache which may be reclaimed: versioned binaries, snapshots and
+	/// generated chain specifications.
+	fn artifacts(&self) -> Result<Vec<Artifact>, Error> {
+		let mut artifacts = vec![];
+		for (path, name, metadata) in self.entries(&self.cache)? {
+			// Only executables are reclaimed, never directories or other files.
+			let executable = metadata.is_file() && metadata.permissions().mode() & 0o111 != 0;
+			let Some(binary) = binary_name(&name).filter(|_| executable) else {
+				continue;
+			};
+			artifacts.push(Artifact {
+				path,
+				size: metadata.len(),
+				modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+				binary: Some(binary),
+				metadata: None,
+			});
+		}
+		// Snapshots are stored alongside their metadata (e.g. `chain-latest.snap.json`).
+		for (path, name, metadata) in self.entries(&self.cache.join(SNAPSHOTS_DIR))? {
+			if !metadata.is_file() || name.ends_with(".json") {
+				continue;
+			}
+			let mut sidecar = path.clone().into_os_string();
+			sidecar.push(".json");
+			let sidecar = PathBuf::from(sidecar);
+			let sidecar = sidecar.exists().then_some(sidecar);
+			let sidecar_size =
+				sidecar.as_ref().and_then(|p| p.metadata().ok()).map_or(0, |m| m.len());
+			artifacts.push(Artifact {
+				path,
+				size: metadata.len() + sidecar_size,
+				modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+				binary: None,
+				metadata: sidecar,
+			});
+		}
+		// Specifications being generated are written to temporary files, which are skipped.
+		for (path, name, metadata) in self.entries(&self.cache.join(CHAIN_SPECS_DIR))? {
+			if !metadata.is_file() || !name.ends_with(".json") {
+				continue;
+			}
+			artifacts.push(Artifact {
+				path,
+				size: metadata.len(),
+				modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+				binary: None,
+				metadata: None,
+			});
+		}
+		Ok(artifacts)
+	}
+
+	/// The entries of a directory of the cache, excluding hidden and protected entries.
+	///
+	/// # Arguments
+	/// * `dir` - The directory.
+	fn entries(&self, dir: &Path) -> Result<Vec<(PathBuf, String, Metadata)>, Error> {
+		if !dir.exists() {
+			return Ok(vec![]);
+		}
+		let mut entries = vec![];
+		for entry in read_dir(dir)? {
+			let entry = entry?;
+			let name = entry.file_name().to_string_lossy().to_string();
+			let path = entry.path();
+			// Hidden files contain state and configuration rather than artifacts.
+			if name.starts_with('.') || self.protected.contains(&path) {
+				continue;
+			}
+			entries.push((path.clone(), name, metadata(&path)?));
+		}
+		Ok(entries)
+	}
+}
+
+/// The name of a binary, if the name of an artifact is that of a versioned binary (e.g.
+/// `polkadot-parachain-v1.13.0` or `polkadot-stable2409`), which has no extension.
+fn binary_name(artifact: &str) -> Option<String> {
+	// Versions contain periods, so an extension is identified by the segment after the last period
+	// starting with a letter (e.g. `chain-spec-v1.0.0.json`).
+	if artifact
+		.rsplit_once('.')
+		.is_some_and(|(_, ext)| ext.starts_with(|c: char| c.is_alphabetic()))
+	{
+		return None;
+	}
+	let segments: Vec<&str> = artifact.split('-').collect();
+	let is_version = |segment: &str| {
+		let version = segment.strip_prefix('v').unwrap_or(segment);
+		version.starts_with(|c: char| c.is_ascii_digit()) || segment.starts_with("stable")
+	};
+	let index = (1..segments.len()).find(|i| is_version(segments[*i]))?;
+	Some(segments[..index].join("-"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::{create_dir, set_permissions, write, File, Permissions};
+
+	const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+	/// Creates an executable artifact of the specified size, last modified the specified number of
+	/// days ago.
+	fn artifact(cache: &Path, name: &str, size: usize, age: u32) -> Result<PathBuf> {
+		let path = cache.join(name);
+		write(&path, vec![0u8; size])?;
+		set_permissions(&path, Permissions::from_mode(0o755))?;
+		File::options()
+			.write(true)
+			.open(&path)?
+			.set_modified(SystemTime::now() - DAY * age)?;
+		Ok(path)
+	}
+
+	fn reclaimed(report: &Report) -> Vec<(String, Reason)> {
+		report
+			.reclaimed
+			.iter()
+			.map(|r| (r.path.file_name().unwrap().to_string_lossy().to_string(), r.reason))
+			.collect()
+	}
+
+	#[test]
+	fn binary_name_works() {
+		assert_eq!(binary_name("polkadot-v1.13.0").as_deref(), Some("polkadot"));
+		assert_eq!(
+			binary_name("polkadot-parachain-v1.13.0").as_deref(),
+			Some("polkadot-parachain")
+		);
+		assert_eq!(binary_name("polkadot-stable2409").as_deref(), Some("polkadot"));
+		assert_eq!(binary_name("pop-node-0.1.0").as_deref(), Some("pop-node"));
+		assert_eq!(binary_name("pop-node-0.1.0-rc1").as_deref(), Some("pop-node"));
+		assert_eq!(binary_name("polkadot"), None);
+		assert_eq!(binary_name("chain-spec-v1.0.0.json"), None);
+		assert_eq!(binary_name("runtime-v1.0.0.compact.wasm"), None);
+		assert_eq!(binary_name("v1.0.0"), None);
+	}
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert_eq!(Policy::load(temp_dir.path())?, Policy::default());
+		write(temp_dir.path().join(POLICY_FILE), "max-size-mib = 2048\nttl-days = 30")?;
+		assert_eq!(
+			Policy::load(temp_dir.path())?,
+			Policy { max_size_mib: Some(2048), keep_last: None, ttl_days: Some(30) }
+		);
+		write(temp_dir.path().join(POLICY_FILE), "keep-last = \"all\"")?;
+		assert!(matches!(Policy::load(temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn keep_last_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		for (version, age) in [("v1.11.0", 3), ("v1.12.0", 2), ("v1.13.0", 1)] {
+			artifact(cache, &format!("polkadot-{version}"), 10, age)?;
+			artifact(cache, &format!("polkadot-parachain-{version}"), 10, age)?;
+		}
+		artifact(cache, "pop-node", 10, 10)?;
+		write(cache.join(POLICY_FILE), "keep-last = 2")?;
+		let collector = Collector::new(cache, Policy::load(cache)?);
+		let report = collector.plan()?;
+		assert_eq!(
+			reclaimed(&report),
+			vec![
+				("polkadot-parachain-v1.11.0".to_string(), Reason::Superseded),
+				("polkadot-v1.11.0".to_string(), Reason::Superseded)
+			]
+		);
+		assert_eq!((report.reclaimed_size(), report.remaining), (20, 40));
+		// Nothing is removed when planning.
+		assert!(cache.join("polkadot-v1.11.0").exists());
+
+		collector.collect()?;
+		assert!(!cache.join("polkadot-v1.11.0").exists());
+		assert!(cache.join("polkadot-v1.12.0").exists() && cache.join("pop-node").exists());
+		assert!(cache.join(POLICY_FILE).exists());
+		Ok(())
+	}
+
+	#[test]
+	fn ttl_and_max_size_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		artifact(cache, "old-v1.0.0", 10, 40)?;
+		artifact(cache, "older-v1.0.0", 10, 50)?;
+		let in_use = artifact(cache, "in-use-v1.0.0", 10, 60)?;
+		artifact(cache, "large-v1.0.0", 2 * 1024 * 1024, 5)?;
+		artifact(cache, "recent-v1.0.0", 1024 * 1024, 1)?;
+		// Neither unversioned binaries, other files nor directories are subject to the policy.
+		artifact(cache, "pop-node", 10, 60)?;
+		artifact(cache, "chain-spec-v1.0.0.json", 10, 60)?;
+		write(cache.join("notes-v1.0.0"), vec![0u8; 10])?;
+		create_dir(cache.join("other-v1"))?;
+		write(cache.join("other-v1/file"), vec![0u8; 20])?;
+
+		let policy = Policy { max_size_mib: Some(2), keep_last: None, ttl_days: Some(30) };
+		let report = Collector::new(cache, policy).protect([in_use]).plan()?;
+		assert_eq!(
+			reclaimed(&report),
+			vec![
+				("old-v1.0.0".to_string(), Reason::Expired),
+				("older-v1.0.0".to_string(), Reason::Expired),
+				("large-v1.0.0".to_string(), Reason::SizeLimit),
+			]
+		);
+		assert_eq!(report.remaining, 1024 * 1024);
+		Ok(())
+	}
+
+	#[test]
+	fn snapshots_and_chain_specs_are_reclaimed() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		create_dir(cache.join(SNAPSHOTS_DIR))?;
+		create_dir(cache.join(CHAIN_SPECS_DIR))?;
+		let snapshots = cache.join(SNAPSHOTS_DIR);
+		artifact(&snapshots, "paseo-latest.snap", 10, 40)?;
+		write(snapshots.join("paseo-latest.snap.json"), vec![0u8; 5])?;
+		artifact(&snapshots, "paseo-0x1234.snap", 2 * 1024 * 1024, 2)?;
+		write(snapshots.join("paseo-0x1234.snap.json"), vec![0u8; 5])?;
+		let chain_specs = cache.join(CHAIN_SPECS_DIR);
+		artifact(&chain_specs, "paseo-local-polkadot-v1.0.0-0123.json", 10, 40)?;
+		artifact(&chain_specs, "paseo-local-polkadot-v1.1.0-0123.tmp", 10, 40)?;
+		artifact(cache, "polkadot-v1.0.0", 1024 * 1024, 1)?;
+		// Versions are only kept of binaries.
+		let policy = Policy { max_size_mib: Some(2), keep_last: Some(0), ttl_days: Some(30) };
+		let report = Collector::new(cache, policy).collect()?;
+		assert_eq!(
+			reclaimed(&report),
+			vec![
+				("polkadot-v1.0.0".to_string(), Reason::Superseded),
+				("paseo-local-polkadot-v1.0.0-0123.json".to_string(), Reason::Expired),
+				("paseo-latest.snap".to_string(), Reason::Expired),
+				("paseo-0x1234.snap".to_string(), Reason::SizeLimit),
+			]
+		);
+		assert_eq!(report.reclaimed_size(), 3 * 1024 * 1024 + 30);
+		assert_eq!(report.remaining, 0);
+		for removed in ["paseo-latest.snap", "paseo-latest.snap.json", "paseo-0x1234.snap.json"] {
+			assert!(!snapshots.join(removed).exists());
+		}
+		assert!(chain_specs.join("paseo-local-polkadot-v1.1.0-0123.tmp").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn default_policy_retains_everything() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		for (version, age) in [("v1.11.0", 3), ("v1.12.0", 2), ("v1.13.0", 1), ("v1.14.0", 0)] {
+			artifact(cache, &format!("polkadot-{version}"), 10, age)?;
+		}
+		let report = Collector::new(cache, Policy::load(cache)?).collect()?;
+		assert!(report.reclaimed.is_empty());
+		assert_eq!(report.remaining, 40);
+		Ok(())
+	}
+}
@@ -55,12 +55,44 @@ pub fn prefix_with_current_dir_if_needed(path: PathBuf) -> PathBuf {
 	path
 }
 
+/// Decodes a hex string, with or without a `0x` prefix.
+///
+/// # Arguments
+/// * `hex` - The hex string to be decoded.
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+	let hex = hex.strip_prefix("0x").unwrap_or(hex);
+	if !hex.len().is_multiple_of(2) {
+		return None;
+	}
+	(0..hex.len())
+		.step_by(2)
+		.map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+		.collect()
+}
+
+/// Encodes bytes as a hex string, prefixed with `0x`.
+///
+/// # Arguments
+/// * `bytes` - The bytes to be encoded.
+pub fn encode_hex(bytes: &[u8]) -> String {
+	format!("0x{}", bytes.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use anyhow::Result;
 	use std::fs;
 
+	#[test]
+	fn decode_hex_works() {
+		assert_eq!(decode_hex("0x0aff"), Some(vec![0x0a, 0xff]));
+		assert_eq!(decode_hex("0aff"), Some(vec![0x0a, 0xff]));
+		assert_eq!(decode_hex("0xabc"), None);
+		assert_eq!(decode_hex("0xzz"), None);
+		assert_eq!(encode_hex(&[0x0a, 0xff]), "0x0aff");
+	}
+
 	#[test]
 	fn test_replace_in_file() -> Result<(), Error> {
 		let temp_dir = tempfile::tempdir()?;
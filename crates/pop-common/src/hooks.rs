@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{migration::read_config, Error};
+use duct::cmd;
+use serde::Deserialize;
+use std::{
+	collections::HashMap,
+	fmt::{Display, Formatter},
+	fs::{copy, create_dir_all},
+	path::{Path, PathBuf},
+};
+
+/// The name of the project configuration file containing lifecycle hooks.
+pub const CONFIG_FILE: &str = "pop.toml";
+
+/// A stage within the lifecycle of a project, at which hooks can be run.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stage {
+	/// Before a project is built.
+	PreBuild,
+	/// After a project has been built.
+	PostBuild,
+	/// Before a chain specification is generated.
+	PreSpec,
+	/// After a chain specification has been generated.
+	PostSpec,
+	/// Before a local network is launched.
+	PreLaunch,
+	/// After a local network has been launched.
+	PostLaunch,
+	/// Before a contract is deployed.
+	PreDeploy,
+	/// After a contract has been deployed.
+	PostDeploy,
+}
+
+impl Display for Stage {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		use Stage::*;
+		let stage = match self {
+			PreBuild => "pre-build",
+			PostBuild => "post-build",
+			PreSpec => "pre-spec",
+			PostSpec => "post-spec",
+			PreLaunch => "pre-launch",
+			PostLaunch => "post-launch",
+			PreDeploy => "pre-deploy",
+			PostDeploy => "post-deploy",
+		};
+		write!(f, "{stage}")
+	}
+}
+
+/// An action provided by pop which can be run as a hook.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "builtin", rename_all = "kebab-case")]
+pub enum Builtin {
+	/// Copies the artifacts of the stage to a directory, relative to the project.
+	CopyArtifacts {
+		/// The destination directory.
+		to: PathBuf,
+	},
+	/// Checks the formatting of the project via `cargo fmt`.
+	FmtCheck,
+}
+
+/// A hook to be run at a stage within the lifecycle of a project.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Hook {
+	/// A script, run via the shell from the project directory.
+	Script(String),
+	/// An action provided by pop.
+	Builtin(Builtin),
+}
+
+/// One or more hooks configured for a stage.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+	One(Hook),
+	Many(Vec<Hook>),
+}
+
+/// The contents of the project configuration file relevant to hooks.
+#[derive(Default, Deserialize)]
+struct Config {
+	#[serde(default)]
+	hooks: HashMap<Stage, OneOrMany>,
+}
+
+/// The context provided to hooks, exposed to scripts as environment variables.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HookContext {
+	/// The artifacts produced by the stage, keyed by name.
+	artifacts: Vec<(String, PathBuf)>,
+	/// Any additional values, keyed by name.
+	values: Vec<(String, String)>,
+}
+
+impl HookContext {
+	/// Adds an artifact produced by the stage, exposed as `POP_ARTIFACT_<NAME>`.
+	///
+	/// # Arguments
+	/// * `name` - The name of the artifact (e.g. `binary`).
+	/// * `path` - The path of the artifact.
+	pub fn artifact(mut self, name: &str, path: impl Into<PathBuf>) -> Self {
+		self.artifacts.push((name.to_string(), path.into()));
+		self
+	}
+
+	/// Adds a value, exposed as `POP_<NAME>`.
+	///
+	/// # Arguments
+	/// * `name` - The name of the value (e.g. `contract_address`).
+	/// * `value` - The value.
+	pub fn value(mut self, name: &str, value: impl Into<String>) -> Self {
+		self.values.push((name.to_string(), value.into()));
+		self
+	}
+
+	/// The environment variables exposed to scripts.
+	///
+	/// # Arguments
+	/// * `stage` - The stage being run.
+	/// * `project` - The project directory.
+	fn env(&self, stage: Stage, project: &Path) -> Vec<(String, String)> {
+		let var = |name: &str| name.to_uppercase().replace(['-', ' '], "_");
+		let mut env = vec![
+			("POP_HOOK".to_string(), stage.to_string()),
+			("POP_PROJECT_DIR".to_string(), project.display().to_string()),
+		];
+		env.extend(self.artifacts.iter().map(|(name, path)| {
+			(format!("POP_ARTIFACT_{}", var(name)), path.display().to_string())
+		}));
+		env.extend(
+			self.values
+				.iter()
+				.map(|(name, value)| (format!("POP_{}", var(name)), value.clone())),
+		);
+		env
+	}
+}
+
+/// The lifecycle hooks configured for a project.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Hooks {
+	/// The project directory.
+	project: PathBuf,
+	/// The hooks configured for each stage.
+	hooks: HashMap<Stage, Vec<Hook>>,
+}
+
+impl Hooks {
+	/// Loads the hooks configured within the `[hooks]` table of the `pop.toml` file of a project,
+	/// if any.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn load(project: &Path) -> Result<Self, Error> {
+		let path = project.join(CONFIG_FILE);
+		let config: Config = match read_config(project)? {
+			Some(config) => toml_edit::de::from_str(&config)
+				.map_err(|e| Error::Config(format!("invalid hooks in {}: {e}", path.display())))?,
+			None => Config::default(),
+		};
+		let hooks = config
+			.hooks
+			.into_iter()
+			.map(|(stage, hooks)| match hooks {
+				OneOrMany::One(hook) => (stage, vec![hook]),
+				OneOrMany::Many(hooks) => (stage, hooks),
+			})
+			.collect();
+		Ok(Self { project: project.to_path_buf(), hooks })
+	}
+
+	/// The hooks configured for a stage.
+	///
+	/// # Arguments
+	/// * `stage` - The stage.
+	pub fn get(&self, stage: Stage) -> &[Hook] {
+		self.hooks.get(&stage).map(Vec::as_slice).unwrap_or_default()
+	}
+
+	/// Runs the hooks configured for a stage in order, stopping at the first failure.
+	///
+	/// # Arguments
+	/// * `stage` - The stage.
+	/// * `context` - The context provided to the hooks.
+	pub fn run(&self, stage: Stage, context: &HookContext) -> Result<(), Error> {
+		for hook in self.get(stage) {
+			match hook {
+				Hook::Script(script) => {
+					let mut expression = cmd("sh", ["-c", script.as_str()]).dir(&self.project);
+					for (key, value) in context.env(stage, &self.project) {
+						expression = expression.env(key, value);
+					}
+					let output = expression.stdout_to_stderr().unchecked().run()?;
+					if !output.status.success() {
+						return Err(Error::Hook(format!(
+							"{stage} hook `{script}` failed: {}",
+							output.status
+						)));
+					}
+				},
+				Hook::Builtin(Builtin::CopyArtifacts { to }) => {
+					let destination = self.project.join(to);
+					create_dir_all(&destination)?;
+					for (_, path) in &context.artifacts {
+						let name = path.file_name().ok_or_else(|| {
+							Error::Hook(format!("{stage} hook cannot copy {}", path.display()))
+						})?;
+						copy(path, destination.join(name))?;
+					}
+				},
+				Hook::Builtin(Builtin::FmtCheck) => {
+					let output = cmd("cargo", ["fmt", "--all", "--check"])
+						.dir(&self.project)
+						.stdout_to_stderr()
+						.unchecked()
+						.run()?;
+					if !output.status.success() {
+						return Err(Error::Hook(format!(
+							"{stage} hook `fmt-check` failed: the project is not formatted"
+						)));
+					}
+				},
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::{read_to_string, write};
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(Hooks::load(temp_dir.path())?.get(Stage::PreBuild).is_empty());
+
+		write(
+			temp_dir.path().join(CONFIG_FILE),
+			r#"
+			[hooks]
+			pre-build = "./scripts/check.sh"
+			post-build = ["echo built", { builtin = "copy-artifacts", to = "dist" }]
+			pre-deploy = { builtin = "fmt-check" }
+			"#,
+		)?;
+		let hooks = Hooks::load(temp_dir.path())?;
+		assert_eq!(hooks.get(Stage::PreBuild), [Hook::Script("./scripts/check.sh".to_string())]);
+		assert_eq!(
+			hooks.get(Stage::PostBuild),
+			[
+				Hook::Script("echo built".to_string()),
+				Hook::Builtin(Builtin::CopyArtifacts { to: PathBuf::from("dist") })
+			]
+		);
+		assert_eq!(hooks.get(Stage::PreDeploy), [Hook::Builtin(Builtin::FmtCheck)]);
+		assert!(hooks.get(Stage::PostLaunch).is_empty());
+
+		write(temp_dir.path().join(CONFIG_FILE), "[hooks]\non-build = \"echo\"")?;
+		assert!(matches!(Hooks::load(temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn run_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path();
+		let artifact = project.join("node");
+		write(&artifact, "binary")?;
+		write(
+			project.join(CONFIG_FILE),
+			r#"
+			[hooks]
+			post-build = [
+				'echo "$POP_HOOK $POP_ARTIFACT_BINARY $POP_PARA_ID" > output',
+				{ builtin = "copy-artifacts", to = "dist" },
+			]
+			pre-build = "exit 1"
+			"#,
+		)?;
+		let hooks = Hooks::load(project)?;
+		let context = HookContext::default().artifact("binary", &artifact).value("para-id", "2000");
+		hooks.run(Stage::PostBuild, &context)?;
+		assert_eq!(
+			read_to_string(project.join("output"))?,
+			format!("post-build {} 2000\n", artifact.display())
+		);
+		assert_eq!(read_to_string(project.join("dist/node"))?, "binary");
+		// Stages without hooks are a no-op.
+		hooks.run(Stage::PreLaunch, &context)?;
+		assert!(matches!(
+			hooks.run(Stage::PreBuild, &context),
+			Err(Error::Hook(e)) if e.starts_with("pre-build hook `exit 1` failed")
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,513 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use std::{
+	collections::HashMap,
+	env,
+	fs::read_to_string,
+	path::Path,
+	sync::{OnceLock, RwLock},
+};
+
+/// The locale of the built-in messages.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// The built-in messages, keyed by message identifier.
+const MESSAGES: [(&str, &str); 166] = [
+	("error.common.account", "Invalid account: {0}"),
+	("error.common.anyhow_error", "Anyhow error: {0}"),
+	("error.common.argument", "Invalid argument `{path}`: {message}"),
+	("error.common.build", "Build error: {0}"),
+	("error.common.config", "Configuration error: {0}"),
+	("error.common.container", "Container error: {0}"),
+	("error.common.environment", "Environment error: {0}"),
+	("error.common.git", "a git error occurred: {0}"),
+	("error.common.hook", "Hook error: {0}"),
+	("error.common.io", "IO error: {0}"),
+	("error.common.key_pair_creation", "Failed to create keypair from URI: {0}"),
+	("error.common.manifest_error", "Manifest error: {0}"),
+	("error.common.manifest_path", "Failed to get manifest path: {0}"),
+	("error.common.notification", "Notification error: {0}"),
+	("error.common.parse_error", "ParseError error: {0}"),
+	("error.common.parse_secret_uri", "Failed to parse secret URI: {0}"),
+	("error.common.preflight", "Preflight check failed: {0}"),
+	("error.common.remote", "Remote execution error: {0}"),
+	("error.common.signing", "Signing error: {0}"),
+	("error.common.source_error", "SourceError error: {0}"),
+	("error.common.template_error", "TemplateError error: {0}"),
+	("error.common.toolchain", "Toolchain error: {0}"),
+	("error.common.unsupported_command", "Unsupported command: {0}"),
+	("error.common.unsupported_platform", "Unsupported platform: {arch} {os}"),
+	("error.common.vendor", "Vendoring error: {0}"),
+	("error.contracts.account_address_parsing", "Failed to parse account address: {0}"),
+	("error.contracts.anyhow_error", "Anyhow error: {0}"),
+	("error.contracts.balance_parsing", "Failed to parse balance: {0}"),
+	("error.contracts.call_contract_error", "{0}"),
+	("error.contracts.common_error", "{0}"),
+	("error.contracts.dry_run_call_contract_error", "Pre-submission dry-run failed: {0}"),
+	("error.contracts.dry_run_upload_contract_error", "Pre-submission dry-run failed: {0}"),
+	("error.contracts.hex_parsing", "Failed to parse hex encoded bytes: {0}"),
+	("error.contracts.http_error", "HTTP error: {0}"),
+	("error.contracts.install_contracts_node", "Failed to install {0}"),
+	("error.contracts.instantiate_contract_error", "{0}"),
+	("error.contracts.invalid_name", "Invalid name: {0}"),
+	("error.contracts.io", "IO error: {0}"),
+	("error.contracts.key_pair_creation", "Failed to create keypair from URI: {0}"),
+	("error.contracts.manifest_path", "Failed to get manifest path: {0}"),
+	("error.contracts.new_contract", "Failed to create new contract project: {0}"),
+	("error.contracts.parse_error", "ParseError error: {0}"),
+	("error.contracts.parse_secret_uri", "Failed to parse secret URI: {0}"),
+	(
+		"error.contracts.repository_missing",
+		"The `Repository` property is missing from the template variant",
+	),
+	("error.contracts.repro", "Invalid reproduction bundle: {0}"),
+	("error.contracts.sourcing_error", "Sourcing error {0}"),
+	("error.contracts.subxt_error", "Subxt error: {0}"),
+	("error.contracts.test_command", "Failed to execute test command: {0}"),
+	("error.contracts.unsupported_chain", "Unsupported chain: {0}"),
+	("error.contracts.unsupported_platform", "Unsupported platform: {os}"),
+	("error.contracts.upload_contract_error", "{0}"),
+	("error.parachains.aborted", "User aborted due to existing target directory."),
+	("error.parachains.anyhow_error", "Anyhow error: {0}"),
+	("error.parachains.ceremony_error", "Multisig ceremony error: {0}"),
+	("error.parachains.common_error", "{0}"),
+	("error.parachains.config", "Configuration error: {0}"),
+	("error.parachains.connection_failure", "Failed to establish a connection: {0}"),
+	("error.parachains.current_dir_access", "Failed to access the current directory"),
+	("error.parachains.endowment_error", "Failed to parse the endowment value"),
+	("error.parachains.export_error", "Export error: {0}"),
+	("error.parachains.extrinsic_submission_error", "Failed to submit the extrinsic: {0}"),
+	("error.parachains.faucet_error", "Faucet error: {0}"),
+	("error.parachains.incompatible_signer", "Incompatible signer: {0}"),
+	("error.parachains.invalid_account", "Invalid account: {0}"),
+	("error.parachains.invalid_multiaddr", "Invalid multiaddress: {0}"),
+	("error.parachains.invalid_runtime", "Invalid runtime: {0}"),
+	("error.parachains.io", "IO error: {0}"),
+	("error.parachains.json_error", "JSON error: {0}"),
+	("error.parachains.missing_binary", "Missing binary: {0}"),
+	("error.parachains.missing_chain_spec", "Missing chain spec file at: {0}"),
+	("error.parachains.missing_command", "Command {command} doesn't exist in binary {binary}"),
+	("error.parachains.not_ready", "The network is not ready: {0}"),
+	("error.parachains.onboarding_error", "Onboarding error: {0}"),
+	("error.parachains.orchestrator_error", "Orchestrator error: {0}"),
+	("error.parachains.pallet_dir_creation", "Failed to create pallet directory"),
+	("error.parachains.path_error", "Invalid path"),
+	("error.parachains.rustfmt_error", "Failed to execute rustfmt"),
+	("error.parachains.sourcing_error", "Template error: {0}"),
+	("error.parachains.subxt_error", "Subxt error: {0}"),
+	("error.parachains.toml_error", "Toml error: {0}"),
+	("error.parachains.unsupported_command", "Unsupported command: {0}"),
+	("error.parachains.upgrade_error", "Runtime upgrade error: {0}"),
+	("error.parachains.workspace_locate", "Failed to locate the workspace"),
+	("error.sourcing.anyhow_error", "Anyhow error: {0}"),
+	("error.sourcing.archive_error", "Archive error: {0}"),
+	("error.sourcing.http_error", "HTTP error: {0}"),
+	("error.sourcing.io", "IO error: {0}"),
+	("error.sourcing.missing_binary", "Missing binary: {0}"),
+	("error.sourcing.parse_error", "ParseError error: {0}"),
+	(
+		"error.templates.repository_missing",
+		"The `Repository` property is missing from the template variant",
+	),
+	(
+		"error.templates.type_missing",
+		"The `TypeMissing` property is missing from the template variant",
+	),
+	("faucet.already_dripped", "already dripped, retry in {retry_after}s"),
+	("faucet.captcha_failed", "unable to verify the captcha: {error}"),
+	("faucet.captcha_required", "a captcha is required"),
+	("faucet.drip_failed", "unable to drip: {error}"),
+	("faucet.incomplete_request", "incomplete request"),
+	("faucet.invalid_address", "invalid address: {error}"),
+	("faucet.invalid_body", "expected a JSON body with an `address`"),
+	("faucet.invalid_captcha", "invalid captcha"),
+	("faucet.invalid_content_length", "invalid content length"),
+	("faucet.invalid_request", "invalid request"),
+	("faucet.invalid_request_line", "invalid request line"),
+	("faucet.method_not_allowed", "method not allowed"),
+	("faucet.not_found", "not found"),
+	("faucet.request_timed_out", "request timed out"),
+	("faucet.request_too_large", "request too large"),
+	(
+		"hrmp.open_failed",
+		"the HRMP channel from {sender} to {recipient} could not be opened: {error}",
+	),
+	("onboarding.check_clang", "`clang` is installed"),
+	("onboarding.check_compiles", "the {template} project compiles"),
+	("onboarding.check_protoc", "`protoc` is installed"),
+	("onboarding.check_rust_src", "the `rust-src` component is installed"),
+	("onboarding.check_wasm_target", "the `wasm32-unknown-unknown` target is installed"),
+	(
+		"onboarding.remedy_clang",
+		"install clang, e.g. via `xcode-select --install` or `apt install clang`",
+	),
+	(
+		"onboarding.remedy_protoc",
+		"install the protobuf compiler, e.g. via `brew install protobuf` or `apt install protobuf-compiler`",
+	),
+	("onboarding.step_build", "Build the parachain"),
+	("onboarding.step_deploy_contract", "Deploy the contract to the running parachain"),
+	("onboarding.step_enter", "Enter the project"),
+	("onboarding.step_launch", "Launch the parachain on a local network"),
+	("onboarding.step_new_contract", "Generate a smart contract"),
+	("preflight.disk", "{available} of free disk space available, {required} required: {guidance}"),
+	(
+		"preflight.disk_guidance",
+		"free up space, e.g. via `cargo clean` in unused projects or by removing cached binaries",
+	),
+	("preflight.file_descriptors", "open file limit of {limit}, {required} required: {guidance}"),
+	("preflight.file_descriptors_guidance", "raise the limit, e.g. via `ulimit -n 4096`"),
+	(
+		"preflight.insufficient",
+		"insufficient resources to {operation}: {shortfalls}. Set `{variable}=1` to skip these checks.",
+	),
+	("preflight.memory", "{available} of memory available, {required} required: {guidance}"),
+	(
+		"preflight.memory_guidance",
+		"close other applications, or reduce parallelism via `CARGO_BUILD_JOBS`",
+	),
+	("proxy.connection_failed", "unable to connect to {upstream}: {error}"),
+	("proxy.invalid_filter", "invalid RPC filter `{pattern}`: {error}"),
+	(
+		"proxy.unsupported_upstream",
+		"the proxy only supports unencrypted WebSocket (`ws://`) upstream endpoints: {upstream}",
+	),
+	("registrar.checking", "Checking the relay chain..."),
+	("registrar.failed", "the registration failed: {error}"),
+	(
+		"registrar.force_registering",
+		"Force registering parachain {para_id} via `paras_sudo_wrapper`...",
+	),
+	(
+		"registrar.registered",
+		"Parachain {para_id} registered, to be onboarded at the start of a subsequent session",
+	),
+	(
+		"registrar.registering",
+		"Registering parachain {para_id} via `registrar.register`, reserving a deposit of {deposit} in addition to the {reservation} reserved with its identifier...",
+	),
+	("setup.cache", "Where should downloaded binaries and artifacts be cached?"),
+	("setup.invalid_answer", "invalid answer to: {prompt}"),
+	("setup.invalid_option", "`{answer}` is not one of: {options}"),
+	("setup.relay_chain", "Which relay chain should be used by default for local networks?"),
+	("setup.telemetry", "Would you like to share anonymous usage metrics to help improve pop?"),
+	("setup.toolchain", "Checking the required toolchain"),
+	("sourcing.building", "Starting build of binary..."),
+	("sourcing.cloning", "Cloning {url}..."),
+	("sourcing.complete", "Sourcing complete."),
+	("sourcing.downloading", "Downloading from {url}..."),
+	("sourcing.extracting", "Extracting from archive..."),
+	("upgrade.authorization_failed", "the authorization failed: {error}"),
+	(
+		"upgrade.authorizing",
+		"Authorizing the upgrade of parachain {para_id} to code hash {hash}...",
+	),
+	(
+		"upgrade.awaiting_enactment",
+		"Waiting for the enactment of the upgrade of parachain {para_id}...",
+	),
+	("upgrade.block_not_after", "block {block} is not after the current block {current}"),
+	("upgrade.enacting", "Enacting the authorized upgrade of parachain {para_id}..."),
+	(
+		"upgrade.scheduling",
+		"Scheduling the enactment of the upgrade of parachain {para_id} at block {block}...",
+	),
+	("upgrade.scheduling_failed", "the scheduling failed: {error}"),
+	("upgrade.session_not_after", "session {session} is not after the current session {current}"),
+	("upgrade.set_code_failed", "the upgrade was rejected: {error}"),
+	("upgrade.time_not_future", "the requested time is not in the future"),
+	("upgrade.time_too_far", "the requested time is too far in the future"),
+	(
+		"upgrade.waiting",
+		"Waiting for the new runtime of parachain {para_id} to be accepted by the relay chain...",
+	),
+	("watchdog.gave_up", "{node} is no longer restarted, having been restarted {restarts} times"),
+	("watchdog.gave_up_reason", "no longer restarted, having been restarted {restarts} times"),
+	("watchdog.no_response", "no response within {limit}s"),
+	("watchdog.recovered", "{node} has recovered"),
+	("watchdog.restart_failed", "{node} could not be restarted: {reason}"),
+	("watchdog.restart_failed_reason", "could not be restarted: {reason}"),
+	("watchdog.restarted", "{node} was restarted"),
+	("watchdog.restarting", "Restarting {node} in {delay}s (attempt {attempt})"),
+	("watchdog.unhealthy", "{node} is unhealthy: {reason}"),
+];
+
+/// The catalog used for messages, selected once per process.
+static CATALOG: OnceLock<RwLock<Catalog>> = OnceLock::new();
+
+/// A catalog of user-facing messages for a locale, falling back to the built-in messages for any
+/// message not translated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Catalog {
+	/// The locale of the catalog.
+	locale: String,
+	/// The message templates, keyed by message identifier.
+	messages: HashMap<String, String>,
+}
+
+impl Default for Catalog {
+	fn default() -> Self {
+		Self {
+			locale: DEFAULT_LOCALE.to_string(),
+			messages: MESSAGES.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+		}
+	}
+}
+
+impl Catalog {
+	/// Loads the catalog for a locale from a directory of translations, where each locale is a
+	/// TOML file of message templates keyed by message identifier (e.g. `es.toml`). Falls back to
+	/// the built-in messages if no translation is available.
+	///
+	/// # Arguments
+	/// * `locale` - The locale (e.g. `es` or `es-ES`).
+	/// * `dir` - The directory containing translations.
+	pub fn load(locale: &str, dir: &Path) -> Result<Self, Error> {
+		let mut catalog = Self::default();
+		// Prefer the most specific translation available, e.g. `es-ES` over `es`.
+		let language = locale.split(['-', '_']).next().unwrap_or(locale);
+		for candidate in [language, locale] {
+			let path = dir.join(format!("{candidate}.toml"));
+			if !path.exists() {
+				continue;
+			}
+			let messages: HashMap<String, String> =
+				toml_edit::de::from_str(&read_to_string(&path)?).map_err(|e| {
+					Error::Config(format!("invalid translations in {}: {e}", path.display()))
+				})?;
+			catalog.messages.extend(messages);
+			catalog.locale = candidate.to_string();
+		}
+		Ok(catalog)
+	}
+
+	/// The locale of the catalog.
+	pub fn locale(&self) -> &str {
+		&self.locale
+	}
+
+	/// Formats a message, substituting each `{name}` placeholder with its argument in a single
+	/// pass, so that placeholders within arguments are left as is. Placeholders without an
+	/// argument are also left as is. Unknown messages are returned as their identifier.
+	///
+	/// # Arguments
+	/// * `key` - The message identifier.
+	/// * `args` - The arguments of the message, as (name, value) pairs.
+	pub fn message(&self, key: &str, args: &[(&str, &str)]) -> String {
+		let template = self.messages.get(key).map_or(key, String::as_str);
+		let mut message = String::with_capacity(template.len());
+		let mut rest = template;
+		while let Some(start) = rest.find('{') {
+			message.push_str(&rest[..start]);
+			rest = &rest[start..];
+			let argument = rest.find('}').and_then(|end| {
+				let name = &rest[1..end];
+				args.iter().find(|(n, _)| *n == name).map(|(_, value)| (end, *value))
+			});
+			match argument {
+				Some((end, value)) => {
+					message.push_str(value);
+					rest = &rest[end + 1..];
+				},
+				None => {
+					message.push('{');
+					rest = &rest[1..];
+				},
+			}
+		}
+		message.push_str(rest);
+		message
+	}
+}
+
+/// Determines the preferred locale from the environment, using `POP_LANG` before the standard
+/// `LC_ALL`, `LC_MESSAGES` and `LANG` variables.
+pub fn locale_from_env() -> String {
+	preferred_locale(|var| env::var(var).ok())
+}
+
+/// Determines the preferred locale from the values of the locale variables, in order of
+/// precedence.
+///
+/// # Arguments
+/// * `var` - Looks up the value of a variable.
+fn preferred_locale(var: impl Fn(&str) -> Option<String>) -> String {
+	["POP_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+		.iter()
+		.filter_map(|name| var(name))
+		.map(|value| parse_locale(&value))
+		.find(|locale| !locale.is_empty())
+		.unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Normalizes a POSIX locale (e.g. `es_ES.UTF-8`) to a language tag (e.g. `es-ES`).
+fn parse_locale(value: &str) -> String {
+	let locale = value.split(['.', '@']).next().unwrap_or_default();
+	match locale {
+		"C" | "POSIX" => DEFAULT_LOCALE.to_string(),
+		locale => locale.replace('_', "-"),
+	}
+}
+
+/// Loads the catalog for the locale preferred by the environment, falling back to the built-in
+/// messages if its translations are unavailable or invalid.
+///
+/// # Arguments
+/// * `dir` - The directory containing translations.
+pub fn catalog_from_env(dir: &Path) -> Catalog {
+	catalog_for(&locale_from_env(), dir)
+}
+
+/// Loads the catalog for a locale, falling back to the built-in messages if its translations are
+/// unavailable or invalid.
+///
+/// # Arguments
+/// * `locale` - The locale (e.g. `es` or `es-ES`).
+/// * `dir` - The directory containing translations.
+pub fn catalog_for(locale: &str, dir: &Path) -> Catalog {
+	Catalog::load(locale, dir).unwrap_or_default()
+}
+
+/// Sets the catalog used for messages produced by the library crates.
+///
+/// # Arguments
+/// * `catalog` - The catalog to be used.
+pub fn set_catalog(catalog: Catalog) {
+	let lock = CATALOG.get_or_init(|| RwLock::new(Catalog::default()));
+	*lock.write().expect("lock not poisoned") = catalog;
+}
+
+/// Formats a message using the current catalog.
+///
+/// # Arguments
+/// * `key` - The message identifier.
+/// * `args` - The arguments of the message, as (name, value) pairs.
+pub fn message(key: &str, args: &[(&str, &str)]) -> String {
+	CATALOG
+		.get_or_init(|| RwLock::new(Catalog::default()))
+		.read()
+		.expect("lock not poisoned")
+		.message(key, args)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	#[test]
+	fn message_works() {
+		let catalog = Catalog::default();
+		assert_eq!(catalog.locale(), "en");
+		assert_eq!(
+			catalog.message("sourcing.downloading", &[("url", "https://example.com")]),
+			"Downloading from https://example.com..."
+		);
+		assert_eq!(catalog.message("unknown.message", &[]), "unknown.message");
+	}
+
+	#[test]
+	fn message_substitutes_placeholders_in_one_pass() {
+		let catalog = Catalog::default();
+		assert_eq!(
+			catalog.message(
+				"watchdog.unhealthy",
+				&[("node", "{reason}"), ("reason", "no response within {limit}s")]
+			),
+			"{reason} is unhealthy: no response within {limit}s"
+		);
+		// Placeholders without an argument are left as is.
+		assert_eq!(
+			catalog.message("watchdog.unhealthy", &[("node", "alice")]),
+			"alice is unhealthy: {reason}"
+		);
+	}
+
+	#[test]
+	fn errors_use_built_in_messages() {
+		assert_eq!(
+			Error::Argument { path: "a.b".into(), message: "invalid".into() }.to_string(),
+			"Invalid argument `a.b`: invalid"
+		);
+		assert_eq!(Error::Config("{0}".into()).to_string(), "Configuration error: {0}");
+		assert_eq!(
+			crate::sourcing::Error::MissingBinary("polkadot".into()).to_string(),
+			"Missing binary: polkadot"
+		);
+		assert_eq!(
+			crate::templates::Error::TypeMissing.to_string(),
+			"The `TypeMissing` property is missing from the template variant"
+		);
+	}
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		write(
+			temp_dir.path().join("es.toml"),
+			r#""sourcing.downloading" = "Descargando desde {url}...""#,
+		)?;
+		write(temp_dir.path().join("es-AR.toml"), r#""sourcing.complete" = "Listo.""#)?;
+
+		let catalog = Catalog::load("es-AR", temp_dir.path())?;
+		assert_eq!(catalog.locale(), "es-AR");
+		assert_eq!(
+			catalog.message("sourcing.downloading", &[("url", "https://example.com")]),
+			"Descargando desde https://example.com..."
+		);
+		assert_eq!(catalog.message("sourcing.complete", &[]), "Listo.");
+		// Untranslated messages fall back to the built-in messages.
+		assert_eq!(catalog.message("sourcing.extracting", &[]), "Extracting from archive...");
+
+		let catalog = Catalog::load("fr", temp_dir.path())?;
+		assert_eq!(catalog, Catalog::default());
+
+		write(temp_dir.path().join("de.toml"), "invalid")?;
+		assert!(matches!(Catalog::load("de", temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn catalog_for_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		write(temp_dir.path().join("es.toml"), r#""setup.toolchain" = "Comprobando""#)?;
+		let catalog = catalog_for("es-ES", temp_dir.path());
+		assert_eq!(catalog.locale(), "es");
+		assert_eq!(catalog.message("setup.toolchain", &[]), "Comprobando");
+		// Invalid translations fall back to the built-in messages.
+		write(temp_dir.path().join("es.toml"), "invalid")?;
+		assert_eq!(catalog_for("es-ES", temp_dir.path()), Catalog::default());
+		Ok(())
+	}
+
+	#[test]
+	fn preferred_locale_works() {
+		let vars = |vars: &'static [(&'static str, &'static str)]| {
+			move |name: &str| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+		};
+		assert_eq!(preferred_locale(vars(&[])), "en");
+		assert_eq!(preferred_locale(vars(&[("LANG", "de_DE.UTF-8")])), "de-DE");
+		assert_eq!(
+			preferred_locale(vars(&[("LANG", "de_DE.UTF-8"), ("POP_LANG", "es_ES.UTF-8")])),
+			"es-ES"
+		);
+		// Empty values are skipped.
+		assert_eq!(preferred_locale(vars(&[("LC_ALL", ""), ("LANG", "fr")])), "fr");
+	}
+
+	#[test]
+	fn built_in_messages_are_unique() {
+		let keys: std::collections::HashSet<_> = MESSAGES.iter().map(|(k, _)| k).collect();
+		assert_eq!(keys.len(), MESSAGES.len());
+	}
+
+	#[test]
+	fn parse_locale_works() {
+		assert_eq!(parse_locale("es_ES.UTF-8"), "es-ES");
+		assert_eq!(parse_locale("de_DE@euro"), "de-DE");
+		assert_eq!(parse_locale("C"), "en");
+		assert_eq!(parse_locale(""), "");
+	}
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{create_signer, Account, Error};
+use serde::{Deserialize, Serialize};
+use std::{fs::write, path::Path};
+pub use subxt_signer::DEV_PHRASE;
+
+/// An account derived from a base mnemonic, as exported within a keyring.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DerivedAccount {
+	/// The secret key URI of the account (e.g. `<mnemonic>//3`).
+	pub suri: String,
+	/// The SS58 address of the account, using the generic Substrate prefix.
+	pub address: String,
+	/// The hex-encoded public key of the account.
+	pub public_key: String,
+}
+
+/// Derives a batch of sr25519 accounts from a base mnemonic, using the hard derivation paths
+/// `//0` to `//<count - 1>`, so that load tests can submit transactions from many accounts.
+///
+/// # Arguments
+/// * `mnemonic` - The base mnemonic (e.g. [`DEV_PHRASE`]).
+/// * `count` - The number of accounts to be derived.
+pub fn derive_accounts(mnemonic: &str, count: usize) -> Result<Vec<DerivedAccount>, Error> {
+	(0..count)
+		.map(|i| {
+			let suri = format!("{mnemonic}//{i}");
+			let account = Account::Id32(create_signer(&suri)?.public_key().0);
+			Ok(DerivedAccount { address: account.to_string(), public_key: account.to_hex(), suri })
+		})
+		.collect()
+}
+
+/// Exports accounts as a JSON keyring, as consumed by load generators and end-to-end tests.
+///
+/// # Arguments
+/// * `accounts` - The accounts to be exported.
+/// * `path` - The path of the keyring.
+pub fn export_keyring(accounts: &[DerivedAccount], path: &Path) -> Result<(), Error> {
+	let contents = serde_json::to_string_pretty(accounts)
+		.map_err(|e| Error::Config(format!("unable to serialize the keyring: {e}")))?;
+	write(path, contents)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn derive_accounts_works() -> Result<()> {
+		let accounts = derive_accounts(DEV_PHRASE, 3)?;
+		assert_eq!(accounts.len(), 3);
+		assert_eq!(accounts[0].suri, format!("{DEV_PHRASE}//0"));
+		assert_eq!(
+			Account::parse(&accounts[1].address)?,
+			Account::Id32(create_signer(&format!("{DEV_PHRASE}//1"))?.public_key().0)
+		);
+		assert_eq!(Account::parse(&accounts[2].public_key)?.to_string(), accounts[2].address);
+		// Derivation is deterministic and each account is distinct.
+		assert_eq!(derive_accounts(DEV_PHRASE, 3)?, accounts);
+		assert_ne!(accounts[0].address, accounts[1].address);
+		assert!(derive_accounts(DEV_PHRASE, 0)?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn export_keyring_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("keyring.json");
+		let accounts = derive_accounts(DEV_PHRASE, 2)?;
+		export_keyring(&accounts, &path)?;
+		let exported: Vec<DerivedAccount> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+		assert_eq!(exported, accounts);
+		Ok(())
+	}
+}
@@ -1,17 +1,57 @@
+pub mod account;
+pub mod args;
+pub mod bootstrap;
 pub mod build;
+pub mod cache;
+pub mod calls;
+pub mod container;
+pub mod environment;
 pub mod errors;
+pub mod gc;
 pub mod git;
 pub mod helpers;
+pub mod hooks;
+pub mod i18n;
+pub mod keyring;
 pub mod manifest;
+pub mod migration;
+pub mod notify;
+pub mod pipeline;
+pub mod preflight;
+pub mod progress;
+pub mod proxy;
+pub mod remote;
+pub mod signer;
+pub mod signing;
+pub mod sink;
 pub mod sourcing;
+pub mod stats;
 pub mod templates;
+pub mod toolchain;
+pub mod vendor;
+pub mod watch;
 
-pub use build::Profile;
+pub use account::{parse_account, Account};
+pub use args::parse_arg;
+pub use build::{
+	missing_toolchain, run_cargo, target_dir, validate_target, BuildMessage, BuildReporter,
+	Features, Profile, Resolution,
+};
 pub use errors::Error;
 pub use git::{Git, GitHub, Release};
-pub use helpers::{get_project_name_from_path, prefix_with_current_dir_if_needed, replace_in_file};
+pub use helpers::{
+	decode_hex, encode_hex, get_project_name_from_path, prefix_with_current_dir_if_needed,
+	replace_in_file,
+};
 pub use manifest::{add_crate_to_workspace, find_workspace_toml};
+pub use signer::{
+	create_ecdsa_signer, create_ethereum_signer, create_signer, AssetFeeConfig, EncodedAssetId,
+	EthereumConfig, EthereumSigner,
+};
+pub use signing::{ArtifactSigner, Verifier};
+pub use sink::{parse_sink, Sink};
 pub use templates::extractor::extract_template_files;
+pub use toolchain::{MissingToolchain, Toolchain};
 
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
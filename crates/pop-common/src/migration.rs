@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{hooks::CONFIG_FILE, Error};
+use std::{
+	fs::{copy, create_dir_all, read_dir, read_to_string, remove_dir_all, write},
+	path::{Path, PathBuf},
+};
+
+/// The name of the file recording the layout version of a directory managed by pop.
+const VERSION_FILE: &str = ".pop-version";
+
+/// A migration which upgrades the layout of a directory from one version to the next.
+#[derive(Clone, Debug)]
+pub struct Migration {
+	/// The version from which the migration upgrades, the migration resulting in the next version.
+	pub from: u32,
+	/// A description of the migration.
+	pub description: &'static str,
+	/// Applies the migration to the directory.
+	pub apply: fn(&Path) -> Result<(), Error>,
+}
+
+/// The outcome of migrating a directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationReport {
+	/// The layout version prior to migration.
+	pub from: u32,
+	/// The layout version after migration.
+	pub to: u32,
+	/// The descriptions of the migrations applied.
+	pub applied: Vec<&'static str>,
+	/// The location of the backup taken prior to migration, if any migrations were applied.
+	pub backup: Option<PathBuf>,
+}
+
+/// Upgrades the layout of a directory managed by pop (e.g. the cache or a project), taking a
+/// backup before any migrations are applied.
+pub struct Migrator {
+	/// The directory to be migrated.
+	dir: PathBuf,
+	/// The migrations, ordered by the version from which they upgrade.
+	migrations: Vec<Migration>,
+	/// Whether the directory is backed up before migrations are applied.
+	backup: bool,
+}
+
+impl Migrator {
+	/// Creates a new migrator for a directory.
+	///
+	/// # Arguments
+	/// * `dir` - The directory to be migrated.
+	/// * `migrations` - The migrations available, which must upgrade from consecutive versions
+	///   starting at zero.
+	pub fn new(dir: impl Into<PathBuf>, mut migrations: Vec<Migration>) -> Self {
+		migrations.sort_by_key(|m| m.from);
+		Self { dir: dir.into(), migrations, backup: true }
+	}
+
+	/// Skips backing up the directory before migrations are applied, for directories whose
+	/// contents can be regenerated (e.g. the cache).
+	pub fn without_backup(mut self) -> Self {
+		self.backup = false;
+		self
+	}
+
+	/// The latest layout version, resulting from all migrations being applied.
+	pub fn latest(&self) -> u32 {
+		self.migrations.last().map_or(0, |m| m.from + 1)
+	}
+
+	/// The current layout version of the directory, with an unversioned directory being version
+	/// zero.
+	pub fn version(&self) -> Result<u32, Error> {
+		let path = self.dir.join(VERSION_FILE);
+		if !path.exists() {
+			return Ok(0);
+		}
+		read_to_string(&path)?
+			.trim()
+			.parse()
+			.map_err(|_| Error::Config(format!("invalid layout version in {}", path.display())))
+	}
+
+	/// The migrations which have yet to be applied to the directory.
+	pub fn pending(&self) -> Result<Vec<&Migration>, Error> {
+		let version = self.version()?;
+		if version > self.latest() {
+			return Err(Error::Config(format!(
+				"{} uses layout version {version}, which is newer than the latest supported version {}: please upgrade pop",
+				self.dir.display(),
+				self.latest()
+			)));
+		}
+		Ok(self.migrations.iter().filter(|m| m.from >= version).collect())
+	}
+
+	/// Applies any pending migrations, backing up the directory beforehand. Should a migration
+	/// fail, the directory is left at the version of the last successful migration and the backup
+	/// can be used to restore it.
+	pub fn migrate(&self) -> Result<MigrationReport, Error> {
+		let from = self.version()?;
+		let pending = self.pending()?;
+		let mut report = MigrationReport { from, to: from, applied: vec![], backup: None };
+		if pending.is_empty() {
+			return Ok(report);
+		}
+		create_dir_all(&self.dir)?;
+		if self.backup {
+			let backup = self.dir.with_file_name(format!(
+				"{}.backup-v{from}",
+				self.dir.file_name().and_then(|n| n.to_str()).unwrap_or("pop")
+			));
+			copy_dir(&self.dir, &backup)?;
+			report.backup = Some(backup);
+		}
+		for migration in pending {
+			(migration.apply)(&self.dir).map_err(|e| {
+				Error::Config(format!("migration `{}` failed: {e}", migration.description))
+			})?;
+			report.to = migration.from + 1;
+			report.applied.push(migration.description);
+			self.set_version(report.to)?;
+		}
+		Ok(report)
+	}
+
+	/// Records the layout version of the directory.
+	fn set_version(&self, version: u32) -> Result<(), Error> {
+		write(self.dir.join(VERSION_FILE), version.to_string())?;
+		Ok(())
+	}
+}
+
+/// The migrations of the cache layout.
+pub fn cache_migrations() -> Vec<Migration> {
+	vec![Migration {
+		from: 0,
+		description: "remove the directory left by extracting archived binaries",
+		apply: |dir| {
+			let artifacts = dir.join("artifacts");
+			if artifacts.is_dir() {
+				remove_dir_all(artifacts)?;
+			}
+			Ok(())
+		},
+	}]
+}
+
+/// The migrations of the project layout, none being required whilst the layout of `pop.toml` is
+/// unchanged. Running them nonetheless rejects projects upgraded by a newer release of pop.
+pub fn project_migrations() -> Vec<Migration> {
+	vec![]
+}
+
+/// Upgrades the layout of the cache, if created by a previous release. The cache can be
+/// regenerated, so no backup is taken.
+///
+/// # Arguments
+/// * `cache` - The cache directory.
+pub fn migrate_cache(cache: &Path) -> Result<MigrationReport, Error> {
+	Migrator::new(cache, cache_migrations()).without_backup().migrate()
+}
+
+/// Reads the `pop.toml` file of a project, if any, upgrading the layout of the project beforehand
+/// if created by a previous release.
+///
+/// # Arguments
+/// * `project` - The project directory.
+pub fn read_config(project: &Path) -> Result<Option<String>, Error> {
+	let path = project.join(CONFIG_FILE);
+	if !path.exists() {
+		return Ok(None);
+	}
+	Migrator::new(project, project_migrations()).migrate()?;
+	Ok(Some(read_to_string(path)?))
+}
+
+/// Recursively copies a directory.
+fn copy_dir(source: &Path, target: &Path) -> Result<(), Error> {
+	create_dir_all(target)?;
+	for entry in read_dir(source)? {
+		let entry = entry?;
+		let path = entry.path();
+		let target = target.join(entry.file_name());
+		if entry.file_type()?.is_dir() {
+			copy_dir(&path, &target)?;
+		} else {
+			copy(&path, &target)?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::rename;
+
+	fn migrations() -> Vec<Migration> {
+		vec![
+			Migration {
+				from: 1,
+				description: "rename network state",
+				apply: |dir| Ok(rename(dir.join("network.toml"), dir.join("state.toml"))?),
+			},
+			Migration {
+				from: 0,
+				description: "add networks directory",
+				apply: |dir| Ok(create_dir_all(dir.join("networks"))?),
+			},
+		]
+	}
+
+	#[test]
+	fn migrate_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let dir = temp_dir.path().join("pop");
+		create_dir_all(&dir)?;
+		write(dir.join("network.toml"), "[relaychain]")?;
+		let migrator = Migrator::new(&dir, migrations());
+		assert_eq!(migrator.version()?, 0);
+		assert_eq!(migrator.latest(), 2);
+		assert_eq!(migrator.pending()?.len(), 2);
+
+		let report = migrator.migrate()?;
+		assert_eq!(
+			report,
+			MigrationReport {
+				from: 0,
+				to: 2,
+				applied: vec!["add networks directory", "rename network state"],
+				backup: Some(temp_dir.path().join("pop.backup-v0")),
+			}
+		);
+		assert!(dir.join("networks").is_dir());
+		assert_eq!(read_to_string(dir.join("state.toml"))?, "[relaychain]");
+		assert_eq!(
+			read_to_string(temp_dir.path().join("pop.backup-v0/network.toml"))?,
+			"[relaychain]"
+		);
+		assert_eq!(migrator.version()?, 2);
+
+		// Subsequent migrations are a no-op.
+		assert_eq!(
+			migrator.migrate()?,
+			MigrationReport { from: 2, to: 2, applied: vec![], backup: None }
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn migrate_without_backup_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let dir = temp_dir.path().join("cache");
+		create_dir_all(&dir)?;
+		write(dir.join("network.toml"), "[relaychain]")?;
+		let report = Migrator::new(&dir, migrations()).without_backup().migrate()?;
+		assert_eq!(report.to, 2);
+		assert_eq!(report.backup, None);
+		assert!(!temp_dir.path().join("cache.backup-v0").exists());
+		assert_eq!(read_to_string(dir.join(VERSION_FILE))?, "2");
+		Ok(())
+	}
+
+	#[test]
+	fn migrate_stops_at_failed_migration() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let migrator = Migrator::new(temp_dir.path().join("pop"), migrations());
+		assert!(matches!(
+			migrator.migrate(),
+			Err(Error::Config(e)) if e.starts_with("migration `rename network state` failed")
+		));
+		assert_eq!(migrator.version()?, 1);
+		Ok(())
+	}
+
+	#[test]
+	fn newer_layout_rejected() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		write(temp_dir.path().join(VERSION_FILE), "3")?;
+		let migrator = Migrator::new(temp_dir.path(), migrations());
+		assert!(matches!(
+			migrator.pending(),
+			Err(Error::Config(e)) if e.contains("newer than the latest supported version 2")
+		));
+		write(temp_dir.path().join(VERSION_FILE), "invalid")?;
+		assert!(matches!(migrator.version(), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn migrate_cache_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		// A cache created by a previous release, with an unversioned layout.
+		create_dir_all(cache.join("artifacts/substrate-contracts-node"))?;
+		write(cache.join("polkadot-v1.13.0"), "polkadot")?;
+		let report = migrate_cache(cache)?;
+		assert_eq!((report.from, report.to, report.backup), (0, 1, None));
+		assert!(!cache.join("artifacts").exists());
+		assert_eq!(read_to_string(cache.join("polkadot-v1.13.0"))?, "polkadot");
+		assert_eq!(read_to_string(cache.join(VERSION_FILE))?, "1");
+		assert!(migrate_cache(cache)?.applied.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn read_config_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path();
+		assert_eq!(read_config(project)?, None);
+		write(project.join(CONFIG_FILE), "[hooks]")?;
+		assert_eq!(read_config(project)?.as_deref(), Some("[hooks]"));
+		// Nothing is written to the project whilst its layout is current.
+		assert!(!project.join(VERSION_FILE).exists());
+		write(project.join(VERSION_FILE), "1")?;
+		assert!(matches!(
+			read_config(project),
+			Err(Error::Config(e)) if e.contains("please upgrade pop")
+		));
+		Ok(())
+	}
+}
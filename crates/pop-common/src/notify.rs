@@ -0,0 +1,437 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{hooks::CONFIG_FILE, migration::read_config, Error};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+	path::Path,
+	time::{SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+/// The kind of an event, by which webhooks subscribe to events.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventKind {
+	/// A local network has been launched.
+	NetworkUp,
+	/// A parachain has been onboarded onto a relay chain.
+	ParaOnboarded,
+	/// A runtime upgrade has been enacted.
+	UpgradeEnacted,
+	/// A collator has crashed or stopped responding.
+	CollatorCrashed,
+	/// A collator has recovered, having previously crashed.
+	CollatorRecovered,
+	/// A contract has been deployed.
+	ContractDeployed,
+	/// The best chain was reorganized, retracting more blocks than permitted.
+	ReorgDetected,
+	/// No block has been finalized for longer than permitted.
+	FinalityStalled,
+}
+
+impl Display for EventKind {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		use EventKind::*;
+		let kind = match self {
+			NetworkUp => "network-up",
+			ParaOnboarded => "para-onboarded",
+			UpgradeEnacted => "upgrade-enacted",
+			CollatorCrashed => "collator-crashed",
+			CollatorRecovered => "collator-recovered",
+			ContractDeployed => "contract-deployed",
+			ReorgDetected => "reorg-detected",
+			FinalityStalled => "finality-stalled",
+		};
+		write!(f, "{kind}")
+	}
+}
+
+/// An event within a pop-driven environment, posted to the webhooks subscribed to it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Event {
+	/// A local network has been launched.
+	NetworkUp {
+		/// The relay chain of the network.
+		relay_chain: String,
+		/// The websocket endpoints of the nodes of the network, keyed by node name.
+		nodes: BTreeMap<String, String>,
+	},
+	/// A parachain has been onboarded onto a relay chain.
+	ParaOnboarded {
+		/// The parachain identifier.
+		para_id: u32,
+		/// The websocket endpoint of a node of the parachain.
+		url: String,
+	},
+	/// A runtime upgrade has been enacted.
+	UpgradeEnacted {
+		/// The identifier of the upgraded parachain.
+		para_id: u32,
+		/// The spec version of the previous runtime.
+		previous_spec_version: u32,
+		/// The spec version of the new runtime.
+		spec_version: u32,
+	},
+	/// A collator has crashed or stopped responding.
+	CollatorCrashed {
+		/// The name of the collator.
+		node: String,
+		/// Why the collator is considered to have crashed.
+		reason: String,
+	},
+	/// A collator has recovered, having previously crashed.
+	CollatorRecovered {
+		/// The name of the collator.
+		node: String,
+	},
+	/// A contract has been deployed.
+	ContractDeployed {
+		/// The address of the contract.
+		address: String,
+		/// The websocket endpoint of the node to which the contract was deployed.
+		url: String,
+	},
+	/// The best chain was reorganized, retracting more blocks than permitted.
+	ReorgDetected {
+		/// The websocket endpoint of the node at which the reorg was observed.
+		url: String,
+		/// The number of best blocks retracted.
+		depth: u32,
+		/// The number of the new best block.
+		block: u32,
+	},
+	/// No block has been finalized for longer than permitted.
+	FinalityStalled {
+		/// The websocket endpoint of the node at which the stall was observed.
+		url: String,
+		/// The number of the last finalized block, if known.
+		last_finalized: Option<u32>,
+		/// The number of seconds since a block was last finalized.
+		seconds: u64,
+	},
+}
+
+impl Event {
+	/// The kind of the event.
+	pub fn kind(&self) -> EventKind {
+		match self {
+			Event::NetworkUp { .. } => EventKind::NetworkUp,
+			Event::ParaOnboarded { .. } => EventKind::ParaOnboarded,
+			Event::UpgradeEnacted { .. } => EventKind::UpgradeEnacted,
+			Event::CollatorCrashed { .. } => EventKind::CollatorCrashed,
+			Event::CollatorRecovered { .. } => EventKind::CollatorRecovered,
+			Event::ContractDeployed { .. } => EventKind::ContractDeployed,
+			Event::ReorgDetected { .. } => EventKind::ReorgDetected,
+			Event::FinalityStalled { .. } => EventKind::FinalityStalled,
+		}
+	}
+
+	/// The payload posted to webhooks: the fields of the event alongside its kind, the project
+	/// and a `text` summary, as displayed by Slack incoming webhooks.
+	///
+	/// # Arguments
+	/// * `project` - The name of the project from which the event originates.
+	/// * `timestamp` - When the event occurred, in seconds since the Unix epoch.
+	fn payload(&self, project: &str, timestamp: u64) -> Value {
+		let mut payload = json!({
+			"event": self.kind().to_string(),
+			"project": project,
+			"timestamp": timestamp,
+			"text": format!("[{project}] {self}"),
+		});
+		if let (Some(payload), Value::Object(fields)) =
+			(payload.as_object_mut(), serde_json::to_value(self).unwrap_or_default())
+		{
+			payload.extend(fields);
+		}
+		payload
+	}
+}
+
+impl Display for Event {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Event::NetworkUp { relay_chain, nodes } =>
+				write!(f, "Network launched: {relay_chain} with {} nodes", nodes.len()),
+			Event::ParaOnboarded { para_id, url } =>
+				write!(f, "Parachain {para_id} onboarded, served at {url}"),
+			Event::UpgradeEnacted { para_id, previous_spec_version, spec_version } => write!(
+				f,
+				"Parachain {para_id} upgraded from spec version {previous_spec_version} to {spec_version}"
+			),
+			Event::CollatorCrashed { node, reason } =>
+				write!(f, "Collator {node} crashed: {reason}"),
+			Event::CollatorRecovered { node } => write!(f, "Collator {node} recovered"),
+			Event::ContractDeployed { address, url } =>
+				write!(f, "Contract deployed at {address} on {url}"),
+			Event::ReorgDetected { url, depth, block } =>
+				write!(f, "Reorg of {depth} blocks on {url}, with #{block} now the best block"),
+			Event::FinalityStalled { url, last_finalized: Some(block), seconds } => write!(
+				f,
+				"Finality stalled on {url}: no block finalized for {seconds}s since #{block}"
+			),
+			Event::FinalityStalled { url, last_finalized: None, seconds } =>
+				write!(f, "Finality stalled on {url}: no block finalized for {seconds}s"),
+		}
+	}
+}
+
+/// A webhook configured within the `[[notifications]]` tables of the project configuration file.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Webhook {
+	/// The URL to which events are posted, or the name of an environment variable containing it
+	/// when prefixed with `$` (e.g. `$SLACK_WEBHOOK_URL`), keeping secrets out of the project.
+	pub url: String,
+	/// The kinds of events posted to the webhook [default: all].
+	#[serde(default)]
+	pub events: Vec<EventKind>,
+}
+
+impl Webhook {
+	/// Whether the webhook is subscribed to events of the specified kind.
+	///
+	/// # Arguments
+	/// * `kind` - The kind of event.
+	pub fn subscribes(&self, kind: EventKind) -> bool {
+		self.events.is_empty() || self.events.contains(&kind)
+	}
+
+	/// The URL to which events are posted, resolving any environment variable.
+	fn resolve(&self) -> Result<Url, Error> {
+		let url = match self.url.strip_prefix('$') {
+			Some(var) => std::env::var(var).map_err(|_| {
+				Error::Notification(format!("the `{var}` environment variable is not set"))
+			})?,
+			None => self.url.clone(),
+		};
+		Url::parse(&url).map_err(|e| Error::Notification(format!("invalid webhook URL: {e}")))
+	}
+}
+
+/// The contents of the project configuration file relevant to notifications.
+#[derive(Default, Deserialize)]
+struct Config {
+	#[serde(default)]
+	notifications: Vec<Webhook>,
+}
+
+/// Posts events to the webhooks configured for a project.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Notifier {
+	/// The name of the project.
+	project: String,
+	/// The webhooks configured.
+	webhooks: Vec<Webhook>,
+}
+
+impl Notifier {
+	/// Loads the webhooks configured within the `[[notifications]]` tables of the `pop.toml`
+	/// file of a project, if any.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn load(project: &Path) -> Result<Self, Error> {
+		let path = project.join(CONFIG_FILE);
+		let config: Config = match read_config(project)? {
+			Some(config) => toml_edit::de::from_str(&config).map_err(|e| {
+				Error::Config(format!("invalid notifications in {}: {e}", path.display()))
+			})?,
+			None => Config::default(),
+		};
+		let project = project
+			.canonicalize()
+			.ok()
+			.and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+			.unwrap_or_else(|| "pop".to_string());
+		Ok(Self { project, webhooks: config.notifications })
+	}
+
+	/// Adds a webhook, in addition to those configured.
+	///
+	/// # Arguments
+	/// * `webhook` - The webhook.
+	pub fn with_webhook(mut self, webhook: Webhook) -> Self {
+		self.webhooks.push(webhook);
+		self
+	}
+
+	/// The webhooks configured.
+	pub fn webhooks(&self) -> &[Webhook] {
+		&self.webhooks
+	}
+
+	/// Posts an event to each webhook subscribed to it. All webhooks are attempted, returning
+	/// the first failure, if any.
+	///
+	/// # Arguments
+	/// * `event` - The event.
+	pub async fn notify(&self, event: &Event) -> Result<(), Error> {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |duration| duration.as_secs());
+		let payload = event.payload(&self.project, timestamp);
+		let client = reqwest::Client::new();
+		let mut result = Ok(());
+		for webhook in self.webhooks.iter().filter(|w| w.subscribes(event.kind())) {
+			let posted = match webhook.resolve() {
+				Ok(url) => client
+					.post(url)
+					.json(&payload)
+					.send()
+					.await
+					.and_then(|r| r.error_for_status())
+					.map(|_| ())
+					.map_err(|e| {
+						Error::Notification(format!("unable to post {}: {e}", event.kind()))
+					}),
+				Err(e) => Err(e),
+			};
+			if let (Err(e), Ok(())) = (posted, &result) {
+				result = Err(e);
+			}
+		}
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use mockito::Matcher;
+	use std::fs::write;
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(Notifier::load(temp_dir.path())?.webhooks().is_empty());
+
+		write(
+			temp_dir.path().join(CONFIG_FILE),
+			r#"
+			[hooks]
+			pre-build = "echo"
+
+			[[notifications]]
+			url = "https://hooks.slack.com/services/T0/B0/X"
+			events = ["network-up", "collator-crashed", "finality-stalled"]
+
+			[[notifications]]
+			url = "$POP_WEBHOOK_URL"
+			"#,
+		)?;
+		let notifier = Notifier::load(temp_dir.path())?;
+		let webhooks = notifier.webhooks();
+		assert_eq!(
+			webhooks[0].events,
+			[EventKind::NetworkUp, EventKind::CollatorCrashed, EventKind::FinalityStalled]
+		);
+		assert!(webhooks[0].subscribes(EventKind::CollatorCrashed));
+		assert!(!webhooks[0].subscribes(EventKind::UpgradeEnacted));
+		assert!(webhooks[1].subscribes(EventKind::UpgradeEnacted));
+
+		let webhook = Webhook {
+			url: "https://example.com/alerts".into(),
+			events: vec![EventKind::CollatorRecovered],
+		};
+		let notifier = notifier.with_webhook(webhook.clone());
+		assert_eq!(notifier.webhooks().len(), 3);
+		assert_eq!(notifier.webhooks()[2], webhook);
+
+		write(temp_dir.path().join(CONFIG_FILE), "[[notifications]]\nevents = [\"network-down\"]")?;
+		assert!(matches!(Notifier::load(temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn payload_works() {
+		let event =
+			Event::UpgradeEnacted { para_id: 2000, previous_spec_version: 1, spec_version: 2 };
+		assert_eq!(
+			event.payload("my-chain", 10),
+			json!({
+				"event": "upgrade-enacted",
+				"project": "my-chain",
+				"timestamp": 10,
+				"text": "[my-chain] Parachain 2000 upgraded from spec version 1 to 2",
+				"para_id": 2000,
+				"previous_spec_version": 1,
+				"spec_version": 2,
+			})
+		);
+
+		let event = Event::FinalityStalled {
+			url: "ws://localhost:9944".into(),
+			last_finalized: Some(10),
+			seconds: 90,
+		};
+		assert_eq!(
+			event.payload("my-chain", 10),
+			json!({
+				"event": "finality-stalled",
+				"project": "my-chain",
+				"timestamp": 10,
+				"text": "[my-chain] Finality stalled on ws://localhost:9944: no block finalized for 90s since #10",
+				"url": "ws://localhost:9944",
+				"last_finalized": 10,
+				"seconds": 90,
+			})
+		);
+	}
+
+	#[tokio::test]
+	async fn notify_works() -> Result<()> {
+		let mut server = mockito::Server::new_async().await;
+		let crashed = server
+			.mock("POST", "/crashed")
+			.match_body(Matcher::PartialJson(json!({
+				"event": "collator-crashed",
+				"node": "collator-01",
+				"reason": "no response within 5s",
+			})))
+			.expect(1)
+			.create_async()
+			.await;
+		let failing = server.mock("POST", "/failing").with_status(500).create_async().await;
+		let notifier = Notifier {
+			project: "my-chain".into(),
+			webhooks: vec![
+				Webhook {
+					url: format!("{}/crashed", server.url()),
+					events: vec![EventKind::CollatorCrashed],
+				},
+				Webhook {
+					url: format!("{}/failing", server.url()),
+					events: vec![EventKind::CollatorCrashed],
+				},
+				Webhook {
+					url: "$POP_UNSET_WEBHOOK_URL".into(),
+					events: vec![EventKind::NetworkUp],
+				},
+			],
+		};
+		let event = Event::CollatorCrashed {
+			node: "collator-01".into(),
+			reason: "no response within 5s".into(),
+		};
+		// Failures of one webhook do not prevent the others being notified.
+		assert!(matches!(
+			notifier.notify(&event).await,
+			Err(Error::Notification(e)) if e.starts_with("unable to post collator-crashed")
+		));
+		crashed.assert_async().await;
+		failing.assert_async().await;
+
+		let event = Event::NetworkUp { relay_chain: "paseo-local".into(), nodes: BTreeMap::new() };
+		assert!(matches!(
+			notifier.notify(&event).await,
+			Err(Error::Notification(e)) if e.contains("POP_UNSET_WEBHOOK_URL")
+		));
+		Ok(())
+	}
+}
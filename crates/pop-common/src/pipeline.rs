@@ -0,0 +1,300 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	fs::read_to_string,
+	path::Path,
+};
+
+/// The kind of operation performed by a pipeline step.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StepKind {
+	/// Build a project, via `pop build`.
+	Build,
+	/// Build a chain specification, via `pop build spec`.
+	Spec,
+	/// Launch a local network, which remains running until the pipeline completes. Requires the
+	/// `file` option.
+	Launch,
+	/// Fund an account on a chain. Requires the `to` and `amount` options.
+	Fund,
+	/// Deploy a contract, via `pop up contract`.
+	Deploy,
+	/// Run the tests of a contract, via `pop test contract`.
+	Test,
+	/// Run an arbitrary command, specified by the `command` option.
+	Run,
+}
+
+impl StepKind {
+	/// The `pop` subcommand which performs the operation, if any.
+	pub fn subcommand(&self) -> &'static [&'static str] {
+		match self {
+			StepKind::Build => &["build"],
+			StepKind::Spec => &["build", "spec"],
+			StepKind::Launch => &["up", "parachain"],
+			StepKind::Fund => &[],
+			StepKind::Deploy => &["up", "contract"],
+			StepKind::Test => &["test", "contract"],
+			StepKind::Run => &[],
+		}
+	}
+}
+
+/// A step of a pipeline.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+	/// The unique identifier of the step.
+	pub id: String,
+	/// The operation performed by the step.
+	pub kind: StepKind,
+	/// The identifiers of the steps which must complete before this step.
+	#[serde(default)]
+	pub needs: Vec<String>,
+	/// The options of the step, passed to the operation as command line arguments.
+	#[serde(default)]
+	pub options: BTreeMap<String, Value>,
+}
+
+impl Step {
+	/// The command line arguments for the step's options: `true` values become flags, arrays are
+	/// repeated and `false`/null values are omitted.
+	pub fn args(&self) -> Vec<String> {
+		let mut args = vec![];
+		for (name, value) in &self.options {
+			// The command of a `run` step is not an argument.
+			if self.kind == StepKind::Run && name == "command" {
+				continue;
+			}
+			let values = match value {
+				Value::Array(values) => values.iter().collect(),
+				value => vec![value],
+			};
+			for value in values {
+				match value {
+					Value::Bool(true) => args.push(format!("--{name}")),
+					Value::Bool(false) | Value::Null => {},
+					Value::String(value) => args.extend([format!("--{name}"), value.clone()]),
+					value => args.extend([format!("--{name}"), value.to_string()]),
+				}
+			}
+		}
+		args
+	}
+
+	/// The command of a `run` step.
+	pub fn command(&self) -> Option<&str> {
+		self.option("command")
+	}
+
+	/// The value of an option, if specified as a string.
+	///
+	/// # Arguments
+	/// * `name` - The name of the option.
+	pub fn option(&self, name: &str) -> Option<&str> {
+		self.options.get(name).and_then(Value::as_str)
+	}
+
+	/// The options which must be specified for the step.
+	fn required_options(&self) -> &'static [&'static str] {
+		match self.kind {
+			StepKind::Launch => &["file"],
+			StepKind::Fund => &["to", "amount"],
+			StepKind::Run => &["command"],
+			_ => &[],
+		}
+	}
+}
+
+/// A declarative sequence of pop operations.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+	/// The steps of the pipeline.
+	pub steps: Vec<Step>,
+}
+
+impl Pipeline {
+	/// Loads a pipeline from a TOML or YAML file, based on its extension.
+	///
+	/// # Arguments
+	/// * `path` - The path of the pipeline file.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let contents = read_to_string(path)?;
+		let pipeline: Self = match path.extension().and_then(|e| e.to_str()) {
+			Some("yaml" | "yml") => serde_yaml::from_str(&contents).map_err(|e| e.to_string()),
+			_ => toml_edit::de::from_str(&contents).map_err(|e| e.to_string()),
+		}
+		.map_err(|e| Error::Config(format!("invalid pipeline {}: {e}", path.display())))?;
+		pipeline.validate()?;
+		Ok(pipeline)
+	}
+
+	/// Validates the pipeline, ensuring step identifiers are unique, dependencies exist and
+	/// required options are specified.
+	pub fn validate(&self) -> Result<(), Error> {
+		let mut ids = HashSet::new();
+		for step in &self.steps {
+			if !ids.insert(step.id.as_str()) {
+				return Err(Error::Config(format!("duplicate step `{}`", step.id)));
+			}
+			if let Some(option) =
+				step.required_options().iter().find(|o| !step.options.contains_key(**o))
+			{
+				return Err(Error::Config(format!(
+					"step `{}` does not specify the `{option}` option",
+					step.id
+				)));
+			}
+		}
+		for step in &self.steps {
+			if let Some(need) = step.needs.iter().find(|need| !ids.contains(need.as_str())) {
+				return Err(Error::Config(format!(
+					"step `{}` needs unknown step `{need}`",
+					step.id
+				)));
+			}
+		}
+		self.plan().map(|_| ())
+	}
+
+	/// Orders the steps such that each step follows the steps it needs, otherwise preserving the
+	/// order in which steps are declared.
+	pub fn plan(&self) -> Result<Vec<&Step>, Error> {
+		let mut remaining: HashMap<&str, usize> =
+			self.steps.iter().map(|s| (s.id.as_str(), s.needs.len())).collect();
+		let mut plan: Vec<&Step> = Vec::with_capacity(self.steps.len());
+		while plan.len() < self.steps.len() {
+			let next = self
+				.steps
+				.iter()
+				.find(|s| remaining.get(s.id.as_str()) == Some(&0))
+				.ok_or_else(|| {
+					let mut cycle: Vec<_> = remaining.keys().copied().collect();
+					cycle.sort();
+					Error::Config(format!("steps contain a dependency cycle: {}", cycle.join(", ")))
+				})?;
+			remaining.remove(next.id.as_str());
+			for step in &self.steps {
+				if let Some(count) = remaining.get_mut(step.id.as_str()) {
+					*count -= step.needs.iter().filter(|n| **n == next.id).count();
+				}
+			}
+			plan.push(next);
+		}
+		Ok(plan)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	const PIPELINE: &str = r#"
+[[steps]]
+id = "test"
+kind = "run"
+needs = ["launch"]
+options = { command = "cargo test --features e2e" }
+
+[[steps]]
+id = "launch"
+kind = "launch"
+needs = ["spec"]
+options = { file = "network.toml", skip-confirm = true }
+
+[[steps]]
+id = "build"
+kind = "build"
+options = { path = "./", release = true }
+
+[[steps]]
+id = "spec"
+kind = "spec"
+needs = ["build"]
+options = { id = 2000, verbose = false }
+
+[[steps]]
+id = "fund"
+kind = "fund"
+needs = ["launch"]
+options = { to = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", amount = "1000000000000" }
+"#;
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("pipeline.toml");
+		write(&path, PIPELINE)?;
+		let pipeline = Pipeline::load(&path)?;
+		let plan: Vec<_> = pipeline.plan()?.iter().map(|s| s.id.as_str()).collect();
+		assert_eq!(plan, vec!["build", "spec", "launch", "test", "fund"]);
+
+		let yaml = temp_dir.path().join("pipeline.yaml");
+		write(
+			&yaml,
+			"steps:\n  - id: build\n    kind: build\n  - id: deploy\n    kind: deploy\n    needs: [build]\n",
+		)?;
+		let pipeline = Pipeline::load(&yaml)?;
+		assert_eq!(pipeline.steps[1].kind, StepKind::Deploy);
+		assert_eq!(pipeline.steps[1].needs, vec!["build"]);
+		Ok(())
+	}
+
+	#[test]
+	fn args_works() -> Result<()> {
+		let pipeline: Pipeline = toml_edit::de::from_str(PIPELINE)?;
+		let step = |id: &str| pipeline.steps.iter().find(|s| s.id == id).unwrap();
+		assert_eq!(step("build").args(), vec!["--path", "./", "--release"]);
+		assert_eq!(step("spec").args(), vec!["--id", "2000"]);
+		assert_eq!(step("launch").args(), vec!["--file", "network.toml", "--skip-confirm"]);
+		assert!(step("test").args().is_empty());
+		assert_eq!(step("test").command(), Some("cargo test --features e2e"));
+		assert_eq!(step("spec").kind.subcommand(), ["build", "spec"]);
+		assert_eq!(step("fund").option("amount"), Some("1000000000000"));
+		Ok(())
+	}
+
+	#[test]
+	fn validate_works() -> Result<()> {
+		let invalid = |toml: &str| -> Result<String> {
+			let pipeline: Pipeline = toml_edit::de::from_str(toml)?;
+			match pipeline.validate() {
+				Err(Error::Config(e)) => Ok(e),
+				result => anyhow::bail!("unexpected result: {result:?}"),
+			}
+		};
+		assert_eq!(
+			invalid(
+				"[[steps]]\nid = \"a\"\nkind = \"build\"\n[[steps]]\nid = \"a\"\nkind = \"spec\""
+			)?,
+			"duplicate step `a`"
+		);
+		assert_eq!(
+			invalid("[[steps]]\nid = \"a\"\nkind = \"build\"\nneeds = [\"b\"]")?,
+			"step `a` needs unknown step `b`"
+		);
+		assert_eq!(
+			invalid("[[steps]]\nid = \"a\"\nkind = \"run\"")?,
+			"step `a` does not specify the `command` option"
+		);
+		assert_eq!(
+			invalid("[[steps]]\nid = \"a\"\nkind = \"fund\"\noptions = { to = \"bob\" }")?,
+			"step `a` does not specify the `amount` option"
+		);
+		assert_eq!(
+			invalid(
+				"[[steps]]\nid = \"a\"\nkind = \"build\"\nneeds = [\"b\"]\n[[steps]]\nid = \"b\"\nkind = \"spec\"\nneeds = [\"a\"]"
+			)?,
+			"steps contain a dependency cycle: a, b"
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{i18n::message, Error};
+use duct::cmd;
+use std::{
+	env,
+	fmt::{Display, Formatter},
+	fs::read_to_string,
+	path::Path,
+};
+
+/// The environment variable which, when set, skips preflight checks.
+pub const SKIP_VAR: &str = "POP_SKIP_PREFLIGHT";
+
+const MIB: u64 = 1024 * 1024;
+const GIB: u64 = 1024 * MIB;
+
+/// The resources required by an operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Requirements {
+	/// The free disk space required, in bytes.
+	pub disk: u64,
+	/// The available memory required, in bytes.
+	pub memory: u64,
+	/// The minimum limit on open file descriptors.
+	pub file_descriptors: u64,
+}
+
+impl Requirements {
+	/// The estimated requirements to build a project, which are considerably higher when building
+	/// from scratch than when existing build artifacts can be reused.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn build(project: &Path) -> Self {
+		let incremental = project.join("target").exists();
+		Self {
+			disk: if incremental { 5 * GIB } else { 20 * GIB },
+			memory: 4 * GIB,
+			file_descriptors: 1024,
+		}
+	}
+
+	/// The estimated requirements to launch a local network.
+	///
+	/// # Arguments
+	/// * `nodes` - The number of nodes within the network.
+	pub fn launch(nodes: usize) -> Self {
+		let nodes = nodes.max(1) as u64;
+		Self { disk: GIB + nodes * 512 * MIB, memory: nodes * 512 * MIB, file_descriptors: 1024 }
+	}
+}
+
+/// The resources available, where known.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Resources {
+	/// The free disk space, in bytes.
+	pub disk: Option<u64>,
+	/// The available memory, in bytes.
+	pub memory: Option<u64>,
+	/// The limit on open file descriptors.
+	pub file_descriptors: Option<u64>,
+}
+
+impl Resources {
+	/// Detects the resources available to an operation writing to the specified path.
+	///
+	/// # Arguments
+	/// * `path` - The path written to by the operation, or one of its ancestors.
+	pub fn detect(path: &Path) -> Self {
+		let path = path.ancestors().find(|p| p.exists()).unwrap_or(Path::new("."));
+		Self {
+			disk: cmd("df", ["-Pk".as_ref(), path.as_os_str()])
+				.stderr_null()
+				.read()
+				.ok()
+				.and_then(|output| parse_df(&output)),
+			memory: read_to_string("/proc/meminfo").ok().and_then(|info| parse_meminfo(&info)),
+			file_descriptors: cmd("sh", ["-c", "ulimit -n"])
+				.read()
+				.ok()
+				.and_then(|limit| limit.trim().parse().ok()),
+		}
+	}
+
+	/// The shortfalls in the resources available against the specified requirements. Resources
+	/// which cannot be determined are assumed to be sufficient.
+	///
+	/// # Arguments
+	/// * `requirements` - The resources required.
+	pub fn shortfalls(&self, requirements: &Requirements) -> Vec<Shortfall> {
+		let mut shortfalls = vec![];
+		if let Some(available) = self.disk.filter(|a| *a < requirements.disk) {
+			shortfalls.push(Shortfall::Disk { available, required: requirements.disk });
+		}
+		if let Some(available) = self.memory.filter(|a| *a < requirements.memory) {
+			shortfalls.push(Shortfall::Memory { available, required: requirements.memory });
+		}
+		if let Some(limit) = self.file_descriptors.filter(|l| *l < requirements.file_descriptors) {
+			shortfalls.push(Shortfall::FileDescriptors {
+				limit,
+				required: requirements.file_descriptors,
+			});
+		}
+		shortfalls
+	}
+}
+
+/// A resource which is insufficient for an operation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shortfall {
+	/// Insufficient free disk space.
+	Disk { available: u64, required: u64 },
+	/// Insufficient available memory.
+	Memory { available: u64, required: u64 },
+	/// An insufficient limit on open file descriptors.
+	FileDescriptors { limit: u64, required: u64 },
+}
+
+impl Shortfall {
+	/// Guidance on how the shortfall can be resolved.
+	pub fn guidance(&self) -> String {
+		match self {
+			Shortfall::Disk { .. } => message("preflight.disk_guidance", &[]),
+			Shortfall::Memory { .. } => message("preflight.memory_guidance", &[]),
+			Shortfall::FileDescriptors { .. } =>
+				message("preflight.file_descriptors_guidance", &[]),
+		}
+	}
+}
+
+impl Display for Shortfall {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let guidance = self.guidance();
+		let message = match self {
+			Shortfall::Disk { available, required } => message(
+				"preflight.disk",
+				&[
+					("available", &size(*available)),
+					("required", &size(*required)),
+					("guidance", &guidance),
+				],
+			),
+			Shortfall::Memory { available, required } => message(
+				"preflight.memory",
+				&[
+					("available", &size(*available)),
+					("required", &size(*required)),
+					("guidance", &guidance),
+				],
+			),
+			Shortfall::FileDescriptors { limit, required } => message(
+				"preflight.file_descriptors",
+				&[
+					("limit", &limit.to_string()),
+					("required", &required.to_string()),
+					("guidance", &guidance),
+				],
+			),
+		};
+		write!(f, "{message}")
+	}
+}
+
+/// The shortfalls in the resources available for an operation, against its requirements. None
+/// are reported if `POP_SKIP_PREFLIGHT` is set.
+///
+/// # Arguments
+/// * `path` - The path written to by the operation.
+/// * `requirements` - The resources required by the operation.
+pub fn shortfalls(path: &Path, requirements: &Requirements) -> Vec<Shortfall> {
+	if env::var_os(SKIP_VAR).is_some() {
+		return vec![];
+	}
+	Resources::detect(path).shortfalls(requirements)
+}
+
+/// Checks that sufficient resources are available for an operation, failing early with guidance
+/// otherwise. Checks are skipped if `POP_SKIP_PREFLIGHT` is set.
+///
+/// # Arguments
+/// * `operation` - A description of the operation (e.g. `build`).
+/// * `path` - The path written to by the operation.
+/// * `requirements` - The resources required by the operation.
+pub fn check(operation: &str, path: &Path, requirements: &Requirements) -> Result<(), Error> {
+	let shortfalls = shortfalls(path, requirements);
+	if shortfalls.is_empty() {
+		return Ok(());
+	}
+	let shortfalls: Vec<_> = shortfalls.iter().map(|s| s.to_string()).collect();
+	Err(Error::Preflight(message(
+		"preflight.insufficient",
+		&[("operation", operation), ("shortfalls", &shortfalls.join("; ")), ("variable", SKIP_VAR)],
+	)))
+}
+
+/// Parses the free disk space, in bytes, from the output of `df -Pk`.
+fn parse_df(output: &str) -> Option<u64> {
+	let available: u64 = output.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+	Some(available * 1024)
+}
+
+/// Parses the available memory, in bytes, from the contents of `/proc/meminfo`.
+fn parse_meminfo(info: &str) -> Option<u64> {
+	let line = info.lines().find(|l| l.starts_with("MemAvailable:"))?;
+	let available: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+	Some(available * 1024)
+}
+
+/// A human-readable size.
+fn size(bytes: u64) -> String {
+	if bytes >= GIB {
+		format!("{:.1} GiB", bytes as f64 / GIB as f64)
+	} else {
+		format!("{} MiB", bytes / MIB)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn requirements_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert_eq!(Requirements::build(temp_dir.path()).disk, 20 * GIB);
+		std::fs::create_dir(temp_dir.path().join("target"))?;
+		assert_eq!(Requirements::build(temp_dir.path()).disk, 5 * GIB);
+		assert_eq!(Requirements::launch(4).memory, 2 * GIB);
+		assert_eq!(Requirements::launch(0), Requirements::launch(1));
+		Ok(())
+	}
+
+	#[test]
+	fn detect_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let resources = Resources::detect(&temp_dir.path().join("missing/target"));
+		assert!(resources.disk.is_some_and(|d| d > 0));
+		assert!(resources.file_descriptors.is_some_and(|l| l > 0));
+		Ok(())
+	}
+
+	#[test]
+	fn shortfalls_works() {
+		let resources =
+			Resources { disk: Some(2 * GIB), memory: None, file_descriptors: Some(256) };
+		let shortfalls = resources.shortfalls(&Requirements {
+			disk: 5 * GIB,
+			memory: 4 * GIB,
+			file_descriptors: 1024,
+		});
+		assert_eq!(
+			shortfalls,
+			vec![
+				Shortfall::Disk { available: 2 * GIB, required: 5 * GIB },
+				Shortfall::FileDescriptors { limit: 256, required: 1024 }
+			]
+		);
+		assert_eq!(
+			shortfalls[0].to_string(),
+			"2.0 GiB of free disk space available, 5.0 GiB required: free up space, e.g. via `cargo clean` in unused projects or by removing cached binaries"
+		);
+		assert!(resources.shortfalls(&Requirements::default()).is_empty());
+	}
+
+	#[test]
+	fn parse_works() {
+		let df = "Filesystem 1024-blocks Used Available Capacity Mounted on\n/dev/vda 264212084 37771692 58285716 40% /\n";
+		assert_eq!(parse_df(df), Some(58285716 * 1024));
+		assert_eq!(parse_df(""), None);
+		let meminfo =
+			"MemTotal:        6147400 kB\nMemFree:          123 kB\nMemAvailable:    5456384 kB\n";
+		assert_eq!(parse_meminfo(meminfo), Some(5456384 * 1024));
+		assert_eq!(parse_meminfo("MemTotal: 1 kB"), None);
+		assert_eq!(size(512 * MIB), "512 MiB");
+	}
+}
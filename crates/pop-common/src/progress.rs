@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	build::{BuildMessage, BuildReporter},
+	manifest::find_workspace_toml,
+	Status,
+};
+use serde::Serialize;
+use std::{
+	fs::read_to_string,
+	io::{stderr, Write},
+	path::Path,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Mutex,
+	},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The environment variable used to request machine-readable progress events.
+pub const PROGRESS_FORMAT_VAR: &str = "POP_PROGRESS";
+
+/// The kind of a progress event.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+	/// A step has started.
+	Started,
+	/// A step has made progress.
+	Progress,
+	/// A step has produced an artifact.
+	Artifact,
+	/// A step has completed successfully.
+	Completed,
+	/// A step has failed.
+	Failed,
+}
+
+/// A machine-readable progress event, emitted as a single line of JSON.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProgressEvent {
+	/// The identifier of the step (e.g. `build`, `spec`, `launch`).
+	pub step: String,
+	/// The kind of event.
+	pub kind: EventKind,
+	/// A human-readable message, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub message: Option<String>,
+	/// The percentage of the step completed, if known.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub percent: Option<u8>,
+	/// The path of an artifact produced by the step, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub artifact: Option<String>,
+	/// The time at which the event occurred, in milliseconds since the Unix epoch.
+	pub timestamp: u64,
+}
+
+impl ProgressEvent {
+	/// Creates a new event for a step.
+	///
+	/// # Arguments
+	/// * `step` - The identifier of the step.
+	/// * `kind` - The kind of event.
+	pub fn new(step: &str, kind: EventKind) -> Self {
+		Self {
+			step: step.to_string(),
+			kind,
+			message: None,
+			percent: None,
+			artifact: None,
+			timestamp: SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|d| d.as_millis() as u64)
+				.unwrap_or_default(),
+		}
+	}
+}
+
+/// Whether machine-readable progress events have been requested via the `POP_PROGRESS`
+/// environment variable.
+pub fn ndjson_requested() -> bool {
+	std::env::var(PROGRESS_FORMAT_VAR).is_ok_and(|format| format.eq_ignore_ascii_case("ndjson"))
+}
+
+/// Estimates the number of crates compiled by a build of a project, from the packages locked
+/// within the `Cargo.lock` file of the project or of its workspace.
+///
+/// # Arguments
+/// * `project` - The project directory.
+pub fn locked_packages(project: &Path) -> Option<usize> {
+	let lock = match project.join("Cargo.lock") {
+		lock if lock.exists() => lock,
+		_ => find_workspace_toml(project)?.with_file_name("Cargo.lock"),
+	};
+	let contents = read_to_string(lock).ok()?;
+	let packages = contents.lines().filter(|line| line.trim() == "[[package]]").count();
+	(packages > 0).then_some(packages)
+}
+
+/// Emits progress events as newline-delimited JSON, by default on stderr so that stdout remains
+/// reserved for results. Any interactive output should be suppressed whilst events are emitted,
+/// so that the stream can be parsed.
+pub struct NdjsonReporter<W: Write + Send = std::io::Stderr> {
+	/// The identifier of the step being reported.
+	step: String,
+	/// The writer to which events are emitted.
+	writer: Mutex<W>,
+	/// The number of crates expected to be compiled, if known.
+	total: Option<usize>,
+	/// The number of crates compiled.
+	compiled: AtomicUsize,
+}
+
+impl NdjsonReporter {
+	/// Creates a reporter which emits events for a step on stderr.
+	///
+	/// # Arguments
+	/// * `step` - The identifier of the step.
+	pub fn stderr(step: &str) -> Self {
+		Self::new(step, stderr())
+	}
+}
+
+impl<W: Write + Send> NdjsonReporter<W> {
+	/// Creates a reporter which emits events for a step to a writer.
+	///
+	/// # Arguments
+	/// * `step` - The identifier of the step.
+	/// * `writer` - The writer to which events are emitted.
+	pub fn new(step: &str, writer: W) -> Self {
+		Self { step: step.to_string(), writer: Mutex::new(writer), total: None, compiled: 0.into() }
+	}
+
+	/// Sets the number of crates expected to be compiled, from which the percentage of a build
+	/// completed is reported.
+	///
+	/// # Arguments
+	/// * `total` - The number of crates expected to be compiled.
+	pub fn with_total(mut self, total: Option<usize>) -> Self {
+		self.total = total;
+		self
+	}
+
+	/// Emits an event. Failures to write are ignored, so that reporting never interrupts the
+	/// operation being reported.
+	///
+	/// # Arguments
+	/// * `event` - The event to be emitted.
+	pub fn emit(&self, event: &ProgressEvent) {
+		if let Ok(line) = serde_json::to_string(event) {
+			let mut writer = self.writer.lock().expect("lock not poisoned");
+			let _ = writeln!(writer, "{line}").and_then(|_| writer.flush());
+		}
+	}
+
+	/// Reports that the step has started.
+	pub fn started(&self) {
+		self.emit(&ProgressEvent::new(&self.step, EventKind::Started));
+	}
+
+	/// Reports the progress of the step.
+	///
+	/// # Arguments
+	/// * `percent` - The percentage of the step completed.
+	/// * `message` - A human-readable message.
+	pub fn progress(&self, percent: Option<u8>, message: &str) {
+		let mut event = ProgressEvent::new(&self.step, EventKind::Progress);
+		event.percent = percent.map(|p| p.min(100));
+		event.message = Some(message.to_string());
+		self.emit(&event);
+	}
+
+	/// Reports an artifact produced by the step.
+	///
+	/// # Arguments
+	/// * `path` - The path of the artifact.
+	pub fn artifact(&self, path: &Path) {
+		let mut event = ProgressEvent::new(&self.step, EventKind::Artifact);
+		event.artifact = Some(path.display().to_string());
+		self.emit(&event);
+	}
+
+	/// Reports that the step has completed.
+	pub fn completed(&self) {
+		let mut event = ProgressEvent::new(&self.step, EventKind::Completed);
+		event.percent = Some(100);
+		self.emit(&event);
+	}
+
+	/// Reports that the step has failed.
+	///
+	/// # Arguments
+	/// * `error` - A description of the failure.
+	pub fn failed(&self, error: &str) {
+		let mut event = ProgressEvent::new(&self.step, EventKind::Failed);
+		event.message = Some(error.to_string());
+		self.emit(&event);
+	}
+}
+
+impl<W: Write + Send> Status for NdjsonReporter<W> {
+	fn update(&self, status: &str) {
+		self.progress(None, status.trim());
+	}
+}
+
+impl<W: Write + Send> BuildReporter for NdjsonReporter<W> {
+	fn report(&self, message: &BuildMessage) {
+		match message {
+			BuildMessage::Compiled { name, fresh } => {
+				let compiled = self.compiled.fetch_add(1, Ordering::Relaxed) + 1;
+				// The estimate excludes build scripts, so the build is only reported as complete
+				// once it has finished.
+				let percent = self.total.map(|total| (compiled * 100 / total.max(1)).min(99) as u8);
+				if !fresh {
+					self.progress(percent, &format!("Compiling {name}"));
+				}
+			},
+			BuildMessage::Error(error) => self.progress(None, error),
+			BuildMessage::Warning(_) | BuildMessage::Finished { .. } => {},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::Value;
+
+	fn events(reporter: NdjsonReporter<Vec<u8>>) -> Vec<Value> {
+		let output = reporter.writer.into_inner().unwrap();
+		String::from_utf8(output)
+			.unwrap()
+			.lines()
+			.map(|line| serde_json::from_str(line).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn ndjson_reporter_works() {
+		let reporter = NdjsonReporter::new("build", Vec::new());
+		reporter.started();
+		reporter.update("   Compiling pop-node");
+		reporter.progress(Some(150), "Linking");
+		reporter.artifact(Path::new("target/release/pop-node"));
+		reporter.completed();
+		reporter.failed("out of disk space");
+
+		let events = events(reporter);
+		assert_eq!(events.len(), 6);
+		assert!(events.iter().all(|e| e["step"] == "build" && e["timestamp"].is_u64()));
+		assert_eq!(events[0]["kind"], "started");
+		assert!(events[0].get("message").is_none());
+		assert_eq!(events[1]["kind"], "progress");
+		assert_eq!(events[1]["message"], "Compiling pop-node");
+		assert_eq!(events[2]["percent"], 100);
+		assert_eq!(events[3]["kind"], "artifact");
+		assert_eq!(events[3]["artifact"], "target/release/pop-node");
+		assert_eq!(events[4]["kind"], "completed");
+		assert_eq!(events[5]["kind"], "failed");
+		assert_eq!(events[5]["message"], "out of disk space");
+	}
+
+	#[test]
+	fn ndjson_reporter_reports_builds() {
+		let reporter = NdjsonReporter::new("build", Vec::new());
+		reporter.report(&BuildMessage::Compiled { name: "serde".into(), fresh: true });
+		reporter.report(&BuildMessage::Compiled { name: "pop-node".into(), fresh: false });
+		reporter.report(&BuildMessage::Finished { success: true });
+
+		let events = events(reporter);
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0]["message"], "Compiling pop-node");
+		assert!(events[0].get("percent").is_none());
+	}
+
+	#[test]
+	fn ndjson_reporter_reports_build_percentages() {
+		let reporter = NdjsonReporter::new("build", Vec::new()).with_total(Some(4));
+		reporter.report(&BuildMessage::Compiled { name: "serde".into(), fresh: true });
+		reporter.report(&BuildMessage::Compiled { name: "scale".into(), fresh: false });
+		reporter.report(&BuildMessage::Error("mismatched types".into()));
+		for name in ["runtime", "node", "build-script"] {
+			reporter.report(&BuildMessage::Compiled { name: name.into(), fresh: false });
+		}
+
+		let events = events(reporter);
+		let percents: Vec<_> = events.iter().map(|e| e["percent"].as_u64()).collect();
+		assert_eq!(percents, [Some(50), None, Some(75), Some(99), Some(99)]);
+		assert_eq!(events[1]["message"], "mismatched types");
+	}
+
+	#[test]
+	fn locked_packages_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("node");
+		std::fs::create_dir(&project)?;
+		assert_eq!(locked_packages(&project), None);
+
+		std::fs::write(temp_dir.path().join("Cargo.toml"), "[workspace]\nmembers = [\"node\"]")?;
+		std::fs::write(
+			temp_dir.path().join("Cargo.lock"),
+			"version = 3\n\n[[package]]\nname = \"a\"\n\n[[package]]\nname = \"b\"\n",
+		)?;
+		assert_eq!(locked_packages(&project), Some(2));
+		std::fs::write(project.join("Cargo.lock"), "version = 3\n\n[[package]]\nname = \"a\"\n")?;
+		assert_eq!(locked_packages(&project), Some(1));
+		Ok(())
+	}
+}
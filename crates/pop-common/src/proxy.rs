@@ -0,0 +1,378 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{i18n::message, Error};
+use futures::{SinkExt, StreamExt};
+use regex::Regex;
+use serde_json::Value;
+use std::{
+	collections::HashMap,
+	net::SocketAddr,
+	sync::{Arc, Mutex},
+};
+use tokio::{
+	net::{TcpListener, TcpStream},
+	task::JoinHandle,
+};
+use tokio_tungstenite::{accept_async, connect_async, tungstenite::Message};
+use url::Url;
+
+/// The direction of a JSON-RPC message relayed by the proxy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+	/// A request sent by the client to the node.
+	Request,
+	/// A response sent by the node to the client.
+	Response,
+	/// A subscription notification sent by the node to the client.
+	Notification,
+}
+
+/// A JSON-RPC message relayed by the proxy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcMessage {
+	/// The direction of the message.
+	pub direction: Direction,
+	/// The method of the message, correlated with the originating request for responses.
+	pub method: Option<String>,
+	/// The message itself.
+	pub payload: Value,
+}
+
+impl RpcMessage {
+	/// Formats the message for display.
+	///
+	/// # Arguments
+	/// * `pretty` - Whether the payload should be pretty-printed.
+	pub fn format(&self, pretty: bool) -> String {
+		let arrow = match self.direction {
+			Direction::Request => "-->",
+			Direction::Response => "<--",
+			Direction::Notification => "<~~",
+		};
+		let payload = match pretty {
+			true => serde_json::to_string_pretty(&self.payload),
+			false => serde_json::to_string(&self.payload),
+		}
+		.unwrap_or_default();
+		format!("{arrow} {} {payload}", self.method.as_deref().unwrap_or("<unknown>"))
+	}
+}
+
+/// Configuration of a logging JSON-RPC proxy.
+#[derive(Clone, Debug)]
+pub struct ProxyConfig {
+	/// The local address on which the proxy listens, with port `0` selecting any available port.
+	pub listen: SocketAddr,
+	/// The WebSocket endpoint of the node to which traffic is relayed.
+	pub upstream: Url,
+	/// If specified, only messages whose method matches the filter are logged.
+	pub filter: Option<Regex>,
+}
+
+impl ProxyConfig {
+	/// Configures a proxy which listens on any available local port.
+	///
+	/// # Arguments
+	/// * `upstream` - The WebSocket endpoint of the node to which traffic is relayed.
+	pub fn new(upstream: Url) -> Self {
+		Self { listen: SocketAddr::from(([127, 0, 0, 1], 0)), upstream, filter: None }
+	}
+
+	/// Only log messages whose method matches the specified regular expression.
+	///
+	/// # Arguments
+	/// * `pattern` - The regular expression used to filter messages by method.
+	pub fn filter(mut self, pattern: &str) -> Result<Self, Error> {
+		let filter = Regex::new(pattern).map_err(|e| {
+			let error = e.to_string();
+			Error::Config(message(
+				"proxy.invalid_filter",
+				&[("pattern", pattern), ("error", &error)],
+			))
+		})?;
+		self.filter = Some(filter);
+		Ok(self)
+	}
+}
+
+/// A local proxy which relays JSON-RPC traffic between clients and a node over WebSocket,
+/// logging each message.
+pub struct RpcProxy {
+	/// The address on which the proxy is listening.
+	local_addr: SocketAddr,
+	/// The task accepting connections.
+	handle: JoinHandle<()>,
+	/// The tasks relaying the traffic of each connection.
+	relays: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl RpcProxy {
+	/// Starts the proxy, which runs until stopped or dropped.
+	///
+	/// # Arguments
+	/// * `config` - The configuration of the proxy.
+	/// * `log` - The function used to log each relayed message matching the filter.
+	pub async fn start(
+		config: ProxyConfig,
+		log: impl Fn(&RpcMessage) + Send + Sync + 'static,
+	) -> Result<Self, Error> {
+		if config.upstream.scheme() != "ws" {
+			return Err(Error::UnsupportedCommand(message(
+				"proxy.unsupported_upstream",
+				&[("upstream", config.upstream.as_str())],
+			)));
+		}
+		let listener = TcpListener::bind(config.listen).await?;
+		let local_addr = listener.local_addr()?;
+		let log: Arc<dyn Fn(&RpcMessage) + Send + Sync> = Arc::new(log);
+		let config = Arc::new(config);
+		let relays = Arc::new(Mutex::new(Vec::<JoinHandle<()>>::new()));
+		let tasks = relays.clone();
+		let handle = tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				let (config, log) = (config.clone(), log.clone());
+				let relay = tokio::spawn(async move {
+					// Connection errors only affect the connecting client.
+					let _ = relay(stream, &config, log).await;
+				});
+				let mut tasks = tasks.lock().expect("lock is not poisoned");
+				tasks.retain(|task| !task.is_finished());
+				tasks.push(relay);
+			}
+		});
+		Ok(Self { local_addr, handle, relays })
+	}
+
+	/// The endpoint to which clients should connect.
+	pub fn url(&self) -> Url {
+		Url::parse(&format!("ws://{}", self.local_addr)).expect("socket address is a valid host")
+	}
+
+	/// Stops the proxy, closing any open connections.
+	pub fn stop(self) {
+		drop(self)
+	}
+}
+
+impl Drop for RpcProxy {
+	fn drop(&mut self) {
+		self.handle.abort();
+		for relay in self.relays.lock().expect("lock is not poisoned").drain(..) {
+			relay.abort();
+		}
+	}
+}
+
+/// Relays messages between a client connection and the upstream node until either side closes.
+async fn relay(
+	stream: TcpStream,
+	config: &ProxyConfig,
+	log: Arc<dyn Fn(&RpcMessage) + Send + Sync>,
+) -> Result<(), Error> {
+	let client = accept_async(stream).await.map_err(|e| Error::Config(e.to_string()))?;
+	let (node, _) = connect_async(config.upstream.as_str()).await.map_err(|e| {
+		let (upstream, error) = (config.upstream.as_str(), e.to_string());
+		Error::Config(message(
+			"proxy.connection_failed",
+			&[("upstream", upstream), ("error", &error)],
+		))
+	})?;
+	let (mut client_tx, mut client_rx) = client.split();
+	let (mut node_tx, mut node_rx) = node.split();
+	// Methods of pending requests, by request identifier, used to correlate responses.
+	let pending = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+	let observe = |direction: Direction, message: &Message| {
+		let Message::Text(text) = message else { return };
+		for message in inspect(direction, text, &pending) {
+			let matches = match (&config.filter, &message.method) {
+				(Some(filter), Some(method)) => filter.is_match(method),
+				(Some(_), None) => false,
+				(None, _) => true,
+			};
+			if matches {
+				log(&message);
+			}
+		}
+	};
+	let upstream = async {
+		while let Some(Ok(message)) = client_rx.next().await {
+			observe(Direction::Request, &message);
+			if node_tx.send(message).await.is_err() {
+				break;
+			}
+		}
+	};
+	let downstream = async {
+		while let Some(Ok(message)) = node_rx.next().await {
+			observe(Direction::Response, &message);
+			if client_tx.send(message).await.is_err() {
+				break;
+			}
+		}
+	};
+	tokio::select! {
+		_ = upstream => {},
+		_ = downstream => {},
+	}
+	Ok(())
+}
+
+/// Parses the (possibly batched) JSON-RPC messages within a text frame, tracking the methods of
+/// requests so that they can be correlated with their responses.
+///
+/// # Arguments
+/// * `direction` - The direction of the frame.
+/// * `text` - The contents of the frame.
+/// * `pending` - The methods of pending requests, by request identifier.
+fn inspect(
+	direction: Direction,
+	text: &str,
+	pending: &Mutex<HashMap<String, String>>,
+) -> Vec<RpcMessage> {
+	let payloads = match serde_json::from_str::<Value>(text) {
+		Ok(Value::Array(payloads)) => payloads,
+		Ok(payload) => vec![payload],
+		Err(_) => return vec![],
+	};
+	let mut pending = pending.lock().expect("lock is not poisoned");
+	payloads
+		.into_iter()
+		.map(|payload| {
+			let id = payload.get("id").filter(|id| !id.is_null()).map(|id| id.to_string());
+			let method = payload.get("method").and_then(Value::as_str).map(String::from);
+			let (direction, method) = match (direction, id) {
+				(Direction::Request, Some(id)) => {
+					if let Some(method) = method.as_ref() {
+						pending.insert(id, method.clone());
+					}
+					(Direction::Request, method)
+				},
+				(Direction::Request, None) => (Direction::Request, method),
+				(_, Some(id)) => (Direction::Response, pending.remove(&id)),
+				(_, None) => (Direction::Notification, method),
+			};
+			RpcMessage { direction, method, payload }
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn inspect_correlates_responses() {
+		let pending = Mutex::new(HashMap::new());
+		let request = inspect(
+			Direction::Request,
+			r#"{"jsonrpc":"2.0","id":1,"method":"chain_getBlockHash","params":[]}"#,
+			&pending,
+		);
+		assert_eq!(request[0].direction, Direction::Request);
+		assert_eq!(request[0].method.as_deref(), Some("chain_getBlockHash"));
+
+		let response =
+			inspect(Direction::Response, r#"{"jsonrpc":"2.0","id":1,"result":"0x00"}"#, &pending);
+		assert_eq!(response[0].direction, Direction::Response);
+		assert_eq!(response[0].method.as_deref(), Some("chain_getBlockHash"));
+		assert!(pending.lock().unwrap().is_empty());
+
+		let notification = inspect(
+			Direction::Response,
+			r#"{"jsonrpc":"2.0","method":"chain_finalizedHead","params":{"subscription":"a"}}"#,
+			&pending,
+		);
+		assert_eq!(notification[0].direction, Direction::Notification);
+		assert_eq!(notification[0].method.as_deref(), Some("chain_finalizedHead"));
+	}
+
+	#[test]
+	fn inspect_handles_batches_and_invalid_frames() {
+		let pending = Mutex::new(HashMap::new());
+		let batch = inspect(
+			Direction::Request,
+			r#"[{"id":1,"method":"system_name"},{"id":2,"method":"system_version"}]"#,
+			&pending,
+		);
+		assert_eq!(batch.len(), 2);
+		assert_eq!(pending.lock().unwrap().len(), 2);
+		assert!(inspect(Direction::Request, "not json", &pending).is_empty());
+	}
+
+	#[test]
+	fn format_works() {
+		let message = RpcMessage {
+			direction: Direction::Request,
+			method: Some("system_name".into()),
+			payload: json!({"id":1,"method":"system_name"}),
+		};
+		assert_eq!(message.format(false), r#"--> system_name {"id":1,"method":"system_name"}"#);
+		let message = RpcMessage { direction: Direction::Notification, method: None, ..message };
+		assert!(message.format(true).starts_with("<~~ <unknown> {\n"));
+	}
+
+	#[test]
+	fn filter_works() {
+		let config = ProxyConfig::new(Url::parse("ws://127.0.0.1:9944").unwrap());
+		assert_eq!(config.listen.port(), 0);
+		let config = config.filter("^chain_").unwrap();
+		assert!(config.filter.as_ref().unwrap().is_match("chain_getBlockHash"));
+		assert!(matches!(
+			ProxyConfig::new(config.upstream).filter("("),
+			Err(Error::Config(e)) if e.starts_with("invalid RPC filter `(`")
+		));
+	}
+
+	#[tokio::test]
+	async fn start_fails_unsupported_upstream() {
+		for upstream in ["wss://rpc.polkadot.io", "http://127.0.0.1:9944"] {
+			let config = ProxyConfig::new(Url::parse(upstream).unwrap());
+			assert!(matches!(
+				RpcProxy::start(config, |_| {}).await,
+				Err(Error::UnsupportedCommand(..))
+			));
+		}
+	}
+
+	#[tokio::test]
+	async fn relay_works() -> anyhow::Result<()> {
+		// A node which responds to every request with a fixed result.
+		let node = TcpListener::bind("127.0.0.1:0").await?;
+		let upstream = Url::parse(&format!("ws://{}", node.local_addr()?))?;
+		tokio::spawn(async move {
+			let (stream, _) = node.accept().await.unwrap();
+			let mut ws = accept_async(stream).await.unwrap();
+			while let Some(Ok(Message::Text(_))) = ws.next().await {
+				let response = r#"{"jsonrpc":"2.0","id":1,"result":"pop-node"}"#;
+				ws.send(Message::Text(response.into())).await.unwrap();
+			}
+		});
+
+		let logged = Arc::new(Mutex::new(vec![]));
+		let messages = logged.clone();
+		let proxy = RpcProxy::start(ProxyConfig::new(upstream).filter("^system_")?, move |m| {
+			messages.lock().unwrap().push(m.clone())
+		})
+		.await?;
+		let (mut client, _) = connect_async(proxy.url().as_str()).await?;
+		client
+			.send(Message::Text(r#"{"jsonrpc":"2.0","id":1,"method":"system_name"}"#.into()))
+			.await?;
+		let Some(Ok(Message::Text(response))) = client.next().await else {
+			anyhow::bail!("expected a response");
+		};
+		assert!(response.contains("pop-node"));
+		proxy.stop();
+		// Stopping the proxy closes the connections it relays.
+		let next = tokio::time::timeout(std::time::Duration::from_secs(5), client.next()).await?;
+		assert!(!matches!(next, Some(Ok(Message::Text(_)))));
+
+		let logged = logged.lock().unwrap();
+		assert_eq!(logged.len(), 2);
+		assert_eq!(logged[0].direction, Direction::Request);
+		assert_eq!(logged[1].direction, Direction::Response);
+		assert!(logged.iter().all(|m| m.method.as_deref() == Some("system_name")));
+		Ok(())
+	}
+}
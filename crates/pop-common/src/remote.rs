@@ -0,0 +1,386 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{hooks::CONFIG_FILE, migration::read_config, Error};
+use duct::{cmd, Expression, Handle};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+	fs::create_dir_all,
+	path::{Path, PathBuf},
+};
+
+/// The directory on the remote host within which projects are synced, by default.
+const DEFAULT_DIR: &str = ".pop/remote";
+
+/// The directories excluded when syncing a project, as they only contain generated files.
+const EXCLUDED: [&str; 3] = [".git", "node_modules", "target"];
+
+/// A remote host on which heavyweight operations, such as builds and node processes, are run over
+/// SSH, whilst orchestration remains local and the artifacts are synced back.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Remote {
+	/// The SSH destination (e.g. `user@build-server`).
+	pub host: String,
+	/// The SSH port, if not the default.
+	#[serde(default)]
+	pub port: Option<u16>,
+	/// The identity file used to authenticate, if not the default.
+	#[serde(default)]
+	pub identity: Option<PathBuf>,
+	/// The directory on the remote host within which projects are synced, relative to the home
+	/// directory of the user unless absolute.
+	#[serde(default = "default_dir")]
+	pub dir: String,
+}
+
+/// The contents of the project configuration file relevant to remote execution.
+#[derive(Default, Deserialize)]
+struct Config {
+	remote: Option<Remote>,
+}
+
+impl Remote {
+	/// Parses a remote host from an SSH destination, optionally including a port (e.g.
+	/// `user@build-server:2222`). IPv6 addresses are enclosed within brackets when including a
+	/// port (e.g. `user@[::1]:2222`).
+	///
+	/// # Arguments
+	/// * `destination` - The SSH destination.
+	pub fn parse(destination: &str) -> Result<Self, Error> {
+		let invalid = || Error::Config(format!("invalid remote host `{destination}`"));
+		let parse_port = |port: &str| {
+			port.parse()
+				.map_err(|_| Error::Config(format!("invalid port in remote host `{destination}`")))
+		};
+		let (user, address) = match destination.rsplit_once('@') {
+			Some((user, address)) => (Some(user), address),
+			None => (None, destination),
+		};
+		let (address, port) = match address.strip_prefix('[') {
+			Some(address) => {
+				let (address, rest) = address.split_once(']').ok_or_else(invalid)?;
+				match rest {
+					"" => (address, None),
+					rest => {
+						let port = rest.strip_prefix(':').ok_or_else(invalid)?;
+						(address, Some(parse_port(port)?))
+					},
+				}
+			},
+			// Unbracketed addresses containing several colons are IPv6 addresses without a port.
+			None => match address.split_once(':') {
+				Some((host, port)) if !port.contains(':') => (host, Some(parse_port(port)?)),
+				_ => (address, None),
+			},
+		};
+		if address.is_empty() || destination.contains(char::is_whitespace) {
+			return Err(invalid());
+		}
+		let host = user.map_or(address.to_string(), |user| format!("{user}@{address}"));
+		Ok(Self { host, port, identity: None, dir: default_dir() })
+	}
+
+	/// Loads the remote host configured within the `[remote]` table of the `pop.toml` file of a
+	/// project, if any.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn load(project: &Path) -> Result<Option<Self>, Error> {
+		let path = project.join(CONFIG_FILE);
+		let Some(config) = read_config(project)? else {
+			return Ok(None);
+		};
+		let config: Config = toml_edit::de::from_str(&config).map_err(|e| {
+			Error::Config(format!("invalid remote host in {}: {e}", path.display()))
+		})?;
+		Ok(config.remote)
+	}
+
+	/// The directory on the remote host to which a project is synced, named after the project
+	/// and a hash of its local path, so that projects sharing a name never share a directory.
+	///
+	/// # Arguments
+	/// * `project` - The local project directory.
+	pub fn project_dir(&self, project: &Path) -> String {
+		let project = project.canonicalize().unwrap_or_else(|_| project.to_path_buf());
+		let name = project
+			.file_name()
+			.map_or("project".to_string(), |n| n.to_string_lossy().to_string());
+		let hash = Sha256::digest(project.as_os_str().as_encoded_bytes());
+		let hash: String = hash[..4].iter().map(|b| format!("{b:02x}")).collect();
+		format!("{}/{name}-{hash}", self.dir.trim_end_matches('/'))
+	}
+
+	/// The host as a destination of `rsync`, which requires IPv6 addresses to be enclosed within
+	/// brackets.
+	fn rsync_host(&self) -> String {
+		let (user, address) = match self.host.rsplit_once('@') {
+			Some((user, address)) => (Some(user), address),
+			None => (None, self.host.as_str()),
+		};
+		let address = match address.contains(':') && !address.starts_with('[') {
+			true => format!("[{address}]"),
+			false => address.to_string(),
+		};
+		user.map_or(address.clone(), |user| format!("{user}@{address}"))
+	}
+
+	/// Syncs a project to the remote host, excluding generated files so that any previous build
+	/// artifacts on the remote host are reused.
+	///
+	/// # Arguments
+	/// * `project` - The local project directory.
+	pub fn sync(&self, project: &Path) -> Result<(), Error> {
+		let dir = self.project_dir(project);
+		self.run_script(&format!("mkdir -p {}", quote(&dir)))?;
+		let mut args = vec!["-az".to_string(), "--delete".to_string()];
+		args.extend(EXCLUDED.iter().map(|e| format!("--exclude={e}")));
+		args.extend([
+			"-e".to_string(),
+			self.rsync_shell(),
+			format!("{}/", project.display()),
+			format!("{}:{dir}/", self.rsync_host()),
+		]);
+		self.check("rsync", cmd("rsync", args))
+	}
+
+	/// Fetches an artifact from the project directory on the remote host.
+	///
+	/// # Arguments
+	/// * `project` - The local project directory.
+	/// * `artifact` - The path of the artifact, relative to the project directory.
+	/// * `destination` - The local path to which the artifact is written.
+	pub fn fetch(&self, project: &Path, artifact: &Path, destination: &Path) -> Result<(), Error> {
+		if let Some(parent) = destination.parent() {
+			create_dir_all(parent)?;
+		}
+		let source =
+			format!("{}:{}/{}", self.rsync_host(), self.project_dir(project), artifact.display());
+		let args = [
+			"-az".to_string(),
+			"-e".to_string(),
+			self.rsync_shell(),
+			source,
+			destination.display().to_string(),
+		];
+		self.check("rsync", cmd("rsync", args))
+	}
+
+	/// Runs a command within the project directory on the remote host, streaming its output.
+	///
+	/// # Arguments
+	/// * `project` - The local project directory.
+	/// * `program` - The program to be run.
+	/// * `args` - The arguments of the program.
+	pub fn run(&self, project: &Path, program: &str, args: &[&str]) -> Result<(), Error> {
+		self.run_script(&self.script(project, program, args))
+	}
+
+	/// Starts a long-running command, such as a node process, within the project directory on the
+	/// remote host, forwarding the specified ports so that it can be reached locally. A terminal is
+	/// allocated on the remote host, so that the command is terminated along with the connection.
+	///
+	/// # Arguments
+	/// * `project` - The local project directory.
+	/// * `program` - The program to be run.
+	/// * `args` - The arguments of the program.
+	/// * `ports` - The ports to be forwarded from the local machine to the remote host.
+	pub fn spawn(
+		&self,
+		project: &Path,
+		program: &str,
+		args: &[&str],
+		ports: &[u16],
+	) -> Result<Handle, Error> {
+		let ssh = self.spawn_args(project, program, args, ports);
+		Ok(cmd(&ssh[0], &ssh[1..]).unchecked().start()?)
+	}
+
+	/// The SSH command used to start a long-running command on the remote host.
+	fn spawn_args(
+		&self,
+		project: &Path,
+		program: &str,
+		args: &[&str],
+		ports: &[u16],
+	) -> Vec<String> {
+		let mut ssh = self.ssh_args();
+		ssh.push("-tt".to_string());
+		for port in ports {
+			ssh.extend(["-L".to_string(), format!("{port}:127.0.0.1:{port}")]);
+		}
+		ssh.extend([self.host.clone(), self.script(project, program, args)]);
+		ssh
+	}
+
+	/// The script run on the remote host to execute a command within the project directory.
+	fn script(&self, project: &Path, program: &str, args: &[&str]) -> String {
+		let command: Vec<_> =
+			std::iter::once(program).chain(args.iter().copied()).map(quote).collect();
+		format!("cd {} && {}", quote(&self.project_dir(project)), command.join(" "))
+	}
+
+	/// Runs a script on the remote host.
+	fn run_script(&self, script: &str) -> Result<(), Error> {
+		let mut ssh = self.ssh_args();
+		ssh.extend([self.host.clone(), script.to_string()]);
+		self.check("ssh", cmd(&ssh[0], &ssh[1..]))
+	}
+
+	/// The SSH command used to connect to the remote host, excluding the destination.
+	fn ssh_args(&self) -> Vec<String> {
+		// Never prompt, as commands are often run non-interactively.
+		let mut args = vec!["ssh".to_string(), "-o".to_string(), "BatchMode=yes".to_string()];
+		if let Some(port) = self.port {
+			args.extend(["-p".to_string(), port.to_string()]);
+		}
+		if let Some(identity) = &self.identity {
+			args.extend(["-i".to_string(), identity.display().to_string()]);
+		}
+		args
+	}
+
+	/// The remote shell used by `rsync`, which splits it into arguments as a shell would.
+	fn rsync_shell(&self) -> String {
+		self.ssh_args().iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" ")
+	}
+
+	/// Runs an expression, failing if it does not succeed.
+	fn check(&self, program: &str, expression: Expression) -> Result<(), Error> {
+		let output = expression.stdout_to_stderr().unchecked().run()?;
+		if !output.status.success() {
+			return Err(Error::Remote(format!(
+				"`{program}` on {} failed: {}",
+				self.host, output.status
+			)));
+		}
+		Ok(())
+	}
+}
+
+fn default_dir() -> String {
+	DEFAULT_DIR.to_string()
+}
+
+/// Quotes an argument for use within a POSIX shell.
+fn quote(arg: &str) -> String {
+	if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:@+,".contains(c))
+	{
+		return arg.to_string();
+	}
+	format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	#[test]
+	fn parse_works() -> Result<()> {
+		let remote = Remote::parse("builder@build-server:2222")?;
+		assert_eq!((remote.host.as_str(), remote.port), ("builder@build-server", Some(2222)));
+		assert_eq!(remote.dir, DEFAULT_DIR);
+		assert_eq!(Remote::parse("build-server")?.port, None);
+		assert!(matches!(Remote::parse("build-server:ssh"), Err(Error::Config(..))));
+		assert!(matches!(Remote::parse(""), Err(Error::Config(..))));
+		// IPv6 addresses.
+		let remote = Remote::parse("builder@[2001:db8::1]:2222")?;
+		assert_eq!((remote.host.as_str(), remote.port), ("builder@2001:db8::1", Some(2222)));
+		assert_eq!(remote.rsync_host(), "builder@[2001:db8::1]");
+		let remote = Remote::parse("[::1]")?;
+		assert_eq!((remote.host.as_str(), remote.port), ("::1", None));
+		assert_eq!(Remote::parse("builder@fe80::1")?.host, "builder@fe80::1");
+		assert_eq!(Remote::parse("build-server")?.rsync_host(), "build-server");
+		for destination in ["[::1", "[::1]2222", "[::1]:ssh", "[]:22"] {
+			assert!(matches!(Remote::parse(destination), Err(Error::Config(..))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert_eq!(Remote::load(temp_dir.path())?, None);
+
+		write(
+			temp_dir.path().join(CONFIG_FILE),
+			r#"
+			[hooks]
+			pre-build = "echo"
+
+			[remote]
+			host = "builder@build-server"
+			identity = "~/.ssh/builder"
+			"#,
+		)?;
+		let remote = Remote::load(temp_dir.path())?.unwrap();
+		assert_eq!(remote.host, "builder@build-server");
+		assert_eq!(remote.identity, Some(PathBuf::from("~/.ssh/builder")));
+		assert_eq!(remote.dir, DEFAULT_DIR);
+		assert_eq!(
+			remote.ssh_args(),
+			["ssh", "-o", "BatchMode=yes", "-i", "~/.ssh/builder"].map(String::from)
+		);
+
+		write(temp_dir.path().join(CONFIG_FILE), "[remote]\nport = 22")?;
+		assert!(matches!(Remote::load(temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn script_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("my parachain");
+		std::fs::create_dir(&project)?;
+		let mut remote = Remote::parse("build-server")?;
+		remote.dir = "/srv/pop/".to_string();
+		let dir = remote.project_dir(&project);
+		let hash = dir.strip_prefix("/srv/pop/my parachain-").unwrap();
+		assert!(hash.len() == 8 && hash.chars().all(|c| c.is_ascii_hexdigit()));
+		assert_eq!(
+			remote.script(&project, "cargo", &["build", "--features", "a b", "it's"]),
+			format!(r"cd '{dir}' && cargo build --features 'a b' 'it'\''s'")
+		);
+		// Projects sharing a name are synced to different directories.
+		let other = temp_dir.path().join("other/my parachain");
+		std::fs::create_dir_all(&other)?;
+		assert_ne!(remote.project_dir(&other), dir);
+		Ok(())
+	}
+
+	#[test]
+	fn spawn_args_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("parachain");
+		std::fs::create_dir(&project)?;
+		let mut remote = Remote::parse("build-server:2222")?;
+		remote.dir = "/srv/pop".to_string();
+		assert_eq!(
+			remote.spawn_args(&project, "./target/release/node", &["--dev"], &[9944]),
+			[
+				"ssh",
+				"-o",
+				"BatchMode=yes",
+				"-p",
+				"2222",
+				"-tt",
+				"-L",
+				"9944:127.0.0.1:9944",
+				"build-server",
+				&format!("cd {} && ./target/release/node --dev", remote.project_dir(&project))
+			]
+			.map(String::from)
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn rsync_shell_quotes_arguments() -> Result<()> {
+		let mut remote = Remote::parse("build-server")?;
+		remote.identity = Some(PathBuf::from("/home/me/my keys/it's"));
+		assert_eq!(remote.rsync_shell(), r"ssh -o BatchMode=yes -i '/home/me/my keys/it'\''s'");
+		Ok(())
+	}
+}
@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, Account};
+use sp_core::keccak_256;
+use subxt::{
+	config::{
+		substrate::{BlakeTwo256, SubstrateExtrinsicParams, SubstrateHeader, H256},
+		Config,
+	},
+	ext::{
+		codec::{Decode, Encode, Output},
+		scale_decode::DecodeAsType,
+		scale_encode::{self, EncodeAsType, TypeResolver},
+	},
+	tx::Signer,
+	utils::{AccountId32, MultiAddress, MultiSignature},
+};
+use subxt_signer::{ecdsa, sr25519::Keypair, SecretUri};
+
+/// Create a signer from a secret URI.
+///
+/// # Arguments
+/// * `suri` - Secret URI string used to generate the `Keypair`.
+pub fn create_signer(suri: &str) -> Result<Keypair, Error> {
+	let uri = <SecretUri as std::str::FromStr>::from_str(suri)
+		.map_err(|e| Error::ParseSecretURI(format!("{}", e)))?;
+	let keypair = Keypair::from_uri(&uri).map_err(|e| Error::KeyPairCreation(format!("{}", e)))?;
+	Ok(keypair)
+}
+
+/// Create an ECDSA signer from a secret URI, for chains whose 32-byte accounts may be signed with
+/// ECDSA keys.
+///
+/// # Arguments
+/// * `suri` - Secret URI string used to generate the ECDSA keypair.
+pub fn create_ecdsa_signer(suri: &str) -> Result<ecdsa::Keypair, Error> {
+	let uri = <SecretUri as std::str::FromStr>::from_str(suri)
+		.map_err(|e| Error::ParseSecretURI(format!("{}", e)))?;
+	ecdsa::Keypair::from_uri(&uri).map_err(|e| Error::KeyPairCreation(format!("{}", e)))
+}
+
+/// Create a signer for Ethereum-compatible chains (e.g. Frontier-based) from a secret URI.
+/// Development accounts such as Alith are specified by their hex-encoded private key.
+///
+/// # Arguments
+/// * `suri` - Secret URI string used to generate the ECDSA keypair.
+pub fn create_ethereum_signer(suri: &str) -> Result<EthereumSigner, Error> {
+	create_ecdsa_signer(suri).map(EthereumSigner)
+}
+
+/// The configuration of Ethereum-compatible chains, whose accounts are 20-byte accounts and
+/// whose transactions are signed with ECDSA signatures over the keccak-256 hash of the payload.
+pub enum EthereumConfig {}
+
+impl Config for EthereumConfig {
+	type Hash = H256;
+	type AccountId = AccountId20;
+	type Address = AccountId20;
+	type Signature = EthereumSignature;
+	type Hasher = BlakeTwo256;
+	type Header = SubstrateHeader<u32, BlakeTwo256>;
+	type ExtrinsicParams = SubstrateExtrinsicParams<Self>;
+	type AssetId = EncodedAssetId;
+}
+
+/// The configuration of Substrate-based chains whose transactions pay fees in an asset via the
+/// `ChargeAssetTxPayment` signed extension, with the asset identified by an [`EncodedAssetId`]
+/// so that chains identifying assets by other than a `u32` (e.g. by their XCM location, as on
+/// asset hubs) are supported. Otherwise equivalent to `SubstrateConfig`.
+pub enum AssetFeeConfig {}
+
+impl Config for AssetFeeConfig {
+	type Hash = H256;
+	type AccountId = AccountId32;
+	type Address = MultiAddress<Self::AccountId, u32>;
+	type Signature = MultiSignature;
+	type Hasher = BlakeTwo256;
+	type Header = SubstrateHeader<u32, BlakeTwo256>;
+	type ExtrinsicParams = SubstrateExtrinsicParams<Self>;
+	type AssetId = EncodedAssetId;
+}
+
+/// The identifier of an asset, already SCALE encoded as the asset identifier type of a chain.
+#[derive(Clone, Debug, DecodeAsType, Eq, PartialEq)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+pub struct EncodedAssetId(pub Vec<u8>);
+
+impl Encode for EncodedAssetId {
+	fn size_hint(&self) -> usize {
+		self.0.len()
+	}
+
+	fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+		dest.write(&self.0);
+	}
+}
+
+impl EncodeAsType for EncodedAssetId {
+	fn encode_as_type_to<R: TypeResolver>(
+		&self,
+		_type_id: R::TypeId,
+		_types: &R,
+		out: &mut Vec<u8>,
+	) -> Result<(), scale_encode::Error> {
+		out.extend_from_slice(&self.0);
+		Ok(())
+	}
+}
+
+/// A 20-byte (Ethereum-style) account.
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, PartialEq)]
+#[codec(crate = subxt::ext::codec)]
+pub struct AccountId20(pub [u8; 20]);
+
+/// A recoverable ECDSA signature, as used by Ethereum-compatible chains.
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, PartialEq)]
+#[codec(crate = subxt::ext::codec)]
+pub struct EthereumSignature(pub [u8; 65]);
+
+/// A signer of transactions for Ethereum-compatible chains.
+#[derive(Clone, Debug)]
+pub struct EthereumSigner(ecdsa::Keypair);
+
+impl EthereumSigner {
+	/// The account of the signer, being the last 20 bytes of the keccak-256 hash of its
+	/// uncompressed public key.
+	pub fn account(&self) -> Account {
+		let public_key = secp256k1::PublicKey::from_slice(&self.0.public_key().0)
+			.expect("keypair holds a valid public key");
+		let hash = keccak_256(&public_key.serialize_uncompressed()[1..]);
+		Account::Id20(hash[12..].try_into().expect("length checked"))
+	}
+
+	/// Signs a payload.
+	///
+	/// # Arguments
+	/// * `payload` - The payload to be signed.
+	pub fn sign(&self, payload: &[u8]) -> EthereumSignature {
+		EthereumSignature(self.0.sign_prehashed(&keccak_256(payload)).0)
+	}
+}
+
+impl<T: Config> Signer<T> for EthereumSigner
+where
+	T::AccountId: From<AccountId20>,
+	T::Address: From<AccountId20>,
+	T::Signature: From<EthereumSignature>,
+{
+	fn account_id(&self) -> T::AccountId {
+		AccountId20(self.account().to_id20()).into()
+	}
+
+	fn address(&self) -> T::Address {
+		AccountId20(self.account().to_id20()).into()
+	}
+
+	fn sign(&self, payload: &[u8]) -> T::Signature {
+		EthereumSigner::sign(self, payload).into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn create_signer_works() -> Result<(), Error> {
+		let keypair = create_signer("//Alice")?;
+		assert_eq!(
+			keypair.public_key().to_account_id().to_string(),
+			"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY" //Alice account
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn create_ethereum_signer_works() -> Result<(), Error> {
+		// Alith, the first development account of Frontier-based chains.
+		let signer = create_ethereum_signer(
+			"0x5fb92d6e98884f76de468fa3f6278f8807c48bebc13595d45af5bdc4da702133",
+		)?;
+		assert_eq!(signer.account(), Account::parse("0xf24ff3a9cf04c71dbc94d0b566f7a27b94566cac")?);
+		// The signature recovers the public key of the signer from the keccak-256 hash.
+		let signature = signer.sign(b"payload");
+		let recovered = sp_core::ecdsa::Signature::from_raw(signature.0)
+			.recover_prehashed(&keccak_256(b"payload"))
+			.expect("signature is valid");
+		assert_eq!(recovered.0, signer.0.public_key().0);
+		Ok(())
+	}
+
+	#[test]
+	fn encoded_asset_id_encodes_as_is() {
+		let asset = EncodedAssetId(1984u32.encode());
+		assert_eq!(asset.encode(), 1984u32.encode());
+		assert_eq!(Some(asset).encode(), Some(1984u32).encode());
+	}
+
+	#[test]
+	fn create_signer_fails_wrong_key() -> Result<(), Error> {
+		assert!(matches!(create_signer("11111"), Err(Error::KeyPairCreation(..))));
+		Ok(())
+	}
+}
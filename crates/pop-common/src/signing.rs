@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use duct::cmd;
+use std::{
+	ffi::OsString,
+	fmt::{Display, Formatter},
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+/// The tool with which artifacts are signed and verified with minisign.
+const MINISIGN: &str = "minisign";
+/// The tool with which artifacts are signed and verified with sigstore.
+const COSIGN: &str = "cosign";
+
+/// How built artifacts (e.g. binaries and runtimes) are signed, so that operators can
+/// authenticate the artifacts published by a release pipeline.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArtifactSigner {
+	/// Sign with a minisign secret key, producing a `.minisig` signature alongside the artifact.
+	Minisign {
+		/// The path of the secret key.
+		secret_key: PathBuf,
+	},
+	/// Sign keylessly with sigstore via `cosign`, authenticating with an OIDC provider and
+	/// producing a `.sigstore.json` bundle alongside the artifact.
+	Sigstore,
+}
+
+impl ArtifactSigner {
+	/// The path of the signature of an artifact, as produced when signing it.
+	///
+	/// # Arguments
+	/// * `artifact` - The path of the artifact.
+	pub fn signature(&self, artifact: &Path) -> PathBuf {
+		let extension = match self {
+			ArtifactSigner::Minisign { .. } => "minisig",
+			ArtifactSigner::Sigstore => "sigstore.json",
+		};
+		append_extension(artifact, extension)
+	}
+
+	/// Signs an artifact, returning the path of its signature.
+	///
+	/// # Arguments
+	/// * `artifact` - The path of the artifact.
+	pub fn sign(&self, artifact: &Path) -> Result<PathBuf, Error> {
+		if !artifact.is_file() {
+			return Err(Error::Signing(format!("{} is not a file", artifact.display())));
+		}
+		let signature = self.signature(artifact);
+		match self {
+			ArtifactSigner::Minisign { secret_key } => run(
+				MINISIGN,
+				vec![
+					"-S".into(),
+					"-s".into(),
+					secret_key.into(),
+					"-m".into(),
+					artifact.into(),
+					"-x".into(),
+					(&signature).into(),
+				],
+			)?,
+			ArtifactSigner::Sigstore => run(
+				COSIGN,
+				vec![
+					"sign-blob".into(),
+					"--yes".into(),
+					"--bundle".into(),
+					(&signature).into(),
+					artifact.into(),
+				],
+			)?,
+		}
+		Ok(signature)
+	}
+}
+
+impl Display for ArtifactSigner {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ArtifactSigner::Minisign { secret_key } =>
+				write!(f, "minisign:{}", secret_key.display()),
+			ArtifactSigner::Sigstore => write!(f, "sigstore"),
+		}
+	}
+}
+
+impl FromStr for ArtifactSigner {
+	type Err = Error;
+
+	fn from_str(signer: &str) -> Result<Self, Self::Err> {
+		match signer.split_once(':') {
+			_ if signer == "sigstore" => Ok(ArtifactSigner::Sigstore),
+			Some(("minisign", key)) if !key.is_empty() =>
+				Ok(ArtifactSigner::Minisign { secret_key: PathBuf::from(key) }),
+			_ => Err(Error::Signing(format!(
+				"invalid signer `{signer}`: expected `minisign:<secret-key>` or `sigstore`"
+			))),
+		}
+	}
+}
+
+/// How the signature of an artifact is verified.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verifier {
+	/// Verify a minisign signature with the public key of the signer.
+	Minisign {
+		/// The path of the public key.
+		public_key: PathBuf,
+	},
+	/// Verify a sigstore bundle, checking the identity of the signer within its certificate.
+	Sigstore {
+		/// The identity of the signer (e.g. an email address, or the workflow of a release
+		/// pipeline).
+		identity: String,
+		/// The OIDC issuer which authenticated the signer (e.g.
+		/// `https://token.actions.githubusercontent.com`).
+		issuer: String,
+	},
+}
+
+impl Verifier {
+	/// Verifies the signature of an artifact, failing if the signature is invalid or was not
+	/// produced by the expected signer.
+	///
+	/// # Arguments
+	/// * `artifact` - The path of the artifact.
+	/// * `signature` - The path of the signature, otherwise that produced alongside the artifact
+	///   when signing it.
+	pub fn verify(&self, artifact: &Path, signature: Option<&Path>) -> Result<(), Error> {
+		let signature = match signature {
+			Some(signature) => signature.to_path_buf(),
+			None => self.signer().signature(artifact),
+		};
+		if !signature.is_file() {
+			return Err(Error::Signing(format!(
+				"no signature of {} found at {}",
+				artifact.display(),
+				signature.display()
+			)));
+		}
+		match self {
+			Verifier::Minisign { public_key } => run(
+				MINISIGN,
+				vec![
+					"-V".into(),
+					"-p".into(),
+					public_key.into(),
+					"-m".into(),
+					artifact.into(),
+					"-x".into(),
+					signature.into(),
+				],
+			),
+			Verifier::Sigstore { identity, issuer } => run(
+				COSIGN,
+				vec![
+					"verify-blob".into(),
+					"--bundle".into(),
+					signature.into(),
+					"--certificate-identity".into(),
+					identity.into(),
+					"--certificate-oidc-issuer".into(),
+					issuer.into(),
+					artifact.into(),
+				],
+			),
+		}
+	}
+
+	/// The signer whose signatures are verified.
+	fn signer(&self) -> ArtifactSigner {
+		match self {
+			Verifier::Minisign { .. } => ArtifactSigner::Minisign { secret_key: PathBuf::new() },
+			Verifier::Sigstore { .. } => ArtifactSigner::Sigstore,
+		}
+	}
+}
+
+/// Appends an extension to a path (e.g. `runtime.wasm` to `runtime.wasm.minisig`).
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+	let mut path = path.as_os_str().to_owned();
+	path.push(".");
+	path.push(extension);
+	PathBuf::from(path)
+}
+
+/// Runs a signing tool, failing with its output if it does not succeed.
+fn run(tool: &str, args: Vec<OsString>) -> Result<(), Error> {
+	let output =
+		cmd(tool, args)
+			.stdout_capture()
+			.stderr_capture()
+			.unchecked()
+			.run()
+			.map_err(|e| {
+				Error::Signing(format!(
+					"`{tool}` could not be run: {e}; check that it is installed"
+				))
+			})?;
+	if !output.status.success() {
+		return Err(Error::Signing(format!(
+			"`{tool}` failed: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		)));
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::write;
+
+	#[test]
+	fn parse_signer_works() -> Result<(), Error> {
+		assert_eq!("sigstore".parse::<ArtifactSigner>()?, ArtifactSigner::Sigstore);
+		let signer = "minisign:keys/release.key".parse::<ArtifactSigner>()?;
+		assert_eq!(signer, ArtifactSigner::Minisign { secret_key: "keys/release.key".into() });
+		assert_eq!(signer.to_string(), "minisign:keys/release.key");
+		for invalid in ["minisign", "minisign:", "gpg:key"] {
+			assert!(matches!(invalid.parse::<ArtifactSigner>(), Err(Error::Signing(_))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn signature_works() {
+		let artifact = Path::new("target/release/wbuild/runtime.compact.compressed.wasm");
+		assert_eq!(
+			ArtifactSigner::Sigstore.signature(artifact),
+			PathBuf::from("target/release/wbuild/runtime.compact.compressed.wasm.sigstore.json")
+		);
+		assert_eq!(
+			ArtifactSigner::Minisign { secret_key: "release.key".into() }
+				.signature(Path::new("node")),
+			PathBuf::from("node.minisig")
+		);
+	}
+
+	#[test]
+	fn verify_fails_without_signature() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let artifact = temp_dir.path().join("node");
+		write(&artifact, "binary")?;
+		let verifier = Verifier::Minisign { public_key: temp_dir.path().join("release.pub") };
+		assert!(matches!(
+			verifier.verify(&artifact, None),
+			Err(Error::Signing(e)) if e.starts_with("no signature of")
+		));
+		assert!(matches!(
+			ArtifactSigner::Sigstore.sign(&temp_dir.path().join("missing")),
+			Err(Error::Signing(e)) if e.ends_with("is not a file")
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use duct::cmd;
+use flate2::{write::GzEncoder, Compression};
+use std::{
+	fs::{create_dir_all, read, write, File},
+	path::{Component, Path, PathBuf},
+	time::{SystemTime, UNIX_EPOCH},
+};
+use tar::{Builder, Header};
+use url::Url;
+
+/// A destination to which generated artifacts are written.
+pub trait Sink {
+	/// Writes an artifact to the sink, returning the location it was written to.
+	///
+	/// # Arguments
+	/// * `name` - The name of the artifact, which may include a relative path.
+	/// * `contents` - The contents of the artifact.
+	fn write(&mut self, name: &str, contents: &[u8]) -> Result<String, Error>;
+
+	/// Writes an existing file to the sink, returning the location it was written to.
+	///
+	/// # Arguments
+	/// * `name` - The name of the artifact, which may include a relative path.
+	/// * `path` - The path of the file to be written.
+	fn write_file(&mut self, name: &str, path: &Path) -> Result<String, Error> {
+		self.write(name, &read(path)?)
+	}
+
+	/// Completes writing to the sink, flushing any buffered artifacts.
+	fn finish(&mut self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+/// Parses a sink from a destination: `s3://bucket/prefix` for S3 compatible storage, a path
+/// ending in `.tar.gz` for a tarball, or otherwise a directory on the local filesystem.
+///
+/// # Arguments
+/// * `destination` - The destination of the artifacts.
+pub fn parse_sink(destination: &str) -> Result<Box<dyn Sink>, Error> {
+	if destination.starts_with("s3://") {
+		let url = Url::parse(destination)?;
+		let bucket = url
+			.host_str()
+			.ok_or_else(|| Error::Config(format!("missing bucket in {destination}")))?;
+		return Ok(Box::new(S3::new(bucket, url.path().trim_matches('/'))));
+	}
+	if destination.ends_with(".tar.gz") || destination.ends_with(".tgz") {
+		return Ok(Box::new(Tarball::create(Path::new(destination))?));
+	}
+	Ok(Box::new(Filesystem::new(destination)))
+}
+
+/// Writes artifacts to a directory on the local filesystem.
+pub struct Filesystem {
+	/// The directory artifacts are written to.
+	dir: PathBuf,
+}
+
+impl Filesystem {
+	/// A sink which writes artifacts to the specified directory, creating it if necessary.
+	///
+	/// # Arguments
+	/// * `dir` - The directory artifacts are written to.
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		Self { dir: dir.into() }
+	}
+}
+
+impl Sink for Filesystem {
+	fn write(&mut self, name: &str, contents: &[u8]) -> Result<String, Error> {
+		let path = self.dir.join(validate(name)?);
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		write(&path, contents)?;
+		Ok(path.display().to_string())
+	}
+}
+
+/// Writes artifacts into a gzip compressed tarball.
+pub struct Tarball {
+	/// The path of the tarball.
+	path: PathBuf,
+	/// The archive being written, until finished.
+	builder: Option<Builder<GzEncoder<File>>>,
+}
+
+impl Tarball {
+	/// A sink which writes artifacts into a new tarball at the specified path.
+	///
+	/// # Arguments
+	/// * `path` - The path of the tarball to be created.
+	pub fn create(path: &Path) -> Result<Self, Error> {
+		if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+			create_dir_all(parent)?;
+		}
+		let file = File::create(path)?;
+		Ok(Self {
+			path: path.to_path_buf(),
+			builder: Some(Builder::new(GzEncoder::new(file, Compression::default()))),
+		})
+	}
+}
+
+impl Sink for Tarball {
+	fn write(&mut self, name: &str, contents: &[u8]) -> Result<String, Error> {
+		validate(name)?;
+		let builder = self
+			.builder
+			.as_mut()
+			.ok_or_else(|| Error::Config("the tarball has already been finished".into()))?;
+		let mut header = Header::new_gnu();
+		header.set_size(contents.len() as u64);
+		header.set_mode(0o644);
+		header.set_mtime(
+			SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|d| d.as_secs())
+				.unwrap_or_default(),
+		);
+		header.set_cksum();
+		builder.append_data(&mut header, name, contents)?;
+		Ok(format!("{}:{name}", self.path.display()))
+	}
+
+	fn finish(&mut self) -> Result<(), Error> {
+		if let Some(builder) = self.builder.take() {
+			builder.into_inner()?.finish()?;
+		}
+		Ok(())
+	}
+}
+
+impl Drop for Tarball {
+	fn drop(&mut self) {
+		let _ = self.finish();
+	}
+}
+
+/// Writes artifacts to S3 compatible storage, using the `aws` command line interface and its
+/// standard credential resolution.
+pub struct S3 {
+	/// The name of the bucket.
+	bucket: String,
+	/// The prefix applied to the name of each artifact.
+	prefix: String,
+	/// If applicable, the endpoint of an S3 compatible service.
+	endpoint: Option<Url>,
+}
+
+impl S3 {
+	/// A sink which writes artifacts to the specified bucket.
+	///
+	/// # Arguments
+	/// * `bucket` - The name of the bucket.
+	/// * `prefix` - The prefix applied to the name of each artifact.
+	pub fn new(bucket: &str, prefix: &str) -> Self {
+		Self { bucket: bucket.into(), prefix: prefix.into(), endpoint: None }
+	}
+
+	/// Specifies the endpoint of an S3 compatible service (e.g. MinIO, R2).
+	///
+	/// # Arguments
+	/// * `endpoint` - The endpoint of the service.
+	pub fn endpoint(mut self, endpoint: Url) -> Self {
+		self.endpoint = Some(endpoint);
+		self
+	}
+
+	/// The location of an artifact within the bucket.
+	///
+	/// # Arguments
+	/// * `name` - The name of the artifact.
+	fn location(&self, name: &str) -> String {
+		match self.prefix.is_empty() {
+			true => format!("s3://{}/{name}", self.bucket),
+			false => format!("s3://{}/{}/{name}", self.bucket, self.prefix),
+		}
+	}
+}
+
+impl Sink for S3 {
+	fn write(&mut self, name: &str, contents: &[u8]) -> Result<String, Error> {
+		validate(name)?;
+		let location = self.location(name);
+		let mut args = vec!["s3".to_string(), "cp".into(), "-".into(), location.clone()];
+		if let Some(endpoint) = self.endpoint.as_ref() {
+			args.push("--endpoint-url".into());
+			args.push(endpoint.to_string());
+		}
+		cmd("aws", args).stdin_bytes(contents).stdout_null().run().map_err(|e| {
+			Error::UnsupportedCommand(format!("unable to write {location} using `aws`: {e}"))
+		})?;
+		Ok(location)
+	}
+}
+
+/// Validates the name of an artifact, rejecting any name which is empty, absolute or refers to
+/// a parent directory, so that artifacts are never written outside of the sink.
+///
+/// # Arguments
+/// * `name` - The name of the artifact, which may include a relative path.
+fn validate(name: &str) -> Result<&Path, Error> {
+	let path = Path::new(name);
+	if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_)))
+	{
+		return Err(Error::Config(format!("invalid artifact name: {name}")));
+	}
+	Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use flate2::read::GzDecoder;
+	use std::{fs::read_to_string, io::Read};
+	use tar::Archive;
+	use tempfile::tempdir;
+
+	#[test]
+	fn filesystem_works() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let mut sink = Filesystem::new(temp_dir.path().join("artifacts"));
+		let location = sink.write("specs/chain-spec.json", b"{}")?;
+		sink.finish()?;
+		let path = temp_dir.path().join("artifacts/specs/chain-spec.json");
+		assert_eq!(location, path.display().to_string());
+		assert_eq!(read_to_string(path)?, "{}");
+		Ok(())
+	}
+
+	#[test]
+	fn writing_outside_sink_fails() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let mut filesystem = Filesystem::new(temp_dir.path().join("artifacts"));
+		let mut tarball = Tarball::create(&temp_dir.path().join("artifacts.tar.gz"))?;
+		let escape = temp_dir.path().join("escape").display().to_string();
+		for name in ["", "../escape", "specs/../../escape", escape.as_str()] {
+			assert!(matches!(filesystem.write(name, b"{}"), Err(Error::Config(..))));
+			assert!(matches!(tarball.write(name, b"{}"), Err(Error::Config(..))));
+			assert!(matches!(S3::new("releases", "").write(name, b"{}"), Err(Error::Config(..))));
+		}
+		assert!(!temp_dir.path().join("escape").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn tarball_works() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let path = temp_dir.path().join("artifacts.tar.gz");
+		let mut sink = Tarball::create(&path)?;
+		assert_eq!(
+			sink.write("para-2000.wasm", b"wasm")?,
+			format!("{}:para-2000.wasm", path.display())
+		);
+		sink.write("para-2000-genesis-state", b"state")?;
+		sink.finish()?;
+		assert!(sink.write("chain-spec.json", b"{}").is_err());
+
+		let mut archive = Archive::new(GzDecoder::new(File::open(&path)?));
+		let mut entries = vec![];
+		for entry in archive.entries()? {
+			let mut entry = entry?;
+			let mut contents = String::new();
+			entry.read_to_string(&mut contents)?;
+			entries.push((entry.path()?.display().to_string(), contents));
+		}
+		assert_eq!(
+			entries,
+			vec![
+				("para-2000.wasm".to_string(), "wasm".to_string()),
+				("para-2000-genesis-state".to_string(), "state".to_string())
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn s3_location_works() {
+		assert_eq!(S3::new("releases", "").location("para.wasm"), "s3://releases/para.wasm");
+		assert_eq!(
+			S3::new("releases", "v1.0.0").location("para.wasm"),
+			"s3://releases/v1.0.0/para.wasm"
+		);
+	}
+
+	#[test]
+	fn parse_sink_works() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let path = temp_dir.path().join("artifacts.tar.gz");
+		let mut sink = parse_sink(path.to_str().unwrap())?;
+		sink.write("chain-spec.json", b"{}")?;
+		sink.finish()?;
+		assert!(path.exists());
+
+		let dir = temp_dir.path().join("artifacts");
+		let mut sink = parse_sink(dir.to_str().unwrap())?;
+		sink.write("chain-spec.json", b"{}")?;
+		assert!(dir.join("chain-spec.json").exists());
+
+		assert!(parse_sink("s3://releases/v1.0.0").is_ok());
+		assert!(parse_sink("s3:///v1.0.0").is_err());
+		Ok(())
+	}
+}
@@ -3,7 +3,7 @@
 mod binary;
 pub use binary::*;
 
-use crate::{Git, Status, APP_USER_AGENT};
+use crate::{i18n::message, Git, Status, APP_USER_AGENT};
 use duct::cmd;
 use flate2::read::GzDecoder;
 use reqwest::StatusCode;
@@ -21,17 +21,17 @@ use url::Url;
 
 #[derive(Error, Debug)]
 pub enum Error {
-	#[error("Anyhow error: {0}")]
+	#[error("{}", message("error.sourcing.anyhow_error", &[("0", &.0.to_string())]))]
 	AnyhowError(#[from] anyhow::Error),
-	#[error("Archive error: {0}")]
+	#[error("{}", message("error.sourcing.archive_error", &[("0", .0.as_str())]))]
 	ArchiveError(String),
-	#[error("HTTP error: {0}")]
+	#[error("{}", message("error.sourcing.http_error", &[("0", &.0.to_string())]))]
 	HttpError(#[from] reqwest::Error),
-	#[error("IO error: {0}")]
+	#[error("{}", message("error.sourcing.io", &[("0", &.0.to_string())]))]
 	IO(#[from] std::io::Error),
-	#[error("Missing binary: {0}")]
+	#[error("{}", message("error.sourcing.missing_binary", &[("0", .0.as_str())]))]
 	MissingBinary(String),
-	#[error("ParseError error: {0}")]
+	#[error("{}", message("error.sourcing.parse_error", &[("0", &.0.to_string())]))]
 	ParseError(#[from] url::ParseError),
 }
 
@@ -244,13 +244,13 @@ async fn from_archive(
 	status: &impl Status,
 ) -> Result<(), Error> {
 	// Download archive
-	status.update(&format!("Downloading from {url}..."));
+	status.update(&message("sourcing.downloading", &[("url", url)]));
 	let response = reqwest::get(url).await?.error_for_status()?;
 	let mut file = tempfile()?;
 	file.write_all(&response.bytes().await?)?;
 	file.seek(SeekFrom::Start(0))?;
 	// Extract contents
-	status.update("Extracting from archive...");
+	status.update(&message("sourcing.extracting", &[]));
 	let tar = GzDecoder::new(file);
 	let mut archive = Archive::new(tar);
 	let temp_dir = tempdir()?;
@@ -271,7 +271,7 @@ async fn from_archive(
 			)));
 		}
 	}
-	status.update("Sourcing complete.");
+	status.update(&message("sourcing.complete", &[]));
 	Ok(())
 }
 
@@ -300,15 +300,15 @@ async fn from_git(
 	// Clone repository into working directory
 	let temp_dir = tempdir()?;
 	let working_dir = temp_dir.path();
-	status.update(&format!("Cloning {url}..."));
+	status.update(&message("sourcing.cloning", &[("url", url)]));
 	Git::clone(&Url::parse(url)?, working_dir, reference)?;
 	// Build binaries
-	status.update("Starting build of binary...");
+	status.update(&message("sourcing.building", &[]));
 	let manifest = manifest
 		.as_ref()
 		.map_or_else(|| working_dir.join("Cargo.toml"), |m| working_dir.join(m));
 	build(manifest, package, artifacts, release, status, verbose).await?;
-	status.update("Sourcing complete.");
+	status.update(&message("sourcing.complete", &[]));
 	Ok(())
 }
 
@@ -349,7 +349,7 @@ async fn from_github_archive(
 				];
 				let mut response = None;
 				for url in urls {
-					status.update(&format!("Downloading from {url}..."));
+					status.update(&message("sourcing.downloading", &[("url", &url)]));
 					response = Some(client.get(url).send().await?.error_for_status());
 					if let Some(Err(e)) = &response {
 						if e.status() == Some(StatusCode::NOT_FOUND) {
@@ -363,7 +363,7 @@ async fn from_github_archive(
 			},
 			None => {
 				let url = format!("https://api.github.com/repos/{owner}/{repository}/tarball");
-				status.update(&format!("Downloading from {url}..."));
+				status.update(&message("sourcing.downloading", &[("url", &url)]));
 				client.get(url).send().await?.error_for_status()?
 			},
 		};
@@ -371,7 +371,7 @@ async fn from_github_archive(
 	file.write_all(&response.bytes().await?)?;
 	file.seek(SeekFrom::Start(0))?;
 	// Extract contents
-	status.update("Extracting from archive...");
+	status.update(&message("sourcing.extracting", &[]));
 	let tar = GzDecoder::new(file);
 	let mut archive = Archive::new(tar);
 	let temp_dir = tempdir()?;
@@ -389,12 +389,12 @@ async fn from_github_archive(
 		                                        * top level directory */
 	}
 	// Build binaries
-	status.update("Starting build of binary...");
+	status.update(&message("sourcing.building", &[]));
 	let manifest = manifest
 		.as_ref()
 		.map_or_else(|| working_dir.join("Cargo.toml"), |m| working_dir.join(m));
 	build(&manifest, package, artifacts, release, status, verbose).await?;
-	status.update("Sourcing complete.");
+	status.update(&message("sourcing.complete", &[]));
 	Ok(())
 }
 
@@ -414,10 +414,10 @@ pub(crate) async fn from_local_package(
 	verbose: bool,
 ) -> Result<(), Error> {
 	// Build binaries
-	status.update("Starting build of binary...");
+	status.update(&message("sourcing.building", &[]));
 	const EMPTY: [(&str, PathBuf); 0] = [];
 	build(manifest, package, &EMPTY, release, status, verbose).await?;
-	status.update("Sourcing complete.");
+	status.update(&message("sourcing.complete", &[]));
 	Ok(())
 }
 
@@ -429,9 +429,9 @@ pub(crate) async fn from_local_package(
 /// * `status` - Used to observe status updates.
 async fn from_url(url: &str, path: &Path, status: &impl Status) -> Result<(), Error> {
 	// Download required version of binaries
-	status.update(&format!("Downloading from {url}..."));
+	status.update(&message("sourcing.downloading", &[("url", url)]));
 	download(url, path).await?;
-	status.update("Sourcing complete.");
+	status.update(&message("sourcing.complete", &[]));
 	Ok(())
 }
 
@@ -0,0 +1,432 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+	fs::{metadata, read_to_string, rename, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+	time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The name of the file within which operations are recorded, hidden so that it is not listed
+/// as a cached artifact.
+pub const STATS_FILE: &str = ".stats.jsonl";
+/// The name of the file to which records are rotated once the size of the stats file reaches its
+/// limit, replacing any records rotated previously.
+pub const ROTATED_STATS_FILE: &str = ".stats.1.jsonl";
+/// The size of the stats file at which its records are rotated, in bytes.
+const MAX_SIZE: u64 = 1024 * 1024;
+
+/// The characters used to render trendlines, from lowest to highest.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A record of an operation performed, such as a build, download or launch.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Record {
+	/// The operation performed (e.g. `build`).
+	pub operation: String,
+	/// The subject of the operation, if any (e.g. the name of a downloaded binary).
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub subject: Option<String>,
+	/// The project the operation was performed for, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub project: Option<PathBuf>,
+	/// When the operation started, in seconds since the Unix epoch.
+	pub timestamp: u64,
+	/// How long the operation took, in milliseconds.
+	pub duration_ms: u64,
+	/// Whether the operation succeeded.
+	pub success: bool,
+	/// Whether the operation benefited from a cache (e.g. an incremental build).
+	#[serde(default)]
+	pub cached: bool,
+}
+
+/// Times an operation, producing a record once finished.
+#[derive(Clone, Debug)]
+pub struct Timer {
+	record: Record,
+	started: Instant,
+}
+
+impl Timer {
+	/// Starts timing an operation.
+	///
+	/// # Arguments
+	/// * `operation` - The operation being performed (e.g. `build`).
+	pub fn start(operation: &str) -> Self {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_secs())
+			.unwrap_or_default();
+		Self {
+			record: Record {
+				operation: operation.to_string(),
+				subject: None,
+				project: None,
+				timestamp,
+				duration_ms: 0,
+				success: false,
+				cached: false,
+			},
+			started: Instant::now(),
+		}
+	}
+
+	/// Sets the subject of the operation.
+	///
+	/// # Arguments
+	/// * `subject` - The subject of the operation (e.g. the name of a binary).
+	pub fn subject(mut self, subject: impl Into<String>) -> Self {
+		self.record.subject = Some(subject.into());
+		self
+	}
+
+	/// Sets the project the operation is performed for, resolved to an absolute path so that
+	/// records can be attributed regardless of the working directory.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn project(mut self, project: &Path) -> Self {
+		self.record.project =
+			Some(project.canonicalize().unwrap_or_else(|_| project.to_path_buf()));
+		self
+	}
+
+	/// Sets whether the operation benefited from a cache.
+	///
+	/// # Arguments
+	/// * `cached` - Whether a cache was used.
+	pub fn cached(mut self, cached: bool) -> Self {
+		self.record.cached = cached;
+		self
+	}
+
+	/// Finishes timing the operation.
+	///
+	/// # Arguments
+	/// * `success` - Whether the operation succeeded.
+	pub fn finish(mut self, success: bool) -> Record {
+		self.record.duration_ms = self.started.elapsed().as_millis() as u64;
+		self.record.success = success;
+		self.record
+	}
+}
+
+/// A filter for querying records.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+	/// Only include records of the specified operation.
+	pub operation: Option<String>,
+	/// Only include records for the specified project.
+	pub project: Option<PathBuf>,
+	/// Only include records of operations started at or after the specified time, in seconds
+	/// since the Unix epoch.
+	pub since: Option<u64>,
+}
+
+impl Filter {
+	/// Whether a record matches the filter.
+	///
+	/// # Arguments
+	/// * `record` - The record.
+	pub fn matches(&self, record: &Record) -> bool {
+		self.operation.as_ref().is_none_or(|o| *o == record.operation) &&
+			self.project.as_ref().is_none_or(|p| {
+				let project = p.canonicalize().unwrap_or_else(|_| p.clone());
+				record.project.as_ref() == Some(&project)
+			}) && self.since.is_none_or(|since| record.timestamp >= since)
+	}
+}
+
+/// A summary of the durations of an operation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+	/// The operation.
+	pub operation: String,
+	/// The number of times the operation was performed.
+	pub count: usize,
+	/// The number of times the operation failed.
+	pub failures: usize,
+	/// The mean duration of successful operations, in milliseconds.
+	pub mean_ms: u64,
+	/// The median duration of successful operations, in milliseconds.
+	pub median_ms: u64,
+	/// The shortest duration of successful operations, in milliseconds.
+	pub min_ms: u64,
+	/// The longest duration of successful operations, in milliseconds.
+	pub max_ms: u64,
+	/// The mean duration of successful operations which benefited from a cache, if any.
+	pub cached_mean_ms: Option<u64>,
+	/// The mean duration of successful operations which did not benefit from a cache, if any.
+	pub uncached_mean_ms: Option<u64>,
+}
+
+/// The durations of operations, recorded locally. Nothing is ever reported over the network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+	path: PathBuf,
+	rotated: PathBuf,
+	max_size: u64,
+}
+
+impl Stats {
+	/// Opens the records stored within a directory.
+	///
+	/// # Arguments
+	/// * `dir` - The directory within which records are stored (e.g. the cache).
+	pub fn new(dir: &Path) -> Self {
+		Self {
+			path: dir.join(STATS_FILE),
+			rotated: dir.join(ROTATED_STATS_FILE),
+			max_size: MAX_SIZE,
+		}
+	}
+
+	/// Sets the size of the stats file at which its records are rotated, so that at most twice
+	/// the size is retained.
+	///
+	/// # Arguments
+	/// * `max_size` - The size, in bytes.
+	pub fn with_max_size(mut self, max_size: u64) -> Self {
+		self.max_size = max_size;
+		self
+	}
+
+	/// Appends a record, first rotating the records once the stats file reaches its size limit.
+	///
+	/// # Arguments
+	/// * `record` - The record.
+	pub fn record(&self, record: &Record) -> Result<(), Error> {
+		if metadata(&self.path).is_ok_and(|m| m.len() >= self.max_size) {
+			rename(&self.path, &self.rotated)?;
+		}
+		let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+		let line = serde_json::to_string(record).map_err(|e| Error::Config(e.to_string()))?;
+		writeln!(file, "{line}")?;
+		Ok(())
+	}
+
+	/// The records matching a filter, in the order recorded, including those rotated. Malformed
+	/// records are skipped.
+	///
+	/// # Arguments
+	/// * `filter` - The filter.
+	pub fn records(&self, filter: &Filter) -> Result<Vec<Record>, Error> {
+		let mut records = vec![];
+		for path in [&self.rotated, &self.path] {
+			if !path.exists() {
+				continue;
+			}
+			records.extend(
+				read_to_string(path)?
+					.lines()
+					.filter_map(|line| serde_json::from_str::<Record>(line).ok())
+					.filter(|record| filter.matches(record)),
+			);
+		}
+		Ok(records)
+	}
+
+	/// Summarizes the durations of each operation matching a filter, ordered by operation.
+	///
+	/// # Arguments
+	/// * `filter` - The filter.
+	pub fn query(&self, filter: &Filter) -> Result<Vec<Summary>, Error> {
+		let records = self.records(filter)?;
+		let mut operations: Vec<&str> = records.iter().map(|r| r.operation.as_str()).collect();
+		operations.sort();
+		operations.dedup();
+		Ok(operations
+			.into_iter()
+			.map(|operation| {
+				let records: Vec<_> = records.iter().filter(|r| r.operation == operation).collect();
+				let mut durations: Vec<u64> =
+					records.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+				durations.sort();
+				let cached = |cached: bool| {
+					mean(
+						records
+							.iter()
+							.filter(|r| r.success && r.cached == cached)
+							.map(|r| r.duration_ms),
+					)
+				};
+				Summary {
+					operation: operation.to_string(),
+					count: records.len(),
+					failures: records.iter().filter(|r| !r.success).count(),
+					mean_ms: mean(durations.iter().copied()).unwrap_or_default(),
+					median_ms: durations.get(durations.len() / 2).copied().unwrap_or_default(),
+					min_ms: durations.first().copied().unwrap_or_default(),
+					max_ms: durations.last().copied().unwrap_or_default(),
+					cached_mean_ms: cached(true),
+					uncached_mean_ms: cached(false),
+				}
+			})
+			.collect())
+	}
+
+	/// The durations of the most recent successful runs of an operation for a project, oldest
+	/// first, showing how its duration is trending.
+	///
+	/// # Arguments
+	/// * `project` - The project.
+	/// * `operation` - The operation.
+	/// * `limit` - The maximum number of runs.
+	pub fn trend(&self, project: &Path, operation: &str, limit: usize) -> Result<Vec<u64>, Error> {
+		let filter = Filter {
+			operation: Some(operation.to_string()),
+			project: Some(project.to_path_buf()),
+			since: None,
+		};
+		let durations: Vec<_> = self
+			.records(&filter)?
+			.into_iter()
+			.filter(|r| r.success)
+			.map(|r| r.duration_ms)
+			.collect();
+		Ok(durations[durations.len().saturating_sub(limit)..].to_vec())
+	}
+}
+
+/// Renders durations as a trendline (e.g. `▁▃█▅`), scaled between the shortest and longest.
+///
+/// # Arguments
+/// * `durations` - The durations.
+pub fn sparkline(durations: &[u64]) -> String {
+	let (Some(min), Some(max)) = (durations.iter().min(), durations.iter().max()) else {
+		return String::new();
+	};
+	let range = (max - min).max(1);
+	durations
+		.iter()
+		.map(|d| BARS[((d - min) * (BARS.len() as u64 - 1) / range) as usize])
+		.collect()
+}
+
+/// The mean of the specified values, if any.
+fn mean(values: impl Iterator<Item = u64>) -> Option<u64> {
+	let (count, total) = values.fold((0u64, 0u64), |(count, total), v| (count + 1, total + v));
+	(count > 0).then(|| total / count)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	fn record(operation: &str, project: &Path, duration_ms: u64, cached: bool) -> Record {
+		Record {
+			operation: operation.to_string(),
+			subject: None,
+			project: Some(project.canonicalize().unwrap()),
+			timestamp: 1_700_000_000 + duration_ms,
+			duration_ms,
+			success: true,
+			cached,
+		}
+	}
+
+	#[test]
+	fn timer_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let record = Timer::start("download")
+			.subject("polkadot")
+			.project(temp_dir.path())
+			.cached(true)
+			.finish(false);
+		assert_eq!(record.operation, "download");
+		assert_eq!(record.subject.as_deref(), Some("polkadot"));
+		assert_eq!(record.project, Some(temp_dir.path().canonicalize()?));
+		assert!(record.cached && !record.success && record.timestamp > 0);
+		Ok(())
+	}
+
+	#[test]
+	fn record_and_query_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let (project, other) = (temp_dir.path().join("project"), temp_dir.path().join("other"));
+		std::fs::create_dir_all(&project)?;
+		std::fs::create_dir_all(&other)?;
+		let stats = Stats::new(temp_dir.path());
+		assert!(stats.query(&Filter::default())?.is_empty());
+
+		for record in [
+			record("build", &project, 60_000, false),
+			record("build", &project, 10_000, true),
+			record("build", &project, 20_000, true),
+			Record { success: false, ..record("build", &project, 5_000, true) },
+			record("build", &other, 90_000, false),
+			record("launch", &project, 3_000, false),
+		] {
+			stats.record(&record)?;
+		}
+		let summaries =
+			stats.query(&Filter { project: Some(project.clone()), ..Default::default() })?;
+		assert_eq!(summaries.len(), 2);
+		assert_eq!(
+			summaries[0],
+			Summary {
+				operation: "build".to_string(),
+				count: 4,
+				failures: 1,
+				mean_ms: 30_000,
+				median_ms: 20_000,
+				min_ms: 10_000,
+				max_ms: 60_000,
+				cached_mean_ms: Some(15_000),
+				uncached_mean_ms: Some(60_000),
+			}
+		);
+		assert_eq!((summaries[1].operation.as_str(), summaries[1].count), ("launch", 1));
+
+		let filter = Filter {
+			operation: Some("build".to_string()),
+			since: Some(1_700_060_000),
+			..Default::default()
+		};
+		assert_eq!(stats.records(&filter)?.len(), 2);
+		assert_eq!(stats.trend(&project, "build", 2)?, vec![10_000, 20_000]);
+		Ok(())
+	}
+
+	#[test]
+	fn records_skips_malformed_records() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		write(
+			temp_dir.path().join(STATS_FILE),
+			"{\"operation\":\"launch\",\"timestamp\":1,\"duration_ms\":2,\"success\":true}\nnot json\n",
+		)?;
+		let records = Stats::new(temp_dir.path()).records(&Filter::default())?;
+		assert_eq!(records.len(), 1);
+		assert_eq!((records[0].project.as_ref(), records[0].cached), (None, false));
+		Ok(())
+	}
+
+	#[test]
+	fn record_rotates_records() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("project");
+		std::fs::create_dir_all(&project)?;
+		let stats = Stats::new(temp_dir.path()).with_max_size(1);
+		for duration in [1_000, 2_000, 3_000] {
+			stats.record(&record("build", &project, duration, false))?;
+		}
+		// Only the most recent records are retained, in the order recorded.
+		let durations: Vec<_> =
+			stats.records(&Filter::default())?.iter().map(|r| r.duration_ms).collect();
+		assert_eq!(durations, [2_000, 3_000]);
+		assert!(temp_dir.path().join(ROTATED_STATS_FILE).exists());
+		Ok(())
+	}
+
+	#[test]
+	fn sparkline_works() {
+		assert_eq!(sparkline(&[]), "");
+		assert_eq!(sparkline(&[5, 5]), "▁▁");
+		assert_eq!(sparkline(&[10, 80, 45, 10]), "▁█▄▁");
+	}
+}
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use crate::i18n::message;
 use strum::{EnumMessage, EnumProperty, VariantArray};
 pub use thiserror::Error;
 
@@ -7,10 +8,10 @@ pub mod extractor;
 
 #[derive(Error, Debug)]
 pub enum Error {
-	#[error("The `Repository` property is missing from the template variant")]
+	#[error("{}", message("error.templates.repository_missing", &[]))]
 	RepositoryMissing,
 
-	#[error("The `TypeMissing` property is missing from the template variant")]
+	#[error("{}", message("error.templates.type_missing", &[]))]
 	TypeMissing,
 }
 
@@ -0,0 +1,373 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use duct::cmd;
+use serde::Deserialize;
+use std::{
+	fmt::{Display, Formatter},
+	fs::{read_to_string, write},
+	path::{Path, PathBuf},
+};
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+/// The file within which the toolchain of a project is pinned.
+pub const TOOLCHAIN_FILE: &str = "rust-toolchain.toml";
+/// The channel pinned for scaffolded projects, unless specified.
+pub const DEFAULT_CHANNEL: &str = "stable";
+
+/// The channels which move to a new release over time, rather than pinning a specific release.
+const MOVING_CHANNELS: [&str; 3] = ["stable", "beta", "nightly"];
+
+/// The toolchain pinned by a project, as managed by `rustup`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Toolchain {
+	/// The channel of the toolchain (e.g. `stable`, `nightly-2024-09-01` or `1.81.0`), unless a
+	/// custom toolchain is specified by its `path`.
+	#[serde(default)]
+	pub channel: String,
+	/// The path of a custom toolchain, which is not managed by `rustup`.
+	#[serde(default)]
+	pub path: Option<PathBuf>,
+	/// The components required (e.g. `rust-src`).
+	#[serde(default)]
+	pub components: Vec<String>,
+	/// The targets required (e.g. `wasm32-unknown-unknown`).
+	#[serde(default)]
+	pub targets: Vec<String>,
+}
+
+/// The contents of the toolchain file relevant to pop.
+#[derive(Deserialize)]
+struct ToolchainFile {
+	toolchain: Toolchain,
+}
+
+/// The parts of a pinned toolchain which are not installed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MissingToolchain {
+	/// The channel of the toolchain, if the toolchain itself is not installed.
+	pub toolchain: Option<String>,
+	/// The components which are not installed.
+	pub components: Vec<String>,
+	/// The targets which are not installed.
+	pub targets: Vec<String>,
+}
+
+impl MissingToolchain {
+	/// Whether the toolchain is fully installed.
+	pub fn is_empty(&self) -> bool {
+		self.toolchain.is_none() && self.components.is_empty() && self.targets.is_empty()
+	}
+}
+
+impl Display for MissingToolchain {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let missing: Vec<_> = self
+			.toolchain
+			.iter()
+			.map(|channel| format!("the `{channel}` toolchain"))
+			.chain(self.components.iter().map(|c| format!("the `{c}` component")))
+			.chain(self.targets.iter().map(|t| format!("the `{t}` target")))
+			.collect();
+		write!(f, "{}", missing.join(", "))
+	}
+}
+
+impl Toolchain {
+	/// The toolchain required to build parachains: a channel, alongside the `rust-src` component
+	/// and the target to which runtimes are compiled.
+	///
+	/// # Arguments
+	/// * `channel` - The channel of the toolchain.
+	pub fn parachain(channel: &str) -> Self {
+		Self {
+			channel: channel.to_string(),
+			path: None,
+			components: vec!["rust-src".into()],
+			targets: vec!["wasm32-unknown-unknown".into()],
+		}
+	}
+
+	/// Loads the toolchain pinned within the `rust-toolchain.toml` file of a project, if any.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn load(project: &Path) -> Result<Option<Self>, Error> {
+		let path = project.join(TOOLCHAIN_FILE);
+		if !path.exists() {
+			return Ok(None);
+		}
+		let file: ToolchainFile =
+			toml_edit::de::from_str(&read_to_string(&path)?).map_err(|e| {
+				Error::Toolchain(format!("invalid toolchain in {}: {e}", path.display()))
+			})?;
+		let toolchain = file.toolchain;
+		if toolchain.channel.is_empty() == toolchain.path.is_none() {
+			return Err(Error::Toolchain(format!(
+				"invalid toolchain in {}: either a `channel` or a `path` must be specified",
+				path.display()
+			)));
+		}
+		Ok(Some(toolchain))
+	}
+
+	/// Whether the channel moves to a new release over time (e.g. `stable` or `1.81`), so that
+	/// builds are not reproducible.
+	pub fn is_moving(&self) -> bool {
+		if self.path.is_some() {
+			return false;
+		}
+		let channel = self.channel.as_str();
+		let number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+		// A version without a patch number (e.g. `1.81`) moves to the latest patch release.
+		let minor = match channel.split('.').collect::<Vec<_>>()[..] {
+			[major, minor] => number(major) && number(minor),
+			_ => false,
+		};
+		minor || MOVING_CHANNELS.iter().any(|moving| lists(channel, moving))
+	}
+
+	/// Pins the toolchain within the `rust-toolchain.toml` file of a project, preserving any
+	/// other settings of the file.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn save(&self, project: &Path) -> Result<PathBuf, Error> {
+		let path = project.join(TOOLCHAIN_FILE);
+		let mut doc = match path.exists() {
+			true => read_to_string(&path)?
+				.parse::<DocumentMut>()
+				.map_err(|e| Error::Toolchain(format!("invalid {}: {e}", path.display())))?,
+			false => DocumentMut::new(),
+		};
+		let toolchain = doc
+			.entry("toolchain")
+			.or_insert(Item::Table(Table::new()))
+			.as_table_mut()
+			.ok_or_else(|| Error::Toolchain(format!("expected a table in {}", path.display())))?;
+		match &self.path {
+			Some(custom) => {
+				toolchain.remove("channel");
+				toolchain["path"] = value(custom.display().to_string());
+			},
+			None => {
+				toolchain.remove("path");
+				toolchain["channel"] = value(&self.channel);
+			},
+		}
+		toolchain["components"] = value(self.components.iter().collect::<Array>());
+		toolchain["targets"] = value(self.targets.iter().collect::<Array>());
+		write(&path, doc.to_string())?;
+		Ok(path)
+	}
+
+	/// Determines which parts of the toolchain are not installed. Toolchains not managed by
+	/// `rustup`, including custom toolchains, are assumed to be installed.
+	pub fn missing(&self) -> MissingToolchain {
+		if self.path.is_some() {
+			return MissingToolchain::default();
+		}
+		let Ok(toolchains) = rustup(&["toolchain", "list"]) else {
+			return MissingToolchain::default();
+		};
+		if !toolchains.lines().any(|t| lists(t, &self.channel)) {
+			return MissingToolchain {
+				toolchain: Some(self.channel.clone()),
+				components: self.components.clone(),
+				targets: self.targets.clone(),
+			};
+		}
+		let channel = self.channel.as_str();
+		let components = rustup(&["component", "list", "--installed", "--toolchain", channel])
+			.unwrap_or_default();
+		let targets =
+			rustup(&["target", "list", "--installed", "--toolchain", channel]).unwrap_or_default();
+		MissingToolchain {
+			toolchain: None,
+			components: self
+				.components
+				.iter()
+				.filter(|c| !components.lines().any(|line| lists(line, c)))
+				.cloned()
+				.collect(),
+			targets: self
+				.targets
+				.iter()
+				.filter(|t| !targets.lines().any(|line| line.trim() == t.as_str()))
+				.cloned()
+				.collect(),
+		}
+	}
+
+	/// Installs the parts of the toolchain which are missing via `rustup`.
+	///
+	/// # Arguments
+	/// * `missing` - The parts of the toolchain which are missing.
+	pub fn install(&self, missing: &MissingToolchain) -> Result<(), Error> {
+		let channel = self.channel.as_str();
+		if let Some(channel) = &missing.toolchain {
+			let mut args = vec!["toolchain", "install", channel, "--profile", "minimal"];
+			for component in &missing.components {
+				args.extend(["--component", component]);
+			}
+			for target in &missing.targets {
+				args.extend(["--target", target]);
+			}
+			return run_rustup(&args);
+		}
+		if !missing.components.is_empty() {
+			let mut args = vec!["component", "add", "--toolchain", channel];
+			args.extend(missing.components.iter().map(String::as_str));
+			run_rustup(&args)?;
+		}
+		if !missing.targets.is_empty() {
+			let mut args = vec!["target", "add", "--toolchain", channel];
+			args.extend(missing.targets.iter().map(String::as_str));
+			run_rustup(&args)?;
+		}
+		Ok(())
+	}
+
+	/// Verifies that the toolchain is installed, installing whatever is missing. Returns what was
+	/// installed.
+	pub fn ensure(&self) -> Result<MissingToolchain, Error> {
+		let missing = self.missing();
+		if missing.is_empty() {
+			return Ok(missing);
+		}
+		self.install(&missing)?;
+		let remaining = self.missing();
+		if !remaining.is_empty() {
+			return Err(Error::Toolchain(format!("{remaining} could not be installed")));
+		}
+		Ok(missing)
+	}
+}
+
+/// Whether a line listed by `rustup` names the toolchain or component, which are listed with the
+/// host triple appended (e.g. `stable-x86_64-unknown-linux-gnu (default)`).
+fn lists(line: &str, name: &str) -> bool {
+	let line = line.split_whitespace().next().unwrap_or_default();
+	line == name ||
+		line.strip_prefix(name)
+			.and_then(|host| host.strip_prefix('-'))
+			.is_some_and(is_triple)
+}
+
+/// Whether a suffix is a target triple (e.g. `aarch64-apple-darwin`), rather than the remainder of
+/// a longer name (e.g. the `src` of `rust-src`, or the date of a dated nightly).
+fn is_triple(suffix: &str) -> bool {
+	let mut parts = suffix.split('-');
+	let arch = parts.next().unwrap_or_default();
+	!arch.is_empty() && !arch.chars().all(|c| c.is_ascii_digit()) && parts.count() >= 2
+}
+
+/// Runs `rustup`, returning its output.
+fn rustup(args: &[&str]) -> Result<String, Error> {
+	Ok(cmd("rustup", args).stderr_null().read()?)
+}
+
+/// Runs `rustup`, failing if it does not succeed.
+fn run_rustup(args: &[&str]) -> Result<(), Error> {
+	cmd("rustup", args)
+		.stdout_null()
+		.stderr_capture()
+		.run()
+		.map(|_| ())
+		.map_err(|e| Error::Toolchain(format!("`rustup {}` failed: {e}", args.join(" "))))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn save_and_load_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path();
+		assert_eq!(Toolchain::load(project)?, None);
+		write(
+			project.join(TOOLCHAIN_FILE),
+			"[toolchain]\nchannel = \"1.81.0\"\nprofile = \"minimal\"\n",
+		)?;
+		assert_eq!(
+			Toolchain::load(project)?,
+			Some(Toolchain { channel: "1.81.0".into(), ..Default::default() })
+		);
+
+		let toolchain = Toolchain::parachain("nightly-2024-09-01");
+		toolchain.save(project)?;
+		assert_eq!(Toolchain::load(project)?, Some(toolchain));
+		let contents = read_to_string(project.join(TOOLCHAIN_FILE))?;
+		assert!(contents.contains("profile = \"minimal\""));
+
+		write(project.join(TOOLCHAIN_FILE), "[toolchain]\ncomponents = []\n")?;
+		assert!(matches!(Toolchain::load(project), Err(Error::Toolchain(_))));
+		write(
+			project.join(TOOLCHAIN_FILE),
+			"[toolchain]\nchannel = \"stable\"\npath = \"/opt/rust\"\n",
+		)?;
+		assert!(matches!(Toolchain::load(project), Err(Error::Toolchain(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn custom_toolchain_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path();
+		write(project.join(TOOLCHAIN_FILE), "[toolchain]\npath = \"/opt/rust\"\n")?;
+		let toolchain = Toolchain::load(project)?.unwrap();
+		assert_eq!(
+			toolchain,
+			Toolchain { path: Some(PathBuf::from("/opt/rust")), ..Default::default() }
+		);
+		assert!(toolchain.missing().is_empty());
+		assert!(!toolchain.is_moving());
+
+		Toolchain::parachain("1.81.0").save(project)?;
+		let contents = read_to_string(project.join(TOOLCHAIN_FILE))?;
+		assert!(!contents.contains("path"));
+		toolchain.save(project)?;
+		assert_eq!(Toolchain::load(project)?, Some(toolchain));
+		Ok(())
+	}
+
+	#[test]
+	fn is_moving_works() {
+		for channel in ["stable", "beta", "nightly", "stable-x86_64-unknown-linux-gnu", "1.81"] {
+			assert!(Toolchain::parachain(channel).is_moving(), "{channel}");
+		}
+		for channel in
+			["1.81.0", "nightly-2024-09-01", "beta-2024-09-01", "1.81.0-aarch64-apple-darwin"]
+		{
+			assert!(!Toolchain::parachain(channel).is_moving(), "{channel}");
+		}
+	}
+
+	#[test]
+	fn lists_works() {
+		assert!(lists("stable-x86_64-unknown-linux-gnu (default)", "stable"));
+		assert!(lists("nightly-2024-09-01-aarch64-apple-darwin", "nightly-2024-09-01"));
+		assert!(lists("rust-src", "rust-src"));
+		assert!(lists("rustfmt-x86_64-unknown-linux-gnu", "rustfmt"));
+		assert!(!lists("rust-src", "rust"));
+		assert!(!lists("nightly-x86_64-unknown-linux-gnu", "stable"));
+		assert!(!lists("stable-x86_64-unknown-linux-gnu", "1.81.0"));
+		assert!(!lists("nightly-2024-09-01-x86_64-unknown-linux-gnu", "nightly"));
+	}
+
+	#[test]
+	fn missing_toolchain_display_works() {
+		let missing = MissingToolchain {
+			toolchain: None,
+			components: vec!["rust-src".into()],
+			targets: vec!["wasm32-unknown-unknown".into()],
+		};
+		assert!(!missing.is_empty());
+		assert_eq!(
+			missing.to_string(),
+			"the `rust-src` component, the `wasm32-unknown-unknown` target"
+		);
+		assert!(MissingToolchain::default().is_empty());
+	}
+}
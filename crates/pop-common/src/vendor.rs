@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use duct::cmd;
+use std::{
+	ffi::OsStr,
+	fs::{create_dir_all, read_to_string, write},
+	path::{Path, PathBuf},
+};
+use toml_edit::{value, DocumentMut, Item, Table};
+
+/// The cargo configuration file of a project, relative to the project directory.
+pub const CARGO_CONFIG: &str = ".cargo/config.toml";
+/// The directory within which crates are vendored, unless specified.
+pub const DEFAULT_VENDOR_DIR: &str = "vendor";
+/// The name of the source replacing crates.io with a registry mirror.
+const MIRROR_SOURCE: &str = "mirror";
+
+/// Where the crates of a project are sourced from, for builds without access to crates.io.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CrateSource {
+	/// The crates are vendored within a directory of the project via `cargo vendor`, including
+	/// git dependencies.
+	Vendored(PathBuf),
+	/// The crates are sourced from a mirror of crates.io, specified as the URL of its index (e.g.
+	/// `sparse+https://crates.example.com/index/`).
+	Mirror(String),
+}
+
+impl CrateSource {
+	/// Configures cargo to source the crates of a project accordingly, within the
+	/// `.cargo/config.toml` file of the project and preserving any other settings of the file.
+	/// Vendoring fetches the crates, so requires access to their sources.
+	///
+	/// # Arguments
+	/// * `project` - The project directory.
+	pub fn configure(&self, project: &Path) -> Result<PathBuf, Error> {
+		let sources = match self {
+			CrateSource::Vendored(directory) => vendor(project, directory)?,
+			CrateSource::Mirror(registry) => mirror(registry),
+		};
+		let path = project.join(CARGO_CONFIG);
+		let mut config = match path.exists() {
+			true => read_to_string(&path)?
+				.parse::<DocumentMut>()
+				.map_err(|e| Error::Config(format!("invalid {}: {e}", path.display())))?,
+			false => DocumentMut::new(),
+		};
+		merge_sources(&mut config, sources)
+			.map_err(|e| Error::Config(format!("{e} in {}", path.display())))?;
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		write(&path, config.to_string())?;
+		Ok(path)
+	}
+}
+
+/// Vendors the crates of a project via `cargo vendor`, returning the source replacements it
+/// reports.
+///
+/// # Arguments
+/// * `project` - The project directory.
+/// * `directory` - The directory within which the crates are vendored, relative to the project.
+fn vendor(project: &Path, directory: &Path) -> Result<Table, Error> {
+	let args = [OsStr::new("vendor"), OsStr::new("--versioned-dirs"), directory.as_os_str()];
+	let output = cmd("cargo", args)
+		.dir(project)
+		.stdout_capture()
+		.stderr_capture()
+		.unchecked()
+		.run()?;
+	if !output.status.success() {
+		return Err(Error::Vendor(format!(
+			"`cargo vendor` failed: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		)));
+	}
+	sources(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The source replacements of crates.io by a registry mirror.
+///
+/// # Arguments
+/// * `registry` - The URL of the index of the mirror.
+fn mirror(registry: &str) -> Table {
+	let mut crates_io = Table::new();
+	crates_io["replace-with"] = value(MIRROR_SOURCE);
+	let mut mirror = Table::new();
+	mirror["registry"] = value(registry);
+	let mut sources = Table::new();
+	sources.insert("crates-io", Item::Table(crates_io));
+	sources.insert(MIRROR_SOURCE, Item::Table(mirror));
+	sources
+}
+
+/// Parses the `[source]` tables of a cargo configuration, as printed by `cargo vendor`.
+fn sources(config: &str) -> Result<Table, Error> {
+	let config = config.parse::<DocumentMut>().map_err(|e| {
+		Error::Vendor(format!("invalid configuration reported by `cargo vendor`: {e}"))
+	})?;
+	match config.get("source").and_then(Item::as_table) {
+		Some(sources) => Ok(sources.clone()),
+		None => Err(Error::Vendor("`cargo vendor` did not report any sources".into())),
+	}
+}
+
+/// Merges source replacements into a cargo configuration, replacing any existing replacement of
+/// the same sources.
+fn merge_sources(config: &mut DocumentMut, sources: Table) -> Result<(), String> {
+	let existing = config
+		.entry("source")
+		.or_insert(Item::Table(Table::new()))
+		.as_table_mut()
+		.ok_or("expected `source` to be a table")?;
+	existing.set_implicit(true);
+	for (name, source) in sources {
+		existing.insert(&name, source);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn configure_mirror_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path();
+		create_dir_all(project.join(".cargo"))?;
+		write(project.join(CARGO_CONFIG), "[net]\ngit-fetch-with-cli = true\n")?;
+		let registry = "sparse+https://crates.example.com/index/";
+		let path = CrateSource::Mirror(registry.into()).configure(project)?;
+		assert_eq!(path, project.join(CARGO_CONFIG));
+		assert_eq!(
+			read_to_string(&path)?,
+			format!(
+				"[net]\ngit-fetch-with-cli = true\n\n[source.crates-io]\nreplace-with = \"mirror\"\n\n[source.mirror]\nregistry = \"{registry}\"\n"
+			)
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn sources_works() -> Result<(), Error> {
+		let reported = r#"
+[source.crates-io]
+replace-with = "vendored-sources"
+
+[source."git+https://github.com/paritytech/polkadot-sdk?branch=master"]
+git = "https://github.com/paritytech/polkadot-sdk"
+branch = "master"
+replace-with = "vendored-sources"
+
+[source.vendored-sources]
+directory = "vendor"
+"#;
+		let vendored = sources(reported)?;
+		assert_eq!(vendored.len(), 3);
+		let mut config = DocumentMut::new();
+		config["source"] = Item::Table(mirror("https://crates.example.com"));
+		merge_sources(&mut config, vendored).unwrap();
+		let config = config.to_string();
+		assert!(config.contains("[source.crates-io]\nreplace-with = \"vendored-sources\""));
+		assert!(config.contains("[source.mirror]"));
+		assert!(config.contains("[source.vendored-sources]\ndirectory = \"vendor\""));
+		assert!(matches!(sources("[net]\noffline = true"), Err(Error::Vendor(_))));
+		Ok(())
+	}
+}
@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use std::{
+	collections::HashMap,
+	fs::{metadata, read_dir},
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+use tokio::time::sleep;
+
+/// The directories ignored by default, as they only contain generated files.
+const IGNORED: [&str; 3] = [".git", "node_modules", "target"];
+
+/// The state of a file, used to determine whether it has changed.
+type FileState = (Option<SystemTime>, u64);
+
+/// Watches directories for changes to the files within them, by periodically polling their
+/// modification times.
+pub struct Watcher {
+	/// The paths being watched.
+	paths: Vec<PathBuf>,
+	/// The names of directories which are ignored.
+	ignored: Vec<String>,
+	/// The last known state of each file.
+	files: HashMap<PathBuf, FileState>,
+}
+
+impl Watcher {
+	/// Creates a new watcher for the specified paths, ignoring `.git`, `node_modules` and
+	/// `target` directories.
+	///
+	/// # Arguments
+	/// * `paths` - The files or directories to be watched.
+	pub fn new(paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+		let mut watcher = Self {
+			paths: paths.into_iter().map(|p| p.into()).collect(),
+			ignored: IGNORED.iter().map(|i| i.to_string()).collect(),
+			files: HashMap::new(),
+		};
+		watcher.files = watcher.scan();
+		watcher
+	}
+
+	/// Ignores any directories with the specified name.
+	///
+	/// # Arguments
+	/// * `name` - The name of the directories to be ignored.
+	pub fn ignore(mut self, name: &str) -> Self {
+		self.ignored.push(name.to_string());
+		self.files = self.scan();
+		self
+	}
+
+	/// Returns the files which have been added, modified or removed since the last check, in
+	/// order.
+	pub fn changes(&mut self) -> Vec<PathBuf> {
+		let files = self.scan();
+		let mut changes: Vec<_> = files
+			.iter()
+			.filter(|(path, state)| self.files.get(*path) != Some(state))
+			.map(|(path, _)| path.clone())
+			.chain(self.files.keys().filter(|path| !files.contains_key(*path)).cloned())
+			.collect();
+		changes.sort();
+		self.files = files;
+		changes
+	}
+
+	/// Waits until files change, returning them once no further changes have occurred for the
+	/// `debounce` period, so that a burst of changes (e.g. saving several files) is reported once.
+	///
+	/// # Arguments
+	/// * `interval` - The interval at which files are polled.
+	/// * `debounce` - The period without changes required before changes are reported.
+	pub async fn wait(&mut self, interval: Duration, debounce: Duration) -> Vec<PathBuf> {
+		let mut changes = loop {
+			let changes = self.changes();
+			if !changes.is_empty() {
+				break changes;
+			}
+			sleep(interval).await;
+		};
+		loop {
+			sleep(debounce).await;
+			let more = self.changes();
+			if more.is_empty() {
+				break;
+			}
+			changes.extend(more);
+		}
+		changes.sort();
+		changes.dedup();
+		changes
+	}
+
+	/// Determines the current state of all files being watched.
+	fn scan(&self) -> HashMap<PathBuf, FileState> {
+		let mut files = HashMap::new();
+		for path in &self.paths {
+			self.visit(path, &mut files);
+		}
+		files
+	}
+
+	/// Records the state of a file, or of all files within a directory.
+	fn visit(&self, path: &Path, files: &mut HashMap<PathBuf, FileState>) {
+		let Ok(metadata) = metadata(path) else {
+			return;
+		};
+		if !metadata.is_dir() {
+			files.insert(path.to_path_buf(), (metadata.modified().ok(), metadata.len()));
+			return;
+		}
+		let Ok(entries) = read_dir(path) else {
+			return;
+		};
+		for entry in entries.flatten() {
+			let path = entry.path();
+			let ignored = path
+				.file_name()
+				.and_then(|n| n.to_str())
+				.is_some_and(|name| self.ignored.iter().any(|i| i == name));
+			if !(ignored && path.is_dir()) {
+				self.visit(&path, files);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::{create_dir_all, remove_file, write};
+
+	#[test]
+	fn changes_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let src = temp_dir.path().join("runtime/src");
+		create_dir_all(&src)?;
+		create_dir_all(temp_dir.path().join("target"))?;
+		write(src.join("lib.rs"), "// lib")?;
+		let mut watcher = Watcher::new([temp_dir.path()]);
+		assert!(watcher.changes().is_empty());
+
+		write(src.join("lib.rs"), "// modified lib")?;
+		write(src.join("weights.rs"), "// weights")?;
+		write(temp_dir.path().join("target/build.log"), "ignored")?;
+		assert_eq!(watcher.changes(), vec![src.join("lib.rs"), src.join("weights.rs")]);
+		assert!(watcher.changes().is_empty());
+
+		remove_file(src.join("weights.rs"))?;
+		assert_eq!(watcher.changes(), vec![src.join("weights.rs")]);
+		Ok(())
+	}
+
+	#[test]
+	fn ignore_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let docs = temp_dir.path().join("docs");
+		create_dir_all(&docs)?;
+		let mut watcher = Watcher::new([temp_dir.path()]).ignore("docs");
+		write(docs.join("README.md"), "# Docs")?;
+		assert!(watcher.changes().is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn wait_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("lib.rs");
+		let mut watcher = Watcher::new([temp_dir.path()]);
+		let writer = tokio::spawn({
+			let path = path.clone();
+			async move {
+				sleep(Duration::from_millis(50)).await;
+				write(path, "// lib")
+			}
+		});
+		let changes = watcher.wait(Duration::from_millis(10), Duration::from_millis(50)).await;
+		writer.await??;
+		assert_eq!(changes, vec![path]);
+		Ok(())
+	}
+}
@@ -2,7 +2,9 @@
 
 use crate::{errors::Error, utils::helpers::get_manifest_path};
 pub use contract_build::Verbosity;
-use contract_build::{execute, BuildMode, BuildResult, ExecuteArgs};
+use contract_build::{execute, BuildMode, BuildResult, ExecuteArgs, Network};
+use duct::cmd;
+use pop_common::Resolution;
 use std::path::Path;
 
 /// Build the smart contract located at the specified `path` in `build_release` mode.
@@ -12,25 +14,56 @@ use std::path::Path;
 ///   if not specified.
 /// * `release` - Whether the smart contract should be built without any debugging functionality.
 /// * `verbosity` - The build output verbosity.
+/// * `resolution` - How the dependencies of the smart contract are resolved.
 pub fn build_smart_contract(
 	path: Option<&Path>,
 	release: bool,
 	verbosity: Verbosity,
+	resolution: &Resolution,
 ) -> anyhow::Result<BuildResult> {
 	let manifest_path = get_manifest_path(path)?;
+	// Contract builds do not accept `--locked`, so the lockfile is verified up front.
+	if resolution.locked {
+		verify_lockfile(manifest_path.as_ref(), resolution)?;
+	}
 
 	let build_mode = match release {
 		true => BuildMode::Release,
 		false => BuildMode::Debug,
 	};
+	let network = match resolution.offline {
+		true => Network::Offline,
+		false => Network::Online,
+	};
 
 	// Default values
-	let args = ExecuteArgs { manifest_path, build_mode, verbosity, ..Default::default() };
+	let args = ExecuteArgs { manifest_path, build_mode, verbosity, network, ..Default::default() };
 
 	// Execute the build and log the output of the build
 	execute(args)
 }
 
+/// Verifies that the lockfile of a smart contract is up to date.
+///
+/// # Arguments
+/// * `manifest` - The path to the smart contract manifest.
+/// * `resolution` - How the dependencies of the smart contract are resolved.
+fn verify_lockfile(manifest: &Path, resolution: &Resolution) -> Result<(), Error> {
+	let mut args = vec!["metadata".to_string(), "--format-version".into(), "1".into()];
+	args.extend(["--manifest-path".to_string(), manifest.display().to_string()]);
+	args.extend(resolution.args());
+	let output = cmd("cargo", args).stdout_null().stderr_capture().unchecked().run()?;
+	if !output.status.success() {
+		return Err(pop_common::Error::Config(format!(
+			"the lockfile of {} is not up to date: {}",
+			manifest.display(),
+			String::from_utf8_lossy(&output.stderr).trim()
+		))
+		.into());
+	}
+	Ok(())
+}
+
 /// Determines whether the manifest at the supplied path is a supported smart contract project.
 ///
 /// # Arguments
@@ -44,7 +77,6 @@ pub fn is_supported(path: Option<&Path>) -> Result<bool, Error> {
 mod tests {
 	use super::*;
 	use contract_build::new_contract_project;
-	use duct::cmd;
 
 	#[test]
 	fn is_supported_works() -> anyhow::Result<()> {
@@ -62,4 +94,23 @@ mod tests {
 		assert!(is_supported(Some(&path.join(name)))?);
 		Ok(())
 	}
+
+	#[test]
+	fn verify_lockfile_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let name = "hello_world";
+		cmd("cargo", ["new", name]).dir(temp_dir.path()).run()?;
+		let manifest = temp_dir.path().join(name).join("Cargo.toml");
+		let resolution = Resolution { locked: true, offline: true };
+		// The lockfile is missing.
+		assert!(matches!(
+			verify_lockfile(&manifest, &resolution),
+			Err(Error::CommonError(pop_common::Error::Config(e))) if e.contains("is not up to date")
+		));
+		cmd("cargo", ["generate-lockfile", "--offline"])
+			.dir(temp_dir.path().join(name))
+			.run()?;
+		verify_lockfile(&manifest, &resolution)?;
+		Ok(())
+	}
 }
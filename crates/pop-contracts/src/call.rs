@@ -2,8 +2,12 @@
 
 use crate::{
 	errors::Error,
+	metadata::MetadataRegistry,
+	pallet::{chain_contracts, ContractsPallet},
+	revive,
+	up::is_revive,
 	utils::{
-		helpers::{get_manifest_path, parse_account, parse_balance},
+		helpers::{get_manifest_path, parse_balance},
 		signer::create_signer,
 	},
 };
@@ -14,6 +18,7 @@ use contract_extrinsics::{
 	ExtrinsicOptsBuilder, TokenMetadata,
 };
 use ink_env::{DefaultEnvironment, Environment};
+use pop_common::Account;
 use sp_weights::Weight;
 use std::path::PathBuf;
 use subxt::{Config, PolkadotConfig as DefaultConfig};
@@ -28,7 +33,7 @@ pub struct CallOpts {
 	pub contract: String,
 	/// The name of the contract message to call.
 	pub message: String,
-	/// The constructor arguments, encoded as strings.
+	/// The message arguments, in a JSON5 or Rust struct-literal style.
 	pub args: Vec<String>,
 	/// Transfers an initial balance to the instantiated contract.
 	pub value: String,
@@ -52,8 +57,15 @@ pub struct CallOpts {
 pub async fn set_up_call(
 	call_opts: CallOpts,
 ) -> anyhow::Result<CallExec<DefaultConfig, DefaultEnvironment, Keypair>> {
-	let token_metadata = TokenMetadata::query::<DefaultConfig>(&call_opts.url).await?;
 	let manifest_path = get_manifest_path(call_opts.path.as_deref())?;
+	// Register the metadata of the contract, so that its arguments are validated and its events
+	// decoded using its types, without parsing it again for subsequent calls.
+	let metadata = MetadataRegistry::global().load(call_opts.path.as_deref())?;
+	let args = metadata.encode_args(&call_opts.message, &call_opts.args)?;
+	// Fail early when the chain cannot host contracts.
+	let chain = chain_contracts(&call_opts.url).await?;
+	chain.ensure_supported()?;
+	let token_metadata = TokenMetadata::query::<DefaultConfig>(&call_opts.url).await?;
 	let signer = create_signer(&call_opts.suri)?;
 
 	let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
@@ -64,11 +76,19 @@ pub async fn set_up_call(
 	let value: BalanceVariant<<DefaultEnvironment as Environment>::Balance> =
 		parse_balance(&call_opts.value)?;
 
-	let contract: <DefaultConfig as Config>::AccountId = parse_account(&call_opts.contract)?;
+	let contract: <DefaultConfig as Config>::AccountId = match chain.pallet {
+		ContractsPallet::Contracts =>
+			pop_common::parse_account(&call_opts.contract).map(Into::into),
+		// Contracts are addressed by 20-byte accounts by `pallet-revive`, held as their mapped
+		// 32-byte accounts by the contract tooling.
+		ContractsPallet::Revive =>
+			Account::parse(&call_opts.contract).map(|account| account.to_id32().into()),
+	}
+	.map_err(|e| Error::AccountAddressParsing(e.to_string()))?;
 
 	let call_exec: CallExec<DefaultConfig, DefaultEnvironment, Keypair> =
 		CallCommandBuilder::new(contract.clone(), &call_opts.message, extrinsic_opts)
-			.args(call_opts.args.clone())
+			.args(args)
 			.value(value.denominate_balance(&token_metadata)?)
 			.gas_limit(call_opts.gas_limit)
 			.proof_size(call_opts.proof_size)
@@ -85,6 +105,17 @@ pub async fn set_up_call(
 pub async fn dry_run_call(
 	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
 ) -> Result<String, Error> {
+	if is_revive(call_exec.client()) {
+		let data = revive::call_dry_run(call_exec)
+			.await?
+			.result
+			.map_err(Error::DryRunCallContractError)?;
+		let value = call_exec
+			.transcoder()
+			.decode_message_return(call_exec.message(), &mut &data[..])
+			.context(format!("Failed to decode return value {data:?}"))?;
+		return Ok(value.to_string());
+	}
 	let call_result = call_exec.call_dry_run().await?;
 	match call_result.result {
 		Ok(ref ret_val) => {
@@ -110,6 +141,14 @@ pub async fn dry_run_call(
 pub async fn dry_run_gas_estimate_call(
 	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
 ) -> Result<Weight, Error> {
+	if is_revive(call_exec.client()) {
+		let dry_run = revive::call_dry_run(call_exec).await?;
+		dry_run.result.map_err(Error::DryRunCallContractError)?;
+		return Ok(Weight::from_parts(
+			call_exec.gas_limit().unwrap_or_else(|| dry_run.gas_required.ref_time()),
+			call_exec.proof_size().unwrap_or_else(|| dry_run.gas_required.proof_size()),
+		));
+	}
 	let call_result = call_exec.call_dry_run().await?;
 	match call_result.result {
 		Ok(_) => {
@@ -142,12 +181,21 @@ pub async fn call_smart_contract(
 ) -> anyhow::Result<String, Error> {
 	let token_metadata = TokenMetadata::query::<DefaultConfig>(url).await?;
 	let metadata = call_exec.client().metadata();
-	let events = call_exec
-		.call(Some(gas_limit))
-		.await
-		.map_err(|error_variant| Error::CallContractError(format!("{:?}", error_variant)))?;
-	let display_events =
-		DisplayEvents::from_events::<DefaultConfig, DefaultEnvironment>(&events, None, &metadata)?;
+	let project = call_exec.opts().manifest_path().and_then(|m| m.parent());
+	let contract = MetadataRegistry::global().load(project)?;
+	let events = if is_revive(call_exec.client()) {
+		revive::call(&call_exec, gas_limit).await?
+	} else {
+		call_exec
+			.call(Some(gas_limit))
+			.await
+			.map_err(|error_variant| Error::CallContractError(format!("{:?}", error_variant)))?
+	};
+	let display_events = DisplayEvents::from_events::<DefaultConfig, DefaultEnvironment>(
+		&events,
+		Some(contract.transcoder()),
+		&metadata,
+	)?;
 
 	let output =
 		display_events.display_events::<DefaultEnvironment>(Verbosity::Default, &token_metadata)?;
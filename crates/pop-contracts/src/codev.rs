@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, is_supported};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	fs::{create_dir_all, read_to_string, write},
+	path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// The default location of the address book, relative to the root of the workspace.
+pub const ADDRESS_BOOK: &str = ".pop/address-book.json";
+/// The depth within a workspace up to which contracts are discovered.
+const MAX_DEPTH: usize = 3;
+
+/// Discovers the contract projects within a workspace, ordered by path.
+///
+/// # Arguments
+/// * `workspace` - The root of the workspace.
+pub fn find_contracts(workspace: &Path) -> Vec<PathBuf> {
+	let mut contracts: Vec<_> = WalkDir::new(workspace)
+		.max_depth(MAX_DEPTH)
+		.into_iter()
+		.filter_entry(|e| {
+			!matches!(e.file_name().to_str(), Some("target" | ".git" | "node_modules"))
+		})
+		.flatten()
+		.filter(|e| e.file_name() == "Cargo.toml")
+		.filter_map(|e| e.path().parent().map(Path::to_path_buf))
+		.filter(|dir| is_supported(Some(dir)).unwrap_or(false))
+		.collect();
+	contracts.sort();
+	contracts
+}
+
+/// The addresses of the contracts deployed to a chain, by the name of their project, so that
+/// frontends, tests and scripts can locate the contracts of a workspace.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AddressBook {
+	/// The endpoint of the chain to which the contracts were deployed.
+	pub url: String,
+	/// The addresses of the contracts, by name.
+	pub contracts: BTreeMap<String, String>,
+}
+
+impl AddressBook {
+	/// Creates an empty address book for a chain.
+	///
+	/// # Arguments
+	/// * `url` - The endpoint of the chain.
+	pub fn new(url: &str) -> Self {
+		Self { url: url.to_string(), contracts: BTreeMap::new() }
+	}
+
+	/// Loads an address book, which is empty when missing.
+	///
+	/// # Arguments
+	/// * `path` - The path of the address book.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		serde_json::from_str(&read_to_string(path)?)
+			.map_err(|e| Error::AnyhowError(anyhow::anyhow!("invalid address book: {e}")))
+	}
+
+	/// Records the address of a contract.
+	///
+	/// # Arguments
+	/// * `name` - The name of the contract.
+	/// * `address` - The address of the contract.
+	pub fn insert(&mut self, name: &str, address: &str) {
+		self.contracts.insert(name.to_string(), address.to_string());
+	}
+
+	/// Saves the address book.
+	///
+	/// # Arguments
+	/// * `path` - The path of the address book.
+	pub fn save(&self, path: &Path) -> Result<(), Error> {
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		let contents = serde_json::to_string_pretty(self)
+			.map_err(|e| Error::AnyhowError(anyhow::anyhow!(e)))?;
+		write(path, contents)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn manifest(dir: &Path, name: &str, dependencies: &str) -> anyhow::Result<()> {
+		create_dir_all(dir.join("src"))?;
+		write(dir.join("src/lib.rs"), "")?;
+		write(
+			dir.join("Cargo.toml"),
+			format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n\n[dependencies]\n{dependencies}"),
+		)?;
+		Ok(())
+	}
+
+	#[test]
+	fn find_contracts_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let workspace = temp_dir.path();
+		manifest(&workspace.join("runtime"), "runtime", "")?;
+		manifest(&workspace.join("contracts/flipper"), "flipper", "ink = \"5.0.0\"\n")?;
+		manifest(&workspace.join("contracts/erc20"), "erc20", "ink = \"5.0.0\"\n")?;
+		manifest(&workspace.join("target/ink/flipper"), "flipper", "ink = \"5.0.0\"\n")?;
+		assert_eq!(
+			find_contracts(workspace),
+			[workspace.join("contracts/erc20"), workspace.join("contracts/flipper")]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn address_book_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join(ADDRESS_BOOK);
+		assert_eq!(AddressBook::load(&path)?, AddressBook::default());
+		let mut address_book = AddressBook::new("ws://127.0.0.1:9944");
+		address_book.insert("flipper", "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+		address_book.save(&path)?;
+		assert_eq!(AddressBook::load(&path)?, address_book);
+		Ok(())
+	}
+}
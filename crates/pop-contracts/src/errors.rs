@@ -1,55 +1,70 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use pop_common::sourcing::Error as SourcingError;
+use pop_common::{i18n, sourcing::Error as SourcingError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 #[allow(clippy::enum_variant_names)]
 pub enum Error {
-	#[error("Anyhow error: {0}")]
+	#[error("{}", localized("anyhow_error", &[("0", &.0.to_string())]))]
 	AnyhowError(#[from] anyhow::Error),
-	#[error("Failed to parse account address: {0}")]
+	#[error("{}", localized("account_address_parsing", &[("0", .0.as_str())]))]
 	AccountAddressParsing(String),
-	#[error("Failed to parse balance: {0}")]
+	#[error("{}", localized("balance_parsing", &[("0", .0.as_str())]))]
 	BalanceParsing(String),
-	#[error("{0}")]
+	#[error("{}", localized("call_contract_error", &[("0", .0.as_str())]))]
 	CallContractError(String),
-	#[error("{0}")]
+	#[error("{}", localized("common_error", &[("0", &.0.to_string())]))]
 	CommonError(#[from] pop_common::Error),
-	#[error("Pre-submission dry-run failed: {0}")]
+	#[error("{}", localized("dry_run_upload_contract_error", &[("0", .0.as_str())]))]
 	DryRunUploadContractError(String),
-	#[error("Pre-submission dry-run failed: {0}")]
+	#[error("{}", localized("dry_run_call_contract_error", &[("0", .0.as_str())]))]
 	DryRunCallContractError(String),
-	#[error("Failed to parse hex encoded bytes: {0}")]
+	#[error("{}", localized("hex_parsing", &[("0", .0.as_str())]))]
 	HexParsing(String),
-	#[error("HTTP error: {0}")]
+	#[error("{}", localized("http_error", &[("0", &.0.to_string())]))]
 	HttpError(#[from] reqwest::Error),
-	#[error("Failed to install {0}")]
+	#[error("{}", localized("install_contracts_node", &[("0", .0.as_str())]))]
 	InstallContractsNode(String),
-	#[error("{0}")]
+	#[error("{}", localized("instantiate_contract_error", &[("0", .0.as_str())]))]
 	InstantiateContractError(String),
-	#[error("Invalid name: {0}")]
+	#[error("{}", localized("invalid_name", &[("0", .0.as_str())]))]
 	InvalidName(String),
-	#[error("IO error: {0}")]
+	#[error("{}", localized("io", &[("0", &.0.to_string())]))]
 	IO(#[from] std::io::Error),
-	#[error("Failed to create keypair from URI: {0}")]
+	#[error("{}", localized("key_pair_creation", &[("0", .0.as_str())]))]
 	KeyPairCreation(String),
-	#[error("Failed to get manifest path: {0}")]
+	#[error("{}", localized("manifest_path", &[("0", .0.as_str())]))]
 	ManifestPath(String),
-	#[error("Failed to create new contract project: {0}")]
+	#[error("{}", localized("new_contract", &[("0", .0.as_str())]))]
 	NewContract(String),
-	#[error("ParseError error: {0}")]
+	#[error("{}", localized("parse_error", &[("0", &.0.to_string())]))]
 	ParseError(#[from] url::ParseError),
-	#[error("Failed to parse secret URI: {0}")]
+	#[error("{}", localized("parse_secret_uri", &[("0", .0.as_str())]))]
 	ParseSecretURI(String),
-	#[error("The `Repository` property is missing from the template variant")]
+	#[error("{}", localized("repro", &[("0", .0.as_str())]))]
+	Repro(String),
+	#[error("{}", localized("repository_missing", &[]))]
 	RepositoryMissing,
-	#[error("Failed to execute test command: {0}")]
+	#[error("{}", localized("subxt_error", &[("0", &.0.to_string())]))]
+	SubxtError(#[from] subxt::Error),
+	#[error("{}", localized("test_command", &[("0", .0.as_str())]))]
 	TestCommand(String),
-	#[error("Unsupported platform: {os}")]
+	#[error("{}", localized("unsupported_chain", &[("0", .0.as_str())]))]
+	UnsupportedChain(String),
+	#[error("{}", localized("unsupported_platform", &[("os", .os)]))]
 	UnsupportedPlatform { os: &'static str },
-	#[error("{0}")]
+	#[error("{}", localized("upload_contract_error", &[("0", .0.as_str())]))]
 	UploadContractError(String),
-	#[error("Sourcing error {0}")]
+	#[error("{}", localized("sourcing_error", &[("0", &.0.to_string())]))]
 	SourcingError(SourcingError),
 }
+
+/// Formats the message of an error via the catalog of user-facing messages.
+///
+/// # Arguments
+/// * `variant` - The identifier of the error variant.
+/// * `args` - The arguments of the message, as (name, value) pairs.
+fn localized(variant: &str, args: &[(&str, &str)]) -> String {
+	i18n::message(&format!("error.contracts.{variant}"), args)
+}
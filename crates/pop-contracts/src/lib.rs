@@ -3,9 +3,14 @@
 #![doc = include_str!("../README.md")]
 mod build;
 mod call;
+mod codev;
 mod errors;
+mod metadata;
 mod new;
 mod node;
+mod pallet;
+mod repro;
+mod revive;
 mod templates;
 mod test;
 mod up;
@@ -15,8 +20,14 @@ pub use build::{build_smart_contract, is_supported, Verbosity};
 pub use call::{
 	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, set_up_call, CallOpts,
 };
+pub use codev::{find_contracts, AddressBook, ADDRESS_BOOK};
+pub use metadata::{MetadataRegistry, ParsedMetadata};
 pub use new::{create_smart_contract, is_valid_contract_name};
 pub use node::{contracts_node_generator, is_chain_alive, run_contracts_node};
+pub use pallet::{
+	chain_contracts, ensure_deployable, runtime_contracts_pallet, ChainContracts, ContractsPallet,
+};
+pub use repro::{export_repro, import_repro, Repro, ReproCall, Toolchain};
 pub use templates::{Contract, ContractType};
 pub use test::{test_e2e_smart_contract, test_smart_contract};
 pub use up::{
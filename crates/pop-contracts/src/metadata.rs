@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, utils::helpers::get_manifest_path};
+use contract_extrinsics::{ContractArtifacts, ContractMessageTranscoder};
+use pop_common::parse_arg;
+use scale_info::{form::PortableForm, Field, PortableRegistry, Type, TypeDef, TypeDefPrimitive};
+use std::{
+	collections::HashMap,
+	fs::{metadata, read_dir},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex, OnceLock},
+	time::SystemTime,
+};
+use subxt::ext::scale_value::{Composite, Primitive, Value, ValueDef};
+
+/// The parsed metadata of a contract, shared by all contracts deployed with the same code.
+pub struct ParsedMetadata {
+	/// The hash of the contract code.
+	code_hash: [u8; 32],
+	/// The name of the contract.
+	name: String,
+	/// The transcoder used to encode arguments and decode return values and events, using the
+	/// type definitions of the contract.
+	transcoder: ContractMessageTranscoder,
+}
+
+impl ParsedMetadata {
+	/// The hash of the contract code.
+	pub fn code_hash(&self) -> &[u8; 32] {
+		&self.code_hash
+	}
+
+	/// The name of the contract.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The transcoder of the contract.
+	pub fn transcoder(&self) -> &ContractMessageTranscoder {
+		&self.transcoder
+	}
+
+	/// Resolves a type defined by the contract (e.g. a custom struct or enum used as an argument
+	/// or within an event), either by its full path (e.g. "flipper::Error") or by its name.
+	///
+	/// # Arguments
+	/// * `path` - The path or name of the type.
+	pub fn type_def(&self, path: &str) -> Option<&Type<PortableForm>> {
+		let types = &self.transcoder.metadata().registry().types;
+		types
+			.iter()
+			.find(|t| t.ty.path.segments.join("::") == path)
+			.or_else(|| types.iter().find(|t| t.ty.path.ident().is_some_and(|name| name == path)))
+			.map(|t| &t.ty)
+	}
+
+	/// Encodes a call to a message of the contract.
+	///
+	/// # Arguments
+	/// * `message` - The name of the message.
+	/// * `args` - The arguments of the message, encoded as strings.
+	pub fn encode_call(&self, message: &str, args: &[String]) -> Result<Vec<u8>, Error> {
+		Ok(self.transcoder.encode(message, self.encode_args(message, args)?)?)
+	}
+
+	/// Validates the arguments of a message or constructor against their types, converting them
+	/// into the format expected by the transcoder. Arguments may be specified in a JSON5 or Rust
+	/// struct-literal style (see [`parse_arg`]), with any error pointing at the offending field.
+	///
+	/// Unknown messages, or an unexpected number of arguments, are left for the transcoder to
+	/// report.
+	///
+	/// # Arguments
+	/// * `label` - The name of the message or constructor.
+	/// * `args` - The arguments, as specified.
+	pub fn encode_args(&self, label: &str, args: &[String]) -> Result<Vec<String>, Error> {
+		let params = match self.params(label) {
+			Some(params) if params.len() == args.len() => params,
+			_ => return Ok(args.to_vec()),
+		};
+		let types = self.transcoder.metadata().registry();
+		params
+			.into_iter()
+			.zip(args)
+			.map(|((name, ty), arg)| Ok(to_scon(&parse_arg(name, arg, ty, types)?, ty, types)))
+			.collect()
+	}
+
+	/// The names of the arguments of a message or constructor, in order, if found.
+	///
+	/// # Arguments
+	/// * `label` - The name of the message or constructor.
+	pub fn arg_names(&self, label: &str) -> Option<Vec<&str>> {
+		Some(self.params(label)?.into_iter().map(|(name, _)| name).collect())
+	}
+
+	/// The names and types of the parameters of a message or constructor, in order.
+	fn params(&self, label: &str) -> Option<Vec<(&str, u32)>> {
+		let spec = self.transcoder.metadata().spec();
+		let params =
+			spec.messages().iter().find(|m| m.label() == label).map(|m| m.args()).or_else(
+				|| spec.constructors().iter().find(|c| c.label() == label).map(|c| c.args()),
+			)?;
+		Some(params.iter().map(|p| (p.label().as_str(), p.ty().ty().id)).collect())
+	}
+}
+
+/// Formats a value, normalised against its type, in the notation expected by the transcoder.
+///
+/// # Arguments
+/// * `value` - The value.
+/// * `ty` - The type of the value within the registry.
+/// * `types` - The registry of types of the contract.
+fn to_scon(value: &Value, ty: u32, types: &PortableRegistry) -> String {
+	match (&value.value, types.resolve(ty).map(|t| &t.type_def)) {
+		(ValueDef::Composite(Composite::Unnamed(values)), Some(TypeDef::Sequence(def))) =>
+			items_to_scon(values, def.type_param.id, types),
+		(ValueDef::Composite(Composite::Unnamed(values)), Some(TypeDef::Array(def))) =>
+			items_to_scon(values, def.type_param.id, types),
+		(ValueDef::Composite(Composite::Unnamed(values)), Some(TypeDef::Tuple(def))) => format!(
+			"({})",
+			values
+				.iter()
+				.zip(&def.fields)
+				.map(|(v, ty)| to_scon(v, ty.id, types))
+				.collect::<Vec<_>>()
+				.join(", ")
+		),
+		(ValueDef::Composite(composite), Some(TypeDef::Composite(def))) =>
+			match (composite, def.fields.as_slice()) {
+				(Composite::Unnamed(values), [field]) if values.len() == 1 =>
+					to_scon(&values[0], field.ty.id, types),
+				(composite, fields) => fields_to_scon(composite, fields, types),
+			},
+		(ValueDef::Variant(variant), Some(TypeDef::Variant(def))) => {
+			let fields = def
+				.variants
+				.iter()
+				.find(|v| v.name == variant.name)
+				.map(|v| v.fields.as_slice());
+			match (&variant.values, fields) {
+				(values @ Composite::Named(_), Some(fields)) if !values.is_empty() =>
+					format!("{} {}", variant.name, fields_to_scon(values, fields, types)),
+				(values, Some(fields)) if !values.is_empty() =>
+					format!("{}{}", variant.name, fields_to_scon(values, fields, types)),
+				_ => variant.name.clone(),
+			}
+		},
+		(_, Some(TypeDef::Compact(def))) => to_scon(value, def.type_param.id, types),
+		(ValueDef::Primitive(Primitive::String(string)), _) => {
+			let mut escaped = String::with_capacity(string.len() + 2);
+			escaped.push('"');
+			for c in string.chars() {
+				match c {
+					'"' => escaped.push_str("\\\""),
+					'\\' => escaped.push_str("\\\\"),
+					'\n' => escaped.push_str("\\n"),
+					'\r' => escaped.push_str("\\r"),
+					'\t' => escaped.push_str("\\t"),
+					c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+					c => escaped.push(c),
+				}
+			}
+			escaped.push('"');
+			escaped
+		},
+		(ValueDef::Primitive(Primitive::Char(c)), _) => format!("'{c}'"),
+		_ => value.to_string(),
+	}
+}
+
+/// Formats the items of a sequence or array in the notation expected by the transcoder.
+fn items_to_scon(values: &[Value], item: u32, types: &PortableRegistry) -> String {
+	let is_bytes = types
+		.resolve(item)
+		.is_some_and(|t| matches!(t.type_def, TypeDef::Primitive(TypeDefPrimitive::U8)));
+	match is_bytes {
+		// Bytes are formatted as hex, as expected for accounts and hashes.
+		true if !values.is_empty() => format!(
+			"0x{}",
+			values
+				.iter()
+				.map(|v| format!("{:02x}", v.as_u128().unwrap_or_default()))
+				.collect::<String>()
+		),
+		_ => format!(
+			"[{}]",
+			values.iter().map(|v| to_scon(v, item, types)).collect::<Vec<_>>().join(", ")
+		),
+	}
+}
+
+/// Formats the fields of a struct or variant in the notation expected by the transcoder.
+fn fields_to_scon(
+	composite: &Composite<()>,
+	fields: &[Field<PortableForm>],
+	types: &PortableRegistry,
+) -> String {
+	let values: Vec<_> = match composite {
+		Composite::Named(values) => values
+			.iter()
+			.zip(fields)
+			.map(|((name, v), field)| format!("{name}: {}", to_scon(v, field.ty.id, types)))
+			.collect(),
+		Composite::Unnamed(values) => values
+			.iter()
+			.zip(fields)
+			.map(|(v, field)| to_scon(v, field.ty.id, types))
+			.collect(),
+	};
+	match composite {
+		Composite::Named(_) => format!("{{ {} }}", values.join(", ")),
+		Composite::Unnamed(_) => format!("({})", values.join(", ")),
+	}
+}
+
+/// A registry of parsed contract metadata, keyed by code hash, so that repeated calls to the same
+/// contract skip re-parsing its metadata and resolve its types consistently.
+#[derive(Default)]
+pub struct MetadataRegistry {
+	/// The parsed metadata, keyed by code hash.
+	contracts: Mutex<HashMap<[u8; 32], Arc<ParsedMetadata>>>,
+	/// The code hashes of the artifacts loaded, keyed by path and the time the artifacts were
+	/// last modified, so that unchanged artifacts are not parsed again.
+	paths: Mutex<HashMap<PathBuf, (SystemTime, [u8; 32])>>,
+}
+
+impl MetadataRegistry {
+	/// The registry shared by all calls within the process.
+	pub fn global() -> &'static Self {
+		static REGISTRY: OnceLock<MetadataRegistry> = OnceLock::new();
+		REGISTRY.get_or_init(Self::default)
+	}
+
+	/// The parsed metadata of the contract with the specified code hash, if registered.
+	///
+	/// # Arguments
+	/// * `code_hash` - The hash of the contract code.
+	pub fn get(&self, code_hash: &[u8; 32]) -> Option<Arc<ParsedMetadata>> {
+		self.contracts.lock().expect("lock not poisoned").get(code_hash).cloned()
+	}
+
+	/// Loads the metadata of a contract, reusing the parsed metadata of any contract registered
+	/// with the same code hash.
+	///
+	/// # Arguments
+	/// * `path` - The path to either the contract project or its artifact (`.contract` or `.json`)
+	///   [default: the current directory].
+	pub fn load(&self, path: Option<&Path>) -> Result<Arc<ParsedMetadata>, Error> {
+		let fingerprint = fingerprint(path.unwrap_or(Path::new("./")));
+		if let Some((path, modified)) = &fingerprint {
+			let cached = self.paths.lock().expect("lock not poisoned").get(path).copied();
+			if let Some(metadata) = cached
+				.filter(|(m, _)| m == modified)
+				.and_then(|(_, code_hash)| self.get(&code_hash))
+			{
+				return Ok(metadata);
+			}
+		}
+		let artifacts = match path.filter(|p| p.is_file()) {
+			Some(file) => ContractArtifacts::from_manifest_or_file(None, Some(&file.to_path_buf())),
+			None => {
+				let manifest_path: PathBuf = get_manifest_path(path)?.into();
+				ContractArtifacts::from_manifest_or_file(Some(&manifest_path), None)
+			},
+		}?;
+		let code_hash = artifacts.code_hash()?;
+		if let Some((path, modified)) = fingerprint {
+			self.paths
+				.lock()
+				.expect("lock not poisoned")
+				.insert(path, (modified, code_hash));
+		}
+		if let Some(metadata) = self.get(&code_hash) {
+			return Ok(metadata);
+		}
+		let metadata = Arc::new(ParsedMetadata {
+			code_hash,
+			name: artifacts.metadata()?.contract.name,
+			transcoder: artifacts.contract_transcoder()?,
+		});
+		let mut contracts = self.contracts.lock().expect("lock not poisoned");
+		Ok(contracts.entry(code_hash).or_insert(metadata).clone())
+	}
+}
+
+/// The canonical path of a contract artifact or project, and the time its artifacts were last
+/// modified, if any were found.
+///
+/// # Arguments
+/// * `path` - The path to either the contract project or its artifact.
+fn fingerprint(path: &Path) -> Option<(PathBuf, SystemTime)> {
+	let path = path.canonicalize().ok()?;
+	let modified = match path.is_file() {
+		true => metadata(&path).and_then(|m| m.modified()).ok()?,
+		false => read_dir(path.join("target/ink"))
+			.ok()?
+			.flatten()
+			.filter_map(|e| e.metadata().and_then(|m| m.modified()).ok())
+			.max()?,
+	};
+	Some((path, modified))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use scale_info::{meta_type, MetaType, Registry};
+	use std::env;
+
+	fn artifact() -> PathBuf {
+		env::current_dir()
+			.expect("Failed to get current directory")
+			.join("tests/files/testing.contract")
+	}
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let registry = MetadataRegistry::default();
+		let metadata = registry.load(Some(&artifact()))?;
+		assert_eq!(metadata.name(), "testing");
+		// Contracts with the same code share their parsed metadata.
+		assert!(Arc::ptr_eq(&registry.load(Some(&artifact()))?, &metadata));
+		assert!(Arc::ptr_eq(&registry.get(metadata.code_hash()).unwrap(), &metadata));
+		assert!(registry.get(&[0; 32]).is_none());
+		Ok(())
+	}
+
+	#[test]
+	fn load_skips_parsing_unmodified_artifacts() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("testing.contract");
+		std::fs::copy(artifact(), &path)?;
+		let registry = MetadataRegistry::default();
+		let metadata = registry.load(Some(&path))?;
+
+		// The artifact is not parsed again whilst unmodified, even if its contents change.
+		let modified = std::fs::metadata(&path)?.modified()?;
+		std::fs::write(&path, "invalid")?;
+		std::fs::File::options().write(true).open(&path)?.set_modified(modified)?;
+		assert!(Arc::ptr_eq(&registry.load(Some(&path))?, &metadata));
+
+		// Modified artifacts are parsed again.
+		std::fs::File::options()
+			.write(true)
+			.open(&path)?
+			.set_modified(modified + std::time::Duration::from_secs(1))?;
+		assert!(registry.load(Some(&path)).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn load_fails_without_artifacts() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(MetadataRegistry::default().load(Some(temp_dir.path())).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn type_def_works() -> Result<()> {
+		let metadata = MetadataRegistry::default().load(Some(&artifact()))?;
+		let error = metadata.type_def("ink_primitives::LangError").unwrap();
+		assert_eq!(metadata.type_def("LangError"), Some(error));
+		assert!(metadata.type_def("Missing").is_none());
+		Ok(())
+	}
+
+	#[test]
+	fn encode_args_works() -> Result<()> {
+		let metadata = MetadataRegistry::default().load(Some(&artifact()))?;
+		assert_eq!(metadata.encode_args("new", &["false".into()])?, ["false"]);
+		assert!(matches!(
+			metadata.encode_args("new", &["1".into()]),
+			Err(Error::CommonError(pop_common::Error::Argument { path, .. })) if path == "init_value"
+		));
+		// Unknown messages and unexpected arguments are left for the transcoder to report.
+		assert_eq!(metadata.encode_args("flip", &["true".into()])?, ["true"]);
+		assert_eq!(metadata.encode_args("missing", &["1".into()])?, ["1"]);
+		Ok(())
+	}
+
+	#[test]
+	fn to_scon_works() -> Result<()> {
+		let scon = |input: &str, ty: MetaType| -> Result<String> {
+			let mut registry = Registry::new();
+			let ty = registry.register_type(&ty).id;
+			let types: PortableRegistry = registry.into();
+			Ok(to_scon(&parse_arg("arg", input, ty, &types)?, ty, &types))
+		};
+		assert_eq!(scon("[1, 2]", meta_type::<Vec<u8>>())?, "0x0102");
+		assert_eq!(scon("[]", meta_type::<Vec<u8>>())?, "[]");
+		assert_eq!(scon("[1, 2]", meta_type::<Vec<u32>>())?, "[1, 2]");
+		assert_eq!(scon("(1, 'a\"b')", meta_type::<(u8, String)>())?, "(1, \"a\\\"b\")");
+		assert_eq!(scon("null", meta_type::<Option<u32>>())?, "None");
+		assert_eq!(scon("-1", meta_type::<Option<i32>>())?, "Some(-1)");
+		assert_eq!(scon("{ Ok: true }", meta_type::<Result<bool, u8>>())?, "Ok(true)");
+		assert_eq!(
+			scon("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", meta_type::<[u8; 32]>())?,
+			"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn encode_call_works() -> Result<()> {
+		let metadata = MetadataRegistry::default().load(Some(&artifact()))?;
+		assert_eq!(metadata.arg_names("new"), Some(vec!["init_value"]));
+		assert_eq!(metadata.arg_names("missing"), None);
+		let selector = metadata.encode_call("get", &[])?;
+		assert_eq!(selector.len(), 4);
+		assert!(metadata.encode_call("flip", &["true".to_string()]).is_err());
+		assert!(metadata.encode_call("missing", &[]).is_err());
+		Ok(())
+	}
+}
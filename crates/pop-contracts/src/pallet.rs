@@ -0,0 +1,280 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, revive::ensure_polkavm};
+use contract_extrinsics::{ContractArtifacts, RawParams, RpcRequest};
+use pop_common::{encode_hex, manifest::from_path};
+use serde_json::Value;
+use sp_core::blake2_64;
+use std::{
+	fmt::{Display, Formatter},
+	path::Path,
+};
+use subxt::{Metadata, OnlineClient, PolkadotConfig};
+use url::Url;
+
+/// The earliest version of `ContractsApi` supported, from which weights are two-dimensional
+/// (reference time and proof size).
+const MIN_CONTRACTS_API_VERSION: u32 = 2;
+
+/// The contracts pallets, in order of preference when a runtime includes both: `pallet-contracts`
+/// is preferred, being the pallet the contract tooling is built for.
+const PALLETS: [ContractsPallet; 2] = [ContractsPallet::Contracts, ContractsPallet::Revive];
+
+/// The pallet with which a runtime hosts smart contracts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContractsPallet {
+	/// `pallet-contracts`, hosting Wasm contracts.
+	Contracts,
+	/// `pallet-revive`, hosting PolkaVM contracts.
+	Revive,
+}
+
+impl ContractsPallet {
+	/// The name of the crate of the pallet.
+	pub fn crate_name(&self) -> &'static str {
+		match self {
+			ContractsPallet::Contracts => "pallet-contracts",
+			ContractsPallet::Revive => "pallet-revive",
+		}
+	}
+
+	/// The name of the pallet within the metadata of a runtime.
+	pub fn pallet_name(&self) -> &'static str {
+		match self {
+			ContractsPallet::Contracts => "Contracts",
+			ContractsPallet::Revive => "Revive",
+		}
+	}
+
+	/// The name of the runtime API of the pallet, used for dry runs and gas estimation.
+	pub fn runtime_api(&self) -> &'static str {
+		match self {
+			ContractsPallet::Contracts => "ContractsApi",
+			ContractsPallet::Revive => "ReviveApi",
+		}
+	}
+}
+
+impl Display for ContractsPallet {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.crate_name())
+	}
+}
+
+/// The contracts pallet exposed by a chain, alongside the version of its runtime API.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ChainContracts {
+	/// The contracts pallet.
+	pub pallet: ContractsPallet,
+	/// The version of the runtime API of the pallet, if exposed.
+	pub api_version: Option<u32>,
+}
+
+impl ChainContracts {
+	/// Checks that contracts can be deployed to and called on the chain, which requires the
+	/// runtime API of its pallet for dry runs and gas estimation, and two-dimensional weights
+	/// when using `pallet-contracts`.
+	pub fn ensure_supported(&self) -> Result<(), Error> {
+		match (self.pallet, self.api_version) {
+			(pallet, None) => Err(Error::UnsupportedChain(format!(
+				"the chain includes `{pallet}`, but does not expose `{}`, required for dry runs and gas estimation",
+				pallet.runtime_api()
+			))),
+			(ContractsPallet::Revive, Some(_)) => Ok(()),
+			(ContractsPallet::Contracts, Some(version)) if version < MIN_CONTRACTS_API_VERSION =>
+				Err(Error::UnsupportedChain(format!(
+					"the chain exposes version {version} of `ContractsApi`, whereas version {MIN_CONTRACTS_API_VERSION} or later is required"
+				))),
+			(ContractsPallet::Contracts, Some(_)) => Ok(()),
+		}
+	}
+}
+
+/// Determines which contracts pallet a runtime includes, based on the dependencies of its
+/// manifest, either directly or as a feature of the `polkadot-sdk` umbrella crate.
+///
+/// # Arguments
+/// * `runtime` - The directory of the runtime.
+pub fn runtime_contracts_pallet(runtime: &Path) -> Result<Option<ContractsPallet>, Error> {
+	let manifest = from_path(Some(runtime))?;
+	let umbrella = manifest.dependencies.get("polkadot-sdk");
+	Ok(PALLETS.into_iter().find(|pallet| {
+		let name = pallet.crate_name();
+		manifest.dependencies.contains_key(name) ||
+			umbrella.is_some_and(|sdk| sdk.req_features().iter().any(|f| f == name))
+	}))
+}
+
+/// Checks that a built contract can be deployed via a contracts pallet. Contracts built for
+/// `pallet-contracts` are compiled to Wasm, whereas `pallet-revive` only accepts PolkaVM code.
+///
+/// # Arguments
+/// * `pallet` - The contracts pallet to which the contract is deployed.
+/// * `contract` - The directory of the contract project, which must be built.
+pub fn ensure_deployable(pallet: ContractsPallet, contract: &Path) -> Result<(), Error> {
+	if pallet == ContractsPallet::Contracts {
+		return Ok(());
+	}
+	let name = from_path(Some(contract))?.package().name().to_string();
+	let artifact = contract.join(format!("target/ink/{name}.contract"));
+	if !artifact.exists() {
+		return Err(Error::UnsupportedChain(format!(
+			"`{name}` has not been built for PolkaVM, as required by `{pallet}`"
+		)));
+	}
+	let source = ContractArtifacts::from_manifest_or_file(None, Some(&artifact))?
+		.metadata()?
+		.source;
+	match source.wasm {
+		Some(code) => ensure_polkavm(&code.0),
+		None => Err(Error::UnsupportedChain(format!("the code of `{name}` was not found"))),
+	}
+}
+
+/// Determines which contracts pallet a chain exposes, and the version of its runtime API, based
+/// on the metadata and runtime version of the chain. Returns an error when the chain cannot host
+/// contracts at all.
+///
+/// # Arguments
+/// * `url` - Endpoint of a node of the chain.
+pub async fn chain_contracts(url: &Url) -> Result<ChainContracts, Error> {
+	let client = OnlineClient::<PolkadotConfig>::from_url(url.as_str())
+		.await
+		.map_err(|e| Error::UnsupportedChain(format!("unable to connect to {url}: {e}")))?;
+	let metadata = client.metadata();
+	let pallets: Vec<_> = metadata.pallets().map(|p| p.name().to_string()).collect();
+	let request = RpcRequest::new(url).await?;
+	let version = request.raw_call("state_getRuntimeVersion", RawParams::new(&[])?).await?;
+	let version: Value = serde_json::from_str(version.get())
+		.map_err(|e| Error::UnsupportedChain(format!("invalid runtime version: {e}")))?;
+	detect(&pallets, &version)
+		.ok_or_else(|| Error::UnsupportedChain(format!("the chain at {url} cannot host contracts, as it includes neither `pallet-contracts` nor `pallet-revive`")))
+}
+
+/// Determines which contracts pallet a chain exposes, based on its metadata.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+pub(crate) fn metadata_contracts_pallet(metadata: &Metadata) -> Option<ContractsPallet> {
+	let pallets: Vec<_> = metadata.pallets().map(|p| p.name().to_string()).collect();
+	preferred(&pallets)
+}
+
+/// The preferred contracts pallet amongst the names of the pallets of a runtime.
+fn preferred(pallets: &[String]) -> Option<ContractsPallet> {
+	PALLETS
+		.into_iter()
+		.find(|pallet| pallets.iter().any(|p| p == pallet.pallet_name()))
+}
+
+/// Determines the contracts pallet from the names of the pallets of a runtime and its runtime
+/// version, whose `apis` are pairs of the identifier of each runtime API and its version.
+fn detect(pallets: &[String], version: &Value) -> Option<ChainContracts> {
+	let pallet = preferred(pallets)?;
+	let id = encode_hex(&blake2_64(pallet.runtime_api().as_bytes()));
+	let api_version = version
+		.get("apis")
+		.and_then(|apis| apis.as_array())
+		.into_iter()
+		.flatten()
+		.filter_map(|api| api.as_array())
+		.find(|api| api.first().and_then(|i| i.as_str()) == Some(id.as_str()))
+		.and_then(|api| api.get(1).and_then(|v| v.as_u64()))
+		.map(|v| v as u32);
+	Some(ChainContracts { pallet, api_version })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+	use std::fs::{create_dir_all, write};
+
+	#[test]
+	fn runtime_contracts_pallet_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let runtime = temp_dir.path();
+		create_dir_all(runtime.join("src"))?;
+		write(runtime.join("src/lib.rs"), "")?;
+		let manifest = |dependencies: &str| {
+			write(
+				runtime.join("Cargo.toml"),
+				format!("[package]\nname = \"runtime\"\nversion = \"0.1.0\"\n\n[dependencies]\n{dependencies}"),
+			)
+		};
+		manifest("pallet-balances = \"39.0.0\"\n")?;
+		assert_eq!(runtime_contracts_pallet(runtime)?, None);
+		manifest("pallet-contracts = \"38.0.0\"\n")?;
+		assert_eq!(runtime_contracts_pallet(runtime)?, Some(ContractsPallet::Contracts));
+		manifest("polkadot-sdk = { version = \"0.7.0\", features = [\"pallet-revive\"] }\n")?;
+		assert_eq!(runtime_contracts_pallet(runtime)?, Some(ContractsPallet::Revive));
+		// `pallet-contracts` is preferred when the runtime includes both.
+		manifest(
+			"pallet-revive = \"0.2.0\"\npolkadot-sdk = { version = \"0.7.0\", features = [\"pallet-contracts\"] }\n",
+		)?;
+		assert_eq!(runtime_contracts_pallet(runtime)?, Some(ContractsPallet::Contracts));
+		Ok(())
+	}
+
+	#[test]
+	fn ensure_deployable_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let contract = temp_dir.path();
+		create_dir_all(contract.join("src"))?;
+		write(contract.join("src/lib.rs"), "")?;
+		write(contract.join("Cargo.toml"), "[package]\nname = \"testing\"\nversion = \"0.1.0\"\n")?;
+		assert!(ensure_deployable(ContractsPallet::Contracts, contract).is_ok());
+		assert!(matches!(
+			ensure_deployable(ContractsPallet::Revive, contract),
+			Err(Error::UnsupportedChain(e)) if e.contains("has not been built for PolkaVM")
+		));
+		// The contract tooling compiles contracts to Wasm.
+		create_dir_all(contract.join("target/ink"))?;
+		std::fs::copy(
+			std::env::current_dir()?.join("tests/files/testing.contract"),
+			contract.join("target/ink/testing.contract"),
+		)?;
+		assert!(ensure_deployable(ContractsPallet::Contracts, contract).is_ok());
+		assert!(matches!(
+			ensure_deployable(ContractsPallet::Revive, contract),
+			Err(Error::UnsupportedChain(e)) if e.contains("must be compiled for PolkaVM")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn detect_works() {
+		let pallets = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+		// The identifier of `ContractsApi`, as listed within the runtime version of a chain.
+		let version = json!({ "apis": [["0xdf6acb689907609b", 5], ["0x68b66ba122c93fa7", 2]] });
+		let contracts = detect(&pallets(&["System", "Contracts"]), &version).unwrap();
+		assert_eq!(
+			contracts,
+			ChainContracts { pallet: ContractsPallet::Contracts, api_version: Some(2) }
+		);
+		assert!(contracts.ensure_supported().is_ok());
+		assert!(detect(&pallets(&["System", "Balances"]), &version).is_none());
+
+		let both = detect(&pallets(&["Revive", "Contracts"]), &version).unwrap();
+		assert_eq!(both, contracts);
+
+		let revive = detect(&pallets(&["Revive"]), &version).unwrap();
+		assert_eq!(revive, ChainContracts { pallet: ContractsPallet::Revive, api_version: None });
+		assert!(matches!(
+			revive.ensure_supported(),
+			Err(Error::UnsupportedChain(e)) if e.contains("does not expose `ReviveApi`")
+		));
+		// The identifier of `ReviveApi`.
+		let id = encode_hex(&blake2_64(b"ReviveApi"));
+		let revive = detect(&pallets(&["Revive"]), &json!({ "apis": [[id, 1]] })).unwrap();
+		assert_eq!(revive.api_version, Some(1));
+		assert!(revive.ensure_supported().is_ok());
+
+		let version = json!({ "apis": [["0x68b66ba122c93fa7", 1]] });
+		let outdated = detect(&pallets(&["Contracts"]), &version).unwrap();
+		assert!(matches!(
+			outdated.ensure_supported(),
+			Err(Error::UnsupportedChain(e)) if e.contains("version 1 of `ContractsApi`")
+		));
+	}
+}
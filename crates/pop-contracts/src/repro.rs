@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use contract_extrinsics::ContractArtifacts;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+	fs::{create_dir_all, read_dir, File},
+	io::Read,
+	path::{Component, Path, PathBuf},
+};
+use tar::{Archive, Builder, EntryType, Header};
+use walkdir::WalkDir;
+
+/// The name of the manifest describing a reproduction bundle.
+const MANIFEST: &str = "repro.json";
+/// The version of the format of reproduction bundles.
+const VERSION: u32 = 1;
+/// The directories never included within a reproduction bundle.
+const IGNORED: [&str; 3] = [".git", "node_modules", "target"];
+/// The files of a contract project included within a reproduction bundle, in addition to its Rust
+/// sources. Only the files required to build the contract are included, so that secrets such as
+/// those within `.env` files are never bundled.
+const INCLUDED: [&str; 4] = ["Cargo.toml", "Cargo.lock", "rust-toolchain", "rust-toolchain.toml"];
+
+/// A minimal reproduction of an issue with a contract: the failing dry-run of a call, along with
+/// the toolchain with which the contract was built.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Repro {
+	/// The version of the format.
+	pub version: u32,
+	/// The versions of the tools with which the contract was built.
+	pub toolchain: Toolchain,
+	/// The call which failed.
+	pub call: ReproCall,
+	/// The output of the failing dry-run.
+	pub trace: String,
+}
+
+/// The versions of the tools with which a contract was built, as recorded within its metadata.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Toolchain {
+	/// The language in which the contract is written (e.g. "ink! 5.0.0").
+	pub language: String,
+	/// The compiler used to compile the contract (e.g. "rustc 1.81.0").
+	pub compiler: String,
+	/// The version of pop with which the bundle was exported.
+	pub pop: String,
+	/// Any further information about the build environment, such as the Rust toolchain and the
+	/// version of `cargo-contract`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub build_info: Option<Map<String, Value>>,
+}
+
+/// The input of a call to a contract.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReproCall {
+	/// The address of the contract called.
+	pub contract: String,
+	/// The name of the contract message called.
+	pub message: String,
+	/// The message arguments.
+	pub args: Vec<String>,
+	/// The balance transferred to the contract.
+	pub value: String,
+	/// The maximum amount of gas specified, if any.
+	pub gas_limit: Option<u64>,
+	/// The maximum proof size specified, if any.
+	pub proof_size: Option<u64>,
+	/// The websocket endpoint of the node called.
+	pub url: String,
+}
+
+impl Repro {
+	/// Describes the failing dry-run of a call to a contract, using the toolchain recorded within
+	/// the artifacts of the contract project.
+	///
+	/// # Arguments
+	/// * `project` - The path of the contract project, which must be built.
+	/// * `pop` - The version of pop with which the bundle is exported.
+	/// * `call` - The call which failed.
+	/// * `trace` - The output of the failing dry-run.
+	pub fn new(project: &Path, pop: &str, call: ReproCall, trace: &str) -> Result<Self, Error> {
+		let source = ContractArtifacts::from_manifest_or_file(None, Some(&artifact(project)?))?
+			.metadata()?
+			.source;
+		Ok(Self {
+			version: VERSION,
+			toolchain: Toolchain {
+				language: source.language.to_string(),
+				compiler: source.compiler.to_string(),
+				pop: pop.to_string(),
+				build_info: source.build_info,
+			},
+			call,
+			trace: trace.to_string(),
+		})
+	}
+}
+
+/// Exports a reproduction bundle: a compressed tar archive containing the source of the contract
+/// project (its manifests, toolchain file and Rust sources), its artifact and the description of
+/// the failing call, so that it can be imported and replayed elsewhere. Returns the files of the
+/// project included.
+///
+/// # Arguments
+/// * `archive` - The path of the bundle to be written.
+/// * `project` - The path of the contract project, which must be built.
+/// * `repro` - The description of the issue.
+pub fn export_repro(archive: &Path, project: &Path, repro: &Repro) -> Result<Vec<PathBuf>, Error> {
+	let artifact = artifact(project)?;
+	let mut builder = Builder::new(GzEncoder::new(File::create(archive)?, Compression::default()));
+	let mut files = vec![];
+	let entries = WalkDir::new(project).sort_by_file_name().into_iter();
+	for entry in entries.filter_entry(|e| !IGNORED.iter().any(|i| e.file_name() == *i)).flatten() {
+		let path = entry.path();
+		let included = INCLUDED.iter().any(|i| entry.file_name() == *i) ||
+			path.extension().is_some_and(|e| e == "rs");
+		if !entry.file_type().is_file() || !included {
+			continue;
+		}
+		let file = path.strip_prefix(project).unwrap_or(path).to_path_buf();
+		builder.append_path_with_name(path, Path::new("source").join(&file))?;
+		files.push(file);
+	}
+	let name = artifact.file_name().unwrap_or_default();
+	builder.append_path_with_name(&artifact, Path::new("source/target/ink").join(name))?;
+
+	let manifest = serde_json::to_vec_pretty(repro).map_err(|e| Error::Repro(e.to_string()))?;
+	let mut header = Header::new_gnu();
+	header.set_size(manifest.len() as u64);
+	header.set_mode(0o644);
+	header.set_cksum();
+	builder.append_data(&mut header, MANIFEST, manifest.as_slice())?;
+	builder.into_inner()?.finish()?;
+	Ok(files)
+}
+
+/// Imports a reproduction bundle created by [`export_repro`], unpacking the contract project
+/// (along with its artifact) within the destination so that the call can be replayed. Only
+/// regular files and directories are unpacked, with bundles containing links rejected.
+///
+/// # Arguments
+/// * `archive` - The path of the bundle.
+/// * `destination` - The directory in which the contract project is unpacked.
+pub fn import_repro(archive: &Path, destination: &Path) -> Result<Repro, Error> {
+	let mut repro = None;
+	let mut bundle = Archive::new(GzDecoder::new(File::open(archive)?));
+	for entry in bundle.entries()? {
+		let mut entry = entry?;
+		let path = entry.path()?.into_owned();
+		if !matches!(entry.header().entry_type(), EntryType::Regular | EntryType::Directory) {
+			return Err(Error::Repro(format!("unsupported entry: {}", path.display())));
+		}
+		if path == Path::new(MANIFEST) {
+			let mut contents = String::new();
+			entry.read_to_string(&mut contents)?;
+			let manifest: Repro = serde_json::from_str(&contents)
+				.map_err(|e| Error::Repro(format!("invalid manifest: {e}")))?;
+			if manifest.version != VERSION {
+				return Err(Error::Repro(format!("unsupported version: {}", manifest.version)));
+			}
+			repro = Some(manifest);
+		} else if let Ok(file) = path.strip_prefix("source") {
+			if file.as_os_str().is_empty() ||
+				!file.components().all(|c| matches!(c, Component::Normal(_)))
+			{
+				return Err(Error::Repro(format!("invalid path: {}", path.display())));
+			}
+			let file = destination.join(file);
+			if let Some(parent) = file.parent() {
+				create_dir_all(parent)?;
+			}
+			entry.unpack(file)?;
+		}
+	}
+	repro.ok_or_else(|| Error::Repro("the manifest is missing".into()))
+}
+
+/// The artifact of a built contract project.
+fn artifact(project: &Path) -> Result<PathBuf, Error> {
+	let ink = project.join("target/ink");
+	let not_built = || Error::Repro(format!("no artifact found within {}", ink.display()));
+	let mut artifacts: Vec<_> = read_dir(&ink)
+		.map_err(|_| not_built())?
+		.flatten()
+		.map(|e| e.path())
+		.filter(|p| p.extension().is_some_and(|e| e == "contract"))
+		.collect();
+	artifacts.sort();
+	artifacts.into_iter().next().ok_or_else(not_built)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::{env, fs};
+
+	#[test]
+	fn export_and_import_repro_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("testing");
+		fs::create_dir_all(project.join("target/ink"))?;
+		fs::create_dir_all(project.join(".git"))?;
+		fs::write(project.join("Cargo.toml"), "[package]\nname = \"testing\"\n")?;
+		fs::write(project.join("lib.rs"), "#[ink::contract]\nmod testing {}\n")?;
+		fs::write(project.join(".git/HEAD"), "ref: refs/heads/main\n")?;
+		fs::write(project.join(".env"), "SURI=//Secret\n")?;
+		fs::write(project.join("notes.txt"), "")?;
+		let archive = temp_dir.path().join("repro.tar.gz");
+		let call = ReproCall {
+			contract: "5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A".into(),
+			message: "flip".into(),
+			args: vec![],
+			value: "0".into(),
+			gas_limit: None,
+			proof_size: Some(1_000),
+			url: "ws://localhost:9944".into(),
+		};
+		assert!(matches!(Repro::new(&project, "0.4.0", call.clone(), ""), Err(Error::Repro(_))));
+
+		fs::copy(
+			env::current_dir()?.join("tests/files/testing.contract"),
+			project.join("target/ink/testing.contract"),
+		)?;
+		let repro = Repro::new(&project, "0.4.0", call, "ContractTrapped")?;
+		assert_eq!(repro.toolchain.pop, "0.4.0");
+		assert!(repro.toolchain.language.starts_with("ink!"));
+		assert!(repro.toolchain.compiler.starts_with("rustc"));
+		assert_eq!(
+			export_repro(&archive, &project, &repro)?,
+			[PathBuf::from("Cargo.toml"), PathBuf::from("lib.rs")]
+		);
+
+		let destination = temp_dir.path().join("imported");
+		assert_eq!(import_repro(&archive, &destination)?, repro);
+		assert!(destination.join("Cargo.toml").exists());
+		assert!(destination.join("lib.rs").exists());
+		assert!(destination.join("target/ink/testing.contract").exists());
+		assert!(!destination.join(".git").exists());
+		assert!(!destination.join(".env").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn import_repro_rejects_links() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let archive = temp_dir.path().join("repro.tar.gz");
+		for entry_type in [EntryType::Symlink, EntryType::Link] {
+			let mut builder =
+				Builder::new(GzEncoder::new(File::create(&archive)?, Compression::default()));
+			let mut header = Header::new_gnu();
+			header.set_entry_type(entry_type);
+			header.set_size(0);
+			builder.append_link(&mut header, "source/lib.rs", "/etc/passwd")?;
+			builder.into_inner()?.finish()?;
+			assert!(matches!(
+				import_repro(&archive, &temp_dir.path().join("imported")),
+				Err(Error::Repro(e)) if e == "unsupported entry: source/lib.rs"
+			));
+		}
+		assert!(!temp_dir.path().join("imported/lib.rs").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn import_repro_fails_without_manifest() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let archive = temp_dir.path().join("repro.tar.gz");
+		Builder::new(GzEncoder::new(File::create(&archive)?, Compression::default()))
+			.into_inner()?
+			.finish()?;
+		assert!(matches!(
+			import_repro(&archive, temp_dir.path()),
+			Err(Error::Repro(e)) if e == "the manifest is missing"
+		));
+		Ok(())
+	}
+}
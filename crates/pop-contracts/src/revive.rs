@@ -0,0 +1,508 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use contract_extrinsics::{CallExec, Code, InstantiateExec, UploadExec};
+use ink_env::DefaultEnvironment;
+use pop_common::{encode_hex, Account};
+use sp_core::keccak_256;
+use sp_weights::Weight;
+use subxt::{
+	blocks::ExtrinsicEvents,
+	dynamic::{At, Value},
+	ext::scale_value::ValueDef,
+	tx::DynamicPayload,
+	Metadata, OnlineClient, PolkadotConfig as DefaultConfig,
+};
+use subxt_signer::sr25519::Keypair;
+
+/// The name of `pallet-revive` within the metadata of a runtime.
+const PALLET: &str = "Revive";
+/// The name of the runtime API of `pallet-revive`.
+const API: &str = "ReviveApi";
+/// The flag set by a contract reverting its execution.
+const REVERT: u128 = 1;
+/// The magic number with which Wasm modules begin.
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// The outcome of a dry run of a contract via `pallet-revive`.
+pub(crate) struct DryRun {
+	/// The weight required.
+	pub(crate) gas_required: Weight,
+	/// The storage deposit charged.
+	pub(crate) storage_deposit: u128,
+	/// The data returned by the contract, or the reason for the failure.
+	pub(crate) result: Result<Vec<u8>, String>,
+}
+
+/// Simulates the instantiation of a contract, as prepared by the contract tooling for
+/// `pallet-contracts`, via `ReviveApi::instantiate`.
+///
+/// # Arguments
+/// * `exec` - The preprocessed data to instantiate a contract.
+pub(crate) async fn instantiate_dry_run(
+	exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> Result<DryRun, Error> {
+	let args = exec.args();
+	let code = match args.code() {
+		Code::Upload(code) => {
+			ensure_polkavm(code)?;
+			Value::unnamed_variant("Upload", [Value::from_bytes(code)])
+		},
+		Code::Existing(hash) => Value::unnamed_variant("Existing", [Value::from_bytes(hash)]),
+	};
+	let result = dry_run(
+		exec.client(),
+		"instantiate",
+		vec![
+			origin(exec.opts().signer()),
+			Value::u128(args.value()),
+			none(),
+			option(exec.opts().storage_deposit_limit().map(Value::u128)),
+			code,
+			Value::from_bytes(args.data()),
+			option(salt(args.salt())?),
+		],
+	)
+	.await?;
+	let metadata = exec.client().metadata();
+	outcome(&result, |error| dispatch_error(error, &metadata))
+}
+
+/// Instantiates a contract, as prepared by the contract tooling for `pallet-contracts`, returning
+/// the address of the contract.
+///
+/// # Arguments
+/// * `exec` - The preprocessed data to instantiate a contract.
+/// * `gas_limit` - The maximum amount of gas to be used.
+pub(crate) async fn instantiate(
+	exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	gas_limit: Weight,
+) -> Result<String, Error> {
+	let args = exec.args();
+	let storage_deposit_limit = match exec.opts().storage_deposit_limit() {
+		Some(limit) => limit,
+		None => instantiate_dry_run(exec).await?.storage_deposit,
+	};
+	let (call, code) = match args.code() {
+		Code::Upload(code) => {
+			ensure_polkavm(code)?;
+			("instantiate_with_code", Value::from_bytes(code))
+		},
+		Code::Existing(hash) => ("instantiate", Value::from_bytes(hash)),
+	};
+	let payload = subxt::dynamic::tx(
+		PALLET,
+		call,
+		vec![
+			Value::u128(args.value()),
+			weight(gas_limit),
+			Value::u128(storage_deposit_limit),
+			code,
+			Value::from_bytes(args.data()),
+			option(salt(args.salt())?),
+		],
+	);
+	let events = submit(exec.client(), &payload, exec.opts().signer())
+		.await
+		.map_err(|e| Error::InstantiateContractError(e.to_string()))?;
+	let address = event(&events, "Instantiated", "contract")
+		.and_then(|address| <[u8; 20]>::try_from(address).ok())
+		.ok_or_else(|| {
+			Error::InstantiateContractError("the contract address was not found".into())
+		})?;
+	Ok(Account::Id20(address).to_string())
+}
+
+/// Simulates the upload of contract code via `ReviveApi::upload_code`, returning the hash of the
+/// code and the storage deposit charged.
+///
+/// # Arguments
+/// * `exec` - The preprocessed data to upload a contract.
+pub(crate) async fn upload_dry_run(
+	exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> Result<(String, u128), Error> {
+	let result = dry_run(
+		exec.client(),
+		"upload_code",
+		vec![
+			origin(exec.opts().signer()),
+			Value::from_bytes(code(exec)?),
+			option(exec.opts().storage_deposit_limit().map(Value::u128)),
+		],
+	)
+	.await?;
+	match &result.value {
+		ValueDef::Variant(variant) if variant.name == "Ok" => {
+			let code_hash = variant.values.at(0).at("code_hash").and_then(bytes);
+			let deposit = variant.values.at(0).at("deposit").and_then(|d| d.as_u128());
+			match (code_hash, deposit) {
+				(Some(code_hash), Some(deposit)) => Ok((encode_hex(&code_hash), deposit)),
+				_ => Err(undecodable()),
+			}
+		},
+		ValueDef::Variant(variant) if variant.name == "Err" =>
+			Err(Error::DryRunUploadContractError(dispatch_error(
+				variant.values.at(0),
+				&exec.client().metadata(),
+			))),
+		_ => Err(undecodable()),
+	}
+}
+
+/// Uploads contract code, returning the hash of the code.
+///
+/// # Arguments
+/// * `exec` - The preprocessed data to upload a contract.
+pub(crate) async fn upload(
+	exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> Result<String, Error> {
+	let code = code(exec)?;
+	let storage_deposit_limit = match exec.opts().storage_deposit_limit() {
+		Some(limit) => limit,
+		None => upload_dry_run(exec).await?.1,
+	};
+	let payload = subxt::dynamic::tx(
+		PALLET,
+		"upload_code",
+		vec![Value::from_bytes(&code), Value::u128(storage_deposit_limit)],
+	);
+	let events = submit(exec.client(), &payload, exec.opts().signer())
+		.await
+		.map_err(|e| Error::UploadContractError(e.to_string()))?;
+	match event(&events, "CodeStored", "code_hash") {
+		Some(code_hash) => Ok(encode_hex(&code_hash)),
+		None => Err(Error::UploadContractError(format!(
+			"This contract has already been uploaded with code hash: {}",
+			encode_hex(&keccak_256(&code))
+		))),
+	}
+}
+
+/// Simulates a call of a contract, as prepared by the contract tooling for `pallet-contracts`,
+/// via `ReviveApi::call`.
+///
+/// # Arguments
+/// * `exec` - The preprocessed data to call a contract.
+pub(crate) async fn call_dry_run(
+	exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> Result<DryRun, Error> {
+	let result = dry_run(
+		exec.client(),
+		"call",
+		vec![
+			origin(exec.opts().signer()),
+			Value::from_bytes(address(exec)),
+			Value::u128(*exec.value()),
+			none(),
+			option(exec.opts().storage_deposit_limit().map(Value::u128)),
+			Value::from_bytes(exec.call_data()),
+		],
+	)
+	.await?;
+	let metadata = exec.client().metadata();
+	outcome(&result, |error| dispatch_error(error, &metadata))
+}
+
+/// Calls a contract, returning the events of the call.
+///
+/// # Arguments
+/// * `exec` - The preprocessed data to call a contract.
+/// * `gas_limit` - The maximum amount of gas to be used.
+pub(crate) async fn call(
+	exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	gas_limit: Weight,
+) -> Result<ExtrinsicEvents<DefaultConfig>, Error> {
+	let storage_deposit_limit = match exec.opts().storage_deposit_limit() {
+		Some(limit) => limit,
+		None => call_dry_run(exec).await?.storage_deposit,
+	};
+	let payload = subxt::dynamic::tx(
+		PALLET,
+		"call",
+		vec![
+			Value::from_bytes(address(exec)),
+			Value::u128(*exec.value()),
+			weight(gas_limit),
+			Value::u128(storage_deposit_limit),
+			Value::from_bytes(exec.call_data()),
+		],
+	);
+	submit(exec.client(), &payload, exec.opts().signer())
+		.await
+		.map_err(|e| Error::CallContractError(e.to_string()))
+}
+
+/// Calls a method of the runtime API of `pallet-revive`.
+async fn dry_run(
+	client: &OnlineClient<DefaultConfig>,
+	method: &str,
+	args: Vec<Value>,
+) -> Result<Value<u32>, Error> {
+	let payload = subxt::dynamic::runtime_api_call(API, method, args);
+	let result = client
+		.runtime_api()
+		.at_latest()
+		.await?
+		.call(payload)
+		.await?
+		.to_value()
+		.map_err(subxt::Error::from)?;
+	Ok(result)
+}
+
+/// Signs and submits an extrinsic, waiting for it to be finalized successfully.
+async fn submit(
+	client: &OnlineClient<DefaultConfig>,
+	payload: &DynamicPayload,
+	signer: &Keypair,
+) -> Result<ExtrinsicEvents<DefaultConfig>, subxt::Error> {
+	client
+		.tx()
+		.sign_and_submit_then_watch_default(payload, signer)
+		.await?
+		.wait_for_finalized_success()
+		.await
+}
+
+/// Decodes the result of a dry run of an instantiation or call, whose return value is flagged
+/// when the contract reverted.
+///
+/// # Arguments
+/// * `result` - The decoded result of the dry run.
+/// * `describe` - Describes the dispatch error of a failed dry run.
+fn outcome(
+	result: &Value<u32>,
+	describe: impl FnOnce(Option<&Value<u32>>) -> String,
+) -> Result<DryRun, Error> {
+	let gas = |field: &str| result.at("gas_required").at(field).and_then(|v| v.as_u128());
+	let (Some(ref_time), Some(proof_size)) = (gas("ref_time"), gas("proof_size")) else {
+		return Err(undecodable());
+	};
+	let storage_deposit = match result.at("storage_deposit").map(|d| &d.value) {
+		Some(ValueDef::Variant(deposit)) if deposit.name == "Charge" =>
+			deposit.values.at(0).and_then(|d| d.as_u128()).ok_or_else(undecodable)?,
+		_ => 0,
+	};
+	let result = match result.at("result").map(|r| &r.value) {
+		Some(ValueDef::Variant(variant)) if variant.name == "Ok" => {
+			let value = variant.values.at(0);
+			// The value returned by an instantiation is nested alongside the contract address.
+			let value = value.at("result").or(value);
+			let flags = value.at("flags").at("bits").and_then(|f| f.as_u128()).unwrap_or_default();
+			let data = value.at("data").and_then(bytes).unwrap_or_default();
+			if flags & REVERT == REVERT {
+				Err(format!("the contract reverted (returning {})", encode_hex(&data)))
+			} else {
+				Ok(data)
+			}
+		},
+		Some(ValueDef::Variant(variant)) if variant.name == "Err" =>
+			Err(describe(variant.values.at(0))),
+		_ => return Err(undecodable()),
+	};
+	Ok(DryRun {
+		gas_required: Weight::from_parts(ref_time as u64, proof_size as u64),
+		storage_deposit,
+		result,
+	})
+}
+
+/// Describes a dispatch error, naming the pallet and variant of module errors.
+fn dispatch_error(error: Option<&Value<u32>>, metadata: &Metadata) -> String {
+	let Some(error) = error else { return "unknown error".into() };
+	if let ValueDef::Variant(variant) = &error.value {
+		if variant.name == "Module" {
+			let module = variant.values.at(0);
+			let index = module.at("index").and_then(|i| i.as_u128());
+			let error = module.at("error").at(0).and_then(|e| e.as_u128());
+			if let Some((pallet, error)) = index.zip(error).and_then(|(index, error)| {
+				let pallet = metadata.pallet_by_index(index as u8)?;
+				Some((pallet.name(), &pallet.error_variant_by_index(error as u8)?.name))
+			}) {
+				return format!("{pallet}::{error}");
+			}
+		}
+	}
+	error.to_string()
+}
+
+/// Finds a field of the first event of `pallet-revive` with the given name, as bytes.
+fn event(events: &ExtrinsicEvents<DefaultConfig>, name: &str, field: &str) -> Option<Vec<u8>> {
+	events
+		.iter()
+		.filter_map(Result::ok)
+		.filter(|e| e.pallet_name() == PALLET && e.variant_name() == name)
+		.find_map(|e| e.field_values().ok()?.at(field).and_then(bytes))
+}
+
+/// The bytes of a decoded value, which may be nested within composites.
+fn bytes<T>(value: &Value<T>) -> Option<Vec<u8>> {
+	fn collect<T>(value: &Value<T>, out: &mut Vec<u8>) -> Option<()> {
+		match &value.value {
+			ValueDef::Composite(composite) => composite.values().try_for_each(|v| collect(v, out)),
+			_ => {
+				out.push(u8::try_from(value.as_u128()?).ok()?);
+				Some(())
+			},
+		}
+	}
+	let mut out = vec![];
+	collect(value, &mut out)?;
+	Some(out)
+}
+
+/// The code of a contract to be uploaded, from its artifacts.
+fn code(exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>) -> Result<Vec<u8>, Error> {
+	let code = exec
+		.opts()
+		.contract_artifacts()?
+		.metadata()?
+		.source
+		.wasm
+		.map(|code| code.0)
+		.ok_or_else(|| Error::UploadContractError("the contract code was not found".into()))?;
+	ensure_polkavm(&code)?;
+	Ok(code)
+}
+
+/// Checks that the code of a contract can be deployed via `pallet-revive`, which only accepts
+/// PolkaVM (RISC-V) code and rejects the Wasm code of contracts built for `pallet-contracts`.
+///
+/// # Arguments
+/// * `code` - The code of the contract.
+pub(crate) fn ensure_polkavm(code: &[u8]) -> Result<(), Error> {
+	if code.starts_with(WASM_MAGIC) {
+		return Err(Error::UnsupportedChain(
+			"the contract is compiled to Wasm, whereas `pallet-revive` only accepts PolkaVM code: the contract must be compiled for PolkaVM".into(),
+		));
+	}
+	Ok(())
+}
+
+/// The 20-byte address of the contract being called, held by the contract tooling as a 32-byte
+/// account.
+fn address(exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>) -> [u8; 20] {
+	Account::Id32(exec.contract().0).to_id20()
+}
+
+/// The account of a signer.
+fn origin(signer: &Keypair) -> Value {
+	Value::from_bytes(signer.public_key().to_account_id().0)
+}
+
+/// The salt of an instantiation, which `pallet-revive` requires to be 32 bytes when provided.
+fn salt(salt: &[u8]) -> Result<Option<Value>, Error> {
+	match salt.len() {
+		0 => Ok(None),
+		32 => Ok(Some(Value::from_bytes(salt))),
+		len => Err(Error::InstantiateContractError(format!(
+			"the salt is {len} bytes, whereas `pallet-revive` requires a salt of 32 bytes"
+		))),
+	}
+}
+
+fn weight(weight: Weight) -> Value {
+	Value::named_composite([
+		("ref_time", Value::u128(weight.ref_time() as u128)),
+		("proof_size", Value::u128(weight.proof_size() as u128)),
+	])
+}
+
+fn option(value: Option<Value>) -> Value {
+	match value {
+		Some(value) => Value::unnamed_variant("Some", [value]),
+		None => none(),
+	}
+}
+
+fn none() -> Value {
+	Value::unnamed_variant("None", [])
+}
+
+fn undecodable() -> Error {
+	Error::UnsupportedChain(format!("unable to decode the result of `{API}`"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bytes_works() {
+		let address = Value::unnamed_composite([Value::from_bytes([1u8; 20])]);
+		assert_eq!(bytes(&address), Some(vec![1; 20]));
+		assert_eq!(bytes(&Value::u128(256)), None);
+	}
+
+	#[test]
+	fn ensure_polkavm_works() {
+		assert!(ensure_polkavm(b"PVM\0\x01").is_ok());
+		assert!(matches!(
+			ensure_polkavm(b"\0asm\x01\0\0\0"),
+			Err(Error::UnsupportedChain(e)) if e.contains("must be compiled for PolkaVM")
+		));
+	}
+
+	#[test]
+	fn salt_works() -> Result<(), Error> {
+		assert!(salt(&[])?.is_none());
+		assert!(salt(&[0; 32])?.is_some());
+		assert!(matches!(
+			salt(&[0]),
+			Err(Error::InstantiateContractError(e)) if e.contains("the salt is 1 bytes")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn outcome_works() -> Result<(), Error> {
+		let result = |result: Value| {
+			Value::named_composite([
+				(
+					"gas_required",
+					Value::named_composite([
+						("ref_time", Value::u128(1_000)),
+						("proof_size", Value::u128(10)),
+					]),
+				),
+				("storage_deposit", Value::unnamed_variant("Charge", [Value::u128(5)])),
+				("result", result),
+			])
+			.map_context(|_| 0u32)
+		};
+		let returned = |flags: u128| {
+			Value::unnamed_variant(
+				"Ok",
+				[Value::named_composite([
+					("flags", Value::named_composite([("bits", Value::u128(flags))])),
+					("data", Value::from_bytes([1, 2])),
+				])],
+			)
+		};
+		let describe = |_: Option<&Value<u32>>| "Revive::ContractTrapped".to_string();
+
+		let dry_run = outcome(&result(returned(0)), describe)?;
+		assert_eq!(dry_run.gas_required, Weight::from_parts(1_000, 10));
+		assert_eq!(dry_run.storage_deposit, 5);
+		assert_eq!(dry_run.result, Ok(vec![1, 2]));
+		// The value returned by an instantiation is nested alongside the contract address.
+		let instantiated = Value::unnamed_variant(
+			"Ok",
+			[Value::named_composite([
+				("result", returned(REVERT).at(0).cloned().unwrap()),
+				("addr", Value::from_bytes([0u8; 20])),
+			])],
+		);
+		assert_eq!(
+			outcome(&result(instantiated), describe)?.result,
+			Err("the contract reverted (returning 0x0102)".into())
+		);
+		let failed = Value::unnamed_variant("Err", [Value::unnamed_variant("Module", [])]);
+		assert_eq!(outcome(&result(failed), describe)?.result, Err(describe(None)));
+		assert!(matches!(
+			outcome(&Value::u128(0).map_context(|_| 0u32), describe),
+			Err(Error::UnsupportedChain(_))
+		));
+		Ok(())
+	}
+}
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0
 use crate::{
 	errors::Error,
+	metadata::MetadataRegistry,
+	pallet::{chain_contracts, metadata_contracts_pallet, ContractsPallet},
+	revive,
 	utils::{
 		helpers::{get_manifest_path, parse_balance},
 		signer::create_signer,
@@ -14,7 +17,7 @@ use ink_env::{DefaultEnvironment, Environment};
 use sp_core::Bytes;
 use sp_weights::Weight;
 use std::{fmt::Write, path::PathBuf};
-use subxt::PolkadotConfig as DefaultConfig;
+use subxt::{OnlineClient, PolkadotConfig as DefaultConfig};
 use subxt_signer::sr25519::Keypair;
 
 /// Attributes for the `up` command
@@ -24,7 +27,7 @@ pub struct UpOpts {
 	pub path: Option<PathBuf>,
 	/// The name of the contract constructor to call.
 	pub constructor: String,
-	/// The constructor arguments, encoded as strings.
+	/// The constructor arguments, in a JSON5 or Rust struct-literal style.
 	pub args: Vec<String>,
 	/// Transfers an initial balance to the instantiated contract.
 	pub value: String,
@@ -50,6 +53,8 @@ pub async fn set_up_deployment(
 	up_opts: UpOpts,
 ) -> anyhow::Result<InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>> {
 	let manifest_path = get_manifest_path(up_opts.path.as_deref())?;
+	// Fail early when the chain cannot host contracts.
+	chain_contracts(&up_opts.url).await?.ensure_supported()?;
 
 	let token_metadata = TokenMetadata::query::<DefaultConfig>(&up_opts.url).await?;
 
@@ -62,10 +67,14 @@ pub async fn set_up_deployment(
 	let value: BalanceVariant<<DefaultEnvironment as Environment>::Balance> =
 		parse_balance(&up_opts.value)?;
 
+	let args = MetadataRegistry::global()
+		.load(up_opts.path.as_deref())?
+		.encode_args(&up_opts.constructor, &up_opts.args)?;
+
 	let instantiate_exec: InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair> =
 		InstantiateCommandBuilder::new(extrinsic_opts)
 			.constructor(up_opts.constructor.clone())
-			.args(up_opts.args.clone())
+			.args(args)
 			.value(value.denominate_balance(&token_metadata)?)
 			.gas_limit(up_opts.gas_limit)
 			.proof_size(up_opts.proof_size)
@@ -84,6 +93,8 @@ pub async fn set_up_upload(
 	up_opts: UpOpts,
 ) -> anyhow::Result<UploadExec<DefaultConfig, DefaultEnvironment, Keypair>> {
 	let manifest_path = get_manifest_path(up_opts.path.as_deref())?;
+	// Fail early when the chain cannot host contracts.
+	chain_contracts(&up_opts.url).await?.ensure_supported()?;
 
 	let signer = create_signer(&up_opts.suri)?;
 	let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
@@ -105,6 +116,15 @@ pub async fn set_up_upload(
 pub async fn dry_run_gas_estimate_instantiate(
 	instantiate_exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
 ) -> Result<Weight, Error> {
+	if is_revive(instantiate_exec.client()) {
+		let dry_run = revive::instantiate_dry_run(instantiate_exec).await?;
+		dry_run.result.map_err(Error::DryRunUploadContractError)?;
+		let args = instantiate_exec.args();
+		return Ok(Weight::from_parts(
+			args.gas_limit().unwrap_or_else(|| dry_run.gas_required.ref_time()),
+			args.proof_size().unwrap_or_else(|| dry_run.gas_required.proof_size()),
+		));
+	}
 	let instantiate_result = instantiate_exec.instantiate_dry_run().await?;
 	match instantiate_result.result {
 		Ok(_) => {
@@ -141,6 +161,10 @@ pub struct UploadDryRunResult {
 pub async fn dry_run_upload(
 	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
 ) -> Result<UploadDryRunResult, Error> {
+	if is_revive(upload_exec.client()) {
+		let (code_hash, deposit) = revive::upload_dry_run(upload_exec).await?;
+		return Ok(UploadDryRunResult { code_hash, deposit: deposit.to_string() });
+	}
 	match upload_exec.upload_code_rpc().await? {
 		Ok(result) => {
 			let upload_result = UploadDryRunResult {
@@ -167,6 +191,9 @@ pub async fn instantiate_smart_contract(
 	instantiate_exec: InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
 	gas_limit: Weight,
 ) -> anyhow::Result<String, Error> {
+	if is_revive(instantiate_exec.client()) {
+		return revive::instantiate(&instantiate_exec, gas_limit).await;
+	}
 	let instantiate_result = instantiate_exec
 		.instantiate(Some(gas_limit))
 		.await
@@ -182,6 +209,9 @@ pub async fn instantiate_smart_contract(
 pub async fn upload_smart_contract(
 	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
 ) -> anyhow::Result<String, Error> {
+	if is_revive(upload_exec.client()) {
+		return revive::upload(upload_exec).await;
+	}
 	let upload_result = upload_exec
 		.upload_code()
 		.await
@@ -200,6 +230,12 @@ pub async fn upload_smart_contract(
 	}
 }
 
+/// Whether a chain hosts contracts via `pallet-revive`, to which the contract tooling, built for
+/// `pallet-contracts`, is adapted.
+pub(crate) fn is_revive(client: &OnlineClient<DefaultConfig>) -> bool {
+	metadata_contracts_pallet(&client.metadata()) == Some(ContractsPallet::Revive)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -8,7 +8,6 @@ use std::{
 	path::{Path, PathBuf},
 	str::FromStr,
 };
-use subxt::{Config, PolkadotConfig as DefaultConfig};
 
 pub fn get_manifest_path(path: Option<&Path>) -> Result<ManifestPath, Error> {
 	if let Some(path) = path {
@@ -27,11 +26,6 @@ pub fn parse_balance(
 	BalanceVariant::from_str(balance).map_err(|e| Error::BalanceParsing(format!("{}", e)))
 }
 
-pub fn parse_account(account: &str) -> Result<<DefaultConfig as Config>::AccountId, Error> {
-	<DefaultConfig as Config>::AccountId::from_str(account)
-		.map_err(|e| Error::AccountAddressParsing(format!("{}", e)))
-}
-
 /// Canonicalizes the given path to ensure consistency and resolve any symbolic links.
 ///
 /// # Arguments
@@ -93,20 +87,4 @@ mod tests {
 		assert!(matches!(parse_balance("wrongbalance"), Err(super::Error::BalanceParsing(..))));
 		Ok(())
 	}
-
-	#[test]
-	fn parse_account_works() -> Result<(), Error> {
-		let account = parse_account("5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A")?;
-		assert_eq!(account.to_string(), "5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A");
-		Ok(())
-	}
-
-	#[test]
-	fn parse_account_fails_wrong_value() -> Result<(), Error> {
-		assert!(matches!(
-			parse_account("wrongaccount"),
-			Err(super::Error::AccountAddressParsing(..))
-		));
-		Ok(())
-	}
 }
@@ -3,14 +3,15 @@
 use crate::errors::Error;
 use contract_build::util::decode_hex;
 use sp_core::Bytes;
-use subxt_signer::{sr25519::Keypair, SecretUri};
+use subxt_signer::sr25519::Keypair;
 
-/// Create a Signer from a secret URI.
+/// Create a Signer from a secret URI, using the signer shared with the other crates.
 pub(crate) fn create_signer(suri: &str) -> Result<Keypair, Error> {
-	let uri = <SecretUri as std::str::FromStr>::from_str(suri)
-		.map_err(|e| Error::ParseSecretURI(format!("{}", e)))?;
-	let keypair = Keypair::from_uri(&uri).map_err(|e| Error::KeyPairCreation(format!("{}", e)))?;
-	Ok(keypair)
+	pop_common::create_signer(suri).map_err(|e| match e {
+		pop_common::Error::ParseSecretURI(e) => Error::ParseSecretURI(e),
+		pop_common::Error::KeyPairCreation(e) => Error::KeyPairCreation(e),
+		e => e.into(),
+	})
 }
 
 /// Parse hex encoded bytes.
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	errors::Error,
+	snapshot::{fork_off, LiveState, Snapshots},
+};
+use duct::cmd;
+use std::path::{Path, PathBuf};
+
+/// The name of the benchmarking binary.
+const BINARY: &str = "frame-omni-bencher";
+/// How the benchmarking binary can be installed.
+const INSTALL: &str = "install it via `cargo install frame-omni-bencher --locked`";
+
+/// Locates the benchmarking binary, either at the specified path or on the `PATH`.
+///
+/// # Arguments
+/// * `binary` - An optional path to the binary.
+pub fn benchmark_binary(binary: Option<&Path>) -> Result<PathBuf, Error> {
+	let binary = binary.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(BINARY));
+	cmd(&binary, ["--version"])
+		.stdout_null()
+		.stderr_null()
+		.run()
+		.map_err(|_| Error::MissingBinary(format!("{}: {INSTALL}", binary.display())))?;
+	Ok(binary)
+}
+
+/// Benchmarks the extrinsics of a pallet against state scraped from a live endpoint, so that
+/// weights reflect realistic data sizes. The live chain is forked off into the genesis state of
+/// a raw chain specification, from a snapshot of its raw storage which is cached and reused by
+/// subsequent benchmarks.
+///
+/// The runtime must be built with the `runtime-benchmarks` feature enabled.
+///
+/// # Arguments
+/// * `binary` - The path to the benchmarking binary.
+/// * `runtime` - The path to the runtime to be benchmarked.
+/// * `pallet` - The name of the pallet to be benchmarked.
+/// * `spec` - The path of the raw chain specification into which the live chain is forked off.
+/// * `state` - The live state.
+/// * `snapshots` - The snapshot cache.
+pub async fn benchmark_live(
+	binary: &Path,
+	runtime: &Path,
+	pallet: &str,
+	spec: &Path,
+	state: &LiveState,
+	snapshots: &Snapshots,
+) -> Result<(), Error> {
+	if !runtime.exists() {
+		return Err(Error::Config(format!("runtime not found: {}", runtime.display())));
+	}
+	let snapshot = snapshots.get_or_scrape(state).await?;
+	let forked = tempfile::NamedTempFile::new()?;
+	fork_off(&snapshot, spec, forked.path())?;
+	cmd(binary, args(runtime, pallet, forked.path())).run()?;
+	Ok(())
+}
+
+/// The arguments used to benchmark a pallet against the genesis state of a chain specification.
+fn args(runtime: &Path, pallet: &str, spec: &Path) -> Vec<String> {
+	[
+		"v1",
+		"benchmark",
+		"pallet",
+		"--runtime",
+		&runtime.display().to_string(),
+		"--pallet",
+		pallet,
+		"--extrinsic",
+		"*",
+		"--genesis-builder",
+		"spec",
+		"--chain",
+		&spec.display().to_string(),
+	]
+	.map(String::from)
+	.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::time::Duration;
+
+	#[test]
+	fn args_works() {
+		assert_eq!(
+			args(Path::new("runtime.wasm"), "pallet_balances", Path::new("forked.json")),
+			[
+				"v1",
+				"benchmark",
+				"pallet",
+				"--runtime",
+				"runtime.wasm",
+				"--pallet",
+				"pallet_balances",
+				"--extrinsic",
+				"*",
+				"--genesis-builder",
+				"spec",
+				"--chain",
+				"forked.json"
+			]
+		);
+	}
+
+	#[test]
+	fn benchmark_binary_fails_when_missing() {
+		assert!(matches!(
+			benchmark_binary(Some(Path::new("/missing/frame-omni-bencher"))),
+			Err(Error::MissingBinary(e)) if e.contains("cargo install")
+		));
+	}
+
+	#[tokio::test]
+	async fn benchmark_live_fails_without_runtime() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::from_secs(60));
+		let state = LiveState { uri: "ws://127.0.0.1:1".into(), at: None, pallets: vec![] };
+		assert!(matches!(
+			benchmark_live(
+				Path::new(BINARY),
+				&temp_dir.path().join("missing.wasm"),
+				"pallet_balances",
+				&temp_dir.path().join("raw.json"),
+				&state,
+				&snapshots
+			)
+			.await,
+			Err(Error::Config(e)) if e.starts_with("runtime not found")
+		));
+		Ok(())
+	}
+}
@@ -1,41 +1,409 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::Error;
+use crate::{collators::CollatorKeys, spec_template::merge, Error};
 use anyhow::Result;
-use duct::cmd;
-use pop_common::{manifest::from_path, Profile};
+use duct::{cmd, Handle};
+use multiaddr::{Multiaddr, Protocol};
+use pop_common::{
+	container::{host_user, Container, Engine},
+	decode_hex, encode_hex,
+	manifest::from_path,
+	remote::Remote,
+	run_cargo, validate_target, Account, BuildReporter, Features, Profile, Resolution,
+};
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::{
+	collections::HashSet,
 	fs,
 	path::{Path, PathBuf},
 	str::FromStr,
+	time::{Duration, Instant},
+};
+use subxt::{
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	ext::codec::Encode,
+	SubstrateConfig,
 };
 
-/// Build the parachain and returns the path to the binary.
+/// The image within which parachains are built when the local toolchain is unavailable.
+pub const BUILD_IMAGE: &str = "docker.io/paritytech/ci-unified";
+/// The tag of the build image, pinned so that the Rust toolchain used by builds is reproducible.
+pub const BUILD_TAG: &str = "bullseye-1.81.0-2024-09-11-v202409111034";
+/// The directory within the build container at which the project is mounted.
+const BUILD_DIR: &str = "/build";
+/// The storage key of the runtime code (`:code`).
+pub(crate) const CODE: &str = "0x3a636f6465";
+/// The code placed at the address of each precompile of an EVM chain, which simply reverts, so
+/// that the precompile is treated as a contract (e.g. by Solidity's `extcodesize` checks).
+pub const EVM_PRECOMPILE_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+/// The artifacts of a parachain build, so that downstream commands need not re-derive them.
+#[derive(Debug, PartialEq)]
+pub struct BuildArtifacts {
+	/// The node binary.
+	pub binary: PathBuf,
+	/// The artifacts of the runtime, if built alongside the node.
+	pub runtime: Option<RuntimeArtifacts>,
+	/// The duration of the build.
+	pub duration: Duration,
+	/// The profile with which the parachain was built.
+	pub profile: Profile,
+	/// The cargo features with which the parachain was built.
+	pub features: Features,
+}
+
+/// The options with which a parachain is built.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildOptions {
+	/// The package to be built, if not the default members of the workspace.
+	pub package: Option<String>,
+	/// The profile the parachain should be built with, either `debug`, `release` or a custom
+	/// profile defined within the manifest.
+	pub profile: Profile,
+	/// The cargo features with which the parachain is built.
+	pub features: Features,
+	/// How the dependencies of the parachain are resolved (e.g. `--locked` or `--offline`).
+	pub resolution: Resolution,
+	/// The target triple to cross-compile for (e.g. "aarch64-unknown-linux-gnu"), if any.
+	pub target: Option<String>,
+	/// The node directory, from which the artifacts are located, if not the `node` subdirectory
+	/// of the project.
+	pub node_path: Option<PathBuf>,
+	/// Whether to build within a container, for when the toolchain required to build locally
+	/// (e.g. `cargo` or the wasm target) is unavailable.
+	pub container: bool,
+}
+
+impl BuildOptions {
+	/// Builds with the specified profile, otherwise using the defaults.
+	///
+	/// # Arguments
+	/// * `profile` - The profile the parachain should be built with.
+	pub fn new(profile: Profile) -> Self {
+		Self {
+			package: None,
+			profile,
+			features: Features::default(),
+			resolution: Resolution::default(),
+			target: None,
+			node_path: None,
+			container: false,
+		}
+	}
+
+	/// The arguments passed to cargo to build a parachain.
+	fn args(&self) -> Vec<String> {
+		let mut args = vec!["build".to_string()];
+		if let Some(package) = &self.package {
+			args.extend(["--package".to_string(), package.to_string()]);
+		}
+		if let Some(target) = &self.target {
+			args.extend(["--target".to_string(), target.to_string()]);
+		}
+		args.extend(self.profile.args());
+		args.extend(self.features.args());
+		args.extend(self.resolution.args());
+		args
+	}
+}
+
+/// Build the parachain and returns its artifacts.
 ///
 /// # Arguments
-/// * `path` - The optional path to the parachain manifest, defaulting to the current directory if
-///   not specified.
-/// * `package` - The optional package to be built.
-/// * `release` - Whether the parachain should be built without any debugging functionality.
-/// * `node_path` - An optional path to the node directory. Defaults to the `node` subdirectory of
-///   the project path if not provided.
+/// * `path` - The path to the parachain project.
+/// * `options` - The options with which the parachain is built.
+/// * `reporter` - Receives the progress of the build, as reported by cargo, unless built within a
+///   container.
 pub fn build_parachain(
 	path: &Path,
-	package: Option<String>,
-	profile: &Profile,
-	node_path: Option<&Path>,
-) -> Result<PathBuf, Error> {
-	let mut args = vec!["build"];
-	if let Some(package) = package.as_deref() {
-		args.push("--package");
-		args.push(package)
+	options: &BuildOptions,
+	reporter: &impl BuildReporter,
+) -> Result<BuildArtifacts, Error> {
+	if options.container {
+		return build_parachain_in_container(path, options);
+	}
+	if let Some(target) = &options.target {
+		validate_target(target)?;
+	}
+	let start = Instant::now();
+	run_cargo(&options.args(), path, reporter)?;
+	build_artifacts(path, options, start)
+}
+
+/// Build the parachain within a container and returns its artifacts. The project is mounted
+/// within the container, so that the artifacts are written to its target directory. Any target
+/// must be installed within the image.
+///
+/// # Arguments
+/// * `path` - The path to the parachain project.
+/// * `options` - The options with which the parachain is built.
+pub fn build_parachain_in_container(
+	path: &Path,
+	options: &BuildOptions,
+) -> Result<BuildArtifacts, Error> {
+	let start = Instant::now();
+	let path = path.canonicalize()?;
+	let args = options.args();
+	let command: Vec<_> = ["cargo"].into_iter().chain(args.iter().map(String::as_str)).collect();
+	build_container(&path).user(host_user()?).stream(Engine::detect()?, &command)?;
+	build_artifacts(&path, options, start)
+}
+
+/// The container within which a parachain project is built. As builds may not run as root, the
+/// cargo home is kept within the mounted target directory.
+///
+/// # Arguments
+/// * `project` - The path to the parachain project.
+fn build_container(project: &Path) -> Container {
+	Container::new(format!("{BUILD_IMAGE}:{BUILD_TAG}"))
+		.mount(project, BUILD_DIR)
+		.env("CARGO_HOME", format!("{BUILD_DIR}/target/.cargo"))
+		.workdir(BUILD_DIR)
+}
+
+/// Locates the artifacts of a parachain build.
+///
+/// # Arguments
+/// * `path` - The path to the parachain project.
+/// * `options` - The options with which the parachain was built.
+/// * `start` - When the build started.
+fn build_artifacts(
+	path: &Path,
+	options: &BuildOptions,
+	start: Instant,
+) -> Result<BuildArtifacts, Error> {
+	let BuildOptions { profile, features, target, node_path, .. } = options;
+	let target_directory = profile.target_directory_for(path, target.as_deref());
+	let binary =
+		binary_path(&target_directory, node_path.as_deref().unwrap_or(&path.join("node")))?;
+	// Projects without a runtime built via `substrate-wasm-builder` only produce a binary.
+	let runtime = locate_runtime(path, None)
+		.ok()
+		.and_then(|(package, _)| runtime_artifacts(&target_directory, Some(&package)).ok());
+	Ok(BuildArtifacts {
+		binary,
+		runtime,
+		duration: start.elapsed(),
+		profile: profile.clone(),
+		features: features.clone(),
+	})
+}
+
+/// Build the parachain on a remote host and returns its artifacts, once the binary and any runtime
+/// are synced back. Any target must be installed on the remote host.
+///
+/// # Arguments
+/// * `remote` - The remote host on which the parachain is built.
+/// * `path` - The path to the parachain project.
+/// * `options` - The options with which the parachain is built.
+pub fn build_parachain_remotely(
+	remote: &Remote,
+	path: &Path,
+	options: &BuildOptions,
+) -> Result<BuildArtifacts, Error> {
+	let start = Instant::now();
+	let BuildOptions { profile, features, target, .. } = options;
+	let args = options.args();
+	let args: Vec<_> = args.iter().map(String::as_str).collect();
+	remote.sync(path)?;
+	remote.run(path, "cargo", &args)?;
+	let target_directory = profile.target_directory_for(path, target.as_deref());
+	let binary = remote_binary(path, options)?;
+	remote.fetch(path, &binary, &path.join(&binary))?;
+	// Projects without a runtime built via `substrate-wasm-builder` only produce a binary.
+	let runtime = locate_runtime(path, None).ok().and_then(|(package, _)| {
+		let name = package.replace('-', "_");
+		let wbuild = profile.target_directory_for(Path::new(""), target.as_deref()).join("wbuild");
+		[format!("{name}.compact.wasm"), format!("{name}.compact.compressed.wasm")]
+			.iter()
+			.try_for_each(|file| {
+				let artifact = wbuild.join(&package).join(file);
+				remote.fetch(path, &artifact, &path.join(&artifact))
+			})
+			.ok()?;
+		runtime_artifacts(&target_directory, Some(&package)).ok()
+	});
+	Ok(BuildArtifacts {
+		binary: path.join(binary),
+		runtime,
+		duration: start.elapsed(),
+		profile: profile.clone(),
+		features: features.clone(),
+	})
+}
+
+/// Launches the node of a parachain built on a remote host, within the project directory on the
+/// remote host, forwarding its RPC port so that it can be reached locally. The node is terminated
+/// along with the returned process.
+///
+/// # Arguments
+/// * `remote` - The remote host on which the parachain was built.
+/// * `path` - The path to the parachain project.
+/// * `options` - The options with which the parachain was built.
+/// * `args` - The arguments with which the node is launched (e.g. `--dev`).
+/// * `rpc_port` - The RPC port of the node.
+pub fn launch_remotely(
+	remote: &Remote,
+	path: &Path,
+	options: &BuildOptions,
+	args: &[&str],
+	rpc_port: u16,
+) -> Result<Handle, Error> {
+	let binary = Path::new(".").join(remote_binary(path, options)?);
+	let port = rpc_port.to_string();
+	let args: Vec<_> = ["--rpc-port", &port].into_iter().chain(args.iter().copied()).collect();
+	Ok(remote.spawn(path, &binary.to_string_lossy(), &args, &[rpc_port])?)
+}
+
+/// The path of the node binary built on a remote host, relative to the project directory.
+///
+/// # Arguments
+/// * `path` - The path to the parachain project.
+/// * `options` - The options with which the parachain is built.
+fn remote_binary(path: &Path, options: &BuildOptions) -> Result<PathBuf, Error> {
+	let BuildOptions { profile, target, node_path, .. } = options;
+	let manifest = from_path(Some(node_path.as_deref().unwrap_or(&path.join("node"))))?;
+	Ok(profile
+		.target_directory_for(Path::new(""), target.as_deref())
+		.join(manifest.package().name()))
+}
+
+/// The artifacts of a runtime, as built by `substrate-wasm-builder`.
+#[derive(Debug, PartialEq)]
+pub struct RuntimeArtifacts {
+	/// The name of the runtime package.
+	pub package: String,
+	/// The compact runtime.
+	pub compact: PathBuf,
+	/// The compact and compressed runtime, as used for deployments and upgrades.
+	pub compressed: PathBuf,
+}
+
+/// Build only the runtime of a parachain, without requiring a node package (e.g. for projects
+/// which run their runtime with the omni-node), and returns the paths to its artifacts.
+///
+/// # Arguments
+/// * `path` - The path to the parachain project.
+/// * `options` - The options with which the runtime is built. The runtime package is located
+///   within the project, unless a package is specified.
+/// * `reporter` - Receives the progress of the build, as reported by cargo, unless built within a
+///   container.
+pub fn build_runtime(
+	path: &Path,
+	options: &BuildOptions,
+	reporter: &impl BuildReporter,
+) -> Result<RuntimeArtifacts, Error> {
+	let package = match &options.package {
+		Some(package) => package.clone(),
+		None => locate_runtime(path, None)?.0,
+	};
+	let options = BuildOptions { package: Some(package.clone()), ..options.clone() };
+	let path = match options.container {
+		true => {
+			let path = path.canonicalize()?;
+			let args = options.args();
+			let command: Vec<_> =
+				["cargo"].into_iter().chain(args.iter().map(String::as_str)).collect();
+			build_container(&path).user(host_user()?).stream(Engine::detect()?, &command)?;
+			path
+		},
+		false => {
+			if let Some(target) = &options.target {
+				validate_target(target)?;
+			}
+			run_cargo(&options.args(), path, reporter)?;
+			path.to_path_buf()
+		},
+	};
+	let target_directory = options.profile.target_directory_for(&path, options.target.as_deref());
+	runtime_artifacts(&target_directory, Some(&package))
+}
+
+/// Locates the runtime of a project, returning the name of its package and its directory. The
+/// runtime is the package built via `substrate-wasm-builder` within the `runtime` directory, the
+/// project itself or the `runtimes` directory.
+///
+/// # Arguments
+/// * `path` - The path to the parachain project.
+/// * `package` - The name of the runtime package, if known.
+pub(crate) fn locate_runtime(
+	path: &Path,
+	package: Option<&str>,
+) -> Result<(String, PathBuf), Error> {
+	let runtimes = fs::read_dir(path.join("runtimes")).into_iter().flatten().flatten();
+	[path.join("runtime"), path.to_path_buf()]
+		.into_iter()
+		.chain(runtimes.map(|e| e.path()))
+		.filter(|dir| dir.join("Cargo.toml").exists())
+		.filter_map(|dir| from_path(Some(&dir)).ok().map(|manifest| (manifest, dir)))
+		.find(|(manifest, _)| {
+			manifest
+				.package
+				.as_ref()
+				.is_some_and(|p| package.is_none_or(|name| p.name() == name)) &&
+				manifest.build_dependencies.contains_key("substrate-wasm-builder")
+		})
+		.map(|(manifest, dir)| (manifest.package().name().to_string(), dir))
+		.ok_or_else(|| match package {
+			Some(package) => Error::Config(format!("the {package} runtime package was not found")),
+			None => Error::Config(
+				"no runtime package found; specify the runtime package to be built".into(),
+			),
+		})
+}
+
+/// Locates the artifacts of a runtime within the target path, as built by `cargo build` via
+/// `substrate-wasm-builder`.
+///
+/// # Arguments
+/// * `target_path` - The path where the build artifacts are expected to be found.
+/// * `package` - The name of the runtime package, otherwise that most recently built.
+pub fn runtime_artifacts(
+	target_path: &Path,
+	package: Option<&str>,
+) -> Result<RuntimeArtifacts, Error> {
+	let wbuild = target_path.join("wbuild");
+	let package = match package {
+		Some(package) => package.to_string(),
+		None => latest_runtime(&wbuild)?,
+	};
+	let package = package.as_str();
+	let dir = wbuild.join(package);
+	let name = package.replace('-', "_");
+	let (compact, compressed) = (
+		dir.join(format!("{name}.compact.wasm")),
+		dir.join(format!("{name}.compact.compressed.wasm")),
+	);
+	if !compact.exists() || !compressed.exists() {
+		return Err(Error::MissingBinary(format!("{package} runtime")));
 	}
-	if matches!(profile, &Profile::Release) {
-		args.push("--release");
+	Ok(RuntimeArtifacts { package: package.to_string(), compact, compressed })
+}
+
+/// The name of the runtime package most recently built within the `wbuild` directory.
+///
+/// # Arguments
+/// * `wbuild` - The directory within which `substrate-wasm-builder` outputs the runtimes.
+fn latest_runtime(wbuild: &Path) -> Result<String, Error> {
+	let mut runtimes = vec![];
+	for entry in fs::read_dir(wbuild).map_err(|_| Error::MissingBinary("runtime".into()))? {
+		let entry = entry?;
+		for file in fs::read_dir(entry.path())?.flatten() {
+			if file
+				.file_name()
+				.to_str()
+				.is_some_and(|f| f.ends_with(".compact.compressed.wasm"))
+			{
+				runtimes.push((file.metadata()?.modified()?, entry.file_name()));
+			}
+		}
 	}
-	cmd("cargo", args).dir(path).run()?;
-	binary_path(&profile.target_directory(path), node_path.unwrap_or(&path.join("node")))
+	runtimes
+		.into_iter()
+		.max_by_key(|(modified, _)| *modified)
+		.map(|(_, package)| package.to_string_lossy().to_string())
+		.ok_or(Error::MissingBinary("runtime".into()))
 }
 
 /// Determines whether the manifest at the supplied path is a supported parachain project.
@@ -75,20 +443,72 @@ pub fn binary_path(target_path: &Path, node_path: &Path) -> Result<PathBuf, Erro
 /// * `binary_path` - The path to the node binary executable that contains the `build-spec` command.
 /// * `plain_chain_spec` - Location of the plain_parachain_spec file to be generated.
 /// * `default_bootnode` - Whether to include localhost as a bootnode.
+/// * `chain` - The chain or genesis preset from which the specification is generated (e.g.
+///   `development` or `local_testnet`), if not the default of the node.
+/// * `relay_chain` - The relay chain to which the parachain connects (e.g. `paseo`), replacing that
+///   hardcoded by the node.
 pub fn generate_plain_chain_spec(
 	binary_path: &Path,
 	plain_chain_spec: &Path,
 	default_bootnode: bool,
+	chain: Option<&str>,
+	relay_chain: Option<&str>,
 ) -> Result<(), Error> {
 	check_command_exists(binary_path, "build-spec")?;
 	let mut args = vec!["build-spec"];
+	if let Some(chain) = chain {
+		args.extend(["--chain", chain]);
+	}
 	if !default_bootnode {
 		args.push("--disable-default-bootnode");
 	}
 	cmd(binary_path, args).stdout_path(plain_chain_spec).stderr_null().run()?;
+	if let Some(relay_chain) = relay_chain {
+		let mut chain_spec = ChainSpec::from(plain_chain_spec)?;
+		chain_spec.replace_relay_chain(relay_chain)?;
+		chain_spec.to_file(plain_chain_spec)?;
+	}
 	Ok(())
 }
 
+/// Lists the genesis presets exposed by a runtime, using `chain-spec-builder`.
+///
+/// # Arguments
+/// * `chain_spec_builder` - The path to the `chain-spec-builder` binary.
+/// * `runtime` - The path to the runtime.
+pub fn list_presets(chain_spec_builder: &Path, runtime: &Path) -> Result<Vec<String>, Error> {
+	let output = cmd(chain_spec_builder, ["list-presets".as_ref(), "--runtime".as_ref(), runtime])
+		.stderr_null()
+		.read()
+		.map_err(|_| Error::MissingCommand {
+			command: "list-presets".to_string(),
+			binary: chain_spec_builder.display().to_string(),
+		})?;
+	parse_presets(&output)
+}
+
+/// Parses the genesis presets listed by `chain-spec-builder`, either as `{"presets": [..]}` or
+/// as an array.
+fn parse_presets(output: &str) -> Result<Vec<String>, Error> {
+	let start = output.find(['{', '[']).ok_or_else(|| {
+		Error::Config(format!("unable to parse the genesis presets: {}", output.trim()))
+	})?;
+	let presets = match Value::from_str(&output[start..])? {
+		Value::Object(mut object) => object.remove("presets").unwrap_or(json!([])),
+		presets => presets,
+	};
+	presets
+		.as_array()
+		.ok_or_else(|| Error::Config("expected the genesis presets to be an array".into()))?
+		.iter()
+		.map(|preset| {
+			preset.as_str().map(String::from).ok_or_else(|| {
+				Error::Config(format!("expected the genesis preset {preset} to be a string"))
+			})
+		})
+		.collect()
+}
+
 /// Generates a raw chain specification file for a parachain.
 ///
 /// # Arguments
@@ -185,6 +605,38 @@ pub fn generate_genesis_state_file(
 	Ok(genesis_file)
 }
 
+/// Exports the head data of a live parachain at a specific block, rather than at genesis, so that
+/// the parachain can be re-registered or recovered from its current state. The head data is
+/// written hex encoded, as with [`generate_genesis_state_file`].
+///
+/// # Arguments
+/// * `url` - Endpoint of a node of the parachain.
+/// * `block` - The number of the block whose head is exported, otherwise the finalized head.
+/// * `output` - The path of the file to be generated.
+pub async fn export_head_data(
+	url: &str,
+	block: Option<u32>,
+	output: &Path,
+) -> Result<PathBuf, Error> {
+	let rpc = RpcClient::from_url(url)
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+	let rpc = LegacyRpcMethods::<SubstrateConfig>::new(rpc);
+	let hash = match block {
+		Some(number) => rpc
+			.chain_get_block_hash(Some(number.into()))
+			.await?
+			.ok_or_else(|| Error::Config(format!("block #{number} not found")))?,
+		None => rpc.chain_get_finalized_head().await?,
+	};
+	let header = rpc
+		.chain_get_header(Some(hash))
+		.await?
+		.ok_or_else(|| Error::Config(format!("the header of block {hash:?} was not found")))?;
+	fs::write(output, encode_hex(&header.encode()))?;
+	Ok(output.to_path_buf())
+}
+
 /// Checks if a given command exists and can be executed by running it with the "--help" argument.
 fn check_command_exists(binary_path: &Path, command: &str) -> Result<(), Error> {
 	cmd(binary_path, vec![command, "--help"]).stdout_null().run().map_err(|_err| {
@@ -197,7 +649,7 @@ fn check_command_exists(binary_path: &Path, command: &str) -> Result<(), Error>
 }
 
 /// A chain specification.
-pub struct ChainSpec(Value);
+pub struct ChainSpec(pub(crate) Value);
 impl ChainSpec {
 	/// Parses a chain specification from a path.
 	///
@@ -231,28 +683,31 @@ impl ChainSpec {
 	///
 	/// # Arguments
 	/// * `para_id` - The new value for the para_id.
+	///
+	/// The EVM chain identifier (`evmChainId.chainId`) of EVM-compatible chains is also replaced
+	/// wherever it mirrors the previous parachain id, as is conventional for their templates, and
+	/// otherwise retained.
 	pub fn replace_para_id(&mut self, para_id: u32) -> Result<(), Error> {
 		// Replace para_id
 		let replace = self
 			.0
 			.get_mut("para_id")
 			.ok_or_else(|| Error::Config("expected `para_id`".into()))?;
+		let previous = replace.as_u64();
 		*replace = json!(para_id);
 
-		// Replace genesis.runtimeGenesis.patch.parachainInfo.parachainId
-		let replace = self
+		// Replace `parachainInfo.parachainId` wherever it occurs within the genesis, however its
+		// config or presets are nested.
+		let genesis = self
 			.0
 			.get_mut("genesis")
-			.ok_or_else(|| Error::Config("expected `genesis`".into()))?
-			.get_mut("runtimeGenesis")
-			.ok_or_else(|| Error::Config("expected `runtimeGenesis`".into()))?
-			.get_mut("patch")
-			.ok_or_else(|| Error::Config("expected `patch`".into()))?
-			.get_mut("parachainInfo")
-			.ok_or_else(|| Error::Config("expected `parachainInfo`".into()))?
-			.get_mut("parachainId")
-			.ok_or_else(|| Error::Config("expected `parachainInfo.parachainId`".into()))?;
-		*replace = json!(para_id);
+			.ok_or_else(|| Error::Config("expected `genesis`".into()))?;
+		if replace_para_ids(genesis, para_id) == 0 {
+			return Err(Error::Config("expected `parachainId` within `genesis`".into()));
+		}
+		if let Some(previous) = previous {
+			replace_evm_chain_ids(genesis, previous, para_id);
+		}
 		Ok(())
 	}
 
@@ -298,18 +753,613 @@ impl ChainSpec {
 		Ok(())
 	}
 
-	/// Converts the chain specification to a string.
-	pub fn to_string(&self) -> Result<String> {
-		Ok(serde_json::to_string_pretty(&self.0)?)
+	/// Get the name of the chain from the chain specification.
+	pub fn get_name(&self) -> Option<&str> {
+		self.0.get("name").and_then(|v| v.as_str())
+	}
+
+	/// Get the identifier of the chain from the chain specification.
+	pub fn get_id(&self) -> Option<&str> {
+		self.0.get("id").and_then(|v| v.as_str())
+	}
+
+	/// Get the token symbol from the properties of the chain specification.
+	pub fn get_token_symbol(&self) -> Option<&str> {
+		self.0.pointer("/properties/tokenSymbol").and_then(|v| v.as_str())
+	}
+
+	/// Get the token decimals from the properties of the chain specification.
+	pub fn get_token_decimals(&self) -> Option<u8> {
+		self.0
+			.pointer("/properties/tokenDecimals")
+			.and_then(|v| v.as_u64())
+			.and_then(|v| u8::try_from(v).ok())
+	}
+
+	/// Get the address format (`ss58Format`) from the properties of the chain specification.
+	pub fn get_ss58_format(&self) -> Option<u16> {
+		self.0
+			.pointer("/properties/ss58Format")
+			.and_then(|v| v.as_u64())
+			.and_then(|v| u16::try_from(v).ok())
+	}
+
+	/// Get the sudo key from the genesis of the chain specification.
+	pub fn get_sudo_key(&self) -> Option<&str> {
+		self.0
+			.pointer("/genesis/runtimeGenesis/patch/sudo/key")
+			.and_then(|v| v.as_str())
+	}
+
+	/// Get the initial endowments from the genesis of the chain specification, as pairs of
+	/// accounts and balances.
+	pub fn get_endowments(&self) -> Option<Vec<(String, u128)>> {
+		self.0
+			.pointer("/genesis/runtimeGenesis/patch/balances/balances")?
+			.as_array()?
+			.iter()
+			.map(|endowment| {
+				let endowment = endowment.as_array()?;
+				let balance = u128::deserialize(endowment.get(1)?).ok()?;
+				Some((endowment.first()?.as_str()?.to_string(), balance))
+			})
+			.collect()
+	}
+
+	/// Get the EVM chain identifier from the genesis of the chain specification, for
+	/// EVM-compatible chains.
+	pub fn get_evm_chain_id(&self) -> Option<u64> {
+		self.0
+			.pointer("/genesis/runtimeGenesis/patch/evmChainId/chainId")
+			.and_then(|v| v.as_u64())
+	}
+
+	/// Get the balances of the EVM accounts endowed within the genesis of the chain
+	/// specification, as pairs of addresses and balances. Accounts with code (e.g. precompiles)
+	/// are excluded.
+	pub fn get_evm_balances(&self) -> Option<Vec<(String, u128)>> {
+		self.evm_accounts()?
+			.iter()
+			.filter(|(_, account)| !has_code(account))
+			.map(|(address, account)| Some((address.clone(), parse_u256(account.get("balance")?)?)))
+			.collect()
+	}
+
+	/// Get the addresses of the precompiles of the genesis of the chain specification: the EVM
+	/// accounts whose code is [`EVM_PRECOMPILE_CODE`].
+	pub fn get_evm_precompiles(&self) -> Vec<String> {
+		self.evm_accounts()
+			.map(|accounts| {
+				accounts
+					.iter()
+					.filter(|(_, account)| is_precompile(account))
+					.map(|(address, _)| address.clone())
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	/// Get the bootnodes from the chain specification.
+	pub fn get_bootnodes(&self) -> Vec<&str> {
+		self.0
+			.get("bootNodes")
+			.and_then(|v| v.as_array())
+			.map(|nodes| nodes.iter().filter_map(|n| n.as_str()).collect())
+			.unwrap_or_default()
+	}
+
+	/// Whether the genesis of the chain specification is raw storage, rather than a runtime
+	/// genesis config.
+	pub fn is_raw(&self) -> bool {
+		self.get_raw_storage().is_some()
+	}
+
+	/// Get the raw genesis storage from the chain specification, as pairs of hex-encoded keys and
+	/// values.
+	pub fn get_raw_storage(&self) -> Option<&serde_json::Map<String, Value>> {
+		self.0.pointer("/genesis/raw/top").and_then(|v| v.as_object())
+	}
+
+	/// Get the runtime code from the raw genesis storage of the chain specification.
+	pub fn get_code(&self) -> Option<Vec<u8>> {
+		self.get_raw_storage()?.get(CODE).and_then(|v| v.as_str()).and_then(decode_hex)
+	}
+
+	/// Appends a bootnode to the chain specification, unless already included.
+	///
+	/// # Arguments
+	/// * `bootnode` - The multiaddress of the bootnode, including its peer ID (e.g.
+	///   `/dns/pop.r0gue.io/tcp/30333/p2p/12D3KooW...`).
+	pub fn add_bootnode(&mut self, bootnode: &str) -> Result<(), Error> {
+		validate_bootnode(bootnode)?;
+		let bootnodes = self.array_mut("bootNodes")?;
+		if !bootnodes.iter().any(|b| b.as_str() == Some(bootnode)) {
+			bootnodes.push(json!(bootnode));
+		}
+		Ok(())
+	}
+
+	/// Replaces the bootnodes of the chain specification with the given ones.
+	///
+	/// # Arguments
+	/// * `bootnodes` - The multiaddresses of the bootnodes, including their peer IDs.
+	pub fn replace_bootnodes(&mut self, bootnodes: &[String]) -> Result<(), Error> {
+		for bootnode in bootnodes {
+			validate_bootnode(bootnode)?;
+		}
+		*self.array_mut("bootNodes")? = bootnodes.iter().map(|b| json!(b)).collect();
+		Ok(())
+	}
+
+	/// Get the telemetry endpoints from the chain specification, as pairs of endpoints and
+	/// verbosity levels.
+	pub fn get_telemetry_endpoints(&self) -> Vec<(&str, u8)> {
+		self.0
+			.get("telemetryEndpoints")
+			.and_then(|v| v.as_array())
+			.map(|endpoints| {
+				endpoints
+					.iter()
+					.filter_map(|endpoint| {
+						let endpoint = endpoint.as_array()?;
+						let verbosity = u8::try_from(endpoint.get(1)?.as_u64()?).ok()?;
+						Some((endpoint.first()?.as_str()?, verbosity))
+					})
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	/// Appends a telemetry endpoint to the chain specification, replacing the verbosity of the
+	/// endpoint if already included.
+	///
+	/// # Arguments
+	/// * `endpoint` - The endpoint, either a multiaddress or a websocket URL (e.g.
+	///   `wss://telemetry.polkadot.io/submit/`).
+	/// * `verbosity` - The verbosity level of the telemetry sent to the endpoint.
+	pub fn add_telemetry_endpoint(&mut self, endpoint: &str, verbosity: u8) -> Result<(), Error> {
+		validate_telemetry_endpoint(endpoint)?;
+		let endpoints = self.array_mut("telemetryEndpoints")?;
+		match endpoints
+			.iter_mut()
+			.find(|e| e.get(0).and_then(|e| e.as_str()) == Some(endpoint))
+		{
+			Some(existing) => *existing = json!([endpoint, verbosity]),
+			None => endpoints.push(json!([endpoint, verbosity])),
+		}
+		Ok(())
+	}
+
+	/// Replaces the telemetry endpoints of the chain specification with the given ones.
+	///
+	/// # Arguments
+	/// * `endpoints` - The endpoints, along with the verbosity levels of the telemetry sent.
+	pub fn replace_telemetry_endpoints(&mut self, endpoints: &[(String, u8)]) -> Result<(), Error> {
+		for (endpoint, _) in endpoints {
+			validate_telemetry_endpoint(endpoint)?;
+		}
+		*self.array_mut("telemetryEndpoints")? = endpoints
+			.iter()
+			.map(|(endpoint, verbosity)| json!([endpoint, verbosity]))
+			.collect();
+		Ok(())
+	}
+
+	/// Replaces the name of the chain with the given one.
+	///
+	/// # Arguments
+	/// * `name` - The new value for the name of the chain.
+	pub fn replace_name(&mut self, name: &str) -> Result<(), Error> {
+		// Replace name
+		let replace =
+			self.0.get_mut("name").ok_or_else(|| Error::Config("expected `name`".into()))?;
+		*replace = json!(name);
+		Ok(())
+	}
+
+	/// Replaces the identifier of the chain with the given one.
+	///
+	/// # Arguments
+	/// * `id` - The new value for the identifier of the chain.
+	pub fn replace_id(&mut self, id: &str) -> Result<(), Error> {
+		// Replace id
+		let replace = self.0.get_mut("id").ok_or_else(|| Error::Config("expected `id`".into()))?;
+		*replace = json!(id);
+		Ok(())
+	}
+
+	/// Replaces the token symbol within the properties of the chain specification, adding the
+	/// properties if missing.
+	///
+	/// # Arguments
+	/// * `symbol` - The new value for the token symbol.
+	pub fn replace_token_symbol(&mut self, symbol: &str) -> Result<(), Error> {
+		self.properties_mut()?.insert("tokenSymbol".into(), json!(symbol));
+		Ok(())
+	}
+
+	/// Replaces the token decimals within the properties of the chain specification, adding the
+	/// properties if missing.
+	///
+	/// # Arguments
+	/// * `decimals` - The new value for the token decimals.
+	pub fn replace_token_decimals(&mut self, decimals: u8) -> Result<(), Error> {
+		self.properties_mut()?.insert("tokenDecimals".into(), json!(decimals));
+		Ok(())
+	}
+
+	/// Replaces the sudo key within the genesis of the chain specification.
+	///
+	/// # Arguments
+	/// * `key` - The address of the new sudo account.
+	pub fn replace_sudo_key(&mut self, key: &str) -> Result<(), Error> {
+		// Replace genesis.runtimeGenesis.patch.sudo.key
+		let replace = self
+			.genesis_patch_mut()?
+			.get_mut("sudo")
+			.ok_or_else(|| Error::Config("expected `sudo`".into()))?;
+		*replace = json!({ "key": key });
+		Ok(())
+	}
+
+	/// Replaces the initial endowments within the genesis of the chain specification.
+	///
+	/// # Arguments
+	/// * `endowments` - The accounts to be endowed, alongside their balances.
+	pub fn replace_endowments(&mut self, endowments: &[(String, u128)]) -> Result<(), Error> {
+		// Replace genesis.runtimeGenesis.patch.balances.balances
+		let replace = self
+			.genesis_patch_mut()?
+			.get_mut("balances")
+			.ok_or_else(|| Error::Config("expected `balances`".into()))?
+			.as_object_mut()
+			.ok_or_else(|| Error::Config("expected `balances` to be an object".into()))?;
+		replace.insert(
+			"balances".into(),
+			endowments.iter().map(|(account, balance)| json!([account, balance])).collect(),
+		);
+		Ok(())
+	}
+
+	/// Replaces the EVM chain identifier within the genesis of the chain specification, for
+	/// EVM-compatible chains.
+	///
+	/// # Arguments
+	/// * `chain_id` - The new value for the EVM chain identifier.
+	pub fn replace_evm_chain_id(&mut self, chain_id: u64) -> Result<(), Error> {
+		// Replace genesis.runtimeGenesis.patch.evmChainId.chainId
+		let replace = self
+			.genesis_patch_mut()?
+			.get_mut("evmChainId")
+			.ok_or_else(|| Error::Config("expected `evmChainId`".into()))?;
+		*replace = json!({ "chainId": chain_id });
+		Ok(())
+	}
+
+	/// Replaces the EVM accounts endowed within the genesis of the chain specification. Accounts
+	/// with code (e.g. precompiles) are retained, with only their balance replaced if specified.
+	///
+	/// # Arguments
+	/// * `balances` - The 20-byte addresses of the accounts to be endowed, alongside their
+	///   balances.
+	pub fn replace_evm_balances(&mut self, balances: &[(String, u128)]) -> Result<(), Error> {
+		let balances = balances
+			.iter()
+			.map(|(address, balance)| Ok((evm_address(address)?, *balance)))
+			.collect::<Result<Vec<_>, Error>>()?;
+		// Replace genesis.runtimeGenesis.patch.evm.accounts
+		let accounts = self.evm_accounts_mut()?;
+		accounts.retain(|_, account| has_code(account));
+		for (address, balance) in balances {
+			let balance = json!(format!("{balance:#x}"));
+			match accounts.get_mut(&address).and_then(|a| a.as_object_mut()) {
+				Some(account) => {
+					account.insert("balance".into(), balance);
+				},
+				None => {
+					accounts.insert(address, evm_account(balance, json!([])));
+				},
+			}
+		}
+		Ok(())
+	}
+
+	/// Replaces the precompiles within the genesis of the chain specification, placing
+	/// [`EVM_PRECOMPILE_CODE`] at the address of each so that they are treated as contracts.
+	///
+	/// # Arguments
+	/// * `addresses` - The 20-byte addresses of the precompiles (e.g. `0x...0800`).
+	pub fn replace_evm_precompiles(&mut self, addresses: &[String]) -> Result<(), Error> {
+		let addresses =
+			addresses.iter().map(|a| evm_address(a)).collect::<Result<Vec<_>, Error>>()?;
+		// Replace genesis.runtimeGenesis.patch.evm.accounts
+		let accounts = self.evm_accounts_mut()?;
+		accounts.retain(|_, account| !is_precompile(account));
+		for address in addresses {
+			let code = json!(EVM_PRECOMPILE_CODE);
+			match accounts.get_mut(&address).and_then(|a| a.as_object_mut()) {
+				Some(account) => {
+					account.insert("code".into(), code);
+				},
+				None => {
+					accounts.insert(address, evm_account(json!("0x0"), code));
+				},
+			}
+		}
+		Ok(())
+	}
+
+	/// Replaces the initial collator set within the genesis of the chain specification: the
+	/// invulnerables of the collator selection and the session keys of each collator, so that the
+	/// chain launches with the specified collators rather than the development accounts. The
+	/// collators and the chain specification are validated before any changes are made.
+	///
+	/// # Arguments
+	/// * `collators` - The collators, alongside their session keys.
+	pub fn replace_collators(&mut self, collators: &[CollatorKeys]) -> Result<(), Error> {
+		let first = collators
+			.first()
+			.ok_or_else(|| Error::Config("expected at least one collator".into()))?;
+		let mut accounts = HashSet::new();
+		for collator in collators {
+			collator.validate()?;
+			if !accounts.insert(&collator.account) {
+				return Err(Error::Config(format!(
+					"collator `{}` is specified more than once",
+					collator.account
+				)));
+			}
+			// Each collator is expected to provide the same session keys, those of the runtime.
+			if !collator.keys.keys().eq(first.keys.keys()) {
+				return Err(Error::Config(format!(
+					"expected collator `{}` to provide the same session keys as `{}`",
+					collator.account, first.account
+				)));
+			}
+		}
+		let patch = self.genesis_patch_mut()?;
+		for section in ["collatorSelection", "session"] {
+			match patch.get(section) {
+				Some(value) if value.is_object() => {},
+				Some(_) =>
+					return Err(Error::Config(format!("expected `{section}` to be an object"))),
+				None => return Err(Error::Config(format!("expected `{section}`"))),
+			}
+		}
+		// Replace genesis.runtimeGenesis.patch.collatorSelection.invulnerables
+		patch["collatorSelection"]["invulnerables"] =
+			collators.iter().map(|collator| json!(collator.account)).collect();
+		// Replace genesis.runtimeGenesis.patch.session.keys
+		patch["session"]["keys"] = collators
+			.iter()
+			.map(|collator| json!([collator.account, collator.account, collator.keys]))
+			.collect();
+		Ok(())
+	}
+
+	/// Returns the genesis patch of the chain specification.
+	fn genesis_patch_mut(&mut self) -> Result<&mut Value, Error> {
+		self.0
+			.get_mut("genesis")
+			.ok_or_else(|| Error::Config("expected `genesis`".into()))?
+			.get_mut("runtimeGenesis")
+			.ok_or_else(|| Error::Config("expected `runtimeGenesis`".into()))?
+			.get_mut("patch")
+			.ok_or_else(|| Error::Config("expected `patch`".into()))
+	}
+
+	/// Returns the EVM accounts within the genesis of the chain specification, by address.
+	fn evm_accounts(&self) -> Option<&serde_json::Map<String, Value>> {
+		self.0
+			.pointer("/genesis/runtimeGenesis/patch/evm/accounts")
+			.and_then(|v| v.as_object())
+	}
+
+	/// Returns the EVM accounts within the genesis of the chain specification, adding them if
+	/// missing (or `null`).
+	fn evm_accounts_mut(&mut self) -> Result<&mut serde_json::Map<String, Value>, Error> {
+		let evm = self
+			.genesis_patch_mut()?
+			.get_mut("evm")
+			.ok_or_else(|| Error::Config("expected `evm`".into()))?
+			.as_object_mut()
+			.ok_or_else(|| Error::Config("expected `evm` to be an object".into()))?;
+		let accounts = evm.entry("accounts").or_insert_with(|| json!({}));
+		if accounts.is_null() {
+			*accounts = json!({});
+		}
+		accounts
+			.as_object_mut()
+			.ok_or_else(|| Error::Config("expected `accounts` to be an object".into()))
+	}
+
+	/// The array at the specified key of the chain specification, which is added if missing
+	/// (or `null`).
+	fn array_mut(&mut self, key: &str) -> Result<&mut Vec<Value>, Error> {
+		let spec = self
+			.0
+			.as_object_mut()
+			.ok_or_else(|| Error::Config("expected an object".into()))?;
+		let array = spec.entry(key).or_insert_with(|| json!([]));
+		if array.is_null() {
+			*array = json!([]);
+		}
+		array
+			.as_array_mut()
+			.ok_or_else(|| Error::Config(format!("expected `{key}` to be an array")))
+	}
+
+	/// Returns the properties of the chain specification, adding them if missing.
+	fn properties_mut(&mut self) -> Result<&mut serde_json::Map<String, Value>, Error> {
+		let spec = self
+			.0
+			.as_object_mut()
+			.ok_or_else(|| Error::Config("expected an object".into()))?;
+		let properties = spec.entry("properties").or_insert_with(|| json!({}));
+		if properties.is_null() {
+			*properties = json!({});
+		}
+		properties
+			.as_object_mut()
+			.ok_or_else(|| Error::Config("expected `properties` to be an object".into()))
+	}
+
+	/// Deep-merges a patch into the chain specification (e.g. overriding balances, collator
+	/// invulnerables or the configuration of pallets), before the raw chain specification is
+	/// generated. Objects are merged recursively, a `null` removes the key and any other value
+	/// replaces that of the chain specification.
+	///
+	/// # Arguments
+	/// * `patch` - The patch, relative to the root of the chain specification.
+	pub fn apply_patch(&mut self, patch: Value) -> Result<(), Error> {
+		if !patch.is_object() {
+			return Err(Error::Config("expected the patch to be an object".into()));
+		}
+		merge(&mut self.0, patch);
+		Ok(())
+	}
+
+	/// Deep-merges a patch file into the chain specification. See [`ChainSpec::apply_patch`].
+	///
+	/// # Arguments
+	/// * `path` - The path to the JSON patch file.
+	pub fn apply_patch_file(&mut self, path: &Path) -> Result<(), Error> {
+		let patch = fs::read_to_string(path).map_err(|e| {
+			Error::Config(format!("unable to read the patch {}: {e}", path.display()))
+		})?;
+		self.apply_patch(serde_json::from_str(&patch)?)
+	}
+
+	/// Converts the chain specification to a string.
+	pub fn to_string(&self) -> Result<String> {
+		Ok(serde_json::to_string_pretty(&self.0)?)
+	}
+
+	/// Writes the chain specification to a file.
+	///
+	/// # Arguments
+	/// * `path` - The path to the chain specification file.
+	pub fn to_file(&self, path: &Path) -> Result<()> {
+		fs::write(path, self.to_string()?)?;
+		Ok(())
+	}
+}
+
+/// Replaces the EVM chain identifiers (`evmChainId.chainId`) within a JSON value which mirror the
+/// previous parachain id, recursively.
+///
+/// # Arguments
+/// * `value` - The JSON value.
+/// * `previous` - The previous value of the parachain id.
+/// * `para_id` - The new value for the parachain id.
+fn replace_evm_chain_ids(value: &mut Value, previous: u64, para_id: u32) {
+	match value {
+		Value::Object(object) =>
+			for (key, value) in object.iter_mut() {
+				match (key.as_str(), value.get_mut("chainId")) {
+					("evmChainId", Some(chain_id)) =>
+						if chain_id.as_u64() == Some(previous) {
+							*chain_id = json!(para_id);
+						},
+					_ => replace_evm_chain_ids(value, previous, para_id),
+				}
+			},
+		Value::Array(values) =>
+			values.iter_mut().for_each(|v| replace_evm_chain_ids(v, previous, para_id)),
+		_ => {},
+	}
+}
+
+/// Validates a 20-byte EVM address, returning it as lowercase hex.
+fn evm_address(address: &str) -> Result<String, Error> {
+	match Account::parse(address) {
+		Ok(account @ Account::Id20(_)) => Ok(account.to_hex()),
+		_ => Err(Error::Config(format!("`{address}` is not a 20-byte EVM address"))),
+	}
+}
+
+/// An EVM account within the genesis, as expected by `pallet-evm`.
+fn evm_account(balance: Value, code: Value) -> Value {
+	json!({ "balance": balance, "code": code, "nonce": "0x0", "storage": {} })
+}
+
+/// Whether an EVM account within the genesis has code, specified as either bytes or hex.
+fn has_code(account: &Value) -> bool {
+	match account.get("code") {
+		Some(Value::Array(code)) => !code.is_empty(),
+		Some(Value::String(code)) => !matches!(code.as_str(), "" | "0x"),
+		_ => false,
+	}
+}
+
+/// Whether an EVM account within the genesis is a precompile, its code being
+/// [`EVM_PRECOMPILE_CODE`].
+fn is_precompile(account: &Value) -> bool {
+	match account.get("code") {
+		Some(code @ Value::Array(_)) => *code == json!(EVM_PRECOMPILE_CODE),
+		Some(Value::String(code)) => decode_hex(code).as_deref() == Some(&EVM_PRECOMPILE_CODE[..]),
+		_ => false,
+	}
+}
+
+/// Parses a 256-bit value within the genesis, specified as either hex or a number, which must
+/// fit within 128 bits.
+fn parse_u256(value: &Value) -> Option<u128> {
+	match value {
+		Value::String(hex) => u128::from_str_radix(hex.strip_prefix("0x")?, 16).ok(),
+		Value::Number(number) => number.as_u64().map(u128::from),
+		_ => None,
+	}
+}
+
+/// Replaces the numeric parachain id of each `parachainInfo` config (`parachainInfo.parachainId`)
+/// within a JSON value, recursively, retaining any other ids (e.g. those of siblings). Returns
+/// the number of values replaced.
+///
+/// # Arguments
+/// * `value` - The JSON value.
+/// * `para_id` - The new value for the parachain id.
+fn replace_para_ids(value: &mut Value, para_id: u32) -> usize {
+	match value {
+		Value::Object(object) => object
+			.iter_mut()
+			.map(|(key, value)| {
+				if key == "parachainInfo" {
+					if let Some(id) = value.get_mut("parachainId").filter(|id| id.is_number()) {
+						*id = json!(para_id);
+						return 1;
+					}
+				}
+				replace_para_ids(value, para_id)
+			})
+			.sum(),
+		Value::Array(values) => values.iter_mut().map(|v| replace_para_ids(v, para_id)).sum(),
+		_ => 0,
+	}
+}
+
+/// Validates the multiaddress of a bootnode, which must include the peer ID of the node.
+fn validate_bootnode(bootnode: &str) -> Result<(), Error> {
+	let address = Multiaddr::from_str(bootnode)
+		.map_err(|e| Error::InvalidMultiaddr(format!("{bootnode}: {e}")))?;
+	if !matches!(address.iter().last(), Some(Protocol::P2p(_))) {
+		return Err(Error::InvalidMultiaddr(format!(
+			"{bootnode}: expected the peer ID of the bootnode (`/p2p/<peer id>`)"
+		)));
 	}
+	Ok(())
+}
 
-	/// Writes the chain specification to a file.
-	///
-	/// # Arguments
-	/// * `path` - The path to the chain specification file.
-	pub fn to_file(&self, path: &Path) -> Result<()> {
-		fs::write(path, self.to_string()?)?;
-		Ok(())
+/// Validates a telemetry endpoint, which is either a multiaddress or a websocket URL.
+fn validate_telemetry_endpoint(endpoint: &str) -> Result<(), Error> {
+	if Multiaddr::from_str(endpoint).is_ok() {
+		return Ok(());
+	}
+	match url::Url::parse(endpoint) {
+		Ok(url) if matches!(url.scheme(), "ws" | "wss") => Ok(()),
+		_ => Err(Error::InvalidMultiaddr(format!(
+			"{endpoint}: expected a multiaddress or a websocket URL"
+		))),
 	}
 }
 
@@ -317,8 +1367,8 @@ impl ChainSpec {
 mod tests {
 	use super::*;
 	use crate::{
-		new_parachain::instantiate_standard_template, templates::Parachain, Config, Error,
-		Zombienet,
+		collators::CollatorKeys, new_parachain::instantiate_standard_template,
+		templates::Parachain, Config, Error, Zombienet,
 	};
 	use anyhow::Result;
 	use pop_common::manifest::Dependency;
@@ -418,14 +1468,97 @@ default_command = "pop-node"
 		let name = "parachain_template_node";
 		cmd("cargo", ["new", name, "--bin"]).dir(temp_dir.path()).run()?;
 		generate_mock_node(&temp_dir.path().join(name))?;
-		let binary = build_parachain(&temp_dir.path().join(name), None, &Profile::Release, None)?;
+		let artifacts = build_parachain(
+			&temp_dir.path().join(name),
+			&BuildOptions::new(Profile::Release),
+			&(),
+		)?;
 		let target_directory = temp_dir.path().join(name).join("target/release");
 		assert!(target_directory.exists());
 		assert!(target_directory.join("parachain_template_node").exists());
 		assert_eq!(
-			binary.display().to_string(),
+			artifacts.binary.display().to_string(),
 			target_directory.join("parachain_template_node").display().to_string()
 		);
+		assert_eq!(artifacts.runtime, None);
+		assert_eq!(artifacts.profile, Profile::Release);
+		assert_eq!(artifacts.features, Features::default());
+		Ok(())
+	}
+
+	#[test]
+	fn build_container_works() {
+		assert_eq!(
+			build_container(Path::new("/project")).user("1000:1000").args(&[
+				"cargo",
+				"build",
+				"--release"
+			]),
+			[
+				"run",
+				"--rm",
+				"--user",
+				"1000:1000",
+				"-v",
+				"/project:/build",
+				"-e",
+				"CARGO_HOME=/build/target/.cargo",
+				"-w",
+				"/build",
+				"docker.io/paritytech/ci-unified:bullseye-1.81.0-2024-09-11-v202409111034",
+				"cargo",
+				"build",
+				"--release"
+			]
+		);
+	}
+
+	#[test]
+	fn args_works() {
+		let features =
+			Features { features: vec!["runtime-benchmarks".into()], ..Default::default() };
+		let options = BuildOptions {
+			package: Some("parachain-template-node".into()),
+			features,
+			resolution: Resolution { locked: true, offline: true },
+			target: Some("aarch64-unknown-linux-gnu".into()),
+			..BuildOptions::new(Profile::Release)
+		};
+		assert_eq!(
+			options.args(),
+			[
+				"build",
+				"--package",
+				"parachain-template-node",
+				"--target",
+				"aarch64-unknown-linux-gnu",
+				"--release",
+				"--features",
+				"runtime-benchmarks",
+				"--locked",
+				"--offline"
+			]
+		);
+		assert_eq!(BuildOptions::new(Profile::Debug).args(), ["build"]);
+	}
+
+	#[test]
+	fn build_parachain_fails_without_target() -> Result<()> {
+		if cmd("rustup", ["--version"]).stdout_null().stderr_null().run().is_err() {
+			return Ok(());
+		}
+		let temp_dir = tempdir()?;
+		assert!(matches!(
+			build_parachain(
+				temp_dir.path(),
+				&BuildOptions {
+					target: Some("missing-unknown-target".into()),
+					..BuildOptions::new(Profile::Release)
+				},
+				&()
+			),
+			Err(Error::CommonError(pop_common::Error::Config(..)))
+		));
 		Ok(())
 	}
 
@@ -443,6 +1576,57 @@ default_command = "pop-node"
 		Ok(())
 	}
 
+	#[test]
+	fn locate_runtime_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert!(matches!(locate_runtime(path, None), Err(Error::Config(..))));
+		fs::create_dir_all(path.join("runtimes/mainnet"))?;
+		fs::write(
+			path.join("runtimes/mainnet/Cargo.toml"),
+			"[package]\nname = \"mainnet-runtime\"\nversion = \"0.1.0\"\n\n[build-dependencies]\nsubstrate-wasm-builder = \"23\"\n",
+		)?;
+		fs::create_dir_all(path.join("runtimes/mainnet/src"))?;
+		fs::write(path.join("runtimes/mainnet/src/lib.rs"), "")?;
+		let runtime = ("mainnet-runtime".to_string(), path.join("runtimes/mainnet"));
+		assert_eq!(locate_runtime(path, None)?, runtime);
+		assert_eq!(locate_runtime(path, Some("mainnet-runtime"))?, runtime);
+		assert!(matches!(
+			locate_runtime(path, Some("testnet-runtime")),
+			Err(Error::Config(e)) if e == "the testnet-runtime runtime package was not found"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn runtime_artifacts_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let target_path = temp_dir.path().join("target/release");
+		let package = "parachain-template-runtime";
+		assert!(matches!(
+			runtime_artifacts(&target_path, Some(package)),
+			Err(Error::MissingBinary(e)) if e == "parachain-template-runtime runtime"
+		));
+		assert!(matches!(runtime_artifacts(&target_path, None), Err(Error::MissingBinary(_))));
+		let runtime = target_path.join("wbuild").join(package);
+		fs::create_dir_all(&runtime)?;
+		fs::write(runtime.join("parachain_template_runtime.compact.wasm"), "")?;
+		fs::write(runtime.join("parachain_template_runtime.compact.compressed.wasm"), "")?;
+		let artifacts = RuntimeArtifacts {
+			package: package.to_string(),
+			compact: runtime.join("parachain_template_runtime.compact.wasm"),
+			compressed: runtime.join("parachain_template_runtime.compact.compressed.wasm"),
+		};
+		assert_eq!(runtime_artifacts(&target_path, Some(package))?, artifacts);
+		// Without a package, the runtime most recently built is located.
+		assert_eq!(runtime_artifacts(&target_path, None)?, artifacts);
+		let other = target_path.join("wbuild/other-runtime");
+		fs::create_dir_all(&other)?;
+		fs::write(other.join("other_runtime.wasm"), "")?;
+		assert_eq!(runtime_artifacts(&target_path, None)?, artifacts);
+		Ok(())
+	}
+
 	#[test]
 	fn binary_path_fails_missing_binary() -> Result<()> {
 		let temp_dir =
@@ -467,6 +1651,8 @@ default_command = "pop-node"
 			&binary_path,
 			&temp_dir.path().join("plain-parachain-chainspec.json"),
 			true,
+			None,
+			Some("paseo"),
 		)?;
 		assert!(plain_chain_spec.exists());
 		{
@@ -518,6 +1704,18 @@ default_command = "pop-node"
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn export_head_data_fails_wrong_url() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let output = temp_dir.path().join("para-2000-genesis-state");
+		assert!(matches!(
+			export_head_data("ws://127.0.0.1:1", Some(1), &output).await,
+			Err(Error::ConnectionFailure(_))
+		));
+		assert!(!output.exists());
+		Ok(())
+	}
+
 	#[test]
 	fn generate_genesis_state_file_wrong_chain_spec() -> Result<()> {
 		assert!(matches!(
@@ -600,6 +1798,47 @@ default_command = "pop-node"
 		Ok(())
 	}
 
+	#[test]
+	fn replace_para_id_replaces_nested_ids() -> Result<()> {
+		let mut chain_spec = ChainSpec(Value::from_str(
+			r#"{"para_id":1000,"genesis":{"runtimeGenesis":{"config":{"parachainInfo":{"parachainId":1000},"sibling":{"para_id":1000,"parachainId":1000,"name":"sibling"}},"presets":[{"parachainInfo":{"parachainId":1000}}]}}}"#,
+		)?);
+		chain_spec.replace_para_id(2001)?;
+		// The ordering of keys is preserved, and the ids of siblings are retained.
+		assert_eq!(
+			serde_json::to_string(&chain_spec.0)?,
+			r#"{"para_id":2001,"genesis":{"runtimeGenesis":{"config":{"parachainInfo":{"parachainId":2001},"sibling":{"para_id":1000,"parachainId":1000,"name":"sibling"}},"presets":[{"parachainInfo":{"parachainId":2001}}]}}}"#
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn replace_para_id_replaces_evm_chain_id() -> Result<()> {
+		let spec = |para_id: u32, evm_chain_id: u64| {
+			json!({
+				"para_id": para_id,
+				"genesis": {
+					"runtimeGenesis": {
+						"patch": {
+							"parachainInfo": { "parachainId": para_id },
+							"evmChainId": { "chainId": evm_chain_id }
+						}
+					}
+				},
+			})
+		};
+		// An EVM chain identifier mirroring the parachain id is replaced.
+		let mut chain_spec = ChainSpec(spec(1000, 1000));
+		chain_spec.replace_para_id(2001)?;
+		assert_eq!(chain_spec.0, spec(2001, 2001));
+		assert_eq!(chain_spec.get_evm_chain_id(), Some(2001));
+		// Otherwise it is retained.
+		let mut chain_spec = ChainSpec(spec(1000, 42));
+		chain_spec.replace_para_id(2001)?;
+		assert_eq!(chain_spec.0, spec(2001, 42));
+		Ok(())
+	}
+
 	#[test]
 	fn replace_para_id_fails() -> Result<()> {
 		let mut chain_spec = ChainSpec(json!({
@@ -631,64 +1870,24 @@ default_command = "pop-node"
 		assert!(
 			matches!(chain_spec.replace_para_id(2001), Err(Error::Config(error)) if error == "expected `genesis`")
 		);
-		chain_spec = ChainSpec(json!({
-			"para_id": 2001,
-			"genesis": {
-				"": {
-					"patch": {
-						"parachainInfo": {
-							"parachainId": 1000
-						}
-					}
-				}
-			},
-		}));
-		assert!(
-			matches!(chain_spec.replace_para_id(2001), Err(Error::Config(error)) if error == "expected `runtimeGenesis`")
-		);
-		chain_spec = ChainSpec(json!({
-			"para_id": 2001,
-			"genesis": {
-				"runtimeGenesis": {
-					"": {
-						"parachainInfo": {
-							"parachainId": 1000
-						}
-					}
-				}
-			},
-		}));
-		assert!(
-			matches!(chain_spec.replace_para_id(2001), Err(Error::Config(error)) if error == "expected `patch`")
-		);
 		chain_spec = ChainSpec(json!({
 			"para_id": 2001,
 			"genesis": {
 				"runtimeGenesis": {
 					"patch": {
-						"": {
-							"parachainId": 1000
+						"parachainInfo": {
 						}
 					}
 				}
 			},
 		}));
 		assert!(
-			matches!(chain_spec.replace_para_id(2001), Err(Error::Config(error)) if error == "expected `parachainInfo`")
+			matches!(chain_spec.replace_para_id(2001), Err(Error::Config(error)) if error == "expected `parachainId` within `genesis`")
 		);
-		chain_spec = ChainSpec(json!({
-			"para_id": 2001,
-			"genesis": {
-				"runtimeGenesis": {
-					"patch": {
-						"parachainInfo": {
-						}
-					}
-				}
-			},
-		}));
+		// The raw genesis storage cannot be edited.
+		chain_spec = ChainSpec(json!({"para_id": 2001, "genesis": {"raw": {"top": {}}}}));
 		assert!(
-			matches!(chain_spec.replace_para_id(2001), Err(Error::Config(error)) if error == "expected `parachainInfo.parachainId`")
+			matches!(chain_spec.replace_para_id(2001), Err(Error::Config(error)) if error == "expected `parachainId` within `genesis`")
 		);
 		Ok(())
 	}
@@ -744,6 +1943,342 @@ default_command = "pop-node"
 		Ok(())
 	}
 
+	#[test]
+	fn bootnodes_works() -> Result<()> {
+		let bootnode =
+			"/ip4/127.0.0.1/tcp/30333/p2p/12D3KooWBmAwcd4PJNJvfV89HwE48nwkRmAgo8Vy3uQEyNNHBox2";
+		let other =
+			"/dns/pop.r0gue.io/tcp/30333/p2p/12D3KooWBmAwcd4PJNJvfV89HwE48nwkRmAgo8Vy3uQEyNNHBox2";
+		let mut chain_spec = ChainSpec(json!({"bootNodes": null}));
+		chain_spec.add_bootnode(bootnode)?;
+		chain_spec.add_bootnode(bootnode)?;
+		chain_spec.add_bootnode(other)?;
+		assert_eq!(chain_spec.get_bootnodes(), [bootnode, other]);
+		chain_spec.replace_bootnodes(&[other.to_string()])?;
+		assert_eq!(chain_spec.0, json!({"bootNodes": [other]}));
+		for invalid in ["127.0.0.1:30333", "/ip4/127.0.0.1/tcp/30333"] {
+			assert!(matches!(chain_spec.add_bootnode(invalid), Err(Error::InvalidMultiaddr(_))));
+			assert!(matches!(
+				chain_spec.replace_bootnodes(&[invalid.to_string()]),
+				Err(Error::InvalidMultiaddr(_))
+			));
+		}
+		assert_eq!(chain_spec.get_bootnodes(), [other]);
+		Ok(())
+	}
+
+	#[test]
+	fn telemetry_endpoints_works() -> Result<()> {
+		let endpoint = "/dns/telemetry.polkadot.io/tcp/443/x-parity-wss/%2Fsubmit%2F";
+		let url = "wss://telemetry.polkadot.io/submit/";
+		let mut chain_spec = ChainSpec(json!({}));
+		chain_spec.add_telemetry_endpoint(endpoint, 0)?;
+		chain_spec.add_telemetry_endpoint(url, 0)?;
+		chain_spec.add_telemetry_endpoint(endpoint, 1)?;
+		assert_eq!(chain_spec.get_telemetry_endpoints(), [(endpoint, 1), (url, 0)]);
+		chain_spec.replace_telemetry_endpoints(&[(url.to_string(), 5)])?;
+		assert_eq!(chain_spec.0, json!({"telemetryEndpoints": [[url, 5]]}));
+		assert!(matches!(
+			chain_spec.add_telemetry_endpoint("https://telemetry.polkadot.io", 0),
+			Err(Error::InvalidMultiaddr(_))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn generate_plain_chain_spec_replaces_relay_chain() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let binary_path = temp_dir.path().join("node");
+		fs::write(
+			&binary_path,
+			"#!/bin/sh\necho '{\"name\": \"Local\", \"relay_chain\": \"rococo-local\"}'\n",
+		)?;
+		fs::set_permissions(&binary_path, fs::Permissions::from_mode(0o755))?;
+		let plain_chain_spec = temp_dir.path().join("plain-parachain-chainspec.json");
+		generate_plain_chain_spec(&binary_path, &plain_chain_spec, true, None, None)?;
+		assert_eq!(ChainSpec::from(&plain_chain_spec)?.get_relay_chain(), Some("rococo-local"));
+		for relay_chain in ["paseo", "westend", "polkadot"] {
+			generate_plain_chain_spec(
+				&binary_path,
+				&plain_chain_spec,
+				true,
+				None,
+				Some(relay_chain),
+			)?;
+			assert_eq!(ChainSpec::from(&plain_chain_spec)?.get_relay_chain(), Some(relay_chain));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn parse_presets_works() -> Result<()> {
+		assert_eq!(
+			parse_presets("{\"presets\":[\"development\",\"local_testnet\"]}\n")?,
+			["development", "local_testnet"]
+		);
+		assert_eq!(parse_presets("Known presets are:\n[\"development\"]")?, ["development"]);
+		assert_eq!(parse_presets("{}")?, Vec::<String>::new());
+		assert!(matches!(parse_presets("no presets"), Err(Error::Config(..))));
+		assert!(matches!(parse_presets("[1]"), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn apply_patch_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut chain_spec = ChainSpec(json!({
+			"name": "Local",
+			"genesis": {"runtimeGenesis": {"patch": {
+				"balances": {"balances": [["5Alice", 100]]},
+				"collatorSelection": {"candidacyBond": 16, "invulnerables": ["5Alice"]},
+				"sudo": {"key": "5Alice"},
+			}}},
+		}));
+		let patch = temp_dir.path().join("patch.json");
+		fs::write(
+			&patch,
+			json!({"genesis": {"runtimeGenesis": {"patch": {
+				"balances": {"balances": [["5Bob", 200]]},
+				"collatorSelection": {"invulnerables": ["5Bob", "5Charlie"]},
+				"sudo": null,
+			}}}})
+			.to_string(),
+		)?;
+		chain_spec.apply_patch_file(&patch)?;
+		assert_eq!(
+			chain_spec.0,
+			json!({
+				"name": "Local",
+				"genesis": {"runtimeGenesis": {"patch": {
+					"balances": {"balances": [["5Bob", 200]]},
+					"collatorSelection": {"candidacyBond": 16, "invulnerables": ["5Bob", "5Charlie"]},
+				}}},
+			})
+		);
+		assert!(matches!(chain_spec.apply_patch(json!([])), Err(Error::Config(_))));
+		assert!(matches!(
+			chain_spec.apply_patch_file(&temp_dir.path().join("missing.json")),
+			Err(Error::Config(_))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn replace_genesis_works() -> Result<()> {
+		let mut chain_spec = ChainSpec(json!({
+			"name": "Development",
+			"id": "dev",
+			"properties": null,
+			"genesis": {
+				"runtimeGenesis": {
+					"patch": {
+						"balances": { "balances": [["Alice", 1]] },
+						"sudo": { "key": "Alice" }
+					}
+				}
+			},
+		}));
+		chain_spec.replace_name("Pop Testnet")?;
+		chain_spec.replace_id("pop-testnet")?;
+		chain_spec.replace_token_symbol("POP")?;
+		chain_spec.replace_token_decimals(10)?;
+		chain_spec.replace_sudo_key("Bob")?;
+		chain_spec.replace_endowments(&[("Bob".into(), 1_000), ("Charlie".into(), 2_000)])?;
+		assert_eq!(chain_spec.get_name(), Some("Pop Testnet"));
+		assert_eq!(chain_spec.get_id(), Some("pop-testnet"));
+		assert_eq!(chain_spec.get_token_symbol(), Some("POP"));
+		assert_eq!(chain_spec.get_token_decimals(), Some(10));
+		assert_eq!(chain_spec.get_ss58_format(), None);
+		assert_eq!(chain_spec.get_sudo_key(), Some("Bob"));
+		assert_eq!(
+			chain_spec.get_endowments(),
+			Some(vec![("Bob".into(), 1_000), ("Charlie".into(), 2_000)])
+		);
+		assert_eq!(
+			chain_spec.0,
+			json!({
+				"name": "Pop Testnet",
+				"id": "pop-testnet",
+				"properties": { "tokenSymbol": "POP", "tokenDecimals": 10 },
+				"genesis": {
+					"runtimeGenesis": {
+						"patch": {
+							"balances": { "balances": [["Bob", 1_000], ["Charlie", 2_000]] },
+							"sudo": { "key": "Bob" }
+						}
+					}
+				},
+			})
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn replace_genesis_fails() -> Result<()> {
+		let mut chain_spec = ChainSpec(json!({
+			"genesis": { "runtimeGenesis": { "patch": {} } },
+			"properties": "invalid",
+		}));
+		assert!(
+			matches!(chain_spec.replace_name("Pop"), Err(Error::Config(error)) if error == "expected `name`")
+		);
+		assert!(
+			matches!(chain_spec.replace_id("pop"), Err(Error::Config(error)) if error == "expected `id`")
+		);
+		assert!(
+			matches!(chain_spec.replace_token_symbol("POP"), Err(Error::Config(error)) if error == "expected `properties` to be an object")
+		);
+		assert!(
+			matches!(chain_spec.replace_sudo_key("Bob"), Err(Error::Config(error)) if error == "expected `sudo`")
+		);
+		assert!(
+			matches!(chain_spec.replace_endowments(&[]), Err(Error::Config(error)) if error == "expected `balances`")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn replace_evm_genesis_works() -> Result<()> {
+		const ALITH: &str = "0xf24FF3a9CF04c71Dbc94D0b566f7A27B94566cac";
+		const BALTATHAR: &str = "0x3Cd0A705a2DC65e5b1E1205896BaA2be8A07c6e0";
+		const PRECOMPILE: &str = "0x0000000000000000000000000000000000000800";
+		let mut chain_spec = ChainSpec(json!({
+			"genesis": {
+				"runtimeGenesis": {
+					"patch": {
+						"evmChainId": { "chainId": 1000 },
+						"evm": {
+							"accounts": {
+								"0xd43593c715fdd31c61141abd04a99fd6822c8558": {
+									"balance": "0xff", "code": [], "nonce": "0x0", "storage": {}
+								},
+								"0x0000000000000000000000000000000000000400": {
+									"balance": "0x0", "code": "0x60006000fd", "nonce": "0x0", "storage": {}
+								}
+							}
+						}
+					}
+				}
+			},
+		}));
+		assert_eq!(
+			chain_spec.get_evm_precompiles(),
+			vec!["0x0000000000000000000000000000000000000400".to_string()]
+		);
+		chain_spec.replace_evm_chain_id(42)?;
+		chain_spec.replace_evm_precompiles(&[PRECOMPILE.into()])?;
+		chain_spec.replace_evm_balances(&[(ALITH.into(), 1_000), (PRECOMPILE.into(), 1)])?;
+		chain_spec.replace_evm_balances(&[(ALITH.into(), 1_000), (BALTATHAR.into(), 2_000)])?;
+		assert_eq!(chain_spec.get_evm_chain_id(), Some(42));
+		assert_eq!(chain_spec.get_evm_precompiles(), vec![PRECOMPILE.to_string()]);
+		assert_eq!(
+			chain_spec.get_evm_balances(),
+			Some(vec![
+				("0xf24ff3a9cf04c71dbc94d0b566f7a27b94566cac".into(), 1_000),
+				("0x3cd0a705a2dc65e5b1e1205896baa2be8a07c6e0".into(), 2_000)
+			])
+		);
+		assert_eq!(
+			chain_spec.0.pointer("/genesis/runtimeGenesis/patch/evm/accounts"),
+			Some(&json!({
+				PRECOMPILE: { "balance": "0x1", "code": [96, 0, 96, 0, 253], "nonce": "0x0", "storage": {} },
+				"0xf24ff3a9cf04c71dbc94d0b566f7a27b94566cac": {
+					"balance": "0x3e8", "code": [], "nonce": "0x0", "storage": {}
+				},
+				"0x3cd0a705a2dc65e5b1e1205896baa2be8a07c6e0": {
+					"balance": "0x7d0", "code": [], "nonce": "0x0", "storage": {}
+				}
+			}))
+		);
+		assert!(matches!(
+			chain_spec.replace_evm_balances(&[("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into(), 1)]),
+			Err(Error::Config(error)) if error == "`5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY` is not a 20-byte EVM address"
+		));
+		let mut chain_spec = ChainSpec(json!({ "genesis": { "runtimeGenesis": { "patch": {} } } }));
+		assert!(
+			matches!(chain_spec.replace_evm_chain_id(42), Err(Error::Config(error)) if error == "expected `evmChainId`")
+		);
+		assert!(
+			matches!(chain_spec.replace_evm_precompiles(&[]), Err(Error::Config(error)) if error == "expected `evm`")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn replace_collators_works() -> Result<()> {
+		const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+		const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+		const CHARLIE: &str = "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y";
+		let collator = |account: &str, keys: Value| CollatorKeys {
+			account: account.into(),
+			keys: keys.as_object().unwrap().clone(),
+		};
+		let genesis = json!({
+			"genesis": {
+				"runtimeGenesis": {
+					"patch": {
+						"collatorSelection": { "candidacyBond": 16, "invulnerables": [ALICE] },
+						"session": { "keys": [[ALICE, ALICE, { "aura": ALICE }]] }
+					}
+				}
+			},
+		});
+		let mut chain_spec = ChainSpec(genesis.clone());
+		let collators =
+			[collator(BOB, json!({ "aura": BOB })), collator(CHARLIE, json!({ "aura": CHARLIE }))];
+		chain_spec.replace_collators(&collators)?;
+		assert_eq!(
+			chain_spec.0,
+			json!({
+				"genesis": {
+					"runtimeGenesis": {
+						"patch": {
+							"collatorSelection": { "candidacyBond": 16, "invulnerables": [BOB, CHARLIE] },
+							"session": { "keys": [
+								[BOB, BOB, { "aura": BOB }],
+								[CHARLIE, CHARLIE, { "aura": CHARLIE }]
+							] }
+						}
+					}
+				},
+			})
+		);
+
+		// Invalid collators leave the chain specification unchanged.
+		let mut chain_spec = ChainSpec(genesis.clone());
+		for (collators, expected) in [
+			(vec![], "expected at least one collator"),
+			(vec![collator("Bob", json!({ "aura": BOB }))], "invalid collator `Bob`"),
+			(
+				vec![collator(BOB, json!({ "aura": BOB })), collator(BOB, json!({ "aura": BOB }))],
+				"is specified more than once",
+			),
+			(
+				vec![
+					collator(BOB, json!({ "aura": BOB })),
+					collator(CHARLIE, json!({ "aura": CHARLIE, "grandpa": CHARLIE })),
+				],
+				"to provide the same session keys",
+			),
+		] {
+			assert!(matches!(
+				chain_spec.replace_collators(&collators),
+				Err(Error::Config(error)) if error.contains(expected)
+			));
+			assert_eq!(chain_spec.0, genesis);
+		}
+
+		let genesis = json!({ "genesis": { "runtimeGenesis": { "patch": {
+			"collatorSelection": { "invulnerables": [ALICE] }
+		} } } });
+		let mut chain_spec = ChainSpec(genesis.clone());
+		assert!(
+			matches!(chain_spec.replace_collators(&collators), Err(Error::Config(error)) if error == "expected `session`")
+		);
+		assert_eq!(chain_spec.0, genesis);
+		Ok(())
+	}
+
 	#[test]
 	fn check_command_exists_fails() -> Result<()> {
 		let binary_path = PathBuf::from("/bin");
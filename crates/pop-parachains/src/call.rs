@@ -0,0 +1,753 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	errors::Error,
+	fees::{fee_asset, AssetLocation, CHARGE_ASSET_TX_PAYMENT},
+};
+use pop_common::{
+	create_ecdsa_signer, create_ethereum_signer, create_signer, parse_arg, Account, AssetFeeConfig,
+	EthereumConfig,
+};
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive, Variant};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+use subxt::{
+	blocks::ExtrinsicEvents,
+	config::{DefaultExtrinsicParams, DefaultExtrinsicParamsBuilder},
+	dynamic::Value,
+	metadata::types::StorageEntryType,
+	tx::{DynamicPayload, Payload, Signer},
+	utils::H256,
+	Config, Metadata, OnlineClient, SubstrateConfig,
+};
+
+/// A signature scheme with which transactions are signed.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum SignatureScheme {
+	/// Schnorrkel signatures, as used by most Substrate chains.
+	Sr25519,
+	/// Ed25519 signatures.
+	Ed25519,
+	/// ECDSA signatures, with 32-byte accounts derived from the public key.
+	Ecdsa,
+	/// ECDSA signatures over the keccak-256 hash of the payload, with 20-byte accounts derived
+	/// from the public key, as used by Ethereum-compatible chains.
+	Ethereum,
+}
+
+/// The accounts of a chain and the signature schemes with which they sign, as determined from
+/// the metadata of the chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainAccounts {
+	/// Whether the chain uses 20-byte (Ethereum-style) accounts.
+	pub ethereum: bool,
+	/// The signature schemes accepted by the chain.
+	pub schemes: Vec<SignatureScheme>,
+}
+
+impl ChainAccounts {
+	/// Detects the accounts of a chain from its metadata.
+	///
+	/// # Arguments
+	/// * `metadata` - The metadata of the chain.
+	pub fn detect(metadata: &Metadata) -> Self {
+		let ethereum = uses_ethereum_accounts(metadata);
+		let types = metadata.types();
+		let signature = metadata.extrinsic().signature_ty();
+		let mut schemes: Vec<_> = match types.resolve(signature).map(|t| &t.type_def) {
+			// e.g. `MultiSignature`, whose variants are named after the schemes.
+			Some(TypeDef::Variant(variant)) => variant
+				.variants
+				.iter()
+				.filter_map(|v| v.name.to_lowercase().parse().ok())
+				.collect(),
+			_ if encoded_len(types, signature) == Some(65) => vec![SignatureScheme::Ethereum],
+			_ => vec![],
+		};
+		if ethereum && schemes.is_empty() {
+			schemes.push(SignatureScheme::Ethereum);
+		}
+		Self { ethereum, schemes }
+	}
+
+	/// Resolves the signature scheme with which transactions are signed, rejecting schemes which
+	/// are not accepted by the chain. Defaults to the scheme conventionally used by the chain.
+	///
+	/// # Arguments
+	/// * `scheme` - The signature scheme requested, if any.
+	pub fn scheme(&self, scheme: Option<SignatureScheme>) -> Result<SignatureScheme, Error> {
+		use SignatureScheme::*;
+		let scheme = match scheme {
+			Some(scheme) => scheme,
+			None if self.ethereum => Ethereum,
+			// The schemes of chains with an unknown signature type cannot be validated.
+			None if self.schemes.is_empty() => Sr25519,
+			None => [Sr25519, Ecdsa]
+				.into_iter()
+				.find(|s| self.schemes.contains(s))
+				.ok_or_else(|| Error::IncompatibleSigner(self.describe()))?,
+		};
+		if !self.schemes.is_empty() && !self.schemes.contains(&scheme) {
+			return Err(Error::IncompatibleSigner(format!(
+				"{scheme} keys cannot sign for this chain, which {}",
+				self.describe()
+			)));
+		}
+		if scheme == Ed25519 {
+			return Err(Error::IncompatibleSigner("ed25519 keys are not yet supported".into()));
+		}
+		Ok(scheme)
+	}
+
+	/// A description of the accounts of the chain.
+	fn describe(&self) -> String {
+		let schemes: Vec<_> = self.schemes.iter().map(|s| s.to_string()).collect();
+		let schemes = format!("{} keys", schemes.join(", "));
+		match self.ethereum {
+			true => format!("uses 20-byte (Ethereum-style) accounts and accepts {schemes}"),
+			false => format!("uses 32-byte accounts and accepts {schemes}"),
+		}
+	}
+}
+
+/// How the fees of a transaction are paid, so that sponsored transactions can be submitted where
+/// the chain supports them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum FeePayment {
+	/// The signer pays the fees in the native currency of the chain.
+	#[default]
+	Native,
+	/// The signer pays the fees in an asset of the `Assets` pallet, identified by its id, via the
+	/// `ChargeAssetTxPayment` signed extension: either converted by the minimum balance of the
+	/// asset (`pallet-asset-tx-payment`), or, where the chain identifies assets by location (e.g.
+	/// asset hubs), swapped via an asset conversion pool.
+	Asset(u32),
+	/// The signer pays the fees in an asset identified by its XCM location (e.g. a foreign asset
+	/// on an asset hub), via the `ChargeAssetTxPayment` signed extension, swapped via an asset
+	/// conversion pool. Requires the chain to identify assets by location.
+	Location(AssetLocation),
+	/// The signer pays the fees as a proxy of an account, on whose behalf the call is dispatched
+	/// via `proxy.proxy`.
+	Proxy(String),
+}
+
+impl FeePayment {
+	/// Checks that the chain supports the fee payment.
+	///
+	/// # Arguments
+	/// * `metadata` - The metadata of the chain.
+	pub fn ensure(&self, metadata: &Metadata) -> Result<(), Error> {
+		let supported = match self {
+			FeePayment::Native => true,
+			FeePayment::Asset(_) | FeePayment::Location(_) => metadata
+				.extrinsic()
+				.signed_extensions()
+				.iter()
+				.any(|e| e.identifier() == CHARGE_ASSET_TX_PAYMENT),
+			FeePayment::Proxy(_) => metadata.pallet_by_name("Proxy").is_some(),
+		};
+		match supported {
+			true => Ok(()),
+			false => Err(Error::UnsupportedCommand(format!(
+				"the chain does not support fees paid {}",
+				self.describe()
+			))),
+		}
+	}
+
+	/// Prepares a call for submission, wrapping it within `proxy.proxy` for proxy-paid fees.
+	///
+	/// # Arguments
+	/// * `call` - The call to be submitted.
+	pub fn prepare(&self, call: &DynamicPayload) -> Result<DynamicPayload, Error> {
+		let FeePayment::Proxy(real) = self else {
+			return Ok(call.clone());
+		};
+		let account =
+			Account::parse(real).map_err(|e| Error::InvalidAccount(format!("{real}: {e}")))?;
+		let real = match account {
+			Account::Id20(account) => Value::from_bytes(account),
+			Account::Id32(account) => Value::unnamed_variant("Id", [Value::from_bytes(account)]),
+		};
+		Ok(subxt::dynamic::tx(
+			"Proxy",
+			"proxy",
+			vec![real, Value::unnamed_variant("None", []), call.clone().into_value()],
+		))
+	}
+
+	/// A description of how fees are paid.
+	fn describe(&self) -> String {
+		match self {
+			FeePayment::Native => "in the native currency".into(),
+			FeePayment::Asset(asset) => format!("in asset {asset}, via `ChargeAssetTxPayment`"),
+			FeePayment::Location(location) =>
+				format!("in the asset at location {location}, via `ChargeAssetTxPayment`"),
+			FeePayment::Proxy(real) => format!("by a proxy of {real}, via `proxy.proxy`"),
+		}
+	}
+}
+
+impl FromStr for FeePayment {
+	type Err = Error;
+
+	/// Parses a fee payment, specified as `native`, `asset:<id>`, `location:<location>` or
+	/// `proxy:<account>`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || {
+			Error::Config(format!(
+				"invalid fee payment `{s}`: expected `native`, `asset:<id>`, `location:<location>` or `proxy:<account>`"
+			))
+		};
+		match s.split_once(':') {
+			None if s == "native" => Ok(FeePayment::Native),
+			Some(("asset", asset)) => asset.parse().map(FeePayment::Asset).map_err(|_| invalid()),
+			Some(("location", location)) => location.parse().map(FeePayment::Location),
+			Some(("proxy", real)) if !real.is_empty() => Ok(FeePayment::Proxy(real.to_string())),
+			_ => Err(invalid()),
+		}
+	}
+}
+
+/// Sets up an [OnlineClient] instance for connecting to a blockchain.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+pub async fn set_up_client(url: &str) -> Result<OnlineClient<SubstrateConfig>, Error> {
+	OnlineClient::<SubstrateConfig>::from_url(url)
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))
+}
+
+/// Sets up an [OnlineClient] instance for connecting to an Ethereum-compatible chain (e.g.
+/// Frontier-based), whose accounts are 20-byte accounts.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+pub async fn set_up_ethereum_client(url: &str) -> Result<OnlineClient<EthereumConfig>, Error> {
+	OnlineClient::<EthereumConfig>::from_url(url)
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))
+}
+
+/// Whether the chain uses 20-byte (Ethereum-style) accounts, determined by the key of the
+/// `system.account` storage map.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+pub fn uses_ethereum_accounts(metadata: &Metadata) -> bool {
+	let key = metadata
+		.pallet_by_name("System")
+		.and_then(|p| p.storage())
+		.and_then(|s| s.entry_by_name("Account"))
+		.map(|e| e.entry_type());
+	match key {
+		Some(StorageEntryType::Map { key_ty, .. }) =>
+			encoded_len(metadata.types(), *key_ty) == Some(20),
+		_ => false,
+	}
+}
+
+/// The encoded length of a type consisting only of bytes, such as an account.
+fn encoded_len(types: &PortableRegistry, id: u32) -> Option<u32> {
+	match &types.resolve(id)?.type_def {
+		TypeDef::Primitive(TypeDefPrimitive::U8) => Some(1),
+		TypeDef::Array(array) => Some(array.len * encoded_len(types, array.type_param.id)?),
+		TypeDef::Composite(composite) =>
+			composite.fields.iter().map(|f| encoded_len(types, f.ty.id)).sum(),
+		TypeDef::Tuple(tuple) => tuple.fields.iter().map(|f| encoded_len(types, f.id)).sum(),
+		_ => None,
+	}
+}
+
+/// Wraps the provided call within `sudo.sudo`, so that it is dispatched with `Root` origin.
+///
+/// # Arguments
+/// * `call` - The call to be dispatched by the sudo key.
+pub fn construct_sudo_extrinsic(call: DynamicPayload) -> DynamicPayload {
+	subxt::dynamic::tx("Sudo", "sudo", vec![call.into_value()])
+}
+
+/// Wraps the provided call within `sudo.sudo_unchecked_weight`, so that it is dispatched with
+/// `Root` origin without its weight being checked. Required for calls such as `system.set_code`,
+/// whose weight can exceed the limits of a block.
+///
+/// # Arguments
+/// * `call` - The call to be dispatched by the sudo key.
+pub fn construct_sudo_unchecked_weight_extrinsic(call: DynamicPayload) -> DynamicPayload {
+	let weight =
+		Value::named_composite([("ref_time", Value::u128(0)), ("proof_size", Value::u128(0))]);
+	subxt::dynamic::tx("Sudo", "sudo_unchecked_weight", vec![call.into_value(), weight])
+}
+
+/// Constructs a call which dispatches the provided calls atomically via `utility.batch_all`.
+///
+/// # Arguments
+/// * `calls` - The calls to be dispatched.
+pub fn construct_batch_extrinsic(calls: Vec<DynamicPayload>) -> DynamicPayload {
+	let calls = calls.into_iter().map(|call| call.into_value()).collect::<Vec<_>>();
+	subxt::dynamic::tx("Utility", "batch_all", vec![Value::unnamed_composite(calls)])
+}
+
+/// Constructs a call which transfers funds to an account via `balances.transfer_keep_alive`.
+///
+/// # Arguments
+/// * `dest` - The address of the account to be funded, either an SS58 address or a hex-encoded
+///   20-byte account of an Ethereum-compatible chain.
+/// * `amount` - The amount to be transferred, in the smallest unit of the chain's currency.
+pub fn construct_transfer_extrinsic(dest: &str, amount: u128) -> Result<DynamicPayload, Error> {
+	let account =
+		Account::parse(dest).map_err(|e| Error::InvalidAccount(format!("{dest}: {e}")))?;
+	// Ethereum-compatible chains look accounts up by their identity, rather than an address.
+	let dest = match account {
+		Account::Id20(account) => Value::from_bytes(account),
+		Account::Id32(account) => Value::unnamed_variant("Id", [Value::from_bytes(account)]),
+	};
+	Ok(subxt::dynamic::tx("Balances", "transfer_keep_alive", vec![dest, Value::u128(amount)]))
+}
+
+/// Constructs a call to any dispatchable function of the chain, validating its arguments against
+/// the metadata of the chain. Arguments may be specified in a JSON5 or Rust struct-literal style
+/// (see [`parse_arg`]), so that complex types can be composed without encoding them manually.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `pallet` - The name of the pallet (e.g. "Balances").
+/// * `function` - The name of the function (e.g. "transfer_keep_alive").
+/// * `args` - The arguments of the function, as specified.
+pub fn construct_extrinsic(
+	metadata: &Metadata,
+	pallet: &str,
+	function: &str,
+	args: &[String],
+) -> Result<DynamicPayload, Error> {
+	let call = find_call(metadata, pallet, function)?;
+	if call.fields.len() != args.len() {
+		return Err(Error::Config(format!(
+			"`{pallet}::{function}` expects {} arguments, found {}",
+			call.fields.len(),
+			args.len()
+		)));
+	}
+	let values = call
+		.fields
+		.iter()
+		.zip(args)
+		.enumerate()
+		.map(|(i, (field, arg))| {
+			let name = field.name.clone().unwrap_or_else(|| i.to_string());
+			Ok(parse_arg(&name, arg, field.ty.id, metadata.types())?)
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+	Ok(subxt::dynamic::tx(pallet, function, values))
+}
+
+/// The names of the arguments of a dispatchable function of the chain, in order. Unnamed
+/// arguments are named by their position.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `pallet` - The name of the pallet (e.g. "Balances").
+/// * `function` - The name of the function (e.g. "transfer_keep_alive").
+pub fn call_arg_names(
+	metadata: &Metadata,
+	pallet: &str,
+	function: &str,
+) -> Result<Vec<String>, Error> {
+	let call = find_call(metadata, pallet, function)?;
+	Ok(call
+		.fields
+		.iter()
+		.enumerate()
+		.map(|(i, field)| field.name.clone().unwrap_or_else(|| i.to_string()))
+		.collect())
+}
+
+fn find_call<'a>(
+	metadata: &'a Metadata,
+	pallet: &str,
+	function: &str,
+) -> Result<&'a Variant<PortableForm>, Error> {
+	metadata
+		.pallet_by_name(pallet)
+		.and_then(|p| p.call_variant_by_name(function))
+		.ok_or_else(|| {
+			Error::Config(format!("the `{pallet}::{function}` call was not found in the metadata"))
+		})
+}
+
+/// Signs and submits a given extrinsic, waiting for it to be finalized successfully. The key is
+/// of the signature scheme conventionally used by the chain, as detected from its metadata.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `call` - The call to be signed and submitted.
+/// * `suri` - The secret URI (e.g., mnemonic or private key) for signing the extrinsic.
+pub async fn sign_and_submit_extrinsic(
+	client: &OnlineClient<SubstrateConfig>,
+	call: &impl Payload,
+	suri: &str,
+) -> Result<ExtrinsicEvents<SubstrateConfig>, Error> {
+	match substrate_scheme(&ChainAccounts::detect(&client.metadata()))? {
+		SignatureScheme::Ecdsa => submit(client, call, &create_ecdsa_signer(suri)?, None).await,
+		_ => submit(client, call, &create_signer(suri)?, None).await,
+	}
+}
+
+/// Resolves the signature scheme conventionally used by a chain, rejecting chains with 20-byte
+/// accounts, whose transactions cannot be constructed with the Substrate configuration.
+///
+/// # Arguments
+/// * `accounts` - The accounts of the chain.
+fn substrate_scheme(accounts: &ChainAccounts) -> Result<SignatureScheme, Error> {
+	match accounts.scheme(None)? {
+		SignatureScheme::Ethereum => Err(Error::IncompatibleSigner(format!(
+			"this transaction can only be signed for chains with 32-byte accounts, but this chain {}",
+			accounts.describe()
+		))),
+		scheme => Ok(scheme),
+	}
+}
+
+/// Signs and submits a given extrinsic, waiting for it to be finalized successfully. The account
+/// type and signature schemes of the chain are detected from its metadata, so that the signer is
+/// configured accordingly. Returns the hash of the extrinsic.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+/// * `call` - The call to be signed and submitted.
+/// * `suri` - The secret URI (e.g., mnemonic or private key) for signing the extrinsic.
+/// * `scheme` - The signature scheme of the key, otherwise the scheme conventionally used by the
+///   chain.
+/// * `fee_payment` - How the fees of the transaction are paid, allowing sponsored transactions.
+pub async fn submit_extrinsic(
+	url: &str,
+	call: &DynamicPayload,
+	suri: &str,
+	scheme: Option<SignatureScheme>,
+	fee_payment: &FeePayment,
+) -> Result<H256, Error> {
+	let client = set_up_client(url).await?;
+	let metadata = client.metadata();
+	fee_payment.ensure(&metadata)?;
+	let call = fee_payment.prepare(call)?;
+	let asset = fee_asset(&client, fee_payment).await?;
+	let hash = match (ChainAccounts::detect(&metadata).scheme(scheme)?, asset) {
+		(SignatureScheme::Ethereum, asset) => {
+			let client = set_up_ethereum_client(url).await?;
+			submit(&client, &call, &create_ethereum_signer(suri)?, asset)
+				.await?
+				.extrinsic_hash()
+		},
+		// Fees paid in an asset require the asset to be identified as on the chain.
+		(scheme, Some(asset)) => {
+			let client = OnlineClient::<AssetFeeConfig>::from_url(url)
+				.await
+				.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+			let events = match scheme {
+				SignatureScheme::Ecdsa =>
+					submit(&client, &call, &create_ecdsa_signer(suri)?, Some(asset)).await?,
+				_ => submit(&client, &call, &create_signer(suri)?, Some(asset)).await?,
+			};
+			events.extrinsic_hash()
+		},
+		(SignatureScheme::Ecdsa, None) => submit(&client, &call, &create_ecdsa_signer(suri)?, None)
+			.await?
+			.extrinsic_hash(),
+		(_, None) => submit(&client, &call, &create_signer(suri)?, None).await?.extrinsic_hash(),
+	};
+	Ok(hash)
+}
+
+/// Signs and submits a given extrinsic to an Ethereum-compatible chain with an ECDSA key,
+/// waiting for it to be finalized successfully.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `call` - The call to be signed and submitted.
+/// * `suri` - The secret URI (e.g., mnemonic or private key) for signing the extrinsic.
+pub async fn sign_and_submit_ethereum_extrinsic(
+	client: &OnlineClient<EthereumConfig>,
+	call: &impl Payload,
+	suri: &str,
+) -> Result<ExtrinsicEvents<EthereumConfig>, Error> {
+	submit(client, call, &create_ethereum_signer(suri)?, None).await
+}
+
+/// Signs and submits a call, paying its fees in the specified asset or otherwise the native
+/// currency.
+async fn submit<C: Config<ExtrinsicParams = DefaultExtrinsicParams<C>>>(
+	client: &OnlineClient<C>,
+	call: &impl Payload,
+	signer: &impl Signer<C>,
+	fee_asset: Option<C::AssetId>,
+) -> Result<ExtrinsicEvents<C>, Error> {
+	let params = match fee_asset {
+		Some(asset) => DefaultExtrinsicParamsBuilder::<C>::new().tip_of(0, asset),
+		None => DefaultExtrinsicParamsBuilder::<C>::new(),
+	};
+	client
+		.tx()
+		.sign_and_submit_then_watch(call, signer, params.build())
+		.await
+		.map_err(|e| Error::ExtrinsicSubmissionError(format!("{e:?}")))?
+		.wait_for_finalized_success()
+		.await
+		.map_err(|e| Error::ExtrinsicSubmissionError(format!("{e:?}")))
+}
+
+/// Returns the named field of the first event matching `pallet` and `variant`, if emitted.
+///
+/// # Arguments
+/// * `events` - The events emitted by an extrinsic.
+/// * `pallet` - The name of the pallet emitting the event.
+/// * `variant` - The name of the event.
+/// * `field` - The name of the field to be returned.
+pub(crate) fn find_event_field(
+	events: &ExtrinsicEvents<SubstrateConfig>,
+	pallet: &str,
+	variant: &str,
+	field: &str,
+) -> Result<Option<Value<u32>>, Error> {
+	for event in events.iter() {
+		let event = event?;
+		if event.pallet_name() != pallet || event.variant_name() != variant {
+			continue;
+		}
+		let fields = event.field_values().map_err(subxt::Error::from)?;
+		return Ok(match fields {
+			subxt::ext::scale_value::Composite::Named(fields) =>
+				fields.into_iter().find(|(name, _)| name == field).map(|(_, value)| value),
+			subxt::ext::scale_value::Composite::Unnamed(_) => None,
+		});
+	}
+	Ok(None)
+}
+
+/// Fetches a storage value of the chain at the latest block, returning `None` if the storage entry
+/// is not available within the runtime or no value is stored.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `pallet` - The name of the pallet.
+/// * `entry` - The name of the storage entry.
+/// * `keys` - The keys of the storage entry.
+pub(crate) async fn fetch_storage(
+	client: &OnlineClient<SubstrateConfig>,
+	pallet: &str,
+	entry: &str,
+	keys: Vec<Value>,
+) -> Result<Option<Value<u32>>, Error> {
+	let metadata = client.metadata();
+	if metadata
+		.pallet_by_name(pallet)
+		.and_then(|p| p.storage())
+		.and_then(|s| s.entry_by_name(entry))
+		.is_none()
+	{
+		return Ok(None);
+	}
+	let address = subxt::dynamic::storage(pallet, entry, keys);
+	match client.storage().at_latest().await?.fetch(&address).await? {
+		Some(value) => Ok(Some(value.to_value().map_err(subxt::Error::from)?)),
+		None => Ok(None),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn set_up_client_fails_wrong_url() {
+		assert!(matches!(
+			set_up_client("wss://wronguri.xyz").await,
+			Err(Error::ConnectionFailure(_))
+		));
+	}
+
+	#[test]
+	fn fee_payment_works() -> Result<(), Error> {
+		assert_eq!("native".parse::<FeePayment>()?, FeePayment::Native);
+		assert_eq!("asset:1984".parse::<FeePayment>()?, FeePayment::Asset(1984));
+		let location = "location:1/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)";
+		assert_eq!(
+			location.parse::<FeePayment>()?,
+			FeePayment::Location(
+				"1/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)".parse()?
+			)
+		);
+		let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+		let proxy = format!("proxy:{alice}").parse::<FeePayment>()?;
+		assert_eq!(proxy, FeePayment::Proxy(alice.to_string()));
+		for invalid in ["", "asset:usdt", "location:", "location:1/X(1)", "proxy:", "sponsor:1"] {
+			assert!(matches!(invalid.parse::<FeePayment>(), Err(Error::Config(_))));
+		}
+
+		let call = construct_batch_extrinsic(vec![]);
+		assert_eq!(FeePayment::Asset(1984).prepare(&call)?, call);
+		let proxied = proxy.prepare(&call)?;
+		assert_eq!(proxied.pallet_name(), "Proxy");
+		assert_eq!(proxied.call_name(), "proxy");
+		assert!(matches!(
+			FeePayment::Proxy("invalid".into()).prepare(&call),
+			Err(Error::InvalidAccount(_))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn chain_accounts_scheme_works() -> Result<(), Error> {
+		use SignatureScheme::*;
+		let substrate = ChainAccounts { ethereum: false, schemes: vec![Ed25519, Sr25519, Ecdsa] };
+		assert_eq!(substrate.scheme(None)?, Sr25519);
+		assert_eq!(substrate.scheme(Some(Ecdsa))?, Ecdsa);
+		assert!(matches!(
+			substrate.scheme(Some(Ethereum)),
+			Err(Error::IncompatibleSigner(e)) if e == "ethereum keys cannot sign for this chain, which uses 32-byte accounts and accepts ed25519, sr25519, ecdsa keys"
+		));
+		assert!(matches!(substrate.scheme(Some(Ed25519)), Err(Error::IncompatibleSigner(..))));
+
+		let ethereum = ChainAccounts { ethereum: true, schemes: vec![Ethereum] };
+		assert_eq!(ethereum.scheme(None)?, Ethereum);
+		assert!(matches!(
+			ethereum.scheme(Some(Sr25519)),
+			Err(Error::IncompatibleSigner(e)) if e == "sr25519 keys cannot sign for this chain, which uses 20-byte (Ethereum-style) accounts and accepts ethereum keys"
+		));
+		let unknown = ChainAccounts { ethereum: false, schemes: vec![] };
+		assert_eq!(unknown.scheme(None)?, Sr25519);
+		assert_eq!(unknown.scheme(Some(Ethereum))?, Ethereum);
+		assert_eq!("ECDSA".to_lowercase().parse::<SignatureScheme>(), Ok(Ecdsa));
+		Ok(())
+	}
+
+	#[test]
+	fn substrate_scheme_works() -> Result<(), Error> {
+		use SignatureScheme::*;
+		let substrate = ChainAccounts { ethereum: false, schemes: vec![Sr25519, Ecdsa] };
+		assert_eq!(substrate_scheme(&substrate)?, Sr25519);
+		let ecdsa = ChainAccounts { ethereum: false, schemes: vec![Ecdsa] };
+		assert_eq!(substrate_scheme(&ecdsa)?, Ecdsa);
+		let ethereum = ChainAccounts { ethereum: true, schemes: vec![Ethereum] };
+		assert!(matches!(
+			substrate_scheme(&ethereum),
+			Err(Error::IncompatibleSigner(e)) if e == "this transaction can only be signed for chains with 32-byte accounts, but this chain uses 20-byte (Ethereum-style) accounts and accepts ethereum keys"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn construct_sudo_extrinsic_works() {
+		let call = subxt::dynamic::tx(
+			"Registrar",
+			"force_register",
+			vec![Value::u128(2000), Value::u128(0)],
+		);
+		let sudo = construct_sudo_extrinsic(call);
+		assert_eq!(sudo.pallet_name(), "Sudo");
+		assert_eq!(sudo.call_name(), "sudo");
+		assert_eq!(
+			sudo.into_value(),
+			Value::unnamed_variant(
+				"Sudo",
+				[Value::unnamed_variant(
+					"sudo",
+					[Value::unnamed_variant(
+						"Registrar",
+						[Value::unnamed_variant(
+							"force_register",
+							[Value::u128(2000), Value::u128(0)]
+						)]
+					)]
+				)]
+			)
+		);
+	}
+
+	#[test]
+	fn construct_sudo_unchecked_weight_extrinsic_works() {
+		let call = subxt::dynamic::tx("System", "set_code", vec![Value::from_bytes([1, 2, 3])]);
+		let sudo = construct_sudo_unchecked_weight_extrinsic(call);
+		assert_eq!(sudo.pallet_name(), "Sudo");
+		assert_eq!(sudo.call_name(), "sudo_unchecked_weight");
+		assert_eq!(
+			sudo.into_value(),
+			Value::unnamed_variant(
+				"Sudo",
+				[Value::unnamed_variant(
+					"sudo_unchecked_weight",
+					[
+						Value::unnamed_variant(
+							"System",
+							[Value::unnamed_variant("set_code", [Value::from_bytes([1, 2, 3])])]
+						),
+						Value::named_composite([
+							("ref_time", Value::u128(0)),
+							("proof_size", Value::u128(0))
+						]),
+					]
+				)]
+			)
+		);
+	}
+
+	#[test]
+	fn construct_batch_extrinsic_works() {
+		let calls = vec![
+			subxt::dynamic::tx("Registrar", "deregister", vec![Value::u128(2000)]),
+			subxt::dynamic::tx("Registrar", "deregister", vec![Value::u128(2001)]),
+		];
+		assert_eq!(
+			construct_batch_extrinsic(calls).into_value(),
+			Value::unnamed_variant(
+				"Utility",
+				[Value::unnamed_variant(
+					"batch_all",
+					[Value::unnamed_composite([
+						Value::unnamed_variant(
+							"Registrar",
+							[Value::unnamed_variant("deregister", [Value::u128(2000)])]
+						),
+						Value::unnamed_variant(
+							"Registrar",
+							[Value::unnamed_variant("deregister", [Value::u128(2001)])]
+						),
+					])]
+				)]
+			)
+		);
+	}
+
+	#[test]
+	fn construct_transfer_extrinsic_works() -> Result<(), Error> {
+		let call = construct_transfer_extrinsic(
+			"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+			1_000_000_000_000,
+		)?;
+		assert_eq!(call.pallet_name(), "Balances");
+		assert_eq!(call.call_name(), "transfer_keep_alive");
+		// 20-byte accounts are not wrapped within an address.
+		let call = construct_transfer_extrinsic("0xf24ff3a9cf04c71dbc94d0b566f7a27b94566cac", 1)?;
+		assert_eq!(
+			call.into_value(),
+			Value::unnamed_variant(
+				"Balances",
+				[Value::unnamed_variant(
+					"transfer_keep_alive",
+					[
+						Value::from_bytes(
+							Account::parse("0xf24ff3a9cf04c71dbc94d0b566f7a27b94566cac")?.to_id20()
+						),
+						Value::u128(1)
+					]
+				)]
+			)
+		);
+		assert!(matches!(
+			construct_transfer_extrinsic("alice", 1),
+			Err(Error::InvalidAccount(e)) if e.starts_with("alice: ")
+		));
+		Ok(())
+	}
+}
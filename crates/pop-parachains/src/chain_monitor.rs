@@ -0,0 +1,476 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	errors::Error,
+	rpc::{ReconnectPolicy, ResilientClient},
+};
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+	time::{Duration, Instant},
+};
+use subxt::{config::Header, utils::H256, OnlineClient, SubstrateConfig};
+use tokio::{
+	sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+	task::JoinHandle,
+	time::interval,
+};
+
+/// The number of best blocks remembered per endpoint, bounding the depth of detectable reorgs.
+const HISTORY: usize = 256;
+
+/// The rules by which the chains served at watched endpoints are monitored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorRules {
+	/// Reorgs retracting more than this number of best blocks are reported.
+	pub reorg_depth: u32,
+	/// The period without a newly finalized block after which finality is considered stalled.
+	pub finality_stall: Duration,
+}
+
+impl Default for MonitorRules {
+	fn default() -> Self {
+		Self { reorg_depth: 1, finality_stall: Duration::from_secs(60) }
+	}
+}
+
+impl MonitorRules {
+	/// The interval at which finality is checked for stalls.
+	fn check_interval(&self) -> Duration {
+		(self.finality_stall / 4).clamp(Duration::from_millis(100), Duration::from_secs(5))
+	}
+}
+
+/// An event observed while monitoring the chain served at an endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainEvent {
+	/// The best chain was reorganized, retracting more blocks than permitted by the rules.
+	Reorg {
+		/// The endpoint.
+		url: String,
+		/// The number of best blocks retracted.
+		depth: u32,
+		/// The number of the last block common to both the retracted and the new best chain.
+		common_ancestor: u32,
+		/// The number of the new best block.
+		block: u32,
+		/// The hash of the new best block.
+		hash: H256,
+	},
+	/// No block has been finalized for longer than permitted by the rules.
+	FinalityStalled {
+		/// The endpoint.
+		url: String,
+		/// The number of the last finalized block, if any block has been finalized since
+		/// monitoring started.
+		last_finalized: Option<u32>,
+		/// How long since a block was last finalized (or monitoring started).
+		elapsed: Duration,
+	},
+	/// Blocks are being finalized again, following a stall.
+	FinalityResumed {
+		/// The endpoint.
+		url: String,
+		/// The number of the newly finalized block.
+		block: u32,
+		/// How long finality was stalled.
+		elapsed: Duration,
+	},
+	/// The connection to the endpoint was lost, with the reason.
+	Disconnected {
+		/// The endpoint.
+		url: String,
+		/// Why the connection was lost.
+		reason: String,
+	},
+	/// The connection to the endpoint was re-established.
+	Reconnected {
+		/// The endpoint.
+		url: String,
+	},
+}
+
+impl ChainEvent {
+	/// The endpoint concerned.
+	pub fn url(&self) -> &str {
+		match self {
+			ChainEvent::Reorg { url, .. } |
+			ChainEvent::FinalityStalled { url, .. } |
+			ChainEvent::FinalityResumed { url, .. } |
+			ChainEvent::Disconnected { url, .. } |
+			ChainEvent::Reconnected { url } => url,
+		}
+	}
+
+	/// Whether the event warrants attention, rather than reporting progress.
+	pub fn is_alert(&self) -> bool {
+		matches!(self, ChainEvent::Reorg { .. } | ChainEvent::FinalityStalled { .. })
+	}
+}
+
+impl Display for ChainEvent {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ChainEvent::Reorg { url, depth, common_ancestor, block, hash } => write!(
+				f,
+				"Reorg of {depth} blocks on {url}: #{common_ancestor} is now followed by #{block} {hash:?}"
+			),
+			ChainEvent::FinalityStalled { url, last_finalized: Some(block), elapsed } => write!(
+				f,
+				"Finality stalled on {url}: no block finalized for {}s since #{block}",
+				elapsed.as_secs()
+			),
+			ChainEvent::FinalityStalled { url, last_finalized: None, elapsed } => write!(
+				f,
+				"Finality stalled on {url}: no block finalized for {}s",
+				elapsed.as_secs()
+			),
+			ChainEvent::FinalityResumed { url, block, elapsed } => write!(
+				f,
+				"Finality resumed on {url} with #{block}, after a stall of {}s",
+				elapsed.as_secs()
+			),
+			ChainEvent::Disconnected { url, reason } =>
+				write!(f, "Connection to {url} lost: {reason}"),
+			ChainEvent::Reconnected { url } => write!(f, "Reconnected to {url}"),
+		}
+	}
+}
+
+/// Monitors the chains served at a set of endpoints for deep reorgs and finality stalls.
+pub struct ChainMonitor {
+	events: UnboundedReceiver<ChainEvent>,
+	tasks: Vec<JoinHandle<()>>,
+}
+
+impl ChainMonitor {
+	/// Starts monitoring the specified endpoints, each within its own task.
+	///
+	/// # Arguments
+	/// * `urls` - The endpoints to be monitored.
+	/// * `rules` - The rules by which the chains are monitored.
+	pub fn start(urls: impl IntoIterator<Item = String>, rules: MonitorRules) -> Self {
+		let (sender, events) = mpsc::unbounded_channel();
+		let tasks = urls
+			.into_iter()
+			.map(|url| tokio::spawn(monitor(url, rules.clone(), sender.clone())))
+			.collect();
+		Self { events, tasks }
+	}
+
+	/// Waits for the next event, returning `None` once no endpoints remain monitored.
+	pub async fn next(&mut self) -> Option<ChainEvent> {
+		self.events.recv().await
+	}
+
+	/// Stops monitoring the endpoints.
+	pub fn stop(self) {
+		for task in self.tasks {
+			task.abort();
+		}
+	}
+}
+
+/// A reorg of the best chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Reorg {
+	/// The number of best blocks retracted.
+	depth: u32,
+	/// The number of the last block common to both chains.
+	common_ancestor: u32,
+}
+
+/// The most recent blocks of the best chain, by number.
+#[derive(Debug, Default)]
+struct BestChain {
+	hashes: BTreeMap<u32, H256>,
+}
+
+impl BestChain {
+	/// Whether the specified block must be added to the route to a new best block, as it is not
+	/// known to be an ancestor of the current best block.
+	///
+	/// # Arguments
+	/// * `number` - The block number.
+	/// * `hash` - The block hash.
+	fn retracts(&self, number: u32, hash: &H256) -> bool {
+		match self.hashes.get(&number) {
+			Some(known) => known != hash,
+			None => self.hashes.last_key_value().is_some_and(|(tip, _)| number > *tip),
+		}
+	}
+
+	/// Imports a new best block along with its ancestors since the last block common to the
+	/// current best chain, returning the reorg, if any blocks were retracted.
+	///
+	/// # Arguments
+	/// * `route` - The blocks from (and excluding) the common ancestor to the new best block, in
+	///   ascending order.
+	fn import(&mut self, route: &[(u32, H256)]) -> Option<Reorg> {
+		let (first, _) = route.first()?;
+		// Blocks reported again (e.g. after reconnecting) are not retracted.
+		let depth = self
+			.hashes
+			.split_off(first)
+			.into_iter()
+			.filter(|block| !route.contains(block))
+			.count() as u32;
+		self.hashes.extend(route.iter().copied());
+		while self.hashes.len() > HISTORY {
+			self.hashes.pop_first();
+		}
+		(depth > 0).then(|| Reorg { depth, common_ancestor: first.saturating_sub(1) })
+	}
+}
+
+/// The progress of finality.
+#[derive(Debug)]
+struct Finality {
+	/// The number of the last finalized block, if any.
+	last: Option<u32>,
+	/// When a block was last finalized (or monitoring started).
+	since: Instant,
+	/// Whether finality has been reported as stalled.
+	stalled: bool,
+}
+
+impl Finality {
+	fn new(now: Instant) -> Self {
+		Self { last: None, since: now, stalled: false }
+	}
+
+	/// Records a finalized block, returning how long finality was stalled, if it had been.
+	fn finalized(&mut self, number: u32, now: Instant) -> Option<Duration> {
+		if self.last.is_some_and(|last| number <= last) {
+			return None;
+		}
+		let resumed = self.stalled.then(|| now.duration_since(self.since));
+		self.last = Some(number);
+		self.since = now;
+		self.stalled = false;
+		resumed
+	}
+
+	/// Checks whether finality has stalled, returning how long since a block was last finalized
+	/// when first exceeding the threshold.
+	fn check(&mut self, threshold: Duration, now: Instant) -> Option<Duration> {
+		let elapsed = now.duration_since(self.since);
+		if self.stalled || elapsed < threshold {
+			return None;
+		}
+		self.stalled = true;
+		Some(elapsed)
+	}
+}
+
+/// Monitors an endpoint until reconnection attempts are exhausted or the receiver of events is
+/// dropped.
+async fn monitor(url: String, rules: MonitorRules, events: UnboundedSender<ChainEvent>) {
+	let client = ResilientClient::new(&url, ReconnectPolicy::default());
+	let mut chain = BestChain::default();
+	let mut finality = Finality::new(Instant::now());
+	let mut disconnected = false;
+	loop {
+		let Ok(online) = client.connect().await else { return };
+		if disconnected && events.send(ChainEvent::Reconnected { url: url.clone() }).is_err() {
+			return;
+		}
+		let Some(reason) = follow(&url, &online, &rules, &mut chain, &mut finality, &events).await
+		else {
+			return;
+		};
+		disconnected = true;
+		if events.send(ChainEvent::Disconnected { url: url.clone(), reason }).is_err() {
+			return;
+		}
+	}
+}
+
+/// Follows the best and finalized blocks of a connected endpoint, returning why the connection
+/// was lost, or `None` once the receiver of events is dropped.
+async fn follow(
+	url: &str,
+	client: &OnlineClient<SubstrateConfig>,
+	rules: &MonitorRules,
+	chain: &mut BestChain,
+	finality: &mut Finality,
+	events: &UnboundedSender<ChainEvent>,
+) -> Option<String> {
+	let closed = || Some("the subscription was closed by the node".to_string());
+	let mut best = match client.blocks().subscribe_best().await {
+		Ok(best) => best,
+		Err(e) => return Some(e.to_string()),
+	};
+	let mut finalized = match client.blocks().subscribe_finalized().await {
+		Ok(finalized) => finalized,
+		Err(e) => return Some(e.to_string()),
+	};
+	let mut checks = interval(rules.check_interval());
+	loop {
+		let event = tokio::select! {
+			block = best.next() => {
+				let block = match block {
+					Some(Ok(block)) => block,
+					Some(Err(e)) => return Some(e.to_string()),
+					None => return closed(),
+				};
+				let (number, hash) = (block.number(), block.hash());
+				let route =
+					match route(client, chain, number, hash, block.header().parent_hash).await {
+						Ok(route) => route,
+						Err(e) => return Some(e.to_string()),
+					};
+				chain.import(&route).filter(|reorg| reorg.depth > rules.reorg_depth).map(|reorg| {
+					ChainEvent::Reorg {
+						url: url.to_string(),
+						depth: reorg.depth,
+						common_ancestor: reorg.common_ancestor,
+						block: number,
+						hash,
+					}
+				})
+			},
+			block = finalized.next() => {
+				let block = match block {
+					Some(Ok(block)) => block,
+					Some(Err(e)) => return Some(e.to_string()),
+					None => return closed(),
+				};
+				finality.finalized(block.number(), Instant::now()).map(|elapsed| {
+					ChainEvent::FinalityResumed { url: url.to_string(), block: block.number(), elapsed }
+				})
+			},
+			_ = checks.tick() => finality.check(rules.finality_stall, Instant::now()).map(|elapsed| {
+				ChainEvent::FinalityStalled { url: url.to_string(), last_finalized: finality.last, elapsed }
+			}),
+		};
+		if let Some(event) = event {
+			if events.send(event).is_err() {
+				return None;
+			}
+		}
+	}
+}
+
+/// Determines the route from the last block common to the current best chain to a new best block,
+/// fetching the headers of its ancestors as required.
+///
+/// # Arguments
+/// * `client` - The client connected to the endpoint.
+/// * `chain` - The current best chain.
+/// * `number` - The number of the new best block.
+/// * `hash` - The hash of the new best block.
+/// * `parent` - The hash of the parent of the new best block.
+async fn route(
+	client: &OnlineClient<SubstrateConfig>,
+	chain: &BestChain,
+	number: u32,
+	hash: H256,
+	parent: H256,
+) -> Result<Vec<(u32, H256)>, Error> {
+	let mut route = vec![(number, hash)];
+	let mut ancestor = (number.saturating_sub(1), parent);
+	while number > 0 && route.len() < HISTORY && chain.retracts(ancestor.0, &ancestor.1) {
+		let header = client.blocks().at(ancestor.1).await?.header().clone();
+		route.push((header.number(), ancestor.1));
+		if header.number() == 0 {
+			break;
+		}
+		ancestor = (header.number() - 1, header.parent_hash);
+	}
+	route.reverse();
+	Ok(route)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hash(fork: u8, number: u32) -> H256 {
+		let mut hash = [fork; 32];
+		hash[..4].copy_from_slice(&number.to_le_bytes());
+		H256(hash)
+	}
+
+	#[test]
+	fn best_chain_detects_reorgs() {
+		let mut chain = BestChain::default();
+		assert_eq!(chain.import(&[(1, hash(0, 1)), (2, hash(0, 2)), (3, hash(0, 3))]), None);
+		// Extending the best chain retracts nothing.
+		assert!(!chain.retracts(3, &hash(0, 3)));
+		assert_eq!(chain.import(&[(4, hash(0, 4))]), None);
+		assert_eq!(chain.import(&[(4, hash(0, 4))]), None);
+		// A gap is filled via the ancestors of the new best block.
+		assert!(chain.retracts(5, &hash(0, 5)));
+		assert_eq!(chain.import(&[(5, hash(0, 5)), (6, hash(0, 6))]), None);
+		// A fork from #3 retracts #4 to #6.
+		assert!(chain.retracts(4, &hash(1, 4)));
+		assert!(!chain.retracts(3, &hash(0, 3)));
+		assert_eq!(
+			chain.import(&[(4, hash(1, 4)), (5, hash(1, 5))]),
+			Some(Reorg { depth: 3, common_ancestor: 3 })
+		);
+		// A sibling of the best block retracts it.
+		assert_eq!(chain.import(&[(5, hash(2, 5))]), Some(Reorg { depth: 1, common_ancestor: 4 }));
+		assert_eq!(chain.hashes.len(), 5);
+	}
+
+	#[test]
+	fn best_chain_is_bounded() {
+		let mut chain = BestChain::default();
+		let route: Vec<_> = (0..HISTORY as u32 * 2).map(|n| (n, hash(0, n))).collect();
+		chain.import(&route);
+		assert_eq!(chain.hashes.len(), HISTORY);
+		assert_eq!(
+			chain.hashes.first_key_value(),
+			Some((&(HISTORY as u32), &hash(0, HISTORY as u32)))
+		);
+	}
+
+	#[test]
+	fn finality_detects_stalls() {
+		let start = Instant::now();
+		let threshold = Duration::from_secs(60);
+		let mut finality = Finality::new(start);
+		assert_eq!(finality.check(threshold, start + Duration::from_secs(30)), None);
+		assert_eq!(finality.finalized(1, start + Duration::from_secs(30)), None);
+		assert_eq!(finality.check(threshold, start + Duration::from_secs(89)), None);
+		// A stall is reported once.
+		assert_eq!(
+			finality.check(threshold, start + Duration::from_secs(90)),
+			Some(Duration::from_secs(60))
+		);
+		assert_eq!(finality.check(threshold, start + Duration::from_secs(120)), None);
+		// Blocks finalized previously do not end the stall.
+		assert_eq!(finality.finalized(1, start + Duration::from_secs(130)), None);
+		assert_eq!(
+			finality.finalized(2, start + Duration::from_secs(150)),
+			Some(Duration::from_secs(120))
+		);
+		assert_eq!(finality.check(threshold, start + Duration::from_secs(180)), None);
+	}
+
+	#[test]
+	fn display_works() {
+		let url = "ws://localhost:9944".to_string();
+		assert_eq!(
+			ChainEvent::FinalityStalled {
+				url: url.clone(),
+				last_finalized: Some(10),
+				elapsed: Duration::from_secs(90)
+			}
+			.to_string(),
+			"Finality stalled on ws://localhost:9944: no block finalized for 90s since #10"
+		);
+		let event = ChainEvent::Reorg {
+			url: url.clone(),
+			depth: 3,
+			common_ancestor: 3,
+			block: 5,
+			hash: H256::zero(),
+		};
+		assert!(event.to_string().starts_with("Reorg of 3 blocks on ws://localhost:9944: #3"));
+		assert!(event.is_alert());
+		assert!(!ChainEvent::Reconnected { url }.is_alert());
+	}
+}
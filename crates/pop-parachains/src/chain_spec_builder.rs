@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{build::CODE, Error};
+use duct::cmd;
+use pop_common::{
+	sourcing::{Binary, Source},
+	target,
+};
+use serde_json::Value;
+use std::{
+	env,
+	fs::{read_to_string, write},
+	path::{Path, PathBuf},
+};
+
+/// The binary with which chain specs are generated from a runtime, for projects without a node.
+pub const CHAIN_SPEC_BUILDER: &str = "chain-spec-builder";
+/// The release of the Polkadot SDK from which `chain-spec-builder` is sourced, unless specified.
+const DEFAULT_RELEASE: &str = "polkadot-stable2409";
+/// The location of the assets published by releases of the Polkadot SDK.
+const RELEASES: &str = "https://github.com/paritytech/polkadot-sdk/releases/download";
+
+/// Initialises `chain-spec-builder`: that already installed on the `PATH`, otherwise that
+/// published by a release of the Polkadot SDK, to be sourced if not already cached.
+///
+/// # Arguments
+/// * `cache` - The location used for caching binaries.
+/// * `release` - The release of the Polkadot SDK to be used (e.g. `polkadot-stable2409`), rather
+///   than any binary already installed.
+pub fn chain_spec_builder(cache: &Path, release: Option<&str>) -> Result<Binary, Error> {
+	if release.is_none() {
+		let installed = env::var_os("PATH")
+			.map(|paths| {
+				env::split_paths(&paths).map(|p| p.join(CHAIN_SPEC_BUILDER)).collect::<Vec<_>>()
+			})
+			.unwrap_or_default()
+			.into_iter()
+			.find(|path| path.is_file());
+		if let Some(path) = installed {
+			return Ok(Binary::Local { name: CHAIN_SPEC_BUILDER.to_string(), path, manifest: None });
+		}
+	}
+	let release = release.unwrap_or(DEFAULT_RELEASE);
+	// Releases only publish binaries for a subset of targets.
+	let asset = match target()? {
+		"x86_64-unknown-linux-gnu" => CHAIN_SPEC_BUILDER.to_string(),
+		"aarch64-apple-darwin" => format!("{CHAIN_SPEC_BUILDER}-aarch64-apple-darwin"),
+		target =>
+			return Err(Error::MissingBinary(format!(
+				"{CHAIN_SPEC_BUILDER} is not published for {target}: install it with `cargo install staging-chain-spec-builder`"
+			))),
+	};
+	let name = format!("{CHAIN_SPEC_BUILDER}-{release}");
+	Ok(Binary::Source {
+		name: name.clone(),
+		source: Source::Url { url: format!("{RELEASES}/{release}/{asset}"), name },
+		cache: cache.to_path_buf(),
+	})
+}
+
+/// Generates the plain text chain specification for a parachain from its runtime, using
+/// `chain-spec-builder`, for projects without a node (e.g. those run with the omni-node).
+///
+/// # Arguments
+/// * `chain_spec_builder` - The path to the `chain-spec-builder` binary.
+/// * `runtime` - The path to the runtime.
+/// * `plain_chain_spec` - Location of the plain_parachain_spec file to be generated.
+/// * `preset` - The genesis preset from which the specification is generated, otherwise the default
+///   genesis config of the runtime.
+/// * `para_id` - The parachain ID.
+/// * `relay_chain` - The relay chain to which the parachain connects.
+pub fn generate_plain_chain_spec_with_runtime(
+	chain_spec_builder: &Path,
+	runtime: &Path,
+	plain_chain_spec: &Path,
+	preset: Option<&str>,
+	para_id: u32,
+	relay_chain: &str,
+) -> Result<(), Error> {
+	if !runtime.exists() {
+		return Err(Error::MissingBinary(format!("runtime at {}", runtime.display())));
+	}
+	let (output, runtime, para_id) = (
+		plain_chain_spec.display().to_string(),
+		runtime.display().to_string(),
+		para_id.to_string(),
+	);
+	let mut args = vec![
+		"-c",
+		&output,
+		"create",
+		"--runtime",
+		&runtime,
+		"--para-id",
+		&para_id,
+		"--relay-chain",
+		relay_chain,
+	];
+	match preset {
+		Some(preset) => args.extend(["named-preset", preset]),
+		None => args.push("default"),
+	}
+	cmd(chain_spec_builder, args).stdout_null().stderr_null().run()?;
+	Ok(())
+}
+
+/// Generates a raw chain specification file for a parachain, using `chain-spec-builder`.
+///
+/// # Arguments
+/// * `chain_spec_builder` - The path to the `chain-spec-builder` binary.
+/// * `plain_chain_spec` - Location of the plain chain specification file.
+/// * `chain_spec_file_name` - The name of the chain specification file to be generated.
+pub fn generate_raw_chain_spec_with_runtime(
+	chain_spec_builder: &Path,
+	plain_chain_spec: &Path,
+	chain_spec_file_name: &str,
+) -> Result<PathBuf, Error> {
+	if !plain_chain_spec.exists() {
+		return Err(Error::MissingChainSpec(plain_chain_spec.display().to_string()));
+	}
+	let raw_chain_spec = plain_chain_spec.with_file_name(chain_spec_file_name);
+	cmd(
+		chain_spec_builder,
+		[
+			"-c".as_ref(),
+			raw_chain_spec.as_os_str(),
+			"convert-to-raw".as_ref(),
+			plain_chain_spec.as_os_str(),
+		],
+	)
+	.stdout_null()
+	.stderr_null()
+	.run()?;
+	Ok(raw_chain_spec)
+}
+
+/// Extracts the WebAssembly runtime for the parachain from its raw chain specification, as an
+/// alternative to exporting it with a node.
+///
+/// # Arguments
+/// * `raw_chain_spec` - Location of the raw chain specification file.
+/// * `wasm_file_name` - The name of the wasm runtime file to be generated.
+pub fn extract_genesis_code(raw_chain_spec: &Path, wasm_file_name: &str) -> Result<PathBuf, Error> {
+	if !raw_chain_spec.exists() {
+		return Err(Error::MissingChainSpec(raw_chain_spec.display().to_string()));
+	}
+	let spec: Value = serde_json::from_str(&read_to_string(raw_chain_spec)?)?;
+	let code = spec
+		.pointer(&format!("/genesis/raw/top/{CODE}"))
+		.and_then(Value::as_str)
+		.ok_or_else(|| {
+			Error::Config("expected the runtime code within the raw chain spec".into())
+		})?;
+	let wasm_file = raw_chain_spec.parent().unwrap_or(Path::new("./")).join(wasm_file_name);
+	write(&wasm_file, code)?;
+	Ok(wasm_file)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+
+	#[test]
+	fn chain_spec_builder_works() -> Result<()> {
+		let cache = tempfile::tempdir()?;
+		let Ok(target) = target() else { return Ok(()) };
+		match chain_spec_builder(cache.path(), Some("polkadot-stable2412")) {
+			Ok(binary) => {
+				assert_eq!(binary.name(), "chain-spec-builder-polkadot-stable2412");
+				assert_eq!(
+					binary.path(),
+					cache.path().join("chain-spec-builder-polkadot-stable2412")
+				);
+			},
+			Err(e) => assert!(
+				matches!(e, Error::MissingBinary(e) if e.contains(target)),
+				"binaries are published for {target}"
+			),
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn extract_genesis_code_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let raw_chain_spec = temp_dir.path().join("chain-spec-raw.json");
+		assert!(matches!(
+			extract_genesis_code(&raw_chain_spec, "para-2000.wasm"),
+			Err(Error::MissingChainSpec(_))
+		));
+		write(&raw_chain_spec, json!({"genesis": {"raw": {"top": {}}}}).to_string())?;
+		assert!(matches!(
+			extract_genesis_code(&raw_chain_spec, "para-2000.wasm"),
+			Err(Error::Config(_))
+		));
+		write(
+			&raw_chain_spec,
+			json!({"genesis": {"raw": {"top": {CODE: "0x0061736d"}}}}).to_string(),
+		)?;
+		let wasm_file = extract_genesis_code(&raw_chain_spec, "para-2000.wasm")?;
+		assert_eq!(wasm_file, temp_dir.path().join("para-2000.wasm"));
+		assert_eq!(read_to_string(wasm_file)?, "0x0061736d");
+		Ok(())
+	}
+}
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use pop_common::parse_account;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{fs::read_to_string, path::Path};
+
+/// A member of the initial collator set, alongside its session keys.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CollatorKeys {
+	/// The account of the collator, which is made invulnerable.
+	pub account: String,
+	/// The public session keys of the collator, by the name of the key within the session keys
+	/// of the runtime (e.g. `{"aura": "5Grw..."}`).
+	pub keys: Map<String, Value>,
+}
+
+impl CollatorKeys {
+	/// Validates that the account and each session key are valid public keys.
+	pub fn validate(&self) -> Result<(), Error> {
+		parse_account(&self.account)
+			.map_err(|e| Error::Config(format!("invalid collator `{}`: {e}", self.account)))?;
+		if self.keys.is_empty() {
+			return Err(Error::Config(format!(
+				"expected the session keys of collator `{}`",
+				self.account
+			)));
+		}
+		for (name, key) in &self.keys {
+			let key = key.as_str().ok_or_else(|| {
+				Error::Config(format!(
+					"expected the `{name}` key of collator `{}` to be a string",
+					self.account
+				))
+			})?;
+			parse_account(key).map_err(|e| {
+				Error::Config(format!("invalid `{name}` key of collator `{}`: {e}", self.account))
+			})?;
+		}
+		Ok(())
+	}
+}
+
+/// Loads the initial collator set from a keys file: a JSON array of collators, each with its
+/// account and public session keys, e.g. `[{"account": "5Grw...", "keys": {"aura": "5Grw..."}}]`.
+/// Only public keys are expected within the file.
+///
+/// # Arguments
+/// * `path` - The path of the keys file.
+pub fn load_collator_keys(path: &Path) -> Result<Vec<CollatorKeys>, Error> {
+	let contents = read_to_string(path).map_err(|e| {
+		Error::Config(format!("unable to read the collator keys {}: {e}", path.display()))
+	})?;
+	let collators: Vec<CollatorKeys> = serde_json::from_str(&contents)?;
+	if collators.is_empty() {
+		return Err(Error::Config(format!("expected collators within {}", path.display())));
+	}
+	for collator in &collators {
+		collator.validate()?;
+	}
+	Ok(collators)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::write;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	#[test]
+	fn load_collator_keys_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("collators.json");
+		write(&path, format!(r#"[{{"account": "{ALICE}", "keys": {{"aura": "{ALICE}"}}}}]"#))?;
+		let collators = load_collator_keys(&path)?;
+		assert_eq!(collators.len(), 1);
+		assert_eq!(collators[0].account, ALICE);
+		assert_eq!(collators[0].keys["aura"], ALICE);
+
+		write(&path, "[]")?;
+		assert!(matches!(load_collator_keys(&path), Err(Error::Config(_))));
+		write(&path, format!(r#"[{{"account": "{ALICE}", "keys": {{"aura": "invalid"}}}}]"#))?;
+		assert!(matches!(
+			load_collator_keys(&path),
+			Err(Error::Config(error)) if error.starts_with("invalid `aura` key of collator")
+		));
+		write(&path, format!(r#"[{{"account": "{ALICE}", "keys": {{}}}}]"#))?;
+		assert!(matches!(load_collator_keys(&path), Err(Error::Config(_))));
+		Ok(())
+	}
+}
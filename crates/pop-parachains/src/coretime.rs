@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{find_event_field, set_up_client, sign_and_submit_extrinsic},
+	errors::Error,
+};
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use subxt::{
+	backend::rpc::{rpc_params, RpcClient},
+	dynamic::Value,
+	tx::DynamicPayload,
+	Metadata,
+};
+use tokio::{sync::watch, task::JoinHandle, time::interval};
+use url::Url;
+
+/// The names used by the on-demand assignment pallet across relay chain runtime versions.
+const ON_DEMAND_PALLETS: [&str; 2] = ["OnDemand", "OnDemandAssignmentProvider"];
+
+/// Configuration for automatically placing on-demand coretime orders for a parachain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OnDemandConfig {
+	/// The parachain identifier on the relay chain.
+	pub para_id: u32,
+	/// The maximum amount which may be spent on a single order.
+	pub max_amount: u128,
+	/// The total amount which may be spent across all orders.
+	pub budget: u128,
+	/// How often the parachain is checked for pending work.
+	pub interval: Duration,
+	/// If specified, the collator endpoint whose transaction pool is used to determine whether
+	/// the parachain has pending work. Orders are placed on every interval otherwise.
+	pub collator: Option<Url>,
+}
+
+/// A summary of the orders placed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrderSummary {
+	/// The number of orders placed.
+	pub orders: u32,
+	/// The total amount spent on orders.
+	pub spent: u128,
+}
+
+impl OrderSummary {
+	/// Whether an order costing up to `amount` can still be placed within `budget`.
+	///
+	/// # Arguments
+	/// * `budget` - The total amount which may be spent.
+	/// * `amount` - The maximum amount of the next order.
+	pub fn can_afford(&self, budget: u128, amount: u128) -> bool {
+		self.spent.checked_add(amount).is_some_and(|total| total <= budget)
+	}
+}
+
+/// A background task which keeps placing on-demand orders for a parachain.
+pub struct OnDemandOrders {
+	/// The handle of the task placing the orders.
+	handle: JoinHandle<Result<(), Error>>,
+	/// Used to signal the task to stop.
+	stop: watch::Sender<bool>,
+	/// The orders placed so far.
+	summary: Arc<Mutex<OrderSummary>>,
+}
+
+impl OnDemandOrders {
+	/// Starts placing on-demand orders for the configured parachain in the background, until
+	/// either stopped or the budget is exhausted.
+	///
+	/// # Arguments
+	/// * `relay_url` - The endpoint of a relay chain node.
+	/// * `config` - The configuration of the orders to be placed.
+	/// * `suri` - The secret URI of the account paying for the orders.
+	pub async fn start(
+		relay_url: &Url,
+		config: OnDemandConfig,
+		suri: String,
+	) -> Result<Self, Error> {
+		let client = set_up_client(relay_url.as_str()).await?;
+		let pallet = on_demand_pallet(&client.metadata()).ok_or_else(|| {
+			Error::UnsupportedCommand("the relay chain does not support on-demand coretime".into())
+		})?;
+		let collator = match config.collator.as_ref() {
+			Some(url) => Some(
+				RpcClient::from_url(url.as_str())
+					.await
+					.map_err(|e| Error::ConnectionFailure(e.to_string()))?,
+			),
+			None => None,
+		};
+
+		let (stop, mut stopped) = watch::channel(false);
+		let summary = Arc::new(Mutex::new(OrderSummary::default()));
+		let orders = summary.clone();
+		let handle = tokio::spawn(async move {
+			let mut ticks = interval(config.interval);
+			loop {
+				tokio::select! {
+					_ = ticks.tick() => {},
+					_ = stopped.changed() => return Ok(()),
+				}
+				if !orders
+					.lock()
+					.expect("lock not poisoned")
+					.can_afford(config.budget, config.max_amount)
+				{
+					return Ok(());
+				}
+				if let Some(collator) = collator.as_ref() {
+					if !has_pending_work(collator).await? {
+						continue;
+					}
+				}
+				let call = place_order(pallet, config.para_id, config.max_amount);
+				let events = sign_and_submit_extrinsic(&client, &call, &suri).await?;
+				// Fall back to the maximum amount when the spot price cannot be determined.
+				let spent = find_event_field(&events, pallet, "OnDemandOrderPlaced", "spot_price")?
+					.and_then(|v| v.as_u128())
+					.unwrap_or(config.max_amount);
+				let mut orders = orders.lock().expect("lock not poisoned");
+				orders.orders += 1;
+				orders.spent = orders.spent.saturating_add(spent);
+			}
+		});
+		Ok(Self { handle, stop, summary })
+	}
+
+	/// The orders placed so far.
+	pub fn summary(&self) -> OrderSummary {
+		self.summary.lock().expect("lock not poisoned").clone()
+	}
+
+	/// Whether the task has finished, either due to an error or the budget being exhausted.
+	pub fn is_finished(&self) -> bool {
+		self.handle.is_finished()
+	}
+
+	/// Stops placing orders, returning a summary of the orders placed.
+	pub async fn stop(self) -> Result<OrderSummary, Error> {
+		let _ = self.stop.send(true);
+		let summary = self.summary.clone();
+		self.handle.await.map_err(|e| Error::AnyhowError(e.into()))??;
+		let summary = summary.lock().expect("lock not poisoned").clone();
+		Ok(summary)
+	}
+}
+
+/// Determines the name of the on-demand assignment pallet within the relay chain runtime, if
+/// available.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the relay chain.
+pub fn on_demand_pallet(metadata: &Metadata) -> Option<&'static str> {
+	ON_DEMAND_PALLETS.into_iter().find(|p| metadata.pallet_by_name(p).is_some())
+}
+
+/// Constructs a call to place an on-demand order for a parachain, keeping the account alive.
+///
+/// # Arguments
+/// * `pallet` - The name of the on-demand assignment pallet.
+/// * `para_id` - The parachain identifier.
+/// * `max_amount` - The maximum amount to be paid for the order.
+pub fn place_order(pallet: &str, para_id: u32, max_amount: u128) -> DynamicPayload {
+	subxt::dynamic::tx(
+		pallet,
+		"place_order_keep_alive",
+		vec![Value::u128(max_amount), Value::u128(para_id as u128)],
+	)
+}
+
+/// Whether the collator has any pending extrinsics within its transaction pool.
+async fn has_pending_work(collator: &RpcClient) -> Result<bool, Error> {
+	let pending: Vec<String> = collator
+		.request("author_pendingExtrinsics", rpc_params![])
+		.await
+		.map_err(Error::SubxtError)?;
+	Ok(!pending.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_afford_works() {
+		let summary = OrderSummary { orders: 1, spent: 100 };
+		assert!(summary.can_afford(200, 100));
+		assert!(!summary.can_afford(199, 100));
+		assert!(!OrderSummary { orders: 1, spent: u128::MAX }.can_afford(u128::MAX, 1));
+	}
+
+	#[test]
+	fn place_order_works() {
+		let call = place_order("OnDemand", 2000, 1_000_000);
+		assert_eq!(call.pallet_name(), "OnDemand");
+		assert_eq!(call.call_name(), "place_order_keep_alive");
+		assert_eq!(
+			call.into_value(),
+			Value::unnamed_variant(
+				"OnDemand",
+				[Value::unnamed_variant(
+					"place_order_keep_alive",
+					[Value::u128(1_000_000), Value::u128(2000)]
+				)]
+			)
+		);
+	}
+
+	#[tokio::test]
+	async fn start_fails_wrong_url() -> anyhow::Result<()> {
+		let config = OnDemandConfig {
+			para_id: 2000,
+			max_amount: 1_000_000,
+			budget: 10_000_000,
+			interval: Duration::from_secs(6),
+			collator: None,
+		};
+		assert!(matches!(
+			OnDemandOrders::start(&Url::parse("wss://wronguri.xyz")?, config, "//Alice".into())
+				.await,
+			Err(Error::ConnectionFailure(_))
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{call::construct_sudo_extrinsic, errors::Error};
+use subxt::{
+	config::{substrate::BlakeTwo256, Hasher},
+	dynamic::Value,
+	tx::DynamicPayload,
+	utils::to_hex,
+	Metadata, OnlineClient, SubstrateConfig,
+};
+
+/// The number of blocks after approval after which a proposal is enacted, by default.
+const DEFAULT_ENACTMENT_DELAY: u32 = 10;
+
+/// The strategy used to dispatch privileged calls on a chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DispatchStrategy {
+	/// Calls are wrapped within `sudo.sudo` and dispatched by the sudo key.
+	Sudo,
+	/// Calls are noted as a preimage and submitted as a referendum.
+	Governance,
+}
+
+impl DispatchStrategy {
+	/// Determines the strategy for dispatching privileged calls, based on the pallets available
+	/// within the runtime.
+	///
+	/// # Arguments
+	/// * `metadata` - The metadata of the chain.
+	pub fn detect(metadata: &Metadata) -> Result<Self, Error> {
+		Self::from_pallets(metadata.pallets().map(|p| p.name()))
+	}
+
+	/// Determines the strategy for dispatching privileged calls from the names of the pallets
+	/// within the runtime.
+	///
+	/// # Arguments
+	/// * `pallets` - The names of the pallets within the runtime.
+	fn from_pallets<'a>(pallets: impl IntoIterator<Item = &'a str>) -> Result<Self, Error> {
+		let pallets: Vec<_> = pallets.into_iter().collect();
+		if pallets.contains(&"Sudo") {
+			return Ok(Self::Sudo);
+		}
+		if pallets.contains(&"Referenda") && pallets.contains(&"Preimage") {
+			return Ok(Self::Governance);
+		}
+		Err(Error::UnsupportedCommand(
+			"privileged calls require either a `Sudo` pallet or the `Referenda` and `Preimage` pallets".into(),
+		))
+	}
+}
+
+/// The artifacts required to submit a privileged call as a governance proposal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+	/// The SCALE encoded call, hex encoded.
+	pub call_data: String,
+	/// The blake2-256 hash of the encoded call, hex encoded.
+	pub call_hash: String,
+	/// The length of the encoded call.
+	pub call_len: u32,
+	/// The call used to note the preimage of the proposal, hex encoded.
+	pub note_preimage: String,
+	/// The call used to submit the proposal as a referendum with `Root` origin, hex encoded.
+	pub submit_referendum: String,
+}
+
+/// A privileged call, prepared for dispatch according to a [DispatchStrategy].
+#[derive(Debug)]
+pub enum PrivilegedCall {
+	/// A call to be signed and submitted by the sudo key.
+	Sudo(DynamicPayload),
+	/// A call to be submitted via governance.
+	Proposal(Proposal),
+}
+
+/// Prepares a privileged call for dispatch, either wrapping it within sudo or producing the
+/// artifacts required to submit it as a governance proposal.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `call` - The call requiring `Root` origin.
+/// * `strategy` - The dispatch strategy to be used, detected from the chain when not specified.
+pub fn prepare_privileged_call(
+	client: &OnlineClient<SubstrateConfig>,
+	call: DynamicPayload,
+	strategy: Option<DispatchStrategy>,
+) -> Result<PrivilegedCall, Error> {
+	let strategy = match strategy {
+		Some(strategy) => strategy,
+		None => DispatchStrategy::detect(&client.metadata())?,
+	};
+	match strategy {
+		DispatchStrategy::Sudo => Ok(PrivilegedCall::Sudo(construct_sudo_extrinsic(call))),
+		DispatchStrategy::Governance => {
+			let tx = client.tx();
+			let call_data = tx.call_data(&call)?;
+			let call_len = call_data.len() as u32;
+			let call_hash = BlakeTwo256::hash(&call_data);
+			let note_preimage = tx.call_data(&note_preimage(&call_data))?;
+			let submit_referendum = tx.call_data(&submit_referendum(
+				call_hash.as_ref(),
+				call_len,
+				DEFAULT_ENACTMENT_DELAY,
+			))?;
+			Ok(PrivilegedCall::Proposal(Proposal {
+				call_data: to_hex(&call_data),
+				call_hash: to_hex(call_hash),
+				call_len,
+				note_preimage: to_hex(note_preimage),
+				submit_referendum: to_hex(submit_referendum),
+			}))
+		},
+	}
+}
+
+/// Constructs a call to note the preimage of an encoded call.
+///
+/// # Arguments
+/// * `call_data` - The encoded call.
+pub(crate) fn note_preimage(call_data: &[u8]) -> DynamicPayload {
+	subxt::dynamic::tx("Preimage", "note_preimage", vec![Value::from_bytes(call_data)])
+}
+
+/// Constructs a call to submit a referendum for a noted preimage on the root track.
+///
+/// # Arguments
+/// * `hash` - The hash of the preimage.
+/// * `len` - The length of the preimage.
+/// * `delay` - The number of blocks after approval before enactment.
+fn submit_referendum(hash: &[u8], len: u32, delay: u32) -> DynamicPayload {
+	submit_referendum_on_track(
+		Value::unnamed_variant("system", [Value::unnamed_variant("Root", [])]),
+		Value::named_variant(
+			"Lookup",
+			[("hash", Value::from_bytes(hash)), ("len", Value::u128(len as u128))],
+		),
+		delay,
+	)
+}
+
+/// Constructs a call to submit a referendum, on the track of the specified origin.
+///
+/// # Arguments
+/// * `origin` - The origin with which the proposal is to be enacted.
+/// * `proposal` - The proposal, bounded either inline or by the hash of its preimage.
+/// * `delay` - The number of blocks after approval before enactment.
+pub(crate) fn submit_referendum_on_track(
+	origin: Value,
+	proposal: Value,
+	delay: u32,
+) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Referenda",
+		"submit",
+		vec![origin, proposal, Value::unnamed_variant("After", [Value::u128(delay as u128)])],
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn detect_prefers_sudo() -> Result<(), Error> {
+		assert_eq!(
+			DispatchStrategy::from_pallets(["System", "Sudo", "Referenda", "Preimage"])?,
+			DispatchStrategy::Sudo
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn detect_governance_works() -> Result<(), Error> {
+		assert_eq!(
+			DispatchStrategy::from_pallets(["System", "Referenda", "Preimage"])?,
+			DispatchStrategy::Governance
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn detect_fails_without_privileged_origin() {
+		assert!(matches!(
+			DispatchStrategy::from_pallets(["System", "Referenda"]),
+			Err(Error::UnsupportedCommand(..))
+		));
+	}
+
+	#[test]
+	fn submit_referendum_works() {
+		let call = submit_referendum(&[0u8; 32], 10, 5);
+		assert_eq!(call.pallet_name(), "Referenda");
+		assert_eq!(call.call_name(), "submit");
+		assert_eq!(
+			call.into_value(),
+			Value::unnamed_variant(
+				"Referenda",
+				[Value::unnamed_variant(
+					"submit",
+					[
+						Value::unnamed_variant("system", [Value::unnamed_variant("Root", [])]),
+						Value::named_variant(
+							"Lookup",
+							[("hash", Value::from_bytes([0u8; 32])), ("len", Value::u128(10))]
+						),
+						Value::unnamed_variant("After", [Value::u128(5)]),
+					]
+				)]
+			)
+		);
+	}
+
+	#[test]
+	fn note_preimage_works() {
+		let call = note_preimage(&[1, 2, 3]);
+		assert_eq!(call.pallet_name(), "Preimage");
+		assert_eq!(call.call_name(), "note_preimage");
+	}
+}
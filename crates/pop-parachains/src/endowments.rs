@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use pop_common::{
+	account::{validate_address, SUBSTRATE_PREFIX},
+	Account,
+};
+use serde_json::Value;
+use std::{collections::HashMap, fs::read_to_string, path::Path};
+
+/// Loads the initial endowments of a chain from a file, as pairs of accounts and balances: either
+/// a CSV file of `account,balance` lines (optionally with an `account,balance` header), or a JSON
+/// file of either
+/// `[account, balance]` pairs, `{"account": ..., "balance": ...}` objects or an object mapping
+/// accounts to balances. Balances may be specified as numbers or strings of digits.
+///
+/// Each account is validated and converted to an SS58 address of the network (20-byte accounts
+/// being kept as hex), and accounts endowed more than once are rejected.
+///
+/// # Arguments
+/// * `path` - The path of the endowments file.
+/// * `prefix` - The address format of the network, if known, to which the addresses must conform.
+pub fn load_endowments(path: &Path, prefix: Option<u16>) -> Result<Vec<(String, u128)>, Error> {
+	let contents = read_to_string(path).map_err(|e| {
+		Error::Config(format!("unable to read the endowments {}: {e}", path.display()))
+	})?;
+	let entries = match path.extension().and_then(|e| e.to_str()) {
+		Some("csv") => parse_csv(&contents)?,
+		_ => parse_json(&contents)?,
+	};
+	if entries.is_empty() {
+		return Err(Error::Config(format!("expected endowments within {}", path.display())));
+	}
+	let mut endowed: HashMap<Account, String> = HashMap::new();
+	let mut endowments = Vec::with_capacity(entries.len());
+	for (entry, account, balance) in entries {
+		let invalid = |e: String| Error::Config(format!("{entry}: {e}"));
+		let parsed = match prefix {
+			Some(prefix) => validate_address(&account, prefix),
+			None => Account::parse(&account),
+		}
+		.map_err(|e| invalid(format!("invalid account `{account}`: {e}")))?;
+		let balance = parse_balance(&balance)
+			.ok_or_else(|| invalid(format!("invalid balance `{balance}` of `{account}`")))?;
+		if let Some(first) = endowed.insert(parsed, entry.clone()) {
+			return Err(invalid(format!("`{account}` is already endowed ({first})")));
+		}
+		let account = match parsed {
+			Account::Id32(_) => parsed.to_ss58(prefix.unwrap_or(SUBSTRATE_PREFIX)),
+			Account::Id20(_) => parsed.to_hex(),
+		};
+		endowments.push((account, balance));
+	}
+	Ok(endowments)
+}
+
+/// Parses the `account,balance` lines of a CSV file, skipping empty lines, comments and an
+/// `account,balance` header, into entries described by their line.
+fn parse_csv(contents: &str) -> Result<Vec<(String, String, String)>, Error> {
+	let mut entries = vec![];
+	let mut first = true;
+	for (index, line) in contents.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let header = std::mem::take(&mut first);
+		let fields: Vec<_> = line.split(',').map(|f| f.trim().trim_matches('"')).collect();
+		let [account, balance] = fields[..] else {
+			return Err(Error::Config(format!(
+				"line {}: expected `account,balance`, found `{line}`",
+				index + 1
+			)));
+		};
+		if header &&
+			account.eq_ignore_ascii_case("account") &&
+			balance.eq_ignore_ascii_case("balance")
+		{
+			continue;
+		}
+		entries.push((format!("line {}", index + 1), account.to_string(), balance.to_string()));
+	}
+	Ok(entries)
+}
+
+/// Parses the endowments of a JSON file into entries described by their position.
+fn parse_json(contents: &str) -> Result<Vec<(String, String, String)>, Error> {
+	let text = |value: &Value| match value {
+		Value::String(text) => Some(text.clone()),
+		Value::Number(number) => Some(number.to_string()),
+		_ => None,
+	};
+	let invalid = |entry: &str| {
+		Error::Config(format!(
+			"{entry}: expected either `[account, balance]` or `{{\"account\": ..., \"balance\": ...}}`"
+		))
+	};
+	match serde_json::from_str(contents)? {
+		Value::Object(endowments) => endowments
+			.iter()
+			.map(|(account, balance)| {
+				let entry = format!("`{account}`");
+				let balance = text(balance).ok_or_else(|| invalid(&entry))?;
+				Ok((entry, account.clone(), balance))
+			})
+			.collect(),
+		Value::Array(endowments) => endowments
+			.iter()
+			.enumerate()
+			.map(|(index, endowment)| {
+				let entry = format!("endowment {}", index + 1);
+				let (account, balance) = match endowment {
+					Value::Array(pair) if pair.len() == 2 => (&pair[0], &pair[1]),
+					Value::Object(object) => (
+						object.get("account").ok_or_else(|| invalid(&entry))?,
+						object.get("balance").ok_or_else(|| invalid(&entry))?,
+					),
+					_ => return Err(invalid(&entry)),
+				};
+				let account = account.as_str().ok_or_else(|| invalid(&entry))?.to_string();
+				let balance = text(balance).ok_or_else(|| invalid(&entry))?;
+				Ok((entry, account, balance))
+			})
+			.collect(),
+		_ => Err(Error::Config("expected a JSON array or object of endowments".into())),
+	}
+}
+
+/// Parses a balance, allowing underscores as separators (e.g. `1_000_000`).
+fn parse_balance(balance: &str) -> Option<u128> {
+	let balance = balance.replace('_', "");
+	if balance.is_empty() || !balance.chars().all(|c| c.is_ascii_digit()) {
+		return None;
+	}
+	balance.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::write;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+	// Alice, within the address format of Polkadot.
+	const ALICE_POLKADOT: &str = "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5";
+
+	#[test]
+	fn load_endowments_from_csv_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("endowments.csv");
+		write(&path, format!("account,balance\n{ALICE},1_000\n\n\"{BOB}\", 2000\n"))?;
+		assert_eq!(
+			load_endowments(&path, Some(42))?,
+			vec![(ALICE.to_string(), 1_000), (BOB.to_string(), 2_000)]
+		);
+		// Balances beyond the range of a `u64`, as are common for 18-decimal tokens.
+		write(&path, format!("# Endowments\n{ALICE},1_000_000_000_000_000_000_000\n"))?;
+		assert_eq!(
+			load_endowments(&path, Some(42))?,
+			vec![(ALICE.to_string(), 1_000_000_000_000_000_000_000)]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn load_endowments_from_json_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("endowments.json");
+		let alice = Account::parse(ALICE)?;
+		write(
+			&path,
+			format!(r#"[["{ALICE_POLKADOT}", 1000], {{"account": "{BOB}", "balance": "2000"}}]"#),
+		)?;
+		assert_eq!(
+			load_endowments(&path, None)?,
+			vec![(ALICE.to_string(), 1_000), (BOB.to_string(), 2_000)]
+		);
+		write(&path, format!(r#"{{"{}": 1000}}"#, alice.to_hex()))?;
+		assert_eq!(load_endowments(&path, Some(0))?, vec![(ALICE_POLKADOT.to_string(), 1_000)]);
+		Ok(())
+	}
+
+	#[test]
+	fn load_endowments_fails() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("endowments.csv");
+		let fails_with = |contents: String, error: &str| -> Result<(), Error> {
+			write(&path, contents)?;
+			assert!(matches!(
+				load_endowments(&path, Some(42)),
+				Err(Error::Config(e)) if e.starts_with(error)
+			));
+			Ok(())
+		};
+		fails_with(format!("{ALICE},1000\n{ALICE_POLKADOT},1000\n"), "line 2: invalid account")?;
+		fails_with(
+			format!("{ALICE},1000\n{ALICE},2000\n"),
+			&format!("line 2: `{ALICE}` is already endowed (line 1)"),
+		)?;
+		fails_with(format!("{ALICE},-1\n"), "line 1: invalid balance `-1`")?;
+		fails_with(format!("{ALICE},1000,1\n"), "line 1: expected `account,balance`")?;
+		fails_with("account,balance\n".into(), "expected endowments within")?;
+		// A malformed first line is reported, rather than being mistaken for a header.
+		fails_with(format!("{}x,1O00\n{BOB},2000\n", &ALICE[1..]), "line 1: invalid account")?;
+		fails_with(format!("address,amount\n{BOB},2000\n"), "line 1: invalid account")?;
+		Ok(())
+	}
+}
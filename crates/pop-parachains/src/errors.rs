@@ -1,46 +1,85 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use pop_common::i18n;
 use thiserror::Error;
 use zombienet_sdk::OrchestratorError;
 
 #[derive(Error, Debug)]
 pub enum Error {
-	#[error("User aborted due to existing target directory.")]
+	#[error("{}", localized("aborted", &[]))]
 	Aborted,
-	#[error("Anyhow error: {0}")]
+	#[error("{}", localized("anyhow_error", &[("0", &.0.to_string())]))]
 	AnyhowError(#[from] anyhow::Error),
-	#[error("{0}")]
+	#[error("{}", localized("ceremony_error", &[("0", .0.as_str())]))]
+	CeremonyError(String),
+	#[error("{}", localized("common_error", &[("0", &.0.to_string())]))]
 	CommonError(#[from] pop_common::Error),
-	#[error("Configuration error: {0}")]
+	#[error("{}", localized("config", &[("0", .0.as_str())]))]
 	Config(String),
-	#[error("Failed to access the current directory")]
+	#[error("{}", localized("connection_failure", &[("0", .0.as_str())]))]
+	ConnectionFailure(String),
+	#[error("{}", localized("current_dir_access", &[]))]
 	CurrentDirAccess,
-	#[error("Failed to parse the endowment value")]
+	#[error("{}", localized("endowment_error", &[]))]
 	EndowmentError,
-	#[error("IO error: {0}")]
+	#[error("{}", localized("export_error", &[("0", .0.as_str())]))]
+	ExportError(String),
+	#[error("{}", localized("extrinsic_submission_error", &[("0", .0.as_str())]))]
+	ExtrinsicSubmissionError(String),
+	#[error("{}", localized("faucet_error", &[("0", .0.as_str())]))]
+	FaucetError(String),
+	#[error("{}", localized("io", &[("0", &.0.to_string())]))]
 	IO(#[from] std::io::Error),
-	#[error("JSON error: {0}")]
+	#[error("{}", localized("incompatible_signer", &[("0", .0.as_str())]))]
+	IncompatibleSigner(String),
+	#[error("{}", localized("invalid_account", &[("0", .0.as_str())]))]
+	InvalidAccount(String),
+	#[error("{}", localized("invalid_multiaddr", &[("0", .0.as_str())]))]
+	InvalidMultiaddr(String),
+	#[error("{}", localized("invalid_runtime", &[("0", .0.as_str())]))]
+	InvalidRuntime(String),
+	#[error("{}", localized("json_error", &[("0", &.0.to_string())]))]
 	JsonError(#[from] serde_json::Error),
-	#[error("Missing binary: {0}")]
+	#[error("{}", localized("missing_binary", &[("0", .0.as_str())]))]
 	MissingBinary(String),
-	#[error("Missing chain spec file at: {0}")]
+	#[error("{}", localized("missing_chain_spec", &[("0", .0.as_str())]))]
 	MissingChainSpec(String),
-	#[error("Command {command} doesn't exist in binary {binary}")]
+	#[error("{}", localized("missing_command", &[
+		("command", .command.as_str()),
+		("binary", .binary.as_str()),
+	]))]
 	MissingCommand { command: String, binary: String },
-	#[error("Orchestrator error: {0}")]
+	#[error("{}", localized("not_ready", &[("0", .0.as_str())]))]
+	NotReady(String),
+	#[error("{}", localized("onboarding_error", &[("0", .0.as_str())]))]
+	OnboardingError(String),
+	#[error("{}", localized("orchestrator_error", &[("0", &.0.to_string())]))]
 	OrchestratorError(#[from] OrchestratorError),
-	#[error("Failed to create pallet directory")]
+	#[error("{}", localized("pallet_dir_creation", &[]))]
 	PalletDirCreation,
-	#[error("Invalid path")]
+	#[error("{}", localized("path_error", &[]))]
 	PathError,
-	#[error("Failed to execute rustfmt")]
+	#[error("{}", localized("rustfmt_error", &[]))]
 	RustfmtError(std::io::Error),
-	#[error("Template error: {0}")]
+	#[error("{}", localized("subxt_error", &[("0", &.0.to_string())]))]
+	SubxtError(#[from] subxt::Error),
+	#[error("{}", localized("sourcing_error", &[("0", &.0.to_string())]))]
 	SourcingError(#[from] pop_common::sourcing::Error),
-	#[error("Toml error: {0}")]
+	#[error("{}", localized("toml_error", &[("0", &.0.to_string())]))]
 	TomlError(#[from] toml_edit::de::Error),
-	#[error("Unsupported command: {0}")]
+	#[error("{}", localized("unsupported_command", &[("0", .0.as_str())]))]
 	UnsupportedCommand(String),
-	#[error("Failed to locate the workspace")]
+	#[error("{}", localized("upgrade_error", &[("0", .0.as_str())]))]
+	UpgradeError(String),
+	#[error("{}", localized("workspace_locate", &[]))]
 	WorkspaceLocate,
 }
+
+/// Formats the message of an error via the catalog of user-facing messages.
+///
+/// # Arguments
+/// * `variant` - The identifier of the error variant.
+/// * `args` - The arguments of the message, as (name, value) pairs.
+fn localized(variant: &str, args: &[(&str, &str)]) -> String {
+	i18n::message(&format!("error.parachains.{variant}"), args)
+}
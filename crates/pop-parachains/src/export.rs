@@ -0,0 +1,480 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{call::set_up_client, errors::Error};
+use clap::ValueEnum;
+use duct::cmd;
+use pop_common::encode_hex;
+use serde_json::{Map, Value};
+use std::{
+	fmt::Write,
+	fs::{create_dir_all, write},
+	ops::RangeInclusive,
+	path::{Path, PathBuf},
+};
+use subxt::{
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	config::{substrate::BlakeTwo256, Hasher},
+	events::Phase,
+	SubstrateConfig,
+};
+
+/// The format in which chain data is exported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+	/// A SQLite database, created via the `sqlite3` CLI.
+	#[default]
+	Sqlite,
+	/// A SQL script creating and populating the tables, which can be loaded into SQLite (or most
+	/// other databases) without any tools installed.
+	Sql,
+	/// A directory of Parquet files, one per table, created via the `duckdb` CLI.
+	Parquet,
+}
+
+/// The type of the values of a column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnType {
+	/// A signed 64-bit integer.
+	Integer,
+	/// A UTF-8 string.
+	Text,
+	/// A boolean.
+	Boolean,
+}
+
+impl ColumnType {
+	/// The type as declared by SQLite.
+	fn sqlite(&self) -> &'static str {
+		match self {
+			ColumnType::Integer | ColumnType::Boolean => "INTEGER",
+			ColumnType::Text => "TEXT",
+		}
+	}
+
+	/// The type as declared by DuckDB, and so of the Parquet column.
+	fn duckdb(&self) -> &'static str {
+		match self {
+			ColumnType::Integer => "BIGINT",
+			ColumnType::Text => "VARCHAR",
+			ColumnType::Boolean => "BOOLEAN",
+		}
+	}
+}
+
+/// A column of a table of exported chain data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Column {
+	/// The name of the column.
+	pub name: &'static str,
+	/// The type of the values of the column.
+	pub ty: ColumnType,
+	/// Whether the column may have no value.
+	pub nullable: bool,
+}
+
+/// Declares a column.
+const fn column(name: &'static str, ty: ColumnType, nullable: bool) -> Column {
+	Column { name, ty, nullable }
+}
+
+/// The schema of the `blocks` table.
+pub const BLOCKS: &[Column] = &[
+	column("number", ColumnType::Integer, false),
+	column("hash", ColumnType::Text, false),
+	column("parent_hash", ColumnType::Text, false),
+	column("timestamp", ColumnType::Integer, true),
+	column("extrinsics", ColumnType::Integer, false),
+	column("events", ColumnType::Integer, false),
+];
+/// The schema of the `extrinsics` table.
+pub const EXTRINSICS: &[Column] = &[
+	column("block_number", ColumnType::Integer, false),
+	column("extrinsic_index", ColumnType::Integer, false),
+	column("hash", ColumnType::Text, false),
+	column("pallet", ColumnType::Text, false),
+	column("call", ColumnType::Text, false),
+	column("signer", ColumnType::Text, true),
+	column("args", ColumnType::Text, false),
+	column("success", ColumnType::Boolean, false),
+];
+/// The schema of the `events` table.
+pub const EVENTS: &[Column] = &[
+	column("block_number", ColumnType::Integer, false),
+	column("event_index", ColumnType::Integer, false),
+	column("extrinsic_index", ColumnType::Integer, true),
+	column("pallet", ColumnType::Text, false),
+	column("variant", ColumnType::Text, false),
+	column("fields", ColumnType::Text, false),
+];
+
+/// A table of exported chain data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+	/// The name of the table.
+	pub name: &'static str,
+	/// The columns of the table.
+	pub columns: &'static [Column],
+	/// The rows of the table, with a value per column.
+	pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+	/// An empty table.
+	///
+	/// # Arguments
+	/// * `name` - The name of the table.
+	/// * `columns` - The columns of the table.
+	pub fn new(name: &'static str, columns: &'static [Column]) -> Self {
+		Self { name, columns, rows: vec![] }
+	}
+
+	/// The statement creating the table within SQLite.
+	fn create_statement(&self) -> String {
+		let columns: Vec<_> = self
+			.columns
+			.iter()
+			.map(|c| {
+				let constraint = if c.nullable { "" } else { " NOT NULL" };
+				format!("{} {}{constraint}", c.name, c.ty.sqlite())
+			})
+			.collect();
+		format!("CREATE TABLE IF NOT EXISTS {} ({});", self.name, columns.join(", "))
+	}
+
+	/// The rows of the table as newline-delimited JSON objects.
+	fn to_ndjson(&self) -> String {
+		self.rows
+			.iter()
+			.map(|row| {
+				let object: Map<_, _> =
+					self.columns.iter().map(|c| c.name.to_string()).zip(row.clone()).collect();
+				Value::Object(object).to_string() + "\n"
+			})
+			.collect()
+	}
+}
+
+/// The decoded extrinsics and events of a range of blocks of a chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainData {
+	/// The blocks, following the [`BLOCKS`] schema.
+	pub blocks: Table,
+	/// The extrinsics, following the [`EXTRINSICS`] schema.
+	pub extrinsics: Table,
+	/// The events, following the [`EVENTS`] schema.
+	pub events: Table,
+}
+
+impl Default for ChainData {
+	fn default() -> Self {
+		Self {
+			blocks: Table::new("blocks", BLOCKS),
+			extrinsics: Table::new("extrinsics", EXTRINSICS),
+			events: Table::new("events", EVENTS),
+		}
+	}
+}
+
+impl ChainData {
+	/// Walks a range of blocks of a chain, decoding their extrinsics and events.
+	///
+	/// # Arguments
+	/// * `url` - The endpoint of a node of the chain, which must retain the state of the blocks
+	///   (e.g. an archive node) for their events to be decoded.
+	/// * `blocks` - The range of block numbers.
+	/// * `progress` - Called with the number of each block once decoded.
+	pub async fn fetch(
+		url: &str,
+		blocks: RangeInclusive<u32>,
+		progress: impl Fn(u32),
+	) -> Result<Self, Error> {
+		if blocks.is_empty() {
+			return Err(Error::ExportError(format!(
+				"invalid block range: {} to {}",
+				blocks.start(),
+				blocks.end()
+			)));
+		}
+		let client = set_up_client(url).await?;
+		let rpc = RpcClient::from_url(url)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+		let rpc = LegacyRpcMethods::<SubstrateConfig>::new(rpc);
+		let mut data = ChainData::default();
+		for number in blocks {
+			let hash = rpc
+				.chain_get_block_hash(Some(number.into()))
+				.await?
+				.ok_or_else(|| Error::ExportError(format!("block #{number} not found")))?;
+			let block = client.blocks().at(hash).await?;
+			let events = block.events().await?;
+			let mut failed = vec![];
+			for (index, event) in events.iter().enumerate() {
+				let event = event.map_err(subxt::Error::from)?;
+				let extrinsic = match event.phase() {
+					Phase::ApplyExtrinsic(index) => Some(index),
+					Phase::Initialization | Phase::Finalization => None,
+				};
+				if event.pallet_name() == "System" && event.variant_name() == "ExtrinsicFailed" {
+					failed.extend(extrinsic);
+				}
+				data.events.rows.push(vec![
+					number.into(),
+					index.into(),
+					extrinsic.into(),
+					event.pallet_name().into(),
+					event.variant_name().into(),
+					event.field_values().map_err(subxt::Error::from)?.to_string().into(),
+				]);
+			}
+			let extrinsics = block.extrinsics().await?;
+			let mut timestamp = None;
+			for extrinsic in extrinsics.iter() {
+				let extrinsic = extrinsic?;
+				let (pallet, call) = (extrinsic.pallet_name()?, extrinsic.variant_name()?);
+				let args = extrinsic.field_values()?;
+				if pallet == "Timestamp" && call == "set" {
+					timestamp = args.values().next().and_then(|now| now.as_u128());
+				}
+				data.extrinsics.rows.push(vec![
+					number.into(),
+					extrinsic.index().into(),
+					format!("{:?}", BlakeTwo256::hash(extrinsic.bytes())).into(),
+					pallet.into(),
+					call.into(),
+					extrinsic.address_bytes().map(encode_hex).into(),
+					args.to_string().into(),
+					(!failed.contains(&extrinsic.index())).into(),
+				]);
+			}
+			data.blocks.rows.push(vec![
+				number.into(),
+				format!("{hash:?}").into(),
+				format!("{:?}", block.header().parent_hash).into(),
+				timestamp.map(|t| t as u64).into(),
+				extrinsics.len().into(),
+				events.len().into(),
+			]);
+			progress(number);
+		}
+		Ok(data)
+	}
+
+	/// The tables of the data.
+	pub fn tables(&self) -> [&Table; 3] {
+		[&self.blocks, &self.extrinsics, &self.events]
+	}
+
+	/// A SQL script creating the tables, if they do not exist, and inserting the rows within a
+	/// single transaction.
+	pub fn to_sql(&self) -> String {
+		let mut sql = String::new();
+		for table in self.tables() {
+			sql.push_str(&table.create_statement());
+			sql.push('\n');
+		}
+		sql.push_str("BEGIN TRANSACTION;\n");
+		for table in self.tables() {
+			let columns: Vec<_> = table.columns.iter().map(|c| c.name).collect();
+			for row in &table.rows {
+				let values: Vec<_> = row.iter().map(sql_literal).collect();
+				let _ = writeln!(
+					sql,
+					"INSERT INTO {} ({}) VALUES ({});",
+					table.name,
+					columns.join(", "),
+					values.join(", ")
+				);
+			}
+		}
+		sql.push_str("COMMIT;\n");
+		sql
+	}
+
+	/// Writes the data in the specified format, returning the files written.
+	///
+	/// # Arguments
+	/// * `format` - The format in which the data is written.
+	/// * `output` - The path of the database or script, or the directory of Parquet files.
+	pub fn write(&self, format: ExportFormat, output: &Path) -> Result<Vec<PathBuf>, Error> {
+		match format {
+			ExportFormat::Sql => {
+				write(output, self.to_sql())?;
+				Ok(vec![output.to_path_buf()])
+			},
+			ExportFormat::Sqlite => {
+				cmd("sqlite3", [output])
+					.stdin_bytes(self.to_sql())
+					.stdout_null()
+					.stderr_capture()
+					.run()
+					.map_err(|e| {
+						Error::ExportError(format!(
+							"unable to create the database via `sqlite3` (is it installed?): {e}"
+						))
+					})?;
+				Ok(vec![output.to_path_buf()])
+			},
+			ExportFormat::Parquet => {
+				create_dir_all(output)?;
+				let staging = tempfile::tempdir()?;
+				let mut files = vec![];
+				for table in self.tables() {
+					let json = staging.path().join(format!("{}.ndjson", table.name));
+					write(&json, table.to_ndjson())?;
+					let parquet = output.join(format!("{}.parquet", table.name));
+					cmd("duckdb", ["-c", &copy_statement(table, &json, &parquet)])
+						.stdout_null()
+						.stderr_capture()
+						.run()
+						.map_err(|e| {
+							Error::ExportError(format!(
+								"unable to create {} via `duckdb` (is it installed?): {e}",
+								parquet.display()
+							))
+						})?;
+					files.push(parquet);
+				}
+				Ok(files)
+			},
+		}
+	}
+}
+
+/// Exports the decoded extrinsics and events of a range of blocks of a chain, returning the files
+/// written.
+///
+/// # Arguments
+/// * `url` - The endpoint of a node of the chain.
+/// * `blocks` - The range of block numbers.
+/// * `format` - The format in which the data is written.
+/// * `output` - The path of the database or script, or the directory of Parquet files.
+/// * `progress` - Called with the number of each block once decoded.
+pub async fn export_chain_data(
+	url: &str,
+	blocks: RangeInclusive<u32>,
+	format: ExportFormat,
+	output: &Path,
+	progress: impl Fn(u32),
+) -> Result<Vec<PathBuf>, Error> {
+	ChainData::fetch(url, blocks, progress).await?.write(format, output)
+}
+
+/// A value as a SQLite literal.
+fn sql_literal(value: &Value) -> String {
+	match value {
+		Value::Null => "NULL".into(),
+		Value::Bool(value) => (*value as u8).to_string(),
+		Value::Number(number) => number.to_string(),
+		Value::String(text) => quote(text),
+		other => quote(&other.to_string()),
+	}
+}
+
+/// Quotes a string as a SQL string literal.
+fn quote(text: &str) -> String {
+	format!("'{}'", text.replace('\'', "''"))
+}
+
+/// The DuckDB statement converting the newline-delimited JSON rows of a table to a Parquet file,
+/// with the columns typed as per the schema of the table.
+fn copy_statement(table: &Table, json: &Path, parquet: &Path) -> String {
+	let columns: Vec<_> = table
+		.columns
+		.iter()
+		.map(|c| format!("{}: {}", quote(c.name), quote(c.ty.duckdb())))
+		.collect();
+	format!(
+		"COPY (SELECT * FROM read_json({}, format = 'newline_delimited', columns = {{{}}})) TO {} (FORMAT PARQUET);",
+		quote(&json.to_string_lossy()),
+		columns.join(", "),
+		quote(&parquet.to_string_lossy())
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+	use std::fs::read_to_string;
+
+	fn data() -> ChainData {
+		let mut data = ChainData::default();
+		data.blocks.rows.push(vec![
+			json!(1),
+			json!("0x01"),
+			json!("0x00"),
+			Value::Null,
+			json!(1),
+			json!(1),
+		]);
+		data.extrinsics.rows.push(vec![
+			json!(1),
+			json!(0),
+			json!("0x02"),
+			json!("System"),
+			json!("remark"),
+			Value::Null,
+			json!("(remark: (0x6974277320706f70))"),
+			json!(true),
+		]);
+		data.events.rows.push(vec![
+			json!(1),
+			json!(0),
+			json!(0),
+			json!("System"),
+			json!("ExtrinsicSuccess"),
+			json!("(dispatch_info: 'it's')"),
+		]);
+		data
+	}
+
+	#[test]
+	fn to_sql_works() {
+		let sql = data().to_sql();
+		assert!(sql.starts_with(
+			"CREATE TABLE IF NOT EXISTS blocks (number INTEGER NOT NULL, hash TEXT NOT NULL, parent_hash TEXT NOT NULL, timestamp INTEGER, extrinsics INTEGER NOT NULL, events INTEGER NOT NULL);\n"
+		));
+		assert!(sql.contains(
+			"INSERT INTO blocks (number, hash, parent_hash, timestamp, extrinsics, events) VALUES (1, '0x01', '0x00', NULL, 1, 1);\n"
+		));
+		assert!(sql.contains("'(remark: (0x6974277320706f70))', 1);\n"));
+		assert!(sql.contains("'(dispatch_info: ''it''s'')');\n"));
+		assert!(sql.ends_with("COMMIT;\n"));
+	}
+
+	#[test]
+	fn write_sql_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let output = temp_dir.path().join("chain.sql");
+		assert_eq!(data().write(ExportFormat::Sql, &output)?, vec![output.clone()]);
+		assert_eq!(read_to_string(&output)?, data().to_sql());
+		Ok(())
+	}
+
+	#[test]
+	fn to_ndjson_works() {
+		assert_eq!(
+			data().blocks.to_ndjson(),
+			"{\"number\":1,\"hash\":\"0x01\",\"parent_hash\":\"0x00\",\"timestamp\":null,\"extrinsics\":1,\"events\":1}\n"
+		);
+		let statement = copy_statement(
+			&data().events,
+			Path::new("/tmp/events.ndjson"),
+			Path::new("/tmp/events.parquet"),
+		);
+		assert!(statement.starts_with(
+			"COPY (SELECT * FROM read_json('/tmp/events.ndjson', format = 'newline_delimited', columns = {'block_number': 'BIGINT', 'event_index': 'BIGINT'"
+		));
+		assert!(statement.ends_with("TO '/tmp/events.parquet' (FORMAT PARQUET);"));
+	}
+
+	#[tokio::test]
+	#[allow(clippy::reversed_empty_ranges)]
+	async fn fetch_fails_with_invalid_range() {
+		assert!(matches!(
+			ChainData::fetch("ws://127.0.0.1:9944", 10..=1, |_| {}).await,
+			Err(Error::ExportError(e)) if e == "invalid block range: 10 to 1"
+		));
+	}
+}
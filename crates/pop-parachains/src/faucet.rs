@@ -0,0 +1,708 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{construct_transfer_extrinsic, set_up_client, submit_extrinsic, FeePayment},
+	errors::Error,
+};
+use pop_common::{i18n::message, Account};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+	collections::HashMap,
+	future::Future,
+	net::{IpAddr, Ipv6Addr, SocketAddr},
+	pin::Pin,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+use subxt::utils::H256;
+use tokio::{
+	io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+	net::{TcpListener, TcpStream},
+	task::JoinHandle,
+	time::timeout,
+};
+use url::Url;
+
+/// The maximum size of a request, including its headers.
+const MAX_REQUEST_SIZE: usize = 16 * 1024;
+/// The time within which a client must send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Verification of the captcha responses of clients, via a `siteverify` API, as exposed by
+/// hCaptcha, reCAPTCHA and Cloudflare Turnstile.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Captcha {
+	/// The endpoint verifying responses (e.g. `https://api.hcaptcha.com/siteverify`).
+	pub verify_url: Url,
+	/// The secret key of the site.
+	pub secret: String,
+}
+
+impl Captcha {
+	/// Verification via hCaptcha.
+	///
+	/// # Arguments
+	/// * `secret` - The secret key of the site.
+	pub fn hcaptcha(secret: String) -> Self {
+		let verify_url = Url::parse("https://api.hcaptcha.com/siteverify").expect("valid url");
+		Self { verify_url, secret }
+	}
+
+	/// Verifies the captcha response of a client.
+	///
+	/// # Arguments
+	/// * `response` - The captcha response submitted by the client.
+	/// * `ip` - The address of the client.
+	async fn verify(&self, response: &str, ip: IpAddr) -> Result<bool, Error> {
+		#[derive(Deserialize)]
+		struct Verification {
+			success: bool,
+		}
+		let ip = ip.to_string();
+		let params = [("secret", self.secret.as_str()), ("response", response), ("remoteip", &ip)];
+		let verification: Verification = reqwest::Client::new()
+			.post(self.verify_url.clone())
+			.form(&params)
+			.send()
+			.await
+			.and_then(|r| r.error_for_status())
+			.map_err(|e| captcha_failed(&e))?
+			.json()
+			.await
+			.map_err(|e| captcha_failed(&e))?;
+		Ok(verification.success)
+	}
+}
+
+/// The error of a failure to verify a captcha.
+fn captcha_failed(error: &reqwest::Error) -> Error {
+	Error::FaucetError(message("faucet.captcha_failed", &[("error", &error.to_string())]))
+}
+
+/// Configuration of a faucet, dripping funds from an account of a chain to those requesting them.
+#[derive(Clone, Debug)]
+pub struct FaucetConfig {
+	/// The local address on which the faucet listens, with port `0` selecting any available port.
+	pub listen: SocketAddr,
+	/// The endpoint of a node of the chain.
+	pub url: Url,
+	/// The secret key URI of the account from which funds are dripped.
+	pub suri: String,
+	/// The amount dripped per request.
+	pub amount: u128,
+	/// The time for which both an account and a client must wait between drips.
+	pub cooldown: Duration,
+	/// The verification of captcha responses, if required.
+	pub captcha: Option<Captcha>,
+	/// The number of trusted reverse proxies the faucet is served behind, each appending the
+	/// address of its peer to the `X-Forwarded-For` header. The address of clients is taken from
+	/// the header when non-zero, skipping the entries appended by all but the outermost proxy, as
+	/// any entries before it can be forged by clients.
+	pub trusted_proxies: usize,
+}
+
+impl FaucetConfig {
+	/// Configures a faucet which listens on any available local port, with a cooldown of an hour
+	/// and without a captcha.
+	///
+	/// # Arguments
+	/// * `url` - The endpoint of a node of the chain.
+	/// * `suri` - The secret key URI of the account from which funds are dripped.
+	/// * `amount` - The amount dripped per request.
+	pub fn new(url: Url, suri: String, amount: u128) -> Self {
+		Self {
+			listen: SocketAddr::from(([127, 0, 0, 1], 0)),
+			url,
+			suri,
+			amount,
+			cooldown: Duration::from_secs(60 * 60),
+			captcha: None,
+			trusted_proxies: 0,
+		}
+	}
+}
+
+/// Dispenses an amount to an account, returning the hash of the transfer.
+pub type Dispense = Arc<
+	dyn Fn(Account, u128) -> Pin<Box<dyn Future<Output = Result<H256, Error>> + Send>>
+		+ Send
+		+ Sync,
+>;
+
+/// A faucet serving drips over HTTP:
+/// - `GET /` describes the faucet: the amount dripped, the cooldown (in seconds) and whether a
+///   captcha is required.
+/// - `POST /drip`, with a JSON body of `{"address": ..., "captcha": ...}`, transfers the amount to
+///   the address, responding with the hash of the transfer.
+///
+/// Drips are rate limited per account and per client, with IPv6 clients limited per /64 network as
+/// hosts are typically assigned an entire network. Clients waiting for the cooldown are told when
+/// to retry (`429 Too Many Requests`, with a `Retry-After` header).
+pub struct Faucet {
+	/// The address on which the faucet is listening.
+	local_addr: SocketAddr,
+	/// The task accepting connections.
+	handle: JoinHandle<()>,
+}
+
+impl Faucet {
+	/// Starts a faucet transferring funds on the chain, which runs until stopped or dropped.
+	///
+	/// # Arguments
+	/// * `config` - The configuration of the faucet.
+	pub async fn start(config: FaucetConfig) -> Result<Self, Error> {
+		// Ensure the chain is reachable before accepting any requests.
+		set_up_client(config.url.as_str()).await?;
+		let (url, suri) = (config.url.to_string(), config.suri.clone());
+		let dispense: Dispense = Arc::new(move |account, amount| {
+			let (url, suri) = (url.clone(), suri.clone());
+			Box::pin(async move {
+				let call = construct_transfer_extrinsic(&account.to_hex(), amount)?;
+				submit_extrinsic(&url, &call, &suri, None, &FeePayment::Native).await
+			})
+		});
+		Self::start_with(config, dispense).await
+	}
+
+	/// Starts a faucet dispensing funds via the specified function, which runs until stopped or
+	/// dropped.
+	///
+	/// # Arguments
+	/// * `config` - The configuration of the faucet.
+	/// * `dispense` - The function dispensing funds to an account.
+	pub async fn start_with(config: FaucetConfig, dispense: Dispense) -> Result<Self, Error> {
+		let listener = TcpListener::bind(config.listen).await?;
+		let local_addr = listener.local_addr()?;
+		let service = Arc::new(Service {
+			limiter: Mutex::new(RateLimiter::new(config.cooldown)),
+			config,
+			dispense,
+			transfers: tokio::sync::Mutex::new(()),
+		});
+		let handle = tokio::spawn(async move {
+			while let Ok((stream, peer)) = listener.accept().await {
+				let service = service.clone();
+				tokio::spawn(async move {
+					// Connection errors only affect the connecting client.
+					let _ = service.serve(stream, peer).await;
+				});
+			}
+		});
+		Ok(Self { local_addr, handle })
+	}
+
+	/// The endpoint at which the faucet is served.
+	pub fn url(&self) -> Url {
+		Url::parse(&format!("http://{}", self.local_addr)).expect("socket address is a valid host")
+	}
+
+	/// Stops the faucet.
+	pub fn stop(self) {}
+}
+
+impl Drop for Faucet {
+	fn drop(&mut self) {
+		self.handle.abort();
+	}
+}
+
+/// The state shared by the connections of a faucet.
+struct Service {
+	config: FaucetConfig,
+	dispense: Dispense,
+	limiter: Mutex<RateLimiter>,
+	/// Serializes transfers, so that the transactions of concurrent drips don't share a nonce.
+	transfers: tokio::sync::Mutex<()>,
+}
+
+impl Service {
+	/// Serves a single request of a client.
+	async fn serve(&self, mut stream: TcpStream, peer: SocketAddr) -> Result<(), Error> {
+		let response = match timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+			Ok(Ok(request)) => self.handle(request, peer.ip()).await,
+			Ok(Err(e)) => Response::error(400, &e.to_string()),
+			Err(_) => Response::error(408, &message("faucet.request_timed_out", &[])),
+		};
+		stream.write_all(&response.to_bytes()).await?;
+		stream.shutdown().await?;
+		Ok(())
+	}
+
+	/// Handles a request, routing it by its method and path.
+	async fn handle(&self, request: Request, peer: IpAddr) -> Response {
+		match (request.method.as_str(), request.path.as_str()) {
+			("OPTIONS", _) => Response { status: 204, body: None, retry_after: None },
+			("GET", "/") => Response::ok(json!({
+				"amount": self.config.amount.to_string(),
+				"cooldown": self.config.cooldown.as_secs(),
+				"captcha": self.config.captcha.is_some(),
+			})),
+			("POST", "/drip") => self.drip(&request.body, self.client(&request, peer)).await,
+			(_, "/" | "/drip") => Response::error(405, &message("faucet.method_not_allowed", &[])),
+			_ => Response::error(404, &message("faucet.not_found", &[])),
+		}
+	}
+
+	/// The address of the client making a request, taken from the `X-Forwarded-For` header when
+	/// behind trusted proxies. Falls back to the address of the peer should the header not contain
+	/// an entry for each proxy.
+	///
+	/// # Arguments
+	/// * `request` - The request.
+	/// * `peer` - The address of the peer making the request.
+	fn client(&self, request: &Request, peer: IpAddr) -> IpAddr {
+		let proxies = self.config.trusted_proxies;
+		if proxies == 0 {
+			return peer;
+		}
+		let forwarded: Vec<&str> = request
+			.header("x-forwarded-for")
+			.map(|f| f.split(',').map(str::trim).collect())
+			.unwrap_or_default();
+		forwarded
+			.len()
+			.checked_sub(proxies)
+			.and_then(|i| forwarded[i].parse().ok())
+			.unwrap_or(peer)
+	}
+
+	/// Drips funds to the account requested, if permitted.
+	async fn drip(&self, body: &[u8], client: IpAddr) -> Response {
+		#[derive(Deserialize)]
+		struct Drip {
+			address: String,
+			captcha: Option<String>,
+		}
+		let Ok(drip) = serde_json::from_slice::<Drip>(body) else {
+			return Response::error(400, &message("faucet.invalid_body", &[]));
+		};
+		let account = match Account::parse(&drip.address) {
+			Ok(account) => account,
+			Err(e) => {
+				let error = message("faucet.invalid_address", &[("error", &e.to_string())]);
+				return Response::error(400, &error);
+			},
+		};
+		if let Some(captcha) = &self.config.captcha {
+			let Some(response) = drip.captcha.as_deref() else {
+				return Response::error(403, &message("faucet.captcha_required", &[]));
+			};
+			match captcha.verify(response, client).await {
+				Ok(true) => {},
+				Ok(false) => return Response::error(403, &message("faucet.invalid_captcha", &[])),
+				Err(e) => return Response::error(502, &e.to_string()),
+			}
+		}
+		let keys = [format!("account {}", account.to_hex()), client_key(client)];
+		let reserved = self.limiter.lock().expect("lock poisoned").reserve(&keys, Instant::now());
+		if let Err(remaining) = reserved {
+			// Round up, so that clients retrying when told to are permitted.
+			let retry_after = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+			let error =
+				message("faucet.already_dripped", &[("retry_after", &retry_after.to_string())]);
+			let mut response = Response::error(429, &error);
+			response.retry_after = Some(retry_after);
+			return response;
+		}
+		let result = {
+			let _transfer = self.transfers.lock().await;
+			(self.dispense)(account, self.config.amount).await
+		};
+		match result {
+			Ok(hash) => Response::ok(json!({
+				"hash": format!("{hash:?}"),
+				"amount": self.config.amount.to_string(),
+			})),
+			Err(e) => {
+				// Failed drips don't count towards the rate limit.
+				self.limiter.lock().expect("lock poisoned").release(&keys);
+				Response::error(500, &message("faucet.drip_failed", &[("error", &e.to_string())]))
+			},
+		}
+	}
+}
+
+/// The key by which a client is rate limited, with IPv6 clients identified by their /64 network.
+///
+/// # Arguments
+/// * `client` - The address of the client.
+fn client_key(client: IpAddr) -> String {
+	match client {
+		IpAddr::V4(ip) => format!("client {ip}"),
+		IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+			Some(ip) => format!("client {ip}"),
+			None => {
+				let network = Ipv6Addr::from(u128::from(ip) & !u128::from(u64::MAX));
+				format!("client {network}/64")
+			},
+		},
+	}
+}
+
+/// Limits drips to one per key (e.g. an account or client) within a cooldown.
+struct RateLimiter {
+	cooldown: Duration,
+	/// The time of the last drip, by key.
+	drips: HashMap<String, Instant>,
+}
+
+impl RateLimiter {
+	fn new(cooldown: Duration) -> Self {
+		Self { cooldown, drips: HashMap::new() }
+	}
+
+	/// Reserves a drip for each of the keys, unless any key dripped within the cooldown, in which
+	/// case the time remaining until a drip is permitted is returned.
+	///
+	/// # Arguments
+	/// * `keys` - The keys identifying the drip.
+	/// * `now` - The current time.
+	fn reserve(&mut self, keys: &[String], now: Instant) -> Result<(), Duration> {
+		let cooldown = self.cooldown;
+		self.drips.retain(|_, dripped| now.duration_since(*dripped) < cooldown);
+		let remaining = keys
+			.iter()
+			.filter_map(|key| self.drips.get(key))
+			.map(|dripped| cooldown - now.duration_since(*dripped))
+			.max();
+		if let Some(remaining) = remaining {
+			return Err(remaining);
+		}
+		for key in keys {
+			self.drips.insert(key.clone(), now);
+		}
+		Ok(())
+	}
+
+	/// Releases the drips reserved for the keys.
+	///
+	/// # Arguments
+	/// * `keys` - The keys identifying the drip.
+	fn release(&mut self, keys: &[String]) {
+		for key in keys {
+			self.drips.remove(key);
+		}
+	}
+}
+
+/// An HTTP request.
+#[derive(Debug, PartialEq)]
+struct Request {
+	method: String,
+	path: String,
+	/// The headers of the request, by lowercase name.
+	headers: HashMap<String, String>,
+	body: Vec<u8>,
+}
+
+impl Request {
+	/// The value of a header.
+	///
+	/// # Arguments
+	/// * `name` - The lowercase name of the header.
+	fn header(&self, name: &str) -> Option<&str> {
+		self.headers.get(name).map(String::as_str)
+	}
+}
+
+/// Reads an HTTP/1.1 request from a stream, up to [`MAX_REQUEST_SIZE`].
+async fn read_request(stream: &mut (impl AsyncRead + Unpin)) -> Result<Request, Error> {
+	let invalid = |key: &str| Error::FaucetError(message(key, &[]));
+	let mut buffer = Vec::new();
+	let mut chunk = [0u8; 4096];
+	let head_end = loop {
+		if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+			break end;
+		}
+		if buffer.len() > MAX_REQUEST_SIZE {
+			return Err(invalid("faucet.request_too_large"));
+		}
+		let read = stream.read(&mut chunk).await?;
+		if read == 0 {
+			return Err(invalid("faucet.incomplete_request"));
+		}
+		buffer.extend_from_slice(&chunk[..read]);
+	};
+	let head = String::from_utf8(buffer[..head_end].to_vec())
+		.map_err(|_| invalid("faucet.invalid_request"))?;
+	let mut lines = head.split("\r\n");
+	let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+	let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+		return Err(invalid("faucet.invalid_request_line"));
+	};
+	let path = target.split('?').next().unwrap_or(target).to_string();
+	let headers: HashMap<_, _> = lines
+		.filter_map(|line| line.split_once(':'))
+		.map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+		.collect();
+	let length = match headers.get("content-length") {
+		Some(length) =>
+			length.parse::<usize>().map_err(|_| invalid("faucet.invalid_content_length"))?,
+		None => 0,
+	};
+	match (head_end + 4).checked_add(length) {
+		Some(size) if size <= MAX_REQUEST_SIZE => {},
+		_ => return Err(invalid("faucet.request_too_large")),
+	}
+	let mut body = buffer.split_off(head_end + 4);
+	while body.len() < length {
+		let read = stream.read(&mut chunk).await?;
+		if read == 0 {
+			return Err(invalid("faucet.incomplete_request"));
+		}
+		body.extend_from_slice(&chunk[..read]);
+	}
+	body.truncate(length);
+	Ok(Request { method: method.to_string(), path, headers, body })
+}
+
+/// An HTTP response, with a JSON body.
+#[derive(Debug, PartialEq)]
+struct Response {
+	status: u16,
+	body: Option<Value>,
+	/// The number of seconds after which the client may retry.
+	retry_after: Option<u64>,
+}
+
+impl Response {
+	fn ok(body: Value) -> Self {
+		Self { status: 200, body: Some(body), retry_after: None }
+	}
+
+	fn error(status: u16, error: &str) -> Self {
+		Self { status, body: Some(json!({ "error": error })), retry_after: None }
+	}
+
+	/// Serializes the response, permitting requests from any origin so that the faucet can be used
+	/// from a web page.
+	fn to_bytes(&self) -> Vec<u8> {
+		let reason = match self.status {
+			200 => "OK",
+			204 => "No Content",
+			400 => "Bad Request",
+			403 => "Forbidden",
+			404 => "Not Found",
+			405 => "Method Not Allowed",
+			408 => "Request Timeout",
+			429 => "Too Many Requests",
+			502 => "Bad Gateway",
+			_ => "Internal Server Error",
+		};
+		let body = self.body.as_ref().map(|b| b.to_string()).unwrap_or_default();
+		let mut response = format!(
+			"HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\nConnection: close\r\n",
+			self.status,
+			body.len()
+		);
+		if let Some(retry_after) = self.retry_after {
+			response.push_str(&format!("Retry-After: {retry_after}\r\n"));
+		}
+		response.push_str("\r\n");
+		response.push_str(&body);
+		response.into_bytes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use reqwest::StatusCode;
+	use std::sync::atomic::{AtomicBool, Ordering};
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+
+	const CHARLIE: &str = "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y";
+	const DAVE: &str = "5DAAnrj7VHTznn2AWBemMuyBwZWs6FNFjdyVXUeYum3PTXFy";
+
+	fn config() -> FaucetConfig {
+		FaucetConfig::new("ws://127.0.0.1:9944".parse().expect("valid url"), "//Alice".into(), 10)
+	}
+
+	async fn faucet(captcha: Option<Captcha>, fails: Arc<AtomicBool>) -> Result<Faucet, Error> {
+		faucet_with(FaucetConfig { captcha, ..config() }, fails).await
+	}
+
+	async fn faucet_with(config: FaucetConfig, fails: Arc<AtomicBool>) -> Result<Faucet, Error> {
+		let dispense: Dispense = Arc::new(move |_, _| {
+			let fails = fails.load(Ordering::SeqCst);
+			Box::pin(async move {
+				match fails {
+					true => Err(Error::FaucetError("insufficient funds".into())),
+					false => Ok(H256::repeat_byte(1)),
+				}
+			})
+		});
+		Faucet::start_with(config, dispense).await
+	}
+
+	async fn drip(faucet: &Faucet, body: Value) -> anyhow::Result<(StatusCode, Value)> {
+		let response = reqwest::Client::new()
+			.post(faucet.url().join("drip")?)
+			.json(&body)
+			.send()
+			.await?;
+		Ok((response.status(), response.json().await?))
+	}
+
+	#[test]
+	fn rate_limiter_works() {
+		let mut limiter = RateLimiter::new(Duration::from_secs(60));
+		let now = Instant::now();
+		let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+		assert_eq!(limiter.reserve(&keys(&["alice", "client"]), now), Ok(()));
+		let later = now + Duration::from_secs(20);
+		assert_eq!(limiter.reserve(&keys(&["bob"]), later), Ok(()));
+		assert_eq!(limiter.reserve(&keys(&["bob", "client"]), later), Err(Duration::from_secs(60)));
+		assert_eq!(limiter.reserve(&keys(&["alice"]), later), Err(Duration::from_secs(40)));
+		limiter.release(&keys(&["alice"]));
+		assert_eq!(limiter.reserve(&keys(&["alice"]), later), Ok(()));
+		assert_eq!(limiter.reserve(&keys(&["client"]), now + Duration::from_secs(60)), Ok(()));
+	}
+
+	#[test]
+	fn client_key_works() {
+		let key = |ip: &str| client_key(ip.parse().expect("valid address"));
+		assert_eq!(key("192.168.1.1"), "client 192.168.1.1");
+		assert_eq!(key("::ffff:192.168.1.1"), "client 192.168.1.1");
+		assert_eq!(key("2001:db8:1:2:3:4:5:6"), "client 2001:db8:1:2::/64");
+		assert_eq!(key("2001:db8:1:2::ffff"), key("2001:db8:1:2:3:4:5:6"));
+		assert_ne!(key("2001:db8:1:3::1"), key("2001:db8:1:2::1"));
+	}
+
+	#[tokio::test]
+	async fn read_request_works() -> Result<(), Error> {
+		let request =
+			b"POST /drip?source=web HTTP/1.1\r\nHost: localhost\r\nContent-Length: 4\r\n\r\n{}\r\n";
+		assert_eq!(
+			read_request(&mut &request[..]).await?,
+			Request {
+				method: "POST".into(),
+				path: "/drip".into(),
+				headers: HashMap::from([
+					("host".into(), "localhost".into()),
+					("content-length".into(), "4".into())
+				]),
+				body: b"{}\r\n".to_vec(),
+			}
+		);
+		assert!(matches!(
+			read_request(&mut &b"GET / HTTP/1.1\r\n"[..]).await,
+			Err(Error::FaucetError(e)) if e == "incomplete request"
+		));
+		assert!(matches!(
+			read_request(
+				&mut &b"POST /drip HTTP/1.1\r\nContent-Length: 18446744073709551615\r\n\r\n"[..]
+			)
+			.await,
+			Err(Error::FaucetError(e)) if e == "request too large"
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn faucet_works() -> anyhow::Result<()> {
+		let fails = Arc::new(AtomicBool::new(false));
+		let faucet = faucet(None, fails.clone()).await?;
+		let info: Value = reqwest::get(faucet.url()).await?.json().await?;
+		assert_eq!(info, json!({ "amount": "10", "cooldown": 3600, "captcha": false }));
+
+		let hash = format!("{:?}", H256::repeat_byte(1));
+		assert_eq!(
+			drip(&faucet, json!({ "address": ALICE })).await?,
+			(StatusCode::OK, json!({ "hash": hash, "amount": "10" }))
+		);
+		// Both the account and the client are rate limited.
+		let response = reqwest::Client::new()
+			.post(faucet.url().join("drip")?)
+			.json(&json!({ "address": ALICE }))
+			.send()
+			.await?;
+		assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+		assert_eq!(response.headers()["retry-after"], "3600");
+		assert_eq!(
+			drip(&faucet, json!({ "address": BOB })).await?.0,
+			StatusCode::TOO_MANY_REQUESTS
+		);
+
+		assert_eq!(drip(&faucet, json!({ "address": "5Grwva" })).await?.0, StatusCode::BAD_REQUEST);
+		assert_eq!(
+			reqwest::get(faucet.url().join("unknown")?).await?.status(),
+			StatusCode::NOT_FOUND
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn faucet_uses_forwarded_client() -> anyhow::Result<()> {
+		let faucet = faucet_with(
+			FaucetConfig { trusted_proxies: 1, ..config() },
+			Arc::new(AtomicBool::new(false)),
+		)
+		.await?;
+		let drip = |address: &str, forwarded: &str| {
+			let request = reqwest::Client::new()
+				.post(faucet.url().join("drip").expect("valid url"))
+				.header("X-Forwarded-For", forwarded)
+				.json(&json!({ "address": address }));
+			async move { anyhow::Ok(request.send().await?.status()) }
+		};
+		assert_eq!(drip(ALICE, "10.0.0.1, 192.0.2.1").await?, StatusCode::OK);
+		// The client is identified by the entry appended by the proxy, not those it forwarded.
+		assert_eq!(drip(BOB, "10.0.0.2, 192.0.2.1").await?, StatusCode::TOO_MANY_REQUESTS);
+		assert_eq!(drip(BOB, "192.0.2.1, 192.0.2.2").await?, StatusCode::OK);
+		// IPv6 clients are limited per /64 network.
+		assert_eq!(drip(CHARLIE, "2001:db8::1").await?, StatusCode::OK);
+		assert_eq!(drip(DAVE, "2001:db8::2").await?, StatusCode::TOO_MANY_REQUESTS);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn faucet_releases_failed_drips() -> anyhow::Result<()> {
+		let fails = Arc::new(AtomicBool::new(true));
+		let faucet = faucet(None, fails.clone()).await?;
+		assert_eq!(
+			drip(&faucet, json!({ "address": ALICE })).await?,
+			(
+				StatusCode::INTERNAL_SERVER_ERROR,
+				json!({ "error": "unable to drip: Faucet error: insufficient funds" })
+			)
+		);
+		fails.store(false, Ordering::SeqCst);
+		assert_eq!(drip(&faucet, json!({ "address": ALICE })).await?.0, StatusCode::OK);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn faucet_verifies_captcha() -> anyhow::Result<()> {
+		let mut server = mockito::Server::new_async().await;
+		let mut verify = |response: &str, success: bool| {
+			server
+				.mock("POST", "/siteverify")
+				.match_body(mockito::Matcher::UrlEncoded("response".into(), response.into()))
+				.with_body(json!({ "success": success }).to_string())
+				.create()
+		};
+		let (_invalid, _valid) = (verify("invalid", false), verify("valid", true));
+		let captcha = Captcha {
+			verify_url: format!("{}/siteverify", server.url()).parse()?,
+			secret: "secret".into(),
+		};
+		let faucet = faucet(Some(captcha), Arc::new(AtomicBool::new(false))).await?;
+		assert_eq!(
+			drip(&faucet, json!({ "address": ALICE })).await?,
+			(StatusCode::FORBIDDEN, json!({ "error": "a captcha is required" }))
+		);
+		assert_eq!(
+			drip(&faucet, json!({ "address": ALICE, "captcha": "invalid" })).await?,
+			(StatusCode::FORBIDDEN, json!({ "error": "invalid captcha" }))
+		);
+		assert_eq!(
+			drip(&faucet, json!({ "address": ALICE, "captcha": "valid" })).await?.0,
+			StatusCode::OK
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,343 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use duct::cmd;
+use pop_common::find_workspace_toml;
+use serde::Deserialize;
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+	fs::{read_to_string, write},
+	path::{Path, PathBuf},
+};
+use toml_edit::{value, Array, DocumentMut, ImDocument, InlineTable, Item};
+
+/// The features which must be propagated to every dependency defining them.
+pub const AUDITED_FEATURES: [&str; 3] = ["std", "runtime-benchmarks", "try-runtime"];
+
+/// The metadata of a workspace, as output by `cargo metadata`.
+#[derive(Deserialize)]
+struct Metadata {
+	packages: Vec<Package>,
+	workspace_members: Vec<String>,
+}
+
+/// A package within the metadata of a workspace.
+#[derive(Deserialize)]
+struct Package {
+	id: String,
+	name: String,
+	manifest_path: PathBuf,
+	features: BTreeMap<String, Vec<String>>,
+	dependencies: Vec<Dependency>,
+}
+
+/// A dependency of a package within the metadata of a workspace.
+#[derive(Deserialize)]
+struct Dependency {
+	name: String,
+	rename: Option<String>,
+	kind: Option<String>,
+	optional: bool,
+	uses_default_features: bool,
+}
+
+impl Dependency {
+	/// The name by which the dependency is referenced within the manifest.
+	fn key(&self) -> &str {
+		self.rename.as_deref().unwrap_or(&self.name)
+	}
+}
+
+/// The kind of misconfiguration of the features of a package.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FeatureIssueKind {
+	/// A feature is not forwarded to a dependency which defines it.
+	Unpropagated {
+		/// The feature.
+		feature: String,
+		/// The name by which the dependency is referenced within the manifest.
+		dependency: String,
+		/// Whether the dependency is optional.
+		optional: bool,
+	},
+	/// A dependency enabling `std` by default is included with its default features, so that
+	/// `std` is enabled within `no_std` builds.
+	DefaultFeatures {
+		/// The name by which the dependency is referenced within the manifest.
+		dependency: String,
+	},
+	/// The `std` feature is not enabled by default.
+	MissingDefaultStd,
+}
+
+/// A misconfiguration of the features of a package within a workspace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureIssue {
+	/// The manifest of the package.
+	pub manifest: PathBuf,
+	/// The line of the manifest to be fixed, if known.
+	pub line: Option<usize>,
+	/// The name of the package.
+	pub package: String,
+	/// The kind of misconfiguration.
+	pub kind: FeatureIssueKind,
+}
+
+impl FeatureIssue {
+	/// Fixes the misconfiguration within the manifest of the package, or within the manifest of
+	/// the workspace for dependencies inherited from it.
+	pub fn fix(&self) -> Result<(), Error> {
+		let mut manifest = self.manifest.clone();
+		let mut doc = parse(&manifest)?;
+		match &self.kind {
+			FeatureIssueKind::Unpropagated { .. } | FeatureIssueKind::MissingDefaultStd => {
+				let feature = match &self.kind {
+					FeatureIssueKind::Unpropagated { feature, .. } => feature.as_str(),
+					_ => "default",
+				};
+				let features = doc["features"].or_insert(toml_edit::table());
+				let entries = features[feature].or_insert(value(Array::new()));
+				entries
+					.as_array_mut()
+					.ok_or_else(|| {
+						Error::Config(format!("expected the `{feature}` feature to be an array"))
+					})?
+					.push(self.entry());
+			},
+			FeatureIssueKind::DefaultFeatures { dependency } => {
+				let inherited = doc
+					.get("dependencies")
+					.and_then(|d| d.get(dependency))
+					.and_then(|d| d.get("workspace"))
+					.and_then(Item::as_bool)
+					.unwrap_or_default();
+				if inherited {
+					manifest =
+						self.manifest.parent().and_then(find_workspace_toml).ok_or_else(|| {
+							Error::Config(format!(
+								"unable to locate the workspace of {}",
+								self.package
+							))
+						})?;
+					doc = parse(&manifest)?;
+				}
+				let dependencies = match inherited {
+					true => &mut doc["workspace"]["dependencies"],
+					false => &mut doc["dependencies"],
+				};
+				let entry = dependencies.get_mut(dependency).ok_or_else(|| {
+					Error::Config(format!("expected the `{dependency}` dependency"))
+				})?;
+				if let Some(version) = entry.as_str().map(String::from) {
+					let mut table = InlineTable::new();
+					table.insert("version", version.into());
+					*entry = value(table);
+				}
+				let table = entry.as_table_like_mut().ok_or_else(|| {
+					Error::Config(format!("expected the `{dependency}` dependency to be a table"))
+				})?;
+				table.insert("default-features", value(false));
+				if let Some(table) = entry.as_inline_table_mut() {
+					table.fmt();
+				}
+			},
+		}
+		write(&manifest, doc.to_string())?;
+		Ok(())
+	}
+
+	/// The entry to be added to the features of the package, if any.
+	fn entry(&self) -> String {
+		match &self.kind {
+			FeatureIssueKind::Unpropagated { feature, dependency, optional } =>
+				format!("{dependency}{}/{feature}", if *optional { "?" } else { "" }),
+			FeatureIssueKind::DefaultFeatures { .. } => "default-features = false".to_string(),
+			FeatureIssueKind::MissingDefaultStd => "std".to_string(),
+		}
+	}
+}
+
+impl Display for FeatureIssue {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.manifest.display())?;
+		if let Some(line) = self.line {
+			write!(f, ":{line}")?;
+		}
+		match &self.kind {
+			FeatureIssueKind::Unpropagated { feature, .. } => write!(
+				f,
+				": the `{feature}` feature of `{}` does not enable \"{}\"",
+				self.package,
+				self.entry()
+			),
+			FeatureIssueKind::DefaultFeatures { dependency } => write!(
+				f,
+				": `{dependency}` enables `std` by default within `{}`; set `default-features = false`",
+				self.package
+			),
+			FeatureIssueKind::MissingDefaultStd =>
+				write!(f, ": the `default` feature of `{}` does not enable \"std\"", self.package),
+		}
+	}
+}
+
+/// Audits the propagation of features across the packages of a workspace: that each of the
+/// [`AUDITED_FEATURES`] defined by a package enables the same feature of each dependency defining
+/// it, and that `std` is only enabled via the `std` feature.
+///
+/// # Arguments
+/// * `path` - The path to the workspace.
+pub fn audit_features(path: &Path) -> Result<Vec<FeatureIssue>, Error> {
+	let output = cmd("cargo", ["metadata", "--format-version", "1"])
+		.dir(path)
+		.stderr_capture()
+		.read()
+		.map_err(|e| Error::Config(format!("unable to read the metadata of the workspace: {e}")))?;
+	let metadata: Metadata = serde_json::from_str(&output)?;
+	let features_of = |name: &str| -> Vec<&BTreeMap<String, Vec<String>>> {
+		metadata
+			.packages
+			.iter()
+			.filter(|p| p.name == name)
+			.map(|p| &p.features)
+			.collect()
+	};
+	let mut issues = vec![];
+	for package in metadata.packages.iter().filter(|p| metadata.workspace_members.contains(&p.id)) {
+		let contents = read_to_string(&package.manifest_path)?;
+		let doc = ImDocument::parse(contents.as_str()).map_err(|e| Error::TomlError(e.into()))?;
+		let line = |keys: &[&str]| -> Option<usize> {
+			let mut item = doc.as_item();
+			for key in keys {
+				item = item.get(key)?;
+			}
+			let start = item.span()?.start;
+			Some(contents[..start].matches('\n').count() + 1)
+		};
+		let issue = |line: Option<usize>, kind: FeatureIssueKind| FeatureIssue {
+			manifest: package.manifest_path.clone(),
+			line,
+			package: package.name.clone(),
+			kind,
+		};
+		let no_std = package.features.contains_key("std");
+		if no_std && !package.features.get("default").is_some_and(|d| d.iter().any(|f| f == "std"))
+		{
+			issues.push(issue(line(&["features", "default"]), FeatureIssueKind::MissingDefaultStd));
+		}
+		for dependency in package.dependencies.iter().filter(|d| d.kind.is_none()) {
+			let features = features_of(&dependency.name);
+			let defines = |feature: &str| features.iter().any(|f| f.contains_key(feature));
+			for feature in AUDITED_FEATURES {
+				let Some(enabled) = package.features.get(feature) else { continue };
+				if !defines(feature) {
+					continue;
+				}
+				let key = dependency.key();
+				let propagated = enabled.iter().any(|entry| {
+					entry == &format!("{key}/{feature}") || entry == &format!("{key}?/{feature}")
+				});
+				if !propagated {
+					issues.push(issue(
+						line(&["features", feature]),
+						FeatureIssueKind::Unpropagated {
+							feature: feature.to_string(),
+							dependency: key.to_string(),
+							optional: dependency.optional,
+						},
+					));
+				}
+			}
+			let std_by_default = features.iter().any(|f| {
+				f.contains_key("std") &&
+					f.get("default").is_some_and(|d| d.iter().any(|f| f == "std"))
+			});
+			if no_std && dependency.uses_default_features && std_by_default {
+				issues.push(issue(
+					line(&["dependencies", dependency.key()]),
+					FeatureIssueKind::DefaultFeatures { dependency: dependency.key().to_string() },
+				));
+			}
+		}
+	}
+	Ok(issues)
+}
+
+/// Parses a manifest for editing.
+fn parse(manifest: &Path) -> Result<DocumentMut, Error> {
+	read_to_string(manifest)?
+		.parse()
+		.map_err(|e: toml_edit::TomlError| Error::TomlError(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::create_dir_all;
+
+	fn package(dir: &Path, name: &str, manifest: &str) -> Result<()> {
+		let path = dir.join(name);
+		create_dir_all(path.join("src"))?;
+		write(path.join("Cargo.toml"), manifest)?;
+		write(path.join("src/lib.rs"), "")?;
+		Ok(())
+	}
+
+	#[test]
+	fn audit_features_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let workspace = temp_dir.path();
+		write(
+			workspace.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"pallet\", \"runtime\"]\nresolver = \"2\"\n\n[workspace.dependencies]\npallet = { path = \"pallet\" }\n",
+		)?;
+		package(
+			workspace,
+			"pallet",
+			"[package]\nname = \"pallet\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[features]\ndefault = [\"std\"]\nstd = []\nruntime-benchmarks = []\ntry-runtime = []\n",
+		)?;
+		package(
+			workspace,
+			"runtime",
+			"[package]\nname = \"runtime\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\npallet = { workspace = true }\n\n[features]\nstd = []\nruntime-benchmarks = [\"pallet/runtime-benchmarks\"]\n",
+		)?;
+
+		let issues = audit_features(workspace)?;
+		let manifest = workspace.join("runtime/Cargo.toml").canonicalize()?;
+		assert_eq!(
+			issues.iter().map(|i| (&i.kind, i.line)).collect::<Vec<_>>(),
+			[
+				(&FeatureIssueKind::MissingDefaultStd, None),
+				(
+					&FeatureIssueKind::Unpropagated {
+						feature: "std".into(),
+						dependency: "pallet".into(),
+						optional: false
+					},
+					Some(10)
+				),
+				(&FeatureIssueKind::DefaultFeatures { dependency: "pallet".into() }, Some(7)),
+			]
+		);
+		assert_eq!(
+			issues[1].to_string(),
+			format!(
+				"{}:10: the `std` feature of `runtime` does not enable \"pallet/std\"",
+				manifest.display()
+			)
+		);
+
+		for issue in &issues {
+			issue.fix()?;
+		}
+		assert!(audit_features(workspace)?.is_empty());
+		let root = read_to_string(workspace.join("Cargo.toml"))?;
+		assert!(root.contains("pallet = { path = \"pallet\", default-features = false }"));
+		let runtime = read_to_string(&manifest)?;
+		assert!(runtime.contains("std = [\"pallet/std\"]"));
+		assert!(runtime.contains("default = [\"std\"]"));
+		Ok(())
+	}
+}
@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{fetch_storage, FeePayment},
+	errors::Error,
+};
+use pop_common::EncodedAssetId;
+use scale_info::{PortableRegistry, TypeDef};
+use std::{fmt, str::FromStr};
+use subxt::{
+	dynamic::Value, ext::scale_encode::EncodeAsType, Metadata, OnlineClient, SubstrateConfig,
+};
+
+/// The signed extension via which the fees of a transaction are paid in an asset.
+pub(crate) const CHARGE_ASSET_TX_PAYMENT: &str = "ChargeAssetTxPayment";
+
+/// The XCM location of an asset, relative to the chain, specified as its parents followed by its
+/// junctions (e.g. `1/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)`).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AssetLocation {
+	/// The number of parent consensus systems.
+	pub parents: u8,
+	/// The junctions within the interior of the location.
+	pub junctions: Vec<Junction>,
+}
+
+/// A junction of the interior of an asset location.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Junction {
+	/// A parachain, identified by its id.
+	Parachain(u32),
+	/// A pallet, identified by its index within the runtime.
+	PalletInstance(u8),
+	/// An index, such as the identifier of an asset within a pallet.
+	GeneralIndex(u128),
+}
+
+impl AssetLocation {
+	/// The location as the XCM location type of a chain.
+	///
+	/// # Arguments
+	/// * `types` - The types of the chain.
+	/// * `ty` - The location type.
+	fn value(&self, types: &PortableRegistry, ty: u32) -> Result<Value, Error> {
+		let junctions: Vec<_> = self
+			.junctions
+			.iter()
+			.map(|junction| match *junction {
+				Junction::Parachain(id) =>
+					Value::unnamed_variant("Parachain", [Value::u128(id as u128)]),
+				Junction::PalletInstance(index) =>
+					Value::unnamed_variant("PalletInstance", [Value::u128(index as u128)]),
+				Junction::GeneralIndex(index) =>
+					Value::unnamed_variant("GeneralIndex", [Value::u128(index)]),
+			})
+			.collect();
+		let interior = match junctions.len() {
+			0 => Value::unnamed_variant("Here", []),
+			len => {
+				let name = format!("X{len}");
+				// Junctions are either a tuple of junctions (XCM v3) or an array of junctions (XCM
+				// v4).
+				let fields = field_type(types, ty, "interior")
+					.and_then(|interior| match &types.resolve(interior)?.type_def {
+						TypeDef::Variant(variant) =>
+							variant.variants.iter().find(|v| v.name == name).map(|v| v.fields.len()),
+						_ => None,
+					})
+					.ok_or_else(|| Error::Config("unsupported location type of assets".into()))?;
+				match fields {
+					1 => Value::unnamed_variant(name, [Value::unnamed_composite(junctions)]),
+					_ => Value::unnamed_variant(name, junctions),
+				}
+			},
+		};
+		Ok(Value::named_composite([
+			("parents", Value::u128(self.parents as u128)),
+			("interior", interior),
+		]))
+	}
+}
+
+impl fmt::Display for AssetLocation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.parents)?;
+		for junction in &self.junctions {
+			match junction {
+				Junction::Parachain(id) => write!(f, "/Parachain({id})")?,
+				Junction::PalletInstance(index) => write!(f, "/PalletInstance({index})")?,
+				Junction::GeneralIndex(index) => write!(f, "/GeneralIndex({index})")?,
+			}
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for AssetLocation {
+	type Err = Error;
+
+	/// Parses a location, specified as its parents followed by its junctions (e.g.
+	/// `1/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)`).
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || {
+			Error::Config(format!(
+				"invalid asset location `{s}`: expected `<parents>` followed by `/Parachain(<id>)`, `/PalletInstance(<index>)` or `/GeneralIndex(<index>)` junctions"
+			))
+		};
+		let mut parts = s.split('/');
+		let parents = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+		let junctions = parts
+			.map(|junction| {
+				let (name, value) = junction
+					.strip_suffix(')')
+					.and_then(|j| j.split_once('('))
+					.ok_or_else(invalid)?;
+				match name {
+					"Parachain" => value.parse().map(Junction::Parachain).ok(),
+					"PalletInstance" => value.parse().map(Junction::PalletInstance).ok(),
+					"GeneralIndex" => value.parse().map(Junction::GeneralIndex).ok(),
+					_ => None,
+				}
+				.ok_or_else(invalid)
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self { parents, junctions })
+	}
+}
+
+/// Resolves the asset in which the fees of transactions are paid, if any, encoded as the asset
+/// identifier of the `ChargeAssetTxPayment` signed extension of the chain. An asset identified by
+/// its id is encoded as either the id itself (e.g. `pallet-asset-tx-payment`), or the XCM location
+/// of the asset within the `Assets` pallet, as on asset hubs (e.g.
+/// `pallet-asset-conversion-tx-payment`). An asset identified by its location requires the chain
+/// to identify assets by location. Where fees are converted via `pallet-asset-conversion`, checks
+/// that a pool of the asset and the native currency exists.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `fee_payment` - How the fees of transactions are paid.
+pub(crate) async fn fee_asset(
+	client: &OnlineClient<SubstrateConfig>,
+	fee_payment: &FeePayment,
+) -> Result<Option<EncodedAssetId>, Error> {
+	let metadata = client.metadata();
+	let (asset, (id, ty)) = match fee_payment {
+		FeePayment::Asset(asset) => (asset.to_string(), asset_id(&metadata, *asset)?),
+		FeePayment::Location(location) => (location.to_string(), location_id(&metadata, location)?),
+		FeePayment::Native | FeePayment::Proxy(_) => return Ok(None),
+	};
+	if metadata.pallet_by_name("AssetConversion").is_some() && is_location(metadata.types(), ty) {
+		let mut pool = None;
+		for native in [native_location(1), native_location(0)] {
+			let key = Value::unnamed_composite([native, id.clone()]);
+			pool = fetch_storage(client, "AssetConversion", "Pools", vec![key]).await?;
+			if pool.is_some() {
+				break;
+			}
+		}
+		if pool.is_none() {
+			return Err(Error::UnsupportedCommand(format!(
+				"no asset conversion pool of asset {asset} and the native currency exists, so fees cannot be paid in the asset"
+			)));
+		}
+	}
+	let encoded = id
+		.encode_as_type(ty, metadata.types())
+		.map_err(|e| Error::Config(format!("unable to encode asset {asset}: {e}")))?;
+	Ok(Some(EncodedAssetId(encoded)))
+}
+
+/// The identifier of an asset as the asset identifier type of the `ChargeAssetTxPayment` signed
+/// extension of a chain, alongside the type.
+fn asset_id(metadata: &Metadata, asset: u32) -> Result<(Value, u32), Error> {
+	let types = metadata.types();
+	let ty = asset_id_type(metadata)?;
+	if !is_location(types, ty) {
+		return Ok((Value::u128(asset as u128), ty));
+	}
+	let pallet = metadata.pallet_by_name("Assets").ok_or_else(|| {
+		Error::UnsupportedCommand(
+			"the chain identifies assets by location, but does not include the `Assets` pallet"
+				.into(),
+		)
+	})?;
+	Ok((asset_location(types, ty, pallet.index(), asset)?, ty))
+}
+
+/// The location of an asset as the asset identifier type of the `ChargeAssetTxPayment` signed
+/// extension of a chain, alongside the type.
+fn location_id(metadata: &Metadata, location: &AssetLocation) -> Result<(Value, u32), Error> {
+	let ty = asset_id_type(metadata)?;
+	if !is_location(metadata.types(), ty) {
+		return Err(Error::UnsupportedCommand(format!(
+			"the chain identifies assets by id, so fees cannot be paid in the asset at location {location}"
+		)));
+	}
+	Ok((location.value(metadata.types(), ty)?, ty))
+}
+
+/// The asset identifier type of the `ChargeAssetTxPayment` signed extension of a chain.
+fn asset_id_type(metadata: &Metadata) -> Result<u32, Error> {
+	let types = metadata.types();
+	metadata
+		.extrinsic()
+		.signed_extensions()
+		.iter()
+		.find(|e| e.identifier() == CHARGE_ASSET_TX_PAYMENT)
+		.and_then(|e| option_param(types, field_type(types, e.extra_ty(), "asset_id")?))
+		.ok_or_else(|| {
+			Error::UnsupportedCommand(format!(
+				"the chain does not pay fees in assets via `{CHARGE_ASSET_TX_PAYMENT}`"
+			))
+		})
+}
+
+/// The XCM location of an asset within the `Assets` pallet, relative to the chain.
+fn asset_location(
+	types: &PortableRegistry,
+	ty: u32,
+	pallet: u8,
+	asset: u32,
+) -> Result<Value, Error> {
+	let junctions = vec![Junction::PalletInstance(pallet), Junction::GeneralIndex(asset as u128)];
+	AssetLocation { parents: 0, junctions }.value(types, ty)
+}
+
+/// The XCM location of the native currency, relative to the chain: the relay chain (`parents`
+/// of one) on asset hubs, otherwise the chain itself.
+fn native_location(parents: u8) -> Value {
+	Value::named_composite([
+		("parents", Value::u128(parents as u128)),
+		("interior", Value::unnamed_variant("Here", [])),
+	])
+}
+
+/// Whether a type is an XCM location, consisting of `parents` and `interior`.
+fn is_location(types: &PortableRegistry, ty: u32) -> bool {
+	field_type(types, ty, "parents").is_some() && field_type(types, ty, "interior").is_some()
+}
+
+/// The type of the named field of a composite type.
+fn field_type(types: &PortableRegistry, ty: u32, name: &str) -> Option<u32> {
+	match &types.resolve(ty)?.type_def {
+		TypeDef::Composite(composite) => composite
+			.fields
+			.iter()
+			.find(|f| f.name.as_deref() == Some(name))
+			.map(|f| f.ty.id),
+		_ => None,
+	}
+}
+
+/// The type wrapped by an `Option` type.
+fn option_param(types: &PortableRegistry, ty: u32) -> Option<u32> {
+	match &types.resolve(ty)?.type_def {
+		TypeDef::Variant(variant) => variant
+			.variants
+			.iter()
+			.find(|v| v.name == "Some")
+			.and_then(|v| v.fields.first())
+			.map(|f| f.ty.id),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, Registry, TypeInfo};
+	use subxt::ext::codec::Encode;
+
+	#[derive(Encode, TypeInfo)]
+	#[codec(crate = subxt::ext::codec)]
+	struct ChargeAssetTxPayment<AssetId> {
+		#[codec(compact)]
+		tip: u128,
+		asset_id: Option<AssetId>,
+	}
+
+	#[derive(Encode, TypeInfo)]
+	#[codec(crate = subxt::ext::codec)]
+	struct Location {
+		parents: u8,
+		interior: Junctions,
+	}
+
+	#[allow(dead_code)]
+	#[derive(Encode, TypeInfo)]
+	#[codec(crate = subxt::ext::codec)]
+	enum Junctions {
+		Here,
+		X1([Junction; 1]),
+		X2([Junction; 2]),
+		X3([Junction; 3]),
+	}
+
+	#[allow(dead_code)]
+	#[derive(Encode, TypeInfo)]
+	#[codec(crate = subxt::ext::codec)]
+	enum Junction {
+		Parachain(#[codec(compact)] u32),
+		PalletInstance(u8),
+		GeneralIndex(#[codec(compact)] u128),
+	}
+
+	fn registry<T: TypeInfo + 'static>() -> (PortableRegistry, u32) {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<T>()).id;
+		(registry.into(), ty)
+	}
+
+	#[test]
+	fn asset_location_works() -> Result<(), Error> {
+		let (types, extension) = registry::<ChargeAssetTxPayment<Location>>();
+		let ty = option_param(&types, field_type(&types, extension, "asset_id").unwrap()).unwrap();
+		assert!(is_location(&types, ty));
+		let location = asset_location(&types, ty, 50, 1984)?;
+		let expected = Location {
+			parents: 0,
+			interior: Junctions::X2([Junction::PalletInstance(50), Junction::GeneralIndex(1984)]),
+		};
+		assert_eq!(location.encode_as_type(ty, &types).unwrap(), expected.encode());
+		let native = native_location(1).encode_as_type(ty, &types).unwrap();
+		assert_eq!(native, Location { parents: 1, interior: Junctions::Here }.encode());
+		Ok(())
+	}
+
+	#[test]
+	fn identifies_assets_by_id() {
+		let (types, extension) = registry::<ChargeAssetTxPayment<u32>>();
+		let ty = option_param(&types, field_type(&types, extension, "asset_id").unwrap()).unwrap();
+		assert!(!is_location(&types, ty));
+		assert_eq!(Value::u128(1984).encode_as_type(ty, &types).unwrap(), 1984u32.encode());
+		assert!(asset_location(&types, ty, 50, 1984).is_err());
+	}
+
+	#[test]
+	fn parsing_asset_location_works() -> Result<(), Error> {
+		let location = "1/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)";
+		let parsed: AssetLocation = location.parse()?;
+		assert_eq!(
+			parsed,
+			AssetLocation {
+				parents: 1,
+				junctions: vec![
+					super::Junction::Parachain(1000),
+					super::Junction::PalletInstance(50),
+					super::Junction::GeneralIndex(1984)
+				],
+			}
+		);
+		assert_eq!(parsed.to_string(), location);
+		assert_eq!("1".parse::<AssetLocation>()?, AssetLocation { parents: 1, junctions: vec![] });
+		for invalid in ["", "here", "1/", "1/Parachain", "1/Parachain(id)", "1/AccountId32(1)"] {
+			assert!(matches!(invalid.parse::<AssetLocation>(), Err(Error::Config(_))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn encoding_asset_location_works() -> Result<(), Error> {
+		let (types, extension) = registry::<ChargeAssetTxPayment<Location>>();
+		let ty = option_param(&types, field_type(&types, extension, "asset_id").unwrap()).unwrap();
+		let location: AssetLocation =
+			"1/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)".parse()?;
+		let expected = Location {
+			parents: 1,
+			interior: Junctions::X3([
+				Junction::Parachain(1000),
+				Junction::PalletInstance(50),
+				Junction::GeneralIndex(1984),
+			]),
+		};
+		assert_eq!(
+			location.value(&types, ty)?.encode_as_type(ty, &types).unwrap(),
+			expected.encode()
+		);
+		let native = AssetLocation { parents: 1, junctions: vec![] }.value(&types, ty)?;
+		let expected = Location { parents: 1, interior: Junctions::Here };
+		assert_eq!(native.encode_as_type(ty, &types).unwrap(), expected.encode());
+		Ok(())
+	}
+}
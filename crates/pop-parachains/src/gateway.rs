@@ -0,0 +1,289 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	generator::parachain::{RpcGateway as Config, RpcWrapper},
+	Error,
+};
+use askama::Template;
+use duct::cmd;
+use rand::RngCore;
+use std::{
+	fs::{create_dir_all, read_to_string, set_permissions, write, Permissions},
+	os::unix::fs::PermissionsExt,
+	path::{absolute, Path, PathBuf},
+};
+
+/// The configuration of the gateway, to be included within the `http` block of nginx.
+const CONFIG: &str = "nginx.conf";
+/// The bearer tokens accepted by the gateway, as rewritten whenever credentials are rotated.
+const AUTH: &str = "auth.conf";
+/// The bearer token with which clients authenticate.
+const TOKEN: &str = "token";
+/// The certificate with which the gateway serves HTTPS.
+const CERTIFICATE: &str = "cert.pem";
+/// The private key of the certificate.
+const KEY: &str = "key.pem";
+/// The number of days for which self-signed certificates are valid.
+const CERTIFICATE_DAYS: u32 = 30;
+
+/// How the RPC endpoints of collators are secured when exposed publicly: restrictions enforced by
+/// the collators themselves, as well as by a gateway (an nginx reverse proxy) terminating TLS and
+/// authenticating clients in front of them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcSecurity {
+	/// The domain at which the gateway is served.
+	pub domain: String,
+	/// The port on which the gateway listens.
+	pub port: u16,
+	/// The origins permitted to access the RPC endpoints (e.g. `https://polkadot.js.org`).
+	pub cors: Vec<String>,
+	/// The maximum number of calls per minute, per connection.
+	pub rate_limit: Option<u32>,
+	/// The maximum number of concurrent connections to each collator.
+	pub max_connections: Option<u32>,
+}
+
+impl Default for RpcSecurity {
+	fn default() -> Self {
+		Self {
+			domain: "localhost".to_string(),
+			port: 8443,
+			cors: vec![],
+			rate_limit: None,
+			max_connections: None,
+		}
+	}
+}
+
+impl RpcSecurity {
+	/// The arguments with which collators are launched, so that any limits are enforced by the
+	/// collators themselves. The remaining restrictions are enforced by a wrapper, as zombienet
+	/// replaces the arguments specifying which methods and origins are permitted.
+	pub fn node_args(&self) -> Vec<String> {
+		let mut args = vec![];
+		if let Some(rate_limit) = self.rate_limit {
+			args.push(format!("--rpc-rate-limit={rate_limit}"));
+		}
+		if let Some(max_connections) = self.max_connections {
+			args.push(format!("--rpc-max-connections={max_connections}"));
+		}
+		args
+	}
+
+	/// The arguments with which the wrapper launches collators, so that only safe methods are
+	/// exposed, to the permitted origins.
+	fn wrapper_args(&self) -> Vec<String> {
+		let mut args = vec!["--rpc-methods=safe".to_string()];
+		if !self.cors.is_empty() {
+			args.push(format!("--rpc-cors={}", self.cors.join(",")));
+		}
+		args
+	}
+
+	/// Generates a wrapper through which the collators of a parachain are launched, replacing the
+	/// RPC arguments added by zombienet so that collators only expose safe methods, locally.
+	///
+	/// # Arguments
+	/// * `dir` - The directory within which the wrapper is generated.
+	/// * `id` - The identifier of the parachain.
+	/// * `binary` - The binary of the collators.
+	pub fn wrap(&self, dir: &Path, id: u32, binary: &Path) -> Result<PathBuf, Error> {
+		create_dir_all(dir)?;
+		let wrapper = RpcWrapper {
+			binary: quote(&absolute(binary)?.display().to_string()),
+			args: self.wrapper_args().iter().map(|arg| quote(arg)).collect(),
+		};
+		let path = dir.canonicalize()?.join(format!("collator-{id}.sh"));
+		write(&path, wrapper.render().expect("infallible"))?;
+		set_permissions(&path, Permissions::from_mode(0o755))?;
+		Ok(path)
+	}
+
+	/// Generates the configuration of a gateway in front of the RPC endpoints of collators,
+	/// including a self-signed certificate for local HTTPS testing. Any credentials previously
+	/// generated within the directory are retained, so that clients remain authenticated.
+	///
+	/// # Arguments
+	/// * `dir` - The directory within which the configuration is generated.
+	/// * `upstreams` - The local ports of the RPC endpoints of the collators.
+	pub fn generate(&self, dir: &Path, upstreams: &[u16]) -> Result<Gateway, Error> {
+		if upstreams.is_empty() {
+			return Err(Error::Config("no RPC endpoints to be secured by the gateway".into()));
+		}
+		create_dir_all(dir)?;
+		let dir = dir.canonicalize()?;
+		let config = Config {
+			dir: dir.display().to_string(),
+			domain: self.domain.clone(),
+			port: self.port,
+			upstreams: upstreams.to_vec(),
+			rate_limit: self.rate_limit,
+			// Allow bursts of up to a second's worth of calls.
+			burst: self.rate_limit.map_or(0, |limit| limit.div_ceil(60).max(1)),
+		};
+		write(dir.join(CONFIG), config.render().expect("infallible"))?;
+		let gateway = Gateway { dir };
+		match gateway.token() {
+			Ok(_) if gateway.certificate().exists() => Ok(gateway),
+			_ => self.rotate(&gateway.dir),
+		}
+	}
+
+	/// Rotates the credentials of a gateway previously generated, replacing its bearer token and
+	/// certificate so that any leaked credentials are revoked once nginx is reloaded.
+	///
+	/// # Arguments
+	/// * `dir` - The directory within which the gateway was generated.
+	pub fn rotate(&self, dir: &Path) -> Result<Gateway, Error> {
+		let gateway = Gateway { dir: dir.to_path_buf() };
+		if !gateway.config().exists() {
+			return Err(Error::Config(format!(
+				"no gateway has been generated in {}",
+				dir.display()
+			)));
+		}
+		let token = generate_token();
+		write(gateway.dir.join(AUTH), auth(&token))?;
+		let path = gateway.dir.join(TOKEN);
+		write(&path, &token)?;
+		set_permissions(&path, Permissions::from_mode(0o600))?;
+		generate_certificate(&gateway.dir, &self.domain)?;
+		Ok(gateway)
+	}
+}
+
+/// The configuration of a gateway securing the RPC endpoints of collators.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gateway {
+	dir: PathBuf,
+}
+
+impl Gateway {
+	/// The configuration of nginx, to be included within its `http` block.
+	pub fn config(&self) -> PathBuf {
+		self.dir.join(CONFIG)
+	}
+
+	/// The self-signed certificate served by the gateway.
+	pub fn certificate(&self) -> PathBuf {
+		self.dir.join(CERTIFICATE)
+	}
+
+	/// The bearer token with which clients authenticate, either via the `Authorization` header or
+	/// the `token` query parameter (e.g. for browsers, which cannot set headers on websockets).
+	pub fn token(&self) -> Result<String, Error> {
+		Ok(read_to_string(self.dir.join(TOKEN))?.trim().to_string())
+	}
+}
+
+/// Quotes an argument for use within a shell script.
+fn quote(arg: &str) -> String {
+	format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Generates a random bearer token.
+fn generate_token() -> String {
+	let mut bytes = [0u8; 32];
+	rand::thread_rng().fill_bytes(&mut bytes);
+	bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// The configuration of nginx authenticating clients with a bearer token.
+fn auth(token: &str) -> String {
+	format!(
+		"map $http_authorization $bearer_authorized {{\n\tdefault 0;\n\t\"Bearer {token}\" 1;\n}}\n\nmap $arg_token $authorized {{\n\tdefault $bearer_authorized;\n\t\"{token}\" 1;\n}}\n"
+	)
+}
+
+/// Generates a self-signed certificate for a domain using `openssl`, for local HTTPS testing.
+fn generate_certificate(dir: &Path, domain: &str) -> Result<(), Error> {
+	let subject = format!("/CN={domain}");
+	let names = format!("subjectAltName=DNS:{domain},DNS:localhost,IP:127.0.0.1");
+	let days = CERTIFICATE_DAYS.to_string();
+	cmd(
+		"openssl",
+		[
+			"req",
+			"-x509",
+			"-newkey",
+			"rsa:2048",
+			"-nodes",
+			"-keyout",
+			KEY,
+			"-out",
+			CERTIFICATE,
+			"-days",
+			&days,
+			"-subj",
+			&subject,
+			"-addext",
+			&names,
+		],
+	)
+	.dir(dir)
+	.stdout_null()
+	.stderr_capture()
+	.run()
+	.map_err(|e| {
+		Error::Config(format!("unable to generate a self-signed certificate with `openssl`: {e}"))
+	})?;
+	set_permissions(dir.join(KEY), Permissions::from_mode(0o600))?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn node_args_works() {
+		assert!(RpcSecurity::default().node_args().is_empty());
+		let security = RpcSecurity {
+			cors: vec!["https://polkadot.js.org".into(), "http://localhost:3000".into()],
+			rate_limit: Some(600),
+			max_connections: Some(100),
+			..Default::default()
+		};
+		assert_eq!(security.node_args(), ["--rpc-rate-limit=600", "--rpc-max-connections=100"]);
+		assert_eq!(
+			security.wrapper_args(),
+			["--rpc-methods=safe", "--rpc-cors=https://polkadot.js.org,http://localhost:3000"]
+		);
+	}
+
+	#[test]
+	fn quote_works() {
+		assert_eq!(quote("--rpc-cors=all"), "'--rpc-cors=all'");
+		assert_eq!(quote("it's"), r"'it'\''s'");
+	}
+
+	#[test]
+	fn generate_works() -> Result<()> {
+		if cmd("openssl", ["version"]).stdout_null().run().is_err() {
+			return Ok(());
+		}
+		let temp_dir = tempfile::tempdir()?;
+		let security = RpcSecurity { rate_limit: Some(600), ..Default::default() };
+		assert!(matches!(security.generate(temp_dir.path(), &[]), Err(Error::Config(_))));
+		assert!(matches!(security.rotate(temp_dir.path()), Err(Error::Config(_))));
+
+		let gateway = security.generate(temp_dir.path(), &[9944, 9945])?;
+		let config = read_to_string(gateway.config())?;
+		assert!(config.contains("\tserver 127.0.0.1:9944;\n\tserver 127.0.0.1:9945;\n"));
+		assert!(config.contains("zone=rpc:10m rate=600r/m;"));
+		assert!(config.contains("limit_req zone=rpc burst=10 nodelay;"));
+		assert!(config.contains("listen 8443 ssl;"));
+		assert!(gateway.certificate().exists());
+		let token = gateway.token()?;
+		assert_eq!(token.len(), 64);
+		assert!(read_to_string(gateway.dir.join(AUTH))?.contains(&format!("\"Bearer {token}\" 1;")));
+
+		// Credentials are retained when regenerated, but replaced when rotated.
+		assert_eq!(security.generate(temp_dir.path(), &[9944])?.token()?, token);
+		let rotated = security.rotate(&gateway.dir)?.token()?;
+		assert_ne!(rotated, token);
+		assert!(!read_to_string(gateway.dir.join(AUTH))?.contains(&token));
+		Ok(())
+	}
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::indexer::Entity;
+use askama::Template;
+
+#[derive(Template)]
+#[template(path = "indexer/schema.graphql.templ", escape = "none")]
+pub(crate) struct Schema<'a> {
+	pub(crate) spec_name: &'a str,
+	pub(crate) entities: &'a [Entity],
+}
+
+#[derive(Template)]
+#[template(path = "indexer/tsconfig.json.templ", escape = "none")]
+pub(crate) struct TsConfig<'a> {
+	pub(crate) out_dir: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "indexer/subquery/package.json.templ", escape = "none")]
+pub(crate) struct SubQueryPackage<'a> {
+	pub(crate) name: &'a str,
+	pub(crate) spec_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "indexer/subquery/project.ts.templ", escape = "none")]
+pub(crate) struct SubQueryProject<'a> {
+	pub(crate) name: &'a str,
+	pub(crate) spec_name: &'a str,
+	pub(crate) endpoint: &'a str,
+	pub(crate) genesis_hash: &'a str,
+	pub(crate) entities: &'a [Entity],
+}
+
+#[derive(Template)]
+#[template(path = "indexer/subquery/mappings.ts.templ", escape = "none")]
+pub(crate) struct SubQueryMappings<'a> {
+	pub(crate) entities: &'a [Entity],
+}
+
+#[derive(Template)]
+#[template(path = "indexer/squid/package.json.templ", escape = "none")]
+pub(crate) struct SquidPackage<'a> {
+	pub(crate) name: &'a str,
+	pub(crate) spec_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "indexer/squid/squid.yaml.templ", escape = "none")]
+pub(crate) struct SquidManifest<'a> {
+	pub(crate) name: &'a str,
+	pub(crate) spec_name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "indexer/squid/main.ts.templ", escape = "none")]
+pub(crate) struct SquidProcessor<'a> {
+	pub(crate) endpoint: &'a str,
+	pub(crate) entities: &'a [Entity],
+}
@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: GPL-3.0
 
+pub mod indexer;
 pub mod pallet;
 pub mod parachain;
@@ -15,3 +15,65 @@ pub(crate) struct ChainSpec {
 pub(crate) struct Network {
 	pub(crate) node: String,
 }
+
+#[derive(Template)]
+#[template(path = "base/workspace_network.templ", escape = "none")]
+pub(crate) struct WorkspaceNetwork<'a> {
+	pub(crate) file: &'a str,
+	pub(crate) relay_chain: &'a str,
+	pub(crate) validators: Vec<String>,
+	pub(crate) parachains: Vec<WorkspaceParachain>,
+}
+
+pub(crate) struct WorkspaceParachain {
+	pub(crate) id: u32,
+	pub(crate) command: String,
+	pub(crate) collator: String,
+}
+
+#[derive(Template)]
+#[template(path = "base/rpc_gateway.templ", escape = "none")]
+pub(crate) struct RpcGateway {
+	pub(crate) dir: String,
+	pub(crate) domain: String,
+	pub(crate) port: u16,
+	pub(crate) upstreams: Vec<u16>,
+	pub(crate) rate_limit: Option<u32>,
+	pub(crate) burst: u32,
+}
+
+#[derive(Template)]
+#[template(path = "base/rpc_wrapper.templ", escape = "none")]
+pub(crate) struct RpcWrapper {
+	pub(crate) binary: String,
+	pub(crate) args: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "presets/block_time.templ", escape = "none")]
+pub(crate) struct BlockTime {
+	pub(crate) name: String,
+	pub(crate) polkadot_sdk: bool,
+	pub(crate) millisecs_per_block: u64,
+	pub(crate) max_block_ref_time: String,
+	pub(crate) max_block_ref_time_expr: String,
+	pub(crate) unincluded_segment_capacity: u32,
+}
+
+#[derive(Template)]
+#[template(path = "presets/xcm_topology.templ", escape = "none")]
+pub(crate) struct XcmTopology {
+	pub(crate) name: String,
+	pub(crate) polkadot_sdk: bool,
+	pub(crate) description: String,
+	pub(crate) asset_hub: bool,
+	pub(crate) asset_hub_para_id: u32,
+}
+
+#[derive(Template)]
+#[template(path = "presets/proxy_multisig.templ", escape = "none")]
+pub(crate) struct ProxyMultisig {
+	pub(crate) name: String,
+	pub(crate) polkadot_sdk: bool,
+	pub(crate) codec: String,
+}
@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{construct_sudo_extrinsic, fetch_storage, find_event_field, sign_and_submit_extrinsic},
+	errors::Error,
+	registrar::dispatch_result,
+};
+use pop_common::i18n::message;
+use subxt::{
+	dynamic::Value, ext::scale_value::ValueDef, tx::DynamicPayload, OnlineClient, SubstrateConfig,
+};
+
+/// The number of open inbound and outbound HRMP channels of a parachain.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HrmpChannels {
+	/// The number of channels opened by other parachains to the parachain.
+	pub inbound: u32,
+	/// The number of channels opened by the parachain to other parachains.
+	pub outbound: u32,
+}
+
+impl HrmpChannels {
+	/// Whether the parachain has any open channels.
+	pub fn is_empty(&self) -> bool {
+		self.inbound == 0 && self.outbound == 0
+	}
+}
+
+/// Queries the number of open HRMP channels of a parachain on the relay chain.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the relay chain.
+/// * `para_id` - The parachain identifier.
+pub async fn query_hrmp_channels(
+	client: &OnlineClient<SubstrateConfig>,
+	para_id: u32,
+) -> Result<HrmpChannels, Error> {
+	Ok(HrmpChannels {
+		inbound: count_channels(client, "HrmpIngressChannelsIndex", para_id).await?,
+		outbound: count_channels(client, "HrmpEgressChannelsIndex", para_id).await?,
+	})
+}
+
+/// Counts the channels within an HRMP channel index of a parachain.
+async fn count_channels(
+	client: &OnlineClient<SubstrateConfig>,
+	index: &str,
+	para_id: u32,
+) -> Result<u32, Error> {
+	let channels = fetch_storage(client, "Hrmp", index, vec![Value::u128(para_id as u128)]).await?;
+	Ok(match channels.map(|channels| channels.value) {
+		Some(ValueDef::Composite(channels)) => channels.len() as u32,
+		_ => 0,
+	})
+}
+
+/// An HRMP channel between two parachains.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HrmpChannel {
+	/// The identifier of the parachain sending messages over the channel.
+	pub sender: u32,
+	/// The identifier of the parachain receiving messages over the channel.
+	pub recipient: u32,
+	/// The maximum number of messages which can be queued within the channel.
+	pub max_capacity: u32,
+	/// The maximum size of a message sent over the channel.
+	pub max_message_size: u32,
+}
+
+impl HrmpChannel {
+	/// The default maximum number of messages queued within a channel.
+	pub const DEFAULT_MAX_CAPACITY: u32 = 8;
+	/// The default maximum size of a message sent over a channel.
+	pub const DEFAULT_MAX_MESSAGE_SIZE: u32 = 512;
+
+	/// Whether the channel is open, or pending acceptance, on the relay chain.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the relay chain.
+	pub async fn exists(&self, client: &OnlineClient<SubstrateConfig>) -> Result<bool, Error> {
+		for entry in ["HrmpChannels", "HrmpOpenChannelRequests"] {
+			if fetch_storage(client, "Hrmp", entry, vec![self.id()]).await?.is_some() {
+				return Ok(true);
+			}
+		}
+		Ok(false)
+	}
+
+	/// The identifier of the channel, used as the key of its storage on the relay chain.
+	fn id(&self) -> Value {
+		Value::named_composite([
+			("sender", Value::u128(self.sender as u128)),
+			("recipient", Value::u128(self.recipient as u128)),
+		])
+	}
+}
+
+/// Constructs a call, requiring `Root` origin, which force opens an HRMP channel between two
+/// parachains.
+///
+/// # Arguments
+/// * `channel` - The channel to be opened.
+pub fn force_open_hrmp_channel(channel: &HrmpChannel) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Hrmp",
+		"force_open_hrmp_channel",
+		vec![
+			Value::u128(channel.sender as u128),
+			Value::u128(channel.recipient as u128),
+			Value::u128(channel.max_capacity as u128),
+			Value::u128(channel.max_message_size as u128),
+		],
+	)
+}
+
+/// Opens HRMP channels on a relay chain via its sudo key, skipping any which are already open or
+/// pending acceptance (e.g. those opened within its genesis). Channels become usable from the
+/// next session of the relay chain. Fails if the sudo key cannot open a channel (e.g. as the
+/// sender lacks the deposit required). Returns the channels opened.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the relay chain.
+/// * `channels` - The channels to be opened.
+/// * `suri` - The secret URI of the sudo key of the relay chain.
+pub async fn open_hrmp_channels(
+	client: &OnlineClient<SubstrateConfig>,
+	channels: &[HrmpChannel],
+	suri: &str,
+) -> Result<Vec<HrmpChannel>, Error> {
+	let mut opened = Vec::new();
+	for channel in channels {
+		if channel.exists(client).await? {
+			continue;
+		}
+		let call = construct_sudo_extrinsic(force_open_hrmp_channel(channel));
+		let events = sign_and_submit_extrinsic(client, &call, suri).await?;
+		if let Some(Err(e)) = find_event_field(&events, "Sudo", "Sudid", "sudo_result")?
+			.map(|result| dispatch_result(&result))
+		{
+			return Err(Error::ExtrinsicSubmissionError(message(
+				"hrmp.open_failed",
+				&[
+					("sender", &channel.sender.to_string()),
+					("recipient", &channel.recipient.to_string()),
+					("error", &e),
+				],
+			)));
+		}
+		opened.push(*channel);
+	}
+	Ok(opened)
+}
+
+/// Constructs a call, requiring `Root` origin, which force closes all HRMP channels of a
+/// parachain.
+///
+/// # Arguments
+/// * `para_id` - The parachain identifier.
+/// * `channels` - The number of open channels of the parachain, used as a weight witness.
+pub fn force_clean_hrmp(para_id: u32, channels: &HrmpChannels) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Hrmp",
+		"force_clean_hrmp",
+		vec![
+			Value::u128(para_id as u128),
+			Value::u128(channels.inbound as u128),
+			Value::u128(channels.outbound as u128),
+		],
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn force_clean_hrmp_works() {
+		let channels = HrmpChannels { inbound: 1, outbound: 2 };
+		assert!(!channels.is_empty());
+		assert!(HrmpChannels::default().is_empty());
+		assert_eq!(
+			force_clean_hrmp(2000, &channels).into_value(),
+			Value::unnamed_variant(
+				"Hrmp",
+				[Value::unnamed_variant(
+					"force_clean_hrmp",
+					[Value::u128(2000), Value::u128(1), Value::u128(2)]
+				)]
+			)
+		);
+	}
+
+	#[test]
+	fn force_open_hrmp_channel_works() {
+		let channel =
+			HrmpChannel { sender: 2000, recipient: 2001, max_capacity: 8, max_message_size: 512 };
+		assert_eq!(
+			channel.id(),
+			Value::named_composite([
+				("sender", Value::u128(2000)),
+				("recipient", Value::u128(2001))
+			])
+		);
+		assert_eq!(
+			force_open_hrmp_channel(&channel).into_value(),
+			Value::unnamed_variant(
+				"Hrmp",
+				[Value::unnamed_variant(
+					"force_open_hrmp_channel",
+					[Value::u128(2000), Value::u128(2001), Value::u128(8), Value::u128(512)]
+				)]
+			)
+		);
+	}
+}
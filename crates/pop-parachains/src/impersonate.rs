@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{call::set_up_client, errors::Error};
+use pop_common::parse_account;
+use sp_crypto_hashing::twox_128;
+use std::fmt::{Display, Formatter};
+use subxt::{
+	backend::rpc::{rpc_params, RpcClient},
+	blocks::ExtrinsicEvents,
+	tx::{Payload, Signer},
+	utils::{AccountId32, MultiAddress, MultiSignature, H256},
+	OnlineClient, SubstrateConfig,
+};
+
+/// The prefix of the signatures accepted by a fork for any account, when signature verification
+/// is mocked (i.e. `mock-signature-host: true` within the chopsticks configuration).
+const MOCK_SIGNATURE_PREFIX: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+/// The byte with which mocked signatures are padded.
+const MOCK_SIGNATURE_PADDING: u8 = 0xcd;
+/// The identifier of the treasury pallet, from which the account of the treasury is derived.
+const TREASURY_PALLET_ID: &[u8; 8] = b"py/trsry";
+/// An RPC method only exposed by forks (e.g. chopsticks).
+pub(crate) const FORK_RPC_METHOD: &str = "dev_newBlock";
+
+/// Signs calls on behalf of an arbitrary account of a forked network, without its key, using the
+/// mocked signatures accepted by the fork. Used to rehearse upgrade and governance scenarios
+/// against real state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Impersonator {
+	/// The account impersonated.
+	account: AccountId32,
+}
+
+impl Impersonator {
+	/// Impersonates an account.
+	///
+	/// # Arguments
+	/// * `account` - The account to be impersonated, either an SS58 address or a hex-encoded
+	///   32-byte account.
+	pub fn new(account: &str) -> Result<Self, Error> {
+		let account =
+			parse_account(account).map_err(|e| Error::InvalidAccount(format!("{account}: {e}")))?;
+		Ok(Self { account: AccountId32(account) })
+	}
+
+	/// Impersonates the account of the treasury.
+	pub fn treasury() -> Self {
+		let mut account = [0u8; 32];
+		account[..4].copy_from_slice(b"modl");
+		account[4..12].copy_from_slice(TREASURY_PALLET_ID);
+		Self { account: AccountId32(account) }
+	}
+
+	/// Impersonates the sudo key of the chain.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	pub async fn sudo(client: &OnlineClient<SubstrateConfig>) -> Result<Self, Error> {
+		let key = [twox_128(b"Sudo"), twox_128(b"Key")].concat();
+		let account = client
+			.storage()
+			.at_latest()
+			.await?
+			.fetch_raw(key)
+			.await?
+			.and_then(|account| <[u8; 32]>::try_from(account).ok())
+			.ok_or_else(|| Error::Config("the chain has no sudo key".into()))?;
+		Ok(Self { account: AccountId32(account) })
+	}
+
+	/// The account impersonated.
+	pub fn account(&self) -> &AccountId32 {
+		&self.account
+	}
+}
+
+impl Display for Impersonator {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.account)
+	}
+}
+
+impl Signer<SubstrateConfig> for Impersonator {
+	fn account_id(&self) -> AccountId32 {
+		self.account.clone()
+	}
+
+	fn address(&self) -> MultiAddress<AccountId32, u32> {
+		self.account.clone().into()
+	}
+
+	fn sign(&self, _signer_payload: &[u8]) -> MultiSignature {
+		MultiSignature::Sr25519(mock_signature())
+	}
+}
+
+/// A signature accepted by a fork for any account, when signature verification is mocked.
+fn mock_signature() -> [u8; 64] {
+	let mut signature = [MOCK_SIGNATURE_PADDING; 64];
+	signature[..4].copy_from_slice(&MOCK_SIGNATURE_PREFIX);
+	signature
+}
+
+/// Whether the chain is a fork (e.g. launched with chopsticks), on which accounts can be
+/// impersonated.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+pub async fn is_fork(url: &str) -> Result<bool, Error> {
+	let rpc = RpcClient::from_url(url)
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+	let methods: serde_json::Value = rpc.request("rpc_methods", rpc_params![]).await?;
+	Ok(methods
+		.get("methods")
+		.and_then(|m| m.as_array())
+		.is_some_and(|m| m.iter().any(|m| m.as_str() == Some(FORK_RPC_METHOD))))
+}
+
+/// Submits a call on behalf of an impersonated account of a fork, waiting for it to be finalized
+/// successfully. Returns the hash of the extrinsic.
+///
+/// # Arguments
+/// * `url` - Endpoint of a node of the fork, whose signature verification is mocked.
+/// * `call` - The call to be submitted.
+/// * `impersonator` - The account impersonated.
+pub async fn submit_impersonated(
+	url: &str,
+	call: &impl Payload,
+	impersonator: &Impersonator,
+) -> Result<H256, Error> {
+	if !is_fork(url).await? {
+		return Err(Error::UnsupportedCommand(format!(
+			"accounts can only be impersonated on a fork, such as one launched with chopsticks: {url} does not expose `{FORK_RPC_METHOD}`"
+		)));
+	}
+	let client = set_up_client(url).await?;
+	Ok(submit_impersonated_with(&client, call, impersonator).await?.extrinsic_hash())
+}
+
+/// Submits a call on behalf of an impersonated account, without checking whether the chain is a
+/// fork, returning the events emitted by the extrinsic once finalized successfully.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the fork.
+/// * `call` - The call to be submitted.
+/// * `impersonator` - The account impersonated.
+pub(crate) async fn submit_impersonated_with(
+	client: &OnlineClient<SubstrateConfig>,
+	call: &impl Payload,
+	impersonator: &Impersonator,
+) -> Result<ExtrinsicEvents<SubstrateConfig>, Error> {
+	client
+		.tx()
+		.sign_and_submit_then_watch_default(call, impersonator)
+		.await
+		.map_err(|e| Error::ExtrinsicSubmissionError(format!("{e:?}")))?
+		.wait_for_finalized_success()
+		.await
+		.map_err(|e| Error::ExtrinsicSubmissionError(format!("{e:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn impersonator_works() -> Result<(), Error> {
+		let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+		let impersonator = Impersonator::new(alice)?;
+		assert_eq!(impersonator.to_string(), alice);
+		assert_eq!(Signer::<SubstrateConfig>::account_id(&impersonator), *impersonator.account());
+		assert!(matches!(Impersonator::new("0x1234"), Err(Error::InvalidAccount(_))));
+		let signature = mock_signature();
+		assert_eq!(&signature[..4], &[0xde, 0xad, 0xbe, 0xef]);
+		assert!(signature[4..].iter().all(|b| *b == 0xcd));
+		assert!(matches!(
+			Signer::<SubstrateConfig>::sign(&impersonator, &[1, 2, 3]),
+			MultiSignature::Sr25519(s) if s == signature
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn treasury_works() {
+		// The treasury account of Polkadot and Kusama.
+		assert_eq!(
+			Impersonator::treasury().to_string(),
+			"5EYCAe5ijiYfyeZ2JJCGq56LmPyNRAKzpG4QkoQkkQNB5e6Z"
+		);
+	}
+}
@@ -0,0 +1,504 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	errors::Error,
+	generator::indexer::{
+		Schema, SquidManifest, SquidPackage, SquidProcessor, SubQueryMappings, SubQueryPackage,
+		SubQueryProject, TsConfig,
+	},
+};
+use askama::DynTemplate;
+use clap::ValueEnum;
+use pop_common::{decode_hex, encode_hex};
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive, Variant};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::{
+	fs::{create_dir_all, write},
+	path::{Path, PathBuf},
+};
+use subxt::{
+	backend::rpc::{rpc_params, RpcClient},
+	ext::codec::Decode,
+	Metadata,
+};
+
+/// The framework of an indexer project.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum IndexerFramework {
+	/// A SubQuery project, indexing events via handlers declared within its manifest.
+	#[default]
+	#[value(name = "subquery")]
+	SubQuery,
+	/// A Subsquid (Squid SDK) project, indexing events via a batch processor.
+	Squid,
+}
+
+/// The type of a field of an entity, as declared within the GraphQL schema.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldType {
+	/// An integer of up to 32 bits.
+	Int,
+	/// An integer of more than 32 bits.
+	BigInt,
+	/// A boolean.
+	Boolean,
+	/// A string, including accounts and hashes.
+	String,
+	/// Any other value, stored as JSON.
+	Json,
+}
+
+impl FieldType {
+	/// The type as declared within a GraphQL schema.
+	pub fn graphql(&self) -> &'static str {
+		match self {
+			FieldType::Int => "Int",
+			FieldType::BigInt => "BigInt",
+			FieldType::Boolean => "Boolean",
+			FieldType::String => "String",
+			FieldType::Json => "JSON",
+		}
+	}
+
+	/// Resolves the type of a field from the type registry of a runtime.
+	///
+	/// # Arguments
+	/// * `types` - The type registry of the runtime.
+	/// * `id` - The identifier of the type of the field.
+	fn resolve(types: &PortableRegistry, id: u32) -> Self {
+		let Some(ty) = types.resolve(id) else {
+			return FieldType::Json;
+		};
+		match &ty.type_def {
+			TypeDef::Primitive(primitive) => match primitive {
+				TypeDefPrimitive::U8 |
+				TypeDefPrimitive::U16 |
+				TypeDefPrimitive::U32 |
+				TypeDefPrimitive::I8 |
+				TypeDefPrimitive::I16 |
+				TypeDefPrimitive::I32 => FieldType::Int,
+				TypeDefPrimitive::U64 |
+				TypeDefPrimitive::U128 |
+				TypeDefPrimitive::U256 |
+				TypeDefPrimitive::I64 |
+				TypeDefPrimitive::I128 |
+				TypeDefPrimitive::I256 => FieldType::BigInt,
+				TypeDefPrimitive::Bool => FieldType::Boolean,
+				TypeDefPrimitive::Str | TypeDefPrimitive::Char => FieldType::String,
+			},
+			TypeDef::Compact(compact) => Self::resolve(types, compact.type_param.id),
+			// Fixed-size byte arrays, such as accounts and hashes, are represented as hex.
+			TypeDef::Array(array)
+				if matches!(
+					types.resolve(array.type_param.id).map(|t| &t.type_def),
+					Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+				) =>
+				FieldType::String,
+			// Wrapper types are represented as the type they wrap.
+			TypeDef::Composite(composite) if composite.fields.len() == 1 =>
+				Self::resolve(types, composite.fields[0].ty.id),
+			_ => FieldType::Json,
+		}
+	}
+}
+
+/// A field of an entity, corresponding to a field of an event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+	/// The name of the field within the schema.
+	pub name: String,
+	/// The type of the field.
+	pub ty: FieldType,
+	/// The expression converting the field of a SubQuery event into the value of the field.
+	pub subquery: String,
+	/// The expression converting the field of a Squid event into the value of the field.
+	pub squid: String,
+}
+
+/// An entity of the starter schema, recording each occurrence of an event of the runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entity {
+	/// The name of the entity (e.g. `BalancesTransfer`).
+	pub name: String,
+	/// The name of the pallet emitting the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub event: String,
+	/// The fields of the event.
+	pub fields: Vec<Field>,
+}
+
+impl Entity {
+	/// The names of the fields common to all entities.
+	const COMMON_FIELDS: [&'static str; 3] = ["id", "blockNumber", "extrinsicIndex"];
+
+	/// Derives an entity from an event variant of a pallet.
+	///
+	/// # Arguments
+	/// * `types` - The type registry of the runtime.
+	/// * `pallet` - The name of the pallet.
+	/// * `event` - The variant of the event.
+	fn new(types: &PortableRegistry, pallet: &str, event: &Variant<PortableForm>) -> Self {
+		let named = event.fields.iter().all(|f| f.name.is_some());
+		let fields = event
+			.fields
+			.iter()
+			.enumerate()
+			.map(|(index, field)| {
+				let ty = FieldType::resolve(types, field.ty.id);
+				// Squid decodes named fields as an object keyed by their names in camel case, and
+				// unnamed fields as either the value itself or an array of values.
+				let (name, squid) = match &field.name {
+					Some(name) if named => {
+						let name = camel_case(name);
+						let squid = format!("event.args.{name}");
+						match Self::COMMON_FIELDS.contains(&name.as_str()) {
+							true => (format!("{name}Arg"), squid),
+							false => (name, squid),
+						}
+					},
+					_ if event.fields.len() == 1 => (format!("arg{index}"), "event.args".into()),
+					_ => (format!("arg{index}"), format!("event.args[{index}]")),
+				};
+				let subquery = format!("data[{index}]");
+				let (subquery, squid) = match ty {
+					FieldType::Int =>
+						(format!("Number({subquery}.toString())"), format!("Number({squid})")),
+					FieldType::BigInt =>
+						(format!("BigInt({subquery}.toString())"), format!("BigInt({squid})")),
+					FieldType::Boolean => (
+						format!("{subquery}.toPrimitive() as boolean"),
+						format!("Boolean({squid})"),
+					),
+					FieldType::String =>
+						(format!("{subquery}.toString()"), format!("String({squid})")),
+					FieldType::Json => (format!("{subquery}.toJSON()"), format!("toJSON({squid})")),
+				};
+				Field { name, ty, subquery, squid }
+			})
+			.collect();
+		Self {
+			name: format!("{pallet}{}", event.name),
+			pallet: pallet.to_string(),
+			event: event.name.clone(),
+			fields,
+		}
+	}
+
+	/// The name of the pallet as a module of the Polkadot.js API (e.g. `parachainSystem`), by
+	/// which SubQuery filters events.
+	pub fn module(&self) -> String {
+		let mut chars = self.pallet.chars();
+		chars
+			.next()
+			.map(|first| first.to_lowercase().chain(chars).collect())
+			.unwrap_or_default()
+	}
+}
+
+/// Derives the entities of a starter schema from the events of a runtime: one entity per event,
+/// with a field per field of the event.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the runtime.
+/// * `pallets` - The pallets whose events are indexed, or all pallets if empty.
+pub fn event_entities(metadata: &Metadata, pallets: &[String]) -> Result<Vec<Entity>, Error> {
+	if let Some(pallet) = pallets.iter().find(|p| metadata.pallet_by_name(p).is_none()) {
+		return Err(Error::Config(format!("pallet `{pallet}` not found within the runtime")));
+	}
+	Ok(metadata
+		.pallets()
+		.filter(|pallet| pallets.is_empty() || pallets.iter().any(|p| p == pallet.name()))
+		.flat_map(|pallet| {
+			pallet
+				.event_variants()
+				.unwrap_or_default()
+				.iter()
+				.map(move |event| Entity::new(metadata.types(), pallet.name(), event))
+		})
+		.collect())
+}
+
+/// The chain indexed by a project.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedChain {
+	/// The endpoint of a node of the chain.
+	pub endpoint: String,
+	/// The hash of the genesis block, identifying the chain.
+	pub genesis_hash: String,
+	/// The name of the runtime specification.
+	pub spec_name: String,
+	/// The version of the runtime specification.
+	pub spec_version: u32,
+	/// The SCALE-encoded metadata of the runtime.
+	pub metadata: Vec<u8>,
+}
+
+impl IndexedChain {
+	/// Fetches the identity and current runtime metadata of a chain.
+	///
+	/// # Arguments
+	/// * `url` - The endpoint of a node of the chain.
+	pub async fn fetch(url: &str) -> Result<Self, Error> {
+		#[derive(Deserialize)]
+		#[serde(rename_all = "camelCase")]
+		struct Version {
+			spec_name: String,
+			spec_version: u32,
+		}
+		let rpc = RpcClient::from_url(url)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+		let genesis_hash: String = rpc.request("chain_getBlockHash", rpc_params![0]).await?;
+		let version: Version = rpc.request("state_getRuntimeVersion", rpc_params![]).await?;
+		let metadata: String = rpc.request("state_getMetadata", rpc_params![]).await?;
+		let metadata = decode_hex(&metadata)
+			.ok_or_else(|| Error::Config(format!("invalid metadata of the chain at {url}")))?;
+		Ok(Self {
+			endpoint: url.to_string(),
+			genesis_hash,
+			spec_name: version.spec_name,
+			spec_version: version.spec_version,
+			metadata,
+		})
+	}
+}
+
+/// Generates an indexer project of a chain, pre-configured with the endpoint and types of the
+/// chain and a starter schema derived from the events of its runtime. Returns the paths of the
+/// generated files.
+///
+/// # Arguments
+/// * `framework` - The framework of the project.
+/// * `name` - The name of the project.
+/// * `chain` - The chain indexed.
+/// * `pallets` - The pallets whose events are indexed, or all pallets if empty.
+/// * `dir` - The directory within which the project is generated.
+pub fn generate_indexer(
+	framework: IndexerFramework,
+	name: &str,
+	chain: &IndexedChain,
+	pallets: &[String],
+	dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+	let metadata = Metadata::decode(&mut &chain.metadata[..])
+		.map_err(|e| Error::Config(format!("invalid metadata of {}: {e}", chain.spec_name)))?;
+	let entities = event_entities(&metadata, pallets)?;
+	if entities.is_empty() {
+		return Err(Error::Config("no events found to be indexed".into()));
+	}
+	let (IndexedChain { endpoint, genesis_hash, spec_name, .. }, entities) =
+		(chain, entities.as_slice());
+	let render = |template: &dyn DynTemplate| template.dyn_render().expect("infallible");
+	let mut files = vec![("schema.graphql", render(&Schema { spec_name, entities }))];
+	match framework {
+		IndexerFramework::SubQuery => files.extend([
+			("package.json", render(&SubQueryPackage { name, spec_name })),
+			("tsconfig.json", render(&TsConfig { out_dir: "dist" })),
+			(
+				"project.ts",
+				render(&SubQueryProject { name, spec_name, endpoint, genesis_hash, entities }),
+			),
+			("src/index.ts", "export * from \"./mappings/mappingHandlers\";\n".to_string()),
+			("src/mappings/mappingHandlers.ts", render(&SubQueryMappings { entities })),
+		]),
+		IndexerFramework::Squid => files.extend([
+			("package.json", render(&SquidPackage { name, spec_name })),
+			("tsconfig.json", render(&TsConfig { out_dir: "lib" })),
+			("squid.yaml", render(&SquidManifest { name, spec_name })),
+			("src/main.ts", render(&SquidProcessor { endpoint, entities })),
+			("typegen.json", serde_json::to_string_pretty(&typegen(entities))? + "\n"),
+			("specs.jsonl", serde_json::to_string(&spec_version(chain))? + "\n"),
+		]),
+	}
+	let mut paths = Vec::with_capacity(files.len());
+	for (file, contents) in files {
+		let path = dir.join(file);
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		write(&path, contents)?;
+		paths.push(path);
+	}
+	Ok(paths)
+}
+
+/// The configuration of the Squid type generator, generating types for the events indexed from
+/// the metadata of the chain (`specs.jsonl`).
+fn typegen(entities: &[Entity]) -> Value {
+	let mut pallets = Map::new();
+	for entity in entities {
+		let pallet = pallets.entry(&entity.pallet).or_insert_with(|| json!({ "events": [] }));
+		if let Some(Value::Array(events)) = pallet.get_mut("events") {
+			events.push(entity.event.clone().into());
+		}
+	}
+	json!({ "outDir": "src/types", "specVersions": "specs.jsonl", "pallets": pallets })
+}
+
+/// The version of the runtime of a chain, in the format of the Squid metadata explorer.
+fn spec_version(chain: &IndexedChain) -> Value {
+	json!({
+		"specName": chain.spec_name,
+		"specVersion": chain.spec_version,
+		"blockNumber": 0,
+		"blockHash": chain.genesis_hash,
+		"metadata": encode_hex(&chain.metadata),
+	})
+}
+
+/// Converts a field name from snake case to camel case (e.g. `asset_id` to `assetId`).
+fn camel_case(name: &str) -> String {
+	let mut words = name.split('_').filter(|w| !w.is_empty());
+	let mut camel = words.next().unwrap_or_default().to_string();
+	for word in words {
+		let mut chars = word.chars();
+		if let Some(first) = chars.next() {
+			camel.extend(first.to_uppercase().chain(chars));
+		}
+	}
+	camel
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::metadata_diff::tests::{encoded_metadata, Pallet};
+	use scale_info::{meta_type, TypeInfo};
+	use std::fs::read_to_string;
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	enum Event {
+		Transferred { from: [u8; 32], to: [u8; 32], amount: u128 },
+		Created(u32, bool),
+		Locked(u32),
+		Paused,
+	}
+
+	fn chain() -> IndexedChain {
+		IndexedChain {
+			endpoint: "ws://127.0.0.1:9944".into(),
+			genesis_hash: format!("0x{}", "00".repeat(32)),
+			spec_name: "pop".into(),
+			spec_version: 1,
+			metadata: encoded_metadata(vec![
+				Pallet {
+					name: "Assets",
+					index: 50,
+					calls: None,
+					events: Some(meta_type::<Event>()),
+					storage: vec![],
+					constants: vec![],
+				},
+				Pallet {
+					name: "Sudo",
+					index: 20,
+					calls: None,
+					events: None,
+					storage: vec![],
+					constants: vec![],
+				},
+			]),
+		}
+	}
+
+	#[test]
+	fn event_entities_works() -> Result<(), Error> {
+		let metadata = Metadata::decode(&mut &chain().metadata[..]).expect("valid metadata");
+		let entities = event_entities(&metadata, &["Assets".into()])?;
+		assert_eq!(
+			entities.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+			["AssetsTransferred", "AssetsCreated", "AssetsLocked", "AssetsPaused"]
+		);
+		let fields = |entity: &Entity| {
+			entity
+				.fields
+				.iter()
+				.map(|f| (f.name.clone(), f.ty, f.squid.clone()))
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(
+			fields(&entities[0]),
+			[
+				("from".into(), FieldType::String, "String(event.args.from)".into()),
+				("to".into(), FieldType::String, "String(event.args.to)".into()),
+				("amount".into(), FieldType::BigInt, "BigInt(event.args.amount)".into()),
+			]
+		);
+		assert_eq!(
+			fields(&entities[1]),
+			[
+				("arg0".into(), FieldType::Int, "Number(event.args[0])".into()),
+				("arg1".into(), FieldType::Boolean, "Boolean(event.args[1])".into()),
+			]
+		);
+		assert_eq!(
+			fields(&entities[2]),
+			[("arg0".into(), FieldType::Int, "Number(event.args)".into())]
+		);
+		assert_eq!(entities[1].fields[1].subquery, "data[1].toPrimitive() as boolean");
+		assert_eq!(entities[0].module(), "assets");
+		assert_eq!(event_entities(&metadata, &[])?, entities);
+		assert!(matches!(
+			event_entities(&metadata, &["Balances".into()]),
+			Err(Error::Config(e)) if e == "pallet `Balances` not found within the runtime"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn generate_indexer_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let dir = temp_dir.path();
+		let chain = chain();
+
+		let files = generate_indexer(IndexerFramework::SubQuery, "pop-indexer", &chain, &[], dir)?;
+		assert_eq!(files.len(), 6);
+		assert!(files.iter().all(|f| f.exists()));
+		let schema = read_to_string(dir.join("schema.graphql"))?;
+		assert!(schema.contains(
+			"type AssetsCreated @entity {\n  id: ID!\n  blockNumber: Int! @index\n  extrinsicIndex: Int\n  arg0: Int\n  arg1: Boolean\n}\n"
+		));
+		let project = read_to_string(dir.join("project.ts"))?;
+		assert!(project.contains(&format!("chainId: \"{}\"", chain.genesis_hash)));
+		assert!(project.contains("endpoint: [\"ws://127.0.0.1:9944\"]"));
+		assert!(project.contains(
+			"handler: \"handleAssetsPaused\",\n            filter: { module: \"assets\", method: \"Paused\" },"
+		));
+		let mappings = read_to_string(dir.join("src/mappings/mappingHandlers.ts"))?;
+		assert!(mappings.contains("    amount: BigInt(data[2].toString()),\n"));
+
+		let dir = &dir.join("squid");
+		let files = generate_indexer(IndexerFramework::Squid, "pop-indexer", &chain, &[], dir)?;
+		assert_eq!(files.len(), 7);
+		let processor = read_to_string(dir.join("src/main.ts"))?;
+		assert!(processor.contains("      \"Assets.Transferred\",\n"));
+		assert!(processor.contains("              arg0: Number(event.args),\n"));
+		let typegen: Value = serde_json::from_str(&read_to_string(dir.join("typegen.json"))?)?;
+		assert_eq!(
+			typegen["pallets"]["Assets"]["events"],
+			json!(["Transferred", "Created", "Locked", "Paused"])
+		);
+		let specs: Value = serde_json::from_str(&read_to_string(dir.join("specs.jsonl"))?)?;
+		assert_eq!(specs["metadata"], encode_hex(&chain.metadata));
+		Ok(())
+	}
+
+	#[test]
+	fn generate_indexer_fails_without_events() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(matches!(
+			generate_indexer(
+				IndexerFramework::Squid,
+				"pop-indexer",
+				&chain(),
+				&["Sudo".into()],
+				temp_dir.path()
+			),
+			Err(Error::Config(e)) if e == "no events found to be indexed"
+		));
+		Ok(())
+	}
+}
@@ -1,25 +1,157 @@
 // SPDX-License-Identifier: GPL-3.0
 
 #![doc = include_str!("../README.md")]
+mod benchmark;
 mod build;
+mod call;
+mod chain_monitor;
+mod chain_spec_builder;
+mod collators;
+mod coretime;
+mod dispatch;
+mod endowments;
 mod errors;
+mod export;
+mod faucet;
+mod features;
+mod fees;
+mod gateway;
 mod generator;
+mod hrmp;
+mod impersonate;
+mod indexer;
+mod metadata_diff;
+mod monitor;
+mod multisig;
 mod new_pallet;
 mod new_parachain;
+mod onboarding;
+mod pallet_index;
+mod presets;
+mod referendum;
+mod registrar;
+mod release_notes;
+mod rpc;
+mod runtime_api;
+mod runtime_info;
+mod runtime_size;
+mod session;
+mod snapshot;
+mod spec_summary;
+mod spec_template;
+mod spec_validation;
+mod srtool;
+mod storage_key;
 mod templates;
+mod test_vectors;
+mod try_runtime;
 mod up;
+mod upgrade;
 mod utils;
+mod verify;
 
+pub use benchmark::{benchmark_binary, benchmark_live};
 pub use build::{
-	binary_path, build_parachain, export_wasm_file, generate_genesis_state_file,
-	generate_plain_chain_spec, generate_raw_chain_spec, is_supported, ChainSpec,
+	binary_path, build_parachain, build_parachain_in_container, build_parachain_remotely,
+	build_runtime, export_head_data, export_wasm_file, generate_genesis_state_file,
+	generate_plain_chain_spec, generate_raw_chain_spec, is_supported, launch_remotely,
+	list_presets, runtime_artifacts, BuildArtifacts, BuildOptions, ChainSpec, RuntimeArtifacts,
+	EVM_PRECOMPILE_CODE,
 };
+pub use call::{
+	call_arg_names, construct_batch_extrinsic, construct_extrinsic, construct_sudo_extrinsic,
+	construct_sudo_unchecked_weight_extrinsic, construct_transfer_extrinsic, set_up_client,
+	set_up_ethereum_client, sign_and_submit_ethereum_extrinsic, sign_and_submit_extrinsic,
+	submit_extrinsic, uses_ethereum_accounts, ChainAccounts, FeePayment, SignatureScheme,
+};
+pub use chain_monitor::{ChainEvent, ChainMonitor, MonitorRules};
+pub use chain_spec_builder::{
+	chain_spec_builder, extract_genesis_code, generate_plain_chain_spec_with_runtime,
+	generate_raw_chain_spec_with_runtime, CHAIN_SPEC_BUILDER,
+};
+pub use collators::{load_collator_keys, CollatorKeys};
+pub use coretime::{on_demand_pallet, place_order, OnDemandConfig, OnDemandOrders, OrderSummary};
+pub use dispatch::{prepare_privileged_call, DispatchStrategy, PrivilegedCall, Proposal};
+pub use endowments::load_endowments;
 pub use errors::Error;
+pub use export::{
+	export_chain_data, ChainData, Column, ColumnType, ExportFormat, Table, BLOCKS, EVENTS,
+	EXTRINSICS,
+};
+pub use faucet::{Captcha, Dispense, Faucet, FaucetConfig};
+pub use features::{audit_features, FeatureIssue, FeatureIssueKind, AUDITED_FEATURES};
+pub use fees::{AssetLocation, Junction};
+pub use gateway::{Gateway, RpcSecurity};
+pub use hrmp::{
+	force_clean_hrmp, force_open_hrmp_channel, open_hrmp_channels, query_hrmp_channels,
+	HrmpChannel, HrmpChannels,
+};
+pub use impersonate::{is_fork, submit_impersonated, Impersonator};
+pub use indexer::{
+	event_entities, generate_indexer, Entity, Field, FieldType, IndexedChain, IndexerFramework,
+};
 pub use indexmap::IndexSet;
-pub use new_pallet::{create_pallet_template, new_pallet_options::*, TemplatePalletConfig};
+pub use metadata_diff::{Changes, ConstantChange, MetadataDiff, PalletDiff};
+pub use monitor::{wait_for_onboarding, OnboardingEvent};
+pub use multisig::{
+	multisig_account, prepare_ceremony, Ceremony, Instruction, ObservedApprovals, Timepoint, Weight,
+};
+pub use new_pallet::{
+	add_pallet_to_runtime, create_pallet_template, new_pallet_options::*, TemplatePalletConfig,
+};
 pub use new_parachain::instantiate_template_dir;
+pub use onboarding::{
+	next_steps, post_install_checks, NextStep, NextSteps, NodePort, PostInstallCheck,
+};
+pub use pallet_index::{IndexCollision, PalletIndex, PalletIndices, Renumbering};
+pub use presets::{apply_runtime_preset, RuntimeDependencies, RuntimePreset};
+pub use referendum::{simulate_referendum, SimulatedEvent, Simulation, StateChange};
+pub use registrar::{
+	clear_all_leases, deregister, deregistration_calls, downgrade_to_parathread, force_lease,
+	query_deposit, query_lifecycle, register_parachain, reserve, reserve_para_id, swap,
+	trigger_onboard, upgrade_to_parachain, CoretimeModel, Lease, ParaLifecycle, RegistrationFlow,
+	RelaySupport,
+};
+pub use release_notes::{load_metadata, ReleaseNotes, VersionSummary};
+pub use rpc::{
+	BlockEvent, Buffering, FinalizedBlock, ReconnectPolicy, ResilientClient, StreamEvent,
+	Subscription,
+};
+pub use runtime_api::{
+	call_runtime_api, decode_runtime_api_result, encode_runtime_api_args, runtime_api_methods,
+	RuntimeApiMethod, RuntimeApiResult,
+};
+pub use runtime_info::{inspect_runtime, RuntimeInfo};
+pub use runtime_size::{runtime_size_report, RuntimeSize, SizeReport};
+pub use session::{Session, SessionCommand};
+pub use snapshot::{fork_off, LiveState, Snapshot, SnapshotKind, Snapshots};
+pub use spec_summary::{decode_raw_storage, ChainSpecSummary, RawStorageEntry};
+pub use spec_template::{ChainSpecTemplate, TemplateVariables};
+pub use spec_validation::{validate_chain_spec, SpecDiagnostic};
+pub use srtool::{
+	build_deterministic_runtime, DeterministicBuild, RuntimeDigest, Srtool, SRTOOL_IMAGE,
+	SRTOOL_TAG,
+};
+pub use storage_key::{decode_storage_key, storage_key, DecodedStorageKey, StorageKeyPart};
 pub use templates::{Config, Parachain, Provider};
-pub use up::Zombienet;
+pub use test_vectors::{generate_test_vectors, resolve_type, TestVector, TestVectors};
+pub use try_runtime::{try_runtime_binary, try_runtime_live, TryRuntimeCheck};
+pub use up::{
+	add_log_filter, check_health, find_networks, log_path, network_nodes, reset_log_filter,
+	validate_log_directives, workspace_nodes, EndpointReport, LogOptions, LogStream, NetworkLogs,
+	NetworkState, NodeEndpoints, NodeReadiness, ParachainAssignment, Peering, Ports,
+	ReadinessProbe, RelaunchPlan, Role, Topology, TopologyNode, Watchdog, WatchdogEvent,
+	WatchdogPolicy, WorkspaceNetwork, WorkspaceNode, Zombienet,
+};
+pub use upgrade::{
+	apply_authorized_upgrade, authorize_upgrade, authorized_upgrade_calls, schedule,
+	schedule_upgrade, set_code, sudo_upgrade, upgrade_local_network, upgrade_local_parachain,
+	upgrade_parachain, wait_for_block, Enactment, RuntimeUpgrade,
+};
 pub use utils::helpers::is_initial_endowment_valid;
+pub use verify::{
+	verify_live_chain, verify_runtime, Artifacts, ChainState, Check, RuntimeVerification,
+	Verification,
+};
 /// Information about the Node. External export from Zombienet-SDK.
 pub use zombienet_sdk::NetworkNode;
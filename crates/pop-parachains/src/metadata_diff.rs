@@ -0,0 +1,499 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use pop_common::encode_hex;
+use scale_info::{form::PortableForm, Field, TypeDef, Variant};
+use std::collections::{BTreeMap, HashSet};
+use subxt::{
+	ext::scale_value::scale::decode_as_type,
+	metadata::types::{PalletMetadata, StorageEntryType},
+	Metadata,
+};
+
+/// The differences between two versions of the named items of a pallet (e.g. its calls).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Changes {
+	/// The items only within the new version.
+	pub added: Vec<String>,
+	/// The items only within the old version.
+	pub removed: Vec<String>,
+	/// The items within both versions, whose structure changed.
+	pub changed: Vec<String>,
+}
+
+impl Changes {
+	/// Whether the versions are the same.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+
+	/// Compares the items of both versions, described by their names and structure.
+	fn between(old: Vec<(String, String)>, new: Vec<(String, String)>) -> Self {
+		let old: BTreeMap<_, _> = old.into_iter().collect();
+		let new: BTreeMap<_, _> = new.into_iter().collect();
+		Self {
+			added: new.keys().filter(|name| !old.contains_key(*name)).cloned().collect(),
+			removed: old.keys().filter(|name| !new.contains_key(*name)).cloned().collect(),
+			changed: new
+				.iter()
+				.filter(|(name, shape)| old.get(*name).is_some_and(|old| old != *shape))
+				.map(|(name, _)| name.clone())
+				.collect(),
+		}
+	}
+}
+
+/// A change of the value of a constant of a pallet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstantChange {
+	/// The name of the constant.
+	pub name: String,
+	/// The old value.
+	pub old: String,
+	/// The new value.
+	pub new: String,
+}
+
+/// The differences between two versions of a pallet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PalletDiff {
+	/// The name of the pallet.
+	pub name: String,
+	/// The old and new index of the pallet, if it moved.
+	pub index: Option<(u8, u8)>,
+	/// The changes of the calls of the pallet, including changes of their indices.
+	pub calls: Changes,
+	/// The changes of the events of the pallet.
+	pub events: Changes,
+	/// The changes of the storage entries of the pallet: changes of the layout of existing
+	/// entries require migrations.
+	pub storage: Changes,
+	/// The changes of the constants of the pallet, other than their values.
+	pub constants: Changes,
+	/// The changes of the values of the constants of the pallet.
+	pub values: Vec<ConstantChange>,
+}
+
+impl PalletDiff {
+	/// Whether the versions of the pallet are the same.
+	pub fn is_empty(&self) -> bool {
+		self.index.is_none() &&
+			self.calls.is_empty() &&
+			self.events.is_empty() &&
+			self.storage.is_empty() &&
+			self.constants.is_empty() &&
+			self.values.is_empty()
+	}
+}
+
+/// The differences between the metadata of two versions of a runtime, compared by the structure
+/// of their types rather than by type identifiers (which differ between versions).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetadataDiff {
+	/// The pallets only within the new version, alongside their indices.
+	pub pallets_added: Vec<(String, u8)>,
+	/// The pallets only within the old version.
+	pub pallets_removed: Vec<String>,
+	/// The changes of the pallets within both versions, if any.
+	pub pallets: Vec<PalletDiff>,
+}
+
+impl MetadataDiff {
+	/// Compares the metadata of two versions of a runtime.
+	///
+	/// # Arguments
+	/// * `old` - The metadata of the old version.
+	/// * `new` - The metadata of the new version.
+	pub fn between(old: &Metadata, new: &Metadata) -> Self {
+		let mut diff = Self::default();
+		for pallet in new.pallets() {
+			let Some(previous) = old.pallet_by_name(pallet.name()) else {
+				diff.pallets_added.push((pallet.name().to_string(), pallet.index()));
+				continue;
+			};
+			let pallet_diff = PalletDiff {
+				name: pallet.name().to_string(),
+				index: (previous.index() != pallet.index())
+					.then(|| (previous.index(), pallet.index())),
+				calls: Changes::between(
+					variants(old, previous.call_variants()),
+					variants(new, pallet.call_variants()),
+				),
+				events: Changes::between(
+					variants(old, previous.event_variants()),
+					variants(new, pallet.event_variants()),
+				),
+				storage: Changes::between(storage(old, &previous), storage(new, &pallet)),
+				constants: Changes::between(constants(old, &previous), constants(new, &pallet)),
+				values: values(old, &previous, new, &pallet),
+			};
+			if !pallet_diff.is_empty() {
+				diff.pallets.push(pallet_diff);
+			}
+		}
+		diff.pallets_removed = old
+			.pallets()
+			.filter(|pallet| new.pallet_by_name(pallet.name()).is_none())
+			.map(|pallet| pallet.name().to_string())
+			.collect();
+		diff
+	}
+
+	/// Whether the metadata of both versions is the same.
+	pub fn is_empty(&self) -> bool {
+		self.pallets_added.is_empty() && self.pallets_removed.is_empty() && self.pallets.is_empty()
+	}
+
+	/// Whether existing transactions are affected (calls changed, removed or moved), requiring
+	/// the transaction version to be bumped.
+	pub fn breaks_transactions(&self) -> bool {
+		!self.pallets_removed.is_empty() ||
+			self.pallets.iter().any(|pallet| {
+				pallet.index.is_some() ||
+					!pallet.calls.removed.is_empty() ||
+					!pallet.calls.changed.is_empty()
+			})
+	}
+}
+
+/// The names and structure of the variants of a pallet (e.g. its calls), including their indices.
+fn variants(
+	metadata: &Metadata,
+	variants: Option<&[Variant<PortableForm>]>,
+) -> Vec<(String, String)> {
+	let mut shapes = Shapes::new(metadata);
+	variants
+		.unwrap_or_default()
+		.iter()
+		.map(|variant| {
+			let shape = format!("{}{}", variant.index, shapes.fields(&variant.fields));
+			(variant.name.clone(), shape)
+		})
+		.collect()
+}
+
+/// The names and layout of the storage entries of a pallet.
+fn storage(metadata: &Metadata, pallet: &PalletMetadata) -> Vec<(String, String)> {
+	let mut shapes = Shapes::new(metadata);
+	let Some(storage) = pallet.storage() else {
+		return vec![];
+	};
+	storage
+		.entries()
+		.iter()
+		.map(|entry| {
+			let shape = match entry.entry_type() {
+				StorageEntryType::Plain(ty) => shapes.describe(*ty),
+				StorageEntryType::Map { hashers, key_ty, value_ty } => format!(
+					"{hashers:?}({}) => {}",
+					shapes.describe(*key_ty),
+					shapes.describe(*value_ty)
+				),
+			};
+			(entry.name().to_string(), format!("{:?} {shape}", entry.modifier()))
+		})
+		.collect()
+}
+
+/// The names and types of the constants of a pallet.
+fn constants(metadata: &Metadata, pallet: &PalletMetadata) -> Vec<(String, String)> {
+	let mut shapes = Shapes::new(metadata);
+	pallet
+		.constants()
+		.map(|constant| (constant.name().to_string(), shapes.describe(constant.ty())))
+		.collect()
+}
+
+/// The changes of the values of the constants within both versions of a pallet.
+fn values(
+	old: &Metadata,
+	previous: &PalletMetadata,
+	new: &Metadata,
+	pallet: &PalletMetadata,
+) -> Vec<ConstantChange> {
+	let value = |metadata: &Metadata, ty: u32, bytes: &[u8]| {
+		decode_as_type(&mut &bytes[..], ty, metadata.types())
+			.map(|value| value.to_string())
+			.unwrap_or_else(|_| encode_hex(bytes))
+	};
+	pallet
+		.constants()
+		.filter_map(|constant| {
+			let before = previous.constant_by_name(constant.name())?;
+			(before.value() != constant.value()).then(|| ConstantChange {
+				name: constant.name().to_string(),
+				old: value(old, before.ty(), before.value()),
+				new: value(new, constant.ty(), constant.value()),
+			})
+		})
+		.collect()
+}
+
+/// Describes the structure of the types of a registry, so that types can be compared across
+/// registries. The outer enums of the runtime (e.g. `RuntimeCall`) are only referred to by name,
+/// so that calls wrapping calls (e.g. `sudo.sudo`) do not change whenever any call changes.
+struct Shapes<'a> {
+	metadata: &'a Metadata,
+	opaque: [u32; 4],
+	visiting: HashSet<u32>,
+}
+
+impl<'a> Shapes<'a> {
+	fn new(metadata: &'a Metadata) -> Self {
+		let outer = metadata.outer_enums();
+		Self {
+			metadata,
+			opaque: [
+				metadata.runtime_ty(),
+				outer.call_enum_ty(),
+				outer.event_enum_ty(),
+				outer.error_enum_ty(),
+			],
+			visiting: HashSet::new(),
+		}
+	}
+
+	/// Describes the structure of a type. Recursive types are referred to by name once already
+	/// being described.
+	fn describe(&mut self, id: u32) -> String {
+		let Some(ty) = self.metadata.types().resolve(id) else {
+			return "?".into();
+		};
+		let name = ty.path.segments.join("::");
+		if self.opaque.contains(&id) || !self.visiting.insert(id) {
+			return name;
+		}
+		let shape = match &ty.type_def {
+			TypeDef::Composite(composite) => format!("{name}{}", self.fields(&composite.fields)),
+			TypeDef::Variant(variant) => {
+				let variants: Vec<_> = variant
+					.variants
+					.iter()
+					.map(|v| format!("{}={}{}", v.name, v.index, self.fields(&v.fields)))
+					.collect();
+				format!("{name}<{}>", variants.join("|"))
+			},
+			TypeDef::Sequence(sequence) => format!("[{}]", self.describe(sequence.type_param.id)),
+			TypeDef::Array(array) =>
+				format!("[{}; {}]", self.describe(array.type_param.id), array.len),
+			TypeDef::Tuple(tuple) => {
+				let fields: Vec<_> = tuple.fields.iter().map(|f| self.describe(f.id)).collect();
+				format!("({})", fields.join(", "))
+			},
+			TypeDef::Primitive(primitive) => format!("{primitive:?}").to_lowercase(),
+			TypeDef::Compact(compact) =>
+				format!("Compact<{}>", self.describe(compact.type_param.id)),
+			TypeDef::BitSequence(_) => "BitSequence".into(),
+		};
+		self.visiting.remove(&id);
+		shape
+	}
+
+	/// Describes the structure of the fields of a composite or variant.
+	fn fields(&mut self, fields: &[Field<PortableForm>]) -> String {
+		let fields: Vec<_> = fields
+			.iter()
+			.map(|field| match &field.name {
+				Some(name) => format!("{name}: {}", self.describe(field.ty.id)),
+				None => self.describe(field.ty.id),
+			})
+			.collect();
+		format!("({})", fields.join(", "))
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use scale_info::{meta_type, MetaType, TypeInfo};
+	use subxt::ext::{
+		codec::{Decode, Encode},
+		frame_metadata::{
+			v15::{
+				CustomMetadata, ExtrinsicMetadata, OuterEnums, PalletCallMetadata,
+				PalletConstantMetadata, PalletEventMetadata, PalletMetadata, PalletStorageMetadata,
+				RuntimeApiMetadata, RuntimeMetadataV15, StorageEntryMetadata, StorageEntryModifier,
+				StorageEntryType,
+			},
+			RuntimeMetadataPrefixed,
+		},
+	};
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	pub(crate) enum CallV1 {
+		#[codec(index = 0)]
+		Transfer { dest: u32, value: u128 },
+		#[codec(index = 1)]
+		Burn { value: u128 },
+	}
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	pub(crate) enum CallV2 {
+		#[codec(index = 0)]
+		Transfer { dest: u32, value: u64 },
+		#[codec(index = 2)]
+		TransferAll { dest: u32 },
+	}
+
+	/// A pallet, specified by its name, index, calls, events, plain storage entries and `u32`
+	/// constants.
+	pub(crate) struct Pallet {
+		pub(crate) name: &'static str,
+		pub(crate) index: u8,
+		pub(crate) calls: Option<MetaType>,
+		pub(crate) events: Option<MetaType>,
+		pub(crate) storage: Vec<(&'static str, MetaType)>,
+		pub(crate) constants: Vec<(&'static str, u32)>,
+	}
+
+	/// Constructs the metadata of a runtime with the specified pallets.
+	pub(crate) fn metadata(pallets: Vec<Pallet>) -> Metadata {
+		Metadata::decode(&mut &encoded_metadata(pallets)[..]).expect("valid metadata")
+	}
+
+	/// Constructs the metadata of a runtime with the specified runtime APIs, but no pallets.
+	pub(crate) fn metadata_with_apis(apis: Vec<RuntimeApiMetadata>) -> Metadata {
+		Metadata::decode(&mut &encode(vec![], apis)[..]).expect("valid metadata")
+	}
+
+	/// Constructs the SCALE-encoded metadata of a runtime with the specified pallets.
+	pub(crate) fn encoded_metadata(pallets: Vec<Pallet>) -> Vec<u8> {
+		encode(pallets, vec![])
+	}
+
+	/// Constructs the SCALE-encoded metadata of a runtime with the specified pallets and runtime
+	/// APIs.
+	fn encode(pallets: Vec<Pallet>, apis: Vec<RuntimeApiMetadata>) -> Vec<u8> {
+		let pallets = pallets
+			.into_iter()
+			.map(|pallet| PalletMetadata {
+				name: pallet.name,
+				storage: Some(PalletStorageMetadata {
+					prefix: pallet.name,
+					entries: pallet
+						.storage
+						.into_iter()
+						.map(|(name, ty)| StorageEntryMetadata {
+							name,
+							modifier: StorageEntryModifier::Optional,
+							ty: StorageEntryType::Plain(ty),
+							default: vec![0],
+							docs: vec![],
+						})
+						.collect(),
+				}),
+				calls: pallet.calls.map(|ty| PalletCallMetadata { ty }),
+				event: pallet.events.map(|ty| PalletEventMetadata { ty }),
+				constants: pallet
+					.constants
+					.into_iter()
+					.map(|(name, value)| PalletConstantMetadata {
+						name,
+						ty: meta_type::<u32>(),
+						value: value.encode(),
+						docs: vec![],
+					})
+					.collect(),
+				error: None,
+				index: pallet.index,
+				docs: vec![],
+			})
+			.collect();
+		let extrinsic = ExtrinsicMetadata {
+			version: 4,
+			address_ty: meta_type::<()>(),
+			call_ty: meta_type::<()>(),
+			signature_ty: meta_type::<()>(),
+			extra_ty: meta_type::<()>(),
+			signed_extensions: vec![],
+		};
+		let outer_enums = OuterEnums {
+			call_enum_ty: meta_type::<()>(),
+			event_enum_ty: meta_type::<()>(),
+			error_enum_ty: meta_type::<()>(),
+		};
+		let metadata = RuntimeMetadataV15::new(
+			pallets,
+			extrinsic,
+			meta_type::<()>(),
+			apis,
+			outer_enums,
+			CustomMetadata { map: Default::default() },
+		);
+		RuntimeMetadataPrefixed::from(metadata).encode()
+	}
+
+	#[test]
+	fn between_works() {
+		let old = metadata(vec![
+			Pallet {
+				name: "Balances",
+				index: 10,
+				calls: Some(meta_type::<CallV1>()),
+				events: None,
+				storage: vec![
+					("TotalIssuance", meta_type::<u128>()),
+					("Locks", meta_type::<u32>()),
+				],
+				constants: vec![("ExistentialDeposit", 1), ("MaxLocks", 50)],
+			},
+			Pallet {
+				name: "Sudo",
+				index: 20,
+				calls: None,
+				events: None,
+				storage: vec![],
+				constants: vec![],
+			},
+		]);
+		let new = metadata(vec![
+			Pallet {
+				name: "Balances",
+				index: 10,
+				calls: Some(meta_type::<CallV2>()),
+				events: None,
+				storage: vec![("TotalIssuance", meta_type::<u64>()), ("Holds", meta_type::<u32>())],
+				constants: vec![("ExistentialDeposit", 10), ("MaxLocks", 50)],
+			},
+			Pallet {
+				name: "Assets",
+				index: 50,
+				calls: None,
+				events: None,
+				storage: vec![],
+				constants: vec![],
+			},
+		]);
+
+		let diff = MetadataDiff::between(&old, &new);
+		assert_eq!(diff.pallets_added, vec![("Assets".to_string(), 50)]);
+		assert_eq!(diff.pallets_removed, vec!["Sudo".to_string()]);
+		assert_eq!(
+			diff.pallets,
+			vec![PalletDiff {
+				name: "Balances".into(),
+				index: None,
+				calls: Changes {
+					added: vec!["TransferAll".into()],
+					removed: vec!["Burn".into()],
+					changed: vec!["Transfer".into()],
+				},
+				events: Changes::default(),
+				storage: Changes {
+					added: vec!["Holds".into()],
+					removed: vec!["Locks".into()],
+					changed: vec!["TotalIssuance".into()],
+				},
+				constants: Changes::default(),
+				values: vec![ConstantChange {
+					name: "ExistentialDeposit".into(),
+					old: "1".into(),
+					new: "10".into(),
+				}],
+			}]
+		);
+		assert!(diff.breaks_transactions());
+		assert!(MetadataDiff::between(&new, &new).is_empty());
+	}
+}
@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	errors::Error,
+	registrar::{query_lifecycle, ParaLifecycle},
+	rpc::{Buffering, ReconnectPolicy, ResilientClient, StreamEvent},
+};
+use std::{
+	fmt::{Display, Formatter},
+	time::Duration,
+};
+
+/// The progress of a parachain being onboarded, as reported by [`wait_for_onboarding`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum OnboardingEvent {
+	/// The lifecycle state of the para on the relay chain changed, as of the specified relay
+	/// chain block, or the para is not (yet) known to the relay chain.
+	Lifecycle {
+		/// The number of the relay chain block.
+		block: u32,
+		/// The lifecycle state of the para, if known to the relay chain.
+		state: Option<ParaLifecycle>,
+	},
+	/// The para has been onboarded, so block production is awaited.
+	Onboarded(ParaLifecycle),
+	/// The connection to a node was lost, with the reason.
+	Disconnected(String),
+	/// The connection to a node was re-established.
+	Reconnected,
+	/// The parachain produced (and finalized) a block.
+	Block(u32),
+}
+
+impl Display for OnboardingEvent {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			OnboardingEvent::Lifecycle { block, state: None } =>
+				write!(f, "Relay chain block #{block}: the para is not yet registered"),
+			OnboardingEvent::Lifecycle { block, state: Some(state) } =>
+				write!(f, "Relay chain block #{block}: the para is {state:?}"),
+			OnboardingEvent::Onboarded(state) =>
+				write!(f, "Onboarded as a {state:?}, waiting for the first block..."),
+			OnboardingEvent::Disconnected(reason) => write!(f, "Connection lost: {reason}"),
+			OnboardingEvent::Reconnected => write!(f, "Reconnected"),
+			OnboardingEvent::Block(number) => write!(f, "Block #{number} produced"),
+		}
+	}
+}
+
+/// Waits until a parachain produces blocks: first following its lifecycle on the relay chain
+/// until it has been onboarded (as a parathread or parachain), then watching the finalized
+/// blocks of the parachain until its first block. Returns the number of that block.
+///
+/// A parathread only produces blocks once coretime has been acquired (e.g. via on-demand orders).
+///
+/// # Arguments
+/// * `relay_url` - Endpoint of a node of the relay chain.
+/// * `para_url` - Endpoint of a node of the parachain.
+/// * `para_id` - The parachain identifier.
+/// * `timeout` - How long to wait before giving up.
+/// * `progress` - Called with each event, so that the progress can be rendered.
+pub async fn wait_for_onboarding(
+	relay_url: &str,
+	para_url: &str,
+	para_id: u32,
+	timeout: Duration,
+	progress: impl Fn(&OnboardingEvent),
+) -> Result<u32, Error> {
+	let wait = async {
+		let state = wait_until_onboarded(relay_url, para_id, &progress).await?;
+		progress(&OnboardingEvent::Onboarded(state));
+		wait_for_block(para_url, &progress).await
+	};
+	tokio::time::timeout(timeout, wait).await.map_err(|_| {
+		Error::OnboardingError(format!(
+			"parachain {para_id} did not produce a block within {timeout:?}"
+		))
+	})?
+}
+
+/// Follows the lifecycle of a para on the relay chain until it has been onboarded.
+async fn wait_until_onboarded(
+	relay_url: &str,
+	para_id: u32,
+	progress: &impl Fn(&OnboardingEvent),
+) -> Result<ParaLifecycle, Error> {
+	let relay = ResilientClient::new(relay_url, ReconnectPolicy::default());
+	let mut blocks = relay.subscribe_finalized_blocks(Buffering::DropNewest(1));
+	let (mut client, mut last) = (None, None);
+	loop {
+		let block = match blocks.next().await {
+			Some(StreamEvent::Item(block)) => block,
+			Some(StreamEvent::Lagged(_)) => continue,
+			Some(event) => {
+				client = None;
+				report(event, progress);
+				continue;
+			},
+			None => return Err(Error::ConnectionFailure(relay_url.to_string())),
+		};
+		let online = match client.take() {
+			Some(client) => client,
+			None => relay.connect().await?,
+		};
+		let state = query_lifecycle(&online, para_id).await?;
+		client = Some(online);
+		if last.as_ref() != Some(&state) {
+			progress(&OnboardingEvent::Lifecycle { block: block.number, state: state.clone() });
+			last = Some(state.clone());
+		}
+		if let Some(state) = state.filter(is_onboarded) {
+			return Ok(state);
+		}
+	}
+}
+
+/// Watches the finalized blocks of a parachain until its first block.
+async fn wait_for_block(
+	para_url: &str,
+	progress: &impl Fn(&OnboardingEvent),
+) -> Result<u32, Error> {
+	let para = ResilientClient::new(para_url, ReconnectPolicy::default());
+	let mut blocks = para.subscribe_finalized_blocks(Buffering::DropNewest(1));
+	loop {
+		match blocks.next().await {
+			Some(StreamEvent::Item(block)) if block.number > 0 => {
+				progress(&OnboardingEvent::Block(block.number));
+				return Ok(block.number);
+			},
+			Some(StreamEvent::Item(_)) => continue,
+			Some(event) => report(event, progress),
+			None => return Err(Error::ConnectionFailure(para_url.to_string())),
+		}
+	}
+}
+
+/// Reports a change of the connection of a subscription.
+fn report<T>(event: StreamEvent<T>, progress: &impl Fn(&OnboardingEvent)) {
+	match event {
+		StreamEvent::Disconnected(reason) => progress(&OnboardingEvent::Disconnected(reason)),
+		StreamEvent::Reconnected => progress(&OnboardingEvent::Reconnected),
+		StreamEvent::Item(_) | StreamEvent::Lagged(_) => {},
+	}
+}
+
+/// Whether a para has been onboarded, and so can produce blocks once it has coretime.
+fn is_onboarded(state: &ParaLifecycle) -> bool {
+	matches!(
+		state,
+		ParaLifecycle::Parathread |
+			ParaLifecycle::Parachain |
+			ParaLifecycle::UpgradingParathread |
+			ParaLifecycle::DowngradingParachain
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Mutex;
+
+	#[test]
+	fn is_onboarded_works() {
+		assert!(!is_onboarded(&ParaLifecycle::Onboarding));
+		assert!(is_onboarded(&ParaLifecycle::Parathread));
+		assert!(is_onboarded(&ParaLifecycle::Parachain));
+		assert!(!is_onboarded(&ParaLifecycle::OffboardingParachain));
+	}
+
+	#[test]
+	fn onboarding_event_display_works() {
+		let state = Some(ParaLifecycle::Onboarding);
+		assert_eq!(
+			OnboardingEvent::Lifecycle { block: 10, state }.to_string(),
+			"Relay chain block #10: the para is Onboarding"
+		);
+		assert_eq!(
+			OnboardingEvent::Lifecycle { block: 1, state: None }.to_string(),
+			"Relay chain block #1: the para is not yet registered"
+		);
+		assert_eq!(OnboardingEvent::Block(1).to_string(), "Block #1 produced");
+	}
+
+	#[tokio::test]
+	async fn wait_for_onboarding_times_out() {
+		let events = Mutex::new(vec![]);
+		let result = wait_for_onboarding(
+			"ws://127.0.0.1:1",
+			"ws://127.0.0.1:2",
+			2000,
+			Duration::from_millis(100),
+			|event| events.lock().unwrap().push(event.clone()),
+		)
+		.await;
+		assert!(matches!(
+			result,
+			Err(Error::OnboardingError(e)) if e == "parachain 2000 did not produce a block within 100ms"
+		));
+		assert!(events.lock().unwrap().is_empty());
+	}
+}
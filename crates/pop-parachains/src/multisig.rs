@@ -0,0 +1,495 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{call::fetch_storage, errors::Error};
+use pop_common::decode_hex;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+use subxt::{
+	config::{substrate::BlakeTwo256, Hasher},
+	dynamic::{At, Value},
+	ext::{codec::Encode, scale_value::ValueDef},
+	tx::DynamicPayload,
+	utils::{to_hex, AccountId32},
+	OnlineClient, SubstrateConfig,
+};
+
+/// The prefix used when deriving the account of a multisig.
+const MULTISIG_PREFIX: &[u8; 16] = b"modlpy/utilisuba";
+
+/// A point in time at which a multisig operation was first approved on-chain.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Timepoint {
+	/// The block at which the operation was first approved.
+	pub height: u32,
+	/// The index of the extrinsic within the block.
+	pub index: u32,
+}
+
+/// The weight of a call.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Weight {
+	/// The computational time, in picoseconds.
+	pub ref_time: u64,
+	/// The size of the proof, in bytes.
+	pub proof_size: u64,
+}
+
+/// A "ceremony package" coordinating the approval of a privileged call by the signatories of a
+/// multisig, shared between the signatories (e.g. as JSON) until the call is executed.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Ceremony {
+	/// The SCALE encoded call to be dispatched by the multisig, hex encoded.
+	pub call_data: String,
+	/// The blake2-256 hash of the encoded call, hex encoded.
+	pub call_hash: String,
+	/// The maximum weight of the call, required when executing it.
+	pub max_weight: Weight,
+	/// The account of the multisig.
+	pub multisig: String,
+	/// The number of approvals required.
+	pub threshold: u16,
+	/// The signatories of the multisig, sorted.
+	pub signatories: Vec<String>,
+	/// When the ceremony expires, as seconds since the Unix epoch.
+	pub expires_at: u64,
+	/// When the call was first approved on-chain, if approved.
+	pub timepoint: Option<Timepoint>,
+	/// The signatories which have approved the call.
+	pub approvals: Vec<String>,
+}
+
+/// The approvals of a call observed on-chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObservedApprovals {
+	/// When the call was first approved.
+	pub timepoint: Timepoint,
+	/// The signatories which have approved the call.
+	pub approvals: Vec<String>,
+}
+
+/// An instruction for a signatory to approve the call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+	/// The signatory.
+	pub signatory: String,
+	/// Whether the approval executes the call, as the final approval required.
+	pub executes: bool,
+	/// The call to be signed and submitted by the signatory, hex encoded.
+	pub call_data: String,
+}
+
+impl Ceremony {
+	/// Loads a ceremony package from a file.
+	///
+	/// # Arguments
+	/// * `path` - The path of the ceremony package.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+	}
+
+	/// Saves the ceremony package to a file, to be shared with the signatories.
+	///
+	/// # Arguments
+	/// * `path` - The path of the ceremony package.
+	pub fn save(&self, path: &Path) -> Result<(), Error> {
+		fs::write(path, serde_json::to_string_pretty(self)?)?;
+		Ok(())
+	}
+
+	/// The signatories which have not yet approved the call.
+	pub fn pending(&self) -> Vec<&String> {
+		self.signatories.iter().filter(|s| !self.approvals.contains(s)).collect()
+	}
+
+	/// Whether the call has been approved by the required number of signatories.
+	pub fn is_approved(&self) -> bool {
+		self.approvals.len() >= self.threshold as usize
+	}
+
+	/// Whether the ceremony has expired.
+	///
+	/// # Arguments
+	/// * `now` - The current time, as seconds since the Unix epoch.
+	pub fn is_expired(&self, now: u64) -> bool {
+		now >= self.expires_at
+	}
+
+	/// Merges the approvals observed on-chain into the ceremony package, ignoring any account
+	/// which is not a signatory. Returns the signatories whose approvals are new.
+	///
+	/// # Arguments
+	/// * `observed` - The approvals observed on-chain.
+	pub fn merge(&mut self, observed: &ObservedApprovals) -> Vec<String> {
+		self.timepoint = Some(observed.timepoint);
+		let mut merged = vec![];
+		for approval in &observed.approvals {
+			if self.signatories.contains(approval) && !self.approvals.contains(approval) {
+				self.approvals.push(approval.clone());
+				merged.push(approval.clone());
+			}
+		}
+		merged
+	}
+
+	/// Observes the approvals of the call on-chain. Returns `None` if the call has not been
+	/// approved by any signatory, or has already been executed.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	pub async fn observe(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+	) -> Result<Option<ObservedApprovals>, Error> {
+		let multisig = parse_account(&self.multisig)?;
+		let call_hash = decode(&self.call_hash)?;
+		let keys = vec![Value::from_bytes(multisig.0), Value::from_bytes(call_hash)];
+		let Some(value) = fetch_storage(client, "Multisig", "Multisigs", keys).await? else {
+			return Ok(None);
+		};
+		let number = |field: &str| {
+			value
+				.at("when")
+				.at(field)
+				.and_then(|v| v.as_u128())
+				.map(|v| v as u32)
+				.ok_or_else(|| {
+					Error::CeremonyError(format!("unable to decode the `{field}` of the timepoint"))
+				})
+		};
+		let timepoint = Timepoint { height: number("height")?, index: number("index")? };
+		let approvals = match value.at("approvals").map(|a| &a.value) {
+			Some(ValueDef::Composite(approvals)) => approvals
+				.values()
+				.filter_map(account_from_value)
+				.map(|a| a.to_string())
+				.collect(),
+			_ => return Err(Error::CeremonyError("unable to decode the approvals".into())),
+		};
+		Ok(Some(ObservedApprovals { timepoint, approvals }))
+	}
+
+	/// The instructions for each pending signatory to approve the call. The final approval
+	/// executes the call, whereas earlier approvals only approve its hash.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	pub fn instructions(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+	) -> Result<Vec<Instruction>, Error> {
+		let signatories = self
+			.signatories
+			.iter()
+			.map(|s| parse_account(s))
+			.collect::<Result<Vec<_>, _>>()?;
+		let executes = self.approvals.len() + 1 >= self.threshold as usize;
+		let call = if executes { Some(self.call(client)?) } else { None };
+		let call_hash = decode(&self.call_hash)?;
+		let mut instructions = vec![];
+		for (signatory, account) in self.signatories.iter().zip(&signatories) {
+			if self.approvals.contains(signatory) {
+				continue;
+			}
+			let others: Vec<_> = signatories.iter().filter(|s| *s != account).cloned().collect();
+			let payload = match &call {
+				Some(call) =>
+					as_multi(self.threshold, &others, self.timepoint, call.clone(), self.max_weight),
+				None => approve_as_multi(
+					self.threshold,
+					&others,
+					self.timepoint,
+					&call_hash,
+					self.max_weight,
+				),
+			};
+			instructions.push(Instruction {
+				signatory: signatory.clone(),
+				executes,
+				call_data: to_hex(client.tx().call_data(&payload)?),
+			});
+		}
+		Ok(instructions)
+	}
+
+	/// Decodes the call to be dispatched by the multisig.
+	fn call(&self, client: &OnlineClient<SubstrateConfig>) -> Result<Value, Error> {
+		let metadata = client.metadata();
+		let call_data = decode(&self.call_data)?;
+		let call = subxt::ext::scale_value::scale::decode_as_type(
+			&mut &call_data[..],
+			metadata.outer_enums().call_enum_ty(),
+			metadata.types(),
+		)
+		.map_err(|e| Error::CeremonyError(format!("unable to decode the call: {e}")))?;
+		Ok(call.remove_context())
+	}
+}
+
+/// Prepares a ceremony package for a privileged call to be dispatched by a multisig, such as a
+/// call wrapped within `sudo.sudo` where the sudo key is a multisig.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `call` - The call to be dispatched by the multisig.
+/// * `signatories` - The signatories of the multisig.
+/// * `threshold` - The number of approvals required.
+/// * `expires_at` - When the ceremony expires, as seconds since the Unix epoch.
+pub async fn prepare_ceremony(
+	client: &OnlineClient<SubstrateConfig>,
+	call: DynamicPayload,
+	signatories: &[String],
+	threshold: u16,
+	expires_at: u64,
+) -> Result<Ceremony, Error> {
+	let mut accounts =
+		signatories.iter().map(|s| parse_account(s)).collect::<Result<Vec<_>, _>>()?;
+	accounts.sort_by_key(|a| a.0);
+	accounts.dedup();
+	if threshold < 2 || threshold as usize > accounts.len() {
+		return Err(Error::CeremonyError(format!(
+			"the threshold must be between 2 and the number of signatories ({})",
+			accounts.len()
+		)));
+	}
+	let call_data = client.tx().call_data(&call)?;
+	let max_weight = query_weight(client, call.into_value(), call_data.len() as u32).await?;
+	Ok(Ceremony {
+		call_hash: to_hex(BlakeTwo256::hash(&call_data)),
+		call_data: to_hex(&call_data),
+		max_weight,
+		multisig: multisig_account(&accounts, threshold).to_string(),
+		threshold,
+		signatories: accounts.iter().map(|a| a.to_string()).collect(),
+		expires_at,
+		timepoint: None,
+		approvals: vec![],
+	})
+}
+
+/// Derives the account of a multisig.
+///
+/// # Arguments
+/// * `signatories` - The signatories of the multisig, sorted.
+/// * `threshold` - The number of approvals required.
+pub fn multisig_account(signatories: &[AccountId32], threshold: u16) -> AccountId32 {
+	let signatories: Vec<[u8; 32]> = signatories.iter().map(|s| s.0).collect();
+	let entropy = (MULTISIG_PREFIX, signatories, threshold).encode();
+	AccountId32(BlakeTwo256::hash(&entropy).0)
+}
+
+/// Queries the weight of a call.
+async fn query_weight(
+	client: &OnlineClient<SubstrateConfig>,
+	call: Value,
+	len: u32,
+) -> Result<Weight, Error> {
+	let payload = subxt::dynamic::runtime_api_call(
+		"TransactionPaymentCallApi",
+		"query_call_info",
+		vec![call, Value::u128(len as u128)],
+	);
+	let info = client
+		.runtime_api()
+		.at_latest()
+		.await?
+		.call(payload)
+		.await?
+		.to_value()
+		.map_err(subxt::Error::from)?;
+	let field = |name: &str| {
+		info.at("weight")
+			.at(name)
+			.and_then(|v| v.as_u128())
+			.map(|v| v as u64)
+			.ok_or_else(|| {
+				Error::CeremonyError(format!("unable to determine the `{name}` of the call"))
+			})
+	};
+	Ok(Weight { ref_time: field("ref_time")?, proof_size: field("proof_size")? })
+}
+
+/// Constructs a call approving the hash of a multisig call.
+fn approve_as_multi(
+	threshold: u16,
+	others: &[AccountId32],
+	timepoint: Option<Timepoint>,
+	call_hash: &[u8],
+	max_weight: Weight,
+) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Multisig",
+		"approve_as_multi",
+		vec![
+			Value::u128(threshold as u128),
+			accounts_value(others),
+			timepoint_value(timepoint),
+			Value::from_bytes(call_hash),
+			weight_value(max_weight),
+		],
+	)
+}
+
+/// Constructs a call approving a multisig call, executing it if the threshold is reached.
+fn as_multi(
+	threshold: u16,
+	others: &[AccountId32],
+	timepoint: Option<Timepoint>,
+	call: Value,
+	max_weight: Weight,
+) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Multisig",
+		"as_multi",
+		vec![
+			Value::u128(threshold as u128),
+			accounts_value(others),
+			timepoint_value(timepoint),
+			call,
+			weight_value(max_weight),
+		],
+	)
+}
+
+fn accounts_value(accounts: &[AccountId32]) -> Value {
+	Value::unnamed_composite(accounts.iter().map(|a| Value::from_bytes(a.0)))
+}
+
+fn timepoint_value(timepoint: Option<Timepoint>) -> Value {
+	match timepoint {
+		Some(Timepoint { height, index }) => Value::unnamed_variant(
+			"Some",
+			[Value::named_composite([
+				("height", Value::u128(height as u128)),
+				("index", Value::u128(index as u128)),
+			])],
+		),
+		None => Value::unnamed_variant("None", []),
+	}
+}
+
+fn weight_value(weight: Weight) -> Value {
+	Value::named_composite([
+		("ref_time", Value::u128(weight.ref_time as u128)),
+		("proof_size", Value::u128(weight.proof_size as u128)),
+	])
+}
+
+/// Extracts an account from a decoded value, whose bytes may be nested within composites.
+fn account_from_value<T>(value: &Value<T>) -> Option<AccountId32> {
+	fn bytes<T>(value: &Value<T>, out: &mut Vec<u8>) {
+		match &value.value {
+			ValueDef::Composite(composite) => composite.values().for_each(|v| bytes(v, out)),
+			_ =>
+				if let Some(byte) = value.as_u128() {
+					out.push(byte as u8)
+				},
+		}
+	}
+	let mut out = vec![];
+	bytes(value, &mut out);
+	<[u8; 32]>::try_from(out).ok().map(AccountId32)
+}
+
+fn decode(hex: &str) -> Result<Vec<u8>, Error> {
+	decode_hex(hex).ok_or_else(|| Error::CeremonyError(format!("invalid hex: {hex}")))
+}
+
+fn parse_account(account: &str) -> Result<AccountId32, Error> {
+	pop_common::parse_account(account)
+		.map(AccountId32)
+		.map_err(|e| Error::InvalidAccount(format!("{account}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+	const CHARLIE: &str = "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y";
+
+	fn ceremony() -> Ceremony {
+		Ceremony {
+			call_data: "0x0000".into(),
+			call_hash: to_hex(BlakeTwo256::hash(&[0, 0])),
+			max_weight: Weight::default(),
+			multisig: ALICE.into(),
+			threshold: 2,
+			signatories: vec![ALICE.into(), BOB.into(), CHARLIE.into()],
+			expires_at: 1_000,
+			timepoint: None,
+			approvals: vec![],
+		}
+	}
+
+	#[test]
+	fn multisig_account_works() -> Result<()> {
+		let mut signatories =
+			vec![parse_account(ALICE)?, parse_account(BOB)?, parse_account(CHARLIE)?];
+		signatories.sort_by_key(|a| a.0);
+		// The 2-of-3 multisig of Alice, Bob and Charlie, as derived by the multisig pallet.
+		assert_eq!(
+			multisig_account(&signatories, 2).to_string(),
+			"5DjYJStmdZ2rcqXbXGX7TW85JsrW6uG4y9MUcLq2BoPMpRA7"
+		);
+		assert_ne!(multisig_account(&signatories, 1), multisig_account(&signatories, 2));
+		Ok(())
+	}
+
+	#[test]
+	fn merge_works() {
+		let mut ceremony = ceremony();
+		assert_eq!(ceremony.pending().len(), 3);
+		let observed = ObservedApprovals {
+			timepoint: Timepoint { height: 10, index: 1 },
+			approvals: vec![BOB.into(), "5DjYJStmdZ2rcqXbXGX7TW85JsrW6uG4y9MUcLq2BoPMpRA7".into()],
+		};
+		assert_eq!(ceremony.merge(&observed), vec![BOB.to_string()]);
+		assert_eq!(ceremony.timepoint, Some(Timepoint { height: 10, index: 1 }));
+		assert!(ceremony.merge(&observed).is_empty());
+		assert_eq!(ceremony.pending(), vec![ALICE, CHARLIE]);
+		assert!(!ceremony.is_approved());
+		ceremony.merge(&ObservedApprovals { approvals: vec![CHARLIE.into()], ..observed });
+		assert!(ceremony.is_approved());
+	}
+
+	#[test]
+	fn save_and_load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("ceremony.json");
+		ceremony().save(&path)?;
+		assert_eq!(Ceremony::load(&path)?, ceremony());
+		assert!(!ceremony().is_expired(999));
+		assert!(ceremony().is_expired(1_000));
+		Ok(())
+	}
+
+	#[test]
+	fn account_from_value_works() -> Result<()> {
+		let account = parse_account(ALICE)?;
+		let value = Value::unnamed_composite([Value::from_bytes(account.0)]);
+		assert_eq!(account_from_value(&value), Some(account));
+		assert_eq!(account_from_value(&Value::from_bytes([1u8; 4])), None);
+		Ok(())
+	}
+
+	#[test]
+	fn approve_as_multi_works() -> Result<()> {
+		let call = approve_as_multi(
+			2,
+			&[parse_account(BOB)?],
+			Some(Timepoint { height: 1, index: 2 }),
+			&[0u8; 32],
+			Weight { ref_time: 3, proof_size: 4 },
+		);
+		assert_eq!(call.pallet_name(), "Multisig");
+		assert_eq!(call.call_name(), "approve_as_multi");
+		let value = call.into_value();
+		assert_eq!(
+			value.at(0).at(2),
+			Some(&timepoint_value(Some(Timepoint { height: 1, index: 2 })))
+		);
+		Ok(())
+	}
+}
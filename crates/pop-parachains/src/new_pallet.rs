@@ -1,9 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0
 
 use std::{
-	fs::{create_dir, create_dir_all, File},
-	path::{Path, PathBuf},
+	fs::{create_dir, create_dir_all, read_to_string, write, File},
+	path::{Component, Path, PathBuf},
 };
+use toml_edit::{value, InlineTable, Item, TableLike};
 
 pub mod new_pallet_options;
 
@@ -15,10 +16,14 @@ use crate::{
 		PalletSimpleBenchmarking, PalletSimpleLib, PalletSimpleMock, PalletSimpleTests,
 		PalletTestsUtils, PalletTryState, PalletTypes, PalletWeights,
 	},
+	presets::read_manifest,
 	utils::helpers::sanitize,
-	TemplatePalletConfigCommonTypes, TemplatePalletStorageTypes,
+	PalletIndices, TemplatePalletConfigCommonTypes, TemplatePalletStorageTypes,
 };
 
+/// The features of a runtime forwarded to the pallets added to it.
+const FORWARDED_FEATURES: [&str; 3] = ["std", "runtime-benchmarks", "try-runtime"];
+
 /// Metadata for the Template Pallet.
 #[derive(Debug)]
 pub struct TemplatePalletConfig {
@@ -155,6 +160,101 @@ fn render_pallet(config: TemplatePalletConfig, pallet_path: &Path) -> Result<(),
 	Ok(())
 }
 
+/// Adds a generated pallet to a runtime: as a dependency of the runtime, which forwards its
+/// features to the pallet, and declared within the runtime at the lowest free index at or above a
+/// given index. The `Config` of the pallet remains to be implemented for the runtime. Returns the
+/// name of the pallet within the runtime and its index.
+///
+/// # Arguments
+/// * `pallet` - The directory of the generated pallet.
+/// * `runtime` - The directory of the runtime.
+/// * `from` - The index from which to search for a free index.
+pub fn add_pallet_to_runtime(
+	pallet: &Path,
+	runtime: &Path,
+	from: u8,
+) -> Result<(String, u8), Error> {
+	// The crate of the pallet is named as by its template (`pallet-^^name|lower^^`).
+	let pallet_name = pallet
+		.file_name()
+		.and_then(|name| name.to_str())
+		.ok_or(Error::PathError)?
+		.replace('-', "_");
+	let krate = format!("pallet-{}", pallet_name.to_lowercase());
+	let name: String = pallet_name
+		.split('_')
+		.flat_map(|part| {
+			let mut chars = part.chars();
+			chars.next().map(|first| first.to_uppercase().chain(chars))
+		})
+		.flatten()
+		.collect();
+
+	let lib = runtime.join("src/lib.rs");
+	let source = read_to_string(&lib)
+		.map_err(|_| Error::Config(format!("expected the runtime at {}", lib.display())))?;
+	let mut pallets = PalletIndices::parse(&source)?;
+	let (source, index) = pallets.declare(&source, &name, &krate.replace('-', "_"), from)?;
+
+	let manifest_path = runtime.join("Cargo.toml");
+	let mut manifest = read_manifest(&manifest_path)?;
+	let dependencies = manifest
+		.get_mut("dependencies")
+		.and_then(Item::as_table_like_mut)
+		.ok_or_else(|| {
+			Error::Config(format!(
+				"no dependencies are declared within {}",
+				manifest_path.display()
+			))
+		})?;
+	if !dependencies.contains_key(&krate) {
+		let path = relative_path(runtime, pallet)?;
+		let mut dependency = InlineTable::new();
+		dependency.insert("path", path.to_string_lossy().as_ref().into());
+		dependency.insert("default-features", false.into());
+		dependencies.insert(&krate, value(dependency));
+	}
+	if let Some(features) = manifest.get_mut("features").and_then(Item::as_table_like_mut) {
+		forward_features(features, &krate);
+	}
+
+	write(&manifest_path, manifest.to_string())?;
+	write(&lib, source)?;
+	Ok((name, index))
+}
+
+/// Forwards the features of a runtime to a pallet, where declared by the runtime.
+///
+/// # Arguments
+/// * `features` - The features of the runtime.
+/// * `krate` - The crate of the pallet.
+fn forward_features(features: &mut dyn TableLike, krate: &str) {
+	for feature in FORWARDED_FEATURES {
+		let Some(feature_list) = features.get_mut(feature).and_then(Item::as_array_mut) else {
+			continue;
+		};
+		let forwarded = format!("{krate}/{feature}");
+		if !feature_list.iter().any(|f| f.as_str() == Some(&forwarded)) {
+			feature_list.push(forwarded);
+		}
+	}
+}
+
+/// The path of a directory relative to another.
+///
+/// # Arguments
+/// * `from` - The directory from which the path is relative.
+/// * `to` - The directory to which the path leads.
+fn relative_path(from: &Path, to: &Path) -> Result<PathBuf, Error> {
+	let (from, to) = (from.canonicalize()?, to.canonicalize()?);
+	let from: Vec<_> = from.components().collect();
+	let to: Vec<_> = to.components().collect();
+	let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+	let mut path: PathBuf = (common..from.len()).map(|_| Component::ParentDir).collect();
+	path.extend(&to[common..]);
+	Ok(path)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -466,4 +566,40 @@ mod tests {
 		// Assert that the result is an error
 		assert!(result.is_err(), "Result should be an error");
 	}
+
+	#[test]
+	fn add_pallet_to_runtime_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+		let runtime = temp_dir.path().join("runtime");
+		let pallet = temp_dir.path().join("pallets/my-pallet");
+		create_dir_all(runtime.join("src"))?;
+		create_dir_all(&pallet)?;
+		write(
+			runtime.join("Cargo.toml"),
+			"[package]\nname = \"runtime\"\n\n[dependencies]\npallet-balances = { version = \"39.0.0\", default-features = false }\n\n[features]\nstd = [\"pallet-balances/std\"]\ntry-runtime = []\n",
+		)?;
+		write(
+			runtime.join("src/lib.rs"),
+			"#[frame_support::runtime]\nmod runtime {\n\t#[runtime::pallet_index(0)]\n\tpub type System = frame_system;\n\t#[runtime::pallet_index(50)]\n\tpub type Template = pallet_template;\n}\n",
+		)?;
+
+		assert_eq!(add_pallet_to_runtime(&pallet, &runtime, 50)?, ("MyPallet".into(), 51));
+		let source = read_to_string(runtime.join("src/lib.rs"))?;
+		assert!(source.contains(
+			"\t#[runtime::pallet_index(51)]\n\tpub type MyPallet = pallet_my_pallet;\n}"
+		));
+		let manifest = read_to_string(runtime.join("Cargo.toml"))?;
+		assert!(manifest.contains(
+			"pallet-my_pallet = { path = \"../pallets/my-pallet\", default-features = false }"
+		));
+		assert!(manifest.contains("std = [\"pallet-balances/std\", \"pallet-my_pallet/std\"]"));
+		assert!(manifest.contains("try-runtime = [\"pallet-my_pallet/try-runtime\"]"));
+		assert!(!manifest.contains("runtime-benchmarks"));
+
+		// Adding the pallet again leaves the runtime unchanged.
+		assert_eq!(add_pallet_to_runtime(&pallet, &runtime, 50)?, ("MyPallet".into(), 51));
+		assert_eq!(read_to_string(runtime.join("src/lib.rs"))?, source);
+		assert_eq!(read_to_string(runtime.join("Cargo.toml"))?, manifest);
+		Ok(())
+	}
 }
@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Parachain;
+use duct::cmd;
+use pop_common::{i18n::message, templates::Template};
+use serde::Serialize;
+use std::{fs::read_to_string, path::Path};
+use toml_edit::{DocumentMut, Item};
+
+/// The documentation of pop.
+const DOCS: &str = "https://learn.onpop.io";
+/// The documentation of the runtime templates of OpenZeppelin.
+const OPENZEPPELIN_DOCS: &str = "https://docs.openzeppelin.com/substrate-runtimes";
+
+/// The outcome of a check run on a freshly generated parachain project.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PostInstallCheck {
+	/// What was checked.
+	pub name: String,
+	/// Whether the check passed.
+	pub passed: bool,
+	/// How a failure can be resolved.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub remedy: Option<String>,
+}
+
+/// A step to be taken after generating a parachain.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NextStep {
+	/// What the step achieves.
+	pub description: String,
+	/// The command to be run.
+	pub command: String,
+}
+
+/// The RPC port of a node of the local network of a template.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NodePort {
+	/// The name of the node.
+	pub node: String,
+	/// The RPC port of the node.
+	pub rpc_port: u16,
+}
+
+/// The onboarding checklist of a generated parachain, as structured data which can be presented
+/// by the CLI or an IDE alike.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct NextSteps {
+	/// The commands to be run, in order.
+	pub commands: Vec<NextStep>,
+	/// Links to relevant documentation.
+	pub docs: Vec<String>,
+	/// The RPC ports of the nodes of the local network, where fixed by its configuration.
+	pub ports: Vec<NodePort>,
+}
+
+/// Checks that a generated parachain can be built: that the toolchains required by the template
+/// are installed and, optionally, that the project compiles.
+///
+/// # Arguments
+/// * `template` - The template from which the parachain was generated.
+/// * `project` - The directory of the generated parachain.
+/// * `build` - Whether to check that the project compiles (via `cargo check`), which may take some
+///   time.
+pub fn post_install_checks(
+	template: &Parachain,
+	project: &Path,
+	build: bool,
+) -> Vec<PostInstallCheck> {
+	let mut checks = vec![
+		check_output(
+			&message("onboarding.check_wasm_target", &[]),
+			"rustup",
+			&["target", "list", "--installed"],
+			"wasm32-unknown-unknown",
+			"rustup target add wasm32-unknown-unknown",
+		),
+		check_output(
+			&message("onboarding.check_rust_src", &[]),
+			"rustup",
+			&["component", "list", "--installed"],
+			"rust-src",
+			"rustup component add rust-src",
+		),
+		check_output(
+			&message("onboarding.check_protoc", &[]),
+			"protoc",
+			&["--version"],
+			"libprotoc",
+			&message("onboarding.remedy_protoc", &[]),
+		),
+		check_output(
+			&message("onboarding.check_clang", &[]),
+			"clang",
+			&["--version"],
+			"clang",
+			&message("onboarding.remedy_clang", &[]),
+		),
+	];
+	if build {
+		let output = cmd("cargo", ["check", "--workspace"])
+			.dir(project)
+			.env("SKIP_WASM_BUILD", "1")
+			.stderr_capture()
+			.unchecked()
+			.run();
+		checks.push(PostInstallCheck {
+			name: message("onboarding.check_compiles", &[("template", template.name())]),
+			passed: output.as_ref().is_ok_and(|o| o.status.success()),
+			remedy: match output {
+				Ok(output) if output.status.success() => None,
+				Ok(output) => Some(tail(&String::from_utf8_lossy(&output.stderr))),
+				Err(e) => Some(e.to_string()),
+			},
+		});
+	}
+	checks
+}
+
+/// The next steps to be taken after generating a parachain from a template.
+///
+/// # Arguments
+/// * `template` - The template from which the parachain was generated.
+/// * `project` - The directory of the generated parachain.
+pub fn next_steps(template: &Parachain, project: &Path) -> NextSteps {
+	let step = |key: &str, command: String| NextStep { description: message(key, &[]), command };
+	let mut commands = vec![
+		step("onboarding.step_enter", format!("cd {}", project.display())),
+		step("onboarding.step_build", "pop build --release".into()),
+	];
+	let mut ports = vec![];
+	if let Some(network_config) = template.network_config() {
+		let command = format!("pop up parachain -f {network_config}");
+		commands.push(step("onboarding.step_launch", command));
+		ports = read_to_string(project.join(network_config))
+			.ok()
+			.and_then(|config| config.parse::<DocumentMut>().ok())
+			.map(|config| node_ports(config.as_item()))
+			.unwrap_or_default();
+	}
+	if matches!(template, Parachain::Contracts | Parachain::ParityContracts) {
+		commands.push(step("onboarding.step_new_contract", "pop new contract my_contract".into()));
+		commands.push(step(
+			"onboarding.step_deploy_contract",
+			"pop up contract -p ./my_contract".into(),
+		));
+	}
+	let mut docs = vec![DOCS.to_string()];
+	if *template == Parachain::OpenZeppelinGeneric {
+		docs.push(OPENZEPPELIN_DOCS.to_string());
+	}
+	if let Ok(repository) = template.repository_url() {
+		docs.push(repository.to_string());
+	}
+	NextSteps { commands, docs, ports }
+}
+
+/// Runs a command, checking that its output contains the expected text.
+fn check_output(
+	name: &str,
+	program: &str,
+	args: &[&str],
+	expected: &str,
+	remedy: &str,
+) -> PostInstallCheck {
+	let passed = cmd(program, args)
+		.stderr_null()
+		.read()
+		.is_ok_and(|output| output.contains(expected));
+	PostInstallCheck {
+		name: name.to_string(),
+		passed,
+		remedy: (!passed).then(|| remedy.to_string()),
+	}
+}
+
+/// The nodes within a network configuration whose RPC port is fixed.
+fn node_ports(item: &Item) -> Vec<NodePort> {
+	let mut ports = vec![];
+	if let Some(table) = item.as_table_like() {
+		let name = table.get("name").and_then(|n| n.as_str());
+		let rpc_port = table.get("rpc_port").and_then(|p| p.as_integer());
+		if let (Some(node), Some(rpc_port)) = (name, rpc_port.and_then(|p| u16::try_from(p).ok())) {
+			ports.push(NodePort { node: node.to_string(), rpc_port });
+		}
+		for (_, value) in table.iter() {
+			ports.extend(node_ports(value));
+		}
+	} else if let Some(tables) = item.as_array_of_tables() {
+		for table in tables.iter() {
+			ports.extend(node_ports(&Item::Table(table.clone())));
+		}
+	}
+	ports
+}
+
+/// The last lines of the output of a command, which usually contain its errors.
+fn tail(output: &str) -> String {
+	let lines: Vec<_> = output.trim().lines().collect();
+	lines[lines.len().saturating_sub(5)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::write;
+
+	#[test]
+	fn check_output_works() {
+		assert_eq!(
+			check_output("echo", "echo", &["pop"], "pop", "install echo"),
+			PostInstallCheck { name: "echo".into(), passed: true, remedy: None }
+		);
+		assert_eq!(
+			check_output("echo", "echo", &["pop"], "polkadot", "install echo").remedy,
+			Some("install echo".into())
+		);
+		assert!(!check_output("missing", "pop-missing-binary", &[], "", "").passed);
+	}
+
+	#[test]
+	fn next_steps_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path();
+		write(
+			project.join("network.toml"),
+			"[relaychain]\nchain = \"paseo-local\"\n\n[[relaychain.nodes]]\nname = \"alice\"\nrpc_port = 8833\n\n[[relaychain.nodes]]\nname = \"bob\"\n\n[[parachains]]\nid = 2000\n\n[[parachains.collators]]\nname = \"collator-01\"\nrpc_port = 9944\n",
+		)?;
+		let steps = next_steps(&Parachain::Contracts, project);
+		assert_eq!(
+			steps.commands.iter().map(|s| s.command.as_str()).collect::<Vec<_>>(),
+			[
+				format!("cd {}", project.display()).as_str(),
+				"pop build --release",
+				"pop up parachain -f ./network.toml",
+				"pop new contract my_contract",
+				"pop up contract -p ./my_contract",
+			]
+		);
+		assert_eq!(steps.docs, [DOCS, "https://github.com/r0gue-io/contracts-parachain"]);
+		assert_eq!(
+			steps.ports,
+			[
+				NodePort { node: "alice".into(), rpc_port: 8833 },
+				NodePort { node: "collator-01".into(), rpc_port: 9944 }
+			]
+		);
+
+		let steps = next_steps(&Parachain::OpenZeppelinGeneric, project);
+		assert_eq!(steps.commands.len(), 3);
+		assert!(steps.ports.is_empty());
+		assert!(steps.docs.contains(&OPENZEPPELIN_DOCS.to_string()));
+		Ok(())
+	}
+
+	#[test]
+	fn tail_works() {
+		assert_eq!(tail("1\n2\n3\n4\n5\n6\n"), "2\n3\n4\n5\n6");
+		assert_eq!(tail("error"), "error");
+	}
+}
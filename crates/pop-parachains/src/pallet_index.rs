@@ -0,0 +1,542 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+	fs::read_to_string,
+	path::Path,
+};
+
+/// A pallet of a runtime, along with the index at which it is declared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PalletIndex {
+	/// The name of the pallet within the runtime (e.g. `Balances`).
+	pub name: String,
+	/// The index of the pallet, used to encode its calls, events and errors.
+	pub index: u8,
+	/// The line of the runtime at which the pallet is declared.
+	pub line: usize,
+}
+
+/// An index declared for more than one pallet of a runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexCollision {
+	/// The index.
+	pub index: u8,
+	/// The names of the pallets declared at the index.
+	pub pallets: Vec<String>,
+}
+
+impl Display for IndexCollision {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "index {} is declared for {}", self.index, self.pallets.join(", "))
+	}
+}
+
+/// A pallet whose index has changed between two versions of a runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Renumbering {
+	/// The name of the pallet.
+	pub pallet: String,
+	/// The index of the pallet within the previous version of the runtime.
+	pub from: u8,
+	/// The index of the pallet within the current version of the runtime.
+	pub to: u8,
+}
+
+impl Display for Renumbering {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "`{}` was renumbered from {} to {}", self.pallet, self.from, self.to)
+	}
+}
+
+/// The registry of the pallet indices of a runtime, as declared within either
+/// `construct_runtime!` or a `#[frame_support::runtime]` module.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PalletIndices {
+	pallets: Vec<PalletIndex>,
+	/// The offset of the brace closing the pallets declared within `construct_runtime!`.
+	close: Option<usize>,
+}
+
+impl PalletIndices {
+	/// Loads the pallet indices declared by a runtime.
+	///
+	/// # Arguments
+	/// * `path` - The path to the runtime: either its crate directory or the source file declaring
+	///   its pallets.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let path = match path.is_dir() {
+			true => path.join("src").join("lib.rs"),
+			false => path.to_path_buf(),
+		};
+		Self::parse(&read_to_string(path)?)
+	}
+
+	/// Parses the pallet indices declared within the source of a runtime.
+	///
+	/// # Arguments
+	/// * `source` - The source declaring the pallets of the runtime.
+	pub fn parse(source: &str) -> Result<Self, Error> {
+		let source = strip_comments(source);
+		if source.contains(PALLET_INDEX) {
+			Ok(Self { pallets: parse_runtime_module(&source)?, close: None })
+		} else if let Some(start) = source.find(CONSTRUCT_RUNTIME) {
+			let (pallets, close) = parse_construct_runtime(&source, start)?;
+			Ok(Self { pallets, close: Some(close) })
+		} else {
+			Err(Error::Config(
+				"no `construct_runtime!` or `#[frame_support::runtime]` declaration found".into(),
+			))
+		}
+	}
+
+	/// The pallets of the runtime, in the order in which they are declared.
+	pub fn pallets(&self) -> &[PalletIndex] {
+		&self.pallets
+	}
+
+	/// The index of a pallet, if declared.
+	///
+	/// # Arguments
+	/// * `name` - The name of the pallet within the runtime.
+	pub fn index_of(&self, name: &str) -> Option<u8> {
+		self.pallets.iter().find(|p| p.name == name).map(|p| p.index)
+	}
+
+	/// Whether an index is not declared for any pallet.
+	///
+	/// # Arguments
+	/// * `index` - The index.
+	pub fn is_free(&self, index: u8) -> bool {
+		!self.pallets.iter().any(|p| p.index == index)
+	}
+
+	/// The lowest free index at or above a given index, allowing related pallets to be grouped
+	/// within a range of indices (e.g. governance pallets from 40).
+	///
+	/// # Arguments
+	/// * `from` - The index from which to search.
+	pub fn next_free(&self, from: u8) -> Option<u8> {
+		(from..=u8::MAX).find(|index| self.is_free(*index))
+	}
+
+	/// Checks that a pallet can be added to the runtime at an index, without colliding with any
+	/// pallet already declared.
+	///
+	/// # Arguments
+	/// * `name` - The name of the pallet within the runtime.
+	/// * `index` - The index at which the pallet is to be added.
+	pub fn check(&self, name: &str, index: u8) -> Result<(), Error> {
+		if let Some(index) = self.index_of(name) {
+			return Err(Error::Config(format!(
+				"a pallet named `{name}` is already declared at index {index}"
+			)));
+		}
+		if let Some(pallet) = self.pallets.iter().find(|p| p.index == index) {
+			return Err(Error::Config(format!(
+				"index {index} is already declared for `{}`",
+				pallet.name
+			)));
+		}
+		Ok(())
+	}
+
+	/// Declares a pallet within the source of the runtime from which the indices were parsed, after
+	/// the last pallet declared and at the lowest free index at or above a given index. Returns the
+	/// updated source and the index of the pallet, with the source unchanged should the pallet
+	/// already be declared. A trailing comma is added to the last pallet declared within
+	/// `construct_runtime!` where missing.
+	///
+	/// # Arguments
+	/// * `source` - The source declaring the pallets of the runtime.
+	/// * `name` - The name of the pallet within the runtime (e.g. `Proxy`).
+	/// * `pallet` - The path of the pallet (e.g. `pallet_proxy`).
+	/// * `from` - The index from which to search for a free index.
+	pub fn declare(
+		&mut self,
+		source: &str,
+		name: &str,
+		pallet: &str,
+		from: u8,
+	) -> Result<(String, u8), Error> {
+		if let Some(index) = self.index_of(name) {
+			return Ok((source.to_string(), index));
+		}
+		let index = self
+			.next_free(from)
+			.ok_or_else(|| Error::Config(format!("no free index for `{name}` from {from}")))?;
+		let last = self
+			.pallets
+			.last()
+			.ok_or_else(|| Error::Config("no pallets are declared by the runtime".into()))?;
+		let unable = || Error::Config(format!("unable to declare `{name}` after `{}`", last.name));
+		let start = last.line - 1;
+		let (mut lines, end) = match self.close {
+			// The declaration of the last pallet ends with `;` within a `#[frame_support::runtime]`
+			// module.
+			None => {
+				let lines: Vec<_> = source.lines().map(String::from).collect();
+				let end = (start..lines.len())
+					.find(|i| strip_comments(&lines[*i]).trim_end().ends_with(';'))
+					.ok_or_else(unable)?;
+				(lines, end)
+			},
+			// The declaration of the last pallet within `construct_runtime!` ends at the last
+			// code preceding the closing brace, which is followed by a comma unless the pallet
+			// is the last entry without a trailing comma.
+			Some(close) => {
+				let content = strip_comments(&source[..close]).trim_end().len();
+				let end = line(source, content) - 1;
+				if end < start || end + 1 == line(source, close) {
+					return Err(unable());
+				}
+				let mut source = source.to_string();
+				if !source[..content].ends_with(',') {
+					source.insert(content, ',');
+				}
+				(source.lines().map(String::from).collect(), end)
+			},
+		};
+		let indent: String = lines[start].chars().take_while(|c| c.is_whitespace()).collect();
+		let declaration = match self.close {
+			None => vec![
+				format!("{indent}#[runtime::pallet_index({index})]"),
+				format!("{indent}pub type {name} = {pallet};"),
+			],
+			Some(_) => vec![format!("{indent}{name}: {pallet} = {index},")],
+		};
+		lines.splice(end + 1..end + 1, declaration);
+		let mut source = lines.join("\n");
+		source.push('\n');
+		*self = Self::parse(&source)?;
+		Ok((source, index))
+	}
+
+	/// The indices declared for more than one pallet, which fail to compile.
+	pub fn collisions(&self) -> Vec<IndexCollision> {
+		let mut pallets: BTreeMap<u8, Vec<String>> = BTreeMap::new();
+		for pallet in &self.pallets {
+			pallets.entry(pallet.index).or_default().push(pallet.name.clone());
+		}
+		pallets
+			.into_iter()
+			.filter(|(_, pallets)| pallets.len() > 1)
+			.map(|(index, pallets)| IndexCollision { index, pallets })
+			.collect()
+	}
+
+	/// The pallets whose indices differ from those within a previous version of the runtime,
+	/// which breaks the encoding of their calls, events and errors across a runtime upgrade.
+	///
+	/// # Arguments
+	/// * `previous` - The pallet indices of the previous version of the runtime.
+	pub fn renumbered(&self, previous: &Self) -> Vec<Renumbering> {
+		self.pallets
+			.iter()
+			.filter_map(|pallet| {
+				let from = previous.index_of(&pallet.name)?;
+				(from != pallet.index).then(|| Renumbering {
+					pallet: pallet.name.clone(),
+					from,
+					to: pallet.index,
+				})
+			})
+			.collect()
+	}
+}
+
+/// The attribute declaring the index of a pallet within a `#[frame_support::runtime]` module.
+const PALLET_INDEX: &str = "pallet_index(";
+/// The macro declaring the pallets of a runtime.
+const CONSTRUCT_RUNTIME: &str = "construct_runtime!";
+
+/// Parses the pallets declared within a `#[frame_support::runtime]` module, such as:
+/// `#[runtime::pallet_index(10)] pub type Balances = pallet_balances;`.
+fn parse_runtime_module(source: &str) -> Result<Vec<PalletIndex>, Error> {
+	let mut pallets = vec![];
+	for (start, _) in source.match_indices(PALLET_INDEX) {
+		let rest = &source[start + PALLET_INDEX.len()..];
+		let end = rest.find(')').ok_or_else(|| invalid_index(&source[start..]))?;
+		let index = rest[..end].trim().parse().map_err(|_| invalid_index(&rest[..end]))?;
+		// The pallet is the type alias following the attribute, after any further attributes.
+		let name = rest[end..]
+			.split_whitespace()
+			.skip_while(|token| *token != "type")
+			.nth(1)
+			.map(|token| token.trim_end_matches(|c: char| !is_identifier(c)))
+			.filter(|name| !name.is_empty())
+			.ok_or_else(|| Error::Config(format!("expected a pallet following index {index}")))?;
+		pallets.push(PalletIndex { name: name.to_string(), index, line: line(source, start) });
+	}
+	Ok(pallets)
+}
+
+/// Parses the pallets declared within `construct_runtime!`, such as `Balances: pallet_balances =
+/// 10,`, where pallets without an explicit index follow the index of the preceding pallet.
+///
+/// Returns the pallets alongside the offset of the brace closing their declarations.
+fn parse_construct_runtime(source: &str, start: usize) -> Result<(Vec<PalletIndex>, usize), Error> {
+	let invalid = || Error::Config("invalid `construct_runtime!` declaration".into());
+	// The pallets are declared within the braces of the runtime enum, following any `where` clause.
+	let open = source[start..]
+		.find("enum")
+		.and_then(|enum_| source[start + enum_..].find('{').map(|brace| start + enum_ + brace))
+		.ok_or_else(invalid)?;
+	let mut pallets: Vec<PalletIndex> = vec![];
+	let (mut depth, mut entry) = (0usize, open + 1);
+	for (offset, c) in source[open + 1..].char_indices() {
+		let position = open + 1 + offset;
+		match c {
+			'{' | '(' | '[' | '<' => depth += 1,
+			'}' | ')' | ']' | '>' if depth > 0 => depth -= 1,
+			',' | '}' if depth == 0 => {
+				let previous = pallets.last().map(|p| p.index);
+				if let Some(pallet) =
+					parse_entry(source, entry, &source[entry..position], previous)?
+				{
+					pallets.push(pallet);
+				}
+				if c == '}' {
+					return Ok((pallets, position));
+				}
+				entry = position + 1;
+			},
+			_ => {},
+		}
+	}
+	Err(invalid())
+}
+
+/// Parses a pallet declared within `construct_runtime!`.
+fn parse_entry(
+	source: &str,
+	start: usize,
+	entry: &str,
+	previous: Option<u8>,
+) -> Result<Option<PalletIndex>, Error> {
+	let mut declaration = entry.trim_start();
+	// Skip any attributes of the pallet, such as `#[cfg(feature = "...")]`.
+	while let Some(attribute) = declaration.strip_prefix("#[") {
+		let end = attribute.find(']').map(|end| end + 1).unwrap_or(attribute.len());
+		declaration = attribute[end..].trim_start();
+	}
+	let Some((name, pallet)) = declaration.split_once(':') else { return Ok(None) };
+	let name = name.trim();
+	let index = match pallet.rsplit_once('=').filter(|(_, index)| !index.contains(['}', '>'])) {
+		Some((_, index)) => index.trim().parse().map_err(|_| invalid_index(index.trim()))?,
+		None => match previous {
+			Some(previous) => previous.checked_add(1).ok_or_else(|| invalid_index(name))?,
+			None => 0,
+		},
+	};
+	let offset = start + (entry.len() - declaration.len());
+	Ok(Some(PalletIndex { name: name.to_string(), index, line: line(source, offset) }))
+}
+
+fn invalid_index(index: &str) -> Error {
+	Error::Config(format!("invalid pallet index: {index}"))
+}
+
+fn is_identifier(c: char) -> bool {
+	c.is_alphanumeric() || c == '_'
+}
+
+/// The line at an offset within the source.
+fn line(source: &str, offset: usize) -> usize {
+	source[..offset].matches('\n').count() + 1
+}
+
+/// Replaces comments with whitespace, preserving the offsets and lines of the remaining source.
+fn strip_comments(source: &str) -> String {
+	let mut stripped = String::with_capacity(source.len());
+	let mut chars = source.chars().peekable();
+	while let Some(c) = chars.next() {
+		match (c, chars.peek()) {
+			('/', Some('/')) => {
+				stripped.push(' ');
+				while let Some(c) = chars.next_if(|c| *c != '\n') {
+					stripped.push_str(&" ".repeat(c.len_utf8()));
+				}
+			},
+			('/', Some('*')) => {
+				stripped.push(' ');
+				let mut previous = ' ';
+				for c in chars.by_ref() {
+					match c {
+						'\n' => stripped.push('\n'),
+						c => stripped.push_str(&" ".repeat(c.len_utf8())),
+					}
+					if previous == '*' && c == '/' {
+						break;
+					}
+					previous = c;
+				}
+			},
+			(c, _) => stripped.push(c),
+		}
+	}
+	stripped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CONSTRUCT_RUNTIME: &str = r#"
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = opaque::Block,
+	{
+		// System support stuff.
+		System: frame_system = 0,
+		ParachainSystem: cumulus_pallet_parachain_system::{Pallet, Call, Storage, Event<T>} = 1,
+		Timestamp: pallet_timestamp,
+
+		/* Monetary stuff. */
+		Balances: pallet_balances = 10,
+		#[cfg(feature = "runtime-benchmarks")]
+		Benchmarks: pallet_benchmarks,
+		Sudo: pallet_sudo = 11,
+	}
+);
+"#;
+
+	const RUNTIME_MODULE: &str = r#"
+#[frame_support::runtime]
+mod runtime {
+	#[runtime::runtime]
+	#[runtime::derive(RuntimeCall, RuntimeEvent)]
+	pub struct Runtime;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system;
+	// #[runtime::pallet_index(1)]
+	// pub type Disabled = pallet_disabled;
+	#[runtime::pallet_index(10)]
+	#[runtime::disable_call]
+	pub type Balances = pallet_balances;
+	#[runtime::pallet_index( 40 )]
+	pub type Sudo = pallet_sudo::Pallet<Runtime>;
+}
+"#;
+
+	fn indices(pallets: &PalletIndices) -> Vec<(&str, u8, usize)> {
+		pallets.pallets().iter().map(|p| (p.name.as_str(), p.index, p.line)).collect()
+	}
+
+	#[test]
+	fn parse_works() -> Result<(), Error> {
+		let pallets = PalletIndices::parse(CONSTRUCT_RUNTIME)?;
+		assert_eq!(
+			indices(&pallets),
+			[
+				("System", 0, 8),
+				("ParachainSystem", 1, 9),
+				("Timestamp", 2, 10),
+				("Balances", 10, 13),
+				("Benchmarks", 11, 15),
+				("Sudo", 11, 16),
+			]
+		);
+		let pallets = PalletIndices::parse(RUNTIME_MODULE)?;
+		assert_eq!(indices(&pallets), [("System", 0, 8), ("Balances", 10, 12), ("Sudo", 40, 15)]);
+		assert!(matches!(PalletIndices::parse("fn main() {}"), Err(Error::Config(_))));
+		assert!(matches!(
+			PalletIndices::parse(
+				"construct_runtime!(pub enum Runtime { System: frame_system = 256 });"
+			),
+			Err(Error::Config(_))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn collisions_works() -> Result<(), Error> {
+		let pallets = PalletIndices::parse(CONSTRUCT_RUNTIME)?;
+		let collisions = pallets.collisions();
+		assert_eq!(
+			collisions,
+			[IndexCollision { index: 11, pallets: vec!["Benchmarks".into(), "Sudo".into()] }]
+		);
+		assert_eq!(collisions[0].to_string(), "index 11 is declared for Benchmarks, Sudo");
+		assert!(PalletIndices::parse(RUNTIME_MODULE)?.collisions().is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn next_free_works() -> Result<(), Error> {
+		let pallets = PalletIndices::parse(RUNTIME_MODULE)?;
+		assert!(!pallets.is_free(10));
+		assert_eq!(pallets.next_free(0), Some(1));
+		assert_eq!(pallets.next_free(40), Some(41));
+		assert_eq!(pallets.index_of("Balances"), Some(10));
+		assert!(pallets.check("Assets", 11).is_ok());
+		assert!(matches!(pallets.check("Balances", 11), Err(Error::Config(_))));
+		assert!(matches!(pallets.check("Assets", 40), Err(Error::Config(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn declare_works() -> Result<(), Error> {
+		let mut pallets = PalletIndices::parse(RUNTIME_MODULE)?;
+		let (source, index) = pallets.declare(RUNTIME_MODULE, "Proxy", "pallet_proxy", 40)?;
+		assert_eq!(index, 41);
+		assert!(source.ends_with(
+			"\tpub type Sudo = pallet_sudo::Pallet<Runtime>;\n\t#[runtime::pallet_index(41)]\n\tpub type Proxy = pallet_proxy;\n}\n"
+		));
+		assert_eq!(pallets.index_of("Proxy"), Some(41));
+		// Pallets already declared are left unchanged.
+		assert_eq!(pallets.declare(&source, "Proxy", "pallet_proxy", 0)?, (source.clone(), 41));
+
+		let mut pallets = PalletIndices::parse(CONSTRUCT_RUNTIME)?;
+		let (source, index) =
+			pallets.declare(CONSTRUCT_RUNTIME, "Multisig", "pallet_multisig", 0)?;
+		assert_eq!(index, 3);
+		assert!(
+			source.contains("\t\tSudo: pallet_sudo = 11,\n\t\tMultisig: pallet_multisig = 3,\n\t}")
+		);
+		assert_eq!(PalletIndices::parse(&source)?.index_of("Multisig"), Some(3));
+		Ok(())
+	}
+
+	#[test]
+	fn declare_adds_missing_trailing_comma() -> Result<(), Error> {
+		let runtime = format!(
+			"{}pub type Executive = frame_executive::Executive<\n\tRuntime,\n\tBlock,\n>;\n",
+			CONSTRUCT_RUNTIME.replace("Sudo: pallet_sudo = 11,", "Sudo: pallet_sudo = 11 // Sudo.")
+		);
+		let mut pallets = PalletIndices::parse(&runtime)?;
+		let (source, index) = pallets.declare(&runtime, "Multisig", "pallet_multisig", 0)?;
+		assert_eq!(index, 3);
+		assert!(source.contains(
+			"\t\tSudo: pallet_sudo = 11, // Sudo.\n\t\tMultisig: pallet_multisig = 3,\n\t}"
+		));
+		assert!(source.ends_with("<\n\tRuntime,\n\tBlock,\n>;\n"));
+		assert_eq!(pallets.index_of("Multisig"), Some(3));
+		assert_eq!(pallets.index_of("Sudo"), Some(11));
+
+		// The closing brace following the last pallet on the same line is not supported.
+		let runtime =
+			"construct_runtime!(\n\tpub enum Runtime {\n\t\tSystem: frame_system = 0 }\n);\n";
+		assert!(matches!(
+			PalletIndices::parse(runtime)?.declare(runtime, "Sudo", "pallet_sudo", 0),
+			Err(Error::Config(e)) if e == "unable to declare `Sudo` after `System`"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn renumbered_works() -> Result<(), Error> {
+		let previous = PalletIndices::parse(RUNTIME_MODULE)?;
+		let current =
+			PalletIndices::parse(&RUNTIME_MODULE.replace("pallet_index(10)", "pallet_index(20)"))?;
+		let renumbered = current.renumbered(&previous);
+		assert_eq!(renumbered, [Renumbering { pallet: "Balances".into(), from: 10, to: 20 }]);
+		assert_eq!(renumbered[0].to_string(), "`Balances` was renumbered from 10 to 20");
+		assert!(previous.renumbered(&previous).is_empty());
+		Ok(())
+	}
+}
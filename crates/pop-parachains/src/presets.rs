@@ -0,0 +1,692 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	generator::parachain::{BlockTime, ProxyMultisig, XcmTopology},
+	utils::helpers::write_to_file,
+	Error, PalletIndices,
+};
+use askama::Template;
+use pop_common::find_workspace_toml;
+use std::{
+	fs::{read_to_string, write},
+	path::{Path, PathBuf},
+};
+use strum_macros::{AsRefStr, Display, EnumMessage, EnumString, VariantArray};
+use toml_edit::{value, Array, DocumentMut, Item, TableLike};
+
+/// The parachain ID of Asset Hub, on Polkadot, Kusama and their testnets alike.
+const ASSET_HUB_PARA_ID: u32 = 1000;
+/// The umbrella crate of the Polkadot SDK, through whose features a runtime may depend on pallets.
+const POLKADOT_SDK: &str = "polkadot-sdk";
+/// The pallet whose declaration as a dependency of a runtime is mirrored by the pallets added.
+const BALANCES: &str = "pallet-balances";
+/// The pallets added by the `proxy-multisig` preset: their names within the runtime, crates and
+/// paths.
+const PROXY_MULTISIG_PALLETS: [(&str, &str, &str); 2] =
+	[("Proxy", "pallet-proxy", "pallet_proxy"), ("Multisig", "pallet-multisig", "pallet_multisig")];
+/// The index from which the pallets of the `proxy-multisig` preset are declared.
+const PROXY_MULTISIG_INDEX: u8 = 40;
+
+/// Vetted configurations of a runtime, which can be applied to a runtime generated by pop rather
+/// than copied from other repositories. Presets sharing a module are alternatives of each other.
+#[derive(
+	AsRefStr, Clone, Debug, Display, EnumMessage, EnumString, Eq, Hash, PartialEq, VariantArray,
+)]
+pub enum RuntimePreset {
+	/// XCM configuration trusting only the relay chain, as the reserve of its native asset.
+	#[strum(
+		serialize = "xcm-relay",
+		message = "XCM: relay chain",
+		detailed_message = "Trust the relay chain as the reserve of its native asset."
+	)]
+	XcmRelay,
+	/// XCM configuration trusting both the relay chain and Asset Hub as reserves.
+	#[strum(
+		serialize = "xcm-asset-hub",
+		message = "XCM: relay chain and Asset Hub",
+		detailed_message = "Trust the relay chain and Asset Hub as the reserves of the native asset of the relay chain, and Asset Hub as that of its assets."
+	)]
+	XcmAssetHub,
+	/// Block time, weight and fee settings for 6s blocks, using asynchronous backing.
+	#[strum(
+		serialize = "block-time-6s",
+		message = "6s blocks",
+		detailed_message = "Block time, weight and fee settings for 6s blocks, using asynchronous backing."
+	)]
+	BlockTime6s,
+	/// Block time, weight and fee settings for 12s blocks, without asynchronous backing.
+	#[strum(
+		serialize = "block-time-12s",
+		message = "12s blocks",
+		detailed_message = "Block time, weight and fee settings for 12s blocks, without asynchronous backing."
+	)]
+	BlockTime12s,
+	/// Proxy and multisig pallets, with deposits proportional to the storage used.
+	#[strum(
+		serialize = "proxy-multisig",
+		message = "Proxies and multisigs",
+		detailed_message = "Configure `pallet_proxy` and `pallet_multisig`, with deposits proportional to the storage used."
+	)]
+	ProxyMultisig,
+}
+
+impl RuntimePreset {
+	/// The name of the module of the runtime configuration to which the preset is written.
+	pub fn module(&self) -> &'static str {
+		match self {
+			RuntimePreset::XcmRelay | RuntimePreset::XcmAssetHub => "xcm_topology",
+			RuntimePreset::BlockTime6s | RuntimePreset::BlockTime12s => "block_time",
+			RuntimePreset::ProxyMultisig => "proxy_multisig",
+		}
+	}
+
+	/// Renders the source of the module of the preset.
+	///
+	/// # Arguments
+	/// * `runtime` - The dependencies of the runtime to which the preset is applied.
+	pub fn render(&self, runtime: &RuntimeDependencies) -> String {
+		let name = self.as_ref().to_string();
+		let polkadot_sdk = runtime.polkadot_sdk;
+		match self {
+			RuntimePreset::XcmRelay | RuntimePreset::XcmAssetHub => {
+				let asset_hub = *self == RuntimePreset::XcmAssetHub;
+				XcmTopology {
+					name,
+					polkadot_sdk,
+					description: match asset_hub {
+						true => "using Asset Hub as the reserve of assets".into(),
+						false => "only connected to its relay chain".into(),
+					},
+					asset_hub,
+					asset_hub_para_id: ASSET_HUB_PARA_ID,
+				}
+				.render()
+			},
+			RuntimePreset::BlockTime6s => BlockTime {
+				name,
+				polkadot_sdk,
+				millisecs_per_block: 6_000,
+				max_block_ref_time: "2 seconds".into(),
+				max_block_ref_time_expr: "WEIGHT_REF_TIME_PER_SECOND.saturating_mul(2)".into(),
+				unincluded_segment_capacity: 3,
+			}
+			.render(),
+			RuntimePreset::BlockTime12s => BlockTime {
+				name,
+				polkadot_sdk,
+				millisecs_per_block: 12_000,
+				max_block_ref_time: "0.5 seconds".into(),
+				max_block_ref_time_expr: "WEIGHT_REF_TIME_PER_SECOND.saturating_div(2)".into(),
+				unincluded_segment_capacity: 1,
+			}
+			.render(),
+			RuntimePreset::ProxyMultisig =>
+				ProxyMultisig { name, polkadot_sdk, codec: runtime.codec.clone() }.render(),
+		}
+		.expect("infallible")
+	}
+}
+
+/// How a runtime depends on the crates used by presets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeDependencies {
+	/// Whether the runtime depends on the Polkadot SDK via its umbrella crate (`polkadot-sdk`),
+	/// through which the crates are then imported.
+	pub polkadot_sdk: bool,
+	/// The name by which the runtime imports `parity-scale-codec` (e.g. `codec`).
+	pub codec: String,
+}
+
+impl Default for RuntimeDependencies {
+	fn default() -> Self {
+		Self { polkadot_sdk: false, codec: "codec".into() }
+	}
+}
+
+impl RuntimeDependencies {
+	/// Determines the dependencies of a runtime from its manifest.
+	///
+	/// # Arguments
+	/// * `manifest` - The manifest of the runtime.
+	fn from_manifest(manifest: &DocumentMut) -> Self {
+		let Some(dependencies) = manifest.get("dependencies").and_then(Item::as_table_like) else {
+			return Self::default();
+		};
+		let codec = dependencies
+			.iter()
+			.find(|(key, dependency)| {
+				*key == "parity-scale-codec" ||
+					dependency.get("package").and_then(Item::as_str) ==
+						Some("parity-scale-codec")
+			})
+			.map_or_else(|| "codec".into(), |(key, _)| key.replace('-', "_"));
+		Self { polkadot_sdk: dependencies.contains_key(POLKADOT_SDK), codec }
+	}
+}
+
+/// Applies a preset to a runtime, writing its module within the configuration of the runtime
+/// (`src/configs`) and declaring it, unless already declared. Any module of an alternative preset
+/// is replaced. The runtime is then integrated with the module:
+/// - block time presets replace the corresponding constants of the runtime (`src/lib.rs`), which
+///   re-exports those of the module in their place;
+/// - the `proxy-multisig` preset adds the pallets to the manifest of the runtime, along with their
+///   features, and declares them within the runtime.
+///
+/// Returns the path of the module.
+///
+/// # Arguments
+/// * `preset` - The preset to be applied.
+/// * `runtime` - The directory of the runtime.
+pub fn apply_runtime_preset(preset: &RuntimePreset, runtime: &Path) -> Result<PathBuf, Error> {
+	let configs = runtime.join("src/configs");
+	let declarations = configs.join("mod.rs");
+	if !declarations.exists() {
+		return Err(Error::Config(format!(
+			"expected the configuration of the runtime at {}",
+			declarations.display()
+		)));
+	}
+	let manifest_path = runtime.join("Cargo.toml");
+	let mut manifest = read_manifest(&manifest_path)?;
+	let dependencies = RuntimeDependencies::from_manifest(&manifest);
+	let lib = runtime.join("src/lib.rs");
+	let source = read_to_string(&lib)
+		.map_err(|_| Error::Config(format!("expected the runtime at {}", lib.display())))?;
+
+	let rendered = preset.render(&dependencies);
+	let integrated = match preset {
+		RuntimePreset::XcmRelay | RuntimePreset::XcmAssetHub => source.clone(),
+		RuntimePreset::BlockTime6s | RuntimePreset::BlockTime12s =>
+			reexport_constants(&source, &rendered, preset.module())?,
+		RuntimePreset::ProxyMultisig => {
+			let crates: Vec<_> = PROXY_MULTISIG_PALLETS.iter().map(|(_, c, _)| *c).collect();
+			add_pallet_dependencies(&mut manifest, &manifest_path, &crates)?;
+			let mut source = define_milliunit(&source)?;
+			let mut pallets = PalletIndices::parse(&source)?;
+			for (name, _, pallet) in PROXY_MULTISIG_PALLETS {
+				source = pallets.declare(&source, name, pallet, PROXY_MULTISIG_INDEX)?.0;
+			}
+			source
+		},
+	};
+
+	let module = configs.join(format!("{}.rs", preset.module()));
+	write_to_file(&module, &rendered)?;
+	let contents = read_to_string(&declarations)?;
+	if let Some(contents) = declare(&contents, preset.module()) {
+		write(&declarations, contents)?;
+	}
+	if integrated != source {
+		write(&lib, integrated)?;
+	}
+	if manifest.to_string() != read_to_string(&manifest_path)? {
+		write(&manifest_path, manifest.to_string())?;
+	}
+	Ok(module)
+}
+
+/// Reads a manifest.
+pub(crate) fn read_manifest(path: &Path) -> Result<DocumentMut, Error> {
+	read_to_string(path)
+		.map_err(|_| Error::Config(format!("expected a manifest at {}", path.display())))?
+		.parse()
+		.map_err(|e| Error::Config(format!("invalid manifest at {}: {e}", path.display())))
+}
+
+/// Adds pallets to the dependencies of a runtime: as features of the umbrella crate of the
+/// Polkadot SDK when depended upon, otherwise as dependencies declared like `pallet-balances`
+/// (including within the workspace when inherited from it), whose features they are added to.
+///
+/// # Arguments
+/// * `manifest` - The manifest of the runtime.
+/// * `path` - The path of the manifest.
+/// * `crates` - The crates of the pallets.
+fn add_pallet_dependencies(
+	manifest: &mut DocumentMut,
+	path: &Path,
+	crates: &[&str],
+) -> Result<(), Error> {
+	let invalid = |e: &str| Error::Config(format!("{e} within {}", path.display()));
+	let dependencies = manifest
+		.get_mut("dependencies")
+		.and_then(Item::as_table_like_mut)
+		.ok_or_else(|| invalid("no dependencies are declared"))?;
+	if let Some(polkadot_sdk) = dependencies.get_mut(POLKADOT_SDK) {
+		let polkadot_sdk = polkadot_sdk
+			.as_table_like_mut()
+			.ok_or_else(|| invalid("`polkadot-sdk` is expected to be declared as a table"))?;
+		if !polkadot_sdk.contains_key("features") {
+			polkadot_sdk.insert("features", value(Array::new()));
+		}
+		let features = polkadot_sdk
+			.get_mut("features")
+			.and_then(Item::as_array_mut)
+			.ok_or_else(|| invalid("the features of `polkadot-sdk` are expected to be an array"))?;
+		for krate in crates {
+			if !features.iter().any(|f| f.as_str() == Some(krate)) {
+				features.push(*krate);
+			}
+		}
+		return Ok(());
+	}
+
+	let balances = dependencies
+		.get(BALANCES)
+		.cloned()
+		.ok_or_else(|| invalid("`pallet-balances` is not declared as a dependency"))?;
+	let inherited = balances.get("workspace").and_then(Item::as_bool) == Some(true);
+	if inherited {
+		let workspace_path = path
+			.parent()
+			.and_then(find_workspace_toml)
+			.ok_or_else(|| invalid("`pallet-balances` is inherited from an unknown workspace"))?;
+		let mut workspace = read_manifest(&workspace_path)?;
+		let dependencies = workspace
+			.get_mut("workspace")
+			.and_then(|w| w.get_mut("dependencies"))
+			.and_then(Item::as_table_like_mut)
+			.ok_or_else(|| invalid("no workspace dependencies are declared"))?;
+		mirror_dependency(dependencies, crates, &workspace_path)?;
+		write(&workspace_path, workspace.to_string())?;
+	}
+	mirror_dependency(dependencies, crates, path)?;
+
+	// Forward the features of the runtime to the pallets, as they are to `pallet-balances`.
+	if let Some(features) = manifest.get_mut("features").and_then(Item::as_table_like_mut) {
+		for (_, feature) in features.iter_mut() {
+			let Some(feature) = feature.as_array_mut() else { continue };
+			let forwarded: Vec<String> = feature
+				.iter()
+				.filter_map(|f| f.as_str()?.strip_prefix(&format!("{BALANCES}/")).map(String::from))
+				.collect();
+			for krate in crates {
+				for forwarded in &forwarded {
+					let forwarded = format!("{krate}/{forwarded}");
+					if !feature.iter().any(|f| f.as_str() == Some(&forwarded)) {
+						feature.push(forwarded);
+					}
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Declares dependencies like `pallet-balances` is declared, unless already declared. Only
+/// declarations sourcing it from a git repository, or inheriting it from the workspace, can be
+/// mirrored, as the versions of the pallets otherwise differ.
+///
+/// # Arguments
+/// * `dependencies` - The dependencies of a manifest.
+/// * `crates` - The dependencies to be declared.
+/// * `path` - The path of the manifest.
+fn mirror_dependency(
+	dependencies: &mut dyn TableLike,
+	crates: &[&str],
+	path: &Path,
+) -> Result<(), Error> {
+	let balances = dependencies.get(BALANCES).cloned().ok_or_else(|| {
+		Error::Config(format!("`{BALANCES}` is not declared within {}", path.display()))
+	})?;
+	if !["git", "workspace"].iter().any(|key| balances.get(key).is_some()) {
+		return Err(Error::Config(format!(
+			"`{BALANCES}` is not sourced from a git repository within {}, so the version of {} cannot be determined",
+			path.display(),
+			crates.join(" and ")
+		)));
+	}
+	for krate in crates {
+		if !dependencies.contains_key(krate) {
+			dependencies.insert(krate, balances.clone());
+		}
+	}
+	Ok(())
+}
+
+/// Defines `MILLIUNIT` within the source of a runtime, following its definition of `UNIT`,
+/// unless already defined.
+///
+/// # Arguments
+/// * `source` - The source of the runtime.
+fn define_milliunit(source: &str) -> Result<String, Error> {
+	if source.lines().any(|line| defines(line, "MILLIUNIT")) {
+		return Ok(source.to_string());
+	}
+	let mut lines: Vec<_> = source.lines().map(String::from).collect();
+	let unit = lines
+		.iter()
+		.position(|line| defines(line, "UNIT") && line.trim_end().ends_with(';'))
+		.ok_or_else(|| Error::Config("expected the runtime to define `UNIT`".into()))?;
+	lines.insert(unit + 1, "pub const MILLIUNIT: Balance = UNIT / 1_000;".into());
+	Ok(lines.join("\n") + "\n")
+}
+
+/// Replaces the definitions of constants within the source of a runtime with a re-export of those
+/// of a preset, declared after the configuration of the runtime (`mod configs;`).
+///
+/// # Arguments
+/// * `source` - The source of the runtime.
+/// * `preset` - The source of the module of the preset.
+/// * `module` - The name of the module of the preset.
+fn reexport_constants(source: &str, preset: &str, module: &str) -> Result<String, Error> {
+	let constants: Vec<_> = preset
+		.lines()
+		.filter_map(|line| line.strip_prefix("pub const ")?.split(':').next())
+		.collect();
+	let mut lines: Vec<String> = source.lines().map(String::from).collect();
+	for constant in constants {
+		let Some(start) = lines.iter().position(|line| defines(line, constant)) else {
+			continue;
+		};
+		// The definition ends with the first `;` outside of any brackets.
+		let mut depth = 0i32;
+		let end = (start..lines.len())
+			.find(|i| {
+				lines[*i].chars().any(|c| {
+					match c {
+						'(' | '[' | '{' => depth += 1,
+						')' | ']' | '}' => depth -= 1,
+						';' if depth == 0 => return true,
+						_ => {},
+					}
+					false
+				})
+			})
+			.ok_or_else(|| Error::Config(format!("invalid definition of `{constant}`")))?;
+		// Remove the documentation of the constant along with its definition.
+		let start = lines[..start]
+			.iter()
+			.rposition(|line| !line.trim_start().starts_with("///"))
+			.map_or(0, |i| i + 1);
+		lines.drain(start..=end);
+	}
+	let reexport = format!("pub use configs::{module}::*;");
+	if !lines.iter().any(|line| line.trim() == reexport) {
+		let configs = lines
+			.iter()
+			.position(|line| matches!(line.trim(), "mod configs;" | "pub mod configs;"))
+			.ok_or_else(|| {
+				Error::Config(
+					"expected the runtime to declare its configuration (`mod configs;`)".into(),
+				)
+			})?;
+		lines.insert(configs + 1, reexport);
+	}
+	Ok(lines.join("\n") + "\n")
+}
+
+/// Whether a line of source defines a constant.
+///
+/// # Arguments
+/// * `line` - The line of source.
+/// * `constant` - The name of the constant.
+fn defines(line: &str, constant: &str) -> bool {
+	let line = line.trim_start();
+	let line = line.strip_prefix("pub ").unwrap_or(line);
+	line.strip_prefix("const ")
+		.and_then(|l| l.strip_prefix(constant))
+		.is_some_and(|l| l.trim_start().starts_with(':'))
+}
+
+/// Declares a module, after the last module declared, unless already declared. Returns `None`
+/// when the module is already declared.
+///
+/// # Arguments
+/// * `contents` - The contents of the module in which the declaration is added.
+/// * `module` - The name of the module to be declared.
+fn declare(contents: &str, module: &str) -> Option<String> {
+	let is_declaration = |line: &str, module: Option<&str>| {
+		let line = line.trim().strip_prefix("pub ").unwrap_or(line.trim());
+		let Some(declared) = line.strip_prefix("mod ").and_then(|l| l.strip_suffix(';')) else {
+			return false;
+		};
+		module.is_none_or(|module| declared.trim() == module)
+	};
+	let lines: Vec<_> = contents.lines().collect();
+	if lines.iter().any(|line| is_declaration(line, Some(module))) {
+		return None;
+	}
+	let declaration = format!("pub mod {module};");
+	let mut lines: Vec<_> = lines.into_iter().map(String::from).collect();
+	match lines.iter().rposition(|line| is_declaration(line, None)) {
+		Some(last) => lines.insert(last + 1, declaration),
+		None => lines.push(declaration),
+	}
+	Some(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::create_dir_all;
+	use strum::VariantArray;
+
+	const MANIFEST: &str = r#"[package]
+name = "parachain-template-runtime"
+
+[dependencies]
+codec = { workspace = true, features = ["derive"] }
+pallet-balances = { workspace = true }
+pallet-sudo = { workspace = true }
+
+[features]
+default = ["std"]
+std = ["codec/std", "pallet-balances/std", "pallet-sudo/std"]
+runtime-benchmarks = ["pallet-balances/runtime-benchmarks"]
+try-runtime = ["pallet-balances/try-runtime", "pallet-sudo/try-runtime"]
+"#;
+
+	const WORKSPACE: &str = r#"[workspace]
+members = ["runtime"]
+
+[workspace.dependencies]
+codec = { version = "3.6.12", default-features = false, package = "parity-scale-codec" }
+pallet-balances = { git = "https://github.com/paritytech/polkadot-sdk", branch = "stable2409", default-features = false }
+"#;
+
+	const RUNTIME: &str = r#"#![cfg_attr(not(feature = "std"), no_std)]
+
+mod configs;
+
+pub type Balance = u128;
+
+/// Unit = the base number of indivisible units for balances
+pub const UNIT: Balance = 1_000_000_000_000;
+
+/// This determines the average expected block time that we are targeting.
+pub const MILLISECS_PER_BLOCK: u64 = 12000;
+
+pub const SLOT_DURATION: u64 = MILLISECS_PER_BLOCK;
+
+/// We allow for 0.5 of a second of compute with a 12 second average block time.
+const MAXIMUM_BLOCK_WEIGHT: Weight = Weight::from_parts(
+	WEIGHT_REF_TIME_PER_SECOND.saturating_div(2),
+	cumulus_primitives_core::relay_chain::MAX_POV_SIZE as u64,
+);
+
+#[frame_support::runtime]
+mod runtime {
+	#[runtime::runtime]
+	pub struct Runtime;
+
+	#[runtime::pallet_index(10)]
+	pub type Balances = pallet_balances;
+	#[runtime::pallet_index(15)]
+	pub type Sudo = pallet_sudo;
+}
+"#;
+
+	/// Creates a workspace containing a runtime, returning the directory of the runtime.
+	fn runtime(workspace: &Path, manifest: &str) -> Result<PathBuf, Error> {
+		let runtime = workspace.join("runtime");
+		create_dir_all(runtime.join("src/configs"))?;
+		write(workspace.join("Cargo.toml"), WORKSPACE)?;
+		write(runtime.join("Cargo.toml"), manifest)?;
+		write(runtime.join("src/lib.rs"), RUNTIME)?;
+		write(runtime.join("src/configs/mod.rs"), "mod xcm_config;\n")?;
+		Ok(runtime)
+	}
+
+	#[test]
+	fn render_works() {
+		let runtime = RuntimeDependencies::default();
+		for preset in RuntimePreset::VARIANTS {
+			let source = preset.render(&runtime);
+			assert!(source.starts_with(&format!(
+				"// Generated by pop: the `{}` runtime preset.",
+				preset.as_ref()
+			)));
+			assert!(!source.contains("^^"));
+			assert!(!source.contains("use polkadot_sdk::*;"));
+			let polkadot_sdk = RuntimeDependencies { polkadot_sdk: true, ..Default::default() };
+			assert!(preset.render(&polkadot_sdk).contains("\nuse polkadot_sdk::*;\n"));
+		}
+		assert!(RuntimePreset::BlockTime6s
+			.render(&runtime)
+			.contains("pub const MILLISECS_PER_BLOCK: u64 = 6000;"));
+		assert!(RuntimePreset::BlockTime12s
+			.render(&runtime)
+			.contains("pub const UNINCLUDED_SEGMENT_CAPACITY: u32 = 1;"));
+		let relay = RuntimePreset::XcmRelay.render(&runtime);
+		assert!(relay.contains("pub type TrustedReserves = RelayReserve;"));
+		assert!(!relay.contains("AssetHub"));
+		assert!(RuntimePreset::XcmAssetHub
+			.render(&runtime)
+			.contains("pub type TrustedReserves = (RelayReserve, AssetHubReserve);"));
+		let codec =
+			RuntimeDependencies { codec: "parity_scale_codec".into(), ..Default::default() };
+		assert!(RuntimePreset::ProxyMultisig
+			.render(&codec)
+			.contains("use parity_scale_codec::{Decode, Encode, MaxEncodedLen};"));
+	}
+
+	#[test]
+	fn declare_works() {
+		assert_eq!(
+			declare("mod xcm_config;\n\nuse frame_support::derive_impl;\n", "block_time"),
+			Some(
+				"mod xcm_config;\npub mod block_time;\n\nuse frame_support::derive_impl;\n".into()
+			)
+		);
+		assert_eq!(declare("pub mod block_time;\n", "block_time"), None);
+		assert_eq!(
+			declare("// Configs\n", "block_time"),
+			Some("// Configs\npub mod block_time;\n".into())
+		);
+	}
+
+	#[test]
+	fn apply_runtime_preset_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(matches!(
+			apply_runtime_preset(&RuntimePreset::BlockTime6s, &temp_dir.path().join("runtime")),
+			Err(Error::Config(_))
+		));
+		let runtime = runtime(temp_dir.path(), MANIFEST)?;
+		let module = apply_runtime_preset(&RuntimePreset::BlockTime6s, &runtime)?;
+		assert_eq!(module, runtime.join("src/configs/block_time.rs"));
+		// Alternatives replace each other.
+		apply_runtime_preset(&RuntimePreset::BlockTime12s, &runtime)?;
+		assert!(read_to_string(&module)?.contains("pub const MILLISECS_PER_BLOCK: u64 = 12000;"));
+		assert_eq!(
+			read_to_string(runtime.join("src/configs/mod.rs"))?,
+			"mod xcm_config;\npub mod block_time;\n"
+		);
+		// The modules of all presets are valid Rust, as formatted on being written.
+		for preset in RuntimePreset::VARIANTS {
+			apply_runtime_preset(preset, &runtime)?;
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn apply_block_time_preset_replaces_constants() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let runtime = runtime(temp_dir.path(), MANIFEST)?;
+		apply_runtime_preset(&RuntimePreset::BlockTime6s, &runtime)?;
+		apply_runtime_preset(&RuntimePreset::BlockTime6s, &runtime)?;
+		let lib = read_to_string(runtime.join("src/lib.rs"))?;
+		assert!(lib.starts_with(
+			"#![cfg_attr(not(feature = \"std\"), no_std)]\n\nmod configs;\npub use configs::block_time::*;\n\npub type Balance = u128;\n"
+		));
+		assert_eq!(lib.matches("pub use configs::block_time::*;").count(), 1);
+		for constant in ["MILLISECS_PER_BLOCK", "SLOT_DURATION", "MAXIMUM_BLOCK_WEIGHT"] {
+			assert!(!lib.contains(&format!("const {constant}:")));
+		}
+		assert!(!lib.contains("average expected block time") && !lib.contains("MAX_POV_SIZE"));
+		assert!(lib.contains("pub const UNIT: Balance = 1_000_000_000_000;"));
+		Ok(())
+	}
+
+	#[test]
+	fn apply_proxy_multisig_preset_adds_pallets() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let runtime = runtime(temp_dir.path(), MANIFEST)?;
+		apply_runtime_preset(&RuntimePreset::ProxyMultisig, &runtime)?;
+		// Applying the preset again changes nothing further.
+		apply_runtime_preset(&RuntimePreset::ProxyMultisig, &runtime)?;
+
+		let manifest = read_to_string(runtime.join("Cargo.toml"))?;
+		assert!(manifest.contains(
+			"pallet-proxy = { workspace = true }\npallet-multisig = { workspace = true }\n"
+		));
+		assert!(manifest.contains(
+			r#"std = ["codec/std", "pallet-balances/std", "pallet-sudo/std", "pallet-proxy/std", "pallet-multisig/std"]"#
+		));
+		assert!(manifest.contains(
+			r#"runtime-benchmarks = ["pallet-balances/runtime-benchmarks", "pallet-proxy/runtime-benchmarks", "pallet-multisig/runtime-benchmarks"]"#
+		));
+		assert!(manifest.contains(r#""pallet-multisig/try-runtime"]"#));
+		let workspace = read_to_string(temp_dir.path().join("Cargo.toml"))?;
+		assert!(workspace.contains(
+			r#"pallet-proxy = { git = "https://github.com/paritytech/polkadot-sdk", branch = "stable2409", default-features = false }"#
+		));
+		assert_eq!(workspace.matches("pallet-multisig = ").count(), 1);
+
+		let lib = read_to_string(runtime.join("src/lib.rs"))?;
+		assert!(lib.contains(
+			"pub const UNIT: Balance = 1_000_000_000_000;\npub const MILLIUNIT: Balance = UNIT / 1_000;\n"
+		));
+		assert!(lib.ends_with(
+			"\tpub type Sudo = pallet_sudo;\n\t#[runtime::pallet_index(40)]\n\tpub type Proxy = pallet_proxy;\n\t#[runtime::pallet_index(41)]\n\tpub type Multisig = pallet_multisig;\n}\n"
+		));
+		assert_eq!(lib.matches("pub type Proxy").count(), 1);
+		let module = read_to_string(runtime.join("src/configs/proxy_multisig.rs"))?;
+		assert!(module.contains("use codec::{Decode, Encode, MaxEncodedLen};"));
+		Ok(())
+	}
+
+	#[test]
+	fn apply_proxy_multisig_preset_enables_polkadot_sdk_features() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let manifest = r#"[dependencies]
+polkadot-sdk = { workspace = true, features = ["pallet-balances"] }
+"#;
+		let runtime = runtime(temp_dir.path(), manifest)?;
+		apply_runtime_preset(&RuntimePreset::ProxyMultisig, &runtime)?;
+		assert_eq!(
+			read_to_string(runtime.join("Cargo.toml"))?,
+			r#"[dependencies]
+polkadot-sdk = { workspace = true, features = ["pallet-balances", "pallet-proxy", "pallet-multisig"] }
+"#
+		);
+		assert_eq!(read_to_string(temp_dir.path().join("Cargo.toml"))?, WORKSPACE);
+		let module = read_to_string(runtime.join("src/configs/proxy_multisig.rs"))?;
+		assert!(module.contains("use polkadot_sdk::*;"));
+		Ok(())
+	}
+
+	#[test]
+	fn apply_proxy_multisig_preset_fails_without_sourced_pallets() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let manifest = "[dependencies]\npallet-balances = \"39.0.0\"\n";
+		let runtime = runtime(temp_dir.path(), manifest)?;
+		assert!(matches!(
+			apply_runtime_preset(&RuntimePreset::ProxyMultisig, &runtime),
+			Err(Error::Config(e)) if e.contains("is not sourced from a git repository")
+		));
+		assert!(!runtime.join("src/configs/proxy_multisig.rs").exists());
+		assert_eq!(read_to_string(runtime.join("src/lib.rs"))?, RUNTIME);
+		Ok(())
+	}
+}
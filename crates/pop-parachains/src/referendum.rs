@@ -0,0 +1,313 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::set_up_client,
+	dispatch::{note_preimage, submit_referendum_on_track},
+	errors::Error,
+	impersonate::{is_fork, submit_impersonated_with, FORK_RPC_METHOD},
+	storage_key, Impersonator,
+};
+use pop_common::{decode_hex, encode_hex};
+use subxt::{
+	backend::rpc::{rpc_params, RpcClient},
+	config::{substrate::BlakeTwo256, Hasher},
+	dynamic::Value,
+	events::Events,
+	ext::{
+		scale_encode::EncodeAsType,
+		scale_value::{Composite, ValueDef},
+	},
+	utils::H256,
+	Metadata, OnlineClient, SubstrateConfig,
+};
+
+/// The maximum length of a call which can be bounded inline, rather than by its preimage.
+const MAX_INLINE_LEN: usize = 128;
+/// The number of blocks after approval after which a submitted referendum would be enacted.
+const ENACTMENT_DELAY: u32 = 1;
+
+/// The outcome of a referendum enacted on a fork.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simulation {
+	/// The index of the referendum submitted for the proposal.
+	pub referendum: Option<u32>,
+	/// The number of the block within which the proposal was enacted.
+	pub block: u32,
+	/// The hash of the block within which the proposal was enacted.
+	pub hash: H256,
+	/// Whether the proposal was dispatched successfully, or the error with which it failed.
+	pub dispatched: Result<(), String>,
+	/// The events emitted within the block in which the proposal was enacted.
+	pub events: Vec<SimulatedEvent>,
+	/// The changes to the watched storage keys.
+	pub changes: Vec<StateChange>,
+}
+
+/// An event emitted while enacting a proposal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulatedEvent {
+	/// The name of the pallet which emitted the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub variant: String,
+	/// The fields of the event.
+	pub fields: String,
+}
+
+/// The value of a storage key before and after a proposal was enacted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateChange {
+	/// The storage key, as a hex string.
+	pub key: String,
+	/// The value before enactment, as a hex string.
+	pub before: Option<String>,
+	/// The value after enactment, as a hex string.
+	pub after: Option<String>,
+}
+
+impl StateChange {
+	/// Whether the value was changed by the proposal.
+	pub fn changed(&self) -> bool {
+		self.before != self.after
+	}
+}
+
+/// Rehearses an OpenGov submission on a fork (e.g. launched with chopsticks, with
+/// `mock-signature-host: true`): the proposal is noted and submitted as a referendum on the
+/// specified track by an impersonated proposer, before the scheduler is fast-forwarded so that
+/// the proposal is enacted within the next block, with the origin of the track. The network
+/// from which the fork was created is unaffected.
+///
+/// # Arguments
+/// * `url` - Endpoint of a node of the fork.
+/// * `call` - The SCALE encoded call proposed, as a hex string.
+/// * `track` - The track on which the referendum is submitted (e.g. "root" or
+///   "whitelisted_caller").
+/// * `proposer` - The account impersonated to note the preimage and submit the referendum, which
+///   must be able to pay their deposits.
+/// * `watch` - The storage keys, as hex strings, whose changes are reported.
+pub async fn simulate_referendum(
+	url: &str,
+	call: &str,
+	track: &str,
+	proposer: &Impersonator,
+	watch: &[String],
+) -> Result<Simulation, Error> {
+	let call = decode_hex(call)
+		.ok_or_else(|| Error::Config(format!("`{call}` is not a valid hex string")))?;
+	if !is_fork(url).await? {
+		return Err(Error::UnsupportedCommand(format!(
+			"referenda can only be simulated on a fork, such as one launched with chopsticks: {url} does not expose `{FORK_RPC_METHOD}`"
+		)));
+	}
+	let client = set_up_client(url).await?;
+	let metadata = client.metadata();
+	let origin = track_origin(track);
+	let proposal = match call.len() <= MAX_INLINE_LEN {
+		true => Value::unnamed_variant("Inline", [Value::from_bytes(&call)]),
+		false => {
+			submit_impersonated_with(&client, &note_preimage(&call), proposer).await?;
+			Value::named_variant(
+				"Lookup",
+				[
+					("hash", Value::from_bytes(BlakeTwo256::hash(&call))),
+					("len", Value::u128(call.len() as u128)),
+				],
+			)
+		},
+	};
+	let submission = submit_referendum_on_track(origin.clone(), proposal.clone(), ENACTMENT_DELAY);
+	let events = submit_impersonated_with(&client, &submission, proposer).await?;
+	let referendum = events
+		.iter()
+		.flatten()
+		.find(|e| e.pallet_name() == "Referenda" && e.variant_name() == "Submitted")
+		.and_then(|e| e.field_values().ok())
+		.and_then(|fields| field(&fields, "index").and_then(|i| i.as_u128()))
+		.map(|index| index as u32);
+
+	let current = client.blocks().at_latest().await?;
+	let before = fetch_raw(&client, current.hash(), watch).await?;
+	let block = current.number() + 1;
+	let agenda = agenda(&metadata, block, origin, proposal)?;
+	let rpc = RpcClient::from_url(url)
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+	let _: serde_json::Value = rpc.request("dev_setStorage", rpc_params![vec![agenda]]).await?;
+	let hash: H256 = rpc.request("dev_newBlock", rpc_params![]).await?;
+
+	let events = client.blocks().at(hash).await?.events().await?;
+	let after = fetch_raw(&client, hash, watch).await?;
+	Ok(Simulation {
+		referendum,
+		block,
+		hash,
+		dispatched: dispatched(&events),
+		events: events
+			.iter()
+			.flatten()
+			.map(|e| SimulatedEvent {
+				pallet: e.pallet_name().to_string(),
+				variant: e.variant_name().to_string(),
+				fields: e.field_values().map(|f| f.to_string()).unwrap_or_default(),
+			})
+			.collect(),
+		changes: watch
+			.iter()
+			.zip(before.into_iter().zip(after))
+			.map(|(key, (before, after))| StateChange { key: key.clone(), before, after })
+			.collect(),
+	})
+}
+
+/// The origin with which proposals on a track are enacted: `Root` for the root track, otherwise
+/// the custom origin named after the track (e.g. `Origins::WhitelistedCaller` for
+/// "whitelisted_caller").
+///
+/// # Arguments
+/// * `track` - The name of the track.
+fn track_origin(track: &str) -> Value {
+	if track.eq_ignore_ascii_case("root") {
+		return Value::unnamed_variant("system", [Value::unnamed_variant("Root", [])]);
+	}
+	let origin: String = track
+		.split(['_', '-'])
+		.map(|word| {
+			let mut chars = word.chars();
+			chars
+				.next()
+				.map(|c| c.to_uppercase().chain(chars).collect::<String>())
+				.unwrap_or_default()
+		})
+		.collect();
+	Value::unnamed_variant("Origins", [Value::unnamed_variant(origin, [])])
+}
+
+/// The raw storage of the agenda of the scheduler at the specified block, containing only the
+/// proposal, as a pair of hex strings.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `block` - The block at which the proposal is to be enacted.
+/// * `origin` - The origin with which the proposal is to be enacted.
+/// * `proposal` - The proposal, bounded either inline or by the hash of its preimage.
+fn agenda(
+	metadata: &Metadata,
+	block: u32,
+	origin: Value,
+	proposal: Value,
+) -> Result<(String, String), Error> {
+	let key = storage_key(metadata, "Scheduler", "Agenda", &[block.to_string()])?;
+	let value_ty = metadata
+		.pallet_by_name("Scheduler")
+		.and_then(|p| p.storage())
+		.and_then(|s| s.entry_by_name("Agenda"))
+		.map(|e| e.entry_type().value_ty())
+		.ok_or_else(|| Error::Config("the chain has no `Scheduler::Agenda`".into()))?;
+	let value = scheduled(origin, proposal)
+		.encode_as_type(value_ty, metadata.types())
+		.map_err(|e| Error::Config(format!("unable to schedule the proposal: {e}")))?;
+	Ok((key, encode_hex(&value)))
+}
+
+/// The agenda of the scheduler for a block, containing only the proposal.
+fn scheduled(origin: Value, proposal: Value) -> Value {
+	Value::unnamed_composite([Value::unnamed_variant(
+		"Some",
+		[Value::named_composite([
+			("maybe_id", Value::unnamed_variant("None", [])),
+			("priority", Value::u128(0)),
+			("call", proposal),
+			("maybe_periodic", Value::unnamed_variant("None", [])),
+			("origin", origin),
+		])],
+	)])
+}
+
+/// Whether the scheduler dispatched the proposal successfully, based on the events of the block
+/// within which it was enacted.
+fn dispatched(events: &Events<SubstrateConfig>) -> Result<(), String> {
+	let result = events
+		.iter()
+		.flatten()
+		.find(|e| e.pallet_name() == "Scheduler" && e.variant_name() == "Dispatched")
+		.and_then(|e| e.field_values().ok())
+		.and_then(|fields| field(&fields, "result").cloned())
+		.ok_or_else(|| "the proposal was not dispatched".to_string())?;
+	dispatch_result(&result)
+}
+
+/// Interprets the result of a dispatch, as emitted by the scheduler.
+fn dispatch_result<T>(result: &Value<T>) -> Result<(), String> {
+	match &result.value {
+		ValueDef::Variant(variant) if variant.name == "Ok" => Ok(()),
+		ValueDef::Variant(variant) => Err(variant.values.to_string()),
+		_ => Err(format!("unexpected dispatch result: {result}")),
+	}
+}
+
+/// A named field of an event.
+fn field<'a, T>(fields: &'a Composite<T>, name: &str) -> Option<&'a Value<T>> {
+	match fields {
+		Composite::Named(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+		Composite::Unnamed(_) => None,
+	}
+}
+
+/// Fetches the values of storage keys at a block, as hex strings.
+async fn fetch_raw(
+	client: &OnlineClient<SubstrateConfig>,
+	block: H256,
+	keys: &[String],
+) -> Result<Vec<Option<String>>, Error> {
+	let storage = client.storage().at(block);
+	let mut values = Vec::with_capacity(keys.len());
+	for key in keys {
+		let bytes = decode_hex(key)
+			.ok_or_else(|| Error::Config(format!("`{key}` is not a valid hex string")))?;
+		values.push(storage.fetch_raw(bytes).await?.as_deref().map(encode_hex));
+	}
+	Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn track_origin_works() {
+		assert_eq!(
+			track_origin("root"),
+			Value::unnamed_variant("system", [Value::unnamed_variant("Root", [])])
+		);
+		assert_eq!(
+			track_origin("whitelisted_caller"),
+			Value::unnamed_variant("Origins", [Value::unnamed_variant("WhitelistedCaller", [])])
+		);
+		assert_eq!(
+			track_origin("general-admin"),
+			Value::unnamed_variant("Origins", [Value::unnamed_variant("GeneralAdmin", [])])
+		);
+	}
+
+	#[test]
+	fn dispatch_result_works() {
+		assert_eq!(
+			dispatch_result(&Value::unnamed_variant("Ok", [Value::unnamed_composite([])])),
+			Ok(())
+		);
+		let error = Value::unnamed_variant("Err", [Value::unnamed_variant("BadOrigin", [])]);
+		assert_eq!(dispatch_result(&error), Err("(BadOrigin ())".into()));
+		assert!(dispatch_result(&Value::u128(0)).is_err());
+		let fields = Composite::named([("result", Value::u128(0))]);
+		assert_eq!(field(&fields, "result"), Some(&Value::u128(0)));
+		assert_eq!(field(&fields, "index"), None);
+	}
+
+	#[test]
+	fn state_change_works() {
+		let change = StateChange { key: "0x01".into(), before: None, after: Some("0x02".into()) };
+		assert!(change.changed());
+		assert!(!StateChange { before: change.after.clone(), ..change }.changed());
+	}
+}
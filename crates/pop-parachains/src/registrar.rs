@@ -0,0 +1,775 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{
+		construct_sudo_extrinsic, fetch_storage, find_event_field, set_up_client,
+		sign_and_submit_extrinsic,
+	},
+	errors::Error,
+	hrmp::{force_clean_hrmp, query_hrmp_channels},
+	upgrade::constant_u128,
+};
+use pop_common::{decode_hex, i18n::message, Status};
+use std::{fs::read, path::Path};
+use subxt::{
+	dynamic::Value,
+	ext::scale_value::{Composite, ValueDef},
+	tx::DynamicPayload,
+	utils::{AccountId32, H256},
+	Metadata, OnlineClient, SubstrateConfig,
+};
+
+/// The model used by a relay chain to allocate coretime to parachains.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoretimeModel {
+	/// Legacy slot leases, won via auctions and optionally funded by crowdloans.
+	Auctions,
+	/// Agile coretime, purchased in bulk on the coretime chain or on-demand on the relay chain.
+	Agile,
+}
+
+/// A flow used to onboard a parachain onto a relay chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RegistrationFlow {
+	/// Reserve a parachain identifier via `registrar.reserve`.
+	Reserve,
+	/// Register a parachain via `registrar.register`.
+	Register,
+	/// Force register a parachain via `paras_sudo_wrapper`, requiring sudo.
+	ForceRegister,
+	/// Bid for a slot lease within an auction.
+	Auction,
+	/// Fund a slot auction bid via a crowdloan.
+	Crowdloan,
+	/// Purchase coretime on-demand.
+	OnDemand,
+	/// Assign bulk coretime to a parachain.
+	BulkCoretime,
+}
+
+/// The onboarding related functionality provided by a relay chain runtime.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RelaySupport {
+	/// Whether parachain identifiers can be reserved and parachains registered.
+	pub registrar: bool,
+	/// Whether slot leases are available.
+	pub slots: bool,
+	/// Whether slot auctions are available.
+	pub auctions: bool,
+	/// Whether crowdloans are available.
+	pub crowdloan: bool,
+	/// Whether agile coretime is available.
+	pub coretime: bool,
+	/// Whether on-demand coretime is available.
+	pub on_demand: bool,
+	/// Whether calls can be dispatched via sudo.
+	pub sudo: bool,
+	/// Whether parachains can be force registered via sudo.
+	pub paras_sudo_wrapper: bool,
+}
+
+impl RelaySupport {
+	/// Determines the onboarding related functionality provided by a relay chain from its
+	/// metadata.
+	///
+	/// # Arguments
+	/// * `metadata` - The metadata of the relay chain.
+	pub fn from_metadata(metadata: &Metadata) -> Self {
+		Self::from_pallets(metadata.pallets().map(|p| p.name()))
+	}
+
+	/// Determines the onboarding related functionality provided by a relay chain by querying its
+	/// metadata.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the relay chain.
+	pub fn query(client: &OnlineClient<SubstrateConfig>) -> Self {
+		Self::from_metadata(&client.metadata())
+	}
+
+	/// Determines the onboarding related functionality provided by a relay chain from the names
+	/// of the pallets within its runtime.
+	///
+	/// # Arguments
+	/// * `pallets` - The names of the pallets within the relay chain runtime.
+	fn from_pallets<'a>(pallets: impl IntoIterator<Item = &'a str>) -> Self {
+		let mut support = Self::default();
+		for pallet in pallets {
+			match pallet {
+				"Registrar" => support.registrar = true,
+				"Slots" => support.slots = true,
+				"Auctions" => support.auctions = true,
+				"Crowdloan" => support.crowdloan = true,
+				"Coretime" => support.coretime = true,
+				"OnDemand" | "OnDemandAssignmentProvider" => support.on_demand = true,
+				"Sudo" => support.sudo = true,
+				"ParasSudoWrapper" => support.paras_sudo_wrapper = true,
+				_ => {},
+			}
+		}
+		support
+	}
+
+	/// The coretime model used by the relay chain, if it can be determined.
+	pub fn model(&self) -> Option<CoretimeModel> {
+		if self.coretime || self.on_demand {
+			Some(CoretimeModel::Agile)
+		} else if self.slots || self.auctions {
+			Some(CoretimeModel::Auctions)
+		} else {
+			None
+		}
+	}
+
+	/// Ensures the specified flow is supported by the relay chain, otherwise explaining why not.
+	///
+	/// # Arguments
+	/// * `flow` - The onboarding flow to be used.
+	pub fn ensure(&self, flow: RegistrationFlow) -> Result<(), Error> {
+		use RegistrationFlow::*;
+		let unsupported = |reason: &str| Err(Error::UnsupportedCommand(reason.to_string()));
+		match flow {
+			Reserve | Register if !self.registrar =>
+				unsupported("the relay chain does not provide a `Registrar` pallet"),
+			ForceRegister if !(self.sudo && self.paras_sudo_wrapper) => unsupported(
+				"force registration requires both `Sudo` and `ParasSudoWrapper` pallets on the relay chain",
+			),
+			Auction if !self.auctions => match self.model() {
+				Some(CoretimeModel::Agile) => unsupported(
+					"the relay chain uses agile coretime, slot auctions are no longer available: purchase coretime on the coretime chain or on-demand instead",
+				),
+				_ => unsupported("the relay chain does not provide an `Auctions` pallet"),
+			},
+			Crowdloan if !self.crowdloan => match self.model() {
+				Some(CoretimeModel::Agile) => unsupported(
+					"the relay chain uses agile coretime, crowdloans are no longer available",
+				),
+				_ => unsupported("the relay chain does not provide a `Crowdloan` pallet"),
+			},
+			OnDemand if !self.on_demand => match self.model() {
+				Some(CoretimeModel::Auctions) => unsupported(
+					"the relay chain still uses slot auctions, on-demand coretime is not yet available",
+				),
+				_ => unsupported("the relay chain does not provide on-demand coretime"),
+			},
+			BulkCoretime if !self.coretime => match self.model() {
+				Some(CoretimeModel::Auctions) => unsupported(
+					"the relay chain still uses slot auctions, bulk coretime is not yet available: a slot lease must be won via an auction instead",
+				),
+				_ => unsupported("the relay chain does not provide a `Coretime` pallet"),
+			},
+			_ => Ok(()),
+		}
+	}
+}
+
+/// The lifecycle state of a para on the relay chain.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParaLifecycle {
+	/// The para is being onboarded.
+	Onboarding,
+	/// The para is a parathread, without a slot lease.
+	Parathread,
+	/// The para is a parachain, holding a slot lease.
+	Parachain,
+	/// The para is being upgraded from a parathread to a parachain.
+	UpgradingParathread,
+	/// The para is being downgraded from a parachain to a parathread.
+	DowngradingParachain,
+	/// The parathread is being offboarded.
+	OffboardingParathread,
+	/// The parachain is being offboarded.
+	OffboardingParachain,
+	/// An unknown state, reported by name.
+	Other(String),
+}
+
+impl From<&str> for ParaLifecycle {
+	fn from(name: &str) -> Self {
+		use ParaLifecycle::*;
+		match name {
+			"Onboarding" => Onboarding,
+			"Parathread" => Parathread,
+			"Parachain" => Parachain,
+			"UpgradingParathread" => UpgradingParathread,
+			"DowngradingParachain" => DowngradingParachain,
+			"OffboardingParathread" => OffboardingParathread,
+			"OffboardingParachain" => OffboardingParachain,
+			other => Other(other.to_string()),
+		}
+	}
+}
+
+/// Queries the lifecycle state of a para, returning `None` if it is not known to the relay chain.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the relay chain.
+/// * `para_id` - The parachain identifier.
+pub async fn query_lifecycle(
+	client: &OnlineClient<SubstrateConfig>,
+	para_id: u32,
+) -> Result<Option<ParaLifecycle>, Error> {
+	let lifecycle =
+		fetch_storage(client, "Paras", "ParaLifecycles", vec![Value::u128(para_id as u128)])
+			.await?;
+	Ok(lifecycle.and_then(|lifecycle| match lifecycle.value {
+		ValueDef::Variant(variant) => Some(ParaLifecycle::from(variant.name.as_str())),
+		_ => None,
+	}))
+}
+
+/// Queries the deposit reserved for a registered para, which is refunded to its manager upon
+/// deregistration.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the relay chain.
+/// * `para_id` - The parachain identifier.
+pub async fn query_deposit(
+	client: &OnlineClient<SubstrateConfig>,
+	para_id: u32,
+) -> Result<Option<u128>, Error> {
+	let info =
+		fetch_storage(client, "Registrar", "Paras", vec![Value::u128(para_id as u128)]).await?;
+	Ok(info.and_then(|info| match info.value {
+		ValueDef::Composite(Composite::Named(fields)) => fields
+			.into_iter()
+			.find(|(name, _)| name == "deposit")
+			.and_then(|(_, deposit)| deposit.as_u128()),
+		_ => None,
+	}))
+}
+
+/// Constructs a call which reserves the next free parachain identifier for the signer, reserving
+/// the deposit required by the relay chain (`ParaDeposit`) from their account.
+pub fn reserve() -> DynamicPayload {
+	subxt::dynamic::tx("Registrar", "reserve", Vec::<Value>::new())
+}
+
+/// Reserves the next free parachain identifier on a relay chain, returning the identifier
+/// reserved.
+///
+/// # Arguments
+/// * `url` - Endpoint of a node of the relay chain.
+/// * `suri` - The secret URI of the account reserving the identifier, which pays the deposit.
+pub async fn reserve_para_id(url: &str, suri: &str) -> Result<u32, Error> {
+	let client = set_up_client(url).await?;
+	RelaySupport::query(&client).ensure(RegistrationFlow::Reserve)?;
+	let events = sign_and_submit_extrinsic(&client, &reserve(), suri).await?;
+	find_event_field(&events, "Registrar", "Reserved", "para_id")?
+		.as_ref()
+		.and_then(para_id)
+		.ok_or_else(|| {
+			Error::ExtrinsicSubmissionError("expected a `Registrar::Reserved` event".into())
+		})
+}
+
+/// The artifacts with which a parachain is registered on a relay chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Registration {
+	/// The parachain identifier, which must have been reserved unless force registered.
+	pub para_id: u32,
+	/// The genesis head (state) of the parachain.
+	pub genesis_head: Vec<u8>,
+	/// The validation code (runtime) of the parachain.
+	pub validation_code: Vec<u8>,
+}
+
+/// The deposit required to register a parachain.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RegistrationDeposit {
+	/// The deposit reserved when the parachain identifier was reserved (`ParaDeposit`).
+	pub reservation: u128,
+	/// The deposit for the storage of the genesis head and validation code, reserved upon
+	/// registration (`DataDepositPerByte`).
+	pub data: u128,
+}
+
+impl RegistrationDeposit {
+	/// The total deposit held for the registered parachain.
+	pub fn total(&self) -> u128 {
+		self.reservation.saturating_add(self.data)
+	}
+}
+
+impl Registration {
+	/// Loads the genesis artifacts exported by `pop build spec`, either hex encoded or raw.
+	///
+	/// # Arguments
+	/// * `para_id` - The parachain identifier.
+	/// * `genesis_state` - The path of the genesis state file.
+	/// * `genesis_code` - The path of the genesis code (wasm) file.
+	pub fn from_files(
+		para_id: u32,
+		genesis_state: &Path,
+		genesis_code: &Path,
+	) -> Result<Self, Error> {
+		let load = |path: &Path| -> Result<Vec<u8>, Error> {
+			let contents = read(path)
+				.map_err(|e| Error::Config(format!("unable to read {}: {e}", path.display())))?;
+			Ok(std::str::from_utf8(&contents)
+				.ok()
+				.and_then(|hex| decode_hex(hex.trim()))
+				.unwrap_or(contents))
+		};
+		let registration = Self {
+			para_id,
+			genesis_head: load(genesis_state)?,
+			validation_code: load(genesis_code)?,
+		};
+		if registration.genesis_head.is_empty() || registration.validation_code.is_empty() {
+			return Err(Error::Config("expected non-empty genesis artifacts".into()));
+		}
+		Ok(registration)
+	}
+
+	/// Constructs a call which registers the parachain via `registrar.register`, dispatched by
+	/// the account which reserved its identifier.
+	pub fn register(&self) -> DynamicPayload {
+		subxt::dynamic::tx(
+			"Registrar",
+			"register",
+			vec![
+				Value::u128(self.para_id as u128),
+				Value::from_bytes(&self.genesis_head),
+				Value::from_bytes(&self.validation_code),
+			],
+		)
+	}
+
+	/// Constructs a call, dispatched via sudo, which force registers the parachain via
+	/// `paras_sudo_wrapper.sudo_schedule_para_initialize`, as available on test relay chains.
+	pub fn force_register(&self) -> DynamicPayload {
+		construct_sudo_extrinsic(subxt::dynamic::tx(
+			"ParasSudoWrapper",
+			"sudo_schedule_para_initialize",
+			vec![
+				Value::u128(self.para_id as u128),
+				Value::named_composite([
+					("genesis_head", Value::from_bytes(&self.genesis_head)),
+					("validation_code", Value::from_bytes(&self.validation_code)),
+					("para_kind", Value::bool(true)),
+				]),
+			],
+		))
+	}
+
+	/// Estimates the deposit required to register the parachain, based on the constants of the
+	/// relay chain.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the relay chain.
+	pub fn deposit(&self, client: &OnlineClient<SubstrateConfig>) -> RegistrationDeposit {
+		let per_byte = constant_u128(client, "Registrar", "DataDepositPerByte").unwrap_or_default();
+		RegistrationDeposit {
+			reservation: constant_u128(client, "Registrar", "ParaDeposit").unwrap_or_default(),
+			data: per_byte.saturating_mul(self.len() as u128),
+		}
+	}
+
+	/// The number of bytes stored upon registration.
+	fn len(&self) -> usize {
+		self.genesis_head.len() + self.validation_code.len()
+	}
+}
+
+/// Registers a parachain on a relay chain with its genesis artifacts, either via
+/// `registrar.register` or, on test relay chains, force registered via sudo. The parachain is
+/// onboarded at the start of a subsequent session. Returns the hash of the extrinsic.
+///
+/// # Arguments
+/// * `url` - Endpoint of a node of the relay chain.
+/// * `registration` - The parachain and its genesis artifacts.
+/// * `suri` - The secret URI of the account which reserved the parachain identifier, or of the sudo
+///   key when force registering.
+/// * `flow` - Either [`RegistrationFlow::Register`] or [`RegistrationFlow::ForceRegister`].
+/// * `status` - An observer of the progress of the registration.
+pub async fn register_parachain(
+	url: &str,
+	registration: &Registration,
+	suri: &str,
+	flow: RegistrationFlow,
+	status: &impl Status,
+) -> Result<H256, Error> {
+	status.update(&message("registrar.checking", &[]));
+	let client = set_up_client(url).await?;
+	RelaySupport::query(&client).ensure(flow)?;
+	let call = match flow {
+		RegistrationFlow::Register => {
+			let deposit = registration.deposit(&client);
+			status.update(&message(
+				"registrar.registering",
+				&[
+					("para_id", &registration.para_id.to_string()),
+					("deposit", &deposit.data.to_string()),
+					("reservation", &deposit.reservation.to_string()),
+				],
+			));
+			registration.register()
+		},
+		RegistrationFlow::ForceRegister => {
+			status.update(&message(
+				"registrar.force_registering",
+				&[("para_id", &registration.para_id.to_string())],
+			));
+			registration.force_register()
+		},
+		flow => {
+			return Err(Error::UnsupportedCommand(format!(
+				"parachains can only be registered via `registrar.register` or `paras_sudo_wrapper`, not {flow:?}"
+			)))
+		},
+	};
+	let events = sign_and_submit_extrinsic(&client, &call, suri).await?;
+	if let Some(Err(e)) = find_event_field(&events, "Sudo", "Sudid", "sudo_result")?
+		.map(|result| dispatch_result(&result))
+	{
+		return Err(Error::ExtrinsicSubmissionError(message(
+			"registrar.failed",
+			&[("error", &e.to_string())],
+		)));
+	}
+	status.update(&message(
+		"registrar.registered",
+		&[("para_id", &registration.para_id.to_string())],
+	));
+	Ok(events.extrinsic_hash())
+}
+
+/// Interprets the result of a call dispatched via sudo.
+pub(crate) fn dispatch_result<T>(result: &Value<T>) -> Result<(), String> {
+	match &result.value {
+		ValueDef::Variant(variant) if variant.name == "Err" => Err(variant.values.to_string()),
+		_ => Ok(()),
+	}
+}
+
+/// A parachain identifier, either as a number or wrapped within a composite (`ParaId`).
+fn para_id<T>(value: &Value<T>) -> Option<u32> {
+	match &value.value {
+		ValueDef::Composite(composite) if composite.len() == 1 =>
+			composite.values().next().and_then(para_id),
+		_ => value.as_u128().and_then(|id| u32::try_from(id).ok()),
+	}
+}
+
+/// Constructs a call which deregisters a parathread, refunding its deposit to its manager. The
+/// call must be dispatched by the manager of the para or with `Root` origin.
+///
+/// # Arguments
+/// * `para_id` - The parachain identifier.
+pub fn deregister(para_id: u32) -> DynamicPayload {
+	subxt::dynamic::tx("Registrar", "deregister", vec![Value::u128(para_id as u128)])
+}
+
+/// Constructs the calls, requiring `Root` origin, which close any open HRMP channels of a para and
+/// then deregister it, so that its identifier and deposit can be recycled on a test relay chain.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the relay chain.
+/// * `para_id` - The parachain identifier.
+pub async fn deregistration_calls(
+	client: &OnlineClient<SubstrateConfig>,
+	para_id: u32,
+) -> Result<Vec<DynamicPayload>, Error> {
+	RelaySupport::query(client).ensure(RegistrationFlow::Register)?;
+	match query_lifecycle(client, para_id).await? {
+		None =>
+			return Err(Error::UnsupportedCommand(format!(
+				"para {para_id} is not registered on the relay chain"
+			))),
+		Some(ParaLifecycle::Parathread) => {},
+		Some(ParaLifecycle::Parachain) =>
+			return Err(Error::UnsupportedCommand(format!(
+				"para {para_id} holds a slot lease and must first be downgraded to a parathread via `ParasSudoWrapper.sudo_schedule_parachain_downgrade`"
+			))),
+		Some(lifecycle) =>
+			return Err(Error::UnsupportedCommand(format!(
+				"para {para_id} cannot be deregistered while {lifecycle:?}, retry after the next session"
+			))),
+	}
+	let mut calls = vec![];
+	let channels = query_hrmp_channels(client, para_id).await?;
+	if !channels.is_empty() {
+		calls.push(force_clean_hrmp(para_id, &channels));
+	}
+	calls.push(deregister(para_id));
+	Ok(calls)
+}
+
+/// Constructs a call which swaps the lease holding status of two paras, along with their
+/// identifiers. The call must be dispatched by the managers of both paras, each with the same
+/// arguments in reverse order, or once with `Root` origin.
+///
+/// # Arguments
+/// * `para_id` - The identifier of the para.
+/// * `other` - The identifier of the para to be swapped with.
+pub fn swap(para_id: u32, other: u32) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Registrar",
+		"swap",
+		vec![Value::u128(para_id as u128), Value::u128(other as u128)],
+	)
+}
+
+/// Constructs a call, requiring `Root` origin, which upgrades a parathread to a parachain at the
+/// start of the next session.
+///
+/// # Arguments
+/// * `para_id` - The parachain identifier.
+pub fn upgrade_to_parachain(para_id: u32) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"ParasSudoWrapper",
+		"sudo_schedule_parathread_upgrade",
+		vec![Value::u128(para_id as u128)],
+	)
+}
+
+/// Constructs a call, requiring `Root` origin, which downgrades a parachain to a parathread at
+/// the start of the next session.
+///
+/// # Arguments
+/// * `para_id` - The parachain identifier.
+pub fn downgrade_to_parathread(para_id: u32) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"ParasSudoWrapper",
+		"sudo_schedule_parachain_downgrade",
+		vec![Value::u128(para_id as u128)],
+	)
+}
+
+/// A slot lease to be forcibly granted to a para on a test relay chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lease {
+	/// The parachain identifier.
+	pub para_id: u32,
+	/// The account from which the lease deposit is reserved.
+	pub leaser: AccountId32,
+	/// The amount to be reserved as the lease deposit.
+	pub amount: u128,
+	/// The first lease period of the lease.
+	pub period_begin: u32,
+	/// The number of lease periods of the lease.
+	pub period_count: u32,
+}
+
+/// Constructs a call, requiring `Root` origin, which grants a slot lease to a para.
+///
+/// # Arguments
+/// * `lease` - The lease to be granted.
+pub fn force_lease(lease: &Lease) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Slots",
+		"force_lease",
+		vec![
+			Value::u128(lease.para_id as u128),
+			Value::from_bytes(lease.leaser.0),
+			Value::u128(lease.amount),
+			Value::u128(lease.period_begin as u128),
+			Value::u128(lease.period_count as u128),
+		],
+	)
+}
+
+/// Constructs a call, requiring `Root` origin, which clears all current and future leases of a
+/// para, unreserving the lease deposits.
+///
+/// # Arguments
+/// * `para_id` - The parachain identifier.
+pub fn clear_all_leases(para_id: u32) -> DynamicPayload {
+	subxt::dynamic::tx("Slots", "clear_all_leases", vec![Value::u128(para_id as u128)])
+}
+
+/// Constructs a call which onboards a para holding a lease for the current lease period, which
+/// otherwise only happens at the start of the next lease period. The call can be dispatched by any
+/// account.
+///
+/// # Arguments
+/// * `para_id` - The parachain identifier.
+pub fn trigger_onboard(para_id: u32) -> DynamicPayload {
+	subxt::dynamic::tx("Slots", "trigger_onboard", vec![Value::u128(para_id as u128)])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use RegistrationFlow::*;
+
+	#[test]
+	fn legacy_relay_detected() {
+		let support =
+			RelaySupport::from_pallets(["System", "Registrar", "Slots", "Auctions", "Crowdloan"]);
+		assert_eq!(support.model(), Some(CoretimeModel::Auctions));
+		for flow in [Reserve, Register, Auction, Crowdloan] {
+			assert!(support.ensure(flow).is_ok());
+		}
+		assert!(matches!(
+			support.ensure(BulkCoretime),
+			Err(Error::UnsupportedCommand(e)) if e.starts_with("the relay chain still uses slot auctions")
+		));
+		assert!(matches!(
+			support.ensure(OnDemand),
+			Err(Error::UnsupportedCommand(e)) if e.starts_with("the relay chain still uses slot auctions")
+		));
+	}
+
+	#[test]
+	fn agile_coretime_relay_detected() {
+		let support = RelaySupport::from_pallets([
+			"System",
+			"Registrar",
+			"Coretime",
+			"OnDemandAssignmentProvider",
+			"Sudo",
+			"ParasSudoWrapper",
+		]);
+		assert_eq!(support.model(), Some(CoretimeModel::Agile));
+		for flow in [Reserve, Register, ForceRegister, OnDemand, BulkCoretime] {
+			assert!(support.ensure(flow).is_ok());
+		}
+		assert!(matches!(
+			support.ensure(Auction),
+			Err(Error::UnsupportedCommand(e)) if e.starts_with("the relay chain uses agile coretime")
+		));
+		assert!(matches!(
+			support.ensure(Crowdloan),
+			Err(Error::UnsupportedCommand(e)) if e.starts_with("the relay chain uses agile coretime")
+		));
+	}
+
+	#[test]
+	fn unknown_relay_detected() {
+		let support = RelaySupport::from_pallets(["System", "Balances"]);
+		assert_eq!(support.model(), None);
+		assert!(matches!(
+			support.ensure(Register),
+			Err(Error::UnsupportedCommand(e)) if e == "the relay chain does not provide a `Registrar` pallet"
+		));
+		assert!(matches!(support.ensure(ForceRegister), Err(Error::UnsupportedCommand(..))));
+	}
+
+	#[test]
+	fn para_lifecycle_from_name_works() {
+		assert_eq!(ParaLifecycle::from("Parathread"), ParaLifecycle::Parathread);
+		assert_eq!(
+			ParaLifecycle::from("OffboardingParachain"),
+			ParaLifecycle::OffboardingParachain
+		);
+		assert_eq!(ParaLifecycle::from("Retired"), ParaLifecycle::Other("Retired".into()));
+	}
+
+	#[test]
+	fn reserve_works() {
+		assert_eq!(
+			reserve().into_value(),
+			Value::unnamed_variant("Registrar", [Value::unnamed_variant("reserve", [])])
+		);
+		assert_eq!(para_id(&Value::u128(2000)), Some(2000));
+		assert_eq!(para_id(&Value::unnamed_composite([Value::u128(2001)])), Some(2001));
+		assert_eq!(para_id(&Value::u128(u128::MAX)), None);
+		assert_eq!(para_id(&Value::string("2000")), None);
+	}
+
+	#[test]
+	fn registration_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let (state, code) = (temp_dir.path().join("state"), temp_dir.path().join("code"));
+		std::fs::write(&state, "0x0102\n")?;
+		std::fs::write(&code, [0x00, 0x61, 0x73, 0x6d, 0xff])?;
+		let registration = Registration::from_files(2000, &state, &code)?;
+		assert_eq!(
+			registration,
+			Registration {
+				para_id: 2000,
+				genesis_head: vec![1, 2],
+				validation_code: vec![0x00, 0x61, 0x73, 0x6d, 0xff],
+			}
+		);
+		assert_eq!(registration.len(), 7);
+		assert_eq!(
+			registration.register().into_value(),
+			Value::unnamed_variant(
+				"Registrar",
+				[Value::unnamed_variant(
+					"register",
+					[
+						Value::u128(2000),
+						Value::from_bytes([1, 2]),
+						Value::from_bytes([0x00, 0x61, 0x73, 0x6d, 0xff]),
+					]
+				)]
+			)
+		);
+		assert_eq!(registration.force_register().call_name(), "sudo");
+		assert_eq!(RegistrationDeposit { reservation: 10, data: 5 }.total(), 15);
+
+		std::fs::write(&state, "")?;
+		assert!(matches!(
+			Registration::from_files(2000, &state, &code),
+			Err(Error::Config(e)) if e == "expected non-empty genesis artifacts"
+		));
+		assert!(matches!(
+			Registration::from_files(2000, &temp_dir.path().join("missing"), &code),
+			Err(Error::Config(_))
+		));
+		assert!(dispatch_result(&Value::unnamed_variant("Ok", [])).is_ok());
+		assert!(dispatch_result(&Value::unnamed_variant("Err", [Value::u128(1)])).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn deregister_works() {
+		assert_eq!(
+			deregister(2000).into_value(),
+			Value::unnamed_variant(
+				"Registrar",
+				[Value::unnamed_variant("deregister", [Value::u128(2000)])]
+			)
+		);
+	}
+
+	#[test]
+	fn swap_works() {
+		assert_eq!(
+			swap(2000, 2001).into_value(),
+			Value::unnamed_variant(
+				"Registrar",
+				[Value::unnamed_variant("swap", [Value::u128(2000), Value::u128(2001)])]
+			)
+		);
+	}
+
+	#[test]
+	fn lifecycle_calls_work() {
+		assert_eq!(upgrade_to_parachain(2000).call_name(), "sudo_schedule_parathread_upgrade");
+		assert_eq!(downgrade_to_parathread(2000).call_name(), "sudo_schedule_parachain_downgrade");
+		assert_eq!(clear_all_leases(2000).call_name(), "clear_all_leases");
+		assert_eq!(trigger_onboard(2000).pallet_name(), "Slots");
+	}
+
+	#[test]
+	fn force_lease_works() {
+		let lease = Lease {
+			para_id: 2000,
+			leaser: AccountId32([1; 32]),
+			amount: 100,
+			period_begin: 0,
+			period_count: 8,
+		};
+		assert_eq!(
+			force_lease(&lease).into_value(),
+			Value::unnamed_variant(
+				"Slots",
+				[Value::unnamed_variant(
+					"force_lease",
+					[
+						Value::u128(2000),
+						Value::from_bytes([1; 32]),
+						Value::u128(100),
+						Value::u128(0),
+						Value::u128(8),
+					]
+				)]
+			)
+		);
+	}
+}
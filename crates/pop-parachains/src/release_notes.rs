@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::set_up_client,
+	errors::Error,
+	metadata_diff::{Changes, MetadataDiff},
+	runtime_info::RuntimeVersion,
+};
+use pop_common::decode_hex;
+use std::{
+	fmt::{Display, Formatter, Write},
+	fs::read,
+};
+use subxt::{ext::codec::Decode, Metadata};
+
+/// The version of a runtime, as exposed by the `System::Version` constant of its metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VersionSummary {
+	/// The name of the runtime specification.
+	pub spec_name: String,
+	/// The version of the runtime specification.
+	pub spec_version: u32,
+	/// The version of the transaction format.
+	pub transaction_version: u32,
+}
+
+impl VersionSummary {
+	/// The version of a runtime, if exposed by its metadata.
+	fn from_metadata(metadata: &Metadata) -> Option<Self> {
+		let version = metadata.pallet_by_name("System")?.constant_by_name("Version")?;
+		let version = RuntimeVersion::decode(version.value()).ok()?;
+		Some(Self {
+			spec_name: version.spec_name,
+			spec_version: version.spec_version,
+			transaction_version: version.transaction_version,
+		})
+	}
+}
+
+/// Human-readable release notes of a runtime upgrade, generated from the differences between the
+/// metadata of the old and new runtimes, and rendered as Markdown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReleaseNotes {
+	/// The version of the old runtime, if known.
+	pub from: Option<VersionSummary>,
+	/// The version of the new runtime, if known.
+	pub to: Option<VersionSummary>,
+	/// The differences between the metadata of the runtimes.
+	pub diff: MetadataDiff,
+}
+
+impl ReleaseNotes {
+	/// Generates the release notes of an upgrade from the metadata of the old and new runtimes.
+	///
+	/// # Arguments
+	/// * `old` - The metadata of the old runtime.
+	/// * `new` - The metadata of the new runtime.
+	pub fn new(old: &Metadata, new: &Metadata) -> Self {
+		Self {
+			from: VersionSummary::from_metadata(old),
+			to: VersionSummary::from_metadata(new),
+			diff: MetadataDiff::between(old, new),
+		}
+	}
+
+	/// Issues with the upgrade which should be addressed before releasing it.
+	pub fn warnings(&self) -> Vec<String> {
+		let mut warnings = vec![];
+		let (Some(from), Some(to)) = (&self.from, &self.to) else {
+			return warnings;
+		};
+		if to.spec_version <= from.spec_version && !self.diff.is_empty() {
+			warnings.push(format!(
+				"The metadata changed, but the spec version was not increased from {}.",
+				from.spec_version
+			));
+		}
+		if to.transaction_version == from.transaction_version && self.diff.breaks_transactions() {
+			warnings.push(format!(
+				"Calls were changed, removed or moved, but the transaction version was not increased from {}.",
+				from.transaction_version
+			));
+		}
+		warnings
+	}
+}
+
+impl Display for ReleaseNotes {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match (&self.from, &self.to) {
+			(Some(from), Some(to)) => {
+				writeln!(f, "# {} v{} → v{}\n", to.spec_name, from.spec_version, to.spec_version)?;
+				if from.transaction_version != to.transaction_version {
+					writeln!(
+						f,
+						"Transaction version: {} → {}\n",
+						from.transaction_version, to.transaction_version
+					)?;
+				}
+			},
+			_ => writeln!(f, "# Runtime upgrade\n")?,
+		}
+		for warning in self.warnings() {
+			writeln!(f, "> ⚠️ {warning}\n")?;
+		}
+		let diff = &self.diff;
+		if diff.is_empty() {
+			return write!(f, "No changes to the metadata of the runtime.");
+		}
+
+		let mut sections = vec![];
+		let mut pallets = String::new();
+		for (name, index) in &diff.pallets_added {
+			writeln!(pallets, "- Added `{name}` (index {index})")?;
+		}
+		for name in &diff.pallets_removed {
+			writeln!(pallets, "- Removed `{name}`")?;
+		}
+		for pallet in &diff.pallets {
+			if let Some((old, new)) = pallet.index {
+				writeln!(pallets, "- Moved `{}` from index {old} to {new}", pallet.name)?;
+			}
+		}
+		sections.push(("Pallets", pallets));
+		sections.push(("Calls", changes(diff, |p| &p.calls, "")?));
+		sections.push(("Events", changes(diff, |p| &p.events, "")?));
+		sections.push((
+			"Storage",
+			changes(diff, |p| &p.storage, " (check that the upgrade includes migrations)")?,
+		));
+		let (mut weights, mut constants) = (String::new(), changes(diff, |p| &p.constants, "")?);
+		for pallet in &diff.pallets {
+			for change in &pallet.values {
+				let list =
+					if change.name.contains("Weight") { &mut weights } else { &mut constants };
+				writeln!(
+					list,
+					"- `{}::{}`: {} → {}",
+					pallet.name, change.name, change.old, change.new
+				)?;
+			}
+		}
+		sections.push(("Weights", weights));
+		sections.push(("Constants", constants));
+
+		let sections: Vec<_> = sections
+			.into_iter()
+			.filter(|(_, items)| !items.is_empty())
+			.map(|(title, items)| format!("## {title}\n\n{}", items.trim_end()))
+			.collect();
+		write!(f, "{}", sections.join("\n\n"))
+	}
+}
+
+/// Lists the changes of an item of the pallets (e.g. their calls) as Markdown.
+///
+/// # Arguments
+/// * `diff` - The differences between the runtimes.
+/// * `items` - The changes of the item of a pallet.
+/// * `note` - A note appended to changes of existing items.
+fn changes(
+	diff: &MetadataDiff,
+	items: impl Fn(&crate::metadata_diff::PalletDiff) -> &Changes,
+	note: &str,
+) -> Result<String, std::fmt::Error> {
+	let mut list = String::new();
+	for pallet in &diff.pallets {
+		let changes = items(pallet);
+		let name = &pallet.name;
+		for item in &changes.added {
+			writeln!(list, "- Added `{name}::{item}`")?;
+		}
+		for item in &changes.changed {
+			writeln!(list, "- Changed `{name}::{item}`{note}")?;
+		}
+		for item in &changes.removed {
+			writeln!(list, "- Removed `{name}::{item}`")?;
+		}
+	}
+	Ok(list)
+}
+
+/// Loads the metadata of a runtime, either from a live chain or a file of SCALE-encoded metadata
+/// (e.g. as produced by `subxt metadata`), optionally hex-encoded.
+///
+/// # Arguments
+/// * `source` - Either the websocket endpoint of a node, or the path of a metadata file.
+pub async fn load_metadata(source: &str) -> Result<Metadata, Error> {
+	if source.starts_with("ws://") || source.starts_with("wss://") {
+		return Ok(set_up_client(source).await?.metadata());
+	}
+	let bytes = read(source)?;
+	let bytes = match std::str::from_utf8(&bytes).ok().and_then(|hex| decode_hex(hex.trim())) {
+		Some(decoded) => decoded,
+		None => bytes,
+	};
+	Metadata::decode(&mut &bytes[..])
+		.map_err(|e| Error::Config(format!("invalid metadata within {source}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::metadata_diff::tests::{metadata, CallV1, CallV2, Pallet};
+	use scale_info::meta_type;
+	use subxt::ext::codec::Encode;
+
+	fn runtime(calls: scale_info::MetaType, existential_deposit: u32) -> Metadata {
+		metadata(vec![Pallet {
+			name: "Balances",
+			index: 10,
+			calls: Some(calls),
+			events: None,
+			storage: vec![("TotalIssuance", meta_type::<u128>())],
+			constants: vec![("ExistentialDeposit", existential_deposit)],
+		}])
+	}
+
+	#[test]
+	fn release_notes_works() {
+		let notes = ReleaseNotes::new(
+			&runtime(meta_type::<CallV1>(), 1),
+			&runtime(meta_type::<CallV2>(), 10),
+		);
+		assert_eq!(notes.from, None);
+		assert!(notes.warnings().is_empty());
+		assert_eq!(
+			notes.to_string(),
+			"# Runtime upgrade\n\n## Calls\n\n- Added `Balances::TransferAll`\n- Changed `Balances::Transfer`\n- Removed `Balances::Burn`\n\n## Constants\n\n- `Balances::ExistentialDeposit`: 1 → 10"
+		);
+
+		let version = |spec_version: u32, transaction_version: u32| VersionSummary {
+			spec_name: "pop".into(),
+			spec_version,
+			transaction_version,
+		};
+		let notes = ReleaseNotes { from: Some(version(1, 1)), to: Some(version(1, 1)), ..notes };
+		assert_eq!(notes.warnings().len(), 2);
+		assert!(notes.to_string().starts_with("# pop v1 → v1\n\n> ⚠️ The metadata changed"));
+
+		let same = ReleaseNotes::new(
+			&runtime(meta_type::<CallV1>(), 1),
+			&runtime(meta_type::<CallV1>(), 1),
+		);
+		assert!(same.to_string().ends_with("No changes to the metadata of the runtime."));
+	}
+
+	#[tokio::test]
+	async fn load_metadata_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let encoded = runtime(meta_type::<CallV1>(), 1).encode();
+		let path = temp_dir.path().join("metadata.scale");
+		std::fs::write(&path, &encoded)?;
+		let metadata = load_metadata(path.to_str().unwrap()).await?;
+		assert!(metadata.pallet_by_name("Balances").is_some());
+		std::fs::write(&path, pop_common::encode_hex(&encoded))?;
+		assert!(load_metadata(path.to_str().unwrap()).await.is_ok());
+		std::fs::write(&path, "invalid")?;
+		assert!(matches!(load_metadata(path.to_str().unwrap()).await, Err(Error::Config(_))));
+		Ok(())
+	}
+}
@@ -0,0 +1,366 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{call::set_up_client, errors::Error};
+use rand::Rng;
+use std::time::Duration;
+use subxt::{utils::H256, OnlineClient, SubstrateConfig};
+use tokio::{
+	sync::mpsc::{self, error::TrySendError},
+	task::JoinHandle,
+	time::sleep,
+};
+
+/// The policy used to reconnect to a node after the connection is lost.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconnectPolicy {
+	/// The delay before the first reconnection attempt, doubled after each failed attempt.
+	pub initial_delay: Duration,
+	/// The maximum delay between reconnection attempts.
+	pub max_delay: Duration,
+	/// The maximum number of consecutive reconnection attempts, or unlimited if not specified.
+	pub max_attempts: Option<u32>,
+	/// The fraction of each delay which is randomized (between 0 and 1), so that clients which
+	/// lost their connections at once do not all reconnect at once.
+	pub jitter: f64,
+}
+
+impl Default for ReconnectPolicy {
+	fn default() -> Self {
+		Self {
+			initial_delay: Duration::from_millis(500),
+			max_delay: Duration::from_secs(30),
+			max_attempts: None,
+			jitter: 0.5,
+		}
+	}
+}
+
+impl ReconnectPolicy {
+	/// The delay before the specified reconnection attempt, prior to any jitter.
+	///
+	/// # Arguments
+	/// * `attempt` - The reconnection attempt, starting at zero.
+	pub fn delay(&self, attempt: u32) -> Duration {
+		self.initial_delay
+			.checked_mul(2u32.saturating_pow(attempt))
+			.map_or(self.max_delay, |delay| delay.min(self.max_delay))
+	}
+
+	/// The delay before the specified reconnection attempt, reduced by a random amount of up to
+	/// the jitter of the policy.
+	///
+	/// # Arguments
+	/// * `attempt` - The reconnection attempt, starting at zero.
+	pub fn jittered_delay(&self, attempt: u32) -> Duration {
+		let jitter = self.jitter.clamp(0.0, 1.0);
+		self.delay(attempt).mul_f64(1.0 - jitter * rand::thread_rng().gen::<f64>())
+	}
+
+	/// Whether another reconnection attempt is permitted.
+	///
+	/// # Arguments
+	/// * `attempt` - The reconnection attempt, starting at zero.
+	fn permits(&self, attempt: u32) -> bool {
+		self.max_attempts.is_none_or(|max| attempt < max)
+	}
+}
+
+/// How items are buffered when a subscriber falls behind.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Buffering {
+	/// Buffer up to the specified number of items, then pause the subscription until the
+	/// subscriber catches up.
+	Block(usize),
+	/// Buffer up to the specified number of items, then drop new items until the subscriber
+	/// catches up, reporting the number dropped via [StreamEvent::Lagged].
+	DropNewest(usize),
+}
+
+impl Default for Buffering {
+	fn default() -> Self {
+		Self::Block(64)
+	}
+}
+
+impl Buffering {
+	/// The capacity of the buffer.
+	fn capacity(&self) -> usize {
+		match self {
+			Buffering::Block(capacity) | Buffering::DropNewest(capacity) => (*capacity).max(1),
+		}
+	}
+}
+
+/// An event emitted by a resilient subscription.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamEvent<T> {
+	/// An item of the subscription.
+	Item(T),
+	/// The connection to the node was lost, with the reason.
+	Disconnected(String),
+	/// The connection to the node was re-established and the subscription resumed.
+	Reconnected,
+	/// The specified number of items were dropped as the subscriber fell behind.
+	Lagged(u64),
+}
+
+/// A finalized block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinalizedBlock {
+	/// The block number.
+	pub number: u32,
+	/// The block hash.
+	pub hash: H256,
+}
+
+/// An event emitted within a finalized block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockEvent {
+	/// The number of the block within which the event was emitted.
+	pub block: u32,
+	/// The name of the pallet which emitted the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub variant: String,
+}
+
+/// A subscription which survives node restarts, resuming from the last item received.
+pub struct Subscription<T> {
+	/// The receiver of subscription events.
+	receiver: mpsc::Receiver<StreamEvent<T>>,
+	/// The task managing the connection.
+	handle: JoinHandle<()>,
+}
+
+impl<T> Subscription<T> {
+	/// Returns the next event of the subscription, or `None` once reconnection attempts have been
+	/// exhausted.
+	pub async fn next(&mut self) -> Option<StreamEvent<T>> {
+		self.receiver.recv().await
+	}
+}
+
+impl<T> Drop for Subscription<T> {
+	fn drop(&mut self) {
+		self.handle.abort();
+	}
+}
+
+/// A client which automatically reconnects to a node, re-establishing active subscriptions.
+#[derive(Clone, Debug)]
+pub struct ResilientClient {
+	/// The endpoint of the node.
+	url: String,
+	/// The policy used when reconnecting.
+	policy: ReconnectPolicy,
+}
+
+impl ResilientClient {
+	/// Creates a new client for the specified node.
+	///
+	/// # Arguments
+	/// * `url` - The endpoint of the node.
+	/// * `policy` - The policy used when reconnecting.
+	pub fn new(url: &str, policy: ReconnectPolicy) -> Self {
+		Self { url: url.to_string(), policy }
+	}
+
+	/// Connects to the node, retrying according to the reconnection policy.
+	pub async fn connect(&self) -> Result<OnlineClient<SubstrateConfig>, Error> {
+		let mut attempt = 0;
+		loop {
+			match set_up_client(&self.url).await {
+				Ok(client) => return Ok(client),
+				Err(e) if !self.policy.permits(attempt) => return Err(e),
+				Err(_) => {
+					sleep(self.policy.jittered_delay(attempt)).await;
+					attempt += 1;
+				},
+			}
+		}
+	}
+
+	/// Subscribes to finalized blocks. Blocks finalized while disconnected are not replayed, but
+	/// blocks are never reported twice.
+	///
+	/// # Arguments
+	/// * `buffering` - How blocks are buffered when the subscriber falls behind.
+	pub fn subscribe_finalized_blocks(&self, buffering: Buffering) -> Subscription<FinalizedBlock> {
+		self.subscribe(buffering, |block, _| async move { Ok(vec![block]) })
+	}
+
+	/// Subscribes to the events emitted within finalized blocks.
+	///
+	/// # Arguments
+	/// * `buffering` - How events are buffered when the subscriber falls behind.
+	pub fn subscribe_events(&self, buffering: Buffering) -> Subscription<BlockEvent> {
+		self.subscribe(buffering, |block, client| async move {
+			let events = client.events().at(block.hash).await?;
+			let mut items = vec![];
+			for event in events.iter() {
+				let event = event.map_err(subxt::Error::from)?;
+				items.push(BlockEvent {
+					block: block.number,
+					pallet: event.pallet_name().to_string(),
+					variant: event.variant_name().to_string(),
+				});
+			}
+			Ok(items)
+		})
+	}
+
+	/// Subscribes to finalized blocks, mapping each to zero or more items.
+	///
+	/// # Arguments
+	/// * `buffering` - How items are buffered when the subscriber falls behind.
+	/// * `map` - The function used to map finalized blocks to items.
+	fn subscribe<T, F, Fut>(&self, buffering: Buffering, map: F) -> Subscription<T>
+	where
+		T: Send + 'static,
+		F: Fn(FinalizedBlock, OnlineClient<SubstrateConfig>) -> Fut + Send + 'static,
+		Fut: std::future::Future<Output = Result<Vec<T>, Error>> + Send,
+	{
+		let (sender, receiver) = mpsc::channel(buffering.capacity());
+		let client = self.clone();
+		let handle = tokio::spawn(async move {
+			let mut emitter = Emitter { sender, buffering, dropped: 0 };
+			let mut last = None;
+			let mut disconnected = false;
+			loop {
+				let Ok(online) = client.connect().await else { return };
+				if disconnected && !emitter.emit(StreamEvent::Reconnected).await {
+					return;
+				}
+				let reason = match online.blocks().subscribe_finalized().await {
+					Ok(mut blocks) => loop {
+						let block = match blocks.next().await {
+							Some(Ok(block)) =>
+								FinalizedBlock { number: block.number(), hash: block.hash() },
+							Some(Err(e)) => break e.to_string(),
+							None => break "the subscription was closed by the node".to_string(),
+						};
+						if last.is_some_and(|last| block.number <= last) {
+							continue;
+						}
+						last = Some(block.number);
+						match map(block, online.clone()).await {
+							Ok(items) =>
+								for item in items {
+									if !emitter.emit(StreamEvent::Item(item)).await {
+										return;
+									}
+								},
+							Err(e) => break e.to_string(),
+						}
+					},
+					Err(e) => e.to_string(),
+				};
+				disconnected = true;
+				if !emitter.emit(StreamEvent::Disconnected(reason)).await {
+					return;
+				}
+			}
+		});
+		Subscription { receiver, handle }
+	}
+}
+
+/// Emits subscription events according to the buffering policy.
+struct Emitter<T> {
+	/// The sender of subscription events.
+	sender: mpsc::Sender<StreamEvent<T>>,
+	/// How events are buffered.
+	buffering: Buffering,
+	/// The number of items dropped since the last event was emitted.
+	dropped: u64,
+}
+
+impl<T> Emitter<T> {
+	/// Emits an event, returning whether the subscriber is still listening.
+	///
+	/// # Arguments
+	/// * `event` - The event to be emitted.
+	async fn emit(&mut self, event: StreamEvent<T>) -> bool {
+		match self.buffering {
+			Buffering::Block(_) => self.sender.send(event).await.is_ok(),
+			Buffering::DropNewest(_) => {
+				if self.dropped > 0 {
+					match self.sender.try_send(StreamEvent::Lagged(self.dropped)) {
+						Ok(()) => self.dropped = 0,
+						Err(TrySendError::Full(_)) => {
+							self.dropped += 1;
+							return true;
+						},
+						Err(TrySendError::Closed(_)) => return false,
+					}
+				}
+				match self.sender.try_send(event) {
+					Ok(()) => true,
+					Err(TrySendError::Full(_)) => {
+						self.dropped += 1;
+						true
+					},
+					Err(TrySendError::Closed(_)) => false,
+				}
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reconnect_policy_works() {
+		let policy = ReconnectPolicy {
+			initial_delay: Duration::from_secs(1),
+			max_delay: Duration::from_secs(10),
+			max_attempts: Some(3),
+			jitter: 0.5,
+		};
+		assert_eq!(policy.delay(0), Duration::from_secs(1));
+		assert_eq!(policy.delay(2), Duration::from_secs(4));
+		assert_eq!(policy.delay(4), Duration::from_secs(10));
+		assert_eq!(policy.delay(u32::MAX), Duration::from_secs(10));
+		assert!(policy.permits(2));
+		assert!(!policy.permits(3));
+		assert!(ReconnectPolicy::default().permits(u32::MAX));
+		for _ in 0..100 {
+			let delay = policy.jittered_delay(2);
+			assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+		}
+		let policy = ReconnectPolicy { jitter: 0.0, ..policy };
+		assert_eq!(policy.jittered_delay(2), Duration::from_secs(4));
+	}
+
+	#[tokio::test]
+	async fn drop_newest_reports_lag() {
+		let (sender, mut receiver) = mpsc::channel(2);
+		let mut emitter = Emitter { sender, buffering: Buffering::DropNewest(2), dropped: 0 };
+		for i in 0..5 {
+			assert!(emitter.emit(StreamEvent::Item(i)).await);
+		}
+		assert_eq!(receiver.recv().await, Some(StreamEvent::Item(0)));
+		assert_eq!(receiver.recv().await, Some(StreamEvent::Item(1)));
+		assert!(emitter.emit(StreamEvent::Item(5)).await);
+		assert_eq!(receiver.recv().await, Some(StreamEvent::Lagged(3)));
+		assert_eq!(receiver.recv().await, Some(StreamEvent::Item(5)));
+		drop(receiver);
+		assert!(!emitter.emit(StreamEvent::Item(6)).await);
+	}
+
+	#[tokio::test]
+	async fn connect_fails_after_max_attempts() {
+		let policy = ReconnectPolicy {
+			initial_delay: Duration::from_millis(1),
+			max_delay: Duration::from_millis(1),
+			max_attempts: Some(1),
+			jitter: 0.0,
+		};
+		let client = ResilientClient::new("ws://127.0.0.1:1", policy);
+		assert!(matches!(client.connect().await, Err(Error::ConnectionFailure(..))));
+		let mut blocks = client.subscribe_finalized_blocks(Buffering::default());
+		assert_eq!(blocks.next().await, None);
+	}
+}
@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, test_vectors::type_path};
+use pop_common::{decode_hex, encode_hex, parse_arg};
+use std::fmt::{Display, Formatter};
+use subxt::{
+	backend::rpc::{rpc_params, RpcClient},
+	dynamic::Value,
+	ext::{scale_encode::EncodeAsType, scale_value::scale::decode_as_type},
+	metadata::types::RuntimeApiMethodMetadata,
+	Metadata, OnlineClient, SubstrateConfig,
+};
+
+/// A method of a runtime API of the chain, as described by its metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeApiMethod {
+	/// The name of the runtime API (e.g. "AccountNonceApi").
+	pub api: String,
+	/// The name of the method (e.g. "account_nonce").
+	pub method: String,
+	/// The names and types of the inputs of the method.
+	pub inputs: Vec<(String, String)>,
+	/// The type of the output of the method.
+	pub output: String,
+}
+
+impl Display for RuntimeApiMethod {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let inputs: Vec<_> = self.inputs.iter().map(|(name, ty)| format!("{name}: {ty}")).collect();
+		write!(f, "{}::{}({}) -> {}", self.api, self.method, inputs.join(", "), self.output)
+	}
+}
+
+/// The result of a call to a runtime API.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeApiResult {
+	/// The result, decoded using the output type of the method.
+	pub value: Value<u32>,
+	/// The SCALE-encoded result, as a hex string.
+	pub encoded: String,
+}
+
+/// Lists the methods of the runtime APIs of the chain, ordered by API and method.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+pub fn runtime_api_methods(metadata: &Metadata) -> Vec<RuntimeApiMethod> {
+	let types = metadata.types();
+	let mut methods: Vec<_> = metadata
+		.runtime_api_traits()
+		.flat_map(|api| {
+			api.methods().map(move |method| RuntimeApiMethod {
+				api: api.name().to_string(),
+				method: method.name().to_string(),
+				inputs: method
+					.inputs()
+					.map(|input| (input.name.clone(), type_path(types, input.ty)))
+					.collect(),
+				output: type_path(types, method.output_ty()),
+			})
+		})
+		.collect();
+	methods.sort_by(|a, b| (&a.api, &a.method).cmp(&(&b.api, &b.method)));
+	methods
+}
+
+/// Encodes the arguments of a call to a runtime API, validating them against the inputs of the
+/// method within the metadata of the chain. Arguments may be specified in a JSON5 or Rust
+/// struct-literal style (see [`parse_arg`]).
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `api` - The name of the runtime API (e.g. "AccountNonceApi").
+/// * `method` - The name of the method (e.g. "account_nonce").
+/// * `args` - The arguments of the method, as specified.
+pub fn encode_runtime_api_args(
+	metadata: &Metadata,
+	api: &str,
+	method: &str,
+	args: &[String],
+) -> Result<Vec<u8>, Error> {
+	let definition = find_method(metadata, api, method)?;
+	if definition.inputs().len() != args.len() {
+		return Err(Error::Config(format!(
+			"`{api}::{method}` expects {} arguments, found {}",
+			definition.inputs().len(),
+			args.len()
+		)));
+	}
+	let mut encoded = vec![];
+	for (input, arg) in definition.inputs().zip(args) {
+		let value = parse_arg(&input.name, arg, input.ty, metadata.types())?;
+		value.encode_as_type_to(input.ty, metadata.types(), &mut encoded).map_err(|e| {
+			Error::Config(format!("unable to encode the argument `{}`: {e}", input.name))
+		})?;
+	}
+	Ok(encoded)
+}
+
+/// Decodes the result of a call to a runtime API, using the output type of the method within the
+/// metadata of the chain.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `api` - The name of the runtime API (e.g. "AccountNonceApi").
+/// * `method` - The name of the method (e.g. "account_nonce").
+/// * `bytes` - The SCALE-encoded result.
+pub fn decode_runtime_api_result(
+	metadata: &Metadata,
+	api: &str,
+	method: &str,
+	mut bytes: &[u8],
+) -> Result<Value<u32>, Error> {
+	let definition = find_method(metadata, api, method)?;
+	let value =
+		decode_as_type(&mut bytes, definition.output_ty(), metadata.types()).map_err(|e| {
+			Error::Config(format!("unable to decode the result of `{api}::{method}`: {e}"))
+		})?;
+	match bytes.is_empty() {
+		true => Ok(value),
+		false => Err(Error::Config(format!(
+			"the result of `{api}::{method}` is longer than its output type"
+		))),
+	}
+}
+
+/// Calls a method of a runtime API at the latest block via `state_call`, encoding its arguments
+/// and decoding its result using the metadata of the chain, so that custom runtime APIs can be
+/// exercised without generating code for them.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+/// * `api` - The name of the runtime API (e.g. "AccountNonceApi").
+/// * `method` - The name of the method (e.g. "account_nonce").
+/// * `args` - The arguments of the method, as specified (see [`parse_arg`]).
+pub async fn call_runtime_api(
+	url: &str,
+	api: &str,
+	method: &str,
+	args: &[String],
+) -> Result<RuntimeApiResult, Error> {
+	let rpc = RpcClient::from_url(url)
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+	let client = OnlineClient::<SubstrateConfig>::from_rpc_client(rpc.clone()).await?;
+	let metadata = client.metadata();
+	let encoded = encode_runtime_api_args(&metadata, api, method, args)?;
+	let result: String = rpc
+		.request("state_call", rpc_params![format!("{api}_{method}"), encode_hex(&encoded)])
+		.await?;
+	let bytes = decode_hex(&result)
+		.ok_or_else(|| Error::Config(format!("`{result}` is not a valid hex string")))?;
+	Ok(RuntimeApiResult {
+		value: decode_runtime_api_result(&metadata, api, method, &bytes)?,
+		encoded: encode_hex(&bytes),
+	})
+}
+
+fn find_method<'a>(
+	metadata: &'a Metadata,
+	api: &str,
+	method: &str,
+) -> Result<&'a RuntimeApiMethodMetadata, Error> {
+	metadata
+		.runtime_api_trait_by_name(api)
+		.and_then(|api| api.method_by_name(method))
+		.ok_or_else(|| {
+			Error::Config(format!(
+				"the `{api}::{method}` runtime API was not found in the metadata"
+			))
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::metadata_diff::tests::metadata_with_apis;
+	use scale_info::{meta_type, TypeInfo};
+	use subxt::ext::{
+		codec::Encode,
+		frame_metadata::v15::{
+			RuntimeApiMetadata, RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata,
+		},
+		scale_value::At,
+	};
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	struct Price {
+		value: u128,
+		decimals: u8,
+	}
+
+	fn metadata() -> Metadata {
+		metadata_with_apis(vec![RuntimeApiMetadata {
+			name: "OracleApi",
+			methods: vec![RuntimeApiMethodMetadata {
+				name: "price",
+				inputs: vec![
+					RuntimeApiMethodParamMetadata { name: "feed", ty: meta_type::<u32>() },
+					RuntimeApiMethodParamMetadata { name: "at", ty: meta_type::<Option<u64>>() },
+				],
+				output: meta_type::<Option<Price>>(),
+				docs: vec![],
+			}],
+			docs: vec![],
+		}])
+	}
+
+	#[test]
+	fn runtime_api_methods_works() {
+		assert_eq!(
+			runtime_api_methods(&metadata())
+				.iter()
+				.map(|m| m.to_string())
+				.collect::<Vec<_>>(),
+			["OracleApi::price(feed: u32, at: Option<u64>) -> Option<pop_parachains::runtime_api::tests::Price>"]
+		);
+	}
+
+	#[test]
+	fn encode_runtime_api_args_works() -> Result<(), Error> {
+		let metadata = metadata();
+		let args = ["7".to_string(), "Some(10)".to_string()];
+		assert_eq!(
+			encode_runtime_api_args(&metadata, "OracleApi", "price", &args)?,
+			(7u32, Some(10u64)).encode()
+		);
+		assert!(matches!(
+			encode_runtime_api_args(&metadata, "OracleApi", "price", &args[..1]),
+			Err(Error::Config(e)) if e == "`OracleApi::price` expects 2 arguments, found 1"
+		));
+		assert!(matches!(
+			encode_runtime_api_args(&metadata, "OracleApi", "feeds", &[]),
+			Err(Error::Config(e)) if e == "the `OracleApi::feeds` runtime API was not found in the metadata"
+		));
+		assert!(encode_runtime_api_args(
+			&metadata,
+			"OracleApi",
+			"price",
+			&["x".into(), "None".into()]
+		)
+		.is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn decode_runtime_api_result_works() -> Result<(), Error> {
+		let metadata = metadata();
+		// `Some(Price { value: 1_500, decimals: 2 })`.
+		let encoded = (1u8, 1_500u128, 2u8).encode();
+		let value = decode_runtime_api_result(&metadata, "OracleApi", "price", &encoded)?;
+		assert_eq!(value.at(0).and_then(|p| p.at("value")).and_then(|v| v.as_u128()), Some(1_500));
+		assert_eq!(value.at(0).and_then(|p| p.at("decimals")).and_then(|v| v.as_u128()), Some(2));
+		let trailing = [&encoded[..], &[0]].concat();
+		assert!(matches!(
+			decode_runtime_api_result(&metadata, "OracleApi", "price", &trailing),
+			Err(Error::Config(_))
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn call_runtime_api_fails_wrong_url() {
+		assert!(matches!(
+			call_runtime_api("wss://wronguri.xyz", "OracleApi", "price", &[]).await,
+			Err(Error::ConnectionFailure(_))
+		));
+	}
+}
@@ -0,0 +1,366 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use pop_common::encode_hex;
+use sp_crypto_hashing::{blake2_256, blake2_64};
+use std::{
+	fmt::{Display, Formatter},
+	fs::read,
+	io::Read,
+	path::Path,
+};
+use subxt::ext::codec::{Compact, Decode, Encode};
+
+/// The prefix of a runtime compressed with zstd, as produced by `substrate-wasm-builder`.
+const ZSTD_PREFIX: [u8; 8] = [82, 188, 83, 118, 70, 219, 142, 5];
+/// The maximum size of a decompressed runtime, guarding against decompression bombs.
+const MAX_UNCOMPRESSED_SIZE: u64 = 50 * 1024 * 1024;
+/// The custom section within which a runtime embeds its version.
+const VERSION_SECTION: &str = "runtime_version";
+/// The custom section within which a runtime embeds the versions of its runtime APIs.
+const APIS_SECTION: &str = "runtime_apis";
+/// The indices of `System` and its `set_code` call within FRAME runtimes.
+const SET_CODE_CALL: [u8; 2] = [0, 2];
+
+/// The properties of a runtime, read from its wasm without a running node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeInfo {
+	/// The name of the runtime specification (e.g. "asset-hub-paseo").
+	pub spec_name: String,
+	/// The name of the implementation of the specification.
+	pub impl_name: String,
+	/// The version of the runtime specification.
+	pub spec_version: u32,
+	/// The version of the implementation of the specification.
+	pub impl_version: u32,
+	/// The version of the transaction format.
+	pub transaction_version: u32,
+	/// The size of the runtime, in bytes.
+	pub size: usize,
+	/// The size of the runtime once decompressed, in bytes.
+	pub uncompressed_size: usize,
+	/// Whether the runtime is compressed.
+	pub compressed: bool,
+	/// The blake2-256 hash of the runtime, as authorized by upgrades.
+	pub blake2_256: String,
+	/// The blake2-256 hash of the `system.set_code` call setting the runtime, as referenced by
+	/// upgrade proposals.
+	pub proposal_hash: String,
+	/// The metadata versions which the runtime provides (e.g. 14 and 15).
+	pub metadata_versions: Vec<u32>,
+}
+
+impl RuntimeInfo {
+	/// Reads the properties of a runtime from its code, compressed or not.
+	///
+	/// # Arguments
+	/// * `code` - The code of the runtime.
+	pub fn from_code(code: &[u8]) -> Result<Self, Error> {
+		let wasm = uncompressed(code)?;
+		let compressed = code.starts_with(&ZSTD_PREFIX);
+		let sections = custom_sections(&wasm)?;
+		let section = |name: &str| sections.iter().find(|(n, _)| n == name).map(|(_, data)| *data);
+		let version = section(VERSION_SECTION).ok_or_else(|| {
+			Error::InvalidRuntime(format!("no `{VERSION_SECTION}` section found"))
+		})?;
+		let mut version = RuntimeVersion::decode(version)?;
+		if let Some(apis) = section(APIS_SECTION) {
+			version.apis = decode_apis(apis)?;
+		}
+		let mut call = SET_CODE_CALL.to_vec();
+		Compact(code.len() as u32).encode_to(&mut call);
+		call.extend_from_slice(code);
+		Ok(Self {
+			metadata_versions: version.metadata_versions(),
+			spec_name: version.spec_name,
+			impl_name: version.impl_name,
+			spec_version: version.spec_version,
+			impl_version: version.impl_version,
+			transaction_version: version.transaction_version,
+			size: code.len(),
+			uncompressed_size: wasm.len(),
+			compressed,
+			blake2_256: encode_hex(&blake2_256(code)),
+			proposal_hash: encode_hex(&blake2_256(&call)),
+		})
+	}
+}
+
+impl Display for RuntimeInfo {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let metadata: Vec<_> = self.metadata_versions.iter().map(|v| format!("V{v}")).collect();
+		writeln!(f, "Spec: {} v{}", self.spec_name, self.spec_version)?;
+		writeln!(f, "Implementation: {} v{}", self.impl_name, self.impl_version)?;
+		writeln!(f, "Transaction version: {}", self.transaction_version)?;
+		match self.compressed {
+			true => writeln!(
+				f,
+				"Size: {} bytes (compressed), {} bytes (uncompressed)",
+				self.size, self.uncompressed_size
+			)?,
+			false => writeln!(f, "Size: {} bytes (uncompressed)", self.size)?,
+		}
+		writeln!(f, "Blake2-256 hash: {}", self.blake2_256)?;
+		writeln!(f, "Proposal hash: {}", self.proposal_hash)?;
+		write!(
+			f,
+			"Metadata: {}",
+			if metadata.is_empty() { "none".into() } else { metadata.join(", ") }
+		)
+	}
+}
+
+/// Reads the properties of a runtime from its wasm file, compressed or not, without a running
+/// node.
+///
+/// # Arguments
+/// * `wasm` - The path of the runtime.
+pub fn inspect_runtime(wasm: &Path) -> Result<RuntimeInfo, Error> {
+	RuntimeInfo::from_code(&read(wasm)?)
+}
+
+/// The version of a runtime, as embedded within its `runtime_version` section (and its
+/// `System::Version` constant).
+pub(crate) struct RuntimeVersion {
+	pub(crate) spec_name: String,
+	pub(crate) impl_name: String,
+	pub(crate) spec_version: u32,
+	pub(crate) impl_version: u32,
+	pub(crate) apis: Vec<([u8; 8], u32)>,
+	pub(crate) transaction_version: u32,
+}
+
+impl RuntimeVersion {
+	/// Decodes the version, whose later fields are absent from older runtimes.
+	pub(crate) fn decode(mut input: &[u8]) -> Result<Self, Error> {
+		let input = &mut input;
+		let invalid = |e: subxt::ext::codec::Error| {
+			Error::InvalidRuntime(format!("invalid runtime version: {e}"))
+		};
+		let spec_name = String::decode(input).map_err(invalid)?;
+		let impl_name = String::decode(input).map_err(invalid)?;
+		let _authoring_version = u32::decode(input).map_err(invalid)?;
+		let spec_version = u32::decode(input).map_err(invalid)?;
+		let impl_version = u32::decode(input).map_err(invalid)?;
+		let apis = Vec::<([u8; 8], u32)>::decode(input).map_err(invalid)?;
+		let transaction_version = match input.is_empty() {
+			true => 1,
+			false => u32::decode(input).map_err(invalid)?,
+		};
+		Ok(Self { spec_name, impl_name, spec_version, impl_version, apis, transaction_version })
+	}
+
+	/// The metadata versions provided, as indicated by the version of the `Metadata` runtime
+	/// API: version 1 provides V14 metadata, whereas version 2 also provides the versions
+	/// listed by `metadata_versions` (V15 onwards), as the metadata itself is only available by
+	/// executing the runtime.
+	fn metadata_versions(&self) -> Vec<u32> {
+		let id = blake2_64(b"Metadata");
+		match self.apis.iter().find(|(api, _)| *api == id).map(|(_, version)| *version) {
+			None => vec![],
+			Some(1) => vec![14],
+			Some(_) => vec![14, 15],
+		}
+	}
+}
+
+/// Decodes the versions of the runtime APIs, embedded as consecutive pairs of identifiers and
+/// little-endian versions.
+fn decode_apis(section: &[u8]) -> Result<Vec<([u8; 8], u32)>, Error> {
+	let (apis, remainder) = section.as_chunks::<12>();
+	if !remainder.is_empty() {
+		return Err(Error::InvalidRuntime(format!("invalid `{APIS_SECTION}` section")));
+	}
+	Ok(apis
+		.iter()
+		.map(|api| {
+			let (id, version) = api.split_at(8);
+			(
+				id.try_into().expect("8 bytes; qed"),
+				u32::from_le_bytes(version.try_into().expect("4 bytes; qed")),
+			)
+		})
+		.collect())
+}
+
+/// The wasm of a runtime, decompressing it if compressed.
+///
+/// # Arguments
+/// * `code` - The code of the runtime.
+pub(crate) fn uncompressed(code: &[u8]) -> Result<Vec<u8>, Error> {
+	match code.strip_prefix(&ZSTD_PREFIX) {
+		Some(blob) => decompress(blob),
+		None => Ok(code.to_vec()),
+	}
+}
+
+/// Decompresses a runtime compressed with zstd, once its prefix has been stripped.
+fn decompress(blob: &[u8]) -> Result<Vec<u8>, Error> {
+	let invalid = |e: String| Error::InvalidRuntime(format!("unable to decompress: {e}"));
+	let decoder = ruzstd::StreamingDecoder::new(blob).map_err(|e| invalid(e.to_string()))?;
+	let mut wasm = vec![];
+	decoder
+		.take(MAX_UNCOMPRESSED_SIZE + 1)
+		.read_to_end(&mut wasm)
+		.map_err(|e| invalid(e.to_string()))?;
+	if wasm.len() as u64 > MAX_UNCOMPRESSED_SIZE {
+		return Err(invalid(format!("exceeds {MAX_UNCOMPRESSED_SIZE} bytes")));
+	}
+	Ok(wasm)
+}
+
+/// A section of a wasm module.
+pub(crate) struct Section<'a> {
+	/// The identifier of the section (e.g. 10 for the code section), zero for custom sections.
+	pub(crate) id: u8,
+	/// The name of a custom section.
+	pub(crate) name: Option<String>,
+	/// The contents of the section, excluding the name of a custom section.
+	pub(crate) data: &'a [u8],
+	/// The size of the section within the module, including its header.
+	pub(crate) size: usize,
+}
+
+/// The sections of a wasm module, in order.
+///
+/// # Arguments
+/// * `wasm` - The wasm module, uncompressed.
+pub(crate) fn sections(wasm: &[u8]) -> Result<Vec<Section<'_>>, Error> {
+	let invalid = || Error::InvalidRuntime("not a wasm module".into());
+	let mut input = wasm.strip_prefix(b"\0asm\x01\0\0\0").ok_or_else(invalid)?;
+	let mut sections = vec![];
+	while let Some((&id, rest)) = input.split_first() {
+		let start = input.len();
+		input = rest;
+		let size = leb128(&mut input).ok_or_else(invalid)? as usize;
+		if size > input.len() {
+			return Err(invalid());
+		}
+		let (mut data, rest) = input.split_at(size);
+		let size = start - rest.len();
+		input = rest;
+		let mut name = None;
+		if id == 0 {
+			let length = leb128(&mut data).ok_or_else(invalid)? as usize;
+			if length > data.len() {
+				return Err(invalid());
+			}
+			let (custom, contents) = data.split_at(length);
+			name = Some(String::from_utf8_lossy(custom).into_owned());
+			data = contents;
+		}
+		sections.push(Section { id, name, data, size });
+	}
+	Ok(sections)
+}
+
+/// The custom sections of a wasm module, as pairs of their names and contents.
+fn custom_sections(wasm: &[u8]) -> Result<Vec<(String, &[u8])>, Error> {
+	Ok(sections(wasm)?.into_iter().filter_map(|s| Some((s.name?, s.data))).collect())
+}
+
+/// Reads an unsigned LEB128-encoded integer, as used by wasm for sizes.
+pub(crate) fn leb128(input: &mut &[u8]) -> Option<u32> {
+	let mut value = 0u32;
+	for shift in (0..35).step_by(7) {
+		let (&byte, rest) = input.split_first()?;
+		*input = rest;
+		value |= ((byte & 0x7f) as u32).checked_shl(shift)?;
+		if byte & 0x80 == 0 {
+			return Some(value);
+		}
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A wasm module embedding the specified runtime version and APIs.
+	fn wasm(version: &[u8], apis: &[u8]) -> Vec<u8> {
+		let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+		for (name, data) in [(VERSION_SECTION, version), (APIS_SECTION, apis)] {
+			let mut section = vec![name.len() as u8];
+			section.extend_from_slice(name.as_bytes());
+			section.extend_from_slice(data);
+			wasm.push(0);
+			// Encodes the size as LEB128, spanning two bytes when needed.
+			match section.len() {
+				len if len < 0x80 => wasm.push(len as u8),
+				len => wasm.extend([(len as u8 & 0x7f) | 0x80, (len >> 7) as u8]),
+			}
+			wasm.extend(section);
+		}
+		wasm
+	}
+
+	fn version(transaction_version: Option<u32>) -> Vec<u8> {
+		let mut version =
+			("pop", "pop-node", 1u32, 1_002u32, 3u32, Vec::<([u8; 8], u32)>::new()).encode();
+		if let Some(transaction_version) = transaction_version {
+			transaction_version.encode_to(&mut version);
+		}
+		version
+	}
+
+	#[test]
+	fn from_code_works() -> Result<(), Error> {
+		let mut apis = blake2_64(b"Metadata").to_vec();
+		apis.extend(2u32.to_le_bytes());
+		let code = wasm(&version(Some(7)), &apis);
+		let info = RuntimeInfo::from_code(&code)?;
+		let mut call = vec![0, 2];
+		Compact(code.len() as u32).encode_to(&mut call);
+		call.extend(&code);
+		assert_eq!(
+			info,
+			RuntimeInfo {
+				spec_name: "pop".into(),
+				impl_name: "pop-node".into(),
+				spec_version: 1_002,
+				impl_version: 3,
+				transaction_version: 7,
+				size: code.len(),
+				uncompressed_size: code.len(),
+				compressed: false,
+				blake2_256: encode_hex(&blake2_256(&code)),
+				proposal_hash: encode_hex(&blake2_256(&call)),
+				metadata_versions: vec![14, 15],
+			}
+		);
+		assert!(info.to_string().contains("Metadata: V14, V15"));
+
+		// Older runtimes omit the transaction version and provide V14 metadata only.
+		let mut apis = blake2_64(b"Metadata").to_vec();
+		apis.extend(1u32.to_le_bytes());
+		let info = RuntimeInfo::from_code(&wasm(&version(None), &apis))?;
+		assert_eq!((info.transaction_version, info.metadata_versions), (1, vec![14]));
+		Ok(())
+	}
+
+	#[test]
+	fn from_code_fails_with_invalid_runtime() {
+		assert!(matches!(
+			RuntimeInfo::from_code(b"not wasm"),
+			Err(Error::InvalidRuntime(e)) if e == "not a wasm module"
+		));
+		assert!(matches!(
+			RuntimeInfo::from_code(b"\0asm\x01\0\0\0"),
+			Err(Error::InvalidRuntime(e)) if e == "no `runtime_version` section found"
+		));
+		let mut compressed = ZSTD_PREFIX.to_vec();
+		compressed.extend(b"not zstd");
+		assert!(matches!(
+			RuntimeInfo::from_code(&compressed),
+			Err(Error::InvalidRuntime(e)) if e.starts_with("unable to decompress")
+		));
+	}
+
+	#[test]
+	fn leb128_works() {
+		assert_eq!(leb128(&mut &[0x02][..]), Some(2));
+		assert_eq!(leb128(&mut &[0xe5, 0x8e, 0x26][..]), Some(624_485));
+		assert_eq!(leb128(&mut &[0x80][..]), None);
+	}
+}
@@ -0,0 +1,357 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	build::RuntimeArtifacts,
+	errors::Error,
+	runtime_info::{leb128, sections, uncompressed},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	fmt::{Display, Formatter},
+	fs::{read, read_to_string, write},
+	path::PathBuf,
+};
+
+/// The number of the largest functions reported.
+const LARGEST_FUNCTIONS: usize = 10;
+/// The growth of the compressed runtime, as a percentage, beyond which the size has regressed.
+const REGRESSION_THRESHOLD: f64 = 5.0;
+
+/// The sizes of the artifacts of a runtime, in bytes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RuntimeSize {
+	/// The size of the compact runtime.
+	pub compact: u64,
+	/// The size of the compact and compressed runtime.
+	pub compressed: u64,
+}
+
+/// A report of the size of a runtime, so that size regressions can be caught: its sizes
+/// compacted and compressed, compared to those of the previous build, and the parts of its
+/// wasm which contribute the most.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeReport {
+	/// The sizes of the runtime.
+	pub size: RuntimeSize,
+	/// The sizes of the runtime of the previous build, if known.
+	pub previous: Option<RuntimeSize>,
+	/// The sections of the compact runtime and their sizes, largest first.
+	pub sections: Vec<(String, u64)>,
+	/// The largest functions of the compact runtime and their sizes, largest first. Functions are
+	/// named if the runtime retains its name section, otherwise referred to by index.
+	pub functions: Vec<(String, u64)>,
+}
+
+impl SizeReport {
+	/// Reports the size of a runtime from its code.
+	///
+	/// # Arguments
+	/// * `compact` - The code of the compact runtime.
+	/// * `compressed` - The code of the compact and compressed runtime.
+	/// * `previous` - The sizes of the runtime of the previous build, if known.
+	pub fn new(
+		compact: &[u8],
+		compressed: &[u8],
+		previous: Option<RuntimeSize>,
+	) -> Result<Self, Error> {
+		let wasm = uncompressed(compact)?;
+		let sections = sections(&wasm)?;
+		let imported = sections
+			.iter()
+			.find(|s| s.id == 2)
+			.map(|s| imported_functions(s.data))
+			.transpose()?
+			.unwrap_or_default();
+		let names = sections
+			.iter()
+			.find(|s| s.name.as_deref() == Some("name"))
+			.and_then(|s| function_names(s.data))
+			.unwrap_or_default();
+		let mut functions = match sections.iter().find(|s| s.id == 10) {
+			Some(code) => function_sizes(code.data)?
+				.into_iter()
+				.enumerate()
+				.map(|(i, size)| {
+					let index = imported + i as u32;
+					let name =
+						names.get(&index).cloned().unwrap_or_else(|| format!("func[{index}]"));
+					(name, size)
+				})
+				.collect(),
+			None => vec![],
+		};
+		functions.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+		functions.truncate(LARGEST_FUNCTIONS);
+		let mut sections: Vec<_> = sections
+			.into_iter()
+			.map(|s| {
+				let name = match s.name {
+					Some(name) => format!("custom `{name}`"),
+					None => section_name(s.id).to_string(),
+				};
+				(name, s.size as u64)
+			})
+			.collect();
+		sections.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+		Ok(Self {
+			size: RuntimeSize {
+				compact: compact.len() as u64,
+				compressed: compressed.len() as u64,
+			},
+			previous,
+			sections,
+			functions,
+		})
+	}
+
+	/// The growth of the compressed runtime since the previous build, as a percentage.
+	pub fn growth(&self) -> Option<f64> {
+		self.previous
+			.map(|previous| percentage(self.size.compressed, previous.compressed))
+	}
+
+	/// Whether the compressed runtime grew by more than 5% since the previous build.
+	pub fn regressed(&self) -> bool {
+		self.growth().is_some_and(|growth| growth > REGRESSION_THRESHOLD)
+	}
+}
+
+impl Display for SizeReport {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let RuntimeSize { compact, compressed } = self.size;
+		write!(f, "Size: {compact} bytes (compact), {compressed} bytes (compressed)")?;
+		if let Some(previous) = self.previous {
+			let change = |current: u64, previous: u64| {
+				format!(
+					"{:+} bytes, {:+.1}%",
+					current as i64 - previous as i64,
+					percentage(current, previous)
+				)
+			};
+			write!(
+				f,
+				"\nPrevious build: {} bytes (compact, {}), {} bytes (compressed, {})",
+				previous.compact,
+				change(compact, previous.compact),
+				previous.compressed,
+				change(compressed, previous.compressed)
+			)?;
+		}
+		let total: u64 = self.sections.iter().map(|(_, size)| size).sum();
+		write!(f, "\nLargest sections:")?;
+		for (name, size) in &self.sections {
+			write!(f, "\n  {name}: {size} bytes ({:.1}%)", *size as f64 * 100.0 / total as f64)?;
+		}
+		if !self.functions.is_empty() {
+			write!(f, "\nLargest functions:")?;
+			for (name, size) in &self.functions {
+				write!(f, "\n  {name}: {size} bytes")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Reports the size of a built runtime, comparing it to the previous build. The sizes of each
+/// build are recorded alongside the artifacts, so that the next build can be compared.
+///
+/// # Arguments
+/// * `artifacts` - The artifacts of the runtime.
+pub fn runtime_size_report(artifacts: &RuntimeArtifacts) -> Result<SizeReport, Error> {
+	let baseline = baseline_path(artifacts);
+	let previous = read_to_string(&baseline).ok().and_then(|s| serde_json::from_str(&s).ok());
+	let report =
+		SizeReport::new(&read(&artifacts.compact)?, &read(&artifacts.compressed)?, previous)?;
+	write(&baseline, serde_json::to_string(&report.size)?)?;
+	Ok(report)
+}
+
+/// The path of the file recording the sizes of the last build of a runtime.
+fn baseline_path(artifacts: &RuntimeArtifacts) -> PathBuf {
+	artifacts.compact.with_extension("size.json")
+}
+
+/// The change from a previous value, as a percentage.
+fn percentage(current: u64, previous: u64) -> f64 {
+	match previous {
+		0 => 0.0,
+		previous => (current as f64 - previous as f64) * 100.0 / previous as f64,
+	}
+}
+
+/// The name of a (non-custom) section of a wasm module.
+fn section_name(id: u8) -> &'static str {
+	match id {
+		1 => "type",
+		2 => "import",
+		3 => "function",
+		4 => "table",
+		5 => "memory",
+		6 => "global",
+		7 => "export",
+		8 => "start",
+		9 => "element",
+		10 => "code",
+		11 => "data",
+		12 => "data count",
+		_ => "unknown",
+	}
+}
+
+/// The number of functions imported by a wasm module, which precede its own functions in the
+/// index space.
+///
+/// # Arguments
+/// * `section` - The contents of the import section.
+fn imported_functions(mut section: &[u8]) -> Result<u32, Error> {
+	let invalid = || Error::InvalidRuntime("invalid import section".into());
+	let input = &mut section;
+	let mut functions = 0;
+	for _ in 0..leb128(input).ok_or_else(invalid)? {
+		for _ in 0..2 {
+			let length = leb128(input).ok_or_else(invalid)? as usize;
+			*input = input.get(length..).ok_or_else(invalid)?;
+		}
+		let (&kind, rest) = input.split_first().ok_or_else(invalid)?;
+		*input = rest;
+		let skip = |input: &mut &[u8], bytes: usize| -> Result<(), Error> {
+			*input = input.get(bytes..).ok_or_else(invalid)?;
+			Ok(())
+		};
+		let limits = |input: &mut &[u8]| -> Result<(), Error> {
+			let (&flags, rest) = input.split_first().ok_or_else(invalid)?;
+			*input = rest;
+			for _ in 0..1 + (flags & 1) {
+				leb128(input).ok_or_else(invalid)?;
+			}
+			Ok(())
+		};
+		match kind {
+			0 => {
+				leb128(input).ok_or_else(invalid)?;
+				functions += 1;
+			},
+			1 => {
+				skip(input, 1)?;
+				limits(input)?;
+			},
+			2 => limits(input)?,
+			3 => skip(input, 2)?,
+			_ => return Err(invalid()),
+		}
+	}
+	Ok(functions)
+}
+
+/// The sizes of the bodies of the functions of a wasm module, in order.
+///
+/// # Arguments
+/// * `section` - The contents of the code section.
+fn function_sizes(mut section: &[u8]) -> Result<Vec<u64>, Error> {
+	let invalid = || Error::InvalidRuntime("invalid code section".into());
+	let input = &mut section;
+	let count = leb128(input).ok_or_else(invalid)?;
+	let mut sizes = Vec::with_capacity(count as usize);
+	for _ in 0..count {
+		let size = leb128(input).ok_or_else(invalid)? as usize;
+		*input = input.get(size..).ok_or_else(invalid)?;
+		sizes.push(size as u64);
+	}
+	Ok(sizes)
+}
+
+/// The names of the functions of a wasm module, by index, from its name section.
+///
+/// # Arguments
+/// * `section` - The contents of the name section.
+fn function_names(mut section: &[u8]) -> Option<HashMap<u32, String>> {
+	let input = &mut section;
+	while let Some((&id, rest)) = input.split_first() {
+		*input = rest;
+		let size = leb128(input)? as usize;
+		let mut subsection = input.get(..size)?;
+		*input = input.get(size..)?;
+		if id != 1 {
+			continue;
+		}
+		let names = &mut subsection;
+		let mut functions = HashMap::new();
+		for _ in 0..leb128(names)? {
+			let index = leb128(names)?;
+			let length = leb128(names)? as usize;
+			functions.insert(index, String::from_utf8_lossy(names.get(..length)?).into_owned());
+			*names = names.get(length..)?;
+		}
+		return Some(functions);
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A section of a wasm module.
+	fn section(id: u8, contents: &[u8]) -> Vec<u8> {
+		[&[id, contents.len() as u8][..], contents].concat()
+	}
+
+	/// A wasm module importing a function, with two functions of its own, the second of which is
+	/// named.
+	fn wasm() -> Vec<u8> {
+		let import = [&[1, 3][..], b"env", &[4], b"func", &[0, 0]].concat();
+		let code = [&[2, 2][..], &[0, 0x0b], &[4], &[0, 0x01, 0x01, 0x0b]].concat();
+		let names = [&[1, 2, 4][..], b"main"].concat();
+		let name = [&[4][..], b"name", &section(1, &names)].concat();
+		[&b"\0asm\x01\0\0\0"[..], &section(2, &import), &section(10, &code), &section(0, &name)]
+			.concat()
+	}
+
+	#[test]
+	fn size_report_works() -> Result<(), Error> {
+		let wasm = wasm();
+		let report = SizeReport::new(&wasm, &wasm[..20], None)?;
+		assert_eq!(report.size, RuntimeSize { compact: wasm.len() as u64, compressed: 20 });
+		assert_eq!(
+			report.sections,
+			vec![
+				("custom `name`".to_string(), 16),
+				("import".to_string(), 14),
+				("code".to_string(), 11)
+			]
+		);
+		assert_eq!(report.functions, vec![("main".to_string(), 4), ("func[1]".to_string(), 2)]);
+		assert_eq!(report.growth(), None);
+		assert!(!report.regressed());
+
+		let previous = RuntimeSize { compact: wasm.len() as u64, compressed: 16 };
+		let report = SizeReport::new(&wasm, &wasm[..20], Some(previous))?;
+		assert_eq!(report.growth(), Some(25.0));
+		assert!(report.regressed());
+		assert!(report.to_string().starts_with(
+			"Size: 49 bytes (compact), 20 bytes (compressed)\nPrevious build: 49 bytes (compact, +0 bytes, +0.0%), 16 bytes (compressed, +4 bytes, +25.0%)\nLargest sections:\n  custom `name`: 16 bytes (39.0%)"
+		));
+		assert!(matches!(SizeReport::new(b"not wasm", &[], None), Err(Error::InvalidRuntime(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn runtime_size_report_compares_previous_build() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let artifacts = RuntimeArtifacts {
+			package: "runtime".into(),
+			compact: temp_dir.path().join("runtime.compact.wasm"),
+			compressed: temp_dir.path().join("runtime.compact.compressed.wasm"),
+		};
+		write(&artifacts.compact, wasm())?;
+		write(&artifacts.compressed, &wasm()[..20])?;
+		assert_eq!(runtime_size_report(&artifacts)?.previous, None);
+		assert!(baseline_path(&artifacts).exists());
+		write(&artifacts.compressed, &wasm()[..30])?;
+		let report = runtime_size_report(&artifacts)?;
+		assert_eq!(report.previous, Some(RuntimeSize { compact: 49, compressed: 20 }));
+		assert_eq!(report.growth(), Some(50.0));
+		Ok(())
+	}
+}
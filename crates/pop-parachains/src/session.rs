@@ -0,0 +1,465 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{construct_extrinsic, fetch_storage, set_up_client, sign_and_submit_extrinsic},
+	errors::Error,
+	rpc::{Buffering, ReconnectPolicy, ResilientClient, StreamEvent},
+};
+use pop_common::parse_arg;
+use scale_info::TypeDef;
+use std::collections::BTreeMap;
+use subxt::{dynamic::Value, metadata::types::StorageEntryType, OnlineClient, SubstrateConfig};
+
+/// The secret key URI of the signer of a session, unless specified.
+const DEFAULT_SURI: &str = "//Alice";
+
+/// A command interpreted by a session.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionCommand {
+	/// Submits a call to a dispatchable function (e.g. `call Balances::transfer_keep_alive
+	/// Id('5GrwvaEF...') 1000`).
+	Call {
+		/// The name of the pallet.
+		pallet: String,
+		/// The name of the function.
+		function: String,
+		/// The arguments of the function.
+		args: Vec<String>,
+	},
+	/// Queries a storage entry at the latest block (e.g. `query System::Account 5GrwvaEF...`).
+	Query {
+		/// The name of the pallet.
+		pallet: String,
+		/// The name of the storage entry.
+		entry: String,
+		/// The keys of the storage entry.
+		keys: Vec<String>,
+	},
+	/// Waits for the specified number of finalized blocks (e.g. `subscribe blocks 2`).
+	Blocks(usize),
+	/// Waits for the events emitted within the specified number of finalized blocks (e.g.
+	/// `subscribe events`).
+	Events(usize),
+	/// Assigns the output of a command to a variable, referenced as `$name` by later commands
+	/// (e.g. `let account = query System::Account 5GrwvaEF...`).
+	Let {
+		/// The name of the variable.
+		name: String,
+		/// The command whose output is assigned.
+		command: Box<SessionCommand>,
+	},
+	/// Sets the secret key URI of the signer of subsequent calls (e.g. `signer //Bob`).
+	Signer(String),
+	/// Lists the variables of the session.
+	Variables,
+	/// Lists the commands executed within the session.
+	History,
+}
+
+impl SessionCommand {
+	/// Parses a command, as entered within a session.
+	///
+	/// # Arguments
+	/// * `line` - The command entered.
+	pub fn parse(line: &str) -> Result<Self, Error> {
+		let line = line.trim();
+		let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+		let rest = rest.trim();
+		match keyword {
+			"call" => {
+				let (target, args) = target(rest)?;
+				Ok(SessionCommand::Call { pallet: target.0, function: target.1, args })
+			},
+			"query" => {
+				let (target, keys) = target(rest)?;
+				Ok(SessionCommand::Query { pallet: target.0, entry: target.1, keys })
+			},
+			"subscribe" => {
+				let args = split_args(rest)?;
+				let count = match args.get(1) {
+					Some(count) => count.parse().map_err(|_| {
+						Error::Config(format!("`{count}` is not a valid number of blocks"))
+					})?,
+					None => 1,
+				};
+				match args.first().map(String::as_str) {
+					Some("blocks") if args.len() <= 2 => Ok(SessionCommand::Blocks(count)),
+					Some("events") if args.len() <= 2 => Ok(SessionCommand::Events(count)),
+					_ => Err(usage("subscribe <blocks|events> [COUNT]")),
+				}
+			},
+			"let" => {
+				let Some((name, command)) = rest.split_once('=') else {
+					return Err(usage("let <NAME> = <COMMAND>"));
+				};
+				let name = name.trim();
+				if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+					return Err(Error::Config(format!("`{name}` is not a valid variable name")));
+				}
+				let command = SessionCommand::parse(command)?;
+				if !matches!(command, SessionCommand::Call { .. } | SessionCommand::Query { .. }) {
+					return Err(Error::Config("only calls and queries can be assigned".into()));
+				}
+				Ok(SessionCommand::Let { name: name.to_string(), command: Box::new(command) })
+			},
+			"signer" => match split_args(rest)?.as_slice() {
+				[suri] => Ok(SessionCommand::Signer(suri.clone())),
+				_ => Err(usage("signer <SURI>")),
+			},
+			"vars" if rest.is_empty() => Ok(SessionCommand::Variables),
+			"history" if rest.is_empty() => Ok(SessionCommand::History),
+			_ => Err(Error::Config(format!(
+				"unknown command `{line}`, expected one of `call`, `query`, `subscribe`, `let`, \
+				 `signer`, `vars` or `history`"
+			))),
+		}
+	}
+}
+
+/// An interactive session with a chain, over which calls, queries and subscriptions are executed
+/// without reconnecting or re-fetching the metadata of the chain for every action. The outputs of
+/// commands can be assigned to variables and referenced by later commands.
+pub struct Session {
+	/// The endpoint of the node.
+	url: String,
+	/// The client used to interact with the chain.
+	client: OnlineClient<SubstrateConfig>,
+	/// The secret key URI of the signer of calls.
+	suri: String,
+	/// The variables of the session.
+	variables: BTreeMap<String, String>,
+	/// The commands executed within the session.
+	history: Vec<String>,
+}
+
+impl Session {
+	/// Connects to a chain, starting a new session.
+	///
+	/// # Arguments
+	/// * `url` - The endpoint of the node.
+	pub async fn connect(url: &str) -> Result<Self, Error> {
+		Ok(Self {
+			url: url.to_string(),
+			client: set_up_client(url).await?,
+			suri: DEFAULT_SURI.to_string(),
+			variables: BTreeMap::new(),
+			history: vec![],
+		})
+	}
+
+	/// The variables of the session, by name.
+	pub fn variables(&self) -> &BTreeMap<String, String> {
+		&self.variables
+	}
+
+	/// The commands executed within the session, in order.
+	pub fn history(&self) -> &[String] {
+		&self.history
+	}
+
+	/// Executes a command, returning its output. Variables referenced by the command are
+	/// substituted before it is parsed.
+	///
+	/// # Arguments
+	/// * `line` - The command entered.
+	pub async fn execute(&mut self, line: &str) -> Result<String, Error> {
+		let command = SessionCommand::parse(&substitute(line, &self.variables)?)?;
+		if !matches!(command, SessionCommand::History) {
+			self.history.push(line.trim().to_string());
+		}
+		self.run(command).await
+	}
+
+	/// Executes a script, one command per line, returning the output of each command. Blank
+	/// lines and lines starting with `#` are ignored.
+	///
+	/// # Arguments
+	/// * `script` - The commands to be executed.
+	pub async fn execute_script(&mut self, script: &str) -> Result<Vec<String>, Error> {
+		let mut outputs = vec![];
+		for line in script.lines().map(str::trim) {
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			outputs.push(self.execute(line).await?);
+		}
+		Ok(outputs)
+	}
+
+	async fn run(&mut self, command: SessionCommand) -> Result<String, Error> {
+		match command {
+			SessionCommand::Call { pallet, function, args } => {
+				let call = construct_extrinsic(&self.client.metadata(), &pallet, &function, &args)?;
+				let events = sign_and_submit_extrinsic(&self.client, &call, &self.suri).await?;
+				Ok(format!("{:?}", events.extrinsic_hash()))
+			},
+			SessionCommand::Query { pallet, entry, keys } => {
+				let keys = storage_keys(&self.client, &pallet, &entry, &keys)?;
+				Ok(match fetch_storage(&self.client, &pallet, &entry, keys).await? {
+					Some(value) => value.to_string(),
+					None => "None".to_string(),
+				})
+			},
+			SessionCommand::Blocks(count) => {
+				let client = ResilientClient::new(&self.url, ReconnectPolicy::default());
+				let mut subscription = client.subscribe_finalized_blocks(Buffering::default());
+				let mut blocks = vec![];
+				while blocks.len() < count {
+					match subscription.next().await {
+						Some(StreamEvent::Item(block)) =>
+							blocks.push(format!("#{} {:?}", block.number, block.hash)),
+						Some(_) => continue,
+						None => break,
+					}
+				}
+				Ok(blocks.join("\n"))
+			},
+			SessionCommand::Events(count) => {
+				let client = ResilientClient::new(&self.url, ReconnectPolicy::default());
+				let mut subscription = client.subscribe_finalized_blocks(Buffering::default());
+				let mut events = vec![];
+				for _ in 0..count {
+					let block = loop {
+						match subscription.next().await {
+							Some(StreamEvent::Item(block)) => break Some(block),
+							Some(_) => continue,
+							None => break None,
+						}
+					};
+					let Some(block) = block else { break };
+					for event in self.client.events().at(block.hash).await?.iter() {
+						let event = event.map_err(subxt::Error::from)?;
+						events.push(format!(
+							"#{} {}::{}",
+							block.number,
+							event.pallet_name(),
+							event.variant_name()
+						));
+					}
+				}
+				Ok(events.join("\n"))
+			},
+			SessionCommand::Let { name, command } => {
+				let output = Box::pin(self.run(*command)).await?;
+				self.variables.insert(name, output.clone());
+				Ok(output)
+			},
+			SessionCommand::Signer(suri) => {
+				self.suri = suri;
+				Ok(format!("Calls are now signed by {}", self.suri))
+			},
+			SessionCommand::Variables => Ok(self
+				.variables
+				.iter()
+				.map(|(name, value)| format!("{name} = {value}"))
+				.collect::<Vec<_>>()
+				.join("\n")),
+			SessionCommand::History => Ok(self
+				.history
+				.iter()
+				.enumerate()
+				.map(|(i, line)| format!("{:>3} {line}", i + 1))
+				.collect::<Vec<_>>()
+				.join("\n")),
+		}
+	}
+}
+
+/// Parses the keys of a storage entry, validating them against the metadata of the chain.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `pallet` - The name of the pallet.
+/// * `entry` - The name of the storage entry.
+/// * `keys` - The keys of the storage entry, as specified.
+fn storage_keys(
+	client: &OnlineClient<SubstrateConfig>,
+	pallet: &str,
+	entry: &str,
+	keys: &[String],
+) -> Result<Vec<Value>, Error> {
+	let metadata = client.metadata();
+	let entry_type = metadata
+		.pallet_by_name(pallet)
+		.and_then(|p| p.storage())
+		.and_then(|s| s.entry_by_name(entry))
+		.map(|e| e.entry_type().clone())
+		.ok_or_else(|| {
+			Error::Config(format!("the `{pallet}::{entry}` storage entry was not found"))
+		})?;
+	let types = match entry_type {
+		StorageEntryType::Plain(_) => vec![],
+		StorageEntryType::Map { hashers, key_ty, .. } if hashers.len() > 1 =>
+			match metadata.types().resolve(key_ty).map(|t| &t.type_def) {
+				Some(TypeDef::Tuple(tuple)) => tuple.fields.iter().map(|f| f.id).collect(),
+				_ => vec![key_ty],
+			},
+		StorageEntryType::Map { key_ty, .. } => vec![key_ty],
+	};
+	// Keys may be omitted from the end, so that all values of a map can be iterated.
+	if keys.len() > types.len() {
+		return Err(Error::Config(format!(
+			"`{pallet}::{entry}` expects at most {} keys, found {}",
+			types.len(),
+			keys.len()
+		)));
+	}
+	keys.iter()
+		.zip(types)
+		.enumerate()
+		.map(|(i, (key, ty))| Ok(parse_arg(&format!("key{i}"), key, ty, metadata.types())?))
+		.collect()
+}
+
+/// Parses the target of a call or query (e.g. `Balances::transfer_keep_alive`), followed by its
+/// arguments.
+///
+/// # Arguments
+/// * `input` - The target and arguments, as entered.
+fn target(input: &str) -> Result<((String, String), Vec<String>), Error> {
+	let mut args = split_args(input)?.into_iter();
+	let target = args.next().unwrap_or_default();
+	match target.split_once("::") {
+		Some((pallet, item)) if !pallet.is_empty() && !item.is_empty() =>
+			Ok(((pallet.to_string(), item.to_string()), args.collect())),
+		_ => Err(Error::Config(format!("`{target}` is not specified as `<Pallet>::<name>`"))),
+	}
+}
+
+/// Splits the arguments of a command by whitespace, except within quotes and brackets, so that
+/// complex arguments (e.g. `{ free: 1, reserved: 2 }`) need not be quoted.
+///
+/// # Arguments
+/// * `input` - The arguments, as entered.
+fn split_args(input: &str) -> Result<Vec<String>, Error> {
+	let (mut args, mut current, mut depth, mut quote) = (vec![], String::new(), 0usize, None);
+	for c in input.chars() {
+		match (quote, c) {
+			(Some(q), c) if c == q => quote = None,
+			(Some(_), _) => {},
+			(None, '"' | '\'') => quote = Some(c),
+			(None, '(' | '[' | '{') => depth += 1,
+			(None, ')' | ']' | '}') =>
+				depth = depth.checked_sub(1).ok_or_else(|| unbalanced(input))?,
+			(None, c) if c.is_whitespace() && depth == 0 => {
+				if !current.is_empty() {
+					args.push(std::mem::take(&mut current));
+				}
+				continue;
+			},
+			_ => {},
+		}
+		current.push(c);
+	}
+	if quote.is_some() || depth != 0 {
+		return Err(unbalanced(input));
+	}
+	if !current.is_empty() {
+		args.push(current);
+	}
+	Ok(args)
+}
+
+/// Substitutes the variables referenced by a command (e.g. `$account`) with their values.
+///
+/// # Arguments
+/// * `line` - The command entered.
+/// * `variables` - The variables of the session.
+fn substitute(line: &str, variables: &BTreeMap<String, String>) -> Result<String, Error> {
+	let mut output = String::with_capacity(line.len());
+	let mut chars = line.chars().peekable();
+	while let Some(c) = chars.next() {
+		if c != '$' {
+			output.push(c);
+			continue;
+		}
+		let mut name = String::new();
+		while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+			name.push(c);
+		}
+		match variables.get(&name) {
+			Some(value) => output.push_str(value),
+			None if name.is_empty() => output.push('$'),
+			None => return Err(Error::Config(format!("the `${name}` variable is not defined"))),
+		}
+	}
+	Ok(output)
+}
+
+fn usage(usage: &str) -> Error {
+	Error::Config(format!("expected `{usage}`"))
+}
+
+fn unbalanced(input: &str) -> Error {
+	Error::Config(format!("`{input}` has unbalanced quotes or brackets"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn parse_works() -> Result<()> {
+		assert_eq!(
+			SessionCommand::parse(
+				"call Balances::transfer_keep_alive Id('5GrwvaEF') { x: 1, y: 2 }"
+			)?,
+			SessionCommand::Call {
+				pallet: "Balances".into(),
+				function: "transfer_keep_alive".into(),
+				args: vec!["Id('5GrwvaEF')".into(), "{ x: 1, y: 2 }".into()],
+			}
+		);
+		assert_eq!(
+			SessionCommand::parse("let alice = query System::Account \"5Grw vaEF\"")?,
+			SessionCommand::Let {
+				name: "alice".into(),
+				command: Box::new(SessionCommand::Query {
+					pallet: "System".into(),
+					entry: "Account".into(),
+					keys: vec!["\"5Grw vaEF\"".into()],
+				}),
+			}
+		);
+		assert_eq!(SessionCommand::parse("subscribe blocks")?, SessionCommand::Blocks(1));
+		assert_eq!(SessionCommand::parse("subscribe events 3")?, SessionCommand::Events(3));
+		assert_eq!(SessionCommand::parse("signer //Bob")?, SessionCommand::Signer("//Bob".into()));
+		assert_eq!(SessionCommand::parse(" vars ")?, SessionCommand::Variables);
+		assert_eq!(SessionCommand::parse("history")?, SessionCommand::History);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_fails_with_invalid_command() {
+		for line in [
+			"",
+			"transfer",
+			"call Balances",
+			"query ::Account",
+			"subscribe",
+			"subscribe blocks many",
+			"let = query System::Number",
+			"let x = vars",
+			"signer",
+			"call Balances::transfer (1",
+		] {
+			assert!(matches!(SessionCommand::parse(line), Err(Error::Config(..))), "{line}");
+		}
+	}
+
+	#[test]
+	fn substitute_works() -> Result<()> {
+		let variables = BTreeMap::from([("dest".to_string(), "Id('5GrwvaEF')".to_string())]);
+		assert_eq!(
+			substitute("call Balances::transfer_keep_alive $dest 10", &variables)?,
+			"call Balances::transfer_keep_alive Id('5GrwvaEF') 10"
+		);
+		assert_eq!(substitute("signer $", &variables)?, "signer $");
+		assert!(matches!(
+			substitute("query System::Account $alice", &variables),
+			Err(Error::Config(e)) if e == "the `$alice` variable is not defined"
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,475 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{build::CODE, errors::Error};
+use duct::cmd;
+use pop_common::{decode_hex, encode_hex, gc::SNAPSHOTS_DIR};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sp_crypto_hashing::twox_128;
+use std::{
+	fs::{create_dir_all, metadata, read_dir, read_to_string, remove_file, write},
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use subxt::{
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	utils::H256,
+	SubstrateConfig,
+};
+
+/// The number of storage keys scraped per request.
+const PAGE_SIZE: u32 = 1_000;
+
+/// The state of a live chain, from which a snapshot is taken.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LiveState {
+	/// The endpoint of a node of the chain.
+	pub uri: String,
+	/// The hash of the block at which the state is taken, otherwise the latest finalized block.
+	pub at: Option<String>,
+	/// The pallets whose state is taken, otherwise all pallets.
+	#[serde(default)]
+	pub pallets: Vec<String>,
+}
+
+/// The format of a snapshot, depending on the tool consuming it.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SnapshotKind {
+	/// State scraped via remote externalities, as used by try-runtime.
+	RemoteExternalities,
+	/// The raw key-value storage of the state as JSON, as used to fork off a live chain and to
+	/// benchmark against live state.
+	RawStorage,
+}
+
+impl SnapshotKind {
+	/// The file extension of the snapshot.
+	fn extension(&self) -> &'static str {
+		match self {
+			SnapshotKind::RemoteExternalities => "snap",
+			SnapshotKind::RawStorage => "state",
+		}
+	}
+}
+
+/// The metadata of a snapshot, stored alongside it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Metadata {
+	/// The format of the snapshot.
+	kind: SnapshotKind,
+	/// The state of which the snapshot was taken.
+	state: LiveState,
+	/// When the snapshot was taken, as seconds since the Unix epoch.
+	created: u64,
+}
+
+/// A cached snapshot of the state of a live chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+	/// The path of the snapshot.
+	pub path: PathBuf,
+	/// The format of the snapshot.
+	pub kind: SnapshotKind,
+	/// The state of which the snapshot was taken.
+	pub state: LiveState,
+	/// When the snapshot was taken, as seconds since the Unix epoch.
+	pub created: u64,
+	/// The size of the snapshot, in bytes.
+	pub size: u64,
+}
+
+impl Snapshot {
+	/// The age of the snapshot.
+	pub fn age(&self) -> Duration {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+		Duration::from_secs(now.saturating_sub(self.created))
+	}
+}
+
+/// A cache of state snapshots taken from live chains, shared by the features requiring live
+/// state so that large state downloads happen once and are reused across runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshots {
+	/// The directory within which snapshots are stored.
+	dir: PathBuf,
+	/// The age after which a snapshot of the latest state is taken again.
+	max_age: Duration,
+}
+
+impl Snapshots {
+	/// Creates a new snapshot cache.
+	///
+	/// # Arguments
+	/// * `cache` - The cache directory, within which a `snapshots` directory is used.
+	/// * `max_age` - The age after which a snapshot of the latest state is taken again.
+	pub fn new(cache: &Path, max_age: Duration) -> Self {
+		Self { dir: cache.join(SNAPSHOTS_DIR), max_age }
+	}
+
+	/// The path of the snapshot of the specified state.
+	///
+	/// # Arguments
+	/// * `kind` - The format of the snapshot.
+	/// * `state` - The live state.
+	pub fn path(&self, kind: SnapshotKind, state: &LiveState) -> PathBuf {
+		let mut name = sanitise(state.uri.split("://").last().unwrap_or(&state.uri));
+		name.push('-');
+		name.push_str(&sanitise(state.at.as_deref().unwrap_or("latest")));
+		if !state.pallets.is_empty() {
+			let mut pallets = state.pallets.clone();
+			pallets.sort();
+			name.push('-');
+			name.push_str(&sanitise(&pallets.join("_")).to_lowercase());
+		}
+		self.dir.join(format!("{name}.{}", kind.extension()))
+	}
+
+	/// The cached snapshot of the specified state, if it can be reused. Snapshots at a specific
+	/// block never change, whereas those of the latest state are only reused until they reach
+	/// the maximum age.
+	///
+	/// # Arguments
+	/// * `kind` - The format of the snapshot.
+	/// * `state` - The live state.
+	pub fn get(&self, kind: SnapshotKind, state: &LiveState) -> Option<Snapshot> {
+		let snapshot = self.load(&self.path(kind, state))?;
+		(state.at.is_some() || snapshot.age() < self.max_age).then_some(snapshot)
+	}
+
+	/// Registers a snapshot of the specified state, recording its metadata ahead of the snapshot
+	/// being populated. Returns the path at which the snapshot is to be stored.
+	///
+	/// # Arguments
+	/// * `kind` - The format of the snapshot.
+	/// * `state` - The live state.
+	pub fn register(&self, kind: SnapshotKind, state: &LiveState) -> Result<PathBuf, Error> {
+		create_dir_all(&self.dir)?;
+		let path = self.path(kind, state);
+		let created = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+		let metadata = Metadata { kind, state: state.clone(), created };
+		write(metadata_path(&path), serde_json::to_string_pretty(&metadata)?)?;
+		Ok(path)
+	}
+
+	/// Takes a snapshot of the specified state via remote externalities, using the try-runtime
+	/// binary.
+	///
+	/// # Arguments
+	/// * `binary` - The path to the try-runtime binary.
+	/// * `state` - The live state.
+	pub fn create(&self, binary: &Path, state: &LiveState) -> Result<Snapshot, Error> {
+		let path = self.path(SnapshotKind::RemoteExternalities, state);
+		create_dir_all(&self.dir)?;
+		let mut args = vec!["create-snapshot".to_string(), "--uri".into(), state.uri.clone()];
+		if let Some(at) = &state.at {
+			args.extend(["--at".into(), at.clone()]);
+		}
+		for pallet in &state.pallets {
+			args.extend(["--pallet".into(), pallet.clone()]);
+		}
+		args.push(path.display().to_string());
+		cmd(binary, args).run()?;
+		self.register(SnapshotKind::RemoteExternalities, state)?;
+		self.load(&path)
+			.ok_or_else(|| Error::Config(format!("snapshot not created at {}", path.display())))
+	}
+
+	/// Reuses the cached snapshot of the specified state, otherwise takes a new one.
+	///
+	/// # Arguments
+	/// * `binary` - The path to the try-runtime binary.
+	/// * `state` - The live state.
+	pub fn get_or_create(&self, binary: &Path, state: &LiveState) -> Result<Snapshot, Error> {
+		match self.get(SnapshotKind::RemoteExternalities, state) {
+			Some(snapshot) => Ok(snapshot),
+			None => self.create(binary, state),
+		}
+	}
+
+	/// Scrapes the raw storage of the specified state via RPC, either of the specified pallets
+	/// or of the entire state.
+	///
+	/// # Arguments
+	/// * `state` - The live state.
+	pub async fn scrape(&self, state: &LiveState) -> Result<Snapshot, Error> {
+		let path = self.path(SnapshotKind::RawStorage, state);
+		create_dir_all(&self.dir)?;
+		let rpc = RpcClient::from_url(&state.uri)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+		let rpc = LegacyRpcMethods::<SubstrateConfig>::new(rpc);
+		let at = match &state.at {
+			Some(at) => decode_hex(at)
+				.filter(|hash| hash.len() == 32)
+				.map(|hash| H256::from_slice(&hash))
+				.ok_or_else(|| Error::Config(format!("invalid block hash: `{at}`")))?,
+			None => rpc.chain_get_finalized_head().await?,
+		};
+		let prefixes: Vec<Vec<u8>> = match state.pallets.is_empty() {
+			true => vec![vec![]],
+			false => state.pallets.iter().map(|p| twox_128(p.as_bytes()).to_vec()).collect(),
+		};
+		let mut storage = Map::new();
+		for prefix in prefixes {
+			let mut start = None;
+			loop {
+				let keys = rpc
+					.state_get_keys_paged(&prefix, PAGE_SIZE, start.as_deref(), Some(at))
+					.await?;
+				let values =
+					rpc.state_query_storage_at(keys.iter().map(Vec::as_slice), Some(at)).await?;
+				for (key, value) in values.into_iter().flat_map(|set| set.changes) {
+					if let Some(value) = value {
+						storage.insert(encode_hex(&key.0), encode_hex(&value.0).into());
+					}
+				}
+				if keys.len() < PAGE_SIZE as usize {
+					break;
+				}
+				start = keys.last().cloned();
+			}
+		}
+		write(&path, serde_json::to_string(&storage)?)?;
+		self.register(SnapshotKind::RawStorage, state)?;
+		self.load(&path)
+			.ok_or_else(|| Error::Config(format!("snapshot not created at {}", path.display())))
+	}
+
+	/// Reuses the cached snapshot of the raw storage of the specified state, otherwise scrapes
+	/// it.
+	///
+	/// # Arguments
+	/// * `state` - The live state.
+	pub async fn get_or_scrape(&self, state: &LiveState) -> Result<Snapshot, Error> {
+		match self.get(SnapshotKind::RawStorage, state) {
+			Some(snapshot) => Ok(snapshot),
+			None => self.scrape(state).await,
+		}
+	}
+
+	/// Lists the cached snapshots, oldest first.
+	pub fn list(&self) -> Result<Vec<Snapshot>, Error> {
+		if !self.dir.exists() {
+			return Ok(vec![]);
+		}
+		let mut snapshots: Vec<_> = read_dir(&self.dir)?
+			.flatten()
+			.map(|e| e.path())
+			.filter(|p| p.extension().is_some_and(|e| e == "json"))
+			.filter_map(|p| self.load(&p.with_extension("")))
+			.collect();
+		snapshots.sort_by_key(|s| s.created);
+		Ok(snapshots)
+	}
+
+	/// Removes the cached snapshots older than the specified age, or all snapshots if no age is
+	/// specified. Returns the snapshots removed.
+	///
+	/// # Arguments
+	/// * `older_than` - The age after which snapshots are removed.
+	pub fn prune(&self, older_than: Option<Duration>) -> Result<Vec<Snapshot>, Error> {
+		let mut pruned = vec![];
+		for snapshot in self.list()? {
+			if older_than.is_none_or(|age| snapshot.age() >= age) {
+				remove_file(&snapshot.path)?;
+				remove_file(metadata_path(&snapshot.path))?;
+				pruned.push(snapshot);
+			}
+		}
+		Ok(pruned)
+	}
+
+	/// Loads a snapshot from its path, provided it exists and its metadata is valid.
+	fn load(&self, path: &Path) -> Option<Snapshot> {
+		let size = metadata(path).ok()?.len();
+		let metadata: Metadata =
+			serde_json::from_str(&read_to_string(metadata_path(path)).ok()?).ok()?;
+		Some(Snapshot {
+			path: path.to_path_buf(),
+			kind: metadata.kind,
+			state: metadata.state,
+			created: metadata.created,
+			size,
+		})
+	}
+}
+
+/// Forks off a live chain, merging the raw storage of a snapshot into the genesis state of a raw
+/// chain specification, so that a network starts from the live state. The runtime code of the
+/// chain specification is retained. Returns the path of the chain specification generated.
+///
+/// # Arguments
+/// * `snapshot` - The snapshot of the raw storage of the live chain.
+/// * `spec` - The path of the raw chain specification.
+/// * `output` - The path of the chain specification to be generated.
+pub fn fork_off(snapshot: &Snapshot, spec: &Path, output: &Path) -> Result<PathBuf, Error> {
+	if snapshot.kind != SnapshotKind::RawStorage {
+		return Err(Error::Config(format!(
+			"{} is not a snapshot of raw storage",
+			snapshot.path.display()
+		)));
+	}
+	let storage: Map<String, Value> = serde_json::from_str(&read_to_string(&snapshot.path)?)?;
+	let mut chain_spec: Value = serde_json::from_str(&read_to_string(spec)?)?;
+	let top = chain_spec
+		.pointer_mut("/genesis/raw/top")
+		.and_then(Value::as_object_mut)
+		.ok_or_else(|| {
+			Error::Config(format!("{} is not a raw chain specification", spec.display()))
+		})?;
+	top.extend(storage.into_iter().filter(|(key, _)| key != CODE));
+	write(output, serde_json::to_string_pretty(&chain_spec)?)?;
+	Ok(output.to_path_buf())
+}
+
+/// Replaces the characters of a component of the name of a snapshot which are not permitted
+/// within file names.
+fn sanitise(component: &str) -> String {
+	component
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+		.collect()
+}
+
+/// The path of the metadata of a snapshot.
+fn metadata_path(snapshot: &Path) -> PathBuf {
+	let mut path = snapshot.as_os_str().to_owned();
+	path.push(".json");
+	path.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn state() -> LiveState {
+		LiveState { uri: "wss://rpc1.paseo.popnetwork.xyz".into(), at: None, pallets: vec![] }
+	}
+
+	#[test]
+	fn path_works() {
+		let snapshots = Snapshots::new(Path::new("/cache"), Duration::from_secs(60));
+		assert_eq!(
+			snapshots.path(SnapshotKind::RemoteExternalities, &state()),
+			PathBuf::from("/cache/snapshots/rpc1_paseo_popnetwork_xyz-latest.snap")
+		);
+		let state = LiveState {
+			at: Some("0x1234".into()),
+			pallets: vec!["System".into(), "Balances".into()],
+			..state()
+		};
+		assert_eq!(
+			snapshots.path(SnapshotKind::RemoteExternalities, &state),
+			PathBuf::from("/cache/snapshots/rpc1_paseo_popnetwork_xyz-0x1234-balances_system.snap")
+		);
+		// Blocks and pallets cannot escape the snapshot directory.
+		let state = LiveState {
+			at: Some("../../etc/passwd".into()),
+			pallets: vec!["../Balances".into()],
+			..state
+		};
+		assert_eq!(
+			snapshots.path(SnapshotKind::RawStorage, &state),
+			PathBuf::from(
+				"/cache/snapshots/rpc1_paseo_popnetwork_xyz-______etc_passwd-___balances.state"
+			)
+		);
+	}
+
+	#[test]
+	fn get_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::from_secs(60));
+		let kind = SnapshotKind::RemoteExternalities;
+		assert_eq!(snapshots.get(kind, &state()), None);
+		// A registered snapshot is only available once populated.
+		let path = snapshots.register(kind, &state())?;
+		assert_eq!(snapshots.get(kind, &state()), None);
+		write(&path, "state")?;
+		let snapshot = snapshots.get(kind, &state()).expect("snapshot is fresh");
+		assert_eq!((snapshot.path, snapshot.size, snapshot.state), (path, 5, state()));
+
+		// Snapshots of the latest state expire, whereas those at a specific block never do.
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::ZERO);
+		assert_eq!(snapshots.get(kind, &state()), None);
+		let state = LiveState { at: Some("0x1234".into()), ..state() };
+		write(snapshots.register(kind, &state)?, "state")?;
+		assert!(snapshots.get(kind, &state).is_some());
+		Ok(())
+	}
+
+	#[test]
+	fn list_and_prune_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::from_secs(60));
+		assert!(snapshots.list()?.is_empty());
+		write(snapshots.register(SnapshotKind::RemoteExternalities, &state())?, "state")?;
+		let at = LiveState { at: Some("0x1234".into()), ..state() };
+		write(snapshots.register(SnapshotKind::RemoteExternalities, &at)?, "state")?;
+		// Snapshots which were registered but never populated are ignored.
+		let pallets = LiveState { pallets: vec!["System".into()], ..state() };
+		snapshots.register(SnapshotKind::RemoteExternalities, &pallets)?;
+		let listed = snapshots.list()?;
+		assert_eq!(listed.len(), 2);
+
+		assert!(snapshots.prune(Some(Duration::from_secs(3600)))?.is_empty());
+		assert_eq!(snapshots.prune(None)?, listed);
+		assert!(snapshots.list()?.is_empty());
+		assert!(!listed[0].path.exists() && !metadata_path(&listed[0].path).exists());
+		Ok(())
+	}
+
+	#[test]
+	fn fork_off_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::from_secs(60));
+		let path = snapshots.register(SnapshotKind::RawStorage, &state())?;
+		write(
+			&path,
+			serde_json::json!({CODE: "0x01", "0xaa": "0x02", "0xbb": "0x03"}).to_string(),
+		)?;
+		let snapshot =
+			snapshots.get(SnapshotKind::RawStorage, &state()).expect("snapshot is fresh");
+		let spec = temp_dir.path().join("raw.json");
+		let top = serde_json::json!({CODE: "0x0061736d", "0xaa": "0x00", "0xcc": "0x04"});
+		write(&spec, serde_json::json!({"genesis": {"raw": {"top": top}}}).to_string())?;
+		let output = temp_dir.path().join("forked.json");
+		fork_off(&snapshot, &spec, &output)?;
+		let forked: Value = serde_json::from_str(&read_to_string(&output)?)?;
+		assert_eq!(
+			forked["genesis"]["raw"]["top"],
+			serde_json::json!({CODE: "0x0061736d", "0xaa": "0x02", "0xbb": "0x03", "0xcc": "0x04"})
+		);
+
+		// Chain specifications which are not raw cannot be forked off.
+		write(&spec, serde_json::json!({"genesis": {"runtimeGenesis": {}}}).to_string())?;
+		assert!(matches!(fork_off(&snapshot, &spec, &output), Err(Error::Config(..))));
+		let path = snapshots.register(SnapshotKind::RemoteExternalities, &state())?;
+		write(&path, "state")?;
+		let snapshot = snapshots.get(SnapshotKind::RemoteExternalities, &state()).unwrap();
+		assert!(matches!(fork_off(&snapshot, &spec, &output), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn scrape_fails_without_node() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::from_secs(60));
+		let state = LiveState { uri: "ws://127.0.0.1:1".into(), ..state() };
+		assert!(matches!(snapshots.get_or_scrape(&state).await, Err(Error::ConnectionFailure(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn create_fails_without_binary() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::from_secs(60));
+		assert!(matches!(
+			snapshots.get_or_create(Path::new("/missing/try-runtime"), &state()),
+			Err(Error::IO(..))
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{decode_storage_key, errors::Error, ChainSpec};
+use pop_common::{decode_hex, encode_hex};
+use sp_crypto_hashing::blake2_256;
+use std::fmt::{Display, Formatter};
+use subxt::Metadata;
+
+/// The human-readable properties of a chain specification, for auditing specifications which
+/// were not generated locally.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainSpecSummary {
+	/// The identifier of the chain.
+	pub id: Option<String>,
+	/// The name of the chain.
+	pub name: Option<String>,
+	/// The chain type (e.g. "Live").
+	pub chain_type: Option<String>,
+	/// The parachain ID, if the chain is a parachain.
+	pub para_id: Option<u64>,
+	/// The relay chain to which the parachain connects.
+	pub relay_chain: Option<String>,
+	/// The protocol ID.
+	pub protocol_id: Option<String>,
+	/// The bootnodes of the chain.
+	pub bootnodes: Vec<String>,
+	/// Whether the genesis is raw storage, rather than a runtime genesis config.
+	pub raw: bool,
+	/// The hash of the runtime code (blake2-256), as registered on the relay chain.
+	pub code_hash: Option<String>,
+	/// The size of the runtime code, in bytes.
+	pub code_size: Option<usize>,
+	/// The number of keys within the raw genesis storage.
+	pub storage_keys: usize,
+}
+
+impl From<&ChainSpec> for ChainSpecSummary {
+	fn from(spec: &ChainSpec) -> Self {
+		let code = spec.get_code();
+		Self {
+			id: spec.get_id().map(String::from),
+			name: spec.get_name().map(String::from),
+			chain_type: spec.get_chain_type().map(String::from),
+			para_id: spec.get_parachain_id(),
+			relay_chain: spec.get_relay_chain().map(String::from),
+			protocol_id: spec.get_protocol_id().map(String::from),
+			bootnodes: spec.get_bootnodes().into_iter().map(String::from).collect(),
+			raw: spec.is_raw(),
+			code_hash: code.as_deref().map(|code| encode_hex(&blake2_256(code))),
+			code_size: code.as_ref().map(Vec::len),
+			storage_keys: spec.get_raw_storage().map_or(0, |storage| storage.len()),
+		}
+	}
+}
+
+impl Display for ChainSpecSummary {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let unknown = "-".to_string();
+		writeln!(f, "id: {}", self.id.as_ref().unwrap_or(&unknown))?;
+		writeln!(f, "name: {}", self.name.as_ref().unwrap_or(&unknown))?;
+		writeln!(f, "chain type: {}", self.chain_type.as_ref().unwrap_or(&unknown))?;
+		if let Some(para_id) = self.para_id {
+			writeln!(f, "para id: {para_id}")?;
+		}
+		if let Some(relay_chain) = &self.relay_chain {
+			writeln!(f, "relay chain: {relay_chain}")?;
+		}
+		writeln!(f, "protocol id: {}", self.protocol_id.as_ref().unwrap_or(&unknown))?;
+		writeln!(f, "bootnodes: {}", self.bootnodes.len())?;
+		for bootnode in &self.bootnodes {
+			writeln!(f, "  {bootnode}")?;
+		}
+		writeln!(f, "genesis: {}", if self.raw { "raw" } else { "plain" })?;
+		if let (Some(hash), Some(size)) = (&self.code_hash, self.code_size) {
+			writeln!(f, "code hash: {hash} ({size} bytes)")?;
+		}
+		write!(f, "storage keys: {}", self.storage_keys)
+	}
+}
+
+/// An entry of the raw genesis storage of a chain specification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawStorageEntry {
+	/// The storage key, as a hex string.
+	pub key: String,
+	/// The value, as a hex string.
+	pub value: String,
+	/// The storage entry (and keys) to which the key refers, if it could be decoded.
+	pub decoded: Option<String>,
+}
+
+/// Decodes the keys of the raw genesis storage of a chain specification, using the metadata of
+/// the runtime, as far as possible: well-known keys (e.g. `:code`) are decoded as strings, while
+/// keys not matching any storage entry of the runtime are left undecoded.
+///
+/// # Arguments
+/// * `spec` - The raw chain specification.
+/// * `metadata` - The metadata of the runtime of the chain.
+pub fn decode_raw_storage(
+	spec: &ChainSpec,
+	metadata: &Metadata,
+) -> Result<Vec<RawStorageEntry>, Error> {
+	let storage = spec
+		.get_raw_storage()
+		.ok_or_else(|| Error::Config("expected a raw chain specification".into()))?;
+	Ok(storage
+		.iter()
+		.map(|(key, value)| {
+			let decoded = well_known_key(key)
+				.or_else(|| decode_storage_key(metadata, key).ok().map(|k| k.to_string()));
+			RawStorageEntry {
+				key: key.clone(),
+				value: value.as_str().map(String::from).unwrap_or_else(|| value.to_string()),
+				decoded,
+			}
+		})
+		.collect())
+}
+
+/// Decodes a well-known storage key, which are the UTF-8 encodings of strings prefixed with `:`.
+fn well_known_key(key: &str) -> Option<String> {
+	decode_hex(key)
+		.and_then(|key| String::from_utf8(key).ok())
+		.filter(|key| key.starts_with(':'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+
+	#[test]
+	fn summary_works() -> Result<()> {
+		let spec = ChainSpec(json!({
+			"name": "Pop Network",
+			"id": "pop",
+			"chainType": "Live",
+			"bootNodes": ["/dns/pop.r0gue.io/tcp/30333/p2p/12D3KooW"],
+			"protocolId": "pop",
+			"relay_chain": "polkadot",
+			"para_id": 3395,
+			"genesis": {"raw": {"top": {"0x3a636f6465": "0x0061736d", "0x3a686561707061676573": "0x00"}}}
+		}));
+		let summary = ChainSpecSummary::from(&spec);
+		assert_eq!(
+			summary,
+			ChainSpecSummary {
+				id: Some("pop".into()),
+				name: Some("Pop Network".into()),
+				chain_type: Some("Live".into()),
+				para_id: Some(3395),
+				relay_chain: Some("polkadot".into()),
+				protocol_id: Some("pop".into()),
+				bootnodes: vec!["/dns/pop.r0gue.io/tcp/30333/p2p/12D3KooW".into()],
+				raw: true,
+				code_hash: Some(encode_hex(&blake2_256(&[0x00, 0x61, 0x73, 0x6d]))),
+				code_size: Some(4),
+				storage_keys: 2,
+			}
+		);
+		assert!(summary.to_string().contains("para id: 3395\nrelay chain: polkadot\n"));
+		assert!(summary.to_string().ends_with("(4 bytes)\nstorage keys: 2"));
+
+		let summary =
+			ChainSpecSummary::from(&ChainSpec(json!({"genesis": {"runtimeGenesis": {}}})));
+		assert!(!summary.raw);
+		assert_eq!(summary.code_hash, None);
+		assert_eq!(summary.storage_keys, 0);
+		assert!(summary.to_string().contains("genesis: plain\n"));
+		Ok(())
+	}
+
+	#[test]
+	fn well_known_key_works() {
+		assert_eq!(well_known_key("0x3a636f6465"), Some(":code".into()));
+		assert_eq!(well_known_key("0x3a686561707061676573"), Some(":heappages".into()));
+		assert_eq!(
+			well_known_key("0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9"),
+			None
+		);
+	}
+}
@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{ChainSpec, Error};
+use serde_json::{json, Map, Value};
+use std::{
+	collections::BTreeMap,
+	fs::{read_dir, read_to_string},
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+/// The variables substituted for the placeholders of a chain specification template.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TemplateVariables {
+	/// The sudo account, substituted for `{{sudo}}`.
+	pub sudo: Option<String>,
+	/// The collator accounts, substituted for `{{collators}}` as an array.
+	pub collators: Vec<String>,
+	/// The endowed accounts and their balances, substituted for `{{endowments}}` as an array of
+	/// `[account, balance]` pairs.
+	pub endowments: Vec<(String, u128)>,
+	/// Any further variables, by name.
+	pub custom: BTreeMap<String, Value>,
+}
+
+impl TemplateVariables {
+	/// The value of a variable, if set.
+	///
+	/// # Arguments
+	/// * `name` - The name of the variable.
+	pub fn get(&self, name: &str) -> Option<Value> {
+		match name {
+			"sudo" => self.sudo.as_ref().map(|sudo| json!(sudo)),
+			"collators" => (!self.collators.is_empty()).then(|| json!(self.collators)),
+			"endowments" => (!self.endowments.is_empty()).then(|| {
+				Value::Array(
+					self.endowments
+						.iter()
+						.map(|(account, balance)| json!([account, balance]))
+						.collect(),
+				)
+			}),
+			name => self.custom.get(name).cloned(),
+		}
+	}
+}
+
+/// A chain specification template, from which the specifications of each environment (e.g.
+/// dev, testnet, mainnet) are derived.
+///
+/// The template is a plain chain specification which may contain `{{name}}` placeholders. A
+/// string consisting solely of a placeholder is replaced by the value of the variable, whereas
+/// placeholders embedded within a string are replaced by its textual representation. The overlay
+/// of an environment is located alongside the template as `<template>.<environment>.json` (e.g.
+/// `chain-spec.testnet.json` for `chain-spec.json`) and is merged into the template, before
+/// placeholders are substituted.
+pub struct ChainSpecTemplate {
+	path: PathBuf,
+	template: Value,
+}
+
+impl ChainSpecTemplate {
+	/// Loads a chain specification template.
+	///
+	/// # Arguments
+	/// * `path` - The path of the template.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		if !path.is_file() {
+			return Err(Error::MissingChainSpec(path.display().to_string()));
+		}
+		Ok(Self { path: path.to_path_buf(), template: parse(path)? })
+	}
+
+	/// The path of the overlay of an environment.
+	///
+	/// # Arguments
+	/// * `environment` - The name of the environment.
+	pub fn overlay_path(&self, environment: &str) -> PathBuf {
+		self.path.with_file_name(format!("{}.{environment}.json", self.stem()))
+	}
+
+	/// The environments for which an overlay is located alongside the template.
+	pub fn environments(&self) -> Result<Vec<String>, Error> {
+		let directory = match self.path.parent() {
+			Some(parent) if !parent.as_os_str().is_empty() => parent,
+			_ => Path::new("."),
+		};
+		let prefix = format!("{}.", self.stem());
+		let mut environments: Vec<_> = read_dir(directory)?
+			.filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+			.filter_map(|name| {
+				let environment = name.strip_prefix(&prefix)?.strip_suffix(".json")?;
+				(!environment.is_empty() && !environment.contains('.'))
+					.then(|| environment.to_string())
+			})
+			.collect();
+		environments.sort();
+		Ok(environments)
+	}
+
+	/// Renders the chain specification of an environment, merging its overlay into the template
+	/// and substituting the variables for any placeholders.
+	///
+	/// # Arguments
+	/// * `environment` - The name of the environment, if any.
+	/// * `variables` - The variables substituted for the placeholders.
+	pub fn render(
+		&self,
+		environment: Option<&str>,
+		variables: &TemplateVariables,
+	) -> Result<ChainSpec, Error> {
+		let mut spec = self.template.clone();
+		if let Some(environment) = environment {
+			let path = self.overlay_path(environment);
+			if !path.is_file() {
+				let environments = self.environments()?;
+				return Err(Error::Config(match environments.is_empty() {
+					true => format!(
+						"no overlay of the `{environment}` environment found at {}",
+						path.display()
+					),
+					false => format!(
+						"no overlay of the `{environment}` environment found at {}, expected one of {}",
+						path.display(),
+						environments.join(", ")
+					),
+				}));
+			}
+			merge(&mut spec, parse(&path)?);
+		}
+		substitute(&mut spec, variables)
+			.map_err(|e| Error::Config(format!("{e} in {}", self.path.display())))?;
+		Ok(ChainSpec(spec))
+	}
+
+	fn stem(&self) -> &str {
+		self.path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default()
+	}
+}
+
+/// Parses a chain specification template or overlay.
+fn parse(path: &Path) -> Result<Value, Error> {
+	Value::from_str(&read_to_string(path)?)
+		.map_err(|e| Error::Config(format!("invalid chain spec template {}: {e}", path.display())))
+}
+
+/// Merges an overlay into a value: objects are merged recursively, a `null` removes the key and
+/// any other value replaces that of the base.
+///
+/// # Arguments
+/// * `base` - The value into which the overlay is merged.
+/// * `overlay` - The overlay.
+pub(crate) fn merge(base: &mut Value, overlay: Value) {
+	match (base, overlay) {
+		(Value::Object(base), Value::Object(overlay)) =>
+			for (key, value) in overlay {
+				match value {
+					Value::Null => {
+						base.remove(&key);
+					},
+					value => merge(base.entry(key).or_insert(Value::Object(Map::new())), value),
+				}
+			},
+		(base, overlay) => *base = overlay,
+	}
+}
+
+/// Substitutes the variables for the placeholders within a value.
+///
+/// # Arguments
+/// * `value` - The value containing placeholders.
+/// * `variables` - The variables substituted for the placeholders.
+fn substitute(value: &mut Value, variables: &TemplateVariables) -> Result<(), String> {
+	match value {
+		Value::String(string) => {
+			if let Some(name) = placeholder(string) {
+				*value = variable(name, variables)?;
+				return Ok(());
+			}
+			let mut rendered = String::new();
+			let mut remaining = string.as_str();
+			while let Some(start) = remaining.find("{{") {
+				let Some(end) = remaining[start..].find("}}") else { break };
+				rendered.push_str(&remaining[..start]);
+				rendered.push_str(
+					&match variable(&remaining[start + 2..start + end], variables)? {
+						Value::String(value) => value,
+						value => value.to_string(),
+					},
+				);
+				remaining = &remaining[start + end + 2..];
+			}
+			rendered.push_str(remaining);
+			*string = rendered;
+		},
+		Value::Array(values) =>
+			for value in values {
+				substitute(value, variables)?;
+			},
+		Value::Object(values) =>
+			for value in values.values_mut() {
+				substitute(value, variables)?;
+			},
+		_ => {},
+	}
+	Ok(())
+}
+
+/// The name of the variable, if a string consists solely of a placeholder.
+fn placeholder(string: &str) -> Option<&str> {
+	let name = string.trim().strip_prefix("{{")?.strip_suffix("}}")?;
+	(!name.contains("{{") && !name.contains("}}")).then_some(name)
+}
+
+/// The value of the variable of a placeholder.
+fn variable(name: &str, variables: &TemplateVariables) -> Result<Value, String> {
+	let name = name.trim();
+	variables
+		.get(name)
+		.ok_or_else(|| format!("the `{{{{{name}}}}}` placeholder is not set"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	const TEMPLATE: &str = r#"{
+		"name": "{{name}} Testnet",
+		"chainType": "Development",
+		"para_id": 2000,
+		"genesis": {
+			"runtimeGenesis": {
+				"patch": {
+					"balances": { "balances": "{{endowments}}" },
+					"collatorSelection": { "invulnerables": "{{collators}}" },
+					"sudo": { "key": "{{ sudo }}" }
+				}
+			}
+		}
+	}"#;
+
+	fn variables() -> TemplateVariables {
+		TemplateVariables {
+			sudo: Some("Alice".into()),
+			collators: vec!["Alice".into(), "Bob".into()],
+			endowments: vec![("Alice".into(), 1_000)],
+			custom: BTreeMap::from([("name".into(), json!("Pop"))]),
+		}
+	}
+
+	#[test]
+	fn render_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("chain-spec.json");
+		write(&path, TEMPLATE)?;
+		write(
+			temp_dir.path().join("chain-spec.mainnet.json"),
+			r#"{ "chainType": "Live", "para_id": 3000, "genesis": { "runtimeGenesis": { "patch": { "sudo": null } } } }"#,
+		)?;
+		let template = ChainSpecTemplate::load(&path)?;
+		assert_eq!(template.environments()?, ["mainnet"]);
+		assert_eq!(
+			template.overlay_path("mainnet"),
+			temp_dir.path().join("chain-spec.mainnet.json")
+		);
+
+		let spec = template.render(None, &variables())?.0;
+		assert_eq!(spec["name"], "Pop Testnet");
+		let patch = &spec["genesis"]["runtimeGenesis"]["patch"];
+		assert_eq!(patch["balances"]["balances"], json!([["Alice", 1_000]]));
+		assert_eq!(patch["collatorSelection"]["invulnerables"], json!(["Alice", "Bob"]));
+		assert_eq!(patch["sudo"]["key"], "Alice");
+
+		let spec = template.render(Some("mainnet"), &variables())?;
+		assert_eq!(spec.get_chain_type(), Some("Live"));
+		assert_eq!(spec.get_parachain_id(), Some(3000));
+		assert!(spec.0["genesis"]["runtimeGenesis"]["patch"].get("sudo").is_none());
+		assert_eq!(
+			spec.0["genesis"]["runtimeGenesis"]["patch"]["collatorSelection"]["invulnerables"],
+			json!(["Alice", "Bob"])
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn render_fails_with_unset_placeholder_or_environment() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("chain-spec.json");
+		write(&path, TEMPLATE)?;
+		let template = ChainSpecTemplate::load(&path)?;
+		let variables = TemplateVariables { sudo: None, ..variables() };
+		assert!(matches!(
+			template.render(None, &variables),
+			Err(Error::Config(e)) if e.starts_with("the `{{sudo}}` placeholder is not set in")
+		));
+		assert!(matches!(
+			template.render(Some("testnet"), &variables),
+			Err(Error::Config(e)) if e.starts_with("no overlay of the `testnet` environment found")
+		));
+		assert!(matches!(
+			ChainSpecTemplate::load(&temp_dir.path().join("missing.json")),
+			Err(Error::MissingChainSpec(_))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn merge_works() {
+		let mut base = json!({ "a": { "b": 1, "c": [1, 2] }, "d": "e" });
+		merge(&mut base, json!({ "a": { "b": 2, "c": [3], "f": true }, "d": null, "g": {} }));
+		assert_eq!(base, json!({ "a": { "b": 2, "c": [3], "f": true }, "g": {} }));
+	}
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::ChainSpec;
+use pop_common::{account::validate_address, parse_account};
+use std::fmt::{Display, Formatter};
+
+/// A structural problem within a chain specification, which would prevent the chain from
+/// launching or connecting as intended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpecDiagnostic {
+	/// The chain specification has no `para_id`.
+	MissingParaId,
+	/// The relay chain of the chain specification is not the one expected.
+	RelayChainMismatch {
+		/// The relay chain expected.
+		expected: String,
+		/// The relay chain of the chain specification, if any.
+		found: Option<String>,
+	},
+	/// The chain specification has no bootnodes, so that nodes cannot discover each other.
+	NoBootnodes,
+	/// An account endowed within the genesis balances is not a valid address.
+	InvalidEndowment {
+		/// The account, as specified.
+		account: String,
+		/// Why the account is invalid.
+		reason: String,
+	},
+	/// The raw genesis storage does not contain the runtime code (`:code`).
+	MissingCode,
+}
+
+impl Display for SpecDiagnostic {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SpecDiagnostic::MissingParaId => write!(f, "missing `para_id`"),
+			SpecDiagnostic::RelayChainMismatch { expected, found: Some(found) } =>
+				write!(f, "`relay_chain` is `{found}`, expected `{expected}`"),
+			SpecDiagnostic::RelayChainMismatch { expected, found: None } =>
+				write!(f, "missing `relay_chain`, expected `{expected}`"),
+			SpecDiagnostic::NoBootnodes => write!(f, "`bootNodes` is empty"),
+			SpecDiagnostic::InvalidEndowment { account, reason } =>
+				write!(f, "invalid account endowed within `balances`: {account} ({reason})"),
+			SpecDiagnostic::MissingCode =>
+				write!(f, "the raw genesis storage does not contain the runtime code (`:code`)"),
+		}
+	}
+}
+
+/// Checks a chain specification for structural problems before it is used, returning the
+/// problems found.
+///
+/// # Arguments
+/// * `spec` - The chain specification, either plain or raw.
+/// * `relay_chain` - The relay chain to which the parachain is expected to connect, if known.
+/// * `require_bootnodes` - Whether the chain specification must contain bootnodes, as is the case
+///   for live networks.
+pub fn validate_chain_spec(
+	spec: &ChainSpec,
+	relay_chain: Option<&str>,
+	require_bootnodes: bool,
+) -> Vec<SpecDiagnostic> {
+	let mut diagnostics = vec![];
+	if spec.get_parachain_id().is_none() {
+		diagnostics.push(SpecDiagnostic::MissingParaId);
+	}
+	if let Some(expected) = relay_chain {
+		let found = spec.get_relay_chain();
+		if found != Some(expected) {
+			diagnostics.push(SpecDiagnostic::RelayChainMismatch {
+				expected: expected.to_string(),
+				found: found.map(String::from),
+			});
+		}
+	}
+	if require_bootnodes && spec.get_bootnodes().is_empty() {
+		diagnostics.push(SpecDiagnostic::NoBootnodes);
+	}
+	let prefix = spec
+		.0
+		.pointer("/properties/ss58Format")
+		.and_then(|p| p.as_u64())
+		.and_then(|p| u16::try_from(p).ok());
+	let endowments = spec
+		.0
+		.pointer("/genesis/runtimeGenesis/patch/balances/balances")
+		.and_then(|b| b.as_array())
+		.into_iter()
+		.flatten();
+	for account in endowments.filter_map(|e| e.get(0).and_then(|a| a.as_str())) {
+		let valid = match prefix {
+			Some(prefix) => validate_address(account, prefix).map(|_| ()),
+			None => parse_account(account).map(|_| ()),
+		};
+		if let Err(e) = valid {
+			diagnostics.push(SpecDiagnostic::InvalidEndowment {
+				account: account.to_string(),
+				reason: e.to_string(),
+			});
+		}
+	}
+	if spec.is_raw() && spec.get_code().is_none() {
+		diagnostics.push(SpecDiagnostic::MissingCode);
+	}
+	diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	#[test]
+	fn validate_chain_spec_works() {
+		let spec = ChainSpec(json!({
+			"bootNodes": ["/dns/pop.r0gue.io/tcp/30333/p2p/12D3KooW"],
+			"relay_chain": "paseo",
+			"para_id": 4385,
+			"properties": {"ss58Format": 42},
+			"genesis": {"runtimeGenesis": {"patch": {"balances": {"balances": [[ALICE, 1]]}}}}
+		}));
+		assert!(validate_chain_spec(&spec, Some("paseo"), true).is_empty());
+
+		let spec = ChainSpec(json!({
+			"bootNodes": [],
+			"relay_chain": "rococo-local",
+			"properties": {"ss58Format": 0},
+			"genesis": {"runtimeGenesis": {"patch": {"balances": {"balances": [
+				[ALICE, 1],
+				["invalid", 1]
+			]}}}}
+		}));
+		let diagnostics = validate_chain_spec(&spec, Some("paseo"), true);
+		assert_eq!(diagnostics.len(), 5);
+		assert_eq!(diagnostics[0], SpecDiagnostic::MissingParaId);
+		assert_eq!(diagnostics[1].to_string(), "`relay_chain` is `rococo-local`, expected `paseo`");
+		assert_eq!(diagnostics[2], SpecDiagnostic::NoBootnodes);
+		assert!(matches!(
+			&diagnostics[3],
+			SpecDiagnostic::InvalidEndowment { account, reason }
+				if account == ALICE && reason.contains("did you mean")
+		));
+		assert!(matches!(
+			&diagnostics[4],
+			SpecDiagnostic::InvalidEndowment { account, .. } if account == "invalid"
+		));
+		// Bootnodes are only required when specified.
+		assert_eq!(validate_chain_spec(&spec, None, false).len(), 3);
+	}
+
+	#[test]
+	fn validate_raw_chain_spec_works() {
+		let spec = ChainSpec(json!({
+			"relay_chain": "paseo",
+			"para_id": 4385,
+			"genesis": {"raw": {"top": {"0x3a636f6465": "0x0061736d"}}}
+		}));
+		assert!(validate_chain_spec(&spec, None, false).is_empty());
+		let spec = ChainSpec(json!({"para_id": 4385, "genesis": {"raw": {"top": {}}}}));
+		assert_eq!(
+			validate_chain_spec(&spec, Some("paseo"), false),
+			[
+				SpecDiagnostic::RelayChainMismatch { expected: "paseo".into(), found: None },
+				SpecDiagnostic::MissingCode
+			]
+		);
+	}
+}
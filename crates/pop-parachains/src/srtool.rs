@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{build::locate_runtime, errors::Error};
+use pop_common::{
+	container::{Container, Engine},
+	Features,
+};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The srtool image, within which runtimes are built deterministically.
+pub const SRTOOL_IMAGE: &str = "docker.io/paritytech/srtool";
+/// The default tag of the srtool image, which determines the Rust toolchain used.
+pub const SRTOOL_TAG: &str = "1.81.0";
+/// The directory within the container at which the project is mounted.
+const BUILD_DIR: &str = "/build";
+
+/// The configuration of a deterministic runtime build.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Srtool {
+	/// The tag of the srtool image, which determines the Rust toolchain used.
+	pub tag: String,
+	/// The runtime package to be built, otherwise located within the project.
+	pub package: Option<String>,
+	/// The profile with which the runtime is built.
+	pub profile: String,
+	/// The cargo features with which the runtime is built.
+	pub features: Features,
+}
+
+impl Default for Srtool {
+	fn default() -> Self {
+		Self {
+			tag: SRTOOL_TAG.to_string(),
+			package: None,
+			profile: "release".to_string(),
+			features: Features::default(),
+		}
+	}
+}
+
+impl Srtool {
+	/// The container within which the runtime of the project is built.
+	///
+	/// # Arguments
+	/// * `project` - The path to the parachain project.
+	fn container(&self, project: &Path) -> Result<Container, Error> {
+		let (package, dir) = locate_runtime(project, self.package.as_deref())?;
+		let runtime_dir = dir.strip_prefix(project).unwrap_or(Path::new(""));
+		let mut build_opts = self.features.features.join(",");
+		if !build_opts.is_empty() {
+			build_opts = format!("--features {build_opts}");
+		}
+		let mut container = Container::new(format!("{SRTOOL_IMAGE}:{}", self.tag))
+			.mount(project, BUILD_DIR)
+			.env("PACKAGE", package)
+			.env("RUNTIME_DIR", runtime_dir.display().to_string())
+			.env("PROFILE", &self.profile)
+			.env("BUILD_OPTS", build_opts);
+		if self.features.no_default_features {
+			container = container.env("DEFAULT_FEATURES", "");
+		}
+		Ok(container)
+	}
+}
+
+/// The digest of a runtime, as required for governance submissions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeDigest {
+	/// The path of the runtime.
+	pub wasm: PathBuf,
+	/// The size of the runtime, in bytes.
+	pub size: u64,
+	/// The blake2-256 hash of the runtime, as authorized by upgrade proposals.
+	pub blake2_256: String,
+	/// The hash of the proposal setting the runtime.
+	pub proposal_hash: String,
+	/// The IPFS hash of the runtime.
+	pub ipfs_hash: String,
+}
+
+/// The result of a deterministic runtime build.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeterministicBuild {
+	/// The name of the runtime package.
+	pub package: String,
+	/// The version of the Rust toolchain with which the runtime was built.
+	pub rustc: String,
+	/// The digest of the compact runtime.
+	pub compact: RuntimeDigest,
+	/// The digest of the compact and compressed runtime.
+	pub compressed: RuntimeDigest,
+}
+
+/// The report emitted by srtool.
+#[derive(Deserialize)]
+struct Report {
+	pkg: String,
+	#[serde(default)]
+	rustc: String,
+	runtimes: Runtimes,
+}
+
+#[derive(Deserialize)]
+struct Runtimes {
+	compact: Output,
+	compressed: Output,
+}
+
+#[derive(Deserialize)]
+struct Output {
+	wasm: String,
+	subwasm: Subwasm,
+}
+
+#[derive(Deserialize)]
+struct Subwasm {
+	size: u64,
+	blake2_256: String,
+	proposal_hash: String,
+	ipfs_hash: String,
+}
+
+impl Output {
+	/// The digest of the runtime, whose path is resolved within the project.
+	fn digest(self, project: &Path) -> RuntimeDigest {
+		let wasm = self.wasm.trim_start_matches(BUILD_DIR).trim_start_matches('/');
+		RuntimeDigest {
+			wasm: project.join(wasm),
+			size: self.subwasm.size,
+			blake2_256: self.subwasm.blake2_256,
+			proposal_hash: self.subwasm.proposal_hash,
+			ipfs_hash: self.subwasm.ipfs_hash,
+		}
+	}
+}
+
+/// Builds the runtime of a parachain deterministically within the srtool container, so that
+/// anyone can reproduce the runtime and verify its digest before voting on an upgrade.
+///
+/// # Arguments
+/// * `project` - The path to the parachain project.
+/// * `srtool` - The configuration of the build.
+pub fn build_deterministic_runtime(
+	project: &Path,
+	srtool: &Srtool,
+) -> Result<DeterministicBuild, Error> {
+	let project = project.canonicalize()?;
+	let container = srtool.container(&project)?;
+	let output = container.run(Engine::detect()?, &["build", "--app", "--json", "-cM"])?;
+	parse_report(&output, &project)
+}
+
+/// Parses the report emitted by srtool, being the last line of its output.
+///
+/// # Arguments
+/// * `output` - The output of srtool.
+/// * `project` - The path to the parachain project.
+fn parse_report(output: &str, project: &Path) -> Result<DeterministicBuild, Error> {
+	let report = output
+		.lines()
+		.rev()
+		.find(|line| line.trim_start().starts_with('{'))
+		.ok_or_else(|| Error::Config("srtool did not emit a report".into()))?;
+	let report: Report = serde_json::from_str(report)?;
+	Ok(DeterministicBuild {
+		package: report.pkg,
+		rustc: report.rustc,
+		compact: report.runtimes.compact.digest(project),
+		compressed: report.runtimes.compressed.digest(project),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs;
+
+	fn project() -> Result<tempfile::TempDir> {
+		let temp_dir = tempfile::tempdir()?;
+		let runtime = temp_dir.path().join("runtime");
+		fs::create_dir_all(runtime.join("src"))?;
+		fs::write(
+			runtime.join("Cargo.toml"),
+			"[package]\nname = \"parachain-template-runtime\"\nversion = \"0.1.0\"\n\n[build-dependencies]\nsubstrate-wasm-builder = \"23\"\n",
+		)?;
+		fs::write(runtime.join("src/lib.rs"), "")?;
+		Ok(temp_dir)
+	}
+
+	#[test]
+	fn container_works() -> Result<()> {
+		let project = project()?;
+		let srtool = Srtool {
+			features: Features {
+				features: vec!["on-chain-release-build".into()],
+				..Default::default()
+			},
+			..Default::default()
+		};
+		assert_eq!(
+			srtool.container(project.path())?.args(&["build"]),
+			[
+				"run".to_string(),
+				"--rm".into(),
+				"-v".into(),
+				format!("{}:/build", project.path().display()),
+				"-e".into(),
+				"PACKAGE=parachain-template-runtime".into(),
+				"-e".into(),
+				"RUNTIME_DIR=runtime".into(),
+				"-e".into(),
+				"PROFILE=release".into(),
+				"-e".into(),
+				"BUILD_OPTS=--features on-chain-release-build".into(),
+				"docker.io/paritytech/srtool:1.81.0".into(),
+				"build".into(),
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_report_works() -> Result<()> {
+		let subwasm = |size: u64, hash: &str| {
+			format!(
+				r#"{{"size":{size},"blake2_256":"0x{hash}","proposal_hash":"0xp{hash}","ipfs_hash":"Qm{hash}"}}"#
+			)
+		};
+		let output = format!(
+			"Building parachain-template-runtime...\n{{\"pkg\":\"parachain-template-runtime\",\"rustc\":\"rustc 1.81.0\",\"runtimes\":{{\"compact\":{{\"wasm\":\"/build/runtime/target/srtool/release/wbuild/parachain-template-runtime/parachain_template_runtime.compact.wasm\",\"subwasm\":{}}},\"compressed\":{{\"wasm\":\"runtime/target/srtool/release/wbuild/parachain-template-runtime/parachain_template_runtime.compact.compressed.wasm\",\"subwasm\":{}}}}}}}\n",
+			subwasm(200, "aa"),
+			subwasm(100, "bb")
+		);
+		let project = Path::new("/project");
+		let build = parse_report(&output, project)?;
+		assert_eq!(build.package, "parachain-template-runtime");
+		assert_eq!(build.rustc, "rustc 1.81.0");
+		let wbuild =
+			project.join("runtime/target/srtool/release/wbuild/parachain-template-runtime");
+		assert_eq!(
+			build.compact,
+			RuntimeDigest {
+				wasm: wbuild.join("parachain_template_runtime.compact.wasm"),
+				size: 200,
+				blake2_256: "0xaa".into(),
+				proposal_hash: "0xpaa".into(),
+				ipfs_hash: "Qmaa".into(),
+			}
+		);
+		assert_eq!(
+			build.compressed.wasm,
+			wbuild.join("parachain_template_runtime.compact.compressed.wasm")
+		);
+		assert_eq!(build.compressed.blake2_256, "0xbb");
+
+		assert!(matches!(parse_report("no report", project), Err(Error::Config(..))));
+		Ok(())
+	}
+}
@@ -0,0 +1,277 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use pop_common::{decode_hex, encode_hex, parse_arg};
+use scale_info::{PortableRegistry, TypeDef};
+use sp_crypto_hashing::{blake2_128, blake2_256, twox_128, twox_256, twox_64};
+use std::fmt::{Display, Formatter};
+use subxt::{
+	dynamic::Value,
+	ext::{scale_encode::EncodeAsType, scale_value::scale::decode_as_type},
+	metadata::types::{StorageEntryType, StorageHasher},
+	Metadata,
+};
+
+/// Computes the storage key of a storage entry, as a hex string, using the hashers of the entry
+/// within the metadata of the chain. Keys may be omitted from the end of a map, so that the
+/// prefix under which all of its values are stored is computed instead.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `pallet` - The name of the pallet (e.g. "System").
+/// * `entry` - The name of the storage entry (e.g. "Account").
+/// * `keys` - The keys of the storage entry, as specified.
+pub fn storage_key(
+	metadata: &Metadata,
+	pallet: &str,
+	entry: &str,
+	keys: &[String],
+) -> Result<String, Error> {
+	let (prefix, hashers, types) = entry_keys(metadata, pallet, entry)?;
+	if keys.len() > types.len() {
+		return Err(Error::Config(format!(
+			"`{pallet}::{entry}` expects at most {} keys, found {}",
+			types.len(),
+			keys.len()
+		)));
+	}
+	let values = keys
+		.iter()
+		.zip(&types)
+		.enumerate()
+		.map(|(i, (key, ty))| Ok(parse_arg(&format!("key{i}"), key, *ty, metadata.types())?))
+		.collect::<Result<Vec<_>, Error>>()?;
+	let mut key = root(&prefix, entry);
+	key.extend(encode_keys(&values, &hashers, &types, metadata.types())?);
+	Ok(encode_hex(&key))
+}
+
+/// A storage key, decoded into the storage entry and keys to which it refers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedStorageKey {
+	/// The name of the pallet.
+	pub pallet: String,
+	/// The name of the storage entry.
+	pub entry: String,
+	/// The keys of the storage entry, which may be fewer than those of the map when the key is a
+	/// prefix.
+	pub keys: Vec<StorageKeyPart>,
+}
+
+impl Display for DecodedStorageKey {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}::{}", self.pallet, self.entry)?;
+		if !self.keys.is_empty() {
+			let keys: Vec<_> = self.keys.iter().map(ToString::to_string).collect();
+			write!(f, "({})", keys.join(", "))?;
+		}
+		Ok(())
+	}
+}
+
+/// A key of a storage entry, decoded from a storage key.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StorageKeyPart {
+	/// A key recovered from the storage key, as it is hashed with a concatenating (or the
+	/// identity) hasher.
+	Value(String),
+	/// The hash of a key, as it is hashed with an opaque hasher.
+	Hash(String),
+}
+
+impl Display for StorageKeyPart {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			StorageKeyPart::Value(value) => write!(f, "{value}"),
+			StorageKeyPart::Hash(hash) => write!(f, "hash({hash})"),
+		}
+	}
+}
+
+/// Decodes a storage key into the storage entry and keys to which it refers, using the metadata
+/// of the chain. Keys hashed with an opaque hasher cannot be recovered, so only their hashes are
+/// returned.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `key` - The storage key, as a hex string.
+pub fn decode_storage_key(metadata: &Metadata, key: &str) -> Result<DecodedStorageKey, Error> {
+	let bytes = decode_hex(key)
+		.ok_or_else(|| Error::Config(format!("`{key}` is not a valid hex string")))?;
+	for pallet in metadata.pallets() {
+		let Some(storage) = pallet.storage() else { continue };
+		for entry in storage.entries() {
+			let root = root(storage.prefix(), entry.name());
+			let Some(remainder) = bytes.strip_prefix(root.as_slice()) else { continue };
+			let (_, hashers, types) = entry_keys(metadata, pallet.name(), entry.name())?;
+			return Ok(DecodedStorageKey {
+				pallet: pallet.name().to_string(),
+				entry: entry.name().to_string(),
+				keys: decode_keys(remainder, &hashers, &types, metadata.types())?,
+			});
+		}
+	}
+	Err(Error::Config(format!("`{key}` does not match any storage entry of the chain")))
+}
+
+/// The storage prefix of the pallet, along with the hashers and types of the keys of a storage
+/// entry.
+fn entry_keys(
+	metadata: &Metadata,
+	pallet: &str,
+	entry: &str,
+) -> Result<(String, Vec<StorageHasher>, Vec<u32>), Error> {
+	let not_found =
+		|| Error::Config(format!("the `{pallet}::{entry}` storage entry was not found"));
+	let storage = metadata
+		.pallet_by_name(pallet)
+		.and_then(|p| p.storage())
+		.ok_or_else(not_found)?;
+	let entry_type = storage.entry_by_name(entry).map(|e| e.entry_type()).ok_or_else(not_found)?;
+	let (hashers, types) = match entry_type {
+		StorageEntryType::Plain(_) => (vec![], vec![]),
+		StorageEntryType::Map { hashers, key_ty, .. } if hashers.len() > 1 =>
+			match metadata.types().resolve(*key_ty).map(|t| &t.type_def) {
+				Some(TypeDef::Tuple(tuple)) =>
+					(hashers.clone(), tuple.fields.iter().map(|f| f.id).collect()),
+				_ => (hashers.clone(), vec![*key_ty]),
+			},
+		StorageEntryType::Map { hashers, key_ty, .. } => (hashers.clone(), vec![*key_ty]),
+	};
+	Ok((storage.prefix().to_string(), hashers, types))
+}
+
+/// The key under which all values of a storage entry are stored.
+fn root(prefix: &str, entry: &str) -> Vec<u8> {
+	[twox_128(prefix.as_bytes()), twox_128(entry.as_bytes())].concat()
+}
+
+/// Encodes and hashes the keys of a storage entry.
+fn encode_keys(
+	values: &[Value],
+	hashers: &[StorageHasher],
+	types: &[u32],
+	registry: &PortableRegistry,
+) -> Result<Vec<u8>, Error> {
+	let mut key = vec![];
+	for ((value, hasher), ty) in values.iter().zip(hashers).zip(types) {
+		let encoded = value
+			.encode_as_type(*ty, registry)
+			.map_err(|e| Error::Config(format!("unable to encode the key {value}: {e}")))?;
+		key.extend(match hasher {
+			StorageHasher::Identity => encoded,
+			StorageHasher::Blake2_128 => blake2_128(&encoded).to_vec(),
+			StorageHasher::Blake2_256 => blake2_256(&encoded).to_vec(),
+			StorageHasher::Blake2_128Concat => [&blake2_128(&encoded)[..], &encoded].concat(),
+			StorageHasher::Twox128 => twox_128(&encoded).to_vec(),
+			StorageHasher::Twox256 => twox_256(&encoded).to_vec(),
+			StorageHasher::Twox64Concat => [&twox_64(&encoded)[..], &encoded].concat(),
+		});
+	}
+	Ok(key)
+}
+
+/// Decodes the keys of a storage entry, following the root of the storage key.
+fn decode_keys(
+	mut bytes: &[u8],
+	hashers: &[StorageHasher],
+	types: &[u32],
+	registry: &PortableRegistry,
+) -> Result<Vec<StorageKeyPart>, Error> {
+	let invalid = || Error::Config("the storage key is longer than expected".into());
+	let mut keys = vec![];
+	for (hasher, ty) in hashers.iter().zip(types) {
+		if bytes.is_empty() {
+			break;
+		}
+		let (length, concat) = match hasher {
+			StorageHasher::Identity => (0, true),
+			StorageHasher::Blake2_128 | StorageHasher::Twox128 => (16, false),
+			StorageHasher::Blake2_256 | StorageHasher::Twox256 => (32, false),
+			StorageHasher::Blake2_128Concat => (16, true),
+			StorageHasher::Twox64Concat => (8, true),
+		};
+		let (hash, remainder) = bytes.split_at_checked(length).ok_or_else(invalid)?;
+		bytes = remainder;
+		if !concat {
+			keys.push(StorageKeyPart::Hash(encode_hex(hash)));
+			continue;
+		}
+		let value = decode_as_type(&mut bytes, *ty, registry)
+			.map_err(|e| Error::Config(format!("unable to decode the storage key: {e}")))?;
+		keys.push(StorageKeyPart::Value(value.to_string()));
+	}
+	match bytes.is_empty() {
+		true => Ok(keys),
+		false => Err(invalid()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, Registry};
+
+	fn registry() -> (PortableRegistry, u32, u32) {
+		let mut registry = Registry::new();
+		let (account, index) = (
+			registry.register_type(&meta_type::<[u8; 4]>()),
+			registry.register_type(&meta_type::<u32>()),
+		);
+		(registry.into(), account.id, index.id)
+	}
+
+	#[test]
+	fn root_works() {
+		assert_eq!(
+			encode_hex(&root("System", "Account")),
+			"0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9"
+		);
+	}
+
+	#[test]
+	fn encode_and_decode_keys_works() -> Result<(), Error> {
+		let (registry, account, index) = registry();
+		let hashers = [StorageHasher::Blake2_128Concat, StorageHasher::Twox64Concat];
+		let types = [account, index];
+		let values = [Value::from_bytes([1, 2, 3, 4]), Value::u128(7)];
+		let key = encode_keys(&values, &hashers, &types, &registry)?;
+		assert_eq!(key.len(), 16 + 4 + 8 + 4);
+		assert_eq!(&key[16..20], &[1, 2, 3, 4]);
+		assert_eq!(&key[28..], &7u32.to_le_bytes());
+		assert_eq!(
+			decode_keys(&key, &hashers, &types, &registry)?,
+			[
+				StorageKeyPart::Value(Value::from_bytes([1, 2, 3, 4]).to_string()),
+				StorageKeyPart::Value("7".into())
+			]
+		);
+		// Prefixes decode to fewer keys.
+		assert_eq!(decode_keys(&key[..20], &hashers, &types, &registry)?.len(), 1);
+		assert!(matches!(
+			decode_keys(&key[..24], &hashers, &types, &registry),
+			Err(Error::Config(_))
+		));
+
+		// Keys hashed with opaque hashers cannot be recovered.
+		let hashers = [StorageHasher::Twox128];
+		let key = encode_keys(&values[1..], &hashers, &types[1..], &registry)?;
+		assert_eq!(
+			decode_keys(&key, &hashers, &types[1..], &registry)?,
+			[StorageKeyPart::Hash(encode_hex(&twox_128(&7u32.to_le_bytes())))]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn display_decoded_storage_key_works() {
+		let key = DecodedStorageKey {
+			pallet: "Multisig".into(),
+			entry: "Multisigs".into(),
+			keys: vec![StorageKeyPart::Value("7".into()), StorageKeyPart::Hash("0x01".into())],
+		};
+		assert_eq!(key.to_string(), "Multisig::Multisigs(7, hash(0x01))");
+		let key = DecodedStorageKey { keys: vec![], ..key };
+		assert_eq!(key.to_string(), "Multisig::Multisigs");
+	}
+}
@@ -0,0 +1,499 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use pop_common::encode_hex;
+use scale_info::{Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde::Serialize;
+use serde_json::{json, Map};
+use std::collections::HashSet;
+use subxt::{
+	ext::{
+		scale_encode::EncodeAsType,
+		scale_value::{scale::decode_as_type, BitSequence, Composite, Primitive, ValueDef},
+	},
+	Metadata,
+};
+
+/// The depth beyond which sequences are left empty and the variant with the fewest fields is
+/// chosen, so that recursive types (e.g. calls batching calls) remain bounded.
+const MAX_DEPTH: usize = 8;
+/// The number of candidate values considered per test vector requested, as candidates with the
+/// same encoding are discarded.
+const CANDIDATES_PER_VECTOR: usize = 4;
+/// The largest integer which JSON parsers are guaranteed to represent exactly.
+const MAX_SAFE_INTEGER: u128 = (1 << 53) - 1;
+
+type Value = subxt::dynamic::Value;
+
+/// A test vector: a value of a type along with its SCALE encoding.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TestVector {
+	/// The value, as JSON. Named composites are objects and unnamed composites are arrays,
+	/// variants are externally tagged (e.g. `{"Some": [1]}`, or `"None"` without fields),
+	/// integers beyond the range representable exactly by JSON parsers are decimal strings and
+	/// 256-bit integers are little-endian hex strings.
+	pub value: serde_json::Value,
+	/// The value, as displayed by `subxt` (e.g. `Some(1)`).
+	pub display: String,
+	/// The SCALE encoding of the value, as a hex string.
+	pub hex: String,
+}
+
+/// Deterministic test vectors of a type within the metadata of a chain.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TestVectors {
+	/// The path of the type (e.g. `sp_runtime::multiaddress::MultiAddress`).
+	pub type_path: String,
+	/// The identifier of the type within the type registry of the metadata.
+	pub type_id: u32,
+	/// The test vectors.
+	pub vectors: Vec<TestVector>,
+}
+
+impl TestVectors {
+	/// The test vectors, as pretty-printed JSON.
+	pub fn to_json(&self) -> Result<String, Error> {
+		Ok(serde_json::to_string_pretty(self)?)
+	}
+}
+
+/// Generates deterministic encode/decode test vectors of a type within the metadata of a chain,
+/// covering the boundaries of its primitives, each of its variants and sequences of varying
+/// lengths. The same metadata always produces the same vectors, so that they can be committed
+/// and checked against clients in other languages.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the chain.
+/// * `path` - The type: its path (e.g. `sp_runtime::multiaddress::MultiAddress`), the last segment
+///   of its path if unique (e.g. `MultiAddress`), a primitive (e.g. `u128`) or its identifier
+///   within the type registry (e.g. `#10`).
+/// * `count` - The maximum number of test vectors generated.
+pub fn generate_test_vectors(
+	metadata: &Metadata,
+	path: &str,
+	count: usize,
+) -> Result<TestVectors, Error> {
+	let registry = metadata.types();
+	let type_id = resolve_type(registry, path)?;
+	let sampler = Sampler { registry };
+	let (mut vectors, mut encodings) = (vec![], HashSet::new());
+	for index in 0..count.saturating_mul(CANDIDATES_PER_VECTOR) {
+		if vectors.len() == count {
+			break;
+		}
+		let value = sampler.sample(type_id, index, 0)?;
+		let encoded = value
+			.encode_as_type(type_id, registry)
+			.map_err(|e| Error::Config(format!("unable to encode `{value}`: {e}")))?;
+		if !encodings.insert(encoded.clone()) {
+			continue;
+		}
+		// Ensure the encoding round-trips, so that only valid vectors are published.
+		let input = &mut &encoded[..];
+		decode_as_type(input, type_id, registry)
+			.map_err(|e| Error::Config(format!("unable to decode `{value}`: {e}")))?;
+		if !input.is_empty() {
+			return Err(Error::Config(format!("`{value}` was not decoded entirely")));
+		}
+		vectors.push(TestVector {
+			value: to_json(&value),
+			display: value.to_string(),
+			hex: encode_hex(&encoded),
+		});
+	}
+	Ok(TestVectors { type_path: type_path(registry, type_id), type_id, vectors })
+}
+
+/// Resolves a type within a type registry, returning its identifier.
+///
+/// # Arguments
+/// * `registry` - The type registry.
+/// * `path` - The type: its path, the last segment of its path if unique, a primitive or its
+///   identifier (e.g. `#10`).
+pub fn resolve_type(registry: &PortableRegistry, path: &str) -> Result<u32, Error> {
+	if let Some(id) = path.strip_prefix('#') {
+		let id = id
+			.parse()
+			.map_err(|_| Error::Config(format!("invalid type identifier: {path}")))?;
+		return registry
+			.resolve(id)
+			.map(|_| id)
+			.ok_or_else(|| Error::Config(format!("no type with identifier {id}")));
+	}
+	let types = || registry.types.iter();
+	let primitives: Vec<_> = types()
+		.filter(|t| matches!(&t.ty.type_def, TypeDef::Primitive(p) if primitive_name(p) == path))
+		.collect();
+	let exact: Vec<_> = types().filter(|t| t.ty.path.segments.join("::") == path).collect();
+	let matches = match (primitives.is_empty(), exact.is_empty()) {
+		(false, _) => primitives,
+		(_, false) => exact,
+		_ => types()
+			.filter(|t| t.ty.path.segments.last().is_some_and(|s| s == path))
+			.collect(),
+	};
+	match matches.as_slice() {
+		[] => Err(Error::Config(format!("no type `{path}` within the metadata"))),
+		[ty] => Ok(ty.id),
+		candidates => {
+			let candidates: Vec<_> = candidates
+				.iter()
+				.map(|t| format!("#{} {}", t.id, type_path(registry, t.id)))
+				.collect();
+			Err(Error::Config(format!(
+				"`{path}` is ambiguous, specify one of: {}",
+				candidates.join(", ")
+			)))
+		},
+	}
+}
+
+/// The path of a type, including its type parameters (e.g. `Option<u32>`).
+pub(crate) fn type_path(registry: &PortableRegistry, id: u32) -> String {
+	let Some(ty) = registry.resolve(id) else { return format!("#{id}") };
+	let name = match &ty.type_def {
+		TypeDef::Primitive(p) => return primitive_name(p).to_string(),
+		TypeDef::Compact(c) => return format!("Compact<{}>", type_path(registry, c.type_param.id)),
+		TypeDef::Sequence(s) => return format!("Vec<{}>", type_path(registry, s.type_param.id)),
+		TypeDef::Array(a) =>
+			return format!("[{}; {}]", type_path(registry, a.type_param.id), a.len),
+		TypeDef::Tuple(t) => {
+			let fields: Vec<_> = t.fields.iter().map(|f| type_path(registry, f.id)).collect();
+			return format!("({})", fields.join(", "));
+		},
+		_ => ty.path.segments.join("::"),
+	};
+	let params: Vec<_> = ty
+		.type_params
+		.iter()
+		.filter_map(|p| p.ty.map(|t| type_path(registry, t.id)))
+		.collect();
+	match params.is_empty() {
+		true => name,
+		false => format!("{name}<{}>", params.join(", ")),
+	}
+}
+
+/// The name of a primitive type (e.g. `u128`).
+fn primitive_name(primitive: &TypeDefPrimitive) -> &'static str {
+	use TypeDefPrimitive::*;
+	match primitive {
+		Bool => "bool",
+		Char => "char",
+		Str => "str",
+		U8 => "u8",
+		U16 => "u16",
+		U32 => "u32",
+		U64 => "u64",
+		U128 => "u128",
+		U256 => "u256",
+		I8 => "i8",
+		I16 => "i16",
+		I32 => "i32",
+		I64 => "i64",
+		I128 => "i128",
+		I256 => "i256",
+	}
+}
+
+/// Deterministically samples values of the types within a type registry.
+struct Sampler<'a> {
+	registry: &'a PortableRegistry,
+}
+
+impl Sampler<'_> {
+	/// Samples a value of a type.
+	///
+	/// # Arguments
+	/// * `id` - The identifier of the type.
+	/// * `index` - The index of the sample, by which the value is chosen.
+	/// * `depth` - The nesting depth of the value.
+	fn sample(&self, id: u32, index: usize, depth: usize) -> Result<Value, Error> {
+		let ty = self
+			.registry
+			.resolve(id)
+			.ok_or_else(|| Error::Config(format!("no type with identifier {id}")))?;
+		let nested = depth + 1;
+		Ok(match &ty.type_def {
+			TypeDef::Composite(composite) => Value::without_context(ValueDef::Composite(
+				self.fields(&composite.fields, index, nested)?,
+			)),
+			TypeDef::Variant(variant) => {
+				let variants = &variant.variants;
+				let chosen = match depth >= MAX_DEPTH {
+					true => variants.iter().min_by_key(|v| v.fields.len()),
+					false => variants.get(index % variants.len().max(1)),
+				}
+				.ok_or_else(|| {
+					Error::Config(format!(
+						"`{}` has no variants, so cannot be instantiated",
+						type_path(self.registry, id)
+					))
+				})?;
+				let fields = self.fields(&chosen.fields, index / variants.len(), nested)?;
+				Value::variant(chosen.name.clone(), fields)
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = if depth >= MAX_DEPTH { 0 } else { index % 3 };
+				self.unnamed((0..len).map(|i| (sequence.type_param.id, index + i)), nested)?
+			},
+			TypeDef::Array(array) => self.unnamed(
+				(0..array.len as usize).map(|i| (array.type_param.id, index + i)),
+				nested,
+			)?,
+			TypeDef::Tuple(tuple) => self
+				.unnamed(tuple.fields.iter().enumerate().map(|(i, f)| (f.id, index + i)), nested)?,
+			TypeDef::Primitive(primitive) => Value::primitive(sample_primitive(primitive, index)),
+			TypeDef::Compact(compact) => self.compact(compact.type_param.id, index, nested)?,
+			TypeDef::BitSequence(_) =>
+				Value::bit_sequence(BitSequence::from_iter((0..index % 10).map(|i| i % 3 == 0))),
+		})
+	}
+
+	/// Samples the fields of a composite or variant, named if all fields are named.
+	fn fields(
+		&self,
+		fields: &[Field<scale_info::form::PortableForm>],
+		index: usize,
+		depth: usize,
+	) -> Result<Composite<()>, Error> {
+		let values = fields
+			.iter()
+			.enumerate()
+			.map(|(i, field)| self.sample(field.ty.id, index + i, depth))
+			.collect::<Result<Vec<_>, Error>>()?;
+		let names: Option<Vec<_>> = fields.iter().map(|f| f.name.clone()).collect();
+		Ok(match names.filter(|names| !names.is_empty()) {
+			Some(names) => Composite::named(names.into_iter().zip(values)),
+			None => Composite::unnamed(values),
+		})
+	}
+
+	/// Samples an unnamed composite of the specified types and sample indices.
+	fn unnamed(
+		&self,
+		items: impl Iterator<Item = (u32, usize)>,
+		depth: usize,
+	) -> Result<Value, Error> {
+		let values = items
+			.map(|(id, index)| self.sample(id, index, depth))
+			.collect::<Result<Vec<_>, Error>>()?;
+		Ok(Value::unnamed_composite(values))
+	}
+
+	/// Samples a compact value, favouring the boundaries between the modes of the compact
+	/// encoding.
+	fn compact(&self, id: u32, index: usize, depth: usize) -> Result<Value, Error> {
+		const BOUNDARIES: [u128; 8] =
+			[0, 63, 64, 16_383, 16_384, (1 << 30) - 1, 1 << 30, u128::MAX];
+		let ty = self
+			.registry
+			.resolve(id)
+			.ok_or_else(|| Error::Config(format!("no type with identifier {id}")))?;
+		match &ty.type_def {
+			TypeDef::Primitive(primitive) => match unsigned_max(primitive) {
+				Some(max) => Ok(Value::u128(BOUNDARIES[index % BOUNDARIES.len()].min(max))),
+				None => self.sample(id, index, depth),
+			},
+			// Compact wrappers of a single field (e.g. `Perbill`) encode as their field.
+			TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+				let field = &composite.fields[0];
+				let value = self.compact(field.ty.id, index, depth + 1)?;
+				Ok(Value::without_context(ValueDef::Composite(match &field.name {
+					Some(name) => Composite::named([(name.clone(), value)]),
+					None => Composite::unnamed([value]),
+				})))
+			},
+			_ => self.sample(id, index, depth),
+		}
+	}
+}
+
+/// The maximum value of an unsigned primitive of up to 128 bits.
+fn unsigned_max(primitive: &TypeDefPrimitive) -> Option<u128> {
+	use TypeDefPrimitive::*;
+	match primitive {
+		U8 => Some(u8::MAX.into()),
+		U16 => Some(u16::MAX.into()),
+		U32 => Some(u32::MAX.into()),
+		U64 => Some(u64::MAX.into()),
+		U128 => Some(u128::MAX),
+		_ => None,
+	}
+}
+
+/// Samples a primitive value: zero, the extremes of its range and a fixed bit pattern.
+fn sample_primitive(primitive: &TypeDefPrimitive, index: usize) -> Primitive {
+	use TypeDefPrimitive::*;
+	const PATTERN: u128 = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+	let pick = |values: &[u128]| values[index % values.len()];
+	match primitive {
+		Bool => Primitive::Bool(index % 2 == 1),
+		Char => Primitive::Char(['a', 'Z', 'ü', '🚀'][index % 4]),
+		Str => Primitive::String(
+			[
+				"",
+				"pop",
+				"Ünïcødé",
+				"a string longer than sixty-three bytes, so that its length is encoded as two bytes",
+			][index % 4]
+				.to_string(),
+		),
+		U8 | U16 | U32 | U64 | U128 => {
+			let max = unsigned_max(primitive).unwrap_or(u128::MAX);
+			Primitive::U128(pick(&[0, max, 1, PATTERN & max, (max >> 1) + 1]))
+		},
+		I8 | I16 | I32 | I64 | I128 => {
+			let (min, max): (i128, i128) = match primitive {
+				I8 => (i8::MIN.into(), i8::MAX.into()),
+				I16 => (i16::MIN.into(), i16::MAX.into()),
+				I32 => (i32::MIN.into(), i32::MAX.into()),
+				I64 => (i64::MIN.into(), i64::MAX.into()),
+				_ => (i128::MIN, i128::MAX),
+			};
+			Primitive::I128([0, -1, min, max, 1][index % 5])
+		},
+		U256 | I256 => {
+			let mut pattern = [0u8; 32];
+			pattern[..16].copy_from_slice(&PATTERN.to_le_bytes());
+			let bytes = [[0u8; 32], [u8::MAX; 32], pattern][index % 3];
+			match primitive {
+				U256 => Primitive::U256(bytes),
+				_ => Primitive::I256(bytes),
+			}
+		},
+	}
+}
+
+/// Converts a value to JSON, as documented by [`TestVector::value`].
+fn to_json(value: &Value) -> serde_json::Value {
+	let composite = |composite: &Composite<()>| match composite {
+		Composite::Named(fields) => serde_json::Value::Object(
+			fields
+				.iter()
+				.map(|(name, value)| (name.clone(), to_json(value)))
+				.collect::<Map<_, _>>(),
+		),
+		Composite::Unnamed(values) => values.iter().map(to_json).collect(),
+	};
+	match &value.value {
+		ValueDef::Composite(fields) => composite(fields),
+		ValueDef::Variant(variant) if variant.values.is_empty() => json!(variant.name),
+		ValueDef::Variant(variant) => json!({ variant.name.clone(): composite(&variant.values) }),
+		ValueDef::BitSequence(bits) => bits.iter().map(serde_json::Value::Bool).collect(),
+		ValueDef::Primitive(primitive) => match primitive {
+			Primitive::Bool(value) => json!(value),
+			Primitive::Char(value) => json!(value),
+			Primitive::String(value) => json!(value),
+			Primitive::U128(value) if *value <= MAX_SAFE_INTEGER => json!(*value as u64),
+			Primitive::U128(value) => json!(value.to_string()),
+			Primitive::I128(value) if value.unsigned_abs() <= MAX_SAFE_INTEGER =>
+				json!(*value as i64),
+			Primitive::I128(value) => json!(value.to_string()),
+			Primitive::U256(bytes) | Primitive::I256(bytes) => json!(encode_hex(bytes)),
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::metadata_diff::tests::{metadata, CallV1, Pallet};
+	use scale_info::{meta_type, TypeInfo};
+	use subxt::ext::codec::Compact;
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	enum Status {
+		Active,
+		Frozen { until: u32 },
+	}
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	struct Account {
+		nonce: u32,
+		balance: Compact<u128>,
+		name: Vec<u8>,
+		status: Status,
+		tags: Option<[u8; 2]>,
+	}
+
+	fn runtime() -> Metadata {
+		metadata(vec![Pallet {
+			name: "Accounts",
+			index: 10,
+			calls: Some(meta_type::<CallV1>()),
+			events: None,
+			storage: vec![
+				("Account", meta_type::<Account>()),
+				("Flag", meta_type::<Option<bool>>()),
+			],
+			constants: vec![],
+		}])
+	}
+
+	#[test]
+	fn resolve_type_works() {
+		let metadata = runtime();
+		let registry = metadata.types();
+		let id = resolve_type(registry, "Account").unwrap();
+		assert_eq!(
+			resolve_type(registry, "pop_parachains::test_vectors::tests::Account").unwrap(),
+			id
+		);
+		assert_eq!(resolve_type(registry, &format!("#{id}")).unwrap(), id);
+		assert_eq!(type_path(registry, resolve_type(registry, "u128").unwrap()), "u128");
+		assert!(matches!(
+			resolve_type(registry, "Missing"),
+			Err(Error::Config(e)) if e == "no type `Missing` within the metadata"
+		));
+		assert!(matches!(
+			resolve_type(registry, "Option"),
+			Err(Error::Config(e)) if e.starts_with("`Option` is ambiguous") && e.contains("Option<bool>")
+		));
+	}
+
+	#[test]
+	fn generate_test_vectors_works() -> anyhow::Result<()> {
+		let metadata = runtime();
+		let vectors = generate_test_vectors(&metadata, "Account", 6)?;
+		assert_eq!(vectors.type_path, "pop_parachains::test_vectors::tests::Account");
+		assert_eq!(vectors.vectors.len(), 6);
+		// Generation is deterministic.
+		assert_eq!(generate_test_vectors(&metadata, "Account", 6)?, vectors);
+		let vector = &vectors.vectors[5];
+		assert_eq!(
+			vector.value,
+			json!({
+				"nonce": 0,
+				"balance": 1073741824,
+				"name": [1],
+				"status": "Active",
+				"tags": { "Some": [[128, 0]] },
+			})
+		);
+		assert_eq!(vector.hex, "0x000000000300000040040100018000");
+		Ok(())
+	}
+
+	#[test]
+	fn generate_test_vectors_deduplicates() -> anyhow::Result<()> {
+		// A status has a single encoding when active and five sampled encodings when frozen.
+		let vectors = generate_test_vectors(&runtime(), "Status", 10)?;
+		assert_eq!(vectors.vectors.len(), 6);
+		assert_eq!(vectors.vectors[0].value, json!("Active"));
+		assert_eq!(vectors.vectors[0].hex, "0x00");
+		Ok(())
+	}
+
+	#[test]
+	fn to_json_works() {
+		assert_eq!(to_json(&Value::u128(u128::MAX)), json!(u128::MAX.to_string()));
+		assert_eq!(to_json(&Value::i128(-1)), json!(-1));
+		assert_eq!(
+			to_json(&Value::unnamed_variant("Some", [Value::bool(true)])),
+			json!({ "Some": [true] })
+		);
+	}
+}
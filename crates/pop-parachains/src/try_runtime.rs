@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	errors::Error,
+	snapshot::{LiveState, Snapshots},
+};
+use duct::cmd;
+use std::path::{Path, PathBuf};
+
+/// The name of the try-runtime binary.
+const BINARY: &str = "try-runtime";
+/// How the try-runtime binary can be installed.
+const INSTALL: &str =
+	"install it via `cargo install --git https://github.com/paritytech/try-runtime-cli --locked`";
+
+/// A check executed against live state via try-runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TryRuntimeCheck {
+	/// Executes the runtime upgrade, including its migrations, validating the weight consumed
+	/// and the `pre_upgrade`/`post_upgrade` hooks.
+	OnRuntimeUpgrade,
+	/// Produces and executes the specified number of empty blocks on top of the state,
+	/// validating the `try_state` hooks of each pallet.
+	FastForward(u32),
+}
+
+impl TryRuntimeCheck {
+	/// The arguments of the try-runtime subcommand executing the check.
+	fn args(&self) -> Vec<String> {
+		match self {
+			TryRuntimeCheck::OnRuntimeUpgrade =>
+				vec!["on-runtime-upgrade".into(), "--checks=all".into()],
+			TryRuntimeCheck::FastForward(blocks) => vec![
+				"fast-forward".into(),
+				"--n-blocks".into(),
+				blocks.to_string(),
+				"--try-state".into(),
+				"all".into(),
+			],
+		}
+	}
+}
+
+/// Locates the try-runtime binary, either at the specified path or on the `PATH`.
+///
+/// # Arguments
+/// * `binary` - An optional path to the binary.
+pub fn try_runtime_binary(binary: Option<&Path>) -> Result<PathBuf, Error> {
+	let binary = binary.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(BINARY));
+	cmd(&binary, ["--version"])
+		.stdout_null()
+		.stderr_null()
+		.run()
+		.map_err(|_| Error::MissingBinary(format!("{}: {INSTALL}", binary.display())))?;
+	Ok(binary)
+}
+
+/// Executes a check of a runtime against state scraped from a live endpoint, so that migrations
+/// and weights are validated against realistic data sizes. The scraped state is cached as a
+/// snapshot and reused by subsequent checks. Returns the path of the snapshot used.
+///
+/// The runtime must be built with the `try-runtime` feature enabled.
+///
+/// # Arguments
+/// * `binary` - The path to the try-runtime binary.
+/// * `runtime` - The path to the runtime to be checked.
+/// * `check` - The check to be executed.
+/// * `state` - The live state.
+/// * `snapshots` - The snapshot cache.
+pub fn try_runtime_live(
+	binary: &Path,
+	runtime: &Path,
+	check: &TryRuntimeCheck,
+	state: &LiveState,
+	snapshots: &Snapshots,
+) -> Result<PathBuf, Error> {
+	if !runtime.exists() {
+		return Err(Error::Config(format!("runtime not found: {}", runtime.display())));
+	}
+	let snapshot = snapshots.get_or_create(binary, state)?;
+	cmd(binary, args(runtime, check, &snapshot.path)).run()?;
+	Ok(snapshot.path)
+}
+
+/// The arguments used to execute a check against a snapshot.
+fn args(runtime: &Path, check: &TryRuntimeCheck, snapshot: &Path) -> Vec<String> {
+	let mut args = vec!["--runtime".to_string(), runtime.display().to_string()];
+	args.extend(check.args());
+	args.extend(["snap".into(), "--path".into(), snapshot.display().to_string()]);
+	args
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::time::Duration;
+
+	fn state() -> LiveState {
+		LiveState { uri: "wss://rpc1.paseo.popnetwork.xyz".into(), at: None, pallets: vec![] }
+	}
+
+	#[test]
+	fn args_works() {
+		assert_eq!(
+			args(
+				Path::new("runtime.wasm"),
+				&TryRuntimeCheck::OnRuntimeUpgrade,
+				Path::new("a.snap")
+			),
+			[
+				"--runtime",
+				"runtime.wasm",
+				"on-runtime-upgrade",
+				"--checks=all",
+				"snap",
+				"--path",
+				"a.snap"
+			]
+		);
+		assert_eq!(
+			args(Path::new("runtime.wasm"), &TryRuntimeCheck::FastForward(5), Path::new("a.snap")),
+			[
+				"--runtime",
+				"runtime.wasm",
+				"fast-forward",
+				"--n-blocks",
+				"5",
+				"--try-state",
+				"all",
+				"snap",
+				"--path",
+				"a.snap"
+			]
+		);
+	}
+
+	#[test]
+	fn try_runtime_binary_fails_when_missing() {
+		assert!(matches!(
+			try_runtime_binary(Some(Path::new("/missing/try-runtime"))),
+			Err(Error::MissingBinary(e)) if e.contains("cargo install")
+		));
+	}
+
+	#[test]
+	fn try_runtime_live_fails_without_runtime() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let snapshots = Snapshots::new(temp_dir.path(), Duration::from_secs(60));
+		assert!(matches!(
+			try_runtime_live(
+				Path::new(BINARY),
+				&temp_dir.path().join("missing.wasm"),
+				&TryRuntimeCheck::OnRuntimeUpgrade,
+				&state(),
+				&snapshots
+			),
+			Err(Error::Config(e)) if e.starts_with("runtime not found")
+		));
+		Ok(())
+	}
+}
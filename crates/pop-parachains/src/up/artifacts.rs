@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::{Binary, Source};
+use crate::errors::Error;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use url::Url;
+
+/// Whether a location specified within a network configuration is that of a published artifact.
+///
+/// # Arguments
+/// * `location` - The location specified.
+pub(super) fn is_published(location: &str) -> bool {
+	["https://", "http://"].iter().any(|p| location.starts_with(p))
+}
+
+/// Initialises a published artifact required to launch a network (e.g. a node binary or chain
+/// specification attached to a release), so that a network can be launched without building
+/// anything locally.
+///
+/// The artifact is cached under its name qualified by its release tag, or by a hash of its URL
+/// when not published as a GitHub release asset, so that artifacts of other releases are never
+/// reused.
+///
+/// # Arguments
+/// * `url` - The URL of the artifact.
+/// * `cache` - The location used for caching artifacts.
+pub(super) fn from_url(url: &str, cache: &Path) -> Result<Binary, Error> {
+	let parsed = Url::parse(url)
+		.map_err(|e| Error::Config(format!("the `{url}` artifact url is invalid: {e}")))?;
+	let segments: Vec<_> = parsed
+		.path_segments()
+		.map(|s| s.filter(|s| !s.is_empty()).collect())
+		.unwrap_or_default();
+	let Some(file) = segments.last() else {
+		return Err(Error::Config(format!("the `{url}` artifact url does not specify a file")));
+	};
+	let version = match segments.as_slice() {
+		[.., "releases", "download", tag, _] => tag.to_string(),
+		_ => Sha256::digest(url.as_bytes())[..4].iter().map(|b| format!("{b:02x}")).collect(),
+	};
+	let path = Path::new(file);
+	let name = match (path.file_stem(), path.extension()) {
+		(Some(stem), Some(extension)) =>
+			format!("{}-{version}.{}", stem.to_string_lossy(), extension.to_string_lossy()),
+		_ => format!("{file}-{version}"),
+	};
+	Ok(Binary::Source {
+		name: name.clone(),
+		source: Source::Url { url: url.to_string(), name },
+		cache: cache.to_path_buf(),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use tempfile::tempdir;
+
+	#[test]
+	fn from_url_works() -> Result<()> {
+		let cache = tempdir()?;
+		let url = "https://github.com/org/chain/releases/download/v1.0.0/chain-node";
+		let binary = from_url(url, cache.path())?;
+		assert_eq!(
+			binary,
+			Binary::Source {
+				name: "chain-node-v1.0.0".into(),
+				source: Source::Url { url: url.into(), name: "chain-node-v1.0.0".into() },
+				cache: cache.path().to_path_buf(),
+			}
+		);
+		assert_eq!(binary.path(), cache.path().join("chain-node-v1.0.0"));
+
+		let url = "https://github.com/org/chain/releases/download/v1.0.0/chain-spec.json";
+		assert_eq!(from_url(url, cache.path())?.name(), "chain-spec-v1.0.0.json");
+
+		// Artifacts published elsewhere are qualified by a hash of their url.
+		let name = from_url("https://example.com/specs/chain-spec.json", cache.path())?
+			.name()
+			.to_string();
+		assert!(name.starts_with("chain-spec-") && name.ends_with(".json"));
+		assert_ne!(name, from_url("https://example.com/v2/chain-spec.json", cache.path())?.name());
+		Ok(())
+	}
+
+	#[test]
+	fn from_url_fails_without_file() {
+		assert!(matches!(
+			from_url("https://example.com/", Path::new("./")),
+			Err(Error::Config(..))
+		));
+		assert!(matches!(from_url("https://", Path::new("./")), Err(Error::Config(..))));
+	}
+
+	#[test]
+	fn is_published_works() {
+		assert!(is_published("https://github.com/org/chain/releases/download/v1.0.0/node"));
+		assert!(!is_published("./target/release/node"));
+		assert!(!is_published("pop-node"));
+	}
+}
@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::{
+	logs::log_path,
+	topology::{Role, Topology, TopologyNode},
+};
+use crate::errors::Error;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use url::Url;
+use zombienet_sdk::Network;
+use zombienet_support::fs::local::LocalFileSystem;
+
+/// The endpoints of a node of a launched network.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NodeEndpoints {
+	/// The name of the node.
+	pub name: String,
+	/// The chain the node belongs to.
+	pub chain: String,
+	/// The role of the node.
+	pub role: Role,
+	/// The identifier of the parachain, if the node is a collator.
+	pub para_id: Option<u32>,
+	/// The websocket endpoint of the JSON-RPC server.
+	pub ws: String,
+	/// The HTTP endpoint of the JSON-RPC server, which is served on the same port.
+	pub http: String,
+	/// The Prometheus metrics endpoint, if exposed.
+	pub prometheus: Option<String>,
+	/// The port used for peer-to-peer networking, if known.
+	pub p2p_port: Option<u16>,
+	/// The directory within which the node stores its data, if specified.
+	pub base_path: Option<String>,
+	/// The path of the log of the node.
+	pub log: PathBuf,
+}
+
+impl NodeEndpoints {
+	/// Determines the endpoints of a node from its description.
+	///
+	/// # Arguments
+	/// * `node` - The node, as described within the topology of the network.
+	/// * `base_dir` - The directory within which the network was launched.
+	fn new(node: &TopologyNode, base_dir: &Path) -> Self {
+		let url = Url::parse(&node.ws_uri).ok();
+		let host = url.as_ref().and_then(|url| url.host_str()).unwrap_or("127.0.0.1").to_string();
+		let http = match url {
+			Some(mut url) => {
+				let scheme = if url.scheme() == "wss" { "https" } else { "http" };
+				let _ = url.set_scheme(scheme);
+				url.to_string()
+			},
+			None => node.ws_uri.replacen("ws", "http", 1),
+		};
+		Self {
+			name: node.name.clone(),
+			chain: node.chain.clone(),
+			role: node.role,
+			para_id: node.para_id,
+			ws: node.ws_uri.clone(),
+			http,
+			prometheus: node.ports.prometheus.map(|port| format!("http://{host}:{port}/metrics")),
+			p2p_port: node.ports.p2p,
+			base_path: node.base_path.clone(),
+			log: log_path(base_dir, &node.name),
+		}
+	}
+}
+
+/// A machine-readable report of the endpoints of a launched network, so that test harnesses and
+/// frontends can connect to its nodes programmatically.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EndpointReport {
+	/// The relay chain of the network.
+	pub relay_chain: String,
+	/// The directory within which the network was launched.
+	pub base_dir: PathBuf,
+	/// The endpoints of the nodes of the network, relay chain nodes first.
+	pub nodes: Vec<NodeEndpoints>,
+}
+
+impl EndpointReport {
+	/// Reports the endpoints of a launched network.
+	///
+	/// # Arguments
+	/// * `network` - The running network.
+	pub fn from_network(network: &Network<LocalFileSystem>) -> Result<Self, Error> {
+		let base_dir = network.base_dir().ok_or(Error::PathError)?;
+		Ok(Self::new(&Topology::from_network(network), Path::new(base_dir)))
+	}
+
+	/// Reports the endpoints of the nodes within the topology of a network.
+	///
+	/// # Arguments
+	/// * `topology` - The topology of the network.
+	/// * `base_dir` - The directory within which the network was launched.
+	fn new(topology: &Topology, base_dir: &Path) -> Self {
+		Self {
+			relay_chain: topology.relay_chain.clone(),
+			base_dir: base_dir.to_path_buf(),
+			nodes: topology.nodes.iter().map(|node| NodeEndpoints::new(node, base_dir)).collect(),
+		}
+	}
+
+	/// The endpoints of the node with the specified name, if any.
+	///
+	/// # Arguments
+	/// * `name` - The name of the node.
+	pub fn node(&self, name: &str) -> Option<&NodeEndpoints> {
+		self.nodes.iter().find(|n| n.name == name)
+	}
+
+	/// Exports the report as JSON.
+	pub fn to_json(&self) -> Result<String, Error> {
+		Ok(serde_json::to_string_pretty(self)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn report() -> EndpointReport {
+		let base_dir = Path::new("/tmp/zombie-1234");
+		let alice = TopologyNode::new(
+			"alice",
+			"paseo-local",
+			None,
+			"ws://127.0.0.1:9944",
+			None,
+			true,
+			&[
+				"--validator",
+				"--rpc-port",
+				"9944",
+				"--prometheus-port",
+				"10944",
+				"--listen-addr",
+				"/ip4/0.0.0.0/tcp/30333/ws",
+				"--base-path",
+				"/tmp/zombie-1234/alice/data",
+			],
+		);
+		let pop = TopologyNode::new(
+			"pop",
+			"pop",
+			Some(4385),
+			"wss://localhost:9945",
+			None,
+			false,
+			&["--collator", "--rpc-port", "9945", "--", "--prometheus-port", "10946"],
+		);
+		EndpointReport::new(&Topology::new("paseo-local", vec![alice, pop]), base_dir)
+	}
+
+	#[test]
+	fn new_works() {
+		let report = report();
+		assert_eq!(
+			report.node("alice").unwrap(),
+			&NodeEndpoints {
+				name: "alice".to_string(),
+				chain: "paseo-local".to_string(),
+				role: Role::Validator,
+				para_id: None,
+				ws: "ws://127.0.0.1:9944".to_string(),
+				http: "http://127.0.0.1:9944/".to_string(),
+				prometheus: Some("http://127.0.0.1:10944/metrics".to_string()),
+				p2p_port: Some(30333),
+				base_path: Some("/tmp/zombie-1234/alice/data".to_string()),
+				log: PathBuf::from("/tmp/zombie-1234/alice/alice.log"),
+			}
+		);
+		// The ports of the relay chain node embedded within a collator are ignored.
+		let pop = report.node("pop").unwrap();
+		assert_eq!((pop.role, pop.para_id), (Role::Collator, Some(4385)));
+		assert_eq!(pop.http, "https://localhost:9945/");
+		assert_eq!((pop.prometheus.as_ref(), pop.p2p_port), (None, None));
+		assert!(report.node("bob").is_none());
+	}
+
+	#[test]
+	fn to_json_works() -> Result<()> {
+		let json: serde_json::Value = serde_json::from_str(&report().to_json()?)?;
+		assert_eq!(json["relay_chain"], "paseo-local");
+		assert_eq!(json["base_dir"], "/tmp/zombie-1234");
+		assert_eq!(json["nodes"][0]["role"], "validator");
+		assert_eq!(json["nodes"][0]["p2p_port"], 30333);
+		assert_eq!(json["nodes"][1]["para_id"], 4385);
+		assert_eq!(json["nodes"][1]["ws"], "wss://localhost:9945");
+		Ok(())
+	}
+}
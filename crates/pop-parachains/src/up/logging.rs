@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use subxt::backend::rpc::rpc_params;
+use zombienet_sdk::NetworkNode;
+
+/// The levels supported within log directives.
+const LEVELS: [&str; 6] = ["error", "warn", "info", "debug", "trace", "off"];
+
+/// Validates comma-separated log directives, in the form accepted by the `-l` node argument (e.g.
+/// `runtime=debug,xcm=trace`).
+///
+/// # Arguments
+/// * `directives` - The log directives.
+pub fn validate_log_directives(directives: &str) -> Result<(), Error> {
+	if directives.trim().is_empty() {
+		return Err(Error::Config("no log directives specified".into()));
+	}
+	for directive in directives.split(',') {
+		let level = match directive.split_once('=') {
+			Some((target, level)) if !target.trim().is_empty() => level,
+			Some(_) => return Err(Error::Config(format!("missing target in `{directive}`"))),
+			None => directive,
+		};
+		if !LEVELS.contains(&level.trim().to_lowercase().as_str()) {
+			return Err(Error::Config(format!(
+				"invalid log level in `{directive}`, expected one of {}",
+				LEVELS.join(", ")
+			)));
+		}
+	}
+	Ok(())
+}
+
+/// Adds log directives to the running node, enabling targeted logging without a restart.
+///
+/// # Arguments
+/// * `node` - The node.
+/// * `directives` - The log directives to be added (e.g. `runtime=debug`).
+pub async fn add_log_filter(node: &NetworkNode, directives: &str) -> Result<(), Error> {
+	validate_log_directives(directives)?;
+	request(node, "system_addLogFilter", Some(directives)).await
+}
+
+/// Resets the log filter of the running node to the directives it was launched with.
+///
+/// # Arguments
+/// * `node` - The node.
+pub async fn reset_log_filter(node: &NetworkNode) -> Result<(), Error> {
+	request(node, "system_resetLogFilter", None).await
+}
+
+/// Submits a log filter request to a node.
+async fn request(node: &NetworkNode, method: &str, directives: Option<&str>) -> Result<(), Error> {
+	let rpc = node.rpc().await.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+	let params = match directives {
+		Some(directives) => rpc_params![directives],
+		None => rpc_params![],
+	};
+	rpc.request::<()>(method, params).await.map_err(|e| {
+		Error::UnsupportedCommand(format!(
+			"{method} failed on {}, which requires the node to expose unsafe RPC methods (`--rpc-methods=unsafe`): {e}",
+			node.name()
+		))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn validate_log_directives_works() {
+		assert!(validate_log_directives("debug").is_ok());
+		assert!(validate_log_directives("runtime=debug,xcm=TRACE").is_ok());
+		assert!(validate_log_directives("runtime::system=off").is_ok());
+		assert!(matches!(
+			validate_log_directives(" "),
+			Err(Error::Config(e)) if e == "no log directives specified"
+		));
+		assert!(matches!(
+			validate_log_directives("=debug"),
+			Err(Error::Config(e)) if e == "missing target in `=debug`"
+		));
+		assert!(matches!(
+			validate_log_directives("runtime=verbose"),
+			Err(Error::Config(e)) if e.starts_with("invalid log level in `runtime=verbose`")
+		));
+	}
+}
@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use regex::Regex;
+use std::{
+	collections::VecDeque,
+	fs::{read_dir, File},
+	io::{BufRead, BufReader, Seek, SeekFrom},
+	path::{Path, PathBuf},
+	thread::sleep,
+	time::{Duration, SystemTime},
+};
+use tokio::sync::mpsc::{self, Sender};
+use zombienet_sdk::Network;
+use zombienet_support::fs::local::LocalFileSystem;
+
+/// The interval at which a followed log is polled for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// The prefix of the directories within which local networks are launched.
+const NETWORK_DIR_PREFIX: &str = "zombie-";
+/// The file within which the state of a launched network is recorded.
+const NETWORK_STATE_FILE: &str = "zombie.json";
+
+/// Options for streaming the log of a node.
+#[derive(Clone, Debug, Default)]
+pub struct LogOptions {
+	/// Keep streaming lines as they are appended to the log, rather than ending with its last
+	/// line.
+	pub follow: bool,
+	/// If specified, only the last lines of the existing log are streamed (before any followed).
+	pub tail: Option<usize>,
+	/// If specified, only lines matching the filter are streamed.
+	pub filter: Option<Regex>,
+}
+
+impl LogOptions {
+	/// Only stream lines matching the specified regular expression.
+	///
+	/// # Arguments
+	/// * `pattern` - The regular expression used to filter lines.
+	pub fn filter(mut self, pattern: &str) -> Result<Self, Error> {
+		let filter = Regex::new(pattern)
+			.map_err(|e| Error::Config(format!("invalid log filter `{pattern}`: {e}")))?;
+		self.filter = Some(filter);
+		Ok(self)
+	}
+
+	/// Whether a line should be streamed.
+	fn matches(&self, line: &str) -> bool {
+		self.filter.as_ref().is_none_or(|filter| filter.is_match(line))
+	}
+}
+
+/// A stream of the lines of a log.
+pub struct LogStream {
+	/// The receiver of lines.
+	receiver: mpsc::Receiver<Result<String, Error>>,
+}
+
+impl LogStream {
+	/// Streams the lines of a log file.
+	///
+	/// # Arguments
+	/// * `path` - The path of the log file.
+	/// * `options` - Options for streaming the log.
+	pub fn open(path: &Path, options: LogOptions) -> Result<Self, Error> {
+		let file = File::open(path)
+			.map_err(|e| Error::Config(format!("unable to open {}: {e}", path.display())))?;
+		let (sender, receiver) = mpsc::channel(256);
+		// The log is read on a dedicated thread, which stops once the stream is dropped.
+		tokio::task::spawn_blocking(move || {
+			if let Err(e) = stream(file, &options, &sender) {
+				let _ = sender.blocking_send(Err(e));
+			}
+		});
+		Ok(Self { receiver })
+	}
+
+	/// Returns the next line of the log, or `None` once the end of the log has been reached when
+	/// not following it.
+	pub async fn next(&mut self) -> Option<Result<String, Error>> {
+		self.receiver.recv().await
+	}
+}
+
+/// Provides the logs of the nodes of a network.
+pub trait NetworkLogs {
+	/// Streams the log of a node of the network.
+	///
+	/// # Arguments
+	/// * `node` - The name of the node.
+	/// * `options` - Options for streaming the log.
+	fn logs(&self, node: &str, options: LogOptions) -> Result<LogStream, Error>;
+}
+
+impl NetworkLogs for Network<LocalFileSystem> {
+	fn logs(&self, node: &str, options: LogOptions) -> Result<LogStream, Error> {
+		let base_dir = self.base_dir().ok_or(Error::PathError)?;
+		self.get_node(node)?;
+		LogStream::open(&log_path(Path::new(base_dir), node), options)
+	}
+}
+
+/// The path of the log of a node of a network.
+///
+/// # Arguments
+/// * `base_dir` - The directory within which the network was launched.
+/// * `node` - The name of the node.
+pub fn log_path(base_dir: &Path, node: &str) -> PathBuf {
+	base_dir.join(node).join(format!("{node}.log"))
+}
+
+/// Lists the local networks launched within a directory (e.g. the temporary directory of the
+/// system), most recently launched first.
+///
+/// # Arguments
+/// * `dir` - The directory within which networks are launched.
+pub fn find_networks(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+	let mut networks = vec![];
+	for entry in read_dir(dir)?.flatten() {
+		let path = entry.path();
+		let is_network = entry.file_name().to_string_lossy().starts_with(NETWORK_DIR_PREFIX) &&
+			path.join(NETWORK_STATE_FILE).is_file();
+		if is_network {
+			let modified = path
+				.join(NETWORK_STATE_FILE)
+				.metadata()
+				.and_then(|m| m.modified())
+				.unwrap_or(SystemTime::UNIX_EPOCH);
+			networks.push((modified, path));
+		}
+	}
+	networks.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+	Ok(networks.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Lists the nodes of a network with a log, by name.
+///
+/// # Arguments
+/// * `base_dir` - The directory within which the network was launched.
+pub fn network_nodes(base_dir: &Path) -> Result<Vec<String>, Error> {
+	let mut nodes: Vec<_> = read_dir(base_dir)?
+		.flatten()
+		.map(|entry| entry.file_name().to_string_lossy().to_string())
+		.filter(|name| log_path(base_dir, name).is_file())
+		.collect();
+	nodes.sort();
+	Ok(nodes)
+}
+
+/// Sends the lines of a log until the end of the log (unless followed) or the receiver is
+/// dropped.
+fn stream(
+	file: File,
+	options: &LogOptions,
+	sender: &Sender<Result<String, Error>>,
+) -> Result<(), Error> {
+	let mut reader = BufReader::new(file);
+	let (mut position, mut line) = (0u64, Vec::new());
+	// The last lines of the existing log, until its end is first reached.
+	let mut backlog = options.tail.map(VecDeque::with_capacity);
+	loop {
+		let read = reader.read_until(b'\n', &mut line)?;
+		position += read as u64;
+		let end = read == 0;
+		// Wait for the rest of a line being written, unless the log is not followed.
+		if line.last() == Some(&b'\n') || (end && !options.follow && !line.is_empty()) {
+			let text = String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']).to_string();
+			line.clear();
+			if options.matches(&text) {
+				match backlog.as_mut() {
+					Some(backlog) => {
+						backlog.push_back(text);
+						if backlog.len() > options.tail.unwrap_or_default() {
+							backlog.pop_front();
+						}
+					},
+					None =>
+						if sender.blocking_send(Ok(text)).is_err() {
+							return Ok(());
+						},
+				}
+			}
+		}
+		if !end {
+			continue;
+		}
+		for line in backlog.take().into_iter().flatten() {
+			if sender.blocking_send(Ok(line)).is_err() {
+				return Ok(());
+			}
+		}
+		if !options.follow || sender.is_closed() {
+			return Ok(());
+		}
+		sleep(POLL_INTERVAL);
+		// Start over if the log was truncated (e.g. the node was restarted).
+		if reader.get_ref().metadata()?.len() < position {
+			reader.seek(SeekFrom::Start(0))?;
+			position = 0;
+			line.clear();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::{fs::write, io::Write};
+
+	async fn collect(mut stream: LogStream) -> Vec<String> {
+		let mut lines = vec![];
+		while let Some(line) = stream.next().await {
+			lines.push(line.unwrap());
+		}
+		lines
+	}
+
+	#[tokio::test]
+	async fn log_stream_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("alice.log");
+		write(&path, "INFO imported #1\nWARN slow block\nINFO imported #2\npartial")?;
+		let lines = collect(LogStream::open(&path, LogOptions::default())?).await;
+		assert_eq!(lines, ["INFO imported #1", "WARN slow block", "INFO imported #2", "partial"]);
+
+		let options = LogOptions { tail: Some(1), ..Default::default() }.filter("imported")?;
+		assert_eq!(collect(LogStream::open(&path, options)?).await, ["INFO imported #2"]);
+		let options = LogOptions { tail: Some(0), ..Default::default() };
+		assert!(collect(LogStream::open(&path, options)?).await.is_empty());
+
+		assert!(matches!(LogOptions::default().filter("("), Err(Error::Config(_))));
+		assert!(matches!(
+			LogStream::open(&temp_dir.path().join("bob.log"), LogOptions::default()),
+			Err(Error::Config(_))
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn log_stream_follows() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("alice.log");
+		write(&path, "first\nsecond\n")?;
+		let options = LogOptions { follow: true, tail: Some(1), ..Default::default() };
+		let mut stream = LogStream::open(&path, options)?;
+		assert_eq!(stream.next().await.unwrap()?, "second");
+		let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
+		file.write_all(b"thi")?;
+		file.flush()?;
+		tokio::time::sleep(POLL_INTERVAL * 2).await;
+		file.write_all(b"rd\n")?;
+		assert_eq!(stream.next().await.unwrap()?, "third");
+		// A truncated log is streamed from its start.
+		write(&path, "restarted\n")?;
+		assert_eq!(stream.next().await.unwrap()?, "restarted");
+		Ok(())
+	}
+
+	#[test]
+	fn find_networks_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(find_networks(temp_dir.path())?.is_empty());
+		let network = temp_dir.path().join("zombie-1234");
+		std::fs::create_dir_all(network.join("alice"))?;
+		std::fs::create_dir_all(network.join("bob"))?;
+		std::fs::create_dir_all(temp_dir.path().join("zombie-incomplete"))?;
+		write(network.join(NETWORK_STATE_FILE), "{}")?;
+		write(log_path(&network, "alice"), "")?;
+		assert_eq!(network_nodes(&network)?, ["alice"]);
+		assert_eq!(log_path(&network, "alice"), network.join("alice/alice.log"));
+		assert_eq!(find_networks(temp_dir.path())?, [network]);
+		Ok(())
+	}
+}
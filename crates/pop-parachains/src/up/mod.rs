@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::errors::Error;
+use crate::{errors::Error, HrmpChannel, RpcSecurity};
 use glob::glob;
 use indexmap::IndexMap;
 pub use pop_common::{
@@ -15,13 +15,35 @@ use std::{
 };
 use symlink::{remove_symlink_file, symlink_file};
 use tempfile::{Builder, NamedTempFile};
-use toml_edit::{value, ArrayOfTables, DocumentMut, Formatted, Item, Table, Value};
+use toml_edit::{
+	value, Array, ArrayOfTables, DocumentMut, Formatted, InlineTable, Item, Table, TableLike, Value,
+};
 use zombienet_sdk::{Network, NetworkConfig, NetworkConfigExt};
 use zombienet_support::fs::local::LocalFileSystem;
 
+mod artifacts;
 mod chain_specs;
+mod endpoints;
+mod logging;
+mod logs;
 mod parachains;
+mod readiness;
+mod relaunch;
 mod relay;
+mod spec_cache;
+mod topology;
+mod watchdog;
+mod workspace;
+
+pub use endpoints::{EndpointReport, NodeEndpoints};
+pub use logging::{add_log_filter, reset_log_filter, validate_log_directives};
+pub use logs::{find_networks, log_path, network_nodes, LogOptions, LogStream, NetworkLogs};
+pub use readiness::{NodeReadiness, ReadinessProbe};
+pub use relaunch::{NetworkState, RelaunchPlan};
+use spec_cache::{ChainSpecCache, ChainSpecKey};
+pub use topology::{ParachainAssignment, Peering, Ports, Role, Topology, TopologyNode};
+pub use watchdog::{check_health, Watchdog, WatchdogEvent, WatchdogPolicy};
+pub use workspace::{workspace_nodes, WorkspaceNetwork, WorkspaceNode};
 
 /// Configuration to launch a local network.
 pub struct Zombienet {
@@ -31,6 +53,14 @@ pub struct Zombienet {
 	relay_chain: RelayChain,
 	/// The configuration required to launch parachains.
 	parachains: IndexMap<u32, Parachain>,
+	/// The published chain specifications referenced by the network configuration, keyed by the
+	/// identifier of their parachain (`None` for the relay chain).
+	chain_specs: IndexMap<Option<u32>, Binary>,
+	/// The location used for caching generated chain specifications.
+	cache: PathBuf,
+	/// The wrappers through which the collators of each parachain are launched, keyed by the
+	/// identifier of the parachain.
+	wrappers: IndexMap<u32, PathBuf>,
 }
 
 impl Zombienet {
@@ -85,10 +115,19 @@ impl Zombienet {
 			cache,
 		)
 		.await?;
-		Ok(Self { network_config, relay_chain, parachains })
+		let chain_specs = network_config.published_chain_specs(cache)?;
+		Ok(Self {
+			network_config,
+			relay_chain,
+			parachains,
+			chain_specs,
+			cache: cache.to_path_buf(),
+			wrappers: IndexMap::new(),
+		})
 	}
 
-	/// The binaries required to launch the network.
+	/// The binaries required to launch the network, including any published chain
+	/// specifications.
 	pub fn binaries(&mut self) -> impl Iterator<Item = &mut Binary> {
 		once([Some(&mut self.relay_chain.binary), self.relay_chain.chain_spec_generator.as_mut()])
 			.chain(
@@ -98,6 +137,42 @@ impl Zombienet {
 			)
 			.flatten()
 			.flatten()
+			.chain(self.chain_specs.values_mut())
+	}
+
+	/// The number of nodes within the network.
+	pub fn node_count(&self) -> usize {
+		self.network_config.node_count()
+	}
+
+	/// Endows accounts within the genesis of the relay chain and each parachain of the network,
+	/// so that they are funded once launched (e.g. for load tests).
+	///
+	/// # Arguments
+	/// * `accounts` - The addresses of the accounts to be endowed.
+	/// * `balance` - The balance endowed to each account.
+	pub fn endow(&mut self, accounts: &[String], balance: u64) -> Result<(), Error> {
+		self.network_config.endow(accounts, balance)
+	}
+
+	/// Secures the RPC endpoints of the collators of each parachain, launching them through a
+	/// wrapper enforcing the restrictions specified. Collators are assigned a fixed RPC port if
+	/// not already configured, so that a gateway can be placed in front of them.
+	///
+	/// # Arguments
+	/// * `security` - How the RPC endpoints are secured.
+	/// * `dir` - The directory within which the wrappers are generated.
+	pub fn secure_rpc(&mut self, security: &RpcSecurity, dir: &Path) -> Result<Vec<u16>, Error> {
+		for (id, parachain) in &self.parachains {
+			self.wrappers.insert(*id, security.wrap(dir, *id, &parachain.binary.path())?);
+		}
+		self.network_config.secure_rpc(&security.node_args())
+	}
+
+	/// The HRMP channels declared within the network configuration (`[[hrmp_channels]]`), to be
+	/// opened once the network is launched.
+	pub fn hrmp_channels(&self) -> Result<Vec<HrmpChannel>, Error> {
+		self.network_config.hrmp_channels()
 	}
 
 	/// Determine parachain configuration based on specified version and network configuration.
@@ -133,7 +208,7 @@ impl Zombienet {
 
 			let chain = table.get("chain").and_then(|i| i.as_str());
 
-			let command = NetworkConfiguration::default_command(table)
+			let specified = NetworkConfiguration::default_command(table)
 				.cloned()
 				.or_else(|| {
 					// Check if any collators define command
@@ -157,7 +232,14 @@ impl Zombienet {
 				.expect("missing default_command set above")
 				.as_str()
 				.expect("expected parachain command to be a string")
-				.to_lowercase();
+				.to_string();
+			let command = specified.to_lowercase();
+
+			// Check if command references a published binary, such as a release asset
+			if artifacts::is_published(&specified) {
+				paras.insert(id, Parachain::from_published(id, &specified, chain, cache)?);
+				continue;
+			}
 
 			// Check if system parachain
 			if let Some(parachain) = parachains::system(
@@ -301,14 +383,43 @@ impl Zombienet {
 			symlink_file(cache.join(format!("{worker}-{version}")), dest)?;
 		}
 
+		// Use any published chain specs, then reuse a previously generated relay chain spec where
+		// possible.
+		self.network_config.use_published_chain_specs(&self.chain_specs)?;
+		self.network_config
+			.cache_relay_chain_spec(&self.relay_chain, &ChainSpecCache::new(&self.cache))?;
+
 		// Load from config and spawn network
-		let config = self.network_config.configure(&self.relay_chain, &self.parachains)?;
+		let config =
+			self.network_config
+				.configure(&self.relay_chain, &self.parachains, &self.wrappers)?;
 		let path = config.path().to_str().expect("temp config file should have a path");
 		let network_config = NetworkConfig::load_from_toml(path)?;
 		Ok(network_config.spawn_native().await?)
 	}
 }
 
+/// The first RPC port assigned to collators whose endpoints are secured.
+const DEFAULT_RPC_PORT: u16 = 9944;
+
+/// The development accounts, endowed within the genesis of development chains.
+const DEV_ACCOUNTS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Returns the table with the specified key, inserting an empty table if missing.
+///
+/// # Arguments
+/// * `parent` - The parent table.
+/// * `key` - The key of the table.
+fn child<'a>(parent: &'a mut dyn TableLike, key: &str) -> Result<&'a mut dyn TableLike, Error> {
+	if !parent.contains_key(key) {
+		parent.insert(key, Item::Value(Value::InlineTable(InlineTable::new())));
+	}
+	parent
+		.get_mut(key)
+		.and_then(Item::as_table_like_mut)
+		.ok_or_else(|| Error::Config(format!("expected `{key}` to be a table")))
+}
+
 /// The network configuration.
 struct NetworkConfiguration(DocumentMut);
 
@@ -380,16 +491,313 @@ impl NetworkConfiguration {
 		relay_chain.get_mut("nodes").and_then(|i| i.as_array_of_tables_mut())
 	}
 
+	/// The number of nodes within the network, including those within groups.
+	fn node_count(&self) -> usize {
+		let count = |chain: &Table, nodes: &str, groups: &str| -> usize {
+			let nodes = chain.get(nodes).and_then(Item::as_array_of_tables).map_or(0, |n| n.len());
+			let groups = chain.get(groups).and_then(Item::as_array_of_tables).map_or(0, |groups| {
+				groups
+					.iter()
+					.filter_map(|g| g.get("count").and_then(Item::as_integer))
+					.map(|count| count.max(0) as usize)
+					.sum()
+			});
+			nodes + groups
+		};
+		let relay_chain = self.relay_chain().map_or(0, |r| count(r, "nodes", "node_groups"));
+		let parachains = self.parachains().map_or(0, |parachains| {
+			parachains
+				.iter()
+				.map(|p| {
+					count(p, "collators", "collator_groups") +
+						usize::from(p.get("collator").is_some_and(Item::is_table))
+				})
+				.sum()
+		});
+		relay_chain + parachains
+	}
+
+	/// The chain specifications referenced by URL (e.g. those attached to a release), keyed by
+	/// the identifier of their parachain (`None` for the relay chain).
+	///
+	/// # Arguments
+	/// * `cache` - The location used for caching artifacts.
+	fn published_chain_specs(&self, cache: &Path) -> Result<IndexMap<Option<u32>, Binary>, Error> {
+		let published = |config: &Table| {
+			config
+				.get("chain_spec_path")
+				.and_then(|i| i.as_str())
+				.filter(|path| artifacts::is_published(path))
+				.map(|url| artifacts::from_url(url, cache))
+				.transpose()
+		};
+		let mut chain_specs = IndexMap::new();
+		if let Some(chain_spec) = published(self.relay_chain()?)? {
+			chain_specs.insert(None, chain_spec);
+		}
+		for table in self.parachains().into_iter().flatten() {
+			if let Some(chain_spec) = published(table)? {
+				chain_specs.insert(Self::id(table)?, chain_spec);
+			}
+		}
+		Ok(chain_specs)
+	}
+
+	/// Endows accounts within the genesis overrides of the relay chain and each parachain. As
+	/// overrides replace the endowments of the chain specification, the development accounts are
+	/// endowed alongside the accounts specified, unless endowments are already overridden.
+	///
+	/// # Arguments
+	/// * `accounts` - The addresses of the accounts to be endowed.
+	/// * `balance` - The balance endowed to each account.
+	fn endow(&mut self, accounts: &[String], balance: u64) -> Result<(), Error> {
+		let balance = i64::try_from(balance).map_err(|_| {
+			Error::Config(format!("the endowment of {balance} exceeds the maximum of {}", i64::MAX))
+		})?;
+		let development: Vec<_> = DEV_ACCOUNTS
+			.iter()
+			.map(|name| {
+				let signer = pop_common::create_signer(&format!("//{name}"))?;
+				Ok(pop_common::Account::Id32(signer.public_key().0).to_string())
+			})
+			.collect::<Result<_, Error>>()?;
+		let endowment =
+			|address: &str| Array::from_iter([Value::from(address), Value::from(balance)]);
+		let endow = |config: &mut Table, key: &str| -> Result<(), Error> {
+			let balances = child(child(config, key)?, "balances")?;
+			let endowments = match balances.get_mut("balances") {
+				Some(endowments) => endowments.as_array_mut().ok_or_else(|| {
+					Error::Config("expected `balances.balances` to be an array".into())
+				})?,
+				None => {
+					let endowments = development.iter().map(|address| endowment(address));
+					balances.insert("balances", value(Array::from_iter(endowments)));
+					balances
+						.get_mut("balances")
+						.and_then(Item::as_array_mut)
+						.expect("endowments inserted")
+				},
+			};
+			endowments.extend(accounts.iter().map(|address| endowment(address)));
+			Ok(())
+		};
+		endow(self.relay_chain_mut()?, "runtime_genesis_patch")?;
+		for parachain in self.parachains_mut().into_iter().flat_map(|p| p.iter_mut()) {
+			endow(parachain, "genesis_overrides")?;
+		}
+		Ok(())
+	}
+
+	/// Adds arguments to the collators of each parachain, assigning them fixed RPC ports if not
+	/// configured, and returns the RPC ports of the collators.
+	///
+	/// # Arguments
+	/// * `args` - The arguments added to each collator.
+	fn secure_rpc(&mut self, args: &[String]) -> Result<Vec<u16>, Error> {
+		let rpc_port = |node: &Table| node.get("rpc_port").and_then(Item::as_integer);
+		let mut used: Vec<i64> = Self::nodes(self.relay_chain()?)
+			.into_iter()
+			.flatten()
+			.filter_map(rpc_port)
+			.collect();
+		for parachain in self.parachains().into_iter().flatten() {
+			used.extend(
+				parachain
+					.get("collators")
+					.and_then(Item::as_array_of_tables)
+					.into_iter()
+					.flatten()
+					.filter_map(rpc_port),
+			);
+			used.extend(parachain.get("collator").and_then(Item::as_table).and_then(rpc_port));
+		}
+		let mut next = i64::from(DEFAULT_RPC_PORT);
+		let mut ports = vec![];
+		let mut secure =
+			|collator: &mut Table| -> Result<(), Error> {
+				let port = match rpc_port(collator) {
+					Some(port) => port,
+					None => {
+						while used.contains(&next) {
+							next += 1;
+						}
+						used.push(next);
+						collator.insert("rpc_port", value(next));
+						next
+					},
+				};
+				ports.push(u16::try_from(port).map_err(|_| {
+					Error::Config(format!("invalid `rpc_port` of collator: {port}"))
+				})?);
+				match collator.get_mut("args") {
+					Some(existing) => existing
+						.as_array_mut()
+						.ok_or_else(|| {
+							Error::Config("expected `args` of collator to be an array".into())
+						})?
+						.extend(args),
+					None => {
+						collator.insert("args", value(Array::from_iter(args)));
+					},
+				}
+				Ok(())
+			};
+		for parachain in self.parachains_mut().into_iter().flat_map(|p| p.iter_mut()) {
+			for collator in parachain
+				.get_mut("collators")
+				.and_then(Item::as_array_of_tables_mut)
+				.into_iter()
+				.flat_map(|c| c.iter_mut())
+			{
+				secure(collator)?;
+			}
+			if let Some(collator) = parachain.get_mut("collator").and_then(Item::as_table_mut) {
+				secure(collator)?;
+			}
+		}
+		Ok(ports)
+	}
+
+	/// Sets the chain specifications referenced by URL to the paths at which they were sourced.
+	///
+	/// # Arguments
+	/// * `chain_specs` - The published chain specifications, keyed by the identifier of their
+	///   parachain (`None` for the relay chain).
+	fn use_published_chain_specs(
+		&mut self,
+		chain_specs: &IndexMap<Option<u32>, Binary>,
+	) -> Result<(), Error> {
+		if let Some(chain_spec) = chain_specs.get(&None) {
+			let path = Self::resolve_path(&chain_spec.path())?;
+			self.relay_chain_mut()?.insert("chain_spec_path", value(path));
+		}
+		for table in self.parachains_mut().into_iter().flat_map(|t| t.iter_mut()) {
+			if let Some(chain_spec) = chain_specs.get(&Self::id(table)?) {
+				table.insert("chain_spec_path", value(Self::resolve_path(&chain_spec.path())?));
+			}
+		}
+		Ok(())
+	}
+
+	/// The HRMP channels declared within the configuration, which must be between two distinct
+	/// parachains of the network.
+	fn hrmp_channels(&self) -> Result<Vec<HrmpChannel>, Error> {
+		let Some(channels) = self.0.get("hrmp_channels") else {
+			return Ok(vec![]);
+		};
+		let channels = channels
+			.as_array_of_tables()
+			.ok_or_else(|| Error::Config("expected `hrmp_channels` to be an array".into()))?;
+		let para_ids = self
+			.parachains()
+			.into_iter()
+			.flatten()
+			.map(Self::id)
+			.collect::<Result<Vec<_>, _>>()?;
+		channels
+			.iter()
+			.map(|table| {
+				let field = |key: &str, default: Option<u32>| {
+					match table.get(key).map(|i| i.as_integer()) {
+						Some(Some(v)) => u32::try_from(v).ok(),
+						Some(None) => None,
+						None => default,
+					}
+					.ok_or_else(|| {
+						Error::Config(format!("expected `hrmp_channels` to have a valid `{key}`"))
+					})
+				};
+				let channel = HrmpChannel {
+					sender: field("sender", None)?,
+					recipient: field("recipient", None)?,
+					max_capacity: field("max_capacity", Some(HrmpChannel::DEFAULT_MAX_CAPACITY))?,
+					max_message_size: field(
+						"max_message_size",
+						Some(HrmpChannel::DEFAULT_MAX_MESSAGE_SIZE),
+					)?,
+				};
+				if channel.sender == channel.recipient {
+					return Err(Error::Config(format!(
+						"the HRMP channel of {} cannot be opened to itself",
+						channel.sender
+					)));
+				}
+				for id in [channel.sender, channel.recipient] {
+					if !para_ids.contains(&Some(id)) {
+						return Err(Error::Config(format!(
+							"the HRMP channel between {} and {} references {id}, which is not a parachain of the network",
+							channel.sender, channel.recipient
+						)));
+					}
+				}
+				Ok(channel)
+			})
+			.collect()
+	}
+
+	/// Returns the `id` of a parachain configuration.
+	fn id(config: &Table) -> Result<Option<u32>, Error> {
+		config
+			.get("id")
+			.and_then(|i| i.as_integer())
+			.map(|id| Some(id as u32))
+			.ok_or_else(|| Error::Config("expected `parachain` to have `id`".into()))
+	}
+
+	/// Sets the relay chain specification to one generated via the cache, so that repeated
+	/// launches with the same binary version, chain and genesis overrides skip regenerating it.
+	/// Configurations specifying how the specification is obtained are left unchanged.
+	///
+	/// # Arguments
+	/// * `relay_chain` - The configuration required to launch the relay chain.
+	/// * `cache` - The cache of generated chain specifications.
+	fn cache_relay_chain_spec(
+		&mut self,
+		relay_chain: &RelayChain,
+		cache: &ChainSpecCache,
+	) -> Result<(), Error> {
+		let config = self.relay_chain_mut()?;
+		if config.contains_key("chain_spec_path") || config.contains_key("chain_spec_command") {
+			return Ok(());
+		}
+		let binary = relay_chain.chain_spec_generator.as_ref().unwrap_or(&relay_chain.binary);
+		let Some(version) = binary.version() else {
+			// Local binaries cannot be identified by their version.
+			return Ok(());
+		};
+		let overrides: Vec<_> = ["genesis", "runtime_genesis_patch", "wasm_override"]
+			.into_iter()
+			.filter_map(|key| config.get(key).map(|item| format!("{key}={item}")))
+			.collect();
+		let key = ChainSpecKey {
+			binary: binary.name().to_string(),
+			version: version.to_string(),
+			chain: relay_chain.chain.clone(),
+			overrides: overrides.join("\n"),
+		};
+		let chain = relay_chain.chain.as_str();
+		let args = match relay_chain.chain_spec_generator {
+			Some(_) => vec![chain],
+			None => vec!["build-spec", "--chain", chain, "--disable-default-bootnode"],
+		};
+		let path = cache
+			.get_or_generate(&key, |output| spec_cache::generate(&binary.path(), &args, output))?;
+		config.insert("chain_spec_path", value(Self::resolve_path(&path)?));
+		Ok(())
+	}
+
 	/// Adapts user provided configuration file to one with resolved binary paths and which is
 	/// compatible with current zombienet-sdk requirements.
 	///
 	/// # Arguments
 	/// * `relay_chain` - The configuration required to launch the relay chain.
 	/// * `parachains` - The configuration required to launch the parachain(s).
+	/// * `wrappers` - The wrappers through which the collators of parachains are launched.
 	fn configure(
 		&mut self,
 		relay_chain: &RelayChain,
 		parachains: &IndexMap<u32, Parachain>,
+		wrappers: &IndexMap<u32, PathBuf>,
 	) -> Result<NamedTempFile, Error> {
 		// Add zombienet-sdk specific settings if missing
 		let settings = self
@@ -418,11 +826,13 @@ impl NetworkConfiguration {
 				}
 			}
 		}
-		// Configure chain spec generator
+		// Configure chain spec generator, unless a chain spec is already provided
 		if let Some(path) = relay_chain.chain_spec_generator.as_ref().map(|b| b.path()) {
-			let command = format!("{} {}", Self::resolve_path(&path)?, "{{chainName}}");
-			*relay_chain_config.entry("chain_spec_command").or_insert(value(&command)) =
-				value(&command);
+			if !relay_chain_config.contains_key("chain_spec_path") {
+				let command = format!("{} {}", Self::resolve_path(&path)?, "{{chainName}}");
+				*relay_chain_config.entry("chain_spec_command").or_insert(value(&command)) =
+					value(&command);
+			}
 		}
 
 		// Update parachain config
@@ -436,12 +846,20 @@ impl NetworkConfiguration {
 				let para =
 					parachains.get(&id).expect("expected parachain existence due to preprocessing");
 
-				// Resolve default_command to binary
-				let path = Self::resolve_path(&para.binary.path())?;
+				// Resolve default_command to binary, launched via its wrapper if any
+				let mut path = Self::resolve_path(&para.binary.path())?;
+				if let Some(wrapper) = wrappers.get(&id) {
+					path = Self::resolve_path(wrapper)?;
+				}
 				table.insert("default_command", value(&path));
 
-				// Configure chain spec generator
-				if let Some(path) = para.chain_spec_generator.as_ref().map(|b| b.path()) {
+				// Configure chain spec generator, unless a chain spec is already provided
+				if let Some(path) = para
+					.chain_spec_generator
+					.as_ref()
+					.map(|b| b.path())
+					.filter(|_| !table.contains_key("chain_spec_path"))
+				{
 					let command = format!("{} {}", Self::resolve_path(&path)?, "{{chainName}}");
 					*table.entry("chain_spec_command").or_insert(value(&command)) = value(&command);
 				}
@@ -459,6 +877,20 @@ impl NetworkConfiguration {
 			}
 		}
 
+		// Apply the default limits of any HRMP channels, which zombienet otherwise requires
+		if let Some(channels) =
+			self.0.get_mut("hrmp_channels").and_then(|c| c.as_array_of_tables_mut())
+		{
+			for channel in channels.iter_mut() {
+				channel
+					.entry("max_capacity")
+					.or_insert(value(HrmpChannel::DEFAULT_MAX_CAPACITY as i64));
+				channel
+					.entry("max_message_size")
+					.or_insert(value(HrmpChannel::DEFAULT_MAX_MESSAGE_SIZE as i64));
+			}
+		}
+
 		// Write adapted zombienet config to temp file
 		let network_config_file = Builder::new().suffix(".toml").tempfile()?;
 		let path = network_config_file
@@ -490,7 +922,6 @@ struct RelayChain {
 	/// The additional workers required by the relay chain node.
 	workers: [&'static str; 2],
 	/// The name of the chain.
-	#[allow(dead_code)]
 	chain: String,
 	/// If applicable, the binary used to generate a chain specification.
 	chain_spec_generator: Option<Binary>,
@@ -532,6 +963,28 @@ impl Parachain {
 		})
 	}
 
+	/// Initializes the configuration required to launch a parachain using a published binary, such
+	/// as a release asset, so that no local project is required.
+	///
+	/// # Arguments
+	/// * `id` - The parachain identifier on the local network.
+	/// * `url` - The URL of the binary.
+	/// * `chain` - The chain specified.
+	/// * `cache` - The location used for caching binaries.
+	fn from_published(
+		id: u32,
+		url: &str,
+		chain: Option<&str>,
+		cache: &Path,
+	) -> Result<Parachain, Error> {
+		Ok(Parachain {
+			id,
+			binary: artifacts::from_url(url, cache)?,
+			chain: chain.map(|c| c.to_string()),
+			chain_spec_generator: None,
+		})
+	}
+
 	/// Initializes the configuration required to launch a parachain using a binary sourced from the
 	/// specified repository.
 	///
@@ -1519,6 +1972,262 @@ validator = true
 			Ok(())
 		}
 
+		#[test]
+		fn node_count_works() -> Result<(), Error> {
+			let config = Builder::new().suffix(".toml").tempfile()?;
+			writeln!(
+				config.as_file(),
+				r#"
+				[relaychain]
+				chain = "rococo-local"
+				[[relaychain.nodes]]
+				name = "alice"
+				[[relaychain.node_groups]]
+				name = "validators"
+				count = 3
+				[[parachains]]
+				id = 1000
+				[parachains.collator]
+				name = "asset-hub"
+				[[parachains]]
+				id = 2000
+				[[parachains.collators]]
+				name = "collator-01"
+				[[parachains.collators]]
+				name = "collator-02"
+			"#
+			)?;
+			assert_eq!(NetworkConfiguration::from(config.path())?.node_count(), 7);
+			Ok(())
+		}
+
+		#[test]
+		fn endow_works() -> Result<(), Error> {
+			let config = Builder::new().suffix(".toml").tempfile()?;
+			writeln!(
+				config.as_file(),
+				r#"
+				[relaychain]
+				chain = "rococo-local"
+				[[parachains]]
+				id = 1000
+				[[parachains]]
+				id = 2000
+				genesis_overrides = {{ balances = {{ balances = [["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", 1]] }} }}
+			"#
+			)?;
+			let mut network_config = NetworkConfiguration::from(config.path())?;
+			let account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".to_string();
+			network_config.endow(std::slice::from_ref(&account), 1_000)?;
+			let endowments = |config: &Table, key: &str| -> Vec<(String, i64)> {
+				config[key]["balances"]["balances"]
+					.as_array()
+					.unwrap()
+					.iter()
+					.map(|e| {
+						let e = e.as_array().unwrap();
+						(
+							e.get(0).unwrap().as_str().unwrap().to_string(),
+							e.get(1).unwrap().as_integer().unwrap(),
+						)
+					})
+					.collect()
+			};
+			// Development accounts are endowed alongside, unless endowments are overridden.
+			let relay_chain = endowments(network_config.relay_chain()?, "runtime_genesis_patch");
+			assert_eq!(relay_chain.len(), DEV_ACCOUNTS.len() + 1);
+			assert_eq!(relay_chain[0].0, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+			assert_eq!(relay_chain.last(), Some(&(account.clone(), 1_000)));
+			let parachains = network_config.parachains().unwrap();
+			assert_eq!(endowments(parachains.get(0).unwrap(), "genesis_overrides"), relay_chain);
+			assert_eq!(
+				endowments(parachains.get(1).unwrap(), "genesis_overrides"),
+				[
+					("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(), 1),
+					(account, 1_000)
+				]
+			);
+			assert!(matches!(network_config.endow(&[], u64::MAX), Err(Error::Config(..))));
+			Ok(())
+		}
+
+		#[test]
+		fn hrmp_channels_works() -> Result<(), Error> {
+			let config = Builder::new().suffix(".toml").tempfile()?;
+			writeln!(
+				config.as_file(),
+				r#"
+				[relaychain]
+				chain = "rococo-local"
+				[[parachains]]
+				id = 2000
+				[[parachains]]
+				id = 2001
+				[[hrmp_channels]]
+				sender = 2000
+				recipient = 2001
+				[[hrmp_channels]]
+				sender = 2001
+				recipient = 2000
+				max_capacity = 4
+				max_message_size = 1024
+			"#
+			)?;
+			assert_eq!(
+				NetworkConfiguration::from(config.path())?.hrmp_channels()?,
+				[
+					HrmpChannel {
+						sender: 2000,
+						recipient: 2001,
+						max_capacity: HrmpChannel::DEFAULT_MAX_CAPACITY,
+						max_message_size: HrmpChannel::DEFAULT_MAX_MESSAGE_SIZE
+					},
+					HrmpChannel {
+						sender: 2001,
+						recipient: 2000,
+						max_capacity: 4,
+						max_message_size: 1024
+					},
+				]
+			);
+			Ok(())
+		}
+
+		#[test]
+		fn hrmp_channels_ensures_parachains_exist() -> Result<(), Error> {
+			let config = Builder::new().suffix(".toml").tempfile()?;
+			writeln!(
+				config.as_file(),
+				r#"
+				[relaychain]
+				chain = "rococo-local"
+				[[parachains]]
+				id = 2000
+				[[hrmp_channels]]
+				sender = 2000
+				recipient = 2002
+			"#
+			)?;
+			assert!(matches!(
+				NetworkConfiguration::from(config.path())?.hrmp_channels(),
+				Err(Error::Config(message)) if message.contains("2002")
+			));
+			Ok(())
+		}
+
+		#[test]
+		fn secure_rpc_works() -> Result<()> {
+			use std::{
+				fs::{set_permissions, write, Permissions},
+				os::unix::fs::PermissionsExt,
+				process::Command,
+			};
+
+			let temp_dir = tempdir()?;
+			let config = temp_dir.path().join("network.toml");
+			write(
+				&config,
+				r#"
+[relaychain]
+chain = "rococo-local"
+
+[[relaychain.nodes]]
+name = "alice"
+rpc_port = 9944
+
+[[parachains]]
+id = 2000
+
+[[parachains.collators]]
+name = "collator-01"
+args = ["-lparachain=debug"]
+
+[[parachains.collators]]
+name = "collator-02"
+rpc_port = 9946
+"#,
+			)?;
+			let relay_chain = temp_dir.path().join("polkadot");
+			File::create(&relay_chain)?;
+			// A node which simply prints the arguments with which it is launched.
+			let node = temp_dir.path().join("node");
+			write(&node, "#!/bin/sh\necho \"$@\"\n")?;
+			set_permissions(&node, Permissions::from_mode(0o755))?;
+
+			let security = RpcSecurity {
+				cors: vec!["https://polkadot.js.org".into()],
+				rate_limit: Some(600),
+				..Default::default()
+			};
+			let mut network_config = NetworkConfiguration::from(&config)?;
+			assert_eq!(network_config.secure_rpc(&security.node_args())?, [9945, 9946]);
+			let wrapper = security.wrap(&temp_dir.path().join("gateway"), 2000, &node)?;
+			let configured = network_config.configure(
+				&RelayChain {
+					binary: Binary::Local {
+						name: "polkadot".to_string(),
+						path: relay_chain,
+						manifest: None,
+					},
+					workers: ["polkadot-execute-worker", ""],
+					chain: "rococo-local".to_string(),
+					chain_spec_generator: None,
+				},
+				&[(
+					2000,
+					Parachain {
+						id: 2000,
+						binary: Binary::Local {
+							name: "node".to_string(),
+							path: node,
+							manifest: None,
+						},
+						chain: None,
+						chain_spec_generator: None,
+					},
+				)]
+				.into(),
+				&[(2000, wrapper.clone())].into(),
+			)?;
+
+			// The collators are launched via the wrapper...
+			let network = NetworkConfig::load_from_toml(configured.path().to_str().unwrap())?;
+			let parachain = network.parachains()[0];
+			assert_eq!(parachain.default_command().unwrap().as_str(), wrapper.to_str().unwrap());
+			let collator = parachain.collators()[0];
+			assert_eq!(collator.rpc_port(), Some(9945));
+			let args: Vec<_> = collator
+				.args()
+				.into_iter()
+				.map(|arg| serde_json::to_value(arg).map(|arg| arg.as_str().unwrap().to_string()))
+				.collect::<Result<_, _>>()?;
+			assert_eq!(args, ["-lparachain=debug", "--rpc-rate-limit=600"]);
+
+			// ...with the arguments generated by zombienet (see `generate_for_cumulus_node`),
+			// which expose all RPC methods to any origin, on all interfaces.
+			let generated = [
+				["--chain", "/cfg/2000.json", "--name", "collator-01"].as_slice(),
+				&["--rpc-cors", "all", "--unsafe-rpc-external", "--rpc-methods", "unsafe"],
+				&["--node-key", "0x01", "--collator", "--rpc-port", "9945"],
+				&args.iter().map(String::as_str).collect::<Vec<_>>(),
+				&["--", "--base-path", "/relay-data", "--chain", "/cfg/rococo-local.json"],
+			]
+			.concat();
+			let output = Command::new(&wrapper).args(&generated).output()?;
+			assert!(output.status.success());
+			assert_eq!(
+				String::from_utf8(output.stdout)?.trim(),
+				"--rpc-methods=safe --rpc-cors=https://polkadot.js.org --chain /cfg/2000.json \
+				 --name collator-01 --node-key 0x01 --collator --rpc-port 9945 -lparachain=debug \
+				 --rpc-rate-limit=600 -- --base-path /relay-data --chain /cfg/rococo-local.json"
+			);
+			// Subcommands, such as those generating chain specifications, are run unchanged.
+			let output =
+				Command::new(&wrapper).args(["build-spec", "--chain", "local"]).output()?;
+			assert_eq!(String::from_utf8(output.stdout)?.trim(), "build-spec --chain local");
+			Ok(())
+		}
+
 		#[test]
 		fn configure_works() -> Result<(), Error> {
 			let config = Builder::new().suffix(".toml").tempfile()?;
@@ -1626,6 +2335,7 @@ command = "./target/release/parachain-template-node"
 					),
 				]
 				.into(),
+				&IndexMap::new(),
 			)?;
 			assert_eq!("toml", configured.path().extension().unwrap());
 
@@ -1754,6 +2464,7 @@ command = "polkadot-parachain"
 					},
 				)]
 				.into(),
+				&IndexMap::new(),
 			)?;
 			assert_eq!("toml", configured.path().extension().unwrap());
 
@@ -1817,6 +2528,116 @@ node_spawn_timeout = 300
 			);
 			Ok(())
 		}
+
+		#[test]
+		fn cache_relay_chain_spec_works() -> Result<(), Error> {
+			use std::os::unix::fs::PermissionsExt;
+			let temp_dir = tempdir()?;
+			let cache = temp_dir.path();
+			// A binary which generates a spec, recording each invocation.
+			let binary = cache.join("polkadot-v1.12.0");
+			let invocations = cache.join("invocations");
+			std::fs::write(
+				&binary,
+				format!("#!/bin/sh\necho \"$@\" >> {}\necho '{{}}'\n", invocations.display()),
+			)?;
+			std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755))?;
+			let relay_chain = RelayChain {
+				binary: Binary::Source {
+					name: "polkadot".to_string(),
+					source: Source::GitHub(ReleaseArchive {
+						owner: "r0gue-io".into(),
+						repository: "polkadot".into(),
+						tag: Some("v1.12.0".into()),
+						tag_format: None,
+						archive: "polkadot.tar.gz".into(),
+						contents: vec![("polkadot", None)],
+						latest: None,
+					}),
+					cache: cache.to_path_buf(),
+				},
+				workers: ["polkadot-execute-worker", ""],
+				chain: "paseo-local".to_string(),
+				chain_spec_generator: None,
+			};
+			let spec_cache = ChainSpecCache::new(cache);
+
+			let config = Builder::new().suffix(".toml").tempfile()?;
+			writeln!(config.as_file(), "[relaychain]\nchain = \"paseo-local\"")?;
+			for _ in 0..2 {
+				let mut network_config = NetworkConfiguration::from(config.path())?;
+				network_config.cache_relay_chain_spec(&relay_chain, &spec_cache)?;
+				let path = network_config.relay_chain()?.get("chain_spec_path").unwrap();
+				assert!(path
+					.as_str()
+					.unwrap()
+					.starts_with(cache.canonicalize()?.to_str().unwrap()));
+			}
+			// The spec was generated once and reused thereafter.
+			assert_eq!(
+				std::fs::read_to_string(&invocations)?,
+				"build-spec --chain paseo-local --disable-default-bootnode\n"
+			);
+
+			// Configurations specifying how the spec is obtained are left unchanged.
+			let config = Builder::new().suffix(".toml").tempfile()?;
+			writeln!(
+				config.as_file(),
+				"[relaychain]\nchain = \"paseo-local\"\nchain_spec_command = \"custom\""
+			)?;
+			let mut network_config = NetworkConfiguration::from(config.path())?;
+			network_config.cache_relay_chain_spec(&relay_chain, &spec_cache)?;
+			assert!(network_config.relay_chain()?.get("chain_spec_path").is_none());
+			Ok(())
+		}
+
+		#[test]
+		fn published_chain_specs_works() -> Result<(), Error> {
+			let temp_dir = tempdir()?;
+			let cache = temp_dir.path();
+			let release = "https://github.com/org/chain/releases/download/v1.0.0";
+			let config = Builder::new().suffix(".toml").tempfile()?;
+			writeln!(
+				config.as_file(),
+				r#"
+				[relaychain]
+				chain = "paseo-local"
+				chain_spec_path = "{release}/paseo-local.json"
+
+				[[parachains]]
+				id = 1000
+				chain_spec_path = "./asset-hub.json"
+
+				[[parachains]]
+				id = 2000
+				default_command = "polkadot-parachain"
+				chain_spec_path = "{release}/chain-spec.json"
+			"#
+			)?;
+			let mut network_config = NetworkConfiguration::from(config.path())?;
+			let chain_specs = network_config.published_chain_specs(cache)?;
+			assert_eq!(
+				chain_specs.iter().map(|(id, spec)| (*id, spec.name())).collect::<Vec<_>>(),
+				[(None, "paseo-local-v1.0.0.json"), (Some(2000), "chain-spec-v1.0.0.json")]
+			);
+
+			for chain_spec in chain_specs.values() {
+				File::create(chain_spec.path())?;
+			}
+			network_config.use_published_chain_specs(&chain_specs)?;
+			let path = |config: &Table| config["chain_spec_path"].as_str().unwrap().to_string();
+			assert_eq!(
+				path(network_config.relay_chain()?),
+				NetworkConfiguration::resolve_path(&cache.join("paseo-local-v1.0.0.json"))?
+			);
+			let parachains = network_config.parachains().unwrap();
+			assert_eq!(path(parachains.get(0).unwrap()), "./asset-hub.json");
+			assert_eq!(
+				path(parachains.get(1).unwrap()),
+				NetworkConfiguration::resolve_path(&cache.join("chain-spec-v1.0.0.json"))?
+			);
+			Ok(())
+		}
 	}
 
 	mod parachain {
@@ -1840,6 +2661,29 @@ node_spawn_timeout = 300
 			Ok(())
 		}
 
+		#[test]
+		fn initializes_from_published_binary() -> Result<(), Error> {
+			let cache = PathBuf::from("./cache");
+			let url = "https://github.com/org/chain/releases/download/v1.0.0/chain-node";
+			assert_eq!(
+				Parachain::from_published(2000, url, Some("dev"), &cache)?,
+				Parachain {
+					id: 2000,
+					binary: Binary::Source {
+						name: "chain-node-v1.0.0".to_string(),
+						source: Source::Url {
+							url: url.to_string(),
+							name: "chain-node-v1.0.0".to_string()
+						},
+						cache,
+					},
+					chain: Some("dev".to_string()),
+					chain_spec_generator: None,
+				}
+			);
+			Ok(())
+		}
+
 		#[test]
 		fn initializes_from_local_package() -> Result<(), Error> {
 			let name = "pop-parachains";
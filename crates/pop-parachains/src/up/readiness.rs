@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use serde_json::Value;
+use std::{
+	fmt::{Display, Formatter},
+	time::{Duration, Instant},
+};
+use subxt::backend::rpc::rpc_params;
+use tokio::time::{sleep, timeout};
+use zombienet_sdk::{Network, NetworkNode};
+use zombienet_support::fs::local::LocalFileSystem;
+
+/// The readiness of a node of a network, as of its most recent probe.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeReadiness {
+	/// The name of the node.
+	pub node: String,
+	/// The number of peers of the node, if its RPC server is reachable.
+	pub peers: Option<u64>,
+	/// Whether the node is expected to have peers, as other nodes of its chain exist.
+	pub expects_peers: bool,
+	/// The number of the last finalized block, if its RPC server is reachable.
+	pub finalized: Option<u32>,
+	/// Whether the finalized height has advanced since the node was first probed.
+	pub finality_advancing: bool,
+}
+
+impl NodeReadiness {
+	/// Whether the RPC server of the node is reachable.
+	pub fn rpc_reachable(&self) -> bool {
+		self.peers.is_some()
+	}
+
+	/// Whether the node is usable: its RPC server is reachable, it has peers (when expected) and
+	/// its finalized height is advancing.
+	pub fn is_ready(&self) -> bool {
+		self.pending().is_empty()
+	}
+
+	/// The checks which the node has yet to pass.
+	pub fn pending(&self) -> Vec<&'static str> {
+		if !self.rpc_reachable() {
+			return vec!["rpc"];
+		}
+		let mut pending = vec![];
+		if self.expects_peers && self.peers == Some(0) {
+			pending.push("peers");
+		}
+		if !self.finality_advancing {
+			pending.push("finality");
+		}
+		pending
+	}
+}
+
+impl Display for NodeReadiness {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let NodeReadiness { node, peers, finalized, .. } = self;
+		match (peers, finalized) {
+			(Some(peers), Some(finalized)) => write!(
+				f,
+				"{node}: {peers} peers, finalized #{finalized}, {}",
+				match self.pending().as_slice() {
+					[] => "ready".to_string(),
+					pending => format!("waiting for {}", pending.join(", ")),
+				}
+			),
+			_ => write!(f, "{node}: RPC server unreachable"),
+		}
+	}
+}
+
+/// The state of the RPC server of a node, as observed by a probe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Observation {
+	/// The number of peers of the node.
+	peers: u64,
+	/// The number of the last finalized block.
+	finalized: u32,
+}
+
+/// Tracks the readiness of a node across probes.
+#[derive(Debug)]
+struct Tracker {
+	/// The name of the node.
+	node: String,
+	/// Whether the node is expected to have peers.
+	expects_peers: bool,
+	/// The finalized height when first observed.
+	baseline: Option<u32>,
+}
+
+impl Tracker {
+	/// Assesses the readiness of the node from an observation of its RPC server.
+	///
+	/// # Arguments
+	/// * `observation` - The observation, or `None` if the RPC server was unreachable.
+	fn assess(&mut self, observation: Option<Observation>) -> NodeReadiness {
+		let finalized = observation.map(|o| o.finalized);
+		if self.baseline.is_none() {
+			self.baseline = finalized;
+		}
+		NodeReadiness {
+			node: self.node.clone(),
+			peers: observation.map(|o| o.peers),
+			expects_peers: self.expects_peers,
+			finalized,
+			finality_advancing: finalized.zip(self.baseline).is_some_and(|(f, b)| f > b),
+		}
+	}
+}
+
+/// Probes the readiness of the nodes of a running network, so that it can be awaited rather than
+/// assumed after a fixed delay.
+pub struct ReadinessProbe {
+	/// The nodes probed, with their trackers.
+	nodes: Vec<(NetworkNode, Tracker)>,
+	/// The time within which a node must respond to a probe.
+	timeout: Duration,
+}
+
+impl ReadinessProbe {
+	/// Probes all nodes of a network. Nodes are only expected to have peers when other nodes of
+	/// their chain exist.
+	///
+	/// # Arguments
+	/// * `network` - The running network.
+	pub fn from_network(network: &Network<LocalFileSystem>) -> Self {
+		let chains = std::iter::once(network.relaychain().nodes())
+			.chain(network.parachains().into_iter().map(|p| p.collators()));
+		let nodes = chains
+			.flat_map(|nodes| {
+				let expects_peers = nodes.len() > 1;
+				nodes.into_iter().map(move |node| {
+					let tracker =
+						Tracker { node: node.name().to_string(), expects_peers, baseline: None };
+					(node.clone(), tracker)
+				})
+			})
+			.collect();
+		Self { nodes, timeout: Duration::from_secs(5) }
+	}
+
+	/// Probes each node once.
+	pub async fn check(&mut self) -> Vec<NodeReadiness> {
+		let mut readiness = Vec::with_capacity(self.nodes.len());
+		for (node, tracker) in &mut self.nodes {
+			let observation = observe(node, self.timeout).await.ok();
+			readiness.push(tracker.assess(observation));
+		}
+		readiness
+	}
+
+	/// Probes the nodes at the specified interval until all are ready, returning their readiness.
+	///
+	/// # Arguments
+	/// * `limit` - How long to wait before giving up.
+	/// * `interval` - The interval between probes.
+	/// * `progress` - Called with the readiness of the nodes after each probe.
+	pub async fn wait(
+		&mut self,
+		limit: Duration,
+		interval: Duration,
+		progress: impl Fn(&[NodeReadiness]),
+	) -> Result<Vec<NodeReadiness>, Error> {
+		let start = Instant::now();
+		loop {
+			let readiness = self.check().await;
+			progress(&readiness);
+			if readiness.iter().all(NodeReadiness::is_ready) {
+				return Ok(readiness);
+			}
+			if start.elapsed() + interval > limit {
+				let pending: Vec<_> =
+					readiness.iter().filter(|r| !r.is_ready()).map(|r| r.to_string()).collect();
+				return Err(Error::NotReady(format!(
+					"not ready within {}s ({})",
+					limit.as_secs(),
+					pending.join("; ")
+				)));
+			}
+			sleep(interval).await;
+		}
+	}
+}
+
+/// Observes the number of peers and the finalized height of a node via its RPC server.
+///
+/// # Arguments
+/// * `node` - The node.
+/// * `limit` - The time within which the node must respond.
+async fn observe(node: &NetworkNode, limit: Duration) -> Result<Observation, Error> {
+	let probe = async {
+		let rpc = node.rpc().await.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+		let health: Value = rpc.request("system_health", rpc_params![]).await?;
+		let head: Value = rpc.request("chain_getFinalizedHead", rpc_params![]).await?;
+		let header: Value = rpc.request("chain_getHeader", rpc_params![head]).await?;
+		Ok(Observation {
+			peers: health["peers"].as_u64().unwrap_or_default(),
+			finalized: parse_number(&header["number"])?,
+		})
+	};
+	timeout(limit, probe)
+		.await
+		.map_err(|_| Error::ConnectionFailure(format!("no response within {}s", limit.as_secs())))?
+}
+
+/// Parses a block number, encoded as a hexadecimal string.
+fn parse_number(number: &Value) -> Result<u32, Error> {
+	number
+		.as_str()
+		.and_then(|n| u32::from_str_radix(n.trim_start_matches("0x"), 16).ok())
+		.ok_or_else(|| Error::ConnectionFailure(format!("invalid block number: {number}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn readiness_works() {
+		let readiness = NodeReadiness {
+			node: "alice".into(),
+			peers: Some(0),
+			expects_peers: true,
+			finalized: Some(0),
+			finality_advancing: false,
+		};
+		assert_eq!(readiness.pending(), ["peers", "finality"]);
+		assert_eq!(
+			readiness.to_string(),
+			"alice: 0 peers, finalized #0, waiting for peers, finality"
+		);
+		let readiness = NodeReadiness {
+			peers: Some(1),
+			finalized: Some(2),
+			finality_advancing: true,
+			..readiness
+		};
+		assert!(readiness.is_ready());
+		assert_eq!(readiness.to_string(), "alice: 1 peers, finalized #2, ready");
+		// Peers are not required of the only node of a chain.
+		let readiness = NodeReadiness { peers: Some(0), expects_peers: false, ..readiness };
+		assert!(readiness.is_ready());
+		let readiness = NodeReadiness { peers: None, finalized: None, ..readiness };
+		assert!(!readiness.rpc_reachable());
+		assert_eq!(readiness.pending(), ["rpc"]);
+		assert_eq!(readiness.to_string(), "alice: RPC server unreachable");
+	}
+
+	#[test]
+	fn tracker_works() {
+		let mut tracker = Tracker { node: "alice".into(), expects_peers: true, baseline: None };
+		// The baseline is only set once the node is reachable.
+		assert!(!tracker.assess(None).rpc_reachable());
+		assert_eq!(tracker.baseline, None);
+		let observation = Observation { peers: 1, finalized: 3 };
+		assert!(!tracker.assess(Some(observation)).finality_advancing);
+		assert_eq!(tracker.baseline, Some(3));
+		let readiness = tracker.assess(Some(Observation { peers: 1, finalized: 4 }));
+		assert!(readiness.finality_advancing);
+		assert!(readiness.is_ready());
+	}
+
+	#[test]
+	fn parse_number_works() {
+		assert_eq!(parse_number(&json!("0x1a")).unwrap(), 26);
+		assert!(parse_number(&json!(26)).is_err());
+	}
+}
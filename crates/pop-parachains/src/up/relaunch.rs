@@ -0,0 +1,337 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use std::{
+	collections::BTreeMap,
+	fs::{metadata, read_to_string},
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+use toml_edit::{DocumentMut, Item, Table};
+use zombienet_sdk::NetworkNode;
+
+/// The tables of a network configuration file containing node definitions, which are compared
+/// separately from the chain they belong to.
+const NODE_TABLES: [&str; 4] = ["nodes", "node_groups", "collators", "collator_groups"];
+
+/// The state of a binary, used to determine whether it has been rebuilt.
+type BinaryState = (PathBuf, Option<SystemTime>, u64);
+
+/// The state of a node within a network.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct NodeState {
+	/// The configuration of the node, normalized.
+	config: Option<String>,
+	/// The state of the binary run by the node.
+	binary: Option<BinaryState>,
+}
+
+/// The state of a launched network, used to determine which nodes are affected by changes to its
+/// configuration or binaries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkState {
+	/// The configuration of the network and each chain, excluding nodes, normalized.
+	chains: BTreeMap<String, String>,
+	/// The state of each node, keyed by name.
+	nodes: BTreeMap<String, NodeState>,
+}
+
+impl NetworkState {
+	/// Captures the state of a launched network.
+	///
+	/// # Arguments
+	/// * `config` - The network configuration file used to launch the network.
+	/// * `nodes` - The nodes of the network.
+	pub fn capture<'a>(
+		config: &Path,
+		nodes: impl IntoIterator<Item = &'a NetworkNode>,
+	) -> Result<Self, Error> {
+		let binaries = nodes
+			.into_iter()
+			.map(|node| (node.name().to_string(), PathBuf::from(node.spec().command())));
+		Self::from_parts(&read_to_string(config)?, binaries)
+	}
+
+	/// Captures the state of a network from its configuration and the binaries run by its nodes.
+	///
+	/// # Arguments
+	/// * `config` - The contents of the network configuration file.
+	/// * `binaries` - The binary run by each node, keyed by node name.
+	fn from_parts(
+		config: &str,
+		binaries: impl IntoIterator<Item = (String, PathBuf)>,
+	) -> Result<Self, Error> {
+		let config: DocumentMut = config
+			.parse()
+			.map_err(|e| Error::Config(format!("invalid network config: {e}")))?;
+		let mut state = Self::default();
+		let mut network = config.as_table().clone();
+		network.remove("relaychain");
+		network.remove("parachains");
+		state.chains.insert("network".into(), normalize(&network));
+		if let Some(relay) = config.get("relaychain").and_then(Item::as_table) {
+			state.add_chain("relay chain".into(), relay, "nodes");
+		}
+		if let Some(parachains) = config.get("parachains").and_then(Item::as_array_of_tables) {
+			for parachain in parachains {
+				let id = parachain.get("id").and_then(Item::as_integer).unwrap_or_default();
+				state.add_chain(format!("parachain {id}"), parachain, "collators");
+				// A single collator can also be defined via a `collator` table.
+				if let Some(collator) = parachain.get("collator").and_then(Item::as_table) {
+					state.add_node(collator);
+				}
+			}
+		}
+		for (name, binary) in binaries {
+			let (modified, len) =
+				metadata(&binary).map(|m| (m.modified().ok(), m.len())).unwrap_or_default();
+			state.nodes.entry(name).or_default().binary = Some((binary, modified, len));
+		}
+		Ok(state)
+	}
+
+	/// Records the configuration of a chain and its nodes.
+	///
+	/// # Arguments
+	/// * `name` - The name of the chain.
+	/// * `chain` - The configuration of the chain.
+	/// * `nodes` - The name of the table containing the nodes of the chain.
+	fn add_chain(&mut self, name: String, chain: &Table, nodes: &str) {
+		let mut config = chain.clone();
+		config.remove("collator");
+		for table in NODE_TABLES {
+			config.remove(table);
+		}
+		self.chains.insert(name, normalize(&config));
+		for node in chain.get(nodes).and_then(Item::as_array_of_tables).into_iter().flatten() {
+			self.add_node(node);
+		}
+	}
+
+	/// Records the configuration of a node.
+	fn add_node(&mut self, node: &Table) {
+		if let Some(name) = node.get("name").and_then(Item::as_str) {
+			self.nodes.entry(name.to_string()).or_default().config = Some(normalize(node));
+		}
+	}
+
+	/// Determines the changes required to bring a running network in line with its current state.
+	///
+	/// # Arguments
+	/// * `current` - The current state of the network.
+	pub fn diff(&self, current: &NetworkState) -> RelaunchPlan {
+		let mut plan = RelaunchPlan::default();
+		for (name, config) in &current.chains {
+			match self.chains.get(name) {
+				Some(previous) if previous == config => {},
+				Some(_) => plan.reasons.push(format!("the {name} configuration changed")),
+				None => plan.reasons.push(format!("{name} was added")),
+			}
+		}
+		for name in self.chains.keys().filter(|n| !current.chains.contains_key(*n)) {
+			plan.reasons.push(format!("{name} was removed"));
+		}
+		for (name, node) in &current.nodes {
+			let Some(previous) = self.nodes.get(name) else {
+				plan.reasons.push(format!("node {name} was added"));
+				continue;
+			};
+			if previous.config != node.config {
+				// Nodes can only be restarted with the arguments they were launched with.
+				plan.reasons.push(format!("the configuration of node {name} changed"));
+			} else if previous.binary != node.binary {
+				plan.restart.push(name.clone());
+			}
+		}
+		for name in self.nodes.keys().filter(|n| !current.nodes.contains_key(*n)) {
+			plan.reasons.push(format!("node {name} was removed"));
+		}
+		plan
+	}
+
+	/// Records the nodes running their rebuilt binaries, once restarted or otherwise updated,
+	/// leaving any changes which require the network to be relaunched pending, so that they
+	/// continue to be reported until applied.
+	///
+	/// # Arguments
+	/// * `current` - The current state of the network.
+	/// * `applied` - The names of the nodes to which the current state has been applied.
+	pub fn apply(&mut self, current: &NetworkState, applied: &[String]) {
+		for name in applied {
+			if let (Some(node), Some(update)) = (self.nodes.get_mut(name), current.nodes.get(name))
+			{
+				node.binary = update.binary.clone();
+			}
+		}
+	}
+}
+
+/// The changes required to bring a running network in line with its configuration and binaries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RelaunchPlan {
+	/// The nodes to be restarted, as the binary they run has changed.
+	pub restart: Vec<String>,
+	/// The reasons the network must be fully relaunched, if any.
+	pub reasons: Vec<String>,
+}
+
+impl RelaunchPlan {
+	/// Whether no changes are required.
+	pub fn is_empty(&self) -> bool {
+		self.restart.is_empty() && self.reasons.is_empty()
+	}
+
+	/// Whether the network must be fully relaunched, as some changes cannot be applied by
+	/// restarting nodes.
+	pub fn requires_relaunch(&self) -> bool {
+		!self.reasons.is_empty()
+	}
+
+	/// Restarts the affected nodes, leaving all other nodes running.
+	///
+	/// # Arguments
+	/// * `nodes` - The nodes of the network.
+	pub async fn restart<'a>(
+		&self,
+		nodes: impl IntoIterator<Item = &'a NetworkNode>,
+	) -> Result<Vec<String>, Error> {
+		let mut restarted = vec![];
+		for node in nodes.into_iter().filter(|n| self.restart.iter().any(|r| r == n.name())) {
+			node.restart(None).await?;
+			restarted.push(node.name().to_string());
+		}
+		Ok(restarted)
+	}
+}
+
+/// Normalizes a table, so that formatting and comments are not considered changes.
+fn normalize(table: &Table) -> String {
+	let document = DocumentMut::from(table.clone());
+	toml_edit::de::from_document::<serde_json::Value>(document)
+		.map(|value| value.to_string())
+		.unwrap_or_else(|_| table.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::write;
+
+	const CONFIG: &str = r#"
+[relaychain]
+chain = "paseo-local"
+
+[[relaychain.nodes]]
+name = "alice"
+validator = true
+
+[[relaychain.nodes]]
+name = "bob"
+validator = true
+
+[[parachains]]
+id = 1000
+chain = "asset-hub-paseo-local"
+
+[parachains.collator]
+name = "asset-hub"
+
+[[parachains]]
+id = 4385
+default_command = "./target/release/pop-node"
+
+[[parachains.collators]]
+name = "pop" # The collator.
+"#;
+
+	#[test]
+	fn diff_detects_rebuilt_binaries() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let polkadot = temp_dir.path().join("polkadot");
+		let pop_node = temp_dir.path().join("pop-node");
+		write(&polkadot, "polkadot")?;
+		write(&pop_node, "pop-node")?;
+		let binaries = || {
+			[
+				("alice".to_string(), polkadot.clone()),
+				("bob".to_string(), polkadot.clone()),
+				("asset-hub".to_string(), polkadot.clone()),
+				("pop".to_string(), pop_node.clone()),
+			]
+		};
+		let state = NetworkState::from_parts(CONFIG, binaries())?;
+		assert!(state.diff(&NetworkState::from_parts(CONFIG, binaries())?).is_empty());
+
+		// Formatting and comments are ignored.
+		let reformatted = CONFIG.replace("# The collator.", "").replace(" = ", "=");
+		assert!(state.diff(&NetworkState::from_parts(&reformatted, binaries())?).is_empty());
+
+		write(&pop_node, "rebuilt pop-node")?;
+		let plan = state.diff(&NetworkState::from_parts(CONFIG, binaries())?);
+		assert_eq!(plan.restart, vec!["pop"]);
+		assert!(!plan.requires_relaunch());
+		Ok(())
+	}
+
+	#[test]
+	fn diff_detects_config_changes() -> Result<()> {
+		let state = NetworkState::from_parts(CONFIG, [])?;
+		let changed = |from: &str, to: &str| -> Result<RelaunchPlan> {
+			Ok(state.diff(&NetworkState::from_parts(&CONFIG.replace(from, to), [])?))
+		};
+		assert_eq!(
+			changed("name = \"bob\"\nvalidator = true", "name = \"bob\"\nvalidator = false")?
+				.reasons,
+			vec!["the configuration of node bob changed"]
+		);
+		assert_eq!(
+			changed("id = 4385", "id = 4386")?.reasons,
+			vec!["parachain 4386 was added", "parachain 4385 was removed"]
+		);
+		assert_eq!(
+			changed("chain = \"paseo-local\"", "chain = \"westend-local\"")?.reasons,
+			vec!["the relay chain configuration changed"]
+		);
+		assert_eq!(
+			changed("name = \"asset-hub\"", "name = \"asset-hub-1\"")?.reasons,
+			vec!["node asset-hub-1 was added", "node asset-hub was removed"]
+		);
+		assert!(changed("# The collator.", "")?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn apply_retains_pending_changes() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let pop_node = temp_dir.path().join("pop-node");
+		write(&pop_node, "pop-node")?;
+		let binaries = || [("pop".to_string(), pop_node.clone())];
+		let mut state = NetworkState::from_parts(CONFIG, binaries())?;
+
+		write(&pop_node, "rebuilt pop-node")?;
+		let changed = CONFIG.replace("validator = true", "validator = false");
+		let current = NetworkState::from_parts(&changed, binaries())?;
+		let plan = state.diff(&current);
+		assert_eq!(plan.restart, vec!["pop"]);
+		assert!(plan.requires_relaunch());
+
+		// Only the restart is applied, the configuration changes remaining pending.
+		state.apply(&current, &plan.restart);
+		let plan = state.diff(&current);
+		assert!(plan.restart.is_empty());
+		assert_eq!(
+			plan.reasons,
+			vec![
+				"the configuration of node alice changed",
+				"the configuration of node bob changed"
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn from_parts_fails_with_invalid_config() {
+		assert!(matches!(NetworkState::from_parts("[relaychain", []), Err(Error::Config(..))));
+	}
+}
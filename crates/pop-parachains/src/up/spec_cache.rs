@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use duct::cmd;
+use pop_common::gc::CHAIN_SPECS_DIR;
+use sha2::{Digest, Sha256};
+use std::{
+	fs::{create_dir_all, read_dir, remove_file, rename},
+	path::{Path, PathBuf},
+};
+
+/// Identifies a generated relay chain specification: specifications generated with the same
+/// binary version, for the same chain and with the same overrides are identical.
+#[derive(Debug, PartialEq)]
+pub(super) struct ChainSpecKey {
+	/// The name of the binary generating the specification.
+	pub(super) binary: String,
+	/// The version of the binary generating the specification.
+	pub(super) version: String,
+	/// The identifier of the chain.
+	pub(super) chain: String,
+	/// The overrides of the network configuration affecting the genesis of the chain.
+	pub(super) overrides: String,
+}
+
+impl ChainSpecKey {
+	/// The prefix shared by the specifications of a chain generated by a binary.
+	fn prefix(&self) -> String {
+		format!("{}-{}-", sanitize(&self.chain), sanitize(&self.binary))
+	}
+
+	/// The name of the cached specification.
+	fn file_name(&self) -> String {
+		let hash = Sha256::digest(self.overrides.as_bytes());
+		let hash: String = hash[..8].iter().map(|b| format!("{b:02x}")).collect();
+		format!("{}{}-{hash}.json", self.prefix(), sanitize(&self.version))
+	}
+}
+
+/// A cache of generated relay chain specifications, so that repeated launches of a network
+/// skip regenerating identical specifications.
+pub(super) struct ChainSpecCache {
+	/// The directory within which specifications are cached.
+	dir: PathBuf,
+}
+
+impl ChainSpecCache {
+	/// Creates a new chain specification cache.
+	///
+	/// # Arguments
+	/// * `cache` - The cache directory, within which a `chain-specs` directory is used.
+	pub(super) fn new(cache: &Path) -> Self {
+		Self { dir: cache.join(CHAIN_SPECS_DIR) }
+	}
+
+	/// The path of the cached specification.
+	///
+	/// # Arguments
+	/// * `key` - The key of the specification.
+	pub(super) fn path(&self, key: &ChainSpecKey) -> PathBuf {
+		self.dir.join(key.file_name())
+	}
+
+	/// Reuses the cached specification, otherwise generates it. Specifications of the same chain
+	/// generated by the same binary, but with another version or other overrides, are removed.
+	///
+	/// # Arguments
+	/// * `key` - The key of the specification.
+	/// * `generate` - Generates the specification at the provided path.
+	pub(super) fn get_or_generate(
+		&self,
+		key: &ChainSpecKey,
+		generate: impl FnOnce(&Path) -> Result<(), Error>,
+	) -> Result<PathBuf, Error> {
+		let path = self.path(key);
+		if path.exists() {
+			return Ok(path);
+		}
+		create_dir_all(&self.dir)?;
+		// Generate to a temporary file, so that a failed generation is never reused.
+		let temp = path.with_extension("tmp");
+		if let Err(e) = generate(&temp) {
+			let _ = remove_file(&temp);
+			return Err(e);
+		}
+		for stale in read_dir(&self.dir)?.flatten().map(|e| e.path()) {
+			let name = stale.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+			if name.starts_with(&key.prefix()) && name.ends_with(".json") {
+				remove_file(&stale)?;
+			}
+		}
+		rename(&temp, &path)?;
+		Ok(path)
+	}
+}
+
+/// Generates a plain chain specification using a binary.
+///
+/// # Arguments
+/// * `binary` - The binary generating the specification.
+/// * `args` - The arguments of the binary.
+/// * `output` - The path at which the specification is written.
+pub(super) fn generate(binary: &Path, args: &[&str], output: &Path) -> Result<(), Error> {
+	cmd(binary, args).stdout_path(output).stderr_null().run()?;
+	Ok(())
+}
+
+fn sanitize(value: &str) -> String {
+	value
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '_' })
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::{read_to_string, write};
+
+	fn key(version: &str, overrides: &str) -> ChainSpecKey {
+		ChainSpecKey {
+			binary: "polkadot".into(),
+			version: version.into(),
+			chain: "paseo-local".into(),
+			overrides: overrides.into(),
+		}
+	}
+
+	#[test]
+	fn get_or_generate_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = ChainSpecCache::new(temp_dir.path());
+		let spec = cache.get_or_generate(&key("v1.12.0", ""), |path| Ok(write(path, "spec")?))?;
+		assert_eq!(read_to_string(&spec)?, "spec");
+		// An identical specification is reused rather than generated again.
+		let reused = cache.get_or_generate(&key("v1.12.0", ""), |_| panic!("not reused"))?;
+		assert_eq!(reused, spec);
+
+		// Changes to the overrides invalidate the cached specification.
+		let updated =
+			cache.get_or_generate(&key("v1.12.0", "[genesis]"), |path| Ok(write(path, "new")?))?;
+		assert_ne!(updated, spec);
+		assert!(!spec.exists());
+		assert_eq!(read_to_string(&updated)?, "new");
+		Ok(())
+	}
+
+	#[test]
+	fn get_or_generate_discards_failures() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = ChainSpecCache::new(temp_dir.path());
+		let key = key("v1.12.0", "");
+		assert!(cache
+			.get_or_generate(&key, |path| {
+				write(path, "partial")?;
+				Err(Error::Config("failed".into()))
+			})
+			.is_err());
+		assert!(!cache.path(&key).exists());
+		assert!(matches!(
+			cache.get_or_generate(&key, |path| generate(Path::new("/missing/polkadot"), &[], path)),
+			Err(Error::IO(..))
+		));
+		Ok(())
+	}
+}
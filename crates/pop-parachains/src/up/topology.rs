@@ -0,0 +1,410 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use serde::Serialize;
+use std::fmt::Write;
+use zombienet_sdk::{Network, NetworkNode};
+use zombienet_support::fs::local::LocalFileSystem;
+
+/// The role of a node within a network.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+	/// A relay chain validator.
+	Validator,
+	/// A parachain collator.
+	Collator,
+	/// A relay chain node which is not a validator.
+	FullNode,
+}
+
+/// The ports exposed by a node.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Ports {
+	/// The port of the JSON-RPC server.
+	pub rpc: Option<u16>,
+	/// The port used for peer-to-peer networking.
+	pub p2p: Option<u16>,
+	/// The port of the Prometheus metrics endpoint.
+	pub prometheus: Option<u16>,
+}
+
+/// A node within the topology of a network.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TopologyNode {
+	/// The name of the node.
+	pub name: String,
+	/// The chain the node belongs to.
+	pub chain: String,
+	/// The identifier of the parachain, if the node is a collator.
+	pub para_id: Option<u32>,
+	/// The role of the node.
+	pub role: Role,
+	/// The libp2p identity of the node, if known.
+	pub peer_id: Option<String>,
+	/// Whether the node is a bootnode.
+	pub bootnode: bool,
+	/// The websocket endpoint of the node.
+	pub ws_uri: String,
+	/// The ports exposed by the node.
+	pub ports: Ports,
+	/// The directory within which the node stores its data, if specified.
+	pub base_path: Option<String>,
+	/// The identities of the peers the node was launched with as bootnodes.
+	#[serde(skip)]
+	bootnodes: Vec<String>,
+}
+
+impl TopologyNode {
+	/// Describes a node from the arguments it was launched with.
+	///
+	/// # Arguments
+	/// * `name` - The name of the node.
+	/// * `chain` - The chain the node belongs to.
+	/// * `para_id` - The identifier of the parachain, if the node is a collator.
+	/// * `ws_uri` - The websocket endpoint of the node.
+	/// * `peer_id` - The libp2p identity of the node, if known.
+	/// * `bootnode` - Whether the node is a bootnode.
+	/// * `args` - The arguments the node was launched with.
+	pub(super) fn new(
+		name: &str,
+		chain: &str,
+		para_id: Option<u32>,
+		ws_uri: &str,
+		peer_id: Option<String>,
+		bootnode: bool,
+		args: &[&str],
+	) -> Self {
+		// Arguments following `--` configure the relay chain node embedded within a collator.
+		let own = args.split(|arg| *arg == "--").next().unwrap_or_default();
+		let port = |name: &str| option(own, name).and_then(|port| port.parse().ok());
+		let role = match para_id {
+			Some(_) => Role::Collator,
+			None if own.contains(&"--validator") => Role::Validator,
+			None => Role::FullNode,
+		};
+		Self {
+			name: name.to_string(),
+			chain: chain.to_string(),
+			para_id,
+			role,
+			peer_id,
+			bootnode,
+			ws_uri: ws_uri.to_string(),
+			ports: Ports {
+				rpc: port("--rpc-port"),
+				// The p2p port is the port component of the listen address (e.g.
+				// `/ip4/0.0.0.0/tcp/30333/ws`).
+				p2p: option(own, "--listen-addr")
+					.and_then(|address| address.split('/').nth(4))
+					.and_then(|port| port.parse().ok()),
+				prometheus: port("--prometheus-port"),
+			},
+			base_path: option(own, "--base-path").map(String::from),
+			bootnodes: args
+				.windows(2)
+				.filter(|pair| pair[0] == "--bootnodes")
+				.flat_map(|pair| pair[1].split_whitespace())
+				.filter_map(|address| address.rsplit_once("/p2p/").map(|(_, id)| id.to_string()))
+				.collect(),
+		}
+	}
+
+	/// Describes a running node.
+	///
+	/// # Arguments
+	/// * `node` - The node.
+	/// * `chain` - The chain the node belongs to.
+	/// * `para_id` - The identifier of the parachain, if the node is a collator.
+	fn from_node(node: &NetworkNode, chain: &str, para_id: Option<u32>) -> Self {
+		// The identity of a node is only exposed via its serialized specification.
+		let spec = serde_json::to_value(node.spec()).unwrap_or_default();
+		Self::new(
+			node.name(),
+			chain,
+			para_id,
+			node.ws_uri(),
+			spec.get("peer_id").and_then(|id| id.as_str()).map(String::from),
+			spec.get("is_bootnode").and_then(|b| b.as_bool()).unwrap_or_default(),
+			&node.args(),
+		)
+	}
+}
+
+/// A parachain within a network and the collators assigned to it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParachainAssignment {
+	/// The identifier of the parachain.
+	pub para_id: u32,
+	/// The chain identifier of the parachain.
+	pub chain: String,
+	/// The names of the collators assigned to the parachain.
+	pub collators: Vec<String>,
+}
+
+/// A connection between two nodes, where a node was launched with another as a bootnode.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Peering {
+	/// The name of the node.
+	pub from: String,
+	/// The name of the bootnode it connects to.
+	pub to: String,
+}
+
+/// The topology of a launched network: its nodes, their roles and ports, the peerings between
+/// them and the assignment of collators to parachains.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Topology {
+	/// The relay chain of the network.
+	pub relay_chain: String,
+	/// The nodes of the network, relay chain nodes first.
+	pub nodes: Vec<TopologyNode>,
+	/// The parachains of the network, ordered by identifier.
+	pub parachains: Vec<ParachainAssignment>,
+	/// The peerings between nodes.
+	pub peers: Vec<Peering>,
+}
+
+impl Topology {
+	/// Determines the topology of a launched network.
+	///
+	/// # Arguments
+	/// * `network` - The running network.
+	pub fn from_network(network: &Network<LocalFileSystem>) -> Self {
+		let relay_chain = network.relaychain().chain();
+		let mut nodes: Vec<_> = network
+			.relaychain()
+			.nodes()
+			.into_iter()
+			.map(|node| TopologyNode::from_node(node, relay_chain, None))
+			.collect();
+		for parachain in network.parachains() {
+			let chain = parachain
+				.chain_id()
+				.map_or(format!("parachain-{}", parachain.para_id()), String::from);
+			nodes.extend(
+				parachain
+					.collators()
+					.into_iter()
+					.map(|node| TopologyNode::from_node(node, &chain, Some(parachain.para_id()))),
+			);
+		}
+		Self::new(relay_chain, nodes)
+	}
+
+	/// Assembles a topology from its nodes, resolving the peerings between them.
+	///
+	/// # Arguments
+	/// * `relay_chain` - The relay chain of the network.
+	/// * `nodes` - The nodes of the network.
+	pub(super) fn new(relay_chain: &str, nodes: Vec<TopologyNode>) -> Self {
+		let mut parachains: Vec<ParachainAssignment> = vec![];
+		for node in &nodes {
+			let Some(para_id) = node.para_id else { continue };
+			match parachains.iter_mut().find(|p| p.para_id == para_id) {
+				Some(parachain) => parachain.collators.push(node.name.clone()),
+				None => parachains.push(ParachainAssignment {
+					para_id,
+					chain: node.chain.clone(),
+					collators: vec![node.name.clone()],
+				}),
+			}
+		}
+		parachains.sort_by_key(|p| p.para_id);
+		let mut peers: Vec<Peering> = vec![];
+		for node in &nodes {
+			for peer_id in &node.bootnodes {
+				let Some(peer) = nodes.iter().find(|n| n.peer_id.as_ref() == Some(peer_id)) else {
+					continue;
+				};
+				let peering = Peering { from: node.name.clone(), to: peer.name.clone() };
+				if peer.name != node.name && !peers.contains(&peering) {
+					peers.push(peering);
+				}
+			}
+		}
+		Self { relay_chain: relay_chain.to_string(), nodes, parachains, peers }
+	}
+
+	/// The node with the specified name, if any.
+	///
+	/// # Arguments
+	/// * `name` - The name of the node.
+	pub fn node(&self, name: &str) -> Option<&TopologyNode> {
+		self.nodes.iter().find(|n| n.name == name)
+	}
+
+	/// Exports the topology as a Graphviz DOT graph, with a cluster for each chain.
+	pub fn to_dot(&self) -> String {
+		let mut dot = "digraph network {\n\trankdir=LR;\n\tnode [shape=box];\n".to_string();
+		let mut chains: Vec<&str> = vec![];
+		for node in &self.nodes {
+			if !chains.contains(&node.chain.as_str()) {
+				chains.push(&node.chain);
+			}
+		}
+		for (index, chain) in chains.iter().enumerate() {
+			let _ = writeln!(dot, "\tsubgraph cluster_{index} {{\n\t\tlabel={chain:?};");
+			for node in self.nodes.iter().filter(|n| n.chain == *chain) {
+				let mut label = format!("{}\n{}", node.name, role(node.role));
+				if let Some(port) = node.ports.rpc {
+					label.push_str(&format!("\nrpc: {port}"));
+				}
+				if let Some(port) = node.ports.p2p {
+					label.push_str(&format!("\np2p: {port}"));
+				}
+				let _ = writeln!(dot, "\t\t{:?} [label={label:?}];", node.name);
+			}
+			dot.push_str("\t}\n");
+		}
+		for peering in &self.peers {
+			let _ = writeln!(dot, "\t{:?} -> {:?} [style=dashed];", peering.from, peering.to);
+		}
+		dot.push_str("}\n");
+		dot
+	}
+
+	/// Exports the topology as JSON.
+	pub fn to_json(&self) -> Result<String, Error> {
+		Ok(serde_json::to_string_pretty(self)?)
+	}
+}
+
+/// The value of an option within a list of arguments, if specified.
+///
+/// # Arguments
+/// * `args` - The arguments.
+/// * `name` - The name of the option (e.g. `--rpc-port`).
+fn option<'a>(args: &[&'a str], name: &str) -> Option<&'a str> {
+	args.windows(2).find(|pair| pair[0] == name).map(|pair| pair[1])
+}
+
+/// A description of a role, used as a label.
+fn role(role: Role) -> &'static str {
+	match role {
+		Role::Validator => "validator",
+		Role::Collator => "collator",
+		Role::FullNode => "full node",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn args(rpc: u16, p2p: u16, bootnodes: &str, extra: &[&'static str]) -> Vec<String> {
+		let mut args: Vec<String> = [
+			"--rpc-port".to_string(),
+			rpc.to_string(),
+			"--prometheus-port".to_string(),
+			(rpc + 1000).to_string(),
+			"--listen-addr".to_string(),
+			format!("/ip4/0.0.0.0/tcp/{p2p}/ws"),
+			"--base-path".to_string(),
+			format!("/tmp/zombie-1234/{rpc}/data"),
+		]
+		.into();
+		if !bootnodes.is_empty() {
+			args.extend(["--bootnodes".to_string(), bootnodes.to_string()]);
+		}
+		args.extend(extra.iter().map(|a| a.to_string()));
+		args
+	}
+
+	fn topology() -> Topology {
+		let node = |name, chain, para_id, peer_id: &str, args: Vec<String>| {
+			let args: Vec<&str> = args.iter().map(String::as_str).collect();
+			TopologyNode::new(
+				name,
+				chain,
+				para_id,
+				"ws://127.0.0.1:9944",
+				Some(peer_id.to_string()),
+				name == "alice",
+				&args,
+			)
+		};
+		let alice = "/ip4/127.0.0.1/tcp/30333/ws/p2p/alice-id";
+		Topology::new(
+			"paseo-local",
+			vec![
+				node(
+					"alice",
+					"paseo-local",
+					None,
+					"alice-id",
+					args(9944, 30333, "", &["--validator"]),
+				),
+				node(
+					"bob",
+					"paseo-local",
+					None,
+					"bob-id",
+					args(9945, 30334, alice, &["--validator"]),
+				),
+				node("charlie", "paseo-local", None, "charlie-id", args(9946, 30335, alice, &[])),
+				node(
+					"pop",
+					"pop",
+					Some(4385),
+					"pop-id",
+					args(9947, 30336, "", &["--collator", "--", "--bootnodes", alice]),
+				),
+			],
+		)
+	}
+
+	#[test]
+	fn new_works() -> Result<()> {
+		let topology = topology();
+		let bob = topology.node("bob").unwrap();
+		assert_eq!(bob.role, Role::Validator);
+		assert_eq!(bob.ports, Ports { rpc: Some(9945), p2p: Some(30334), prometheus: Some(10945) });
+		assert_eq!(bob.base_path.as_deref(), Some("/tmp/zombie-1234/9945/data"));
+		assert!(topology.node("alice").unwrap().bootnode);
+		assert_eq!(topology.node("charlie").unwrap().role, Role::FullNode);
+		// Ports of the embedded relay chain node are ignored.
+		let pop = topology.node("pop").unwrap();
+		assert_eq!((pop.role, pop.ports.rpc), (Role::Collator, Some(9947)));
+		assert_eq!(
+			topology.parachains,
+			vec![ParachainAssignment {
+				para_id: 4385,
+				chain: "pop".to_string(),
+				collators: vec!["pop".to_string()]
+			}]
+		);
+		assert_eq!(
+			topology
+				.peers
+				.iter()
+				.map(|p| (p.from.as_str(), p.to.as_str()))
+				.collect::<Vec<_>>(),
+			vec![("bob", "alice"), ("charlie", "alice"), ("pop", "alice")]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn to_dot_works() {
+		let dot = topology().to_dot();
+		assert!(dot.starts_with("digraph network {"));
+		assert!(dot.contains("subgraph cluster_0 {\n\t\tlabel=\"paseo-local\";"));
+		assert!(dot.contains("subgraph cluster_1 {\n\t\tlabel=\"pop\";"));
+		assert!(dot.contains("\"bob\" [label=\"bob\\nvalidator\\nrpc: 9945\\np2p: 30334\"];"));
+		assert!(dot.contains("\"pop\" -> \"alice\" [style=dashed];"));
+	}
+
+	#[test]
+	fn to_json_works() -> Result<()> {
+		let json: serde_json::Value = serde_json::from_str(&topology().to_json()?)?;
+		assert_eq!(json["relay_chain"], "paseo-local");
+		assert_eq!(json["nodes"][3]["role"], "collator");
+		assert_eq!(json["nodes"][3]["para_id"], 4385);
+		assert_eq!(json["nodes"][0]["ports"]["p2p"], 30333);
+		assert_eq!(json["parachains"][0]["collators"][0], "pop");
+		assert_eq!(json["peers"][0]["to"], "alice");
+		Ok(())
+	}
+}
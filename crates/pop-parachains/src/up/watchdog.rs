@@ -0,0 +1,440 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use pop_common::{i18n::message, notify::Event};
+use std::{
+	fmt::{Display, Formatter},
+	time::{Duration, Instant},
+};
+use subxt::backend::rpc::rpc_params;
+use tokio::{
+	sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+	task::JoinHandle,
+	time::{sleep, timeout},
+};
+use zombienet_sdk::NetworkNode;
+
+/// The policy by which the health of nodes is checked and unhealthy nodes are restarted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchdogPolicy {
+	/// The interval between health checks of a node.
+	pub interval: Duration,
+	/// The time within which a node must respond to a health check.
+	pub timeout: Duration,
+	/// Whether unhealthy nodes are restarted, rather than only reported.
+	pub restart: bool,
+	/// The number of consecutive failed health checks after which a node is restarted.
+	pub failure_threshold: u32,
+	/// The delay before the first restart of a node, doubled after each subsequent restart.
+	pub initial_backoff: Duration,
+	/// The maximum delay before restarting a node.
+	pub max_backoff: Duration,
+	/// The maximum number of consecutive restarts of a node before giving up, or unlimited if not
+	/// specified. Restarts are no longer consecutive once a node has remained healthy for the
+	/// maximum backoff.
+	pub max_restarts: Option<u32>,
+}
+
+impl Default for WatchdogPolicy {
+	fn default() -> Self {
+		Self {
+			interval: Duration::from_secs(10),
+			timeout: Duration::from_secs(5),
+			restart: true,
+			failure_threshold: 3,
+			initial_backoff: Duration::from_secs(1),
+			max_backoff: Duration::from_secs(300),
+			max_restarts: None,
+		}
+	}
+}
+
+impl WatchdogPolicy {
+	/// The delay before restarting a node.
+	///
+	/// # Arguments
+	/// * `restarts` - The number of consecutive restarts of the node so far.
+	pub fn backoff(&self, restarts: u32) -> Duration {
+		self.initial_backoff
+			.checked_mul(2u32.saturating_pow(restarts))
+			.map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+	}
+}
+
+/// An event observed while supervising the nodes of a network.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatchdogEvent {
+	/// A node failed a health check, having previously been healthy.
+	Unhealthy {
+		/// The name of the node.
+		node: String,
+		/// Why the health check failed.
+		reason: String,
+	},
+	/// A node is being restarted after the specified delay.
+	Restarting {
+		/// The name of the node.
+		node: String,
+		/// The number of consecutive restarts, including this one.
+		attempt: u32,
+		/// The delay before the node is restarted.
+		delay: Duration,
+	},
+	/// A node was restarted.
+	Restarted {
+		/// The name of the node.
+		node: String,
+	},
+	/// A node could not be restarted.
+	RestartFailed {
+		/// The name of the node.
+		node: String,
+		/// Why the restart failed.
+		reason: String,
+	},
+	/// A node passed a health check, having previously been unhealthy.
+	Recovered {
+		/// The name of the node.
+		node: String,
+	},
+	/// A node is no longer supervised, having reached the maximum number of consecutive
+	/// restarts.
+	GaveUp {
+		/// The name of the node.
+		node: String,
+		/// The number of consecutive restarts of the node.
+		restarts: u32,
+	},
+}
+
+impl WatchdogEvent {
+	/// The name of the node concerned.
+	pub fn node(&self) -> &str {
+		match self {
+			WatchdogEvent::Unhealthy { node, .. } |
+			WatchdogEvent::Restarting { node, .. } |
+			WatchdogEvent::Restarted { node } |
+			WatchdogEvent::RestartFailed { node, .. } |
+			WatchdogEvent::Recovered { node } |
+			WatchdogEvent::GaveUp { node, .. } => node,
+		}
+	}
+
+	/// Whether the event warrants attention, rather than reporting progress.
+	pub fn is_alert(&self) -> bool {
+		!matches!(self, WatchdogEvent::Restarting { .. })
+	}
+
+	/// The event to be posted to webhooks, if any: nodes which become unhealthy, cannot be
+	/// restarted or are given up on are reported as crashed.
+	pub fn notification(&self) -> Option<Event> {
+		let node = self.node().to_string();
+		match self {
+			WatchdogEvent::Unhealthy { reason, .. } =>
+				Some(Event::CollatorCrashed { node, reason: reason.clone() }),
+			WatchdogEvent::RestartFailed { reason, .. } => Some(Event::CollatorCrashed {
+				node,
+				reason: message("watchdog.restart_failed_reason", &[("reason", reason)]),
+			}),
+			WatchdogEvent::GaveUp { restarts, .. } => Some(Event::CollatorCrashed {
+				node,
+				reason: message("watchdog.gave_up_reason", &[("restarts", &restarts.to_string())]),
+			}),
+			WatchdogEvent::Recovered { .. } => Some(Event::CollatorRecovered { node }),
+			WatchdogEvent::Restarting { .. } | WatchdogEvent::Restarted { .. } => None,
+		}
+	}
+}
+
+impl Display for WatchdogEvent {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let message = match self {
+			WatchdogEvent::Unhealthy { node, reason } =>
+				message("watchdog.unhealthy", &[("node", node), ("reason", reason)]),
+			WatchdogEvent::Restarting { node, attempt, delay } => message(
+				"watchdog.restarting",
+				&[
+					("node", node),
+					("delay", &delay.as_secs().to_string()),
+					("attempt", &attempt.to_string()),
+				],
+			),
+			WatchdogEvent::Restarted { node } => message("watchdog.restarted", &[("node", node)]),
+			WatchdogEvent::RestartFailed { node, reason } =>
+				message("watchdog.restart_failed", &[("node", node), ("reason", reason)]),
+			WatchdogEvent::Recovered { node } => message("watchdog.recovered", &[("node", node)]),
+			WatchdogEvent::GaveUp { node, restarts } => message(
+				"watchdog.gave_up",
+				&[("node", node), ("restarts", &restarts.to_string())],
+			),
+		};
+		f.write_str(&message)
+	}
+}
+
+/// Supervises the nodes of a running network, restarting any which fail their health checks.
+pub struct Watchdog {
+	events: UnboundedReceiver<WatchdogEvent>,
+	tasks: Vec<JoinHandle<()>>,
+}
+
+impl Watchdog {
+	/// Starts supervising the specified nodes, each within its own task.
+	///
+	/// # Arguments
+	/// * `nodes` - The nodes to be supervised.
+	/// * `policy` - The policy by which the nodes are checked and restarted.
+	pub fn start(nodes: impl IntoIterator<Item = NetworkNode>, policy: WatchdogPolicy) -> Self {
+		let (sender, events) = mpsc::unbounded_channel();
+		let tasks = nodes
+			.into_iter()
+			.map(|node| tokio::spawn(supervise(node, policy.clone(), sender.clone())))
+			.collect();
+		Self { events, tasks }
+	}
+
+	/// Waits for the next event, returning `None` once no nodes remain supervised.
+	pub async fn next(&mut self) -> Option<WatchdogEvent> {
+		self.events.recv().await
+	}
+
+	/// Stops supervising the nodes.
+	pub fn stop(self) {
+		for task in self.tasks {
+			task.abort();
+		}
+	}
+}
+
+/// The action to be taken after a failed health check.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+	/// Wait for further health checks.
+	Wait,
+	/// Restart the node after the specified delay.
+	Restart { attempt: u32, delay: Duration },
+	/// Stop supervising the node.
+	GiveUp,
+}
+
+/// The health of a supervised node.
+#[derive(Debug, Default)]
+struct Health {
+	/// The number of consecutive failed health checks since the node was last restarted.
+	failures: u32,
+	/// The number of consecutive restarts of the node.
+	restarts: u32,
+	/// Whether the node failed its most recent health check.
+	unhealthy: bool,
+	/// When the node became healthy, following a restart.
+	healthy_since: Option<Instant>,
+}
+
+impl Health {
+	/// Records a successful health check, returning whether the node has recovered.
+	fn succeeded(&mut self, policy: &WatchdogPolicy, now: Instant) -> bool {
+		let recovered = self.unhealthy;
+		self.failures = 0;
+		self.unhealthy = false;
+		let since = *self.healthy_since.get_or_insert(now);
+		// Restarts are considered independent once a node has remained healthy long enough.
+		if now.duration_since(since) >= policy.max_backoff {
+			self.restarts = 0;
+		}
+		recovered
+	}
+
+	/// Records a failed health check, returning the action to be taken.
+	fn failed(&mut self, policy: &WatchdogPolicy) -> Action {
+		self.unhealthy = true;
+		self.healthy_since = None;
+		self.failures += 1;
+		if !policy.restart || self.failures < policy.failure_threshold {
+			return Action::Wait;
+		}
+		if policy.max_restarts.is_some_and(|max| self.restarts >= max) {
+			return Action::GiveUp;
+		}
+		let delay = policy.backoff(self.restarts);
+		self.failures = 0;
+		self.restarts += 1;
+		Action::Restart { attempt: self.restarts, delay }
+	}
+}
+
+/// Supervises a node until giving up or the receiver of events is dropped.
+async fn supervise(
+	node: NetworkNode,
+	policy: WatchdogPolicy,
+	events: UnboundedSender<WatchdogEvent>,
+) {
+	let name = node.name().to_string();
+	let mut health = Health::default();
+	loop {
+		sleep(policy.interval).await;
+		let event = match check_health(&node, policy.timeout).await {
+			Ok(()) => health
+				.succeeded(&policy, Instant::now())
+				.then(|| WatchdogEvent::Recovered { node: name.clone() }),
+			Err(e) => {
+				let unhealthy = (!health.unhealthy).then(|| WatchdogEvent::Unhealthy {
+					node: name.clone(),
+					reason: e.to_string(),
+				});
+				if let Some(event) = unhealthy {
+					if events.send(event).is_err() {
+						return;
+					}
+				}
+				match health.failed(&policy) {
+					Action::Wait => None,
+					Action::GiveUp => {
+						let _ = events.send(WatchdogEvent::GaveUp {
+							node: name.clone(),
+							restarts: health.restarts,
+						});
+						return;
+					},
+					Action::Restart { attempt, delay } => {
+						let event =
+							WatchdogEvent::Restarting { node: name.clone(), attempt, delay };
+						if events.send(event).is_err() {
+							return;
+						}
+						Some(match node.restart(Some(delay)).await {
+							Ok(()) => WatchdogEvent::Restarted { node: name.clone() },
+							Err(e) => WatchdogEvent::RestartFailed {
+								node: name.clone(),
+								reason: e.to_string(),
+							},
+						})
+					},
+				}
+			},
+		};
+		if let Some(event) = event {
+			if events.send(event).is_err() {
+				return;
+			}
+		}
+	}
+}
+
+/// Checks the health of a node, which must respond to `system_health` within the timeout.
+///
+/// # Arguments
+/// * `node` - The node.
+/// * `limit` - The time within which the node must respond.
+pub async fn check_health(node: &NetworkNode, limit: Duration) -> Result<(), Error> {
+	let check = async {
+		let rpc = node.rpc().await.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+		let _: serde_json::Value = rpc.request("system_health", rpc_params![]).await?;
+		Ok(())
+	};
+	timeout(limit, check)
+		.await
+		.map_err(|_| {
+			let limit = limit.as_secs().to_string();
+			Error::ConnectionFailure(message("watchdog.no_response", &[("limit", &limit)]))
+		})?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn policy() -> WatchdogPolicy {
+		WatchdogPolicy {
+			failure_threshold: 2,
+			initial_backoff: Duration::from_secs(1),
+			max_backoff: Duration::from_secs(10),
+			max_restarts: Some(3),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn backoff_works() {
+		let policy = policy();
+		assert_eq!(policy.backoff(0), Duration::from_secs(1));
+		assert_eq!(policy.backoff(1), Duration::from_secs(2));
+		assert_eq!(policy.backoff(3), Duration::from_secs(8));
+		assert_eq!(policy.backoff(4), Duration::from_secs(10));
+		assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(10));
+	}
+
+	#[test]
+	fn health_restarts_with_backoff() {
+		let policy = policy();
+		let mut health = Health::default();
+		let mut restarts = vec![];
+		for _ in 0..6 {
+			restarts.push(health.failed(&policy));
+		}
+		assert_eq!(
+			restarts,
+			[
+				Action::Wait,
+				Action::Restart { attempt: 1, delay: Duration::from_secs(1) },
+				Action::Wait,
+				Action::Restart { attempt: 2, delay: Duration::from_secs(2) },
+				Action::Wait,
+				Action::Restart { attempt: 3, delay: Duration::from_secs(4) },
+			]
+		);
+		assert_eq!(health.failed(&policy), Action::Wait);
+		assert_eq!(health.failed(&policy), Action::GiveUp);
+
+		// Unhealthy nodes are only reported when not restarted.
+		let policy = WatchdogPolicy { restart: false, ..policy };
+		let mut health = Health::default();
+		for _ in 0..6 {
+			assert_eq!(health.failed(&policy), Action::Wait);
+		}
+		assert!(health.unhealthy);
+	}
+
+	#[test]
+	fn health_recovers() {
+		let policy = policy();
+		let mut health = Health::default();
+		let now = Instant::now();
+		assert!(!health.succeeded(&policy, now));
+		health.failed(&policy);
+		health.failed(&policy);
+		assert_eq!(health.restarts, 1);
+		assert!(health.succeeded(&policy, now));
+		assert!(!health.succeeded(&policy, now + Duration::from_secs(1)));
+		// Restarts are only reset once the node has remained healthy for the maximum backoff.
+		assert_eq!(health.restarts, 1);
+		health.succeeded(&policy, now + policy.max_backoff);
+		assert_eq!(health.restarts, 0);
+	}
+
+	#[test]
+	fn watchdog_event_works() {
+		let event = WatchdogEvent::Restarting {
+			node: "collator-01".into(),
+			attempt: 2,
+			delay: Duration::from_secs(4),
+		};
+		assert_eq!(event.node(), "collator-01");
+		assert!(!event.is_alert());
+		assert_eq!(event.to_string(), "Restarting collator-01 in 4s (attempt 2)");
+		assert_eq!(event.notification(), None);
+		let event = WatchdogEvent::Recovered { node: "collator-01".into() };
+		assert!(event.is_alert());
+		assert_eq!(
+			event.notification(),
+			Some(Event::CollatorRecovered { node: "collator-01".into() })
+		);
+		let event = WatchdogEvent::GaveUp { node: "collator-01".into(), restarts: 3 };
+		assert_eq!(
+			event.notification(),
+			Some(Event::CollatorCrashed {
+				node: "collator-01".into(),
+				reason: "no longer restarted, having been restarted 3 times".into(),
+			})
+		);
+	}
+}
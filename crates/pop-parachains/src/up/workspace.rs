@@ -0,0 +1,339 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::DEV_ACCOUNTS;
+use crate::{
+	errors::Error,
+	generator::parachain::{WorkspaceNetwork as Network, WorkspaceParachain},
+};
+use askama::Template;
+use pop_common::{
+	manifest::{from_path, Manifest},
+	Profile,
+};
+use serde_json::Value;
+use std::{
+	fs::read_to_string,
+	path::{Path, PathBuf},
+};
+
+/// The relay chain used when not specified by the chain specification of a node.
+const DEFAULT_RELAY_CHAIN: &str = "rococo-local";
+/// The first para id assigned to nodes whose para id cannot be determined.
+const FIRST_PARA_ID: u32 = 2000;
+/// Dependencies indicating that a package is a collator node.
+const NODE_DEPENDENCIES: [&str; 3] =
+	["cumulus-client-cli", "cumulus-client-collator", "cumulus-client-service"];
+
+/// A collator node within a workspace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkspaceNode {
+	/// The name of the package.
+	pub package: String,
+	/// The name of the binary built by the package.
+	pub binary: String,
+	/// The path of the package.
+	pub path: PathBuf,
+	/// The para id, as specified within the chain specification of the project or node.
+	pub para_id: Option<u32>,
+	/// The relay chain, as specified within the chain specification of the project or node.
+	pub relay_chain: Option<String>,
+}
+
+/// A network launching the collator nodes of a workspace, each as a parachain, alongside a local
+/// relay chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkspaceNetwork {
+	/// The relay chain.
+	pub relay_chain: String,
+	/// The parachains, keyed by para id.
+	pub parachains: Vec<(u32, WorkspaceNode)>,
+}
+
+impl WorkspaceNetwork {
+	/// Inspects a workspace for collator nodes, determining the para id of each from the chain
+	/// specification of the project (`*.json` at its root, for a single node) or of the node
+	/// (`src/chain_spec.rs`). Nodes whose para id cannot be determined, or is already taken, are
+	/// assigned the next available para id from 2000.
+	///
+	/// # Arguments
+	/// * `path` - The path of the workspace.
+	/// * `relay_chain` - The relay chain, otherwise that of the chain specifications, defaulting to
+	///   `rococo-local`.
+	pub fn inspect(path: &Path, relay_chain: Option<&str>) -> Result<Self, Error> {
+		let nodes = workspace_nodes(path)?;
+		if nodes.is_empty() {
+			return Err(Error::Config(format!("no collator nodes found within {}", path.display())));
+		}
+		let relay_chain = relay_chain
+			.map(String::from)
+			.or_else(|| nodes.iter().find_map(|n| n.relay_chain.clone()))
+			.unwrap_or_else(|| DEFAULT_RELAY_CHAIN.to_string());
+		let mut parachains: Vec<(u32, WorkspaceNode)> = vec![];
+		for node in nodes {
+			let taken = |id: &u32| parachains.iter().any(|(p, _)| p == id);
+			let id = match node.para_id.filter(|id| !taken(id)) {
+				Some(id) => id,
+				None => (FIRST_PARA_ID..).find(|id| !taken(id)).expect("para ids available"),
+			};
+			parachains.push((id, node));
+		}
+		Ok(Self { relay_chain, parachains })
+	}
+
+	/// Renders the network as a zombienet configuration, launching the binaries built with the
+	/// specified profile. Binary paths are relative to the workspace, from which the network is
+	/// launched.
+	///
+	/// # Arguments
+	/// * `file` - The name of the configuration file, referenced within its header.
+	/// * `profile` - The profile with which the nodes are built.
+	pub fn render(&self, file: &str, profile: &Profile) -> String {
+		let target = profile.target_directory(Path::new("./"));
+		let validators = DEV_ACCOUNTS
+			.iter()
+			.take((self.parachains.len() + 1).clamp(2, DEV_ACCOUNTS.len()))
+			.map(|name| name.to_lowercase())
+			.collect();
+		let parachains = self
+			.parachains
+			.iter()
+			.enumerate()
+			.map(|(i, (id, node))| WorkspaceParachain {
+				id: *id,
+				command: target.join(&node.binary).display().to_string(),
+				collator: format!("collator-{:02}", i + 1),
+			})
+			.collect();
+		Network { file, relay_chain: &self.relay_chain, validators, parachains }
+			.render()
+			.expect("infallible")
+	}
+}
+
+/// Locates the collator nodes within a workspace: its members, the package at its root and the
+/// conventional `node` directory.
+///
+/// # Arguments
+/// * `path` - The path of the workspace.
+pub fn workspace_nodes(path: &Path) -> Result<Vec<WorkspaceNode>, Error> {
+	let manifest = from_path(Some(path))?;
+	let mut dirs = vec![];
+	if manifest.package.is_some() {
+		dirs.push(path.to_path_buf());
+	}
+	for member in manifest.workspace.iter().flat_map(|w| w.members.iter()) {
+		let pattern = path.join(member);
+		let pattern = pattern.to_str().ok_or(Error::PathError)?;
+		for dir in glob::glob(pattern).map_err(|e| Error::Config(e.to_string()))?.flatten() {
+			dirs.push(dir);
+		}
+	}
+	dirs.push(path.join("node"));
+
+	let mut nodes: Vec<WorkspaceNode> = vec![];
+	for dir in dirs.into_iter().filter(|d| d.join("Cargo.toml").exists()) {
+		let dir = dir.canonicalize()?;
+		if nodes.iter().any(|n| n.path == dir) {
+			continue;
+		}
+		let manifest = from_path(Some(&dir))?;
+		if !is_node(&manifest) {
+			continue;
+		}
+		let package = manifest.package().name().to_string();
+		let binary = manifest
+			.bin
+			.first()
+			.and_then(|b| b.name.clone())
+			.unwrap_or_else(|| package.clone());
+		let (para_id, relay_chain) = read_to_string(dir.join("src/chain_spec.rs"))
+			.map(|source| parse_chain_spec_source(&source))
+			.unwrap_or_default();
+		nodes.push(WorkspaceNode { package, binary, path: dir, para_id, relay_chain });
+	}
+	// The chain specification of the project identifies the parachain of a single node.
+	if let [node] = nodes.as_mut_slice() {
+		if let Some((para_id, relay_chain)) = project_chain_spec(path) {
+			node.para_id = Some(para_id);
+			node.relay_chain = relay_chain.or(node.relay_chain.take());
+		}
+	}
+	Ok(nodes)
+}
+
+/// Whether a package is a collator node: a binary depending on the cumulus client.
+fn is_node(manifest: &Manifest) -> bool {
+	manifest.package.is_some() &&
+		!manifest.bin.is_empty() &&
+		manifest.dependencies.iter().any(|(name, dependency)| {
+			NODE_DEPENDENCIES.contains(&name.as_str()) ||
+				(name == "polkadot-sdk" && dependency.req_features().iter().any(|f| f == "node"))
+		})
+}
+
+/// The para id and relay chain specified within a chain specification at the root of a project,
+/// if any.
+fn project_chain_spec(path: &Path) -> Option<(u32, Option<String>)> {
+	let pattern = path.join("*.json");
+	let mut specs: Vec<_> = glob::glob(pattern.to_str()?).ok()?.flatten().collect();
+	specs.sort();
+	specs.into_iter().find_map(|spec| {
+		let spec: Value = serde_json::from_str(&read_to_string(spec).ok()?).ok()?;
+		let field = |keys: [&str; 2]| keys.into_iter().find_map(|key| spec.get(key));
+		let para_id = field(["para_id", "paraId"])?.as_u64()?.try_into().ok()?;
+		let relay_chain = field(["relay_chain", "relayChain"]).and_then(Value::as_str);
+		Some((para_id, relay_chain.map(String::from)))
+	})
+}
+
+/// Parses the para id and relay chain from the chain specification source of a node (e.g.
+/// `Extensions { relay_chain: "rococo-local".into(), para_id: 1000 }`).
+fn parse_chain_spec_source(source: &str) -> (Option<u32>, Option<String>) {
+	let number = |prefix: &str| {
+		source.match_indices(prefix).find_map(|(i, _)| {
+			let digits: String = source[i + prefix.len()..]
+				.trim_start()
+				.chars()
+				.take_while(|c| c.is_ascii_digit() || *c == '_')
+				.filter(|c| *c != '_')
+				.collect();
+			digits.parse().ok()
+		})
+	};
+	let para_id = number("para_id:").or_else(|| number("PARA_ID: u32 ="));
+	let relay_chain = source.match_indices("relay_chain:").find_map(|(i, _)| {
+		let rest = source[i + "relay_chain:".len()..].trim_start().strip_prefix('"')?;
+		rest.split_once('"').map(|(chain, _)| chain.to_string())
+	});
+	(para_id, relay_chain)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::{create_dir_all, write};
+
+	fn node(dir: &Path, package: &str, chain_spec: Option<&str>) -> Result<()> {
+		create_dir_all(dir.join("src"))?;
+		write(
+			dir.join("Cargo.toml"),
+			format!(
+				r#"[package]
+name = "{package}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+cumulus-client-service = "0.1"
+"#
+			),
+		)?;
+		write(dir.join("src/main.rs"), "fn main() {}")?;
+		if let Some(chain_spec) = chain_spec {
+			write(dir.join("src/chain_spec.rs"), chain_spec)?;
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn parse_chain_spec_source_works() {
+		assert_eq!(
+			parse_chain_spec_source(
+				r#"pub struct Extensions { pub relay_chain: String, pub para_id: u32 }
+				Extensions { relay_chain: "paseo-local".into(), para_id: 4_385 }"#
+			),
+			(Some(4385), Some("paseo-local".into()))
+		);
+		assert_eq!(
+			parse_chain_spec_source(
+				"pub const PARA_ID: u32 = 1000;\nExtensions { para_id: PARA_ID }"
+			),
+			(Some(1000), None)
+		);
+		assert_eq!(parse_chain_spec_source(""), (None, None));
+	}
+
+	#[test]
+	fn inspect_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		write(
+			path.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"nodes/*\", \"runtime\"]\nresolver = \"2\"\n",
+		)?;
+		node(
+			&path.join("nodes/alpha"),
+			"alpha-node",
+			Some(r#"Extensions { relay_chain: "paseo-local".into(), para_id: 1000 }"#),
+		)?;
+		node(&path.join("nodes/beta"), "beta-node", Some("Extensions { para_id: 1000 }"))?;
+		node(&path.join("nodes/gamma"), "gamma-node", None)?;
+		create_dir_all(path.join("runtime/src"))?;
+		write(
+			path.join("runtime/Cargo.toml"),
+			"[package]\nname = \"runtime\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+		)?;
+		write(path.join("runtime/src/lib.rs"), "")?;
+
+		let network = WorkspaceNetwork::inspect(path, None)?;
+		assert_eq!(network.relay_chain, "paseo-local");
+		assert_eq!(
+			network
+				.parachains
+				.iter()
+				.map(|(id, n)| (*id, n.binary.as_str()))
+				.collect::<Vec<_>>(),
+			[(1000, "alpha-node"), (2000, "beta-node"), (2001, "gamma-node")]
+		);
+		assert_eq!(
+			WorkspaceNetwork::inspect(path, Some("rococo-local"))?.relay_chain,
+			"rococo-local"
+		);
+
+		let rendered = network.render("network.toml", &Profile::Release);
+		assert!(rendered.contains("launch with `pop up parachain -f network.toml`"));
+		let config: toml_edit::DocumentMut = rendered.parse()?;
+		assert_eq!(config["relaychain"]["chain"].as_str(), Some("paseo-local"));
+		assert_eq!(config["relaychain"]["nodes"].as_array_of_tables().unwrap().len(), 4);
+		let parachains = config["parachains"].as_array_of_tables().unwrap();
+		assert_eq!(parachains.len(), 3);
+		let parachain = parachains.get(2).unwrap();
+		assert_eq!(parachain["id"].as_integer(), Some(2001));
+		assert_eq!(parachain["default_command"].as_str(), Some("./target/release/gamma-node"));
+		assert_eq!(parachain["collators"][0]["name"].as_str(), Some("collator-03"));
+		Ok(())
+	}
+
+	#[test]
+	fn inspect_uses_project_chain_spec() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		write(path.join("Cargo.toml"), "[workspace]\nmembers = [\"node\"]\nresolver = \"2\"\n")?;
+		node(&path.join("node"), "parachain-template-node", Some("Extensions { para_id: 1000 }"))?;
+		write(path.join("chain-spec.json"), r#"{"para_id": 4385, "relay_chain": "paseo-local"}"#)?;
+
+		let network = WorkspaceNetwork::inspect(path, None)?;
+		assert_eq!(network.relay_chain, "paseo-local");
+		assert_eq!(network.parachains[0].0, 4385);
+		let config: toml_edit::DocumentMut =
+			network.render("network.toml", &Profile::Debug).parse()?;
+		assert_eq!(config["relaychain"]["nodes"].as_array_of_tables().unwrap().len(), 2);
+		assert_eq!(
+			config["parachains"][0]["default_command"].as_str(),
+			Some("./target/debug/parachain-template-node")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn inspect_fails_without_nodes() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		write(temp_dir.path().join("Cargo.toml"), "[workspace]\nmembers = []\n")?;
+		assert!(matches!(
+			WorkspaceNetwork::inspect(temp_dir.path(), None),
+			Err(Error::Config(message)) if message.starts_with("no collator nodes found")
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,716 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{
+		construct_sudo_extrinsic, construct_sudo_unchecked_weight_extrinsic, fetch_storage,
+		find_event_field, set_up_client, sign_and_submit_extrinsic,
+	},
+	errors::Error,
+	registrar::dispatch_result,
+};
+use pop_common::{i18n::message, Status};
+use std::{
+	fs::read,
+	path::Path,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use subxt::{
+	backend::StreamOfResults,
+	client::RuntimeVersion,
+	config::{substrate::BlakeTwo256, Hasher},
+	dynamic::Value,
+	ext::scale_value::ValueDef,
+	tx::DynamicPayload,
+	Metadata, OnlineClient, SubstrateConfig,
+};
+use zombienet_sdk::Network;
+use zombienet_support::fs::local::LocalFileSystem;
+
+/// The lowest identifier of a parachain which is not a system parachain.
+const FIRST_PUBLIC_PARA_ID: u32 = 2000;
+/// The maximum time to wait for an upgrade to be enacted. Parachain upgrades are only enacted
+/// once the new validation code has been accepted by the relay chain.
+const UPGRADE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The result of a runtime upgrade.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RuntimeUpgrade {
+	/// The identifier of the upgraded parachain.
+	pub para_id: u32,
+	/// The specification version of the runtime before the upgrade.
+	pub previous_spec_version: u32,
+	/// The specification version of the runtime after the upgrade.
+	pub spec_version: u32,
+}
+
+/// When a runtime upgrade is to be enacted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Enactment {
+	/// Enact at the specified block.
+	Block(u32),
+	/// Enact within the specified session.
+	Session(u32),
+	/// Enact at the first block produced after the specified time.
+	Time(SystemTime),
+}
+
+impl Enactment {
+	/// Resolves the block at which the upgrade is to be enacted, based on the current state of
+	/// the chain. Returns `None` for sessions, whose length is not exposed by parachains (whose
+	/// sessions are rotated by `pallet-session` rather than by BABE epochs), so are awaited via
+	/// [Enactment::wait] instead.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	pub async fn resolve(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+	) -> Result<Option<u32>, Error> {
+		let current_block = query_u128(client, "System", "Number")
+			.await?
+			.ok_or_else(|| Error::UpgradeError("unable to determine the current block".into()))?
+			as u32;
+		match self {
+			Enactment::Block(block) => {
+				if *block <= current_block {
+					return Err(Error::UpgradeError(message(
+						"upgrade.block_not_after",
+						&[("block", &block.to_string()), ("current", &current_block.to_string())],
+					)));
+				}
+				Ok(Some(*block))
+			},
+			Enactment::Session(session) => {
+				let current_session = current_session(client).await?;
+				if *session <= current_session {
+					return Err(Error::UpgradeError(message(
+						"upgrade.session_not_after",
+						&[
+							("session", &session.to_string()),
+							("current", &current_session.to_string()),
+						],
+					)));
+				}
+				Ok(None)
+			},
+			Enactment::Time(time) => {
+				let now = query_u128(client, "Timestamp", "Now").await?.ok_or_else(|| {
+					Error::UpgradeError("unable to determine the current time".into())
+				})? as u64;
+				let block_time = block_time(client).ok_or_else(|| {
+					Error::UpgradeError(
+						"unable to determine the block time, specify a block instead".into(),
+					)
+				})?;
+				block_at_time(current_block, Duration::from_millis(now), block_time, *time)
+					.map(Some)
+			},
+		}
+	}
+
+	/// Waits until the upgrade is to be enacted: until the resolved block has been finalized, or
+	/// until the session has started.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	pub async fn wait(&self, client: &OnlineClient<SubstrateConfig>) -> Result<(), Error> {
+		match (self, self.resolve(client).await?) {
+			(Enactment::Session(session), _) => wait_for_session(client, *session).await,
+			(_, Some(block)) => wait_for_block(client, block).await,
+			(_, None) => Ok(()),
+		}
+	}
+}
+
+/// Returns the index of the current session of the chain, as rotated by `pallet-session`.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+async fn current_session(client: &OnlineClient<SubstrateConfig>) -> Result<u32, Error> {
+	Ok(query_u128(client, "Session", "CurrentIndex").await?.ok_or_else(|| {
+		Error::UpgradeError(
+			"unable to determine the current session, as the chain does not include `pallet-session`: specify a block instead".into(),
+		)
+	})? as u32)
+}
+
+/// Waits until the specified session has started, checking the current session as each block is
+/// finalized.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `session` - The session to wait for.
+async fn wait_for_session(
+	client: &OnlineClient<SubstrateConfig>,
+	session: u32,
+) -> Result<(), Error> {
+	let mut blocks = client.blocks().subscribe_finalized().await?;
+	while let Some(finalized) = blocks.next().await {
+		finalized?;
+		if current_session(client).await? >= session {
+			return Ok(());
+		}
+	}
+	Err(Error::UpgradeError(format!("the subscription ended before session {session}")))
+}
+
+/// Calculates the first block produced after a future time.
+///
+/// # Arguments
+/// * `current_block` - The current block number.
+/// * `now` - The timestamp of the current block, since the Unix epoch.
+/// * `block_time` - The expected time between blocks.
+/// * `time` - The time at which the upgrade is to be enacted.
+fn block_at_time(
+	current_block: u32,
+	now: Duration,
+	block_time: Duration,
+	time: SystemTime,
+) -> Result<u32, Error> {
+	let time = time
+		.duration_since(UNIX_EPOCH)
+		.map_err(|e| Error::UpgradeError(format!("invalid time: {e}")))?;
+	let remaining = time
+		.checked_sub(now)
+		.filter(|remaining| !remaining.is_zero())
+		.ok_or_else(|| Error::UpgradeError(message("upgrade.time_not_future", &[])))?;
+	if block_time.is_zero() {
+		return Err(Error::UpgradeError("invalid block time".into()));
+	}
+	let blocks = remaining.as_millis().div_ceil(block_time.as_millis());
+	u32::try_from(blocks)
+		.ok()
+		.and_then(|blocks| current_block.checked_add(blocks))
+		.ok_or_else(|| Error::UpgradeError(message("upgrade.time_too_far", &[])))
+}
+
+/// Returns the expected time between blocks, based on the constants of the runtime.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+fn block_time(client: &OnlineClient<SubstrateConfig>) -> Option<Duration> {
+	constant_u128(client, "Aura", "SlotDuration")
+		.or_else(|| constant_u128(client, "Babe", "ExpectedBlockTime"))
+		.or_else(|| constant_u128(client, "Timestamp", "MinimumPeriod").map(|p| p * 2))
+		.filter(|millis| *millis > 0)
+		.map(|millis| Duration::from_millis(millis as u64))
+}
+
+/// Returns a numeric constant of the runtime, if available.
+pub(crate) fn constant_u128(
+	client: &OnlineClient<SubstrateConfig>,
+	pallet: &str,
+	name: &str,
+) -> Option<u128> {
+	client
+		.constants()
+		.at(&subxt::dynamic::constant(pallet, name))
+		.ok()
+		.and_then(|value| value.to_value().ok())
+		.and_then(|value| as_u128(&value))
+}
+
+/// Returns a numeric plain storage value of the chain, if available.
+async fn query_u128(
+	client: &OnlineClient<SubstrateConfig>,
+	pallet: &str,
+	entry: &str,
+) -> Result<Option<u128>, Error> {
+	Ok(fetch_storage(client, pallet, entry, vec![])
+		.await?
+		.and_then(|value| as_u128(&value)))
+}
+
+/// Converts a numeric value to a `u128`, unwrapping any single-field composite within which it is
+/// wrapped (e.g. `ParaId(u32)`).
+///
+/// # Arguments
+/// * `value` - The value to be converted.
+fn as_u128<T>(value: &Value<T>) -> Option<u128> {
+	match &value.value {
+		ValueDef::Composite(fields) if fields.len() == 1 =>
+			fields.values().next().and_then(as_u128),
+		_ => value.as_u128(),
+	}
+}
+
+/// Constructs a call which sets the code of the runtime.
+///
+/// # Arguments
+/// * `code` - The code of the new runtime.
+pub fn set_code(code: Vec<u8>) -> DynamicPayload {
+	subxt::dynamic::tx("System", "set_code", vec![Value::from_bytes(code)])
+}
+
+/// Constructs a call which authorizes a runtime upgrade, to be subsequently enacted by
+/// [apply_authorized_upgrade].
+///
+/// # Arguments
+/// * `code` - The code of the new runtime.
+pub fn authorize_upgrade(code: &[u8]) -> DynamicPayload {
+	let code_hash = BlakeTwo256::hash(code);
+	subxt::dynamic::tx("System", "authorize_upgrade", vec![Value::from_bytes(code_hash)])
+}
+
+/// Constructs a call which enacts a previously authorized runtime upgrade. The call can be
+/// submitted by any account.
+///
+/// # Arguments
+/// * `code` - The code of the new runtime, matching the authorized code hash.
+pub fn apply_authorized_upgrade(code: Vec<u8>) -> DynamicPayload {
+	subxt::dynamic::tx("System", "apply_authorized_upgrade", vec![Value::from_bytes(code)])
+}
+
+/// Constructs the calls which authorize and then enact a runtime upgrade of a parachain: via
+/// `parachain_system.authorize_upgrade` and `parachain_system.enact_authorized_upgrade` where the
+/// runtime still provides them, otherwise via [authorize_upgrade] and [apply_authorized_upgrade].
+///
+/// # Arguments
+/// * `metadata` - The metadata of the parachain.
+/// * `code` - The code of the new runtime.
+pub fn authorized_upgrade_calls(
+	metadata: &Metadata,
+	code: Vec<u8>,
+) -> (DynamicPayload, DynamicPayload) {
+	let parachain_system = metadata
+		.pallet_by_name("ParachainSystem")
+		.is_some_and(|p| p.call_variant_by_name("authorize_upgrade").is_some());
+	if !parachain_system {
+		return (authorize_upgrade(&code), apply_authorized_upgrade(code));
+	}
+	let code_hash = BlakeTwo256::hash(&code);
+	(
+		subxt::dynamic::tx(
+			"ParachainSystem",
+			"authorize_upgrade",
+			vec![Value::from_bytes(code_hash), Value::bool(true)],
+		),
+		subxt::dynamic::tx(
+			"ParachainSystem",
+			"enact_authorized_upgrade",
+			vec![Value::from_bytes(code)],
+		),
+	)
+}
+
+/// Constructs a call which schedules the provided call to be dispatched with `Root` origin at
+/// the specified block.
+///
+/// # Arguments
+/// * `block` - The block at which the call is to be dispatched.
+/// * `call` - The call to be dispatched.
+pub fn schedule(block: u32, call: DynamicPayload) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Scheduler",
+		"schedule",
+		vec![
+			Value::u128(block as u128),
+			Value::unnamed_variant("None", vec![]),
+			Value::u128(0),
+			call.into_value(),
+		],
+	)
+}
+
+/// Constructs the calls, each requiring `Root` origin, which authorize a runtime upgrade of a
+/// parachain and schedule the enactment of the authorized upgrade at the specified block.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the parachain.
+/// * `block` - The block at which the upgrade is to be enacted.
+/// * `code` - The code of the new runtime.
+pub fn schedule_upgrade(
+	metadata: &Metadata,
+	block: u32,
+	code: Vec<u8>,
+) -> (DynamicPayload, DynamicPayload) {
+	let (authorize, enact) = authorized_upgrade_calls(metadata, code);
+	(authorize, schedule(block, enact))
+}
+
+/// Upgrades the runtime of the parachain within a local network launched by pop, via sudo, and
+/// waits for the new runtime to be enacted. The network must contain a single parachain which is
+/// not a system parachain, otherwise use [upgrade_local_parachain].
+///
+/// # Arguments
+/// * `network` - The running network.
+/// * `new_wasm` - The path of the new runtime.
+/// * `suri` - The secret URI of the sudo key of the parachain (e.g. `//Alice`).
+/// * `enactment` - When the upgrade is to be enacted, otherwise immediately.
+pub async fn upgrade_local_network(
+	network: &Network<LocalFileSystem>,
+	new_wasm: &Path,
+	suri: &str,
+	enactment: Option<&Enactment>,
+) -> Result<RuntimeUpgrade, Error> {
+	let para_ids: Vec<_> = network.parachains().iter().map(|p| p.para_id()).collect();
+	upgrade_local_parachain(network, select_parachain(&para_ids)?, new_wasm, suri, enactment).await
+}
+
+/// Upgrades the runtime of a parachain within a local network launched by pop, via sudo, and
+/// waits for the new runtime to be enacted.
+///
+/// # Arguments
+/// * `network` - The running network.
+/// * `para_id` - The identifier of the parachain to be upgraded.
+/// * `new_wasm` - The path of the new runtime.
+/// * `suri` - The secret URI of the sudo key of the parachain (e.g. `//Alice`).
+/// * `enactment` - When the upgrade is to be enacted, otherwise immediately.
+pub async fn upgrade_local_parachain(
+	network: &Network<LocalFileSystem>,
+	para_id: u32,
+	new_wasm: &Path,
+	suri: &str,
+	enactment: Option<&Enactment>,
+) -> Result<RuntimeUpgrade, Error> {
+	let parachain = network
+		.parachains()
+		.into_iter()
+		.find(|p| p.para_id() == para_id)
+		.ok_or_else(|| Error::UpgradeError(format!("parachain {para_id} is not in the network")))?;
+	let collator = parachain.collators().into_iter().next().ok_or_else(|| {
+		Error::UpgradeError(format!("parachain {para_id} does not have any collators"))
+	})?;
+	let client = set_up_client(collator.ws_uri()).await?;
+	let code = read(new_wasm)?;
+	let (previous_spec_version, spec_version) =
+		sudo_upgrade(&client, code, suri, enactment).await?;
+	Ok(RuntimeUpgrade { para_id, previous_spec_version, spec_version })
+}
+
+/// Upgrades the runtime of a chain via `sudo.sudo_unchecked_weight(system.set_code)`, waiting
+/// until a runtime with a new specification version has been enacted. Returns the previous and
+/// new specification versions, or the dispatch error should the upgrade be rejected.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `code` - The code of the new runtime.
+/// * `suri` - The secret URI of the sudo key.
+/// * `enactment` - When the upgrade is to be enacted, otherwise immediately.
+pub async fn sudo_upgrade(
+	client: &OnlineClient<SubstrateConfig>,
+	code: Vec<u8>,
+	suri: &str,
+	enactment: Option<&Enactment>,
+) -> Result<(u32, u32), Error> {
+	if let Some(enactment) = enactment {
+		enactment.wait(client).await?;
+	}
+	let previous = client.backend().current_runtime_version().await?.spec_version;
+	// Subscribe before submitting, so that the enactment of the upgrade cannot be missed.
+	let versions = client.backend().stream_runtime_version().await?;
+	let call = construct_sudo_unchecked_weight_extrinsic(set_code(code));
+	let events = sign_and_submit_extrinsic(client, &call, suri).await?;
+	if let Some(Err(e)) = find_event_field(&events, "Sudo", "Sudid", "sudo_result")?
+		.map(|result| dispatch_result(&result))
+	{
+		return Err(Error::UpgradeError(message(
+			"upgrade.set_code_failed",
+			&[("error", &e.to_string())],
+		)));
+	}
+	Ok((previous, wait_for_spec_version(versions, previous).await?))
+}
+
+/// Upgrades the runtime of a live parachain: authorizing the hash of the new code via sudo, then
+/// enacting the authorized upgrade with the new code, and waiting until a runtime with a new
+/// specification version is live.
+///
+/// When an enactment is specified, the upgrade is authorized immediately and its enactment is
+/// delayed: scheduled via the scheduler of the parachain when enacted at a block, otherwise
+/// submitted once the enactment has been reached.
+///
+/// # Arguments
+/// * `url` - Endpoint of a node of the parachain.
+/// * `new_wasm` - The path of the new runtime.
+/// * `suri` - The secret URI of the sudo key, also used to enact the upgrade.
+/// * `enactment` - When the upgrade is to be enacted, otherwise immediately.
+/// * `status` - An observer of the progress of the upgrade.
+pub async fn upgrade_parachain(
+	url: &str,
+	new_wasm: &Path,
+	suri: &str,
+	enactment: Option<&Enactment>,
+	status: &impl Status,
+) -> Result<RuntimeUpgrade, Error> {
+	let code = read(new_wasm)?;
+	let client = set_up_client(url).await?;
+	let para_id = query_u128(&client, "ParachainInfo", "ParachainId")
+		.await?
+		.ok_or_else(|| Error::UpgradeError("unable to determine the parachain identifier".into()))?
+		as u32;
+	let previous = client.backend().current_runtime_version().await?.spec_version;
+	let block = match enactment {
+		Some(enactment) => enactment.resolve(&client).await?,
+		None => None,
+	};
+	let metadata = client.metadata();
+	let scheduler = metadata.pallet_by_name("Scheduler").is_some();
+	let (authorize, enact) = authorized_upgrade_calls(&metadata, code.clone());
+
+	let id = para_id.to_string();
+	status.update(&message(
+		"upgrade.authorizing",
+		&[("para_id", &id), ("hash", &format!("{:?}", BlakeTwo256::hash(&code)))],
+	));
+	let events =
+		sign_and_submit_extrinsic(&client, &construct_sudo_extrinsic(authorize), suri).await?;
+	if let Some(Err(e)) = find_event_field(&events, "Sudo", "Sudid", "sudo_result")?
+		.map(|result| dispatch_result(&result))
+	{
+		return Err(Error::UpgradeError(message(
+			"upgrade.authorization_failed",
+			&[("error", &e.to_string())],
+		)));
+	}
+
+	// Subscribe before enacting, so that the enactment of the upgrade cannot be missed.
+	let versions = client.backend().stream_runtime_version().await?;
+	match (enactment, block) {
+		(Some(_), Some(block)) if scheduler => {
+			status.update(&message(
+				"upgrade.scheduling",
+				&[("para_id", &id), ("block", &block.to_string())],
+			));
+			let events = sign_and_submit_extrinsic(
+				&client,
+				&construct_sudo_extrinsic(schedule(block, enact)),
+				suri,
+			)
+			.await?;
+			if let Some(Err(e)) = find_event_field(&events, "Sudo", "Sudid", "sudo_result")?
+				.map(|result| dispatch_result(&result))
+			{
+				return Err(Error::UpgradeError(message(
+					"upgrade.scheduling_failed",
+					&[("error", &e.to_string())],
+				)));
+			}
+			wait_for_block(&client, block).await?;
+		},
+		(enactment, _) => {
+			if let Some(enactment) = enactment {
+				status.update(&message("upgrade.awaiting_enactment", &[("para_id", &id)]));
+				enactment.wait(&client).await?;
+			}
+			status.update(&message("upgrade.enacting", &[("para_id", &id)]));
+			sign_and_submit_extrinsic(&client, &enact, suri).await?;
+		},
+	}
+	status.update(&message("upgrade.waiting", &[("para_id", &id)]));
+	let spec_version = wait_for_spec_version(versions, previous).await?;
+	Ok(RuntimeUpgrade { para_id, previous_spec_version: previous, spec_version })
+}
+
+/// Waits until a runtime with a specification version other than the previous one is live,
+/// returning the new specification version.
+///
+/// # Arguments
+/// * `versions` - The stream of runtime versions of the chain.
+/// * `previous` - The specification version of the runtime before the upgrade.
+async fn wait_for_spec_version(
+	mut versions: StreamOfResults<RuntimeVersion>,
+	previous: u32,
+) -> Result<u32, Error> {
+	let wait = async {
+		while let Some(version) = versions.next().await {
+			let version = version?.spec_version;
+			if version != previous {
+				return Ok(version);
+			}
+		}
+		Err(Error::UpgradeError("the subscription ended before the upgrade was enacted".into()))
+	};
+	tokio::time::timeout(UPGRADE_TIMEOUT, wait).await.map_err(|_| {
+		Error::UpgradeError(format!(
+			"the upgrade was not enacted within {} seconds",
+			UPGRADE_TIMEOUT.as_secs()
+		))
+	})?
+}
+
+/// Selects the parachain to be upgraded within a network: the only parachain, or otherwise the
+/// only parachain which is not a system parachain.
+///
+/// # Arguments
+/// * `para_ids` - The identifiers of the parachains within the network.
+fn select_parachain(para_ids: &[u32]) -> Result<u32, Error> {
+	if let [para_id] = para_ids {
+		return Ok(*para_id);
+	}
+	let candidates: Vec<_> = para_ids.iter().filter(|id| **id >= FIRST_PUBLIC_PARA_ID).collect();
+	match candidates[..] {
+		[para_id] => Ok(*para_id),
+		[] => Err(Error::UpgradeError(format!(
+			"the network does not contain a parachain to be upgraded, with an identifier of {FIRST_PUBLIC_PARA_ID} or above"
+		))),
+		_ => {
+			let candidates: Vec<_> = candidates.iter().map(|id| id.to_string()).collect();
+			Err(Error::UpgradeError(format!(
+				"the network contains multiple parachains ({}), specify the parachain to be upgraded",
+				candidates.join(", ")
+			)))
+		},
+	}
+}
+
+/// Waits until the specified block has been finalized.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `block` - The block to wait for.
+pub async fn wait_for_block(
+	client: &OnlineClient<SubstrateConfig>,
+	block: u32,
+) -> Result<(), Error> {
+	let mut blocks = client.blocks().subscribe_finalized().await?;
+	while let Some(finalized) = blocks.next().await {
+		if finalized?.number() >= block {
+			return Ok(());
+		}
+	}
+	Err(Error::UpgradeError(format!("the subscription ended before block {block}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn as_u128_works() {
+		assert_eq!(as_u128(&Value::u128(1_000)), Some(1_000));
+		// A `ParaId`, as decoded from storage.
+		assert_eq!(as_u128(&Value::unnamed_composite([Value::u128(2_000)])), Some(2_000));
+		assert_eq!(
+			as_u128(&Value::named_composite([("id", Value::unnamed_composite([Value::u128(3)]))])),
+			Some(3)
+		);
+		assert_eq!(as_u128(&Value::unnamed_composite([Value::u128(1), Value::u128(2)])), None);
+		assert_eq!(as_u128(&Value::string("2000")), None);
+	}
+
+	#[test]
+	fn as_u128_decodes_para_id() -> Result<()> {
+		use scale_info::{meta_type, PortableRegistry, Registry, TypeInfo};
+		use subxt::ext::{codec::Encode, scale_value::scale::decode_as_type};
+
+		#[derive(Encode, TypeInfo)]
+		#[codec(crate = subxt::ext::codec)]
+		struct ParaId(u32);
+
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<ParaId>()).id;
+		let types: PortableRegistry = registry.into();
+		let value = decode_as_type(&mut &ParaId(2_000).encode()[..], ty, &types)?;
+		assert!(matches!(value.value, ValueDef::Composite(_)));
+		assert_eq!(value.as_u128(), None);
+		assert_eq!(as_u128(&value), Some(2_000));
+		Ok(())
+	}
+
+	#[test]
+	fn block_at_time_works() -> Result<()> {
+		let now = Duration::from_secs(1_700_000_000);
+		let block_time = Duration::from_secs(6);
+		assert_eq!(
+			block_at_time(1_000, now, block_time, UNIX_EPOCH + now + Duration::from_secs(60))?,
+			1_010
+		);
+		// Rounds up to the first block produced after the requested time.
+		assert_eq!(
+			block_at_time(1_000, now, block_time, UNIX_EPOCH + now + Duration::from_secs(61))?,
+			1_011
+		);
+		assert!(matches!(
+			block_at_time(1_000, now, block_time, UNIX_EPOCH + now),
+			Err(Error::UpgradeError(message)) if message == "the requested time is not in the future"
+		));
+		assert!(block_at_time(1_000, now, Duration::ZERO, SystemTime::now()).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn select_parachain_works() -> Result<()> {
+		assert_eq!(select_parachain(&[2000])?, 2000);
+		assert_eq!(select_parachain(&[1000])?, 1000);
+		assert_eq!(select_parachain(&[1000, 1005, 4385])?, 4385);
+		assert!(matches!(
+			select_parachain(&[]),
+			Err(Error::UpgradeError(message)) if message == "the network does not contain a parachain to be upgraded, with an identifier of 2000 or above"
+		));
+		assert!(matches!(
+			select_parachain(&[1000, 1005]),
+			Err(Error::UpgradeError(message)) if message == "the network does not contain a parachain to be upgraded, with an identifier of 2000 or above"
+		));
+		assert!(matches!(
+			select_parachain(&[1000, 2000, 2001]),
+			Err(Error::UpgradeError(message)) if message == "the network contains multiple parachains (2000, 2001), specify the parachain to be upgraded"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn schedule_upgrade_works() {
+		let metadata = crate::metadata_diff::tests::metadata(vec![]);
+		let (authorize, call) = schedule_upgrade(&metadata, 1_000, vec![1, 2, 3]);
+		assert_eq!(authorize.into_value(), super::authorize_upgrade(&[1, 2, 3]).into_value());
+		assert_eq!(call.pallet_name(), "Scheduler");
+		assert_eq!(call.call_name(), "schedule");
+		assert_eq!(
+			call.into_value(),
+			Value::unnamed_variant(
+				"Scheduler",
+				[Value::unnamed_variant(
+					"schedule",
+					[
+						Value::u128(1_000),
+						Value::unnamed_variant("None", vec![]),
+						Value::u128(0),
+						Value::unnamed_variant(
+							"System",
+							[Value::unnamed_variant(
+								"apply_authorized_upgrade",
+								[Value::from_bytes([1, 2, 3])]
+							)]
+						),
+					]
+				)]
+			)
+		);
+	}
+
+	#[test]
+	fn authorize_upgrade_works() {
+		let code = vec![1, 2, 3];
+		let call = authorize_upgrade(&code);
+		assert_eq!(call.call_name(), "authorize_upgrade");
+		assert_eq!(
+			call.into_value(),
+			Value::unnamed_variant(
+				"System",
+				[Value::unnamed_variant(
+					"authorize_upgrade",
+					[Value::from_bytes(BlakeTwo256::hash(&code))]
+				)]
+			)
+		);
+		assert_eq!(apply_authorized_upgrade(code).call_name(), "apply_authorized_upgrade");
+	}
+
+	#[tokio::test]
+	async fn upgrade_parachain_fails_wrong_url() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let wasm = temp_dir.path().join("runtime.compact.compressed.wasm");
+		assert!(matches!(
+			upgrade_parachain("ws://127.0.0.1:1", &wasm, "//Alice", None, &()).await,
+			Err(Error::IO(_))
+		));
+		std::fs::write(&wasm, [0u8; 8])?;
+		assert!(matches!(
+			upgrade_parachain("ws://127.0.0.1:1", &wasm, "//Alice", None, &()).await,
+			Err(Error::ConnectionFailure(_))
+		));
+		Ok(())
+	}
+}
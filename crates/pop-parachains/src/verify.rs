@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{call::set_up_client, errors::Error, runtime_info::uncompressed};
+use pop_common::decode_hex;
+use serde_json::{Map, Value};
+use std::{
+	fmt::{Display, Formatter},
+	fs::{read, read_to_string},
+	path::{Path, PathBuf},
+};
+use subxt::{
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	config::{substrate::BlakeTwo256, Hasher},
+	utils::H256,
+	SubstrateConfig,
+};
+
+/// The well-known storage key of the runtime code.
+const CODE: &[u8] = b":code";
+
+/// The locally held artifacts which a live chain is verified against.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Artifacts {
+	/// The chain specification, providing the chain name and properties.
+	pub chain_spec: Option<PathBuf>,
+	/// The genesis state (head) of the chain, as exported via `export-genesis-state`.
+	pub genesis_state: Option<PathBuf>,
+	/// The runtime, as last enacted on the chain (e.g. a `.compact.compressed.wasm` file).
+	pub wasm: Option<PathBuf>,
+}
+
+/// The identifying state of a chain, where known.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainState {
+	/// The genesis hash.
+	pub genesis_hash: Option<H256>,
+	/// The hash of the runtime code.
+	pub code_hash: Option<H256>,
+	/// The name of the chain.
+	pub name: Option<String>,
+	/// The properties of the chain (e.g. `tokenSymbol`).
+	pub properties: Map<String, Value>,
+}
+
+impl ChainState {
+	/// The state of a chain expected from locally held artifacts.
+	///
+	/// # Arguments
+	/// * `artifacts` - The locally held artifacts.
+	pub fn from_artifacts(artifacts: &Artifacts) -> Result<Self, Error> {
+		let mut state = ChainState::default();
+		if let Some(path) = &artifacts.chain_spec {
+			let chain_spec: Value = serde_json::from_str(&read_to_string(path)?)?;
+			state.name = chain_spec.get("name").and_then(|n| n.as_str()).map(String::from);
+			if let Some(properties) = chain_spec.get("properties").and_then(|p| p.as_object()) {
+				state.properties = properties.clone();
+			}
+		}
+		if let Some(path) = &artifacts.genesis_state {
+			// The genesis hash is the hash of the encoded genesis header.
+			let head = decode_hex(read_to_string(path)?.trim()).ok_or_else(|| {
+				Error::Config(format!("invalid genesis state: {}", path.display()))
+			})?;
+			state.genesis_hash = Some(BlakeTwo256::hash(&head));
+		}
+		if let Some(path) = &artifacts.wasm {
+			state.code_hash = Some(BlakeTwo256::hash(&read(path)?));
+		}
+		Ok(state)
+	}
+
+	/// Fetches the state of a live chain.
+	///
+	/// # Arguments
+	/// * `endpoint` - The endpoint of a node of the chain.
+	pub async fn fetch(endpoint: &str) -> Result<Self, Error> {
+		let client = set_up_client(endpoint).await?;
+		let rpc = RpcClient::from_url(endpoint)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+		let rpc = LegacyRpcMethods::<SubstrateConfig>::new(rpc);
+		let code = client.storage().at_latest().await?.fetch_raw(CODE).await?;
+		Ok(ChainState {
+			genesis_hash: Some(client.genesis_hash()),
+			code_hash: code.map(|code| BlakeTwo256::hash(&code)),
+			name: Some(rpc.system_chain().await?),
+			properties: rpc.system_properties().await?,
+		})
+	}
+}
+
+/// A single comparison between a live chain and the locally held artifacts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+	/// What was compared (e.g. `genesis hash`).
+	pub name: String,
+	/// The value expected from the artifacts.
+	pub expected: String,
+	/// The value of the live chain, if any.
+	pub actual: Option<String>,
+}
+
+impl Check {
+	/// Whether the live chain matches the artifacts.
+	pub fn passed(&self) -> bool {
+		self.actual.as_ref() == Some(&self.expected)
+	}
+}
+
+impl Display for Check {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match &self.actual {
+			_ if self.passed() => write!(f, "{} matches: {}", self.name, self.expected),
+			Some(actual) =>
+				write!(f, "{} mismatch: expected {}, found {actual}", self.name, self.expected),
+			None => write!(f, "{} missing: expected {}", self.name, self.expected),
+		}
+	}
+}
+
+/// The result of verifying a live chain against locally held artifacts.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Verification {
+	/// The comparisons performed.
+	pub checks: Vec<Check>,
+}
+
+impl Verification {
+	/// Compares the state of a live chain to that expected. Only the state known from the
+	/// artifacts is compared.
+	///
+	/// # Arguments
+	/// * `expected` - The state expected from the artifacts.
+	/// * `actual` - The state of the live chain.
+	pub fn compare(expected: &ChainState, actual: &ChainState) -> Self {
+		let mut checks = vec![];
+		let mut check = |name: &str, expected: Option<String>, actual: Option<String>| {
+			if let Some(expected) = expected {
+				checks.push(Check { name: name.to_string(), expected, actual });
+			}
+		};
+		let hash = |hash: Option<H256>| hash.map(|h| format!("{h:?}"));
+		check("genesis hash", hash(expected.genesis_hash), hash(actual.genesis_hash));
+		check("runtime code hash", hash(expected.code_hash), hash(actual.code_hash));
+		check("chain name", expected.name.clone(), actual.name.clone());
+		for (property, value) in &expected.properties {
+			check(
+				&format!("property `{property}`"),
+				Some(value.to_string()),
+				actual.properties.get(property).map(|v| v.to_string()),
+			);
+		}
+		Verification { checks }
+	}
+
+	/// Whether the live chain matches all the artifacts.
+	pub fn verified(&self) -> bool {
+		self.checks.iter().all(Check::passed)
+	}
+
+	/// The checks which failed.
+	pub fn failures(&self) -> impl Iterator<Item = &Check> {
+		self.checks.iter().filter(|c| !c.passed())
+	}
+}
+
+/// Verifies that a live chain matches locally held artifacts, by comparing its genesis hash,
+/// runtime code hash and chain properties.
+///
+/// # Arguments
+/// * `endpoint` - The endpoint of a node of the chain.
+/// * `artifacts` - The locally held artifacts.
+pub async fn verify_live_chain(
+	endpoint: &str,
+	artifacts: &Artifacts,
+) -> Result<Verification, Error> {
+	let expected = ChainState::from_artifacts(artifacts)?;
+	if expected == ChainState::default() {
+		return Err(Error::Config("no artifacts specified to verify the chain against".into()));
+	}
+	let actual = ChainState::fetch(endpoint).await?;
+	Ok(Verification::compare(&expected, &actual))
+}
+
+/// The result of verifying a locally built runtime against the runtime code of a live chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeVerification {
+	/// The hash of the locally built runtime.
+	pub local_hash: H256,
+	/// The hash of the runtime code of the chain, if any.
+	pub on_chain_hash: Option<H256>,
+	/// Whether both runtimes are the same wasm once decompressed, despite their code differing
+	/// (e.g. one being compressed).
+	pub same_wasm: bool,
+}
+
+impl RuntimeVerification {
+	/// Compares a locally built runtime to the runtime code of a chain.
+	///
+	/// # Arguments
+	/// * `local` - The code of the locally built runtime.
+	/// * `on_chain` - The runtime code of the chain, if any.
+	pub fn compare(local: &[u8], on_chain: Option<&[u8]>) -> Result<Self, Error> {
+		let on_chain_hash = on_chain.map(BlakeTwo256::hash);
+		let same_wasm = match on_chain {
+			Some(code) if code != local => uncompressed(code)? == uncompressed(local)?,
+			_ => false,
+		};
+		Ok(Self { local_hash: BlakeTwo256::hash(local), on_chain_hash, same_wasm })
+	}
+
+	/// Whether the runtime code of the chain is exactly the locally built runtime.
+	pub fn matches(&self) -> bool {
+		self.on_chain_hash == Some(self.local_hash)
+	}
+}
+
+impl Display for RuntimeVerification {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let local = self.local_hash;
+		match self.on_chain_hash {
+			_ if self.matches() => write!(f, "runtime matches the on-chain code: {local:?}"),
+			Some(on_chain) if self.same_wasm => write!(
+				f,
+				"runtime matches the on-chain code once decompressed, but is encoded differently: local {local:?}, on-chain {on_chain:?}"
+			),
+			Some(on_chain) =>
+				write!(f, "runtime mismatch: local {local:?}, on-chain {on_chain:?}"),
+			None => write!(f, "runtime code missing on-chain: local {local:?}"),
+		}
+	}
+}
+
+/// Verifies that a locally built runtime is the runtime of a live chain, by comparing it to the
+/// runtime code (`:code`) of the chain.
+///
+/// # Arguments
+/// * `wasm` - The path of the locally built runtime.
+/// * `endpoint` - The endpoint of a node of the chain.
+pub async fn verify_runtime(wasm: &Path, endpoint: &str) -> Result<RuntimeVerification, Error> {
+	let local = read(wasm)?;
+	let client = set_up_client(endpoint).await?;
+	let on_chain = client.storage().at_latest().await?.fetch_raw(CODE).await?;
+	RuntimeVerification::compare(&local, on_chain.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+	use std::fs::write;
+
+	#[test]
+	fn from_artifacts_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let chain_spec = temp_dir.path().join("raw-parachain-chainspec.json");
+		write(
+			&chain_spec,
+			json!({"name": "Pop Network", "properties": {"tokenSymbol": "POP", "tokenDecimals": 10}})
+				.to_string(),
+		)?;
+		let genesis_state = temp_dir.path().join("para-2000-genesis-state");
+		write(&genesis_state, "0x0102ff\n")?;
+		let wasm = temp_dir.path().join("para-2000.wasm");
+		write(&wasm, [0x00, 0x61, 0x73, 0x6d])?;
+
+		let state = ChainState::from_artifacts(&Artifacts {
+			chain_spec: Some(chain_spec),
+			genesis_state: Some(genesis_state),
+			wasm: Some(wasm),
+		})?;
+		assert_eq!(state.name.as_deref(), Some("Pop Network"));
+		assert_eq!(state.properties.get("tokenSymbol"), Some(&json!("POP")));
+		assert_eq!(state.genesis_hash, Some(BlakeTwo256::hash(&[0x01, 0x02, 0xff])));
+		assert_eq!(state.code_hash, Some(BlakeTwo256::hash(&[0x00, 0x61, 0x73, 0x6d])));
+		assert_eq!(ChainState::from_artifacts(&Artifacts::default())?, ChainState::default());
+		Ok(())
+	}
+
+	#[test]
+	fn compare_works() {
+		let expected = ChainState {
+			genesis_hash: Some(H256::repeat_byte(1)),
+			code_hash: Some(H256::repeat_byte(2)),
+			name: None,
+			properties: json!({"tokenSymbol": "POP", "ss58Format": 0}).as_object().unwrap().clone(),
+		};
+		let actual = ChainState {
+			genesis_hash: Some(H256::repeat_byte(1)),
+			code_hash: Some(H256::repeat_byte(3)),
+			name: Some("Pop Network".into()),
+			properties: json!({"tokenSymbol": "POP"}).as_object().unwrap().clone(),
+		};
+		let verification = Verification::compare(&expected, &actual);
+		assert_eq!(verification.checks.len(), 4);
+		assert!(!verification.verified());
+		let failures: Vec<_> = verification.failures().map(|c| c.to_string()).collect();
+		assert_eq!(
+			failures,
+			vec![
+				format!(
+					"runtime code hash mismatch: expected {:?}, found {:?}",
+					H256::repeat_byte(2),
+					H256::repeat_byte(3)
+				),
+				"property `ss58Format` missing: expected 0".to_string(),
+			]
+		);
+		assert!(Verification::compare(&expected, &expected).verified());
+	}
+
+	#[test]
+	fn runtime_verification_works() -> Result<()> {
+		let wasm = [0x00, 0x61, 0x73, 0x6d];
+		let verification = RuntimeVerification::compare(&wasm, Some(&wasm))?;
+		assert!(verification.matches());
+		assert_eq!(
+			verification.to_string(),
+			format!("runtime matches the on-chain code: {:?}", BlakeTwo256::hash(&wasm))
+		);
+
+		let other = [0x00, 0x61, 0x73, 0x6e];
+		let verification = RuntimeVerification::compare(&wasm, Some(&other))?;
+		assert!(!verification.matches() && !verification.same_wasm);
+		assert_eq!(
+			verification.to_string(),
+			format!(
+				"runtime mismatch: local {:?}, on-chain {:?}",
+				BlakeTwo256::hash(&wasm),
+				BlakeTwo256::hash(&other)
+			)
+		);
+
+		let verification = RuntimeVerification::compare(&wasm, None)?;
+		assert_eq!(verification.on_chain_hash, None);
+		assert!(!verification.matches());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn verify_runtime_fails_wrong_url() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let wasm = temp_dir.path().join("runtime.wasm");
+		write(&wasm, [0x00, 0x61, 0x73, 0x6d])?;
+		assert!(matches!(
+			verify_runtime(&wasm, "wss://127.0.0.1:9944").await,
+			Err(Error::ConnectionFailure(_))
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn verify_live_chain_fails_without_artifacts() {
+		assert!(matches!(
+			verify_live_chain("wss://127.0.0.1:9944", &Artifacts::default()).await,
+			Err(Error::Config(e)) if e.contains("no artifacts")
+		));
+	}
+}